@@ -0,0 +1,573 @@
+use crate::{
+    commands::Command,
+    layout::{LayoutContext, ScrollOffset},
+    style::{Length, MotionPreference, Rgba, Style},
+    EngineError, Id,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{mpsc::Sender, Arc},
+    time::{Duration, Instant},
+};
+
+/// Style properties that can be animated with [`crate::Engine::animate`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AnimatedProperty {
+    Width,
+    Height,
+    Color,
+    BackgroundColor,
+}
+
+/// A value of an animated property.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnimationValue {
+    Px(f64),
+    Color(Rgba),
+}
+
+/// Timing function mapping linear progress to eased progress.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    CubicBezier(f64, f64, f64, f64),
+    /// Damped spring with unit mass: `Spring(damping, stiffness)`.
+    ///
+    /// The animation duration is mapped onto the time the spring needs to settle, so
+    /// underdamped springs overshoot and oscillate before coming to rest at the target.
+    Spring(f64, f64),
+    /// Exponential deceleration: `Decay(rate)`, where higher rates slow down sooner.
+    Decay(f64),
+}
+
+impl Easing {
+    /// Map linear progress `t` in [0, 1] to eased progress.
+    pub fn sample(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Easing::Linear => t,
+            Easing::Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            Easing::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Easing::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Easing::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+            Easing::Spring(damping, stiffness) => spring(damping, stiffness, t),
+            Easing::Decay(rate) => decay(rate, t),
+        }
+    }
+}
+
+/// Remaining displacement below which a spring counts as settled.
+const SPRING_SETTLE_TOLERANCE: f64 = 1e-3;
+
+/// Evaluate a damped spring moving from 0 to 1, with `t` spanning its settling time.
+fn spring(damping: f64, stiffness: f64, t: f64) -> f64 {
+    if t >= 1.0 {
+        return 1.0;
+    }
+
+    let omega0 = stiffness.max(1e-6).sqrt();
+    // An undamped spring never settles, so keep a minimal amount of damping.
+    let zeta = (damping / (2.0 * omega0)).max(0.01);
+    let settle_time = spring_settle_time(omega0, zeta);
+    spring_position(omega0, zeta, t * settle_time)
+}
+
+fn spring_position(omega0: f64, zeta: f64, time: f64) -> f64 {
+    if zeta < 1.0 {
+        // Underdamped: decaying oscillation around the target.
+        let omega_d = omega0 * (1.0 - zeta * zeta).sqrt();
+        let envelope = (-zeta * omega0 * time).exp();
+        1.0 - envelope
+            * ((omega_d * time).cos() + (zeta * omega0 / omega_d) * (omega_d * time).sin())
+    } else if zeta == 1.0 {
+        // Critically damped.
+        1.0 - (-omega0 * time).exp() * (1.0 + omega0 * time)
+    } else {
+        // Overdamped: sum of two decaying exponentials.
+        let root = (zeta * zeta - 1.0).sqrt();
+        let r1 = -omega0 * (zeta - root);
+        let r2 = -omega0 * (zeta + root);
+        1.0 + (r2 * (r1 * time).exp() - r1 * (r2 * time).exp()) / (r1 - r2)
+    }
+}
+
+fn spring_settle_time(omega0: f64, zeta: f64) -> f64 {
+    if zeta < 1.0 {
+        // The oscillation stays within its exponential envelope.
+        let amplitude = 1.0 / (1.0 - zeta * zeta).sqrt();
+        return (amplitude / SPRING_SETTLE_TOLERANCE).ln() / (zeta * omega0);
+    }
+
+    // (Over/critically) damped springs approach the target monotonically, so step
+    // forward from the slowest decay rate until the remaining distance is small enough.
+    let slowest_rate = omega0 * (zeta - (zeta * zeta - 1.0).sqrt());
+    let mut time = (1.0 / SPRING_SETTLE_TOLERANCE).ln() / slowest_rate;
+    for _ in 0..64 {
+        if 1.0 - spring_position(omega0, zeta, time) <= SPRING_SETTLE_TOLERANCE {
+            break;
+        }
+        time *= 1.1;
+    }
+    time
+}
+
+/// Exponential ease-out normalized to reach 1 at `t = 1`.
+fn decay(rate: f64, t: f64) -> f64 {
+    if rate <= 0.0 {
+        return t;
+    }
+    (1.0 - (-rate * t).exp()) / (1.0 - (-rate).exp())
+}
+
+/// Evaluate a CSS `cubic-bezier()` timing function at progress `t`.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, t: f64) -> f64 {
+    // Control points are P0 = (0, 0), P1 = (x1, y1), P2 = (x2, y2), P3 = (1, 1).
+    let x1 = x1.clamp(0.0, 1.0);
+    let x2 = x2.clamp(0.0, 1.0);
+
+    let bezier = |p1: f64, p2: f64, s: f64| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+    };
+    let bezier_slope = |p1: f64, p2: f64, s: f64| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * p1 + 6.0 * inv * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+    };
+
+    // Solve x(s) = t for s: Newton's method first, bisection as a fallback.
+    let mut s = t;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, s) - t;
+        if error.abs() < 1e-7 {
+            return bezier(y1, y2, s);
+        }
+        let slope = bezier_slope(x1, x2, s);
+        if slope.abs() < 1e-7 {
+            break;
+        }
+        s -= error / slope;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    s = t;
+    for _ in 0..32 {
+        let x = bezier(x1, x2, s);
+        if (x - t).abs() < 1e-7 {
+            break;
+        }
+        if x < t {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+
+    bezier(y1, y2, s)
+}
+
+impl AnimatedProperty {
+    /// Whether changing the property moves or resizes boxes, rather than only
+    /// changing how they are painted.
+    pub fn affects_layout(&self) -> bool {
+        match self {
+            AnimatedProperty::Width | AnimatedProperty::Height => true,
+            AnimatedProperty::Color | AnimatedProperty::BackgroundColor => false,
+        }
+    }
+
+    /// Read the raw specified value, so it can be restored verbatim later.
+    fn snapshot(&self, style: &Style) -> PropertySnapshot {
+        match self {
+            AnimatedProperty::Width => PropertySnapshot::Length(style.width),
+            AnimatedProperty::Height => PropertySnapshot::Length(style.height),
+            AnimatedProperty::Color => PropertySnapshot::Color(style.color),
+            AnimatedProperty::BackgroundColor => PropertySnapshot::Color(style.background_color),
+        }
+    }
+
+    fn restore(&self, style: &mut Style, snapshot: PropertySnapshot) {
+        match (self, snapshot) {
+            (AnimatedProperty::Width, PropertySnapshot::Length(v)) => style.width = v,
+            (AnimatedProperty::Height, PropertySnapshot::Length(v)) => style.height = v,
+            (AnimatedProperty::Color, PropertySnapshot::Color(v)) => style.color = v,
+            (AnimatedProperty::BackgroundColor, PropertySnapshot::Color(v)) => {
+                style.background_color = v
+            }
+            _ => {}
+        }
+    }
+
+    fn write(&self, style: &mut Style, value: AnimationValue) {
+        match (self, value) {
+            (AnimatedProperty::Width, AnimationValue::Px(px)) => style.width = Some(Length::Px(px)),
+            (AnimatedProperty::Height, AnimationValue::Px(px)) => {
+                style.height = Some(Length::Px(px))
+            }
+            (AnimatedProperty::Color, AnimationValue::Color(c)) => style.color = Some(c),
+            (AnimatedProperty::BackgroundColor, AnimationValue::Color(c)) => {
+                style.background_color = Some(c)
+            }
+            // Mismatched value kinds are ignored.
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum PropertySnapshot {
+    Length(Option<Length>),
+    Color(Option<Rgba>),
+}
+
+impl AnimationValue {
+    /// Interpolate between two values at eased progress `t`.
+    ///
+    /// Values of different kinds cannot be interpolated and flip at the midpoint,
+    /// like discretely animated CSS properties.
+    pub fn interpolate(&self, to: &AnimationValue, t: f64) -> AnimationValue {
+        match (self, to) {
+            (AnimationValue::Px(a), AnimationValue::Px(b)) => AnimationValue::Px(a + (b - a) * t),
+            (AnimationValue::Color(a), AnimationValue::Color(b)) => {
+                let lerp = |a: u8, b: u8| -> u8 {
+                    (a as f64 + (b as f64 - a as f64) * t)
+                        .round()
+                        .clamp(0.0, 255.0) as u8
+                };
+                AnimationValue::Color(Rgba {
+                    r: lerp(a.r, b.r),
+                    g: lerp(a.g, b.g),
+                    b: lerp(a.b, b.b),
+                    a: lerp(a.a, b.a),
+                })
+            }
+            _ => {
+                if t < 0.5 {
+                    *self
+                } else {
+                    *to
+                }
+            }
+        }
+    }
+}
+
+/// Callback fired once when an animation runs to completion.
+pub(crate) type FinishedCallback = Box<dyn FnOnce() + Send>;
+
+/// Parameters of an animation, as sent from the [`crate::Engine`] to the command thread.
+pub(crate) struct AnimationSpec {
+    pub id: u64,
+    pub node: Id,
+    pub property: AnimatedProperty,
+    pub from: AnimationValue,
+    pub to: AnimationValue,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+/// Handle to a running animation created by [`crate::Engine::animate`].
+#[derive(Clone)]
+pub struct AnimationHandle {
+    id: u64,
+    sender: Sender<Command>,
+}
+
+impl AnimationHandle {
+    pub(crate) fn new(id: u64, sender: Sender<Command>) -> Self {
+        Self { id, sender }
+    }
+
+    /// Freeze the animation at its current progress
+    pub fn pause(&self) -> Result<(), EngineError> {
+        self.send(Command::PauseAnimation(self.id))
+    }
+
+    /// Continue a paused animation from where it was paused
+    pub fn resume(&self) -> Result<(), EngineError> {
+        self.send(Command::ResumeAnimation(self.id))
+    }
+
+    /// Stop the animation and restore the property's pre-animation value
+    pub fn cancel(&self) -> Result<(), EngineError> {
+        self.send(Command::CancelAnimation(self.id))
+    }
+
+    /// Register a callback fired on the command thread when the animation finishes.
+    ///
+    /// Cancelled animations never fire their callbacks. If the animation has already
+    /// ended, the callback runs immediately.
+    pub fn on_finished<F>(&self, callback: F) -> Result<(), EngineError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.send(Command::OnAnimationFinished(self.id, Box::new(callback)))
+    }
+
+    fn send(&self, command: Command) -> Result<(), EngineError> {
+        self.sender
+            .send(command)
+            .map_err(|_| EngineError::CommandThreadDown)
+    }
+}
+
+struct Animation {
+    spec: AnimationSpec,
+    started: Instant,
+    paused_at: Option<Instant>,
+    original: PropertySnapshot,
+    on_finished: Vec<FinishedCallback>,
+}
+
+impl Animation {
+    fn progress(&self, now: Instant) -> f64 {
+        let end = self.paused_at.unwrap_or(now);
+        let elapsed = end.saturating_duration_since(self.started);
+        if self.spec.duration.is_zero() {
+            return 1.0;
+        }
+        (elapsed.as_secs_f64() / self.spec.duration.as_secs_f64()).min(1.0)
+    }
+
+    fn value_at(&self, progress: f64) -> AnimationValue {
+        let eased = self.spec.easing.sample(progress);
+        self.spec.from.interpolate(&self.spec.to, eased)
+    }
+}
+
+/// What animations and smooth scrolls do while the user prefers reduced motion, see
+/// [`crate::Engine::set_reduced_motion`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReducedMotion {
+    /// Run as usual; stylesheets can still adapt with `@media (prefers-reduced-motion)`.
+    Ignore,
+    /// Jump straight to the end.
+    #[default]
+    Skip,
+    /// Run for at most this long.
+    Shorten(Duration),
+}
+
+/// Duration of a `scroll-behavior: smooth` scroll.
+pub(crate) const SMOOTH_SCROLL_DURATION: Duration = Duration::from_millis(300);
+
+/// A smooth scroll of a node's content towards a target offset.
+struct ScrollAnimation {
+    node: Id,
+    from: ScrollOffset,
+    to: ScrollOffset,
+    started: Instant,
+    duration: Duration,
+}
+
+impl ScrollAnimation {
+    fn progress(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.started);
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (elapsed.as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+    }
+
+    fn offset_at(&self, progress: f64) -> ScrollOffset {
+        let t = Easing::Ease.sample(progress);
+        ScrollOffset {
+            x: self.from.x + (self.to.x - self.from.x) * t,
+            y: self.from.y + (self.to.y - self.from.y) * t,
+        }
+    }
+}
+
+/// Animation state owned by the command thread.
+///
+/// Every tick, the current value of each animation is written into
+/// [`LayoutContext::style_overrides`], which is applied on top of the stylesheet cascade.
+/// When an animation ends, the value the property had before it started is written back
+/// into the node's base style, so stylesheet-driven values take over again.
+#[derive(Default)]
+pub(crate) struct Animator {
+    animations: Vec<Animation>,
+    scrolls: Vec<ScrollAnimation>,
+    /// Longest an animation or smooth scroll started from now on may run, if limited.
+    max_duration: Option<Duration>,
+}
+
+impl Animator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether any animation needs frame ticks.
+    pub fn is_running(&self) -> bool {
+        self.animations.iter().any(|a| a.paused_at.is_none()) || !self.scrolls.is_empty()
+    }
+
+    /// Limit how long animations and smooth scrolls started from now on run, following
+    /// the user's motion preference and how the engine handles it.
+    pub fn set_reduced_motion(&mut self, preference: MotionPreference, handling: ReducedMotion) {
+        self.max_duration = match (preference, handling) {
+            (MotionPreference::NoPreference, _) | (_, ReducedMotion::Ignore) => None,
+            (MotionPreference::Reduce, ReducedMotion::Skip) => Some(Duration::ZERO),
+            (MotionPreference::Reduce, ReducedMotion::Shorten(max)) => Some(max),
+        };
+    }
+
+    fn limit_duration(&self, duration: Duration) -> Duration {
+        self.max_duration.map_or(duration, |max| duration.min(max))
+    }
+
+    pub fn start(&mut self, mut spec: AnimationSpec, ctx: &LayoutContext, now: Instant) {
+        spec.duration = self.limit_duration(spec.duration);
+        let original = match ctx.document.get_node(spec.node) {
+            Some(node) => spec.property.snapshot(&node.borrow().layout.style),
+            None => spec.property.snapshot(&Style::default()),
+        };
+
+        // A new animation of the same property replaces the old one, which keeps
+        // the original value of the first.
+        let original = match self
+            .animations
+            .iter()
+            .position(|a| a.spec.node == spec.node && a.spec.property == spec.property)
+        {
+            Some(index) => self.animations.remove(index).original,
+            None => original,
+        };
+
+        self.animations.push(Animation {
+            spec,
+            started: now,
+            paused_at: None,
+            original,
+            on_finished: Vec::new(),
+        });
+    }
+
+    pub fn pause(&mut self, id: u64, now: Instant) {
+        if let Some(animation) = self.animations.iter_mut().find(|a| a.spec.id == id) {
+            if animation.paused_at.is_none() {
+                animation.paused_at = Some(now);
+            }
+        }
+    }
+
+    pub fn resume(&mut self, id: u64, now: Instant) {
+        if let Some(animation) = self.animations.iter_mut().find(|a| a.spec.id == id) {
+            if let Some(paused_at) = animation.paused_at.take() {
+                animation.started += now.saturating_duration_since(paused_at);
+            }
+        }
+    }
+
+    pub fn cancel(&mut self, id: u64, ctx: &mut LayoutContext, now: Instant) {
+        if let Some(index) = self.animations.iter().position(|a| a.spec.id == id) {
+            let animation = self.animations.remove(index);
+            restore_original(&animation, ctx);
+            self.write_overrides(ctx, now);
+        }
+    }
+
+    pub fn on_finished(&mut self, id: u64, callback: FinishedCallback) {
+        match self.animations.iter_mut().find(|a| a.spec.id == id) {
+            Some(animation) => animation.on_finished.push(callback),
+            None => callback(),
+        }
+    }
+
+    /// Smoothly scroll `node` from its current offset to `to`, replacing any
+    /// smooth scroll already in flight for it.
+    pub fn scroll(&mut self, node: Id, to: ScrollOffset, ctx: &LayoutContext, now: Instant) {
+        let Some(from) = ctx
+            .document
+            .get_node(node)
+            .map(|n| n.borrow().scroll_offset)
+        else {
+            return;
+        };
+        self.cancel_scroll(node);
+        self.scrolls.push(ScrollAnimation {
+            node,
+            from,
+            to,
+            started: now,
+            duration: self.limit_duration(SMOOTH_SCROLL_DURATION),
+        });
+    }
+
+    /// Stop a smooth scroll of `node` where it currently is.
+    pub fn cancel_scroll(&mut self, node: Id) {
+        self.scrolls.retain(|s| s.node != node);
+    }
+
+    /// Advance all animations to `now`, retiring those that have finished.
+    ///
+    /// Returns whether the document must be laid out again. When it doesn't, only paint
+    /// properties changed, and their new values in [`LayoutContext::style_overrides`] can
+    /// be patched into the published snapshot with [`RenderNode::apply_paint_overrides`].
+    ///
+    /// [`RenderNode::apply_paint_overrides`]: crate::layout::RenderNode::apply_paint_overrides
+    pub fn tick(&mut self, ctx: &mut LayoutContext, now: Instant) -> bool {
+        let scrolled = !self.scrolls.is_empty();
+        self.scrolls.retain(|scroll| {
+            let progress = scroll.progress(now);
+            if let Some(node) = ctx.document.get_node(scroll.node) {
+                node.borrow_mut().scroll_offset = scroll.offset_at(progress);
+            }
+            progress < 1.0
+        });
+
+        let (finished, running): (Vec<_>, Vec<_>) = self
+            .animations
+            .drain(..)
+            .partition(|a| a.paused_at.is_none() && a.progress(now) >= 1.0);
+        self.animations = running;
+
+        self.write_overrides(ctx, now);
+
+        let needs_layout = scrolled
+            || !finished.is_empty()
+            || self
+                .animations
+                .iter()
+                .any(|a| a.paused_at.is_none() && a.spec.property.affects_layout());
+
+        for animation in finished {
+            restore_original(&animation, ctx);
+            for callback in animation.on_finished {
+                callback();
+            }
+        }
+        needs_layout
+    }
+
+    fn write_overrides(&self, ctx: &mut LayoutContext, now: Instant) {
+        let mut overrides: HashMap<Id, Style> = HashMap::new();
+        for animation in &self.animations {
+            let value = animation.value_at(animation.progress(now));
+            let style = overrides.entry(animation.spec.node).or_default();
+            animation.spec.property.write(style, value);
+        }
+        ctx.style_overrides = overrides;
+    }
+}
+
+fn restore_original(animation: &Animation, ctx: &LayoutContext) {
+    if let Some(node) = ctx.document.get_node(animation.spec.node) {
+        let mut node = node.borrow_mut();
+        let mut style = node.layout.style.as_ref().clone();
+        animation
+            .spec
+            .property
+            .restore(&mut style, animation.original);
+        node.layout.style = Arc::new(style);
+    }
+}
+
+#[cfg(test)]
+mod animation_tests;
@@ -0,0 +1,321 @@
+use super::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+fn spec(node: Id, from: f64, to: f64) -> AnimationSpec {
+    AnimationSpec {
+        id: 1,
+        node,
+        property: AnimatedProperty::Width,
+        from: AnimationValue::Px(from),
+        to: AnimationValue::Px(to),
+        duration: Duration::from_millis(100),
+        easing: Easing::Linear,
+    }
+}
+
+fn context_with_node(width: Option<Length>) -> (LayoutContext, Id) {
+    let mut ctx = LayoutContext::new();
+    let id = ctx.document.create_node(Id::from_u64(1), None);
+    let node = ctx.document.get_node(id).unwrap();
+    node.borrow_mut().layout.style = Arc::new(Style {
+        width,
+        ..Default::default()
+    });
+    (ctx, id)
+}
+
+fn overridden_width(ctx: &LayoutContext, id: Id) -> Option<Length> {
+    ctx.style_overrides.get(&id).and_then(|style| style.width)
+}
+
+#[test]
+fn test_easing_endpoints() {
+    for easing in [
+        Easing::Linear,
+        Easing::Ease,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+        Easing::CubicBezier(0.1, 0.7, 1.0, 0.1),
+        Easing::Spring(10.0, 100.0),
+        Easing::Spring(20.0, 100.0),
+        Easing::Spring(40.0, 100.0),
+        Easing::Decay(5.0),
+    ] {
+        assert!(easing.sample(0.0).abs() < 1e-6, "{:?}", easing);
+        assert!((easing.sample(1.0) - 1.0).abs() < 1e-6, "{:?}", easing);
+    }
+}
+
+#[test]
+fn test_cubic_bezier_matches_known_values() {
+    // `ease-in-out` is symmetric around the midpoint.
+    assert!((Easing::EaseInOut.sample(0.5) - 0.5).abs() < 1e-4);
+    // `ease-in` starts slow, `ease-out` starts fast.
+    assert!(Easing::EaseIn.sample(0.25) < 0.25);
+    assert!(Easing::EaseOut.sample(0.25) > 0.25);
+    // A linear bezier is the identity.
+    assert!((Easing::CubicBezier(0.0, 0.0, 1.0, 1.0).sample(0.3) - 0.3).abs() < 1e-4);
+}
+
+#[test]
+fn test_spring_easing() {
+    // Underdamped springs overshoot the target.
+    let underdamped = Easing::Spring(4.0, 100.0);
+    let max = (0..100)
+        .map(|i| underdamped.sample(i as f64 / 100.0))
+        .fold(f64::MIN, f64::max);
+    assert!(max > 1.0);
+
+    // Critically damped and overdamped springs never overshoot.
+    for easing in [Easing::Spring(20.0, 100.0), Easing::Spring(60.0, 100.0)] {
+        let mut previous = 0.0;
+        for i in 0..=100 {
+            let value = easing.sample(i as f64 / 100.0);
+            assert!(value <= 1.0 + 1e-9, "{:?} overshoots", easing);
+            assert!(value + 1e-9 >= previous, "{:?} is not monotonic", easing);
+            previous = value;
+        }
+    }
+
+    // The spring is settled by the end of the animation.
+    assert!((underdamped.sample(0.999) - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn test_decay_easing() {
+    let decay = Easing::Decay(5.0);
+    // Most of the distance is covered early.
+    assert!(decay.sample(0.25) > 0.7);
+    assert!(decay.sample(0.5) < decay.sample(0.75));
+    // A non-positive rate degrades to linear.
+    assert!((Easing::Decay(0.0).sample(0.3) - 0.3).abs() < 1e-9);
+}
+
+#[test]
+fn test_interpolate_values() {
+    assert_eq!(
+        AnimationValue::Px(0.0).interpolate(&AnimationValue::Px(200.0), 0.25),
+        AnimationValue::Px(50.0)
+    );
+
+    let black = Rgba {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    let white = Rgba {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    };
+    assert_eq!(
+        AnimationValue::Color(black).interpolate(&AnimationValue::Color(white), 0.5),
+        AnimationValue::Color(Rgba {
+            r: 128,
+            g: 128,
+            b: 128,
+            a: 255
+        })
+    );
+
+    // Mismatched kinds flip at the midpoint.
+    let px = AnimationValue::Px(10.0);
+    let color = AnimationValue::Color(black);
+    assert_eq!(px.interpolate(&color, 0.4), px);
+    assert_eq!(px.interpolate(&color, 0.6), color);
+}
+
+#[test]
+fn test_animator_writes_overrides_while_running() {
+    let (mut ctx, id) = context_with_node(None);
+    let mut animator = Animator::new();
+    let start = Instant::now();
+
+    animator.start(spec(id, 100.0, 200.0), &ctx, start);
+    assert!(animator.is_running());
+
+    animator.tick(&mut ctx, start + Duration::from_millis(50));
+    assert_eq!(overridden_width(&ctx, id), Some(Length::Px(150.0)));
+}
+
+#[test]
+fn test_animator_tick_needs_layout_only_for_layout_properties() {
+    let (mut ctx, id) = context_with_node(None);
+    let mut animator = Animator::new();
+    let start = Instant::now();
+    let red = Rgba {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+
+    animator.start(
+        AnimationSpec {
+            property: AnimatedProperty::BackgroundColor,
+            from: AnimationValue::Color(Rgba::default()),
+            to: AnimationValue::Color(red),
+            ..spec(id, 0.0, 0.0)
+        },
+        &ctx,
+        start,
+    );
+    assert!(!animator.tick(&mut ctx, start + Duration::from_millis(50)));
+    assert!(ctx.style_overrides[&id].background_color.is_some());
+
+    // Restoring the original value at the end goes through layout.
+    assert!(animator.tick(&mut ctx, start + Duration::from_millis(150)));
+
+    animator.start(spec(id, 100.0, 200.0), &ctx, start);
+    assert!(animator.tick(&mut ctx, start + Duration::from_millis(50)));
+}
+
+#[test]
+fn test_animator_finishes_and_restores_original_value() {
+    let (mut ctx, id) = context_with_node(Some(Length::Px(40.0)));
+    let mut animator = Animator::new();
+    let start = Instant::now();
+    let finished = Arc::new(AtomicBool::new(false));
+
+    animator.start(spec(id, 100.0, 200.0), &ctx, start);
+    let finished_flag = finished.clone();
+    animator.on_finished(
+        1,
+        Box::new(move || finished_flag.store(true, Ordering::SeqCst)),
+    );
+
+    animator.tick(&mut ctx, start + Duration::from_millis(150));
+
+    assert!(!animator.is_running());
+    assert!(finished.load(Ordering::SeqCst));
+    assert_eq!(overridden_width(&ctx, id), None);
+    let node = ctx.document.get_node(id).unwrap();
+    assert_eq!(node.borrow().layout.style.width, Some(Length::Px(40.0)));
+}
+
+#[test]
+fn test_animator_pause_and_resume() {
+    let (mut ctx, id) = context_with_node(None);
+    let mut animator = Animator::new();
+    let start = Instant::now();
+
+    animator.start(spec(id, 0.0, 100.0), &ctx, start);
+    animator.pause(1, start + Duration::from_millis(20));
+    assert!(!animator.is_running());
+
+    // Time passes while paused without advancing the animation.
+    animator.resume(1, start + Duration::from_millis(520));
+    animator.tick(&mut ctx, start + Duration::from_millis(540));
+    assert_eq!(overridden_width(&ctx, id), Some(Length::Px(40.0)));
+}
+
+#[test]
+fn test_animator_cancel_does_not_fire_finished() {
+    let (mut ctx, id) = context_with_node(None);
+    let mut animator = Animator::new();
+    let start = Instant::now();
+    let finished = Arc::new(AtomicBool::new(false));
+
+    animator.start(spec(id, 0.0, 100.0), &ctx, start);
+    let finished_flag = finished.clone();
+    animator.on_finished(
+        1,
+        Box::new(move || finished_flag.store(true, Ordering::SeqCst)),
+    );
+    animator.tick(&mut ctx, start + Duration::from_millis(50));
+    animator.cancel(1, &mut ctx, start + Duration::from_millis(60));
+
+    assert!(!animator.is_running());
+    assert!(!finished.load(Ordering::SeqCst));
+    assert_eq!(overridden_width(&ctx, id), None);
+    let node = ctx.document.get_node(id).unwrap();
+    assert_eq!(node.borrow().layout.style.width, None);
+}
+
+#[test]
+fn test_smooth_scroll_reaches_target() {
+    let (mut ctx, id) = context_with_node(None);
+    let mut animator = Animator::new();
+    let start = Instant::now();
+    let target = ScrollOffset { x: 0.0, y: 120.0 };
+
+    animator.scroll(id, target, &ctx, start);
+    assert!(animator.is_running());
+
+    animator.tick(&mut ctx, start + SMOOTH_SCROLL_DURATION / 2);
+    let midway = ctx.document.get_node(id).unwrap().borrow().scroll_offset;
+    assert!(midway.y > 0.0 && midway.y < 120.0);
+
+    animator.tick(&mut ctx, start + SMOOTH_SCROLL_DURATION);
+    assert_eq!(
+        ctx.document.get_node(id).unwrap().borrow().scroll_offset,
+        target
+    );
+    assert!(!animator.is_running());
+}
+
+#[test]
+fn test_cancel_scroll_keeps_current_offset() {
+    let (mut ctx, id) = context_with_node(None);
+    let mut animator = Animator::new();
+    let start = Instant::now();
+
+    animator.scroll(id, ScrollOffset { x: 0.0, y: 100.0 }, &ctx, start);
+    animator.tick(&mut ctx, start + SMOOTH_SCROLL_DURATION / 2);
+    let interrupted = ctx.document.get_node(id).unwrap().borrow().scroll_offset;
+
+    animator.cancel_scroll(id);
+    assert!(!animator.is_running());
+    animator.tick(&mut ctx, start + SMOOTH_SCROLL_DURATION);
+    assert_eq!(
+        ctx.document.get_node(id).unwrap().borrow().scroll_offset,
+        interrupted
+    );
+}
+
+#[test]
+fn test_reduced_motion_skips_animations_and_smooth_scrolls() {
+    let (mut ctx, id) = context_with_node(None);
+    let mut animator = Animator::new();
+    animator.set_reduced_motion(MotionPreference::Reduce, ReducedMotion::Skip);
+    let start = Instant::now();
+    let target = ScrollOffset { x: 0.0, y: 120.0 };
+
+    animator.start(spec(id, 10.0, 20.0), &ctx, start);
+    animator.scroll(id, target, &ctx, start);
+    animator.tick(&mut ctx, start);
+    assert!(!animator.is_running());
+    assert_eq!(
+        ctx.document.get_node(id).unwrap().borrow().scroll_offset,
+        target
+    );
+
+    // Without the preference, the handling doesn't matter
+    animator.set_reduced_motion(MotionPreference::NoPreference, ReducedMotion::Skip);
+    animator.start(spec(id, 10.0, 20.0), &ctx, start);
+    animator.tick(&mut ctx, start);
+    assert!(animator.is_running());
+}
+
+#[test]
+fn test_reduced_motion_shortens_smooth_scrolls() {
+    let (mut ctx, id) = context_with_node(None);
+    let mut animator = Animator::new();
+    let max = SMOOTH_SCROLL_DURATION / 4;
+    animator.set_reduced_motion(MotionPreference::Reduce, ReducedMotion::Shorten(max));
+    let start = Instant::now();
+    let target = ScrollOffset { x: 0.0, y: 120.0 };
+
+    animator.scroll(id, target, &ctx, start);
+    animator.tick(&mut ctx, start + max / 2);
+    assert!(animator.is_running());
+    animator.tick(&mut ctx, start + max);
+    assert!(!animator.is_running());
+    assert_eq!(
+        ctx.document.get_node(id).unwrap().borrow().scroll_offset,
+        target
+    );
+}
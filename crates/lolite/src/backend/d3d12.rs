@@ -1,4 +1,4 @@
-use super::{InputState, Params, RenderingBackend};
+use super::{BackendOptions, GpuCacheBudget, GpuCacheUsage, InputState, Params, RenderingBackend};
 use anyhow::Result;
 use skia_safe::{
     gpu::{
@@ -29,7 +29,6 @@ use windows::{
     },
 };
 use winit::{
-    dpi::{LogicalSize, Size},
     event::WindowEvent,
     event_loop::ActiveEventLoop,
     window::{Window, WindowAttributes},
@@ -52,27 +51,32 @@ pub struct D3D12Backend {
     input_state: InputState,
     current_width: u32,
     current_height: u32,
+    gpu_cache: GpuCacheBudget,
 }
 
 impl RenderingBackend for D3D12Backend {
-    fn new(event_loop: &ActiveEventLoop) -> Result<Self> {
+    fn new(
+        event_loop: &ActiveEventLoop,
+        window_attributes: WindowAttributes,
+        options: &BackendOptions,
+    ) -> Result<Self> {
         // Enable D3D12 debug layer (best effort)
-        #[cfg(debug_assertions)]
-        unsafe {
-            let mut dbg: Option<ID3D12Debug> = None;
-            if D3D12GetDebugInterface(&mut dbg).is_ok() {
-                if let Some(debug) = dbg {
-                    debug.EnableDebugLayer();
+        if options.d3d12_debug_layer {
+            unsafe {
+                let mut dbg: Option<ID3D12Debug> = None;
+                if D3D12GetDebugInterface(&mut dbg).is_ok() {
+                    if let Some(debug) = dbg {
+                        debug.EnableDebugLayer();
+                    }
                 }
             }
         }
-        let mut window_attributes = WindowAttributes::default();
-        window_attributes.inner_size = Some(Size::new(LogicalSize::new(800, 800)));
-        window_attributes.title = "Lolite CSS - Direct3D 12".into();
+        // Flip-model swap chains can't be multisampled
+        if options.msaa_samples > 1 {
+            eprintln!("MSAA is not supported by the Direct3D 12 backend; rendering without it");
+        }
 
-        let window = event_loop
-            .create_window(window_attributes)
-            .expect("Failed to create window");
+        let window = event_loop.create_window(window_attributes)?;
 
         let hwnd = HWND(u64::from(window.id()) as *mut _);
         let (width, height) = window.inner_size().into();
@@ -88,7 +92,8 @@ impl RenderingBackend for D3D12Backend {
             memory_allocator: None,
             protected_context: Protected::No,
         };
-        let direct_context = unsafe { DirectContext::new_d3d(&backend_context, None) }.unwrap();
+        let mut direct_context = unsafe { DirectContext::new_d3d(&backend_context, None) }.unwrap();
+        options.gpu_cache.apply(&mut direct_context);
 
         let swap_chain: IDXGISwapChain3 = unsafe {
             factory.CreateSwapChainForHwnd(
@@ -124,6 +129,7 @@ impl RenderingBackend for D3D12Backend {
             input_state: InputState::default(),
             current_width: width,
             current_height: height,
+            gpu_cache: options.gpu_cache,
         };
 
         backend.recreate_surfaces(width, height)?;
@@ -165,6 +171,7 @@ impl RenderingBackend for D3D12Backend {
         // Extra flush to ensure state transitions back to PRESENT/COMMON before Present
         self.direct_context.flush_and_submit();
         unsafe { self.swap_chain.Present(1, DXGI_PRESENT::default()) }.unwrap();
+        self.gpu_cache.trim(&mut self.direct_context);
     }
 
     fn input_state_mut(&mut self) -> &mut InputState {
@@ -176,6 +183,28 @@ impl RenderingBackend for D3D12Backend {
     fn request_redraw(&self) {
         self.window.request_redraw();
     }
+
+    fn window_id(&self) -> winit::window::WindowId {
+        self.window.id()
+    }
+
+    fn describe(&self) -> String {
+        match unsafe { self.backend_context.adapter.GetDesc1() } {
+            Ok(desc) => {
+                let name = &desc.Description;
+                let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+                format!(
+                    "{}, feature level 11_0",
+                    String::from_utf16_lossy(&name[..len])
+                )
+            }
+            Err(err) => format!("unknown adapter ({})", err),
+        }
+    }
+
+    fn gpu_cache_usage(&self) -> GpuCacheUsage {
+        GpuCacheUsage::of(&self.direct_context)
+    }
 }
 
 impl D3D12Backend {
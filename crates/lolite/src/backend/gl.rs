@@ -1,4 +1,4 @@
-use super::{InputState, Params, RenderingBackend};
+use super::{BackendOptions, GpuCacheBudget, GpuCacheUsage, InputState, Params, RenderingBackend};
 use anyhow::Result;
 use raw_window_handle::HasWindowHandle;
 use skia_safe::{
@@ -7,7 +7,6 @@ use skia_safe::{
 };
 use std::{ffi::CString, num::NonZeroU32};
 use winit::{
-    dpi::{LogicalSize, Size},
     event::WindowEvent,
     event_loop::ActiveEventLoop,
     window::{Window, WindowAttributes},
@@ -31,6 +30,9 @@ pub struct OpenGlBackend {
     num_samples: usize,
     stencil_size: usize,
     input_state: InputState,
+    /// `GL_VERSION` of the context.
+    version: String,
+    gpu_cache: GpuCacheBudget,
 }
 
 // Guarantee drop order: Window must be dropped after DirectContext.
@@ -79,12 +81,16 @@ impl OpenGlBackend {
 }
 
 impl RenderingBackend for OpenGlBackend {
-    fn new(event_loop: &ActiveEventLoop) -> Result<Self> {
+    fn new(
+        event_loop: &ActiveEventLoop,
+        window_attributes: WindowAttributes,
+        options: &BackendOptions,
+    ) -> Result<Self> {
         use gl::types::GLint;
 
-        let window_attributes = WindowAttributes::default()
-            .with_title("Lolite CSS - OpenGL")
-            .with_inner_size(Size::new(LogicalSize::new(800, 800)));
+        // Prefer the config closest to the requested sample count.
+        let samples_off =
+            |config: &glutin::config::Config| config.num_samples().abs_diff(options.msaa_samples);
 
         let template = ConfigTemplateBuilder::new()
             .with_alpha_size(8)
@@ -98,7 +104,7 @@ impl RenderingBackend for OpenGlBackend {
                         let transparency_check = config.supports_transparency().unwrap_or(false)
                             & !accum.supports_transparency().unwrap_or(false);
 
-                        if transparency_check || config.num_samples() < accum.num_samples() {
+                        if transparency_check || samples_off(&config) < samples_off(&accum) {
                             config
                         } else {
                             accum
@@ -108,10 +114,9 @@ impl RenderingBackend for OpenGlBackend {
             })
             .map_err(|e| anyhow::anyhow!(e.to_string()))?;
 
-        let window = window.expect("Could not create window with OpenGL context");
-        let window_handle = window
-            .window_handle()
-            .expect("Failed to retrieve RawWindowHandle");
+        let window =
+            window.ok_or_else(|| anyhow::anyhow!("Could not create window with OpenGL context"))?;
+        let window_handle = window.window_handle()?;
         let raw_window_handle = window_handle.as_raw();
 
         let context_attributes = ContextAttributesBuilder::new().build(Some(raw_window_handle));
@@ -123,12 +128,11 @@ impl RenderingBackend for OpenGlBackend {
             gl_config
                 .display()
                 .create_context(&gl_config, &context_attributes)
-                .unwrap_or_else(|_| {
+                .or_else(|_| {
                     gl_config
                         .display()
                         .create_context(&gl_config, &fallback_context_attributes)
-                        .expect("failed to create context")
-                })
+                })?
         };
 
         let (width, height): (u32, u32) = window.inner_size().into();
@@ -141,13 +145,10 @@ impl RenderingBackend for OpenGlBackend {
         let gl_surface = unsafe {
             gl_config
                 .display()
-                .create_window_surface(&gl_config, &attrs)
-                .expect("Could not create gl window surface")
+                .create_window_surface(&gl_config, &attrs)?
         };
 
-        let gl_context = not_current_gl_context
-            .make_current(&gl_surface)
-            .expect("Could not make GL context current when setting up skia renderer");
+        let gl_context = not_current_gl_context.make_current(&gl_surface)?;
 
         gl::load_with(|s| {
             gl_config
@@ -155,6 +156,16 @@ impl RenderingBackend for OpenGlBackend {
                 .get_proc_address(CString::new(s).unwrap().as_c_str())
         });
 
+        let version = unsafe {
+            let version = gl::GetString(gl::VERSION);
+            if version.is_null() {
+                anyhow::bail!("Could not query the OpenGL version");
+            }
+            std::ffi::CStr::from_ptr(version.cast())
+                .to_string_lossy()
+                .into_owned()
+        };
+
         let interface = skia_safe::gpu::gl::Interface::new_load_with(|name| {
             if name == "eglGetCurrentDisplay" {
                 return std::ptr::null();
@@ -167,6 +178,7 @@ impl RenderingBackend for OpenGlBackend {
 
         let mut gr_context = skia_safe::gpu::direct_contexts::make_gl(interface, None)
             .ok_or_else(|| anyhow::anyhow!("Could not create Skia GL direct context"))?;
+        options.gpu_cache.apply(&mut gr_context);
 
         let fb_info = {
             let mut fboid: GLint = 0;
@@ -196,6 +208,8 @@ impl RenderingBackend for OpenGlBackend {
             num_samples,
             stencil_size,
             input_state: InputState::default(),
+            version,
+            gpu_cache: options.gpu_cache,
         })
     }
 
@@ -227,6 +241,7 @@ impl RenderingBackend for OpenGlBackend {
         (params.on_draw)(self.env.surface.canvas());
         self.env.gr_context.flush_and_submit();
         let _ = self.env.gl_surface.swap_buffers(&self.env.gl_context);
+        self.gpu_cache.trim(&mut self.env.gr_context);
     }
 
     fn input_state_mut(&mut self) -> &mut InputState {
@@ -240,4 +255,16 @@ impl RenderingBackend for OpenGlBackend {
     fn request_redraw(&self) {
         self.env.window.request_redraw();
     }
+
+    fn window_id(&self) -> winit::window::WindowId {
+        self.env.window.id()
+    }
+
+    fn describe(&self) -> String {
+        self.version.clone()
+    }
+
+    fn gpu_cache_usage(&self) -> GpuCacheUsage {
+        GpuCacheUsage::of(&self.env.gr_context)
+    }
 }
@@ -1,7 +1,8 @@
-use super::{InputState, Params, RenderingBackend};
+use super::{
+    BackendOptions, GpuCacheBudget, GpuCacheUsage, InputState, Params, RenderingBackend,
+};
 use anyhow::Result;
 use winit::{
-    dpi::{LogicalSize, Size},
     event::WindowEvent,
     event_loop::ActiveEventLoop,
     window::{Window, WindowAttributes},
@@ -36,23 +37,31 @@ pub struct MetalBackend {
     input_state: InputState,
     current_width: u32,
     current_height: u32,
+    gpu_cache: GpuCacheBudget,
 }
 
 impl RenderingBackend for MetalBackend {
-    fn new(event_loop: &ActiveEventLoop) -> Result<Self> {
-        let mut window_attributes = WindowAttributes::default();
-        window_attributes.inner_size = Some(Size::new(LogicalSize::new(800, 800)));
-        window_attributes.title = "Lolite CSS - Metal".into();
-        
+    fn new(
+        event_loop: &ActiveEventLoop,
+        mut window_attributes: WindowAttributes,
+        options: &BackendOptions,
+    ) -> Result<Self> {
+        // Metal reads these when the device is created
+        if options.metal_validation {
+            std::env::set_var("MTL_DEBUG_LAYER", "1");
+            std::env::set_var("METAL_DEVICE_WRAPPER_TYPE", "1");
+        }
+        if options.msaa_samples > 1 {
+            eprintln!("MSAA is not supported by the Metal backend; rendering without it");
+        }
+
         // Enable high DPI awareness on macOS
         #[cfg(target_os = "macos")]
         {
             window_attributes = window_attributes.with_theme(Some(winit::window::Theme::Light));
         }
 
-        let window = event_loop
-            .create_window(window_attributes)
-            .expect("Failed to create window");
+        let window = event_loop.create_window(window_attributes)?;
 
         let logical_size = window.inner_size();
         let physical_size = window.outer_size();
@@ -109,8 +118,9 @@ impl RenderingBackend for MetalBackend {
         };
 
         // Create Skia Metal DirectContext
-        let direct_context = unsafe { make_metal(&backend_context, None) }
+        let mut direct_context = unsafe { make_metal(&backend_context, None) }
             .ok_or_else(|| anyhow::anyhow!("Failed to create Metal DirectContext"))?;
+        options.gpu_cache.apply(&mut direct_context);
 
         let mut backend = Self {
             window,
@@ -121,6 +131,7 @@ impl RenderingBackend for MetalBackend {
             input_state: InputState::default(),
             current_width: width,
             current_height: height,
+            gpu_cache: options.gpu_cache,
         };
 
         backend.recreate_surfaces(width, height)?;
@@ -186,6 +197,7 @@ impl RenderingBackend for MetalBackend {
             // Present the drawable
             drawable.present();
         }
+        self.gpu_cache.trim(&mut self.direct_context);
     }
 
     fn input_state_mut(&mut self) -> &mut InputState {
@@ -199,6 +211,18 @@ impl RenderingBackend for MetalBackend {
     fn request_redraw(&self) {
         self.window.request_redraw();
     }
+
+    fn window_id(&self) -> winit::window::WindowId {
+        self.window.id()
+    }
+
+    fn describe(&self) -> String {
+        self.device.name().to_string()
+    }
+
+    fn gpu_cache_usage(&self) -> GpuCacheUsage {
+        GpuCacheUsage::of(&self.direct_context)
+    }
 }
 
 impl MetalBackend {
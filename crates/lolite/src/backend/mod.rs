@@ -1,41 +1,71 @@
-use anyhow::Result;
-use skia_safe::Canvas;
-use winit::{event::WindowEvent, event_loop::ActiveEventLoop};
+use std::time::Duration;
+#[cfg(feature = "windowing")]
+use {
+    crate::input::KeyEvent,
+    crate::style::ColorScheme,
+    crate::zoom::ZoomStep,
+    anyhow::Result,
+    skia_safe::{gpu::DirectContext, Canvas},
+    winit::{
+        dpi::PhysicalPosition,
+        event::WindowEvent,
+        event_loop::ActiveEventLoop,
+        window::{WindowAttributes, WindowId},
+    },
+};
 
-#[cfg(target_os = "windows")]
+#[cfg(all(feature = "windowing", target_os = "windows"))]
 pub mod d3d12;
-#[cfg(target_os = "linux")]
+#[cfg(all(feature = "windowing", target_os = "linux"))]
 pub mod gl;
-#[cfg(target_os = "macos")]
+#[cfg(all(feature = "windowing", target_os = "macos"))]
 pub mod metal;
 
 /// Common parameters shared across all rendering backends
+#[cfg(feature = "windowing")]
 pub struct Params {
     pub on_draw: Box<dyn FnMut(&Canvas)>,
     pub on_click: Box<dyn FnMut(f64, f64)>, // x, y coordinates
+    pub on_scroll: Box<dyn FnMut(f64, f64, f64, f64)>, // x, y coordinates, dx, dy in px
+    pub on_pointer: Box<dyn FnMut(Option<PhysicalPosition<f64>>, bool)>, // cursor if inside, left button held
+    pub on_resize: Box<dyn FnMut(f64, f64)>, // new inner width, height in px
+    pub on_present: Box<dyn FnMut(Duration, u32, GpuCacheUsage)>, // render time, missed vsyncs
+    pub on_zoom: Box<dyn FnMut(ZoomStep)>,
+    pub on_color_scheme: Box<dyn FnMut(ColorScheme)>, // system or window theme
+    pub on_key: Box<dyn FnMut(&KeyEvent)>,
+    pub on_focus: Box<dyn FnMut(bool)>, // window activated or deactivated
 }
 
 /// State shared across all backends for input handling
+#[cfg(feature = "windowing")]
 pub struct InputState {
     pub x: f32,
     pub y: f32,
-    pub cursor_position: Option<winit::dpi::PhysicalPosition<f64>>,
+    pub cursor_position: Option<PhysicalPosition<f64>>,
+    pub left_button_pressed: bool,
 }
 
+#[cfg(feature = "windowing")]
 impl Default for InputState {
     fn default() -> Self {
         Self {
             x: 100.0,
             y: 100.0,
             cursor_position: None,
+            left_button_pressed: false,
         }
     }
 }
 
 /// Trait that all rendering backends must implement
+#[cfg(feature = "windowing")]
 pub trait RenderingBackend {
-    /// Create a new backend instance
-    fn new(event_loop: &ActiveEventLoop) -> Result<Self>
+    /// Create a new backend instance rendering into a window with the given attributes
+    fn new(
+        event_loop: &ActiveEventLoop,
+        window_attributes: WindowAttributes,
+        options: &BackendOptions,
+    ) -> Result<Self>
     where
         Self: Sized;
 
@@ -53,23 +83,101 @@ pub trait RenderingBackend {
 
     /// Request a redraw
     fn request_redraw(&self);
+
+    /// Id of the window rendered into
+    fn window_id(&self) -> WindowId;
+
+    /// Describe the device and API version in use, for [`BackendReport`]
+    fn describe(&self) -> String;
+
+    /// Memory held by the GPU resource cache after the last frame
+    fn gpu_cache_usage(&self) -> GpuCacheUsage;
 }
 
-/// Available backend types
+/// Which rendering backend the event loop ended up on, and why.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackendReport {
+    /// Name of the backend in use.
+    pub backend: &'static str,
+    /// What the backend found when it started, e.g. the OpenGL version.
+    pub details: String,
+    /// Preferred backends that failed to start, with the reason, in preference order.
+    pub skipped: Vec<(&'static str, String)>,
+}
+
+/// Rendering backends. Only some are available on each platform, see
+/// [`BackendType::is_available`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BackendType {
-    #[cfg(all(target_os = "windows"))]
+    /// Direct3D 12, on Windows.
     D3D12,
-    #[cfg(target_os = "macos")]
+    /// Metal, on macOS.
     Metal,
-    #[cfg(target_os = "linux")]
+    /// OpenGL, on Linux.
     OpenGL,
 }
 
 impl BackendType {
+    /// Backends usable on the current platform, most preferred first
+    pub fn preference_list() -> Vec<Self> {
+        vec![Self::default()]
+    }
+
+    /// Whether this backend is compiled in for the current platform.
+    pub fn is_available(&self) -> bool {
+        match self {
+            BackendType::D3D12 => cfg!(target_os = "windows"),
+            BackendType::Metal => cfg!(target_os = "macos"),
+            BackendType::OpenGL => cfg!(target_os = "linux"),
+        }
+    }
+
+    /// Create a window rendered with this backend
+    #[cfg(feature = "windowing")]
+    pub(crate) fn create(
+        &self,
+        event_loop: &ActiveEventLoop,
+        window_attributes: WindowAttributes,
+        options: &BackendOptions,
+    ) -> Result<Box<dyn RenderingBackend>> {
+        Ok(match self {
+            #[cfg(target_os = "windows")]
+            BackendType::D3D12 => Box::new(d3d12::D3D12Backend::new(
+                event_loop,
+                window_attributes,
+                options,
+            )?),
+            #[cfg(target_os = "macos")]
+            BackendType::Metal => Box::new(metal::MetalBackend::new(
+                event_loop,
+                window_attributes,
+                options,
+            )?),
+            #[cfg(target_os = "linux")]
+            BackendType::OpenGL => Box::new(gl::OpenGlBackend::new(
+                event_loop,
+                window_attributes,
+                options,
+            )?),
+            #[allow(unreachable_patterns)]
+            _ => anyhow::bail!("not available on this platform"),
+        })
+    }
+
+    /// Get a human-readable name for the backend
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackendType::D3D12 => "Direct3D 12",
+            BackendType::Metal => "Metal",
+            BackendType::OpenGL => "OpenGL",
+        }
+    }
+}
+
+impl Default for BackendType {
     /// Get the default backend for the current platform
-    pub fn default() -> Self {
-        #[cfg(all(target_os = "windows"))]
+    fn default() -> Self {
+        #[cfg(target_os = "windows")]
         return BackendType::D3D12;
 
         #[cfg(target_os = "macos")]
@@ -81,16 +189,86 @@ impl BackendType {
         #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
         compile_error!("No default backend available for this platform");
     }
+}
 
-    /// Get a human-readable name for the backend
-    pub fn name(&self) -> &'static str {
-        match self {
-            #[cfg(all(target_os = "windows"))]
-            BackendType::D3D12 => "Direct3D 12",
-            #[cfg(target_os = "macos")]
-            BackendType::Metal => "Metal",
-            #[cfg(target_os = "linux")]
-            BackendType::OpenGL => "OpenGL",
+/// How [`crate::Engine::run`] sets up rendering. Options for a backend other than the
+/// one in use are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackendOptions {
+    /// Backends to try, most preferred first; the first one that starts is used.
+    pub preference: Vec<BackendType>,
+    /// Enable the Direct3D 12 debug layer. On by default in debug builds.
+    pub d3d12_debug_layer: bool,
+    /// Enable Metal API validation. On by default in debug builds.
+    pub metal_validation: bool,
+    /// Samples per pixel for multisample anti-aliasing, or 0 to disable it. The
+    /// closest sample count the device supports is used.
+    pub msaa_samples: u8,
+    /// Smooth the edges of backgrounds, rounded corners and borders by how much of each
+    /// pixel they cover. Works on every backend, unlike `msaa_samples`. On by default.
+    pub anti_alias: bool,
+    /// Memory each window may keep in GPU resources cached between frames.
+    pub gpu_cache: GpuCacheBudget,
+}
+
+impl Default for BackendOptions {
+    fn default() -> Self {
+        Self {
+            preference: BackendType::preference_list(),
+            d3d12_debug_layer: cfg!(debug_assertions),
+            metal_validation: cfg!(debug_assertions),
+            msaa_samples: 0,
+            anti_alias: true,
+            gpu_cache: GpuCacheBudget::default(),
+        }
+    }
+}
+
+/// Limits on the GPU resource cache of a window, which keeps textures, glyph atlases
+/// and offscreen layers around for reuse by later frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GpuCacheBudget {
+    /// Bytes the cache may hold; beyond it the least recently used resources are freed
+    /// first. `None` keeps the backend's default, 256 MiB with current Skia.
+    pub max_bytes: Option<usize>,
+    /// Free cached resources that no frame used for this long, even under budget.
+    pub max_unused: Option<Duration>,
+}
+
+#[cfg(feature = "windowing")]
+impl GpuCacheBudget {
+    /// Apply the limit to a freshly created context.
+    pub(crate) fn apply(&self, context: &mut DirectContext) {
+        if let Some(max_bytes) = self.max_bytes {
+            context.set_resource_cache_limit(max_bytes);
+        }
+    }
+
+    /// Evict what went unused for too long, after a frame is submitted.
+    pub(crate) fn trim(&self, context: &mut DirectContext) {
+        if let Some(max_unused) = self.max_unused {
+            context.perform_deferred_cleanup(max_unused, None);
+        }
+    }
+}
+
+/// Memory held by the GPU resource cache of a window, see [`crate::FrameStats::gpu_cache`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct GpuCacheUsage {
+    pub resources: usize,
+    pub bytes: usize,
+    /// Budget the cache is held to, see [`GpuCacheBudget::max_bytes`].
+    pub limit: usize,
+}
+
+#[cfg(feature = "windowing")]
+impl GpuCacheUsage {
+    pub(crate) fn of(context: &DirectContext) -> Self {
+        let usage = context.resource_cache_usage();
+        Self {
+            resources: usage.resource_count,
+            bytes: usage.resource_bytes,
+            limit: context.resource_cache_limit(),
         }
     }
 }
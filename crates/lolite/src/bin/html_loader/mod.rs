@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use lolite::{Engine, Id};
+
+/// Copy the elements and text of an HTML document under the engine's root; `<style>`
+/// elements are added as stylesheets instead.
+pub fn load_html_into_engine(engine: &Engine, html: &str) -> Result<()> {
+    let dom = html_parser::Dom::parse(html).context("Failed to parse HTML")?;
+
+    let mut next_id: u64 = 1;
+    let root = engine.root_id();
+
+    for node in &dom.children {
+        copy_nodes(engine, node, root, &mut next_id)?;
+    }
+
+    Ok(())
+}
+
+fn copy_nodes(
+    engine: &Engine,
+    html_node: &html_parser::Node,
+    parent: Id,
+    next_id: &mut u64,
+) -> Result<()> {
+    match html_node {
+        html_parser::Node::Element(element) => {
+            // Treat <style> as stylesheet input only.
+            if element.name.eq_ignore_ascii_case("style") {
+                if let Some(text) = element.children.get(0).and_then(|child| child.text()) {
+                    engine.add_stylesheet(text)?;
+                }
+                return Ok(());
+            }
+
+            let id = Id::from_u64(*next_id);
+            *next_id += 1;
+
+            // A lone text child is the element's content; otherwise text becomes text nodes.
+            let text = match element.children.as_slice() {
+                [child] => child.text().map(|s| s.to_owned()),
+                _ => None,
+            };
+            let has_text = text.is_some();
+
+            engine.create_node(id, text)?;
+            engine.set_parent(parent, id)?;
+
+            // Preserve element id as an attribute.
+            if let Some(id_attr) = &element.id {
+                engine.set_attribute(id, "id".to_owned(), id_attr.to_owned())?;
+            }
+
+            // Preserve classes.
+            if !element.classes.is_empty() {
+                engine.set_attribute(id, "class".to_owned(), element.classes.join(" "))?;
+            }
+
+            // Preserve other attributes.
+            for (key, value_opt) in &element.attributes {
+                if key == "id" || key == "class" {
+                    continue;
+                }
+                if let Some(value) = value_opt {
+                    engine.set_attribute(id, key.to_owned(), value.to_owned())?;
+                }
+            }
+
+            if !has_text {
+                for child in &element.children {
+                    copy_nodes(engine, child, id, next_id)?;
+                }
+            }
+        }
+        html_parser::Node::Text(text) => {
+            let id = Id::from_u64(*next_id);
+            *next_id += 1;
+            engine.create_text_node(id, text)?;
+            engine.set_parent(parent, id)?;
+        }
+        _ => {
+            // Ignore comments, doctypes, etc.
+        }
+    }
+
+    Ok(())
+}
@@ -1,14 +1,31 @@
+mod html_loader;
+
 use anyhow::{Context, Result};
-use lolite::{Engine, Id, Params};
+use html_loader::load_html_into_engine;
+use lolite::{audit_stylesheet, compile_stylesheet, Engine, Params};
+
+const USAGE: &str = "Usage: lolite_html <path/to/file.html>\n       lolite_html --compile-css <input.css> <output>\n       lolite_html --audit-css <input.css>";
 
 fn main() -> Result<()> {
     let mut args = std::env::args().skip(1);
-    let html_path = args
-        .next()
-        .context("Usage: lolite_html <path/to/file.html>")?;
+    let html_path = args.next().context(USAGE)?;
+
+    if html_path == "--compile-css" {
+        let (Some(input), Some(output), None) = (args.next(), args.next(), args.next()) else {
+            anyhow::bail!(USAGE);
+        };
+        return compile_css_file(&input, &output);
+    }
+
+    if html_path == "--audit-css" {
+        let (Some(input), None) = (args.next(), args.next()) else {
+            anyhow::bail!(USAGE);
+        };
+        return audit_css_file(&input);
+    }
 
     if args.next().is_some() {
-        anyhow::bail!("Usage: lolite_html <path/to/file.html>");
+        anyhow::bail!(USAGE);
     }
 
     let html = std::fs::read_to_string(&html_path)
@@ -22,84 +39,22 @@ fn main() -> Result<()> {
         .map_err(|e| anyhow::anyhow!("Engine failed: {e:?}"))
 }
 
-fn load_html_into_engine(engine: &Engine, html: &str) -> Result<()> {
-    let dom = html_parser::Dom::parse(html).context("Failed to parse HTML")?;
-
-    let mut next_id: u64 = 1;
-    let root = engine.root_id();
-
-    for node in &dom.children {
-        copy_nodes(engine, node, root, &mut next_id)?;
-    }
-
-    Ok(())
+/// Precompile a stylesheet for `Engine::add_compiled_stylesheet`.
+fn compile_css_file(input: &str, output: &str) -> Result<()> {
+    let css = std::fs::read_to_string(input)
+        .with_context(|| format!("Failed to read CSS file: {input}"))?;
+    let compiled = compile_stylesheet(&css)
+        .map_err(|e| anyhow::anyhow!("Failed to parse CSS file {input}: {e}"))?;
+    std::fs::write(output, compiled)
+        .with_context(|| format!("Failed to write compiled stylesheet: {output}"))
 }
 
-fn copy_nodes(
-    engine: &Engine,
-    html_node: &html_parser::Node,
-    parent: Id,
-    next_id: &mut u64,
-) -> Result<()> {
-    match html_node {
-        html_parser::Node::Element(element) => {
-            // Treat <style> as stylesheet input only.
-            if element.name.eq_ignore_ascii_case("style") {
-                if let Some(text) = element.children.get(0).and_then(|child| child.text()) {
-                    engine.add_stylesheet(text);
-                }
-                return Ok(());
-            }
-
-            let id = Id::from_u64(*next_id);
-            *next_id += 1;
-
-            // Lolite currently only supports "text if it's the only child".
-            let text = element
-                .children
-                .get(0)
-                .and_then(|child| child.text())
-                .map(|s| s.to_owned());
-
-            engine.create_node(id, text);
-            engine.set_parent(parent, id);
-
-            // Preserve element id as an attribute.
-            if let Some(id_attr) = &element.id {
-                engine.set_attribute(id, "id".to_owned(), id_attr.to_owned());
-            }
-
-            // Preserve classes.
-            if !element.classes.is_empty() {
-                engine.set_attribute(id, "class".to_owned(), element.classes.join(" "));
-            }
-
-            // Preserve other attributes.
-            for (key, value_opt) in &element.attributes {
-                if key == "id" || key == "class" {
-                    continue;
-                }
-                if let Some(value) = value_opt {
-                    engine.set_attribute(id, key.to_owned(), value.to_owned());
-                }
-            }
-
-            // Recurse into element children.
-            for child in &element.children {
-                // Skip the text child we already captured.
-                if child.text().is_some() {
-                    continue;
-                }
-                copy_nodes(engine, child, id, next_id)?;
-            }
-        }
-        html_parser::Node::Text(_) => {
-            // Text nodes are handled as element text (when they're the only child).
-        }
-        _ => {
-            // Ignore comments, doctypes, etc.
-        }
+/// Print everything in a stylesheet that the engine will ignore.
+fn audit_css_file(input: &str) -> Result<()> {
+    let css = std::fs::read_to_string(input)
+        .with_context(|| format!("Failed to read CSS file: {input}"))?;
+    for diagnostic in audit_stylesheet(&css) {
+        println!("{input}:{diagnostic}");
     }
-
     Ok(())
 }
@@ -0,0 +1,426 @@
+//! Import Web Platform Tests reftests and run them against the headless engine.
+//!
+//! `import` converts a curated list of WPT tests and their `<link rel="match">`
+//! references into documents lolite can load: scripts, metadata and instruction
+//! paragraphs are dropped and inline `style` attributes become `#id` rules. Tests using
+//! elements lolite has no equivalent for are skipped with the reason.
+//!
+//! `run` lays out each test and its reference without a window and compares the painted
+//! backgrounds and borders pixel by pixel. Statuses are kept in `results.tsv` to report
+//! tests that started passing or regressed since the previous run, and each run appends
+//! its pass count to `history.tsv`.
+
+mod html_loader;
+
+use anyhow::{Context, Result};
+use html_loader::load_html_into_engine;
+use lolite::{Engine, Id};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const USAGE: &str =
+    "Usage: lolite_wpt import <wpt-root> <list> <out-dir>\n       lolite_wpt run <dir>";
+
+/// Viewport tests are laid out in, as in WPT's reftest harness.
+const VIEWPORT: (usize, usize) = (800, 600);
+
+/// Lists the converted tests and references, one `<test> == <reference>` per line.
+const MANIFEST: &str = "MANIFEST";
+const RESULTS: &str = "results.tsv";
+const HISTORY: &str = "history.tsv";
+
+/// Elements that can't be approximated with lolite's nodes.
+const UNSUPPORTED_ELEMENTS: &[&str] = &[
+    "script", "img", "iframe", "canvas", "svg", "video", "input", "button", "select", "textarea",
+    "table", "object",
+];
+
+/// Elements dropped from converted documents.
+const DROPPED_ELEMENTS: &[&str] = &["title", "meta", "link", "p"];
+
+fn main() -> Result<ExitCode> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["import", wpt_root, list, out_dir] => {
+            import(Path::new(wpt_root), Path::new(list), Path::new(out_dir))?;
+            Ok(ExitCode::SUCCESS)
+        }
+        ["run", dir] => run(Path::new(dir)),
+        _ => anyhow::bail!(USAGE),
+    }
+}
+
+/// Convert the tests listed in `list`, paths relative to `wpt_root` such as
+/// `css/css-flexbox/align-items-001.html`, into `out_dir` along with a manifest.
+fn import(wpt_root: &Path, list: &Path, out_dir: &Path) -> Result<()> {
+    let list = std::fs::read_to_string(list)
+        .with_context(|| format!("Failed to read test list: {}", list.display()))?;
+
+    let mut manifest = String::new();
+    let mut converted = HashSet::new();
+    for test in list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        match import_test(wpt_root, test, out_dir, &mut converted) {
+            Ok(reference) => {
+                println!("imported {test}");
+                writeln!(manifest, "{test} == {reference}")?;
+            }
+            Err(err) => println!("skipped {test}: {err:#}"),
+        }
+    }
+
+    std::fs::create_dir_all(out_dir)?;
+    std::fs::write(out_dir.join(MANIFEST), manifest)
+        .with_context(|| format!("Failed to write manifest in {}", out_dir.display()))
+}
+
+/// Convert a test and its reference, returning the reference's path.
+fn import_test(
+    wpt_root: &Path,
+    test: &str,
+    out_dir: &Path,
+    converted: &mut HashSet<String>,
+) -> Result<String> {
+    let html = std::fs::read_to_string(wpt_root.join(test)).context("not found")?;
+    let dom = html_parser::Dom::parse(&html).context("invalid HTML")?;
+
+    let mut links = Vec::new();
+    find_links(&dom.children, &mut links);
+    if links.iter().any(|(rel, _)| rel == "mismatch") {
+        anyhow::bail!("mismatch references aren't supported");
+    }
+    let [(_, href)] = links
+        .iter()
+        .filter(|(rel, _)| rel == "match")
+        .collect::<Vec<_>>()[..]
+    else {
+        anyhow::bail!("not a reftest with a single match reference");
+    };
+    let reference = resolve(test, href);
+
+    let reference_html = std::fs::read_to_string(wpt_root.join(&reference))
+        .with_context(|| format!("reference {reference} not found"))?;
+    let reference_dom = html_parser::Dom::parse(&reference_html)
+        .with_context(|| format!("invalid HTML in reference {reference}"))?;
+
+    let test_document = convert(test, &dom)?;
+    let reference_document =
+        convert(&reference, &reference_dom).with_context(|| format!("reference {reference}"))?;
+
+    write(&out_dir.join(test), &test_document)?;
+    if converted.insert(reference.clone()) {
+        write(&out_dir.join(&reference), &reference_document)?;
+    }
+    Ok(reference)
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Collect the `rel` and `href` of every `<link>`.
+fn find_links(nodes: &[html_parser::Node], links: &mut Vec<(String, String)>) {
+    for element in nodes.iter().filter_map(html_parser::Node::element) {
+        if element.name.eq_ignore_ascii_case("link") {
+            if let (Some(Some(rel)), Some(Some(href))) = (
+                element.attributes.get("rel"),
+                element.attributes.get("href"),
+            ) {
+                links.push((rel.to_ascii_lowercase(), href.clone()));
+            }
+        }
+        find_links(&element.children, links);
+    }
+}
+
+/// Resolve a reference's `href` against the path of the test linking to it; paths
+/// starting with `/` are relative to the WPT root.
+fn resolve(test: &str, href: &str) -> String {
+    let mut path: Vec<&str> = Vec::new();
+    if !href.starts_with('/') {
+        path.extend(test.split('/'));
+        path.pop();
+    }
+    for segment in href.split('/') {
+        match segment {
+            "." | "" => {}
+            ".." => {
+                path.pop();
+            }
+            segment => path.push(segment),
+        }
+    }
+    path.join("/")
+}
+
+/// Simplify a WPT document into one lolite can load.
+fn convert(path: &str, dom: &html_parser::Dom) -> Result<String> {
+    let mut converter = Converter::default();
+    for node in &dom.children {
+        converter.copy(node)?;
+    }
+
+    let mut document = format!("<!-- Converted from WPT {path} by lolite_wpt -->\n");
+    for css in &converter.stylesheets {
+        writeln!(document, "<style>{css}</style>")?;
+    }
+    if !converter.inline_styles.is_empty() {
+        writeln!(document, "<style>\n{}</style>", converter.inline_styles)?;
+    }
+    document.push_str(&converter.body);
+    Ok(document)
+}
+
+#[derive(Default)]
+struct Converter {
+    stylesheets: Vec<String>,
+    /// Rules standing in for `style` attributes.
+    inline_styles: String,
+    body: String,
+    next_inline_id: usize,
+    depth: usize,
+}
+
+impl Converter {
+    fn copy(&mut self, node: &html_parser::Node) -> Result<()> {
+        let element = match node {
+            html_parser::Node::Element(element) => element,
+            html_parser::Node::Text(text) => {
+                if !text.trim().is_empty() {
+                    self.indent();
+                    writeln!(self.body, "{}", text.trim())?;
+                }
+                return Ok(());
+            }
+            html_parser::Node::Comment(_) => return Ok(()),
+        };
+        let name = element.name.to_ascii_lowercase();
+
+        if UNSUPPORTED_ELEMENTS.contains(&name.as_str()) {
+            anyhow::bail!("uses <{name}>");
+        }
+        if name == "style" {
+            if let Some(css) = element.children.first().and_then(|child| child.text()) {
+                self.stylesheets.push(css.to_string());
+            }
+            return Ok(());
+        }
+        // Keep the contents of <html>, which lolite's root stands in for, and of <head>,
+        // for its stylesheets.
+        if name == "html" || name == "head" {
+            for child in &element.children {
+                self.copy(child)?;
+            }
+            return Ok(());
+        }
+        if DROPPED_ELEMENTS.contains(&name.as_str()) {
+            return Ok(());
+        }
+
+        let mut id = element.id.clone();
+        if let Some(Some(style)) = element.attributes.get("style") {
+            let id = id.get_or_insert_with(|| {
+                self.next_inline_id += 1;
+                format!("wpt-inline-{}", self.next_inline_id)
+            });
+            writeln!(self.inline_styles, "#{id} {{ {style} }}")?;
+        }
+
+        self.indent();
+        write!(self.body, "<{name}")?;
+        if let Some(id) = id {
+            write!(self.body, " id=\"{id}\"")?;
+        }
+        if !element.classes.is_empty() {
+            write!(self.body, " class=\"{}\"", element.classes.join(" "))?;
+        }
+        // Sorted, so that converting again gives the same document
+        let attributes: BTreeMap<_, _> = element.attributes.iter().collect();
+        for (key, value) in attributes {
+            if matches!(key.as_str(), "id" | "class" | "style") {
+                continue;
+            }
+            match value {
+                Some(value) => write!(self.body, " {key}=\"{value}\"")?,
+                None => write!(self.body, " {key}")?,
+            }
+        }
+        writeln!(self.body, ">")?;
+
+        self.depth += 1;
+        for child in &element.children {
+            self.copy(child)?;
+        }
+        self.depth -= 1;
+        self.indent();
+        writeln!(self.body, "</{name}>")?;
+        Ok(())
+    }
+
+    fn indent(&mut self) {
+        self.body.push_str(&"  ".repeat(self.depth));
+    }
+}
+
+/// Run every test of the manifest in `dir`, failing if any test regressed.
+fn run(dir: &Path) -> Result<ExitCode> {
+    let manifest = std::fs::read_to_string(dir.join(MANIFEST))
+        .with_context(|| format!("No {MANIFEST} in {}; import tests first", dir.display()))?;
+    let previous = read_results(&dir.join(RESULTS));
+
+    let mut results = BTreeMap::new();
+    for line in manifest.lines().filter(|line| !line.trim().is_empty()) {
+        let Some((test, reference)) = line.split_once(" == ") else {
+            eprintln!("Invalid manifest line: {line}");
+            continue;
+        };
+        let status = match compare(&dir.join(test), &dir.join(reference)) {
+            Ok(0) => "PASS".to_string(),
+            Ok(differing) => format!("FAIL ({differing} pixels differ)"),
+            Err(err) => format!("ERROR ({err:#})"),
+        };
+        println!("{status:<32} {test}");
+        results.insert(test.to_string(), status);
+    }
+
+    let passing = |results: &BTreeMap<String, String>, test: &str| {
+        results.get(test).is_some_and(|status| status == "PASS")
+    };
+    let mut regressed = 0;
+    for test in results.keys() {
+        match (passing(&previous, test), passing(&results, test)) {
+            (false, true) if previous.contains_key(test) => println!("newly passing: {test}"),
+            (true, false) => {
+                println!("regressed: {test}");
+                regressed += 1;
+            }
+            _ => {}
+        }
+    }
+
+    let passed = results
+        .keys()
+        .filter(|test| passing(&results, test))
+        .count();
+    println!("{passed} of {} tests pass", results.len());
+
+    let mut contents = String::new();
+    for (test, status) in &results {
+        writeln!(contents, "{test}\t{status}")?;
+    }
+    std::fs::write(dir.join(RESULTS), contents)?;
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut history = std::fs::read_to_string(dir.join(HISTORY)).unwrap_or_default();
+    writeln!(history, "{timestamp}\t{passed}\t{}", results.len())?;
+    std::fs::write(dir.join(HISTORY), history)?;
+
+    Ok(if regressed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Statuses of the previous run by test, or none if there wasn't one.
+fn read_results(path: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(test, status)| (test.to_string(), status.to_string()))
+        .collect()
+}
+
+/// Render a test and its reference, returning how many pixels differ.
+fn compare(test: &Path, reference: &Path) -> Result<usize> {
+    let test = render(test)?;
+    let reference = render(reference)?;
+    Ok(test.iter().zip(&reference).filter(|(a, b)| a != b).count())
+}
+
+/// Lay a document out without a window and paint the backgrounds and borders of its
+/// nodes in document order, one color per pixel.
+fn render(path: &Path) -> Result<Vec<String>> {
+    let html = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let engine = Engine::new();
+    engine.set_viewport(VIEWPORT.0 as f64, VIEWPORT.1 as f64)?;
+    load_html_into_engine(&engine, &html)?;
+    engine.flush()?;
+
+    let mut pixels = vec![String::new(); VIEWPORT.0 * VIEWPORT.1];
+    paint(&engine, engine.root_id(), &mut pixels)?;
+    engine.request_exit()?;
+    Ok(pixels)
+}
+
+fn paint(engine: &Engine, id: Id, pixels: &mut [String]) -> Result<()> {
+    if let (Some(bounds), Some(style)) = (engine.node_bounds(id), engine.computed_style(id)) {
+        let (x, y) = (bounds.x, bounds.y);
+        let (width, height) = (bounds.width, bounds.height);
+        let border = |side: &str| {
+            style
+                .get(&format!("border-{side}-width"))
+                .and_then(|width| width.trim_end_matches("px").parse::<f64>().ok())
+                .unwrap_or(0.0)
+        };
+        let color = |property: &str| style.get(property).unwrap_or_default().to_string();
+        let (top, right, bottom, left) = (
+            border("top"),
+            border("right"),
+            border("bottom"),
+            border("left"),
+        );
+
+        fill(pixels, (x, y, width, height), color("background-color"));
+        fill(pixels, (x, y, width, top), color("border-top-color"));
+        fill(
+            pixels,
+            (x + width - right, y, right, height),
+            color("border-right-color"),
+        );
+        fill(
+            pixels,
+            (x, y + height - bottom, width, bottom),
+            color("border-bottom-color"),
+        );
+        fill(pixels, (x, y, left, height), color("border-left-color"));
+    }
+
+    for child in engine.children(id)?.unwrap_or_default() {
+        paint(engine, child, pixels)?;
+    }
+    Ok(())
+}
+
+/// Fill the pixels whose centers are inside a rectangle, ignoring transparent colors.
+fn fill(pixels: &mut [String], (x, y, width, height): (f64, f64, f64, f64), color: String) {
+    if color.is_empty() || (color.starts_with("rgba(") && color.ends_with(", 0)")) {
+        return;
+    }
+    let columns = |start: f64, end: f64, limit: usize| {
+        let start = (start - 0.5).ceil().clamp(0.0, limit as f64) as usize;
+        let end = (end - 0.5).ceil().clamp(0.0, limit as f64) as usize;
+        start..end
+    };
+    for row in columns(y, y + height, VIEWPORT.1) {
+        for column in columns(x, x + width, VIEWPORT.0) {
+            pixels[row * VIEWPORT.0 + column] = color.clone();
+        }
+    }
+}
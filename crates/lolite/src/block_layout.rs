@@ -0,0 +1,271 @@
+use crate::flex_layout::{
+    collapsed_run_text, is_auto, is_text_run, own_units, place_text_run, resolve_style,
+    text_run_size, transfer_size,
+};
+use crate::layout::{LayoutContext, MeasureConstraints, Node, Rect, Size, SizeLimits};
+use crate::style::{AspectRatio, BoxSizing, Display, Length, Style, TextDirection};
+use crate::text::FontSpec;
+use crate::units::ResolutionContext;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct BlockLayoutEngine;
+
+impl BlockLayoutEngine {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Lays out the children of a block container in normal flow (CSS 2 §9.4.1): each
+    /// child is a block-level box stacked below the previous one, and each run of text
+    /// children is wrapped in an anonymous block box.
+    ///
+    /// The container must already be sized; `units` is the context its own lengths are
+    /// resolved in.
+    pub fn layout_block_children(
+        &self,
+        container: Rc<RefCell<Node>>,
+        container_style: &Style,
+        ctx: &LayoutContext,
+        units: &ResolutionContext,
+    ) {
+        self.layout_flow(container, container_style, ctx, units, true);
+    }
+
+    /// Stack the children in the container's content box, returning the height they
+    /// take up. Without a `definite_height`, the container's height follows from that,
+    /// and percentage heights of the children behave as `auto`.
+    fn layout_flow(
+        &self,
+        container: Rc<RefCell<Node>>,
+        container_style: &Style,
+        ctx: &LayoutContext,
+        units: &ResolutionContext,
+        definite_height: bool,
+    ) -> f64 {
+        let bounds = container.borrow().layout.bounds;
+        let padding = container_style.padding.resolved();
+        let border = container_style.border_width.resolved();
+        let content_x =
+            bounds.x + units.inline_or_zero(border.left) + units.inline_or_zero(padding.left);
+        let content_y =
+            bounds.y + units.inline_or_zero(border.top) + units.inline_or_zero(padding.top);
+        let content_width =
+            (bounds.width - units.horizontal(&padding) - units.horizontal(&border)).max(0.0);
+        let content_height =
+            (bounds.height - units.vertical(&padding) - units.vertical(&border)).max(0.0);
+        let rtl = container_style.direction == Some(TextDirection::Rtl);
+
+        // The container's content box is the containing block of its children.
+        let child_units = units.with_containing_block(Size {
+            width: content_width,
+            height: content_height,
+        });
+
+        // Group children into element boxes and runs of text.
+        let mut runs: Vec<Vec<Rc<RefCell<Node>>>> = Vec::new();
+        for child in container.borrow().box_children() {
+            match runs.last_mut() {
+                Some(run) if is_text_run(run) && child.borrow().is_text_node() => run.push(child),
+                _ => runs.push(vec![child]),
+            }
+        }
+
+        let text_style = container_style.inherited();
+        let mut cursor_y = content_y;
+        // Bottom margin of the previous box, collapsed with the top margin of the next
+        let mut pending_margin = 0.0;
+
+        for run in runs {
+            if is_text_run(&run) {
+                // Runs whose white space collapses away entirely are not rendered.
+                if collapsed_run_text(&run).iter().all(String::is_empty) {
+                    for node in &run {
+                        let mut node = node.borrow_mut();
+                        node.layout.bounds = Rect::new(content_x, cursor_y, 0.0, 0.0);
+                        node.layout.text = None;
+                    }
+                    continue;
+                }
+
+                cursor_y += pending_margin;
+                pending_margin = 0.0;
+                let height = text_run_height(&run, &text_style, ctx, content_width);
+                place_text_run(
+                    &run,
+                    Rect::new(content_x, cursor_y, content_width, height),
+                    &text_style,
+                    ctx,
+                );
+                cursor_y += height;
+                continue;
+            }
+
+            let child = run[0].clone();
+            let style = resolve_style(&child, ctx, container_style, &child_units);
+            let own = own_units(&child_units, &style);
+            let limits = SizeLimits::of(&style, &child_units);
+            let box_sizing = style.box_sizing.unwrap_or(BoxSizing::ContentBox);
+
+            let padding = style.padding.resolved();
+            let border = style.border_width.resolved();
+            let width_edges = own.horizontal(&padding) + own.horizontal(&border);
+            let height_edges = own.vertical(&padding) + own.vertical(&border);
+            let border_box = |px: f64, edges: f64| match box_sizing {
+                BoxSizing::ContentBox => px + edges,
+                BoxSizing::BorderBox => px,
+            };
+
+            // Auto margins are zero here; they take the free space once the width is known.
+            let margin = style.margin.resolved();
+            let margin_left = child_units.inline_or_zero(margin.left);
+            let margin_right = child_units.inline_or_zero(margin.right);
+
+            let specified_height = match style.height {
+                Some(Length::Percent(_)) if !definite_height => None,
+                height => height.and_then(|height| own.block(height)),
+            }
+            .map(|px| border_box(px, height_edges));
+
+            // §10.3.3 An auto width fills the containing block, unless an aspect ratio
+            // gives it from the height.
+            let ratio = style.aspect_ratio.and_then(AspectRatio::value);
+            let specified_width = style.width.and_then(|width| own.inline(width));
+            let width = match (specified_width, ratio, specified_height) {
+                (Some(px), _, _) => border_box(px, width_edges),
+                (None, Some(ratio), Some(height)) => {
+                    transfer_size(height, ratio, height_edges, width_edges, box_sizing)
+                }
+                _ => (content_width - margin_left - margin_right).max(0.0),
+            };
+            let width = limits.clamp_width(width);
+
+            let free = (content_width - width - margin_left - margin_right).max(0.0);
+            let x = match (is_auto(&margin.left), is_auto(&margin.right)) {
+                (true, true) => content_x + free / 2.0,
+                (true, false) => content_x + free,
+                (false, true) => content_x + margin_left,
+                // Boxes start at the right in right-to-left text
+                (false, false) if rtl => content_x + content_width - margin_right - width,
+                (false, false) => content_x + margin_left,
+            };
+
+            // §8.3.1 Adjacent vertical margins of siblings collapse.
+            let margin_top = child_units.inline_or_zero(margin.top);
+            let margin_bottom = child_units.inline_or_zero(margin.bottom);
+            cursor_y += collapse_margins(pending_margin, margin_top);
+
+            let is_container = child.borrow().has_box_children();
+            let fixed_height = specified_height.or_else(|| {
+                ratio.map(|ratio| {
+                    transfer_size(width, 1.0 / ratio, width_edges, height_edges, box_sizing)
+                })
+            });
+            // §10.6.3 An auto height is that of the content; containers find it by laying
+            // out their children.
+            let height = match fixed_height {
+                Some(height) => height,
+                None if is_container => height_edges,
+                // Leaves without text or a measure function have no content.
+                None => leaf_content_height(&child, &style, ctx, (width - width_edges).max(0.0))
+                    .map_or(height_edges, |height| height + height_edges),
+            };
+
+            {
+                let mut node = child.borrow_mut();
+                node.layout.bounds = Rect::new(x, cursor_y, width, limits.clamp_height(height));
+                node.layout.style = Arc::new(style.clone());
+            }
+
+            if is_container {
+                let definite = fixed_height.is_some();
+                let content = match style.display.unwrap_or_default() {
+                    Display::Block => self.layout_flow(child.clone(), &style, ctx, &own, definite),
+                    Display::Flex => {
+                        ctx.layout_children(child.clone(), &style, &own);
+                        let content_top = cursor_y
+                            + own.inline_or_zero(border.top)
+                            + own.inline_or_zero(padding.top);
+                        children_extent(&child, &own) - content_top
+                    }
+                };
+                if !definite {
+                    child.borrow_mut().layout.bounds.height =
+                        limits.clamp_height(content.max(0.0) + height_edges);
+                }
+            }
+
+            cursor_y += child.borrow().layout.bounds.height;
+            pending_margin = margin_bottom;
+        }
+
+        // Margins don't collapse through the container, so the last one stays inside.
+        (cursor_y + pending_margin - content_y).max(0.0)
+    }
+}
+
+/// The margin between two adjacent vertical margins: the largest positive one, less the
+/// most negative one.
+fn collapse_margins(a: f64, b: f64) -> f64 {
+    a.max(b).max(0.0) + a.min(b).min(0.0)
+}
+
+/// Height of a run of text nodes: a single node wraps to `width`, and several are set
+/// side by side on one line.
+fn text_run_height(
+    run: &[Rc<RefCell<Node>>],
+    style: &Style,
+    ctx: &LayoutContext,
+    width: f64,
+) -> f64 {
+    if run.len() > 1 {
+        return text_run_size(run, style, ctx).height;
+    }
+    let text = collapsed_run_text(run).concat();
+    let font = FontSpec::from_style(style);
+    ctx.text_node_defaults
+        .measure(ctx.text_measurer.as_ref(), &text, &font, Some(width))
+        .height
+}
+
+/// Content height of a leaf holding text or with a measure function, `None` for other
+/// leaves.
+fn leaf_content_height(
+    node: &Rc<RefCell<Node>>,
+    style: &Style,
+    ctx: &LayoutContext,
+    content_width: f64,
+) -> Option<f64> {
+    let node = node.borrow();
+    if let Some(text) = node.layout.text.as_deref() {
+        let font = FontSpec::from_style(style);
+        let size = ctx.text_node_defaults.measure(
+            ctx.text_measurer.as_ref(),
+            text,
+            &font,
+            Some(content_width),
+        );
+        return Some(size.height);
+    }
+    let measure = ctx.measure_functions.get(&node.id)?;
+    let size = measure(MeasureConstraints {
+        width: Some(content_width),
+        height: None,
+    });
+    Some(size.height)
+}
+
+/// The bottom edge of a laid out container's children, including their bottom margins.
+fn children_extent(node: &Rc<RefCell<Node>>, units: &ResolutionContext) -> f64 {
+    node.borrow()
+        .box_children()
+        .iter()
+        .map(|child| {
+            let child = child.borrow();
+            let margin = child.layout.style.margin.bottom.unwrap_or(Length::Px(0.0));
+            child.layout.bounds.y + child.layout.bounds.height + units.inline_or_zero(margin)
+        })
+        .fold(f64::NEG_INFINITY, f64::max)
+}
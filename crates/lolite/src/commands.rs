@@ -1,44 +1,246 @@
-use crate::css_parser::parse_css;
-use crate::layout::{build_render_tree, LayoutContext, RenderNode};
-use crate::Id;
+use crate::animation::{AnimationSpec, Animator, FinishedCallback, ReducedMotion};
+use crate::css_parser::{parse_css_with_diagnostics, CssDiagnostic};
+use crate::embed::EmbeddedDocument;
+use crate::layout::{
+    build_render_tree, Document, LayoutContext, MeasureFn, RenderNode, ScrollOffset, Size,
+};
+use crate::limits::{LimitError, Limits};
+use crate::locale::Locale;
+use crate::persist::SavedDocument;
+use crate::snapshot::SnapshotQueue;
+use crate::stats::EngineStats;
+#[cfg(feature = "windowing")]
+use crate::style::Overflow;
+use crate::style::ScrollBehavior;
+use crate::style::{
+    ColorScheme, FontFace, MotionPreference, Selector, StyleOrigin, StyleSheet, StyleSource,
+};
+use crate::text::TextNodeDefaults;
+use crate::{CheckpointId, Id, StylesheetId};
+use std::collections::{HashMap, VecDeque};
 use std::sync::{
     mpsc::{self, Receiver},
-    Arc, RwLock,
+    Arc, Mutex,
 };
 use std::time::{Duration, Instant};
 
 use crate::windowing::{WindowMessage, WindowMessageSender};
 
+/// Callback invoked with the node id and its new laid-out width and height.
+pub(crate) type ResizeCallback = Box<dyn Fn(Id, f64, f64) + Send>;
+
+/// Callback invoked once with the instant by which it should return.
+pub(crate) type IdleCallback = Box<dyn FnOnce(Instant) + Send>;
+
+/// Callback invoked with each mutation rejected for exceeding the document limits.
+pub(crate) type LimitCallback = Box<dyn FnMut(LimitError) + Send>;
+pub(crate) type CssDiagnosticCallback = Box<dyn FnMut(StylesheetId, &CssDiagnostic) + Send>;
+
+/// Callback returning the source of the stylesheet an `@import` URL refers to.
+pub(crate) type ImportResolver = Box<dyn FnMut(&str) -> Option<String> + Send>;
+
+/// Callback returning the font file an `@font-face` rule refers to.
+pub(crate) type FontLoader = Box<dyn FnMut(&FontFace) -> Option<Vec<u8>> + Send>;
+
 pub(crate) enum Command {
-    AddStylesheet(String),
-    CreateNode(Id, Option<String>),
+    /// Add a stylesheet of an origin, confined to the subtree under a node if one is given.
+    AddStylesheet(StylesheetId, Option<Id>, StyleOrigin, String),
+    /// Add a stylesheet parsed ahead of time, with its source.
+    AddCompiledStylesheet(StylesheetId, String, StyleSheet),
+    RemoveStylesheet(StylesheetId),
+    ReplaceStylesheet(StylesheetId, String),
+    /// Create a node with an optional tag and text content.
+    CreateNode(Id, Option<String>, Option<String>),
+    /// Create a text node, see [`crate::layout::NodeKind::Text`].
+    CreateTextNode(Id, String),
     SetParent(Id, Id),
     SetAttribute(Id, String, String),
+    SetDebugName(Id, String),
+    /// Set or clear the measure function of a leaf node.
+    SetMeasure(Id, Option<MeasureFn>),
+    /// Show a document in the content box of a node, or stop showing one.
+    SetEmbeddedDocument(Id, Option<EmbeddedDocument>),
+    /// Also ask the event loop of the document embedding this one to redraw whenever a
+    /// snapshot is published.
+    SetEmbedder(Option<WindowMessageSender>),
+    SetViewport(f64, f64),
+    /// Lay out at the viewport size divided by this page zoom factor.
+    SetZoom(f64),
+    /// Evaluate `prefers-color-scheme` media queries against this scheme.
+    SetColorScheme(ColorScheme),
+    SetMotionPreference(MotionPreference),
+    SetReducedMotion(ReducedMotion),
+    ObserveResize(Id, ResizeCallback),
+    UnobserveResize(Id),
+    StartAnimation(AnimationSpec),
+    PauseAnimation(u64),
+    ResumeAnimation(u64),
+    CancelAnimation(u64),
+    OnAnimationFinished(u64, FinishedCallback),
+    /// Run a callback once the command thread has nothing else to do.
+    OnIdle(IdleCallback),
+    /// Reject mutations that would make the document exceed these limits.
+    SetLimits(Limits),
+    /// Change the sizes and fonts assumed for nodes sized by the text heuristic.
+    SetTextNodeDefaults(TextNodeDefaults),
+    SetLocale(Locale),
+    /// Report rejected mutations to a callback instead of logging them.
+    OnLimitExceeded(LimitCallback),
+    /// Report what stylesheets drop or only partly honor to a callback instead of
+    /// logging it.
+    OnCssDiagnostic(CssDiagnosticCallback),
+    /// Load the stylesheets of `@import` rules with a callback.
+    SetImportResolver(ImportResolver),
+    /// Load the fonts of `@font-face` rules with a callback.
+    SetFontLoader(FontLoader),
+    /// Scroll a node's content to the given offset, honouring its `scroll-behavior`.
+    ScrollTo(Id, f64, f64),
+    /// Scroll the scrollable ancestors of a node so that it sits at their top-left corner.
+    ScrollIntoView(Id),
+    /// Pointer over the given node (the innermost one under it, or `None` outside the
+    /// document), and whether the left button is held.
+    #[cfg(any(test, feature = "windowing"))]
+    SetPointer(Option<Id>, bool),
+    /// Give keyboard focus to a node, for `:focus` rules.
+    SetFocus(Option<Id>),
+    /// Wheel input over the given hit-test path (topmost first), as a scroll delta in px.
+    #[cfg(feature = "windowing")]
+    UserScroll(Vec<Id>, f64, f64),
+    /// Deep-copy a node and its descendants, sending back the id of the detached copy.
+    CloneSubtree(Id, mpsc::Sender<Option<Id>>),
+    /// Send back the children of a node, or `None` for an unknown node.
+    GetChildren(Id, mpsc::Sender<Option<Vec<Id>>>),
+    /// Send back the parent of a node.
+    GetParent(Id, mpsc::Sender<Option<Id>>),
+    /// Send back the nodes matching a selector, in document order.
+    QuerySelector(Vec<Selector>, mpsc::Sender<Vec<Id>>),
+    /// Send back where the declarations that apply to a node were declared, and which
+    /// are overridden.
+    GetStyleSources(Id, mpsc::Sender<Option<Vec<StyleSource>>>),
+    /// Send back counters describing the document.
+    GetStats(mpsc::Sender<EngineStats>),
+    /// Capture the document and stylesheets and send them back.
+    SaveDocument(mpsc::Sender<SavedDocument>),
+    /// Replace the document and stylesheets, giving the stylesheets the given ids.
+    LoadDocument(SavedDocument, Vec<StylesheetId>),
+    /// Save the nodes of the document, to roll back to.
+    Checkpoint(CheckpointId),
+    /// Restore the nodes saved by a checkpoint.
+    Rollback(CheckpointId),
+    /// Drop the nodes saved by a checkpoint.
+    DiscardCheckpoint(CheckpointId),
+    /// Lay out everything received so far, publish the snapshot, then signal the sender.
+    Flush(mpsc::Sender<()>),
+    /// Apply several commands at once, followed by a single layout pass.
+    Batch(Vec<Command>),
     #[allow(unused)]
     Layout,
+    /// Flush any pending layout and stop the command handler.
+    Shutdown,
+}
+
+/// Interval between animation frames while any animation is running.
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Idle time after the last wheel input before a scroll counts as ended and snaps.
+#[cfg(feature = "windowing")]
+const SCROLL_SNAP_DELAY: Duration = Duration::from_millis(150);
+
+/// Delay used to coalesce consecutive mutations into a single layout pass.
+const LAYOUT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Longest deadline given to an idle callback, so new commands aren't kept waiting.
+const MAX_IDLE_PERIOD: Duration = Duration::from_millis(50);
+
+/// A stylesheet in the cascade, with the source it was parsed from.
+pub(crate) struct LoadedStylesheet {
+    pub id: StylesheetId,
+    pub css: String,
+    /// Root of the subtree the rules are confined to, if any.
+    pub scope: Option<Id>,
+    pub origin: StyleOrigin,
+    pub sheet: StyleSheet,
+}
+
+struct ResizeObserver {
+    callback: ResizeCallback,
+    last_size: Option<(f64, f64)>,
+}
+
+/// Lay out embedded documents at the size of the content box of their node, as last
+/// sent in `sizes`.
+fn resize_embedded_documents(node: &RenderNode, sizes: &mut HashMap<Id, Option<Size>>) {
+    if let (Some(document), Some(size)) = (&node.embedded, sizes.get_mut(&node.id)) {
+        let content_box = node.content_box();
+        let content_size = Size {
+            width: content_box.width,
+            height: content_box.height,
+        };
+        if *size != Some(content_size) {
+            *size = Some(content_size);
+            document.send(Command::SetViewport(
+                content_size.width,
+                content_size.height,
+            ));
+        }
+    }
+
+    for child in &node.children {
+        resize_embedded_documents(child, sizes);
+    }
+}
+
+fn notify_resize_observers(node: &RenderNode, observers: &mut HashMap<Id, Vec<ResizeObserver>>) {
+    if let Some(node_observers) = observers.get_mut(&node.id) {
+        let size = (node.bounds.width, node.bounds.height);
+        for observer in node_observers.iter_mut() {
+            if observer.last_size != Some(size) {
+                observer.last_size = Some(size);
+                (observer.callback)(node.id, size.0, size.1);
+            }
+        }
+    }
+
+    for child in &node.children {
+        notify_resize_observers(child, observers);
+    }
 }
 
 pub(crate) fn handle_commands(
     rx: Receiver<Command>,
-    snapshot: Arc<RwLock<Option<RenderNode>>>,
+    snapshots: Arc<Mutex<SnapshotQueue>>,
+    layout_time: Arc<Mutex<Duration>>,
     message_sender: WindowMessageSender,
 ) {
-    let mut ctx = LayoutContext::new();
-    let mut deadline: Option<Instant> = None;
+    let mut handler = CommandHandler::new(snapshots, layout_time, message_sender);
 
     loop {
+        // Advance running animations on the frame ticker.
+        if handler.animator.is_running() {
+            let now = Instant::now();
+            if handler.next_frame <= now {
+                if handler.animator.tick(&mut handler.ctx, now) {
+                    handler.publish_snapshot();
+                } else {
+                    handler.publish_animated_paint();
+                }
+                handler.next_frame = now + FRAME_INTERVAL;
+                continue;
+            }
+        }
+
+        // Settle scroll containers whose wheel scrolling has ended onto snap points.
+        if handler.settle_scroll_snaps(Instant::now()) {
+            continue;
+        }
+
         // Determine timeout based on debounce deadline
-        let timeout = match deadline {
+        let timeout = match handler.deadline {
             Some(dl) => {
                 let now = Instant::now();
                 if dl <= now {
                     // Deadline expired: run layout now
-                    ctx.layout();
-                    let root = ctx.document.root_node();
-                    let snap = build_render_tree(root);
-                    *snapshot.write().unwrap() = Some(snap);
-                    message_sender.send(WindowMessage::Redraw);
-                    deadline = None;
+                    handler.publish_snapshot();
                     // After layout, continue to next iteration
                     continue;
                 } else {
@@ -47,55 +249,893 @@ pub(crate) fn handle_commands(
             }
             None => Duration::from_millis(u64::MAX / 2), // effectively wait forever
         };
+        let timeout = if handler.animator.is_running() {
+            timeout.min(handler.next_frame.saturating_duration_since(Instant::now()))
+        } else {
+            timeout
+        };
+        let timeout = match handler.pending_snaps.values().min() {
+            Some(snap) => timeout.min(snap.saturating_duration_since(Instant::now())),
+            None => timeout,
+        };
+
+        // Run idle callbacks one at a time while no commands are waiting, each with a
+        // deadline before the next scheduled work.
+        if let Some(callback) = handler.idle_callbacks.pop_front() {
+            match rx.try_recv() {
+                Ok(cmd) => {
+                    handler.idle_callbacks.push_front(callback);
+                    if !handler.handle(cmd) {
+                        break;
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    callback(Instant::now() + timeout.min(MAX_IDLE_PERIOD));
+                }
+                Err(mpsc::TryRecvError::Disconnected) => break,
+            }
+            continue;
+        }
 
         match rx.recv_timeout(timeout) {
-            Ok(cmd) => match cmd {
-                Command::AddStylesheet(css) => match parse_css(&css) {
-                    Ok(sheet) => {
-                        for rule in sheet.rules {
-                            ctx.style_sheet.add_rule(rule);
-                        }
-                        if deadline.is_none() {
-                            deadline = Some(Instant::now() + Duration::from_millis(100));
-                        }
+            Ok(cmd) => {
+                if !handler.handle(cmd) {
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // handled at top loop when checking expired deadline
+                continue;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// State owned by the command thread.
+struct CommandHandler {
+    ctx: LayoutContext,
+    snapshots: Arc<Mutex<SnapshotQueue>>,
+    message_sender: WindowMessageSender,
+    /// Event loop of the document this one is embedded in, if it isn't the same.
+    embedder: Option<WindowMessageSender>,
+    /// Nodes showing an embedded document, with the viewport last given to it.
+    embedded_sizes: HashMap<Id, Option<Size>>,
+    /// When set, a layout pass is pending and runs at this instant.
+    deadline: Option<Instant>,
+    /// Rules of each stylesheet, in cascade order.
+    stylesheets: Vec<LoadedStylesheet>,
+    /// Node states saved by `Checkpoint`, without stylesheets.
+    checkpoints: HashMap<CheckpointId, SavedDocument>,
+    resize_observers: HashMap<Id, Vec<ResizeObserver>>,
+    /// Scroll containers to snap once wheel input has been idle until the given instant.
+    pending_snaps: HashMap<Id, Instant>,
+    animator: Animator,
+    /// How animations are handled while the user prefers reduced motion.
+    reduced_motion: ReducedMotion,
+    next_frame: Instant,
+    last_layout_duration: Option<Duration>,
+    /// Layout time not yet reported in frame stats, shared with the window.
+    layout_time: Arc<Mutex<Duration>>,
+    snapshot_node_count: usize,
+    idle_callbacks: VecDeque<IdleCallback>,
+    /// Window size set with `SetViewport`, before zoom.
+    window_size: Size,
+    zoom: f64,
+    limits: Limits,
+    on_limit_exceeded: Option<LimitCallback>,
+    on_css_diagnostic: Option<CssDiagnosticCallback>,
+    import_resolver: Option<ImportResolver>,
+    font_loader: Option<FontLoader>,
+}
+
+impl CommandHandler {
+    fn new(
+        snapshots: Arc<Mutex<SnapshotQueue>>,
+        layout_time: Arc<Mutex<Duration>>,
+        message_sender: WindowMessageSender,
+    ) -> Self {
+        let mut ctx = LayoutContext::new();
+        ctx.locale = Locale::from_environment().unwrap_or_default();
+        Self {
+            idle_callbacks: VecDeque::new(),
+            limits: Limits::default(),
+            on_limit_exceeded: None,
+            on_css_diagnostic: None,
+            import_resolver: None,
+            font_loader: None,
+            window_size: ctx.viewport,
+            zoom: 1.0,
+            ctx,
+            snapshots,
+            layout_time,
+            message_sender,
+            embedder: None,
+            embedded_sizes: HashMap::new(),
+            deadline: None,
+            stylesheets: Vec::new(),
+            checkpoints: HashMap::new(),
+            resize_observers: HashMap::new(),
+            pending_snaps: HashMap::new(),
+            animator: Animator::new(),
+            reduced_motion: ReducedMotion::default(),
+            next_frame: Instant::now(),
+            last_layout_duration: None,
+            snapshot_node_count: 0,
+        }
+    }
+
+    /// Zoomed pages are laid out in a proportionally smaller viewport, then painted scaled up.
+    fn update_viewport(&mut self) {
+        let media = self.ctx.media_state();
+        self.ctx.viewport = Size {
+            width: self.window_size.width / self.zoom,
+            height: self.window_size.height / self.zoom,
+        };
+        self.restyle_if_media_changed(media);
+        self.schedule_layout();
+    }
+
+    /// Styles keep declarations from rules that matched before, so restyle every node
+    /// once a `@media` rule starts or stops applying.
+    fn restyle_if_media_changed(&mut self, before: Vec<bool>) {
+        if self.ctx.media_state() != before {
+            self.ctx.document.reset_styles();
+        }
+    }
+
+    /// Request a layout pass after the debounce delay, unless one is already pending.
+    fn schedule_layout(&mut self) {
+        if self.deadline.is_none() {
+            self.deadline = Some(Instant::now() + LAYOUT_DEBOUNCE);
+        }
+    }
+
+    /// Report a mutation rejected by the document limits.
+    fn limit_exceeded(&mut self, action: &str, err: LimitError) {
+        match &mut self.on_limit_exceeded {
+            Some(callback) => callback(err),
+            None => eprintln!("Failed to {}: {}", action, err),
+        }
+    }
+
+    /// Parse a stylesheet and the stylesheets it imports, reporting what they drop or
+    /// only partly honor.
+    fn parse_stylesheet(&mut self, id: StylesheetId, css: &str) -> StyleSheet {
+        let (sheet, diagnostics) = parse_css_with_diagnostics(css);
+        for diagnostic in &diagnostics {
+            self.css_diagnostic(id, diagnostic);
+        }
+        let sheet = self.resolve_imports(id, sheet, &mut Vec::new());
+        self.load_font_faces(&sheet);
+        sheet
+    }
+
+    /// Report something stylesheet `id`, or one it imports, drops or only partly honors.
+    fn css_diagnostic(&mut self, id: StylesheetId, diagnostic: &CssDiagnostic) {
+        match &mut self.on_css_diagnostic {
+            Some(callback) => callback(id, diagnostic),
+            None => eprintln!("CSS: {}", diagnostic),
+        }
+    }
+
+    /// Put the rules of the stylesheets `sheet` imports ahead of its own, loading them
+    /// with the import resolver. `importing` holds the URLs of the imports being
+    /// resolved, to break cycles. Imports that can't be loaded are reported and skipped.
+    fn resolve_imports(
+        &mut self,
+        id: StylesheetId,
+        sheet: StyleSheet,
+        importing: &mut Vec<String>,
+    ) -> StyleSheet {
+        if sheet.imports.is_empty() {
+            return sheet;
+        }
+        let mut resolved = StyleSheet::new();
+        for import in &sheet.imports {
+            let css = match &mut self.import_resolver {
+                Some(resolve) if !importing.contains(&import.url) => resolve(&import.url),
+                _ => None,
+            };
+            let Some(css) = css else {
+                self.css_diagnostic(id, &CssDiagnostic::unresolved_import(import));
+                continue;
+            };
+            if let Err(err) = self.limits.check_stylesheet(&css) {
+                self.limit_exceeded("import stylesheet", err);
+                continue;
+            }
+            let (imported, diagnostics) = parse_css_with_diagnostics(&css);
+            for diagnostic in &diagnostics {
+                self.css_diagnostic(id, diagnostic);
+            }
+            importing.push(import.url.clone());
+            let imported = self.resolve_imports(id, imported, importing);
+            importing.pop();
+            resolved.extend(&imported);
+        }
+        resolved.extend(&sheet);
+        resolved
+    }
+
+    /// Load the fonts of the `@font-face` rules of `sheet` with the font loader, and make
+    /// them available to text under their family.
+    fn load_font_faces(&mut self, sheet: &StyleSheet) {
+        let Some(load) = &mut self.font_loader else {
+            return;
+        };
+        for face in &sheet.font_faces {
+            let Some(data) = load(face) else {
+                continue;
+            };
+            if !self.ctx.text_measurer.register_font_face(face, &data) {
+                eprintln!("Failed to load font for family {:?}", face.family);
+            }
+        }
+    }
+
+    /// Whether a node can be created with this id, reporting why not otherwise.
+    fn check_new_node(&mut self, id: Id) -> bool {
+        // Replacing a node would leave its old subtree and parent link dangling
+        if self.ctx.document.get_node(id).is_some() {
+            eprintln!(
+                "Failed to create node {}: id already exists",
+                self.ctx.document.describe(id)
+            );
+            false
+        } else if self.ctx.document.node_count() >= self.limits.max_nodes {
+            let limit = self.limits.max_nodes;
+            self.limit_exceeded("create node", LimitError::TooManyNodes { limit });
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Check that attaching `child` under `parent` keeps the tree within the depth limit.
+    fn check_depth(&self, parent: Id, child: Id) -> Result<(), LimitError> {
+        let limit = self.limits.max_tree_depth;
+        // Measuring the subtree isn't free, so skip it when there's nothing to enforce.
+        if limit == usize::MAX {
+            return Ok(());
+        }
+        let document = &self.ctx.document;
+        let (_, height) = document.subtree_extent(child);
+        if document.depth(parent) + 1 + height > limit {
+            return Err(LimitError::TooDeep { limit });
+        }
+        Ok(())
+    }
+
+    /// Check that a freshly restored document is within the node and depth limits.
+    fn check_restored(&self, document: &Document) -> Result<(), LimitError> {
+        if document.node_count() > self.limits.max_nodes {
+            return Err(LimitError::TooManyNodes {
+                limit: self.limits.max_nodes,
+            });
+        }
+        let tops = document
+            .nodes()
+            .filter(|node| node.borrow().parent.is_none());
+        for top in tops {
+            let (_, height) = document.subtree_extent(top.borrow().id);
+            if height > self.limits.max_tree_depth {
+                return Err(LimitError::TooDeep {
+                    limit: self.limits.max_tree_depth,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Detach the documents embedded in nodes of `previous`, the document just replaced,
+    /// that no longer show them in the current one.
+    fn detach_embedded(&mut self, previous: &Document) {
+        for node in previous.nodes() {
+            let node = node.borrow();
+            let Some(embedded) = &node.embedded else {
+                continue;
+            };
+            let kept = self
+                .ctx
+                .document
+                .get_node(node.id)
+                .is_some_and(|current| current.borrow().embedded.is_some());
+            if !kept {
+                embedded.send(Command::SetEmbedder(None));
+                self.embedded_sizes.remove(&node.id);
+            }
+        }
+    }
+
+    /// Append a stylesheet to the end of the cascade.
+    fn add_stylesheet(&mut self, id: StylesheetId, css: String, sheet: StyleSheet) {
+        self.add_scoped_stylesheet(id, None, StyleOrigin::Author, css, sheet);
+    }
+
+    /// Append a stylesheet of `origin` whose rules only apply under `scope`, if given.
+    fn add_scoped_stylesheet(
+        &mut self,
+        id: StylesheetId,
+        scope: Option<Id>,
+        origin: StyleOrigin,
+        css: String,
+        sheet: StyleSheet,
+    ) {
+        let sheet = sheet.scoped_to(scope).with_origin(origin).loaded_from(id);
+        self.ctx.style_sheet.extend(&sheet);
+        self.stylesheets.push(LoadedStylesheet {
+            id,
+            css,
+            scope,
+            origin,
+            sheet,
+        });
+        self.schedule_layout();
+    }
+
+    /// Show the effect of a change in interaction state right away, rather than after
+    /// the usual debounce, if any rule depends on it.
+    fn restyle_interaction(&mut self) {
+        if self.ctx.style_sheet.uses_pseudo_classes() {
+            self.publish_snapshot();
+        }
+    }
+
+    /// Rebuild the cascade from the remaining stylesheets and restyle every node.
+    fn rebuild_cascade(&mut self) {
+        self.ctx.style_sheet = StyleSheet::new();
+        for loaded in &self.stylesheets {
+            self.ctx.style_sheet.extend(&loaded.sheet);
+        }
+        self.ctx.document.reset_styles();
+        self.schedule_layout();
+    }
+
+    /// Lay out the document, publish the new snapshot and notify resize observers.
+    fn publish_snapshot(&mut self) {
+        let started = Instant::now();
+        self.ctx.layout();
+        let elapsed = started.elapsed();
+        self.last_layout_duration = Some(elapsed);
+        *self.layout_time.lock().unwrap() += elapsed;
+        let root = self.ctx.document.root_node();
+        let snap = build_render_tree(root);
+        self.snapshot_node_count = snap.node_count();
+        if !self.resize_observers.is_empty() {
+            notify_resize_observers(&snap, &mut self.resize_observers);
+        }
+        if !self.embedded_sizes.is_empty() {
+            resize_embedded_documents(&snap, &mut self.embedded_sizes);
+        }
+        self.snapshots.lock().unwrap().publish(snap);
+        self.request_redraw();
+        self.deadline = None;
+    }
+
+    /// Ask the window showing the document, and the one embedding it, to draw the
+    /// latest snapshot.
+    fn request_redraw(&self) {
+        self.message_sender.send(WindowMessage::Redraw);
+        if let Some(embedder) = &self.embedder {
+            embedder.send(WindowMessage::Redraw);
+        }
+    }
+
+    /// Patch the current values of paint-only animations into the published snapshot,
+    /// skipping layout. Falls back to a full layout before the first snapshot.
+    fn publish_animated_paint(&mut self) {
+        {
+            let mut snapshots = self.snapshots.lock().unwrap();
+            if let Some(root) = snapshots.latest_mut() {
+                root.apply_paint_overrides(&self.ctx.style_overrides);
+                self.request_redraw();
+                return;
+            }
+        }
+        self.publish_snapshot();
+    }
+
+    /// Scroll `id` to `offset`, animating when its computed style asks for smooth scrolling.
+    fn scroll_to(&mut self, id: Id, offset: ScrollOffset) {
+        let Some(node) = self.ctx.document.get_node(id) else {
+            eprintln!(
+                "Failed to scroll {}: node not found",
+                self.ctx.document.describe(id)
+            );
+            return;
+        };
+        let (target, smooth) = {
+            let node = node.borrow();
+            (
+                node.clamp_scroll_offset(offset),
+                node.layout.style.scroll_behavior == Some(ScrollBehavior::Smooth),
+            )
+        };
+
+        let now = Instant::now();
+        if smooth {
+            self.animator.scroll(id, target, &self.ctx, now);
+            self.next_frame = now;
+        } else {
+            self.animator.cancel_scroll(id);
+            node.borrow_mut().scroll_offset = target;
+            self.publish_snapshot();
+        }
+    }
+
+    /// Align a node with the top-left corner of its nearest scrollable ancestor, then that
+    /// ancestor with the next scrollable one out, so nested containers all reveal it.
+    fn scroll_into_view(&mut self, id: Id) {
+        let document = &self.ctx.document;
+        let Some(node) = document.get_node(id) else {
+            eprintln!(
+                "Failed to scroll {} into view: node not found",
+                document.describe(id)
+            );
+            return;
+        };
+
+        let mut targets = Vec::new();
+        let mut target = node.borrow().layout.bounds;
+        let mut ancestor = node.borrow().parent;
+        while let Some(ancestor_id) = ancestor {
+            let Some(container) = document.get_node(ancestor_id) else {
+                break;
+            };
+            let container = container.borrow();
+            if container.is_scrollable() {
+                // Layout bounds ignore scrolling, so the offset is the distance between boxes
+                let bounds = container.layout.bounds;
+                let offset = ScrollOffset {
+                    x: target.x - bounds.x,
+                    y: target.y - bounds.y,
+                };
+                targets.push((ancestor_id, offset));
+                target = bounds;
+            }
+            ancestor = container.parent;
+        }
+
+        for (container, offset) in targets {
+            self.scroll_to(container, offset);
+        }
+    }
+
+    /// Smoothly snap every container whose snap delay has passed.
+    /// Returns whether any container was due.
+    fn settle_scroll_snaps(&mut self, now: Instant) -> bool {
+        let due: Vec<Id> = self
+            .pending_snaps
+            .iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &due {
+            self.pending_snaps.remove(id);
+            let Some(node) = self.ctx.document.get_node(*id) else {
+                continue;
+            };
+            let target = {
+                let node = node.borrow();
+                node.snap_scroll_offset(node.scroll_offset)
+            };
+            if let Some(target) = target {
+                self.animator.scroll(*id, target, &self.ctx, now);
+                self.next_frame = now;
+            }
+        }
+        !due.is_empty()
+    }
+
+    /// Apply a single command. Returns `false` once the handler should stop.
+    fn handle(&mut self, cmd: Command) -> bool {
+        match cmd {
+            Command::AddStylesheet(id, scope, origin, css) => {
+                if let Err(err) = self.limits.check_stylesheet(&css) {
+                    self.limit_exceeded("add stylesheet", err);
+                    return true;
+                }
+                let sheet = self.parse_stylesheet(id, &css);
+                self.add_scoped_stylesheet(id, scope, origin, css, sheet);
+            }
+            Command::AddCompiledStylesheet(id, css, sheet) => {
+                if let Err(err) = self.limits.check_stylesheet(&css) {
+                    self.limit_exceeded("add stylesheet", err);
+                    return true;
+                }
+                let sheet = self.resolve_imports(id, sheet, &mut Vec::new());
+                self.load_font_faces(&sheet);
+                self.add_stylesheet(id, css, sheet);
+            }
+            Command::RemoveStylesheet(id) => {
+                let count = self.stylesheets.len();
+                self.stylesheets.retain(|sheet| sheet.id != id);
+                if self.stylesheets.len() != count {
+                    self.rebuild_cascade();
+                }
+            }
+            Command::ReplaceStylesheet(id, css) => {
+                if let Err(err) = self.limits.check_stylesheet(&css) {
+                    self.limit_exceeded("replace stylesheet", err);
+                    return true;
+                }
+                let sheet = self.parse_stylesheet(id, &css);
+                match self.stylesheets.iter_mut().find(|loaded| loaded.id == id) {
+                    Some(loaded) => {
+                        loaded.css = css;
+                        loaded.sheet = sheet
+                            .scoped_to(loaded.scope)
+                            .with_origin(loaded.origin)
+                            .loaded_from(id);
+                        self.rebuild_cascade();
                     }
-                    Err(e) => {
-                        eprintln!("Failed to parse CSS: {}", e);
+                    None => eprintln!("Failed to replace stylesheet: stylesheet not found"),
+                }
+            }
+            Command::CreateNode(id, tag, text) => {
+                if self.check_new_node(id) {
+                    self.ctx.document.create_node(id, text);
+                    if let Some(node) = self.ctx.document.get_node(id) {
+                        node.borrow_mut().tag = tag;
                     }
-                },
-                Command::CreateNode(id, text) => {
-                    ctx.document.create_node(id, text);
-                    if deadline.is_none() {
-                        deadline = Some(Instant::now() + Duration::from_millis(100));
+                    self.schedule_layout();
+                }
+            }
+            Command::CreateTextNode(id, text) => {
+                if self.check_new_node(id) {
+                    self.ctx.document.create_text_node(id, text);
+                    self.schedule_layout();
+                }
+            }
+            Command::CloneSubtree(id, reply) => {
+                let (size, _) = self.ctx.document.subtree_extent(id);
+                let limit = self.limits.max_nodes;
+                if self.ctx.document.node_count().saturating_add(size) > limit {
+                    self.limit_exceeded("clone subtree", LimitError::TooManyNodes { limit });
+                    let _ = reply.send(None);
+                    return true;
+                }
+                let _ = reply.send(self.ctx.document.clone_subtree(id));
+            }
+            Command::SetParent(p, c) => {
+                if let Err(err) = self.check_depth(p, c) {
+                    self.limit_exceeded("set parent", err);
+                    return true;
+                }
+                let old_parent = self
+                    .ctx
+                    .document
+                    .get_node(c)
+                    .and_then(|node| node.borrow().parent);
+                if let Err(e) = self.ctx.document.set_parent(p, c).map_err(str::to_string) {
+                    eprintln!(
+                        "Failed to set parent of {} to {}: {}",
+                        self.ctx.document.describe(c),
+                        self.ctx.document.describe(p),
+                        e
+                    );
+                } else if self.ctx.style_sheet.uses_structural_pseudo_classes() {
+                    // Siblings on both sides shift position, which can change what
+                    // `:nth-child()` and friends match for them and their descendants
+                    for parent in old_parent.into_iter().chain([p]) {
+                        self.ctx.document.reset_subtree_styles(parent);
                     }
                 }
-                Command::SetParent(p, c) => {
-                    ctx.document.set_parent(p, c).expect("data thread down");
-                    if deadline.is_none() {
-                        deadline = Some(Instant::now() + Duration::from_millis(100));
+                self.schedule_layout();
+            }
+            Command::SetAttribute(id, k, v) => {
+                self.ctx.document.set_attribute(id, k, v);
+                self.schedule_layout();
+            }
+            Command::SetDebugName(id, name) => match self.ctx.document.get_node(id) {
+                Some(node) => node.borrow_mut().debug_name = Some(name),
+                None => eprintln!("Failed to set debug name {:?}: node not found", name),
+            },
+            Command::SetMeasure(id, measure) => {
+                match measure {
+                    Some(measure) => self.ctx.measure_functions.insert(id, measure),
+                    None => self.ctx.measure_functions.remove(&id),
+                };
+                self.schedule_layout();
+            }
+            Command::SetEmbeddedDocument(id, document) => {
+                let Some(node) = self.ctx.document.get_node(id) else {
+                    eprintln!("Failed to embed a document in {:?}: node not found", id);
+                    return true;
+                };
+                let previous = std::mem::replace(&mut node.borrow_mut().embedded, document.clone());
+                if let Some(previous) = previous {
+                    previous.send(Command::SetEmbedder(None));
+                }
+                match document {
+                    Some(document) => {
+                        document.send(Command::SetEmbedder(Some(self.message_sender.clone())));
+                        // Sized at the next layout
+                        self.embedded_sizes.insert(id, None);
+                    }
+                    None => {
+                        self.embedded_sizes.remove(&id);
                     }
                 }
-                Command::SetAttribute(id, k, v) => {
-                    ctx.document.set_attribute(id, k, v);
-                    if deadline.is_none() {
-                        deadline = Some(Instant::now() + Duration::from_millis(100));
+                self.schedule_layout();
+            }
+            Command::SetEmbedder(embedder) => {
+                self.embedder = embedder;
+                self.request_redraw();
+            }
+            Command::SetViewport(width, height) => {
+                self.window_size = Size { width, height };
+                self.update_viewport();
+            }
+            Command::SetZoom(zoom) => {
+                self.zoom = zoom;
+                self.update_viewport();
+            }
+            Command::SetColorScheme(color_scheme) => {
+                let media = self.ctx.media_state();
+                self.ctx.color_scheme = color_scheme;
+                self.restyle_if_media_changed(media);
+                self.schedule_layout();
+            }
+            Command::SetMotionPreference(preference) => {
+                let media = self.ctx.media_state();
+                self.ctx.motion_preference = preference;
+                self.animator
+                    .set_reduced_motion(preference, self.reduced_motion);
+                self.restyle_if_media_changed(media);
+                self.schedule_layout();
+            }
+            Command::SetReducedMotion(handling) => {
+                self.reduced_motion = handling;
+                self.animator
+                    .set_reduced_motion(self.ctx.motion_preference, handling);
+            }
+            Command::ObserveResize(id, callback) => {
+                self.resize_observers
+                    .entry(id)
+                    .or_default()
+                    .push(ResizeObserver {
+                        callback,
+                        last_size: None,
+                    });
+                // Make sure the new observer sees the current size.
+                self.schedule_layout();
+            }
+            Command::UnobserveResize(id) => {
+                self.resize_observers.remove(&id);
+            }
+            Command::StartAnimation(spec) => {
+                let now = Instant::now();
+                self.animator.start(spec, &self.ctx, now);
+                self.next_frame = now;
+            }
+            Command::PauseAnimation(id) => {
+                self.animator.pause(id, Instant::now());
+            }
+            Command::ResumeAnimation(id) => {
+                self.animator.resume(id, Instant::now());
+                self.next_frame = Instant::now();
+            }
+            Command::CancelAnimation(id) => {
+                self.animator.cancel(id, &mut self.ctx, Instant::now());
+                self.schedule_layout();
+            }
+            Command::OnAnimationFinished(id, callback) => {
+                self.animator.on_finished(id, callback);
+            }
+            Command::OnIdle(callback) => self.idle_callbacks.push_back(callback),
+            Command::SetLimits(limits) => self.limits = limits,
+            Command::SetTextNodeDefaults(defaults) => {
+                self.ctx.text_node_defaults = defaults;
+                // Resolved styles hold the previous default font
+                self.ctx.document.reset_styles();
+                self.schedule_layout();
+            }
+            Command::SetLocale(locale) => {
+                self.ctx.locale = locale;
+                // Resolved styles hold the previous direction and font fallbacks
+                self.ctx.document.reset_styles();
+                self.schedule_layout();
+            }
+            Command::OnLimitExceeded(callback) => self.on_limit_exceeded = Some(callback),
+            Command::OnCssDiagnostic(callback) => self.on_css_diagnostic = Some(callback),
+            Command::SetImportResolver(resolver) => self.import_resolver = Some(resolver),
+            Command::SetFontLoader(loader) => self.font_loader = Some(loader),
+            Command::ScrollTo(id, x, y) => {
+                self.scroll_to(id, ScrollOffset { x, y });
+            }
+            Command::ScrollIntoView(id) => self.scroll_into_view(id),
+            #[cfg(any(test, feature = "windowing"))]
+            Command::SetPointer(hovered, pressed) => {
+                if self.ctx.document.set_pointer(hovered, pressed) {
+                    self.restyle_interaction();
+                }
+            }
+            Command::SetFocus(focused) => {
+                if self.ctx.document.set_focused(focused) {
+                    self.restyle_interaction();
+                }
+            }
+            #[cfg(feature = "windowing")]
+            Command::UserScroll(path, dx, dy) => {
+                // Scroll the innermost node under the cursor that has overflowing content,
+                // along the axes its `overflow` lets the user scroll.
+                let scrollable = path.iter().find_map(|id| {
+                    self.ctx
+                        .document
+                        .get_node(*id)
+                        .filter(|node| node.borrow().is_user_scrollable())
+                });
+                if let Some(node) = scrollable {
+                    let id = node.borrow().id;
+                    // User input interrupts any smooth scroll in progress.
+                    self.animator.cancel_scroll(id);
+                    {
+                        let mut node = node.borrow_mut();
+                        let current = node.scroll_offset;
+                        let (overflow_x, overflow_y) = node.layout.style.overflow();
+                        let delta = |overflow: Overflow, delta: f64| {
+                            if overflow.is_user_scrollable() {
+                                delta
+                            } else {
+                                0.0
+                            }
+                        };
+                        node.scroll_offset = node.clamp_scroll_offset(ScrollOffset {
+                            x: current.x + delta(overflow_x, dx),
+                            y: current.y + delta(overflow_y, dy),
+                        });
                     }
+                    self.pending_snaps
+                        .insert(id, Instant::now() + SCROLL_SNAP_DELAY);
+                    self.publish_snapshot();
                 }
-                Command::Layout => {
-                    // Immediate layout flush
-                    ctx.layout();
-                    let root = ctx.document.root_node();
-                    let snap = build_render_tree(root);
-                    *snapshot.write().unwrap() = Some(snap);
-                    message_sender.send(WindowMessage::Redraw);
-                    deadline = None;
+            }
+            Command::Batch(commands) => {
+                for cmd in commands {
+                    if !self.handle(cmd) {
+                        return false;
+                    }
+                }
+                // The whole batch becomes visible in a single layout pass.
+                if self.deadline.is_some() {
+                    self.publish_snapshot();
                 }
-            },
-            Err(mpsc::RecvTimeoutError::Timeout) => {
-                // handled at top loop when checking expired deadline
-                continue;
             }
-            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            // The callers of the queries below may have stopped waiting.
+            Command::GetChildren(id, reply) => {
+                let children = self.ctx.document.get_node(id).map(|node| {
+                    let node = node.borrow();
+                    node.children
+                        .iter()
+                        .map(|child| child.borrow().id)
+                        .collect()
+                });
+                let _ = reply.send(children);
+            }
+            Command::GetParent(id, reply) => {
+                let parent = self
+                    .ctx
+                    .document
+                    .get_node(id)
+                    .and_then(|node| node.borrow().parent);
+                let _ = reply.send(parent);
+            }
+            Command::QuerySelector(selectors, reply) => {
+                let _ = reply.send(self.ctx.document.query_selector_all(&selectors));
+            }
+            Command::GetStyleSources(id, reply) => {
+                // `var()` references resolve against the node's up-to-date style
+                if self.deadline.is_some() {
+                    self.publish_snapshot();
+                }
+                let sources = self
+                    .ctx
+                    .document
+                    .get_node(id)
+                    .map(|node| self.ctx.style_sources(&node.borrow()));
+                let _ = reply.send(sources);
+            }
+            Command::GetStats(reply) => {
+                let document = &self.ctx.document;
+                let stats = EngineStats {
+                    node_count: document.nodes().count(),
+                    detached_node_count: document
+                        .nodes()
+                        .filter(|node| {
+                            let node = node.borrow();
+                            node.parent.is_none() && node.id != document.root_id()
+                        })
+                        .count(),
+                    stylesheet_count: self.stylesheets.len(),
+                    rule_count: self.ctx.style_sheet.rules.len(),
+                    last_layout_duration: self.last_layout_duration,
+                    snapshot_node_count: self.snapshot_node_count,
+                };
+                let _ = reply.send(stats);
+            }
+            Command::SaveDocument(reply) => {
+                let saved = SavedDocument::capture(&self.ctx.document, &self.stylesheets);
+                // The caller may have stopped waiting.
+                let _ = reply.send(saved);
+            }
+            Command::LoadDocument(saved, ids) => {
+                let document = saved
+                    .stylesheets
+                    .iter()
+                    .try_for_each(|saved| self.limits.check_stylesheet(&saved.css))
+                    .map(|()| saved.restore())
+                    .and_then(|document| self.check_restored(&document).map(|()| document));
+                let document = match document {
+                    Ok(document) => document,
+                    Err(err) => {
+                        self.limit_exceeded("load document", err);
+                        return true;
+                    }
+                };
+                let previous = std::mem::replace(&mut self.ctx.document, document);
+                // The loaded document embeds none of the previous one's documents
+                self.detach_embedded(&previous);
+                self.stylesheets.clear();
+                for (id, saved) in ids.into_iter().zip(saved.stylesheets) {
+                    let (css, scope) = (saved.css, saved.scope.map(Id::from_u64));
+                    let sheet = self.parse_stylesheet(id, &css);
+                    self.stylesheets.push(LoadedStylesheet {
+                        id,
+                        css,
+                        scope,
+                        origin: saved.origin,
+                        sheet: sheet
+                            .scoped_to(scope)
+                            .with_origin(saved.origin)
+                            .loaded_from(id),
+                    });
+                }
+                self.rebuild_cascade();
+            }
+            Command::Checkpoint(id) => {
+                let saved = SavedDocument::capture(&self.ctx.document, &[]);
+                self.checkpoints.insert(id, saved);
+            }
+            Command::Rollback(id) => {
+                let Some(saved) = self.checkpoints.get(&id) else {
+                    eprintln!("Failed to roll back: checkpoint not found");
+                    return true;
+                };
+                let document = saved.restore();
+                if let Err(err) = self.check_restored(&document) {
+                    self.limit_exceeded("roll back", err);
+                    return true;
+                }
+                let previous = std::mem::replace(&mut self.ctx.document, document);
+                self.ctx.document.keep_view_state(&previous);
+                // Documents embedded in nodes that are gone no longer show here
+                self.detach_embedded(&previous);
+                self.schedule_layout();
+            }
+            Command::DiscardCheckpoint(id) => {
+                self.checkpoints.remove(&id);
+            }
+            Command::Flush(done) => {
+                self.publish_snapshot();
+                // The caller may have stopped waiting.
+                let _ = done.send(());
+            }
+            Command::Layout => {
+                // Immediate layout flush
+                self.publish_snapshot();
+            }
+            Command::Shutdown => {
+                // Commands queued before the shutdown have already been handled;
+                // publish their pending layout so the final snapshot is up to date.
+                if self.deadline.is_some() {
+                    self.publish_snapshot();
+                }
+                return false;
+            }
         }
+
+        true
     }
 }
@@ -0,0 +1,66 @@
+use crate::{
+    css_parser::parse_css,
+    style::{FontFace, Keyframes, Rule, StyleImport, StyleSheet},
+};
+use serde::{Deserialize, Serialize};
+
+/// Leading bytes identifying a compiled stylesheet.
+const MAGIC: &[u8; 4] = b"LLSS";
+
+/// Bumped whenever the serialized rule layout changes.
+const FORMAT_VERSION: u32 = 32;
+
+#[derive(Serialize, Deserialize)]
+struct CompiledStylesheet {
+    /// Kept so that [`crate::Engine::save_document`] can persist the stylesheet.
+    css: String,
+    rules: Vec<Rule>,
+    /// Cascade layers in declaration order, including ones without rules.
+    layers: Vec<String>,
+    keyframes: Vec<Keyframes>,
+    /// Resolved when the stylesheet is added, like those of a parsed stylesheet.
+    imports: Vec<StyleImport>,
+    font_faces: Vec<FontFace>,
+}
+
+/// Parse CSS ahead of time into the binary form loaded by
+/// [`crate::Engine::add_compiled_stylesheet`].
+///
+/// The output is only valid for the version of lolite that produced it.
+pub fn compile_stylesheet(css: &str) -> Result<Vec<u8>, String> {
+    let sheet = parse_css(css)?;
+    let compiled = CompiledStylesheet {
+        css: css.to_string(),
+        rules: sheet.rules,
+        layers: sheet.layers,
+        keyframes: sheet.keyframes,
+        imports: sheet.imports,
+        font_faces: sheet.font_faces,
+    };
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, &compiled).map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+/// Decode a compiled stylesheet into its source and parsed stylesheet.
+pub(crate) fn decode_stylesheet(bytes: &[u8]) -> Option<(String, StyleSheet)> {
+    let payload = bytes.strip_prefix(MAGIC)?;
+    let (version, payload) = payload.split_first_chunk::<4>()?;
+    if u32::from_le_bytes(*version) != FORMAT_VERSION {
+        return None;
+    }
+    let compiled: CompiledStylesheet = bincode::deserialize(payload).ok()?;
+    let sheet = StyleSheet {
+        rules: compiled.rules,
+        layers: compiled.layers,
+        keyframes: compiled.keyframes,
+        imports: compiled.imports,
+        font_faces: compiled.font_faces,
+    };
+    Some((compiled.css, sheet))
+}
+
+#[cfg(test)]
+mod compiled_stylesheet_tests;
@@ -0,0 +1,44 @@
+use super::*;
+use crate::style::{Length, Selector};
+
+#[test]
+fn test_compile_round_trip() {
+    let css = ".card { width: 120px; } .title { color: red; }";
+    let compiled = compile_stylesheet(css).unwrap();
+
+    let (source, sheet) = decode_stylesheet(&compiled).unwrap();
+    let rules = &sheet.rules;
+    assert_eq!(source, css);
+    assert_eq!(rules.len(), 2);
+    assert_eq!(
+        rules[0].selectors,
+        vec![Selector::Class("card".to_string())]
+    );
+    assert!(rules[0]
+        .declarations
+        .iter()
+        .any(|d| d.width == Some(Length::Px(120.0))));
+}
+
+#[test]
+fn test_compile_keeps_layer_order() {
+    let compiled =
+        compile_stylesheet("@layer base, theme; @layer theme { .a { width: 1px; } }").unwrap();
+
+    let (_, sheet) = decode_stylesheet(&compiled).unwrap();
+    assert_eq!(sheet.layers, vec!["base".to_string(), "theme".to_string()]);
+    assert_eq!(sheet.rules[0].layer.as_deref(), Some("theme"));
+}
+
+#[test]
+fn test_decode_rejects_invalid_input() {
+    let mut compiled = compile_stylesheet(".a { width: 1px; }").unwrap();
+
+    assert!(decode_stylesheet(b"").is_none());
+    assert!(decode_stylesheet(b".a { width: 1px; }").is_none());
+    assert!(decode_stylesheet(&compiled[..compiled.len() - 3]).is_none());
+
+    // A different format version is rejected
+    compiled[MAGIC.len()] ^= 0xff;
+    assert!(decode_stylesheet(&compiled).is_none());
+}
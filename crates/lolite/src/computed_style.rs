@@ -0,0 +1,827 @@
+use crate::animation::Easing;
+use crate::style::{
+    AlignContent, AlignItems, AlignSelf, AspectRatio, BackgroundImage, BackgroundRepeat,
+    BackgroundSize, BorderStyle, BoxSizing, Contain, ContentVisibility, Display, FlexDirection,
+    FlexWrap, FontStyle, GridLine, GridTrack, JustifyContent, Length, LineHeight, Overflow,
+    Position, Radius, RepeatCount, RepeatStyle, Rgba, ScrollBehavior, ScrollSnapAlign,
+    ScrollSnapAxis, ScrollSnapStrictness, Style, TextDirection, TrackBreadth, TrackSize,
+    TransitionProperty,
+};
+use crate::units::ResolutionContext;
+use crate::Size;
+use std::fmt;
+
+/// Computed values of a node's style, serialized as canonical CSS strings.
+///
+/// Colors serialize as `rgb()`/`rgba()`, absolute lengths in `px`, and keywords in
+/// their CSS spelling. Unset properties report their initial value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComputedStyle {
+    properties: Vec<(&'static str, String)>,
+}
+
+impl ComputedStyle {
+    pub(crate) fn from_style(style: &Style) -> Self {
+        // Viewport units are already resolved in laid-out styles, and percentages are
+        // kept as specified, so only the font size matters here.
+        let units = ResolutionContext::new(Size::default());
+        let font_size = units.font_size(style.font_size);
+        let units = units.with_font_size(font_size);
+        let length = |value: Option<Length>, initial: Length| {
+            serialize_length(value.unwrap_or(initial), &units)
+        };
+        let max_length = |value: Option<Length>| match value.unwrap_or_default() {
+            Length::Auto => "none".to_string(),
+            value => serialize_length(value, &units),
+        };
+        let gap = |value: Option<Length>| match value.unwrap_or_default() {
+            Length::Auto => "normal".to_string(),
+            value => serialize_length(value, &units),
+        };
+        let color = style.color.unwrap_or(Rgba {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        });
+        let border_width = style.border_width.resolved();
+        let has_border = [
+            border_width.top,
+            border_width.right,
+            border_width.bottom,
+            border_width.left,
+        ]
+        .iter()
+        .any(|width| units.resolve(*width, 0.0).unwrap_or(0.0) > 0.0);
+        // An unset border style is drawn as solid, so report it that way.
+        let border_style = |value: Option<BorderStyle>| match value {
+            Some(value) => value.keyword(),
+            None if has_border => "solid",
+            None => "none",
+        };
+        let radius = |value: Option<Radius>| {
+            let radius = value.unwrap_or_default();
+            let x = serialize_length(zero_auto(radius.x), &units);
+            let y = serialize_length(zero_auto(radius.y), &units);
+            if x == y {
+                x
+            } else {
+                format!("{} {}", x, y)
+            }
+        };
+        let line_height = match style.line_height.unwrap_or_default() {
+            LineHeight::Normal => "normal".to_string(),
+            LineHeight::Number(number) => serialize_number(number),
+            // Percentages are of the font size, not of the containing block
+            LineHeight::Length(Length::Percent(percent)) => {
+                serialize_px(percent / 100.0 * font_size)
+            }
+            LineHeight::Length(length) => serialize_length(length, &units),
+        };
+        let snap_type = style.scroll_snap_type.unwrap_or_default();
+        let overflow = style.overflow();
+
+        let properties = vec![
+            (
+                "display",
+                style.display.unwrap_or_default().keyword().to_string(),
+            ),
+            ("color", serialize_color(color)),
+            (
+                "background-color",
+                serialize_color(style.background_color.unwrap_or_default()),
+            ),
+            (
+                "background-image",
+                serialize_list(style.background_image.as_deref(), |image| match image {
+                    BackgroundImage::None => "none".to_string(),
+                    BackgroundImage::Url(url) => {
+                        format!(
+                            "url(\"{}\")",
+                            url.replace('\\', "\\\\").replace('"', "\\\"")
+                        )
+                    }
+                }),
+            ),
+            (
+                "background-position",
+                serialize_list(style.background_position.as_deref(), |position| {
+                    format!(
+                        "{} {}",
+                        serialize_length(position.x, &units),
+                        serialize_length(position.y, &units)
+                    )
+                }),
+            ),
+            (
+                "background-size",
+                serialize_list(style.background_size.as_deref(), |size| match *size {
+                    BackgroundSize::Explicit(width, Length::Auto) => {
+                        serialize_length(width, &units)
+                    }
+                    BackgroundSize::Explicit(width, height) => format!(
+                        "{} {}",
+                        serialize_length(width, &units),
+                        serialize_length(height, &units)
+                    ),
+                    BackgroundSize::Cover => "cover".to_string(),
+                    BackgroundSize::Contain => "contain".to_string(),
+                }),
+            ),
+            (
+                "background-repeat",
+                serialize_list(style.background_repeat.as_deref(), serialize_repeat),
+            ),
+            (
+                "border-top-color",
+                serialize_color(style.border_color.top.unwrap_or(color)),
+            ),
+            (
+                "border-right-color",
+                serialize_color(style.border_color.right.unwrap_or(color)),
+            ),
+            (
+                "border-bottom-color",
+                serialize_color(style.border_color.bottom.unwrap_or(color)),
+            ),
+            (
+                "border-left-color",
+                serialize_color(style.border_color.left.unwrap_or(color)),
+            ),
+            (
+                "border-top-style",
+                border_style(style.border_style.top).to_string(),
+            ),
+            (
+                "border-right-style",
+                border_style(style.border_style.right).to_string(),
+            ),
+            (
+                "border-bottom-style",
+                border_style(style.border_style.bottom).to_string(),
+            ),
+            (
+                "border-left-style",
+                border_style(style.border_style.left).to_string(),
+            ),
+            (
+                "border-top-width",
+                serialize_length(border_width.top, &units),
+            ),
+            (
+                "border-right-width",
+                serialize_length(border_width.right, &units),
+            ),
+            (
+                "border-bottom-width",
+                serialize_length(border_width.bottom, &units),
+            ),
+            (
+                "border-left-width",
+                serialize_length(border_width.left, &units),
+            ),
+            (
+                "border-top-left-radius",
+                radius(style.border_radius.top_left),
+            ),
+            (
+                "border-top-right-radius",
+                radius(style.border_radius.top_right),
+            ),
+            (
+                "border-bottom-right-radius",
+                radius(style.border_radius.bottom_right),
+            ),
+            (
+                "border-bottom-left-radius",
+                radius(style.border_radius.bottom_left),
+            ),
+            (
+                "box-sizing",
+                style.box_sizing.unwrap_or_default().keyword().to_string(),
+            ),
+            ("margin-top", length(style.margin.top, Length::Px(0.0))),
+            ("margin-right", length(style.margin.right, Length::Px(0.0))),
+            (
+                "margin-bottom",
+                length(style.margin.bottom, Length::Px(0.0)),
+            ),
+            ("margin-left", length(style.margin.left, Length::Px(0.0))),
+            ("padding-top", length(style.padding.top, Length::Px(0.0))),
+            (
+                "padding-right",
+                length(style.padding.right, Length::Px(0.0)),
+            ),
+            (
+                "padding-bottom",
+                length(style.padding.bottom, Length::Px(0.0)),
+            ),
+            ("padding-left", length(style.padding.left, Length::Px(0.0))),
+            (
+                "position",
+                style.position.unwrap_or_default().keyword().to_string(),
+            ),
+            ("top", length(style.inset.top, Length::Auto)),
+            ("right", length(style.inset.right, Length::Auto)),
+            ("bottom", length(style.inset.bottom, Length::Auto)),
+            ("left", length(style.inset.left, Length::Auto)),
+            ("width", length(style.width, Length::Auto)),
+            ("height", length(style.height, Length::Auto)),
+            ("min-width", length(style.min_width, Length::Auto)),
+            ("max-width", max_length(style.max_width)),
+            ("min-height", length(style.min_height, Length::Auto)),
+            ("max-height", max_length(style.max_height)),
+            (
+                "aspect-ratio",
+                match style.aspect_ratio.unwrap_or_default() {
+                    AspectRatio::Auto => "auto".to_string(),
+                    AspectRatio::Ratio(width, height) => {
+                        format!("{} / {}", serialize_number(width), serialize_number(height))
+                    }
+                },
+            ),
+            (
+                "font-family",
+                style
+                    .font_family
+                    .clone()
+                    .unwrap_or_else(|| "Arial".to_string()),
+            ),
+            ("font-size", serialize_px(font_size)),
+            ("font-weight", style.font_weight.unwrap_or(400).to_string()),
+            (
+                "font-style",
+                style.font_style.unwrap_or_default().keyword().to_string(),
+            ),
+            ("line-height", line_height),
+            (
+                "direction",
+                style.direction.unwrap_or_default().keyword().to_string(),
+            ),
+            (
+                "flex-direction",
+                style
+                    .flex_direction
+                    .unwrap_or_default()
+                    .keyword()
+                    .to_string(),
+            ),
+            (
+                "flex-wrap",
+                style.flex_wrap.unwrap_or_default().keyword().to_string(),
+            ),
+            (
+                "justify-content",
+                style
+                    .justify_content
+                    .unwrap_or_default()
+                    .keyword()
+                    .to_string(),
+            ),
+            (
+                "align-items",
+                style.align_items.unwrap_or_default().keyword().to_string(),
+            ),
+            (
+                "align-content",
+                style
+                    .align_content
+                    .unwrap_or_default()
+                    .keyword()
+                    .to_string(),
+            ),
+            ("row-gap", gap(style.row_gap)),
+            ("column-gap", gap(style.column_gap)),
+            (
+                "grid-template-columns",
+                serialize_track_list(style.grid_template_columns.as_deref(), &units),
+            ),
+            (
+                "grid-template-rows",
+                serialize_track_list(style.grid_template_rows.as_deref(), &units),
+            ),
+            (
+                "flex-grow",
+                serialize_number(style.flex_grow.unwrap_or(0.0)),
+            ),
+            (
+                "flex-shrink",
+                serialize_number(style.flex_shrink.unwrap_or(1.0)),
+            ),
+            ("flex-basis", length(style.flex_basis, Length::Auto)),
+            (
+                "align-self",
+                style.align_self.unwrap_or_default().keyword().to_string(),
+            ),
+            ("order", style.order.unwrap_or(0).to_string()),
+            ("grid-row-start", serialize_grid_line(&style.grid_row_start)),
+            ("grid-row-end", serialize_grid_line(&style.grid_row_end)),
+            (
+                "grid-column-start",
+                serialize_grid_line(&style.grid_column_start),
+            ),
+            (
+                "grid-column-end",
+                serialize_grid_line(&style.grid_column_end),
+            ),
+            (
+                "transition-property",
+                match style.transition_property.as_deref() {
+                    None => "all".to_string(),
+                    Some([]) => "none".to_string(),
+                    Some(properties) => properties
+                        .iter()
+                        .map(|property| match property {
+                            TransitionProperty::All => "all",
+                            TransitionProperty::Named(name) => name,
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                },
+            ),
+            (
+                "transition-duration",
+                serialize_times(style.transition_duration.as_deref()),
+            ),
+            (
+                "transition-timing-function",
+                match style.transition_timing_function.as_deref() {
+                    None => "ease".to_string(),
+                    Some(easings) => easings
+                        .iter()
+                        .map(serialize_easing)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                },
+            ),
+            (
+                "transition-delay",
+                serialize_times(style.transition_delay.as_deref()),
+            ),
+            (
+                "scroll-behavior",
+                style
+                    .scroll_behavior
+                    .unwrap_or_default()
+                    .keyword()
+                    .to_string(),
+            ),
+            (
+                "scroll-snap-type",
+                match snap_type.axis {
+                    ScrollSnapAxis::None => "none".to_string(),
+                    axis => match snap_type.strictness {
+                        // `proximity` is the default and is omitted.
+                        ScrollSnapStrictness::Proximity => axis.keyword().to_string(),
+                        strictness => format!("{} {}", axis.keyword(), strictness.keyword()),
+                    },
+                },
+            ),
+            (
+                "scroll-snap-align",
+                style
+                    .scroll_snap_align
+                    .unwrap_or_default()
+                    .keyword()
+                    .to_string(),
+            ),
+            (
+                "contain",
+                serialize_contain(style.contain.unwrap_or_default()),
+            ),
+            (
+                "content-visibility",
+                style
+                    .content_visibility
+                    .unwrap_or_default()
+                    .keyword()
+                    .to_string(),
+            ),
+            ("overflow-x", overflow.0.keyword().to_string()),
+            ("overflow-y", overflow.1.keyword().to_string()),
+        ];
+
+        Self { properties }
+    }
+
+    /// Get the serialized value of a property, e.g. `"background-color"`.
+    pub fn get(&self, property: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(name, _)| *name == property)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterate over all properties and their serialized values.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+    }
+}
+
+impl fmt::Display for ComputedStyle {
+    /// Format as a CSS declaration block body, e.g. `display: flex; color: rgb(0, 0, 0);`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}: {};", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize a color as `rgb(r, g, b)`, or `rgba(r, g, b, a)` when not fully opaque.
+pub(crate) fn serialize_color(color: Rgba) -> String {
+    if color.a == 255 {
+        format!("rgb({}, {}, {})", color.r, color.g, color.b)
+    } else {
+        format!(
+            "rgba({}, {}, {}, {})",
+            color.r,
+            color.g,
+            color.b,
+            serialize_number(color.a as f64 / 255.0)
+        )
+    }
+}
+
+/// Serialize a length, resolving `em` against the font size in `units`.
+pub(crate) fn serialize_length(length: Length, units: &ResolutionContext) -> String {
+    match length {
+        Length::Auto => "auto".to_string(),
+        Length::Percent(value) => format!("{}%", serialize_number(value)),
+        // Only left unresolved in styles that haven't been laid out yet
+        Length::Vw(value) => format!("{}vw", serialize_number(value)),
+        Length::Vh(value) => format!("{}vh", serialize_number(value)),
+        Length::Vmin(value) => format!("{}vmin", serialize_number(value)),
+        Length::Vmax(value) => format!("{}vmax", serialize_number(value)),
+        length => serialize_px(units.resolve(length, 0.0).unwrap_or(0.0)),
+    }
+}
+
+fn serialize_px(value: f64) -> String {
+    format!("{}px", serialize_number(value))
+}
+
+/// Format a number without trailing zeros, rounded to three decimals.
+fn serialize_number(value: f64) -> String {
+    let rounded = (value * 1000.0).round() / 1000.0;
+    // Avoid serializing negative zero as "-0".
+    format!("{}", if rounded == 0.0 { 0.0 } else { rounded })
+}
+
+/// Radii cannot be `auto`; treat an unset component as zero.
+fn zero_auto(length: Length) -> Length {
+    match length {
+        Length::Auto => Length::Px(0.0),
+        length => length,
+    }
+}
+
+fn serialize_track_list(tracks: Option<&[GridTrack]>, units: &ResolutionContext) -> String {
+    match tracks {
+        None | Some([]) => "none".to_string(),
+        Some(tracks) => serialize_tracks(tracks, units),
+    }
+}
+
+fn serialize_tracks(tracks: &[GridTrack], units: &ResolutionContext) -> String {
+    let tracks: Vec<_> = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::LineNames(names) => format!("[{}]", names.join(" ")),
+            GridTrack::Size(TrackSize::Breadth(size)) => serialize_breadth(size, units),
+            GridTrack::Size(TrackSize::MinMax(min, max)) => format!(
+                "minmax({}, {})",
+                serialize_breadth(min, units),
+                serialize_breadth(max, units)
+            ),
+            GridTrack::Size(TrackSize::FitContent(limit)) => {
+                format!("fit-content({})", serialize_length(*limit, units))
+            }
+            GridTrack::Repeat(count, tracks) => {
+                let count = match count {
+                    RepeatCount::Count(count) => count.to_string(),
+                    RepeatCount::AutoFill => "auto-fill".to_string(),
+                    RepeatCount::AutoFit => "auto-fit".to_string(),
+                };
+                format!("repeat({}, {})", count, serialize_tracks(tracks, units))
+            }
+        })
+        .collect();
+    tracks.join(" ")
+}
+
+fn serialize_breadth(breadth: &TrackBreadth, units: &ResolutionContext) -> String {
+    match breadth {
+        TrackBreadth::Length(length) => serialize_length(*length, units),
+        TrackBreadth::Fraction(fraction) => format!("{}fr", serialize_number(*fraction)),
+        TrackBreadth::MinContent => "min-content".to_string(),
+        TrackBreadth::MaxContent => "max-content".to_string(),
+    }
+}
+
+/// A comma-separated list, or the initial value if unset.
+fn serialize_list<T: Default>(values: Option<&[T]>, serialize: impl Fn(&T) -> String) -> String {
+    match values {
+        None => serialize(&T::default()),
+        Some(values) => values.iter().map(serialize).collect::<Vec<_>>().join(", "),
+    }
+}
+
+/// A `background-repeat`, in its shortest form.
+fn serialize_repeat(repeat: &BackgroundRepeat) -> String {
+    match (repeat.x, repeat.y) {
+        (RepeatStyle::Repeat, RepeatStyle::NoRepeat) => "repeat-x".to_string(),
+        (RepeatStyle::NoRepeat, RepeatStyle::Repeat) => "repeat-y".to_string(),
+        (x, y) if x == y => x.keyword().to_string(),
+        (x, y) => format!("{} {}", x.keyword(), y.keyword()),
+    }
+}
+
+/// Times in seconds, `0s` if unset.
+fn serialize_times(times: Option<&[f64]>) -> String {
+    times
+        .unwrap_or(&[0.0])
+        .iter()
+        .map(|time| format!("{}s", serialize_number(*time)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn serialize_easing(easing: &Easing) -> String {
+    match *easing {
+        Easing::Linear => "linear".to_string(),
+        Easing::Ease => "ease".to_string(),
+        Easing::EaseIn => "ease-in".to_string(),
+        Easing::EaseOut => "ease-out".to_string(),
+        Easing::EaseInOut => "ease-in-out".to_string(),
+        Easing::CubicBezier(x1, y1, x2, y2) => format!(
+            "cubic-bezier({}, {}, {}, {})",
+            serialize_number(x1),
+            serialize_number(y1),
+            serialize_number(x2),
+            serialize_number(y2)
+        ),
+        // Only set through `Engine::animate`, never from CSS
+        Easing::Spring(damping, stiffness) => format!(
+            "spring({}, {})",
+            serialize_number(damping),
+            serialize_number(stiffness)
+        ),
+        Easing::Decay(rate) => format!("decay({})", serialize_number(rate)),
+    }
+}
+
+fn serialize_grid_line(line: &Option<GridLine>) -> String {
+    match line.as_ref().unwrap_or(&GridLine::Auto) {
+        GridLine::Auto => "auto".to_string(),
+        GridLine::Name(name) => name.clone(),
+        GridLine::Index(index, None) => index.to_string(),
+        GridLine::Index(index, Some(name)) => format!("{} {}", index, name),
+        GridLine::Span(count, None) => format!("span {}", count),
+        GridLine::Span(1, Some(name)) => format!("span {}", name),
+        GridLine::Span(count, Some(name)) => format!("span {} {}", count, name),
+    }
+}
+
+fn serialize_contain(contain: Contain) -> String {
+    match (contain.layout, contain.paint) {
+        (false, false) => "none",
+        (true, false) => "layout",
+        (false, true) => "paint",
+        (true, true) => "content",
+    }
+    .to_string()
+}
+
+/// CSS spelling of a keyword value.
+trait Keyword {
+    fn keyword(&self) -> &'static str;
+}
+
+impl Keyword for Display {
+    fn keyword(&self) -> &'static str {
+        match self {
+            Display::Block => "block",
+            Display::Flex => "flex",
+        }
+    }
+}
+
+impl Keyword for BorderStyle {
+    fn keyword(&self) -> &'static str {
+        match self {
+            BorderStyle::None => "none",
+            BorderStyle::Hidden => "hidden",
+            BorderStyle::Solid => "solid",
+            BorderStyle::Dotted => "dotted",
+            BorderStyle::Dashed => "dashed",
+            BorderStyle::Double => "double",
+            BorderStyle::Groove => "groove",
+            BorderStyle::Ridge => "ridge",
+            BorderStyle::Inset => "inset",
+            BorderStyle::Outset => "outset",
+        }
+    }
+}
+
+impl Keyword for TextDirection {
+    fn keyword(&self) -> &'static str {
+        match self {
+            TextDirection::Ltr => "ltr",
+            TextDirection::Rtl => "rtl",
+        }
+    }
+}
+
+impl Keyword for RepeatStyle {
+    fn keyword(&self) -> &'static str {
+        match self {
+            RepeatStyle::Repeat => "repeat",
+            RepeatStyle::NoRepeat => "no-repeat",
+            RepeatStyle::Space => "space",
+            RepeatStyle::Round => "round",
+        }
+    }
+}
+
+impl Keyword for FontStyle {
+    fn keyword(&self) -> &'static str {
+        match self {
+            FontStyle::Normal => "normal",
+            FontStyle::Italic => "italic",
+            FontStyle::Oblique => "oblique",
+        }
+    }
+}
+
+impl Keyword for BoxSizing {
+    fn keyword(&self) -> &'static str {
+        match self {
+            BoxSizing::ContentBox => "content-box",
+            BoxSizing::BorderBox => "border-box",
+        }
+    }
+}
+
+impl Keyword for FlexDirection {
+    fn keyword(&self) -> &'static str {
+        match self {
+            FlexDirection::Row => "row",
+            FlexDirection::RowReverse => "row-reverse",
+            FlexDirection::Column => "column",
+            FlexDirection::ColumnReverse => "column-reverse",
+        }
+    }
+}
+
+impl Keyword for FlexWrap {
+    fn keyword(&self) -> &'static str {
+        match self {
+            FlexWrap::NoWrap => "nowrap",
+            FlexWrap::Wrap => "wrap",
+            FlexWrap::WrapReverse => "wrap-reverse",
+        }
+    }
+}
+
+impl Keyword for JustifyContent {
+    fn keyword(&self) -> &'static str {
+        match self {
+            JustifyContent::FlexStart => "flex-start",
+            JustifyContent::FlexEnd => "flex-end",
+            JustifyContent::Center => "center",
+            JustifyContent::SpaceBetween => "space-between",
+            JustifyContent::SpaceAround => "space-around",
+            JustifyContent::SpaceEvenly => "space-evenly",
+            JustifyContent::Start => "start",
+            JustifyContent::End => "end",
+            JustifyContent::Left => "left",
+            JustifyContent::Right => "right",
+        }
+    }
+}
+
+impl Keyword for AlignItems {
+    fn keyword(&self) -> &'static str {
+        match self {
+            AlignItems::Stretch => "stretch",
+            AlignItems::FlexStart => "flex-start",
+            AlignItems::FlexEnd => "flex-end",
+            AlignItems::Center => "center",
+            AlignItems::Baseline => "baseline",
+            AlignItems::Start => "start",
+            AlignItems::End => "end",
+        }
+    }
+}
+
+impl Keyword for AlignContent {
+    fn keyword(&self) -> &'static str {
+        match self {
+            AlignContent::Stretch => "stretch",
+            AlignContent::FlexStart => "flex-start",
+            AlignContent::FlexEnd => "flex-end",
+            AlignContent::Center => "center",
+            AlignContent::SpaceBetween => "space-between",
+            AlignContent::SpaceAround => "space-around",
+            AlignContent::SpaceEvenly => "space-evenly",
+            AlignContent::Start => "start",
+            AlignContent::End => "end",
+        }
+    }
+}
+
+impl Keyword for AlignSelf {
+    fn keyword(&self) -> &'static str {
+        match self {
+            AlignSelf::Auto => "auto",
+            AlignSelf::FlexStart => "flex-start",
+            AlignSelf::FlexEnd => "flex-end",
+            AlignSelf::Center => "center",
+            AlignSelf::Baseline => "baseline",
+            AlignSelf::Stretch => "stretch",
+            AlignSelf::Start => "start",
+            AlignSelf::End => "end",
+        }
+    }
+}
+
+impl Keyword for ScrollBehavior {
+    fn keyword(&self) -> &'static str {
+        match self {
+            ScrollBehavior::Auto => "auto",
+            ScrollBehavior::Smooth => "smooth",
+        }
+    }
+}
+
+impl Keyword for ContentVisibility {
+    fn keyword(&self) -> &'static str {
+        match self {
+            ContentVisibility::Visible => "visible",
+            ContentVisibility::Auto => "auto",
+            ContentVisibility::Hidden => "hidden",
+        }
+    }
+}
+
+impl Keyword for Position {
+    fn keyword(&self) -> &'static str {
+        match self {
+            Position::Static => "static",
+            Position::Relative => "relative",
+            Position::Absolute => "absolute",
+            Position::Fixed => "fixed",
+        }
+    }
+}
+
+impl Keyword for Overflow {
+    fn keyword(&self) -> &'static str {
+        match self {
+            Overflow::Visible => "visible",
+            Overflow::Hidden => "hidden",
+            Overflow::Scroll => "scroll",
+            Overflow::Auto => "auto",
+        }
+    }
+}
+
+impl Keyword for ScrollSnapAxis {
+    fn keyword(&self) -> &'static str {
+        match self {
+            ScrollSnapAxis::None => "none",
+            ScrollSnapAxis::X => "x",
+            ScrollSnapAxis::Y => "y",
+            ScrollSnapAxis::Both => "both",
+        }
+    }
+}
+
+impl Keyword for ScrollSnapStrictness {
+    fn keyword(&self) -> &'static str {
+        match self {
+            ScrollSnapStrictness::Mandatory => "mandatory",
+            ScrollSnapStrictness::Proximity => "proximity",
+        }
+    }
+}
+
+impl Keyword for ScrollSnapAlign {
+    fn keyword(&self) -> &'static str {
+        match self {
+            ScrollSnapAlign::None => "none",
+            ScrollSnapAlign::Start => "start",
+            ScrollSnapAlign::Center => "center",
+            ScrollSnapAlign::End => "end",
+        }
+    }
+}
+
+#[cfg(test)]
+mod computed_style_tests;
@@ -0,0 +1,207 @@
+use super::*;
+use crate::css_parser::parse_css;
+use crate::style::Directional;
+
+fn computed(css: &str) -> ComputedStyle {
+    let sheet = parse_css(css).expect("Failed to parse CSS");
+    let mut style = Style::default();
+    for declaration in &sheet.rules[0].declarations {
+        style.merge(declaration);
+    }
+    ComputedStyle::from_style(&style)
+}
+
+#[test]
+fn test_serialize_colors() {
+    let style = computed(".a { color: rebeccapurple; background-color: rgba(255, 0, 0, 0.5); }");
+
+    assert_eq!(style.get("color"), Some("rgb(102, 51, 153)"));
+    assert_eq!(
+        style.get("background-color"),
+        Some("rgba(255, 0, 0, 0.502)")
+    );
+    // Border colors default to the text color
+    assert_eq!(style.get("border-top-color"), Some("rgb(102, 51, 153)"));
+}
+
+#[test]
+fn test_serialize_lengths_in_px() {
+    let style = computed(".a { width: 2em; height: 50%; margin: 10px; }");
+
+    assert_eq!(style.get("width"), Some("24px"));
+    assert_eq!(style.get("height"), Some("50%"));
+    assert_eq!(style.get("margin-left"), Some("10px"));
+    assert_eq!(style.get("padding-left"), Some("0px"));
+    assert_eq!(style.get("font-size"), Some("12px"));
+    assert_eq!(style.get("flex-basis"), Some("auto"));
+
+    // Em lengths resolve against the node's own font size
+    let style = ComputedStyle::from_style(&Style {
+        width: Some(Length::Em(2.0)),
+        font_size: Some(Length::Px(20.0)),
+        ..Default::default()
+    });
+    assert_eq!(style.get("width"), Some("40px"));
+    assert_eq!(style.get("font-size"), Some("20px"));
+}
+
+#[test]
+fn test_serialize_size_limits() {
+    let style = computed(".a { min-width: 2em; max-height: 50%; }");
+
+    assert_eq!(style.get("min-width"), Some("24px"));
+    assert_eq!(style.get("max-height"), Some("50%"));
+    assert_eq!(style.get("min-height"), Some("auto"));
+    assert_eq!(style.get("max-width"), Some("none"));
+}
+
+#[test]
+fn test_serialize_keywords() {
+    let style = computed(
+        ".a { flex-direction: column-reverse; justify-content: space-between; scroll-snap-type: x mandatory; }",
+    );
+
+    assert_eq!(style.get("display"), Some("block"));
+    assert_eq!(style.get("flex-direction"), Some("column-reverse"));
+    assert_eq!(style.get("justify-content"), Some("space-between"));
+    assert_eq!(style.get("align-items"), Some("stretch"));
+    assert_eq!(style.get("scroll-snap-type"), Some("x mandatory"));
+    assert_eq!(style.get("box-sizing"), Some("content-box"));
+
+    // Later declarations that don't set `display` keep it
+    let style = computed(".a { display: flex; color: red; }");
+    assert_eq!(style.get("display"), Some("flex"));
+}
+
+#[test]
+fn test_serialize_containment() {
+    let style = computed(".a { contain: paint layout; content-visibility: auto; }");
+    assert_eq!(style.get("contain"), Some("content"));
+    assert_eq!(style.get("content-visibility"), Some("auto"));
+
+    let style = computed(".a { contain: style paint; }");
+    assert_eq!(style.get("contain"), Some("paint"));
+    assert_eq!(style.get("content-visibility"), Some("visible"));
+}
+
+#[test]
+fn test_serialize_overflow() {
+    let style = computed(".a { overflow-y: hidden; }");
+    // `visible` next to a clipping axis is used as `auto`
+    assert_eq!(style.get("overflow-x"), Some("auto"));
+    assert_eq!(style.get("overflow-y"), Some("hidden"));
+
+    let style = computed(".a { color: red; }");
+    assert_eq!(style.get("overflow-x"), Some("visible"));
+    assert_eq!(style.get("overflow-y"), Some("visible"));
+}
+
+#[test]
+fn test_serialize_position() {
+    let style = computed(".a { position: relative; inset: 1em auto; }");
+    assert_eq!(style.get("position"), Some("relative"));
+    assert_eq!(style.get("top"), Some("12px"));
+    assert_eq!(style.get("right"), Some("auto"));
+    assert_eq!(style.get("bottom"), Some("12px"));
+
+    let style = computed(".a { color: red; }");
+    assert_eq!(style.get("position"), Some("static"));
+    assert_eq!(style.get("left"), Some("auto"));
+}
+
+#[test]
+fn test_serialize_grid() {
+    let style = computed(
+        ".a { grid-template-columns: [a] repeat(2, 1fr minmax(2em, max-content)) 50%; \
+         grid-area: 2 / main; }",
+    );
+    assert_eq!(
+        style.get("grid-template-columns"),
+        Some("[a] repeat(2, 1fr minmax(24px, max-content)) 50%")
+    );
+    assert_eq!(style.get("grid-template-rows"), Some("none"));
+    assert_eq!(style.get("grid-row-start"), Some("2"));
+    assert_eq!(style.get("grid-column-start"), Some("main"));
+    assert_eq!(style.get("grid-row-end"), Some("auto"));
+    assert_eq!(style.get("grid-column-end"), Some("main"));
+}
+
+#[test]
+fn test_serialize_transitions() {
+    let style =
+        computed(".a { transition: width 250ms, color 1s cubic-bezier(0, 0, 0.5, 1) -2s; }");
+    assert_eq!(style.get("transition-property"), Some("width, color"));
+    assert_eq!(style.get("transition-duration"), Some("0.25s, 1s"));
+    assert_eq!(
+        style.get("transition-timing-function"),
+        Some("ease, cubic-bezier(0, 0, 0.5, 1)")
+    );
+    assert_eq!(style.get("transition-delay"), Some("0s, -2s"));
+
+    let style = computed(".a { width: 1px; }");
+    assert_eq!(style.get("transition-property"), Some("all"));
+    assert_eq!(style.get("transition-duration"), Some("0s"));
+    assert_eq!(style.get("transition-timing-function"), Some("ease"));
+}
+
+#[test]
+fn test_serialize_fonts() {
+    let style = computed(".a { font: italic 600 20px/150% Arial, sans-serif; }");
+    assert_eq!(style.get("font-family"), Some("Arial, sans-serif"));
+    assert_eq!(style.get("font-weight"), Some("600"));
+    assert_eq!(style.get("font-style"), Some("italic"));
+    assert_eq!(style.get("line-height"), Some("30px"));
+
+    let style = computed(".a { line-height: 1.25; }");
+    assert_eq!(style.get("font-weight"), Some("400"));
+    assert_eq!(style.get("font-style"), Some("normal"));
+    assert_eq!(style.get("line-height"), Some("1.25"));
+}
+
+#[test]
+fn test_serialize_backgrounds() {
+    let style = computed(
+        r#".a { background: url(a.png) right 2em / cover no-repeat, url("b.png") repeat-x red; }"#,
+    );
+    assert_eq!(style.get("background-color"), Some("rgb(255, 0, 0)"));
+    assert_eq!(
+        style.get("background-image"),
+        Some(r#"url("a.png"), url("b.png")"#)
+    );
+    assert_eq!(style.get("background-position"), Some("100% 24px, 0% 0%"));
+    assert_eq!(style.get("background-size"), Some("cover, auto"));
+    assert_eq!(style.get("background-repeat"), Some("no-repeat, repeat-x"));
+
+    let style = computed(".a { background-color: blue; }");
+    assert_eq!(style.get("background-image"), Some("none"));
+    assert_eq!(style.get("background-position"), Some("0% 0%"));
+    assert_eq!(style.get("background-size"), Some("auto"));
+    assert_eq!(style.get("background-repeat"), Some("repeat"));
+}
+
+#[test]
+fn test_serialize_border_style_follows_width() {
+    let mut style = Style {
+        border_width: Directional::set_all(Some(Length::Px(1.0))),
+        ..Default::default()
+    };
+    assert_eq!(
+        ComputedStyle::from_style(&style).get("border-top-style"),
+        Some("solid")
+    );
+
+    style.border_width = Directional::default();
+    assert_eq!(
+        ComputedStyle::from_style(&style).get("border-top-style"),
+        Some("none")
+    );
+}
+
+#[test]
+fn test_serialize_numbers() {
+    assert_eq!(serialize_number(0.1 + 0.2), "0.3");
+    assert_eq!(serialize_number(-0.0001), "0");
+    assert_eq!(serialize_number(12.0), "12");
+    let units = ResolutionContext::new(Size::default());
+    assert_eq!(serialize_length(Length::Px(1.5), &units), "1.5px");
+}
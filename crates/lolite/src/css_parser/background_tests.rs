@@ -0,0 +1,157 @@
+use crate::css_parser::fixtures::declarations;
+use crate::style::{
+    BackgroundImage, BackgroundPosition, BackgroundRepeat, BackgroundSize, Length, RepeatStyle,
+    Rgba,
+};
+
+fn position(x: Length, y: Length) -> BackgroundPosition {
+    BackgroundPosition { x, y }
+}
+
+#[test]
+fn test_parse_background_shorthand() {
+    let styles = declarations(
+        r#".a {
+            background: url(top.png) center / 10px auto no-repeat, url("base.png") left bottom repeat-y #00ff00;
+            background: red;
+        }"#,
+    );
+    assert_eq!(styles.len(), 2);
+
+    assert_eq!(
+        styles[0].background_image,
+        Some(vec![
+            BackgroundImage::Url("top.png".to_string()),
+            BackgroundImage::Url("base.png".to_string()),
+        ])
+    );
+    assert_eq!(
+        styles[0].background_position,
+        Some(vec![
+            position(Length::Percent(50.0), Length::Percent(50.0)),
+            position(Length::Percent(0.0), Length::Percent(100.0)),
+        ])
+    );
+    assert_eq!(
+        styles[0].background_size,
+        Some(vec![
+            BackgroundSize::Explicit(Length::Px(10.0), Length::Auto),
+            BackgroundSize::default(),
+        ])
+    );
+    assert_eq!(
+        styles[0].background_repeat,
+        Some(vec![
+            BackgroundRepeat {
+                x: RepeatStyle::NoRepeat,
+                y: RepeatStyle::NoRepeat,
+            },
+            BackgroundRepeat {
+                x: RepeatStyle::NoRepeat,
+                y: RepeatStyle::Repeat,
+            },
+        ])
+    );
+    assert_eq!(
+        styles[0].background_color,
+        Some(Rgba {
+            r: 0,
+            g: 255,
+            b: 0,
+            a: 255,
+        })
+    );
+
+    // A color alone still resets the layers to a single empty one
+    assert_eq!(
+        styles[1].background_image,
+        Some(vec![BackgroundImage::None])
+    );
+    assert_eq!(
+        styles[1].background_position,
+        Some(vec![BackgroundPosition::default()])
+    );
+    assert_eq!(
+        styles[1].background_color,
+        Some(Rgba {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        })
+    );
+}
+
+#[test]
+fn test_parse_background_longhands() {
+    let styles = declarations(
+        ".a {
+            background-image: none, url(a.png);
+            background-position: top, 10px, bottom right, 25% 2em;
+            background-size: contain, 50%, auto 20px;
+            background-repeat: repeat-x, space round, no-repeat;
+        }",
+    );
+    assert_eq!(styles.len(), 4);
+    assert_eq!(
+        styles[0].background_image,
+        Some(vec![
+            BackgroundImage::None,
+            BackgroundImage::Url("a.png".to_string()),
+        ])
+    );
+    assert_eq!(
+        styles[1].background_position,
+        Some(vec![
+            position(Length::Percent(50.0), Length::Percent(0.0)),
+            position(Length::Px(10.0), Length::Percent(50.0)),
+            position(Length::Percent(100.0), Length::Percent(100.0)),
+            position(Length::Percent(25.0), Length::Em(2.0)),
+        ])
+    );
+    assert_eq!(
+        styles[2].background_size,
+        Some(vec![
+            BackgroundSize::Contain,
+            BackgroundSize::Explicit(Length::Percent(50.0), Length::Auto),
+            BackgroundSize::Explicit(Length::Auto, Length::Px(20.0)),
+        ])
+    );
+    assert_eq!(
+        styles[3].background_repeat,
+        Some(vec![
+            BackgroundRepeat {
+                x: RepeatStyle::Repeat,
+                y: RepeatStyle::NoRepeat,
+            },
+            BackgroundRepeat {
+                x: RepeatStyle::Space,
+                y: RepeatStyle::Round,
+            },
+            BackgroundRepeat {
+                x: RepeatStyle::NoRepeat,
+                y: RepeatStyle::NoRepeat,
+            },
+        ])
+    );
+}
+
+#[test]
+fn test_reject_invalid_background_values() {
+    let styles = declarations(
+        ".a {
+            background: red, url(a.png);
+            background: url(a.png) url(b.png);
+            background: 10px / cover / contain;
+            background: no-repeat / cover;
+            background-image: linear-gradient(red, blue);
+            background-position: 10px left;
+            background-position: top 10px;
+            background-size: -1px;
+            background-repeat: repeat-x repeat;
+            width: 1px;
+        }",
+    );
+    assert_eq!(styles.len(), 1);
+    assert_eq!(styles[0].width, Some(Length::Px(1.0)));
+}
@@ -0,0 +1,274 @@
+use super::parser::StyleDeclarationParser;
+use crate::style::{
+    BackgroundImage, BackgroundPosition, BackgroundRepeat, BackgroundSize, Length, RepeatStyle,
+    Rgba, Style,
+};
+use cssparser::{match_ignore_ascii_case, ParseError, Parser, Token};
+
+/// One layer of the `background` shorthand, with omitted values defaulted.
+struct BackgroundLayer {
+    image: BackgroundImage,
+    position: BackgroundPosition,
+    size: BackgroundSize,
+    repeat: BackgroundRepeat,
+    /// Only allowed in the last layer.
+    color: Option<Rgba>,
+}
+
+/// A component of `background-position`, before it is known which axis it is for.
+#[derive(Clone, Copy)]
+enum PositionComponent {
+    Center,
+    /// `left` or `right`, as a percentage.
+    Horizontal(Length),
+    /// `top` or `bottom`, as a percentage.
+    Vertical(Length),
+    Length(Length),
+}
+
+impl PositionComponent {
+    fn offset(self) -> Length {
+        match self {
+            PositionComponent::Center => Length::Percent(50.0),
+            PositionComponent::Horizontal(offset)
+            | PositionComponent::Vertical(offset)
+            | PositionComponent::Length(offset) => offset,
+        }
+    }
+}
+
+impl StyleDeclarationParser {
+    /// Parse the `background` shorthand into `style`, resetting the longhands it omits:
+    /// comma-separated layers of an image, a position with an optional `/ size` and a
+    /// repeat in any order, the last layer optionally with a color.
+    pub(crate) fn parse_background_shorthand<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+        style: &mut Style,
+    ) -> Result<(), ParseError<'i, ()>> {
+        let layers = input.parse_comma_separated(|input| self.parse_background_layer(input))?;
+        let (last, layers_above) = layers.split_last().expect("at least one layer");
+        if layers_above.iter().any(|layer| layer.color.is_some()) {
+            return Err(input.new_error_for_next_token());
+        }
+
+        style.background_color = Some(last.color.unwrap_or_default());
+        style.background_image = Some(layers.iter().map(|l| l.image.clone()).collect());
+        style.background_position = Some(layers.iter().map(|l| l.position).collect());
+        style.background_size = Some(layers.iter().map(|l| l.size).collect());
+        style.background_repeat = Some(layers.iter().map(|l| l.repeat).collect());
+        Ok(())
+    }
+
+    fn parse_background_layer<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<BackgroundLayer, ParseError<'i, ()>> {
+        let mut image = None;
+        let mut position = None;
+        let mut size = None;
+        let mut repeat = None;
+        let mut color = None;
+        loop {
+            if image.is_none() {
+                if let Ok(value) = input.try_parse(parse_background_image) {
+                    image = Some(value);
+                    continue;
+                }
+            }
+            if position.is_none() {
+                if let Ok(value) = input.try_parse(|i| self.parse_background_position(i)) {
+                    position = Some(value);
+                    // A size may only follow the position
+                    if input.try_parse(|i| i.expect_delim('/')).is_ok() {
+                        size = Some(self.parse_background_size(input)?);
+                    }
+                    continue;
+                }
+            }
+            if repeat.is_none() {
+                if let Ok(value) = input.try_parse(parse_background_repeat) {
+                    repeat = Some(value);
+                    continue;
+                }
+            }
+            if color.is_none() {
+                if let Ok(value) = input.try_parse(|i| self.parse_color_value(i)) {
+                    color = Some(value);
+                    continue;
+                }
+            }
+            break;
+        }
+
+        if image.is_none() && position.is_none() && repeat.is_none() && color.is_none() {
+            return Err(input.new_error_for_next_token());
+        }
+        Ok(BackgroundLayer {
+            image: image.unwrap_or_default(),
+            position: position.unwrap_or_default(),
+            size: size.unwrap_or_default(),
+            repeat: repeat.unwrap_or_default(),
+            color,
+        })
+    }
+
+    /// Parse a `background-position` of one or two values: keywords, lengths or
+    /// percentages, horizontal first unless keywords say otherwise. A missing value
+    /// is `center`.
+    pub(crate) fn parse_background_position<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<BackgroundPosition, ParseError<'i, ()>> {
+        let start = input.state();
+        let first = self.parse_position_component(input)?;
+        let Ok(second) = input.try_parse(|i| self.parse_position_component(i)) else {
+            return Ok(match first {
+                PositionComponent::Vertical(y) => BackgroundPosition {
+                    x: Length::Percent(50.0),
+                    y,
+                },
+                first => BackgroundPosition {
+                    x: first.offset(),
+                    y: Length::Percent(50.0),
+                },
+            });
+        };
+
+        use PositionComponent::{Center, Horizontal, Length as Offset, Vertical};
+        match (first, second) {
+            (Center | Horizontal(_) | Offset(_), Center | Vertical(_) | Offset(_)) => {
+                Ok(BackgroundPosition {
+                    x: first.offset(),
+                    y: second.offset(),
+                })
+            }
+            // Keywords may come vertical first, but lengths can't be swapped
+            (Vertical(_) | Center, Horizontal(_) | Center) => Ok(BackgroundPosition {
+                x: second.offset(),
+                y: first.offset(),
+            }),
+            _ => {
+                input.reset(&start);
+                Err(input.new_error_for_next_token())
+            }
+        }
+    }
+
+    fn parse_position_component<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<PositionComponent, ParseError<'i, ()>> {
+        if let Ok(keyword) = input.try_parse(|i| i.expect_ident_cloned()) {
+            let location = input.current_source_location();
+            return match_ignore_ascii_case! { &keyword,
+                "center" => Ok(PositionComponent::Center),
+                "left" => Ok(PositionComponent::Horizontal(Length::Percent(0.0))),
+                "right" => Ok(PositionComponent::Horizontal(Length::Percent(100.0))),
+                "top" => Ok(PositionComponent::Vertical(Length::Percent(0.0))),
+                "bottom" => Ok(PositionComponent::Vertical(Length::Percent(100.0))),
+                _ => Err(location.new_unexpected_token_error(Token::Ident(keyword))),
+            };
+        }
+        match self.parse_length_value(input)? {
+            Length::Auto => Err(input.new_error_for_next_token()),
+            length => Ok(PositionComponent::Length(length)),
+        }
+    }
+
+    /// Parse a `background-size`: `cover`, `contain`, or a width and an optional height,
+    /// each a non-negative length, percentage or `auto`. A missing height is `auto`.
+    pub(crate) fn parse_background_size<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<BackgroundSize, ParseError<'i, ()>> {
+        if input
+            .try_parse(|i| i.expect_ident_matching("cover"))
+            .is_ok()
+        {
+            return Ok(BackgroundSize::Cover);
+        }
+        if input
+            .try_parse(|i| i.expect_ident_matching("contain"))
+            .is_ok()
+        {
+            return Ok(BackgroundSize::Contain);
+        }
+        let width = self.parse_background_size_length(input)?;
+        let height = input
+            .try_parse(|i| self.parse_background_size_length(i))
+            .unwrap_or(Length::Auto);
+        Ok(BackgroundSize::Explicit(width, height))
+    }
+
+    fn parse_background_size_length<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<Length, ParseError<'i, ()>> {
+        let start = input.state();
+        let length = self.parse_length_value(input)?;
+        if length.is_negative() {
+            input.reset(&start);
+            return Err(input.new_error_for_next_token());
+        }
+        Ok(length)
+    }
+}
+
+/// Parse a `background-image`: `none` or a `url()`. Gradients aren't supported.
+pub(crate) fn parse_background_image<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<BackgroundImage, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    match input.next()?.clone() {
+        Token::Ident(keyword) if keyword.eq_ignore_ascii_case("none") => Ok(BackgroundImage::None),
+        Token::UnquotedUrl(url) => Ok(BackgroundImage::Url(url.to_string())),
+        Token::Function(name) if name.eq_ignore_ascii_case("url") => {
+            input.parse_nested_block(|input| {
+                Ok(BackgroundImage::Url(input.expect_string()?.to_string()))
+            })
+        }
+        token => Err(location.new_unexpected_token_error(token)),
+    }
+}
+
+/// Parse a `background-repeat`: `repeat-x`, `repeat-y`, or one or two of `repeat`,
+/// `no-repeat`, `space` and `round`, the horizontal first. A single value applies to
+/// both axes.
+pub(crate) fn parse_background_repeat<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<BackgroundRepeat, ParseError<'i, ()>> {
+    if input
+        .try_parse(|i| i.expect_ident_matching("repeat-x"))
+        .is_ok()
+    {
+        return Ok(BackgroundRepeat {
+            x: RepeatStyle::Repeat,
+            y: RepeatStyle::NoRepeat,
+        });
+    }
+    if input
+        .try_parse(|i| i.expect_ident_matching("repeat-y"))
+        .is_ok()
+    {
+        return Ok(BackgroundRepeat {
+            x: RepeatStyle::NoRepeat,
+            y: RepeatStyle::Repeat,
+        });
+    }
+    let x = parse_repeat(input)?;
+    let y = input.try_parse(parse_repeat).unwrap_or(x);
+    Ok(BackgroundRepeat { x, y })
+}
+
+fn parse_repeat<'i>(input: &mut Parser<'i, '_>) -> Result<RepeatStyle, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let keyword = input.expect_ident()?.clone();
+    match_ignore_ascii_case! { &keyword,
+        "repeat" => Ok(RepeatStyle::Repeat),
+        "no-repeat" => Ok(RepeatStyle::NoRepeat),
+        "space" => Ok(RepeatStyle::Space),
+        "round" => Ok(RepeatStyle::Round),
+        _ => Err(location.new_unexpected_token_error(Token::Ident(keyword))),
+    }
+}
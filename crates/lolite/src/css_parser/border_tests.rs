@@ -1,5 +1,5 @@
 use crate::css_parser::parse_css;
-use crate::style::{BorderStyle, Length, Selector};
+use crate::style::{BorderStyle, Length, Selector, Style};
 
 #[test]
 fn test_parse_border_shorthand_width_and_color() {
@@ -13,7 +13,7 @@ fn test_parse_border_shorthand_width_and_color() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("btn".to_string()));
+    assert_eq!(rule.selectors, vec![Selector::Class("btn".to_string())]);
 
     let mut found_width = false;
     let mut found_color = false;
@@ -52,7 +52,10 @@ fn test_parse_border_shorthand_none() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("noborder".to_string()));
+    assert_eq!(
+        rule.selectors,
+        vec![Selector::Class("noborder".to_string())]
+    );
 
     // Also ensure we did parse the style keyword.
     let mut saw_none_style = false;
@@ -84,7 +87,7 @@ fn test_parse_border_shorthand_rejects_duplicate_width() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("dup".to_string()));
+    assert_eq!(rule.selectors, vec![Selector::Class("dup".to_string())]);
 
     let mut saw_width_decl = false;
     let mut saw_any_border_field = false;
@@ -133,7 +136,7 @@ fn test_parse_border_shorthand_rejects_duplicate_color() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("dup".to_string()));
+    assert_eq!(rule.selectors, vec![Selector::Class("dup".to_string())]);
 
     let mut saw_width_decl = false;
     let mut saw_any_border_field = false;
@@ -182,7 +185,7 @@ fn test_parse_border_shorthand_rejects_duplicate_style() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("dup".to_string()));
+    assert_eq!(rule.selectors, vec![Selector::Class("dup".to_string())]);
 
     let mut saw_width_decl = false;
     let mut saw_any_border_field = false;
@@ -230,7 +233,7 @@ fn test_parse_border_style_property() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("styled".to_string()));
+    assert_eq!(rule.selectors, vec![Selector::Class("styled".to_string())]);
 
     let mut saw_dashed = false;
     for declaration in &rule.declarations {
@@ -257,7 +260,7 @@ fn test_parse_border_side_longhands() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("sides".to_string()));
+    assert_eq!(rule.selectors, vec![Selector::Class("sides".to_string())]);
 
     let mut saw_top_color = false;
     let mut saw_right_style = false;
@@ -294,3 +297,41 @@ fn test_parse_border_side_longhands() {
     assert!(saw_bottom_width);
     assert!(saw_left_width);
 }
+
+#[test]
+fn test_parse_border_side_shorthands() {
+    let css = r#"
+        .sides {
+            border: 1px solid #000000;
+            border-top: 4px dashed #ff0000;
+            border-left: none;
+        }
+    "#;
+
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    let mut style = Style::default();
+    for declaration in &stylesheet.rules[0].declarations {
+        style.merge(declaration);
+    }
+
+    assert_eq!(style.border_width.top, Some(Length::Px(4.0)));
+    assert_eq!(style.border_style.top, Some(BorderStyle::Dashed));
+    assert_eq!(style.border_color.top.map(|c| c.r), Some(0xFF));
+
+    // Components omitted from a side shorthand keep the earlier value
+    assert_eq!(style.border_width.left, Some(Length::Px(1.0)));
+    assert_eq!(style.border_style.left, Some(BorderStyle::None));
+
+    assert_eq!(style.border_width.right, Some(Length::Px(1.0)));
+    assert_eq!(style.border_style.bottom, Some(BorderStyle::Solid));
+}
+
+#[test]
+fn test_parse_border_side_shorthand_rejects_unknown_tokens() {
+    let stylesheet =
+        parse_css(".a { border-right: 2px solid wobbly; }").expect("Failed to parse CSS");
+    assert!(stylesheet.rules[0]
+        .declarations
+        .iter()
+        .all(|d| d.border_width.right.is_none()));
+}
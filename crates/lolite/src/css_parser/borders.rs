@@ -2,6 +2,14 @@ use super::parser::StyleDeclarationParser;
 use crate::style::{BorderStyle, Directional, Length, Radius, Rgba, Style};
 use cssparser::{ParseError, Parser};
 
+/// Components of a `border` shorthand value; omitted ones are `None`.
+#[derive(Default)]
+struct BorderLine {
+    width: Option<Length>,
+    style: Option<BorderStyle>,
+    color: Option<Rgba>,
+}
+
 impl StyleDeclarationParser {
     pub(crate) fn try_parse_line_width<'i, 't>(
         &mut self,
@@ -97,40 +105,34 @@ impl StyleDeclarationParser {
         Ok(None)
     }
 
-    pub(crate) fn parse_border_shorthand<'i, 't>(
+    /// Parse `<line-width> || <line-style> || <color>`, as used by the `border` shorthands.
+    fn parse_border_line<'i, 't>(
         &mut self,
         input: &mut Parser<'i, 't>,
-        style: &mut Style,
-    ) -> Result<(), ParseError<'i, ()>> {
-        // border: <line-width> || <line-style> || <color>
-        let mut saw_width = false;
-        let mut saw_color = false;
-        let mut saw_style = false;
+    ) -> Result<BorderLine, ParseError<'i, ()>> {
+        let mut line = BorderLine::default();
         while !input.is_exhausted() {
             if let Some(width) = self.try_parse_line_width(input)? {
-                if saw_width {
+                if line.width.is_some() {
                     return Err(input.new_error_for_next_token());
                 }
-                saw_width = true;
-                style.border_width = Directional::set_all(Some(width));
+                line.width = Some(width);
                 continue;
             }
 
             if let Ok(color) = input.try_parse(|i| self.parse_color_value(i)) {
-                if saw_color {
+                if line.color.is_some() {
                     return Err(input.new_error_for_next_token());
                 }
-                saw_color = true;
-                style.border_color = Directional::set_all(Some(color));
+                line.color = Some(color);
                 continue;
             }
 
             if let Some(border_style) = self.try_parse_line_style(input)? {
-                if saw_style {
+                if line.style.is_some() {
                     return Err(input.new_error_for_next_token());
                 }
-                saw_style = true;
-                style.border_style = Directional::set_all(Some(border_style));
+                line.style = Some(border_style);
                 continue;
             }
 
@@ -138,6 +140,46 @@ impl StyleDeclarationParser {
             return Err(input.new_error_for_next_token());
         }
 
+        Ok(line)
+    }
+
+    pub(crate) fn parse_border_shorthand<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+        style: &mut Style,
+    ) -> Result<(), ParseError<'i, ()>> {
+        // border: <line-width> || <line-style> || <color>
+        let line = self.parse_border_line(input)?;
+        if let Some(width) = line.width {
+            style.border_width = Directional::set_all(Some(width));
+        }
+        if let Some(border_style) = line.style {
+            style.border_style = Directional::set_all(Some(border_style));
+        }
+        if let Some(color) = line.color {
+            style.border_color = Directional::set_all(Some(color));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn parse_border_side_shorthand<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+        width: &mut Option<Length>,
+        border_style: &mut Option<BorderStyle>,
+        color: &mut Option<Rgba>,
+    ) -> Result<(), ParseError<'i, ()>> {
+        // border-top/right/bottom/left: <line-width> || <line-style> || <color>
+        let line = self.parse_border_line(input)?;
+        if line.width.is_some() {
+            *width = line.width;
+        }
+        if line.style.is_some() {
+            *border_style = line.style;
+        }
+        if line.color.is_some() {
+            *color = line.color;
+        }
         Ok(())
     }
 
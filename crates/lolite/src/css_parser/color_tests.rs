@@ -135,6 +135,53 @@ fn test_named_colors_exact_values() {
     );
 }
 
+#[test]
+fn test_hex_colors_with_alpha() {
+    let css = r#"
+        .a { background-color: #f008; }
+        .b { background-color: #0000ff80; }
+        .c { background-color: #ABC; }
+        .bad { background-color: #12345; }
+    "#;
+
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    let get_bg = |idx: usize| -> Option<Rgba> {
+        stylesheet.rules[idx]
+            .declarations
+            .iter()
+            .find_map(|d| d.background_color)
+    };
+
+    assert_eq!(
+        get_bg(0),
+        Some(Rgba {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 0x88
+        })
+    );
+    assert_eq!(
+        get_bg(1),
+        Some(Rgba {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 0x80
+        })
+    );
+    assert_eq!(
+        get_bg(2),
+        Some(Rgba {
+            r: 0xAA,
+            g: 0xBB,
+            b: 0xCC,
+            a: 255
+        })
+    );
+    assert_eq!(get_bg(3), None);
+}
+
 #[test]
 fn test_rgb_rgba_comma_syntax() {
     let css = r#"
@@ -193,6 +240,46 @@ fn test_rgb_rgba_comma_syntax() {
     );
 }
 
+#[test]
+fn test_rgb_and_rgba_are_aliases() {
+    let css = r#"
+        .a { background-color: rgb(255, 0, 0, 0.5); }
+        .b { background-color: rgba(0, 0, 255); }
+        .c { background-color: rgba(0 0 255); }
+        .mixed { background-color: rgb(255, 0%, 0); }
+        .none { background-color: rgb(none, 0, 0); }
+    "#;
+
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    let get_bg = |idx: usize| -> Option<Rgba> {
+        stylesheet.rules[idx]
+            .declarations
+            .iter()
+            .find_map(|d| d.background_color)
+    };
+
+    assert_eq!(
+        get_bg(0),
+        Some(Rgba {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 128
+        })
+    );
+    let blue = Rgba {
+        r: 0,
+        g: 0,
+        b: 255,
+        a: 255,
+    };
+    assert_eq!(get_bg(1), Some(blue));
+    assert_eq!(get_bg(2), Some(blue));
+    // The legacy syntax neither mixes numbers with percentages nor allows `none`
+    assert_eq!(get_bg(3), None);
+    assert_eq!(get_bg(4), None);
+}
+
 #[test]
 fn test_rgb_rgba_modern_syntax() {
     let css = r#"
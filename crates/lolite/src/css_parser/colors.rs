@@ -22,6 +22,59 @@ impl StyleDeclarationParser {
         }
     }
 
+    fn parse_rgb_color<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Rgba, ParseError<'i, ()>> {
+        // rgb() and rgba() are aliases, and take an optional alpha in both syntaxes.
+        // See CSS Color 4 §5.1.
+        let start = input.state();
+        let percentages = matches!(input.next(), Ok(Token::Percentage { .. }));
+        input.reset(&start);
+
+        // Legacy: rgb(<c>, <c>, <c>[, <alpha>]?), all numbers or all percentages
+        let first = input.try_parse(|i| {
+            let r = Self::parse_legacy_rgb_channel(i, percentages)?;
+            i.expect_comma()?;
+            Ok::<_, ParseError<'i, ()>>(r)
+        });
+        if let Ok(r) = first {
+            let g = Self::parse_legacy_rgb_channel(input, percentages)?;
+            input.expect_comma()?;
+            let b = Self::parse_legacy_rgb_channel(input, percentages)?;
+            let a = if input.try_parse(|i| i.expect_comma()).is_ok() {
+                self.parse_alpha_channel(input)?
+            } else {
+                255
+            };
+            return Ok(Rgba { r, g, b, a });
+        }
+
+        // Modern: rgb(<c> <c> <c> [ / <alpha> ]?)
+        let r = self.parse_rgb_channel_or_none(input)?;
+        let g = self.parse_rgb_channel_or_none(input)?;
+        let b = self.parse_rgb_channel_or_none(input)?;
+        let a = if input.try_parse(|i| i.expect_delim('/')).is_ok() {
+            self.parse_alpha_value_u8(input)?
+        } else {
+            255
+        };
+        Ok(Rgba { r, g, b, a })
+    }
+
+    fn parse_legacy_rgb_channel<'i, 't>(
+        input: &mut Parser<'i, 't>,
+        percentage: bool,
+    ) -> Result<u8, ParseError<'i, ()>> {
+        match input.next()? {
+            Token::Number { value, .. } if !percentage => Ok(Self::clamp_u8(*value)),
+            Token::Percentage { unit_value, .. } if percentage => {
+                Ok(Self::clamp_u8(*unit_value * 255.0))
+            }
+            _ => Err(input.new_error_for_next_token()),
+        }
+    }
+
     fn parse_rgb_channel_or_none<'i, 't>(
         &mut self,
         input: &mut Parser<'i, 't>,
@@ -228,55 +281,8 @@ impl StyleDeclarationParser {
                 .ok_or_else(|| input.new_error_for_next_token()),
             Token::Function(name) => {
                 let func = name.as_ref();
-                if func.eq_ignore_ascii_case("rgb") {
-                    input.parse_nested_block(|input| {
-                        // Legacy: rgb(<c>, <c>, <c>)
-                        // Modern: rgb(<c> <c> <c> [ / <alpha> ]?)
-                        let r = self.parse_rgb_channel_or_none(input)?;
-
-                        if input.try_parse(|i| i.expect_comma()).is_ok() {
-                            let g = self.parse_rgb_channel(input)?;
-                            input.expect_comma()?;
-                            let b = self.parse_rgb_channel(input)?;
-                            return Ok(Rgba { r, g, b, a: 255 });
-                        }
-
-                        let g = self.parse_rgb_channel_or_none(input)?;
-                        let b = self.parse_rgb_channel_or_none(input)?;
-                        let a = if input.try_parse(|i| i.expect_delim('/')).is_ok() {
-                            self.parse_alpha_value_u8(input)?
-                        } else {
-                            255
-                        };
-
-                        Ok(Rgba { r, g, b, a })
-                    })
-                } else if func.eq_ignore_ascii_case("rgba") {
-                    input.parse_nested_block(|input| {
-                        // Legacy: rgba(<c>, <c>, <c>, <alpha>)
-                        // Modern: rgba(<c> <c> <c> [ / <alpha> ]?)
-                        // (Modern syntax is identical to rgb(); keep `rgba()` accepted for web compatibility.)
-                        let r = self.parse_rgb_channel_or_none(input)?;
-
-                        if input.try_parse(|i| i.expect_comma()).is_ok() {
-                            let g = self.parse_rgb_channel(input)?;
-                            input.expect_comma()?;
-                            let b = self.parse_rgb_channel(input)?;
-                            input.expect_comma()?;
-                            let a = self.parse_alpha_channel(input)?;
-                            return Ok(Rgba { r, g, b, a });
-                        }
-
-                        let g = self.parse_rgb_channel_or_none(input)?;
-                        let b = self.parse_rgb_channel_or_none(input)?;
-                        let a = if input.try_parse(|i| i.expect_delim('/')).is_ok() {
-                            self.parse_alpha_value_u8(input)?
-                        } else {
-                            255
-                        };
-
-                        Ok(Rgba { r, g, b, a })
-                    })
+                if func.eq_ignore_ascii_case("rgb") || func.eq_ignore_ascii_case("rgba") {
+                    input.parse_nested_block(|input| self.parse_rgb_color(input))
                 } else if func.eq_ignore_ascii_case("hsl") || func.eq_ignore_ascii_case("hsla") {
                     input.parse_nested_block(|input| self.parse_hsl_color(input))
                 } else if func.eq_ignore_ascii_case("hwb") {
@@ -297,33 +303,29 @@ impl StyleDeclarationParser {
 /// Parse a hex color string into Rgba
 fn parse_hex_color(hex: &str) -> Result<Rgba, &'static str> {
     let hex = hex.trim_start_matches('#');
-
-    match hex.len() {
-        3 => {
-            // #rgb -> #rrggbb
-            let r =
-                u8::from_str_radix(&hex[0..1].repeat(2), 16).map_err(|_| "Invalid hex digit")?;
-            let g =
-                u8::from_str_radix(&hex[1..2].repeat(2), 16).map_err(|_| "Invalid hex digit")?;
-            let b =
-                u8::from_str_radix(&hex[2..3].repeat(2), 16).map_err(|_| "Invalid hex digit")?;
-            Ok(Rgba { r, g, b, a: 255 })
-        }
-        6 => {
-            // #rrggbb
-            let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| "Invalid hex digit")?;
-            let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| "Invalid hex digit")?;
-            let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| "Invalid hex digit")?;
-            Ok(Rgba { r, g, b, a: 255 })
-        }
-        8 => {
-            // #rrggbbaa
-            let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| "Invalid hex digit")?;
-            let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| "Invalid hex digit")?;
-            let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| "Invalid hex digit")?;
-            let a = u8::from_str_radix(&hex[6..8], 16).map_err(|_| "Invalid hex digit")?;
-            Ok(Rgba { r, g, b, a })
-        }
-        _ => Err("Invalid hex color length"),
+    if !hex.is_ascii() {
+        return Err("Invalid hex digit");
     }
+
+    // #rgb and #rgba repeat each digit, #rrggbb and #rrggbbaa spell both out
+    let digits = match hex.len() {
+        3 | 4 => 1,
+        6 | 8 => 2,
+        _ => return Err("Invalid hex color length"),
+    };
+    let channel = |index: usize| -> Result<u8, &'static str> {
+        let digit = &hex[index * digits..(index + 1) * digits];
+        u8::from_str_radix(&digit.repeat(3 - digits), 16).map_err(|_| "Invalid hex digit")
+    };
+    let a = if hex.len() == 4 * digits {
+        channel(3)?
+    } else {
+        255
+    };
+    Ok(Rgba {
+        r: channel(0)?,
+        g: channel(1)?,
+        b: channel(2)?,
+        a,
+    })
 }
@@ -1,5 +1,8 @@
-use crate::css_parser::parse_css;
-use crate::style::{Display, Selector};
+use crate::css_parser::{audit_stylesheet, parse_css, parse_selector};
+use crate::style::{
+    AttributeMatch, Content, ContentItem, Display, Length, Nth, Overflow, Position, PseudoClass,
+    PseudoElement, Selector, SourceSpan, TextDirection,
+};
 
 #[test]
 fn test_parse_simple_css_document() {
@@ -42,8 +45,8 @@ fn test_parse_simple_css_document() {
     // Test first rule (.container)
     let container_rule = &stylesheet.rules[0];
     assert_eq!(
-        container_rule.selector,
-        Selector::Class("container".to_string())
+        container_rule.selectors,
+        vec![Selector::Class("container".to_string())]
     );
     assert!(!container_rule.declarations.is_empty());
 
@@ -53,8 +56,8 @@ fn test_parse_simple_css_document() {
     let mut found_width = false;
 
     for declaration in &container_rule.declarations {
-        match declaration.display {
-            Display::Flex => found_display = true,
+        if declaration.display == Some(Display::Flex) {
+            found_display = true;
         }
         if declaration.background_color.is_some() {
             found_background = true;
@@ -70,12 +73,15 @@ fn test_parse_simple_css_document() {
 
     // Test second rule (.box)
     let box_rule = &stylesheet.rules[1];
-    assert_eq!(box_rule.selector, Selector::Class("box".to_string()));
+    assert_eq!(box_rule.selectors, vec![Selector::Class("box".to_string())]);
     assert!(!box_rule.declarations.is_empty());
 
     // Test third rule (button)
     let button_rule = &stylesheet.rules[2];
-    assert_eq!(button_rule.selector, Selector::Tag("button".to_string()));
+    assert_eq!(
+        button_rule.selectors,
+        vec![Selector::Tag("button".to_string())]
+    );
     assert!(!button_rule.declarations.is_empty());
 }
 
@@ -96,7 +102,10 @@ fn test_parse_flex_properties() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("flex-container".to_string()));
+    assert_eq!(
+        rule.selectors,
+        vec![Selector::Class("flex-container".to_string())]
+    );
 
     // Verify we can parse all the flex properties
     assert!(!rule.declarations.is_empty());
@@ -152,16 +161,16 @@ fn test_complex_selectors() {
     assert_eq!(stylesheet.rules.len(), 3);
 
     assert_eq!(
-        stylesheet.rules[0].selector,
-        Selector::Class("main-container".to_string())
+        stylesheet.rules[0].selectors,
+        vec![Selector::Class("main-container".to_string())]
     );
     assert_eq!(
-        stylesheet.rules[1].selector,
-        Selector::Tag("div".to_string())
+        stylesheet.rules[1].selectors,
+        vec![Selector::Tag("div".to_string())]
     );
     assert_eq!(
-        stylesheet.rules[2].selector,
-        Selector::Class("sidebar".to_string())
+        stylesheet.rules[2].selectors,
+        vec![Selector::Class("sidebar".to_string())]
     );
 }
 
@@ -185,7 +194,469 @@ fn test_single_rule_css() {
     let stylesheet = parse_css(css).expect("Failed to parse single rule CSS");
     assert_eq!(stylesheet.rules.len(), 1);
     assert_eq!(
-        stylesheet.rules[0].selector,
-        Selector::Class("single".to_string())
+        stylesheet.rules[0].selectors,
+        vec![Selector::Class("single".to_string())]
+    );
+}
+
+#[test]
+fn test_parse_selector() {
+    assert_eq!(
+        parse_selector(".item"),
+        Ok(vec![Selector::Class("item".to_string())])
+    );
+    assert_eq!(
+        parse_selector("div"),
+        Ok(vec![Selector::Tag("div".to_string())])
+    );
+    assert_eq!(parse_selector("*"), Ok(vec![Selector::Universal]));
+    assert_eq!(
+        parse_selector("#sidebar"),
+        Ok(vec![Selector::Id("sidebar".to_string())])
+    );
+    // Not a valid identifier
+    assert!(parse_selector("#1st").is_err());
+    assert!(parse_selector(".item >").is_err());
+    assert!(parse_selector(".item + .child").is_err());
+    assert!(parse_selector(".item,").is_err());
+    assert!(parse_selector("").is_err());
+}
+
+#[test]
+fn test_parse_combinators() {
+    let class = |name: &str| Box::new(Selector::Class(name.to_string()));
+    assert_eq!(
+        parse_selector(".panel .title"),
+        Ok(vec![Selector::Descendant(class("panel"), class("title"))])
+    );
+    assert_eq!(
+        parse_selector(".panel>.title"),
+        Ok(vec![Selector::Child(class("panel"), class("title"))])
+    );
+    // Chains nest to the left, so the rightmost compound is the subject
+    assert_eq!(
+        parse_selector(".a .b > .c"),
+        Ok(vec![Selector::Child(
+            Box::new(Selector::Descendant(class("a"), class("b"))),
+            class("c")
+        )])
+    );
+    assert_eq!(
+        parse_selector("button.primary#ok"),
+        Ok(vec![Selector::Compound(vec![
+            Selector::Tag("button".to_string()),
+            Selector::Class("primary".to_string()),
+            Selector::Id("ok".to_string()),
+        ])])
+    );
+    let stylesheet = parse_css(".list > li { width: 1px; }").unwrap();
+    assert_eq!(
+        stylesheet.rules[0].selectors,
+        vec![Selector::Child(
+            class("list"),
+            Box::new(Selector::Tag("li".to_string()))
+        )]
+    );
+}
+
+#[test]
+fn test_parse_selector_lists() {
+    assert_eq!(
+        parse_selector("h1, h2 , .title"),
+        Ok(vec![
+            Selector::Tag("h1".to_string()),
+            Selector::Tag("h2".to_string()),
+            Selector::Class("title".to_string()),
+        ])
+    );
+    let stylesheet = parse_css(".a, .b > .c { width: 1px; } .bad, + .d { width: 2px; }").unwrap();
+    // One invalid selector drops the whole rule
+    assert_eq!(stylesheet.rules.len(), 1);
+    assert_eq!(stylesheet.rules[0].selectors.len(), 2);
+}
+
+#[test]
+fn test_specificity() {
+    let specificity = |selector| parse_selector(selector).unwrap()[0].specificity();
+    assert_eq!(specificity("*"), (0, 0, 0));
+    assert_eq!(specificity("li"), (0, 0, 1));
+    assert_eq!(specificity("li.item[data-open]"), (0, 2, 1));
+    assert_eq!(specificity("#nav > ul li.active"), (1, 1, 2));
+}
+
+#[test]
+fn test_parse_important_declarations() {
+    let css = ".a { width: 1px !important; height: 2px; color: red ! IMPORTANT; --gap: 3px !important; margin: var(--gap) !important; }";
+    let stylesheet = parse_css(css).unwrap();
+    let rule = &stylesheet.rules[0];
+
+    assert_eq!(rule.declarations.len(), 1);
+    assert_eq!(rule.declarations[0].height, Some(Length::Px(2.0)));
+    let important = &rule.important_declarations;
+    assert_eq!(important.len(), 4);
+    assert_eq!(important[0].width, Some(Length::Px(1.0)));
+    assert!(important[1].color.is_some());
+    // The flag isn't part of raw values
+    assert_eq!(important[2].custom_properties.get("--gap"), Some("3px"));
+    assert_eq!(
+        important[3].var_declaration.as_ref().unwrap().value,
+        "var(--gap)"
+    );
+
+    // Any other flag drops the declaration
+    let css = ".a { width: 1px !default; }";
+    let stylesheet = parse_css(css).unwrap();
+    assert!(stylesheet.rules[0].declarations.is_empty());
+    assert!(stylesheet.rules[0].important_declarations.is_empty());
+    assert_eq!(audit_stylesheet(css).len(), 1);
+}
+
+#[test]
+fn test_parse_attribute_selectors() {
+    let attribute = |name: &str, matcher| Ok(vec![Selector::Attribute(name.to_string(), matcher)]);
+    assert_eq!(
+        parse_selector("[disabled]"),
+        attribute("disabled", AttributeMatch::Exists)
+    );
+    assert_eq!(
+        parse_selector("[data-state=open]"),
+        attribute("data-state", AttributeMatch::Equals("open".to_string()))
+    );
+    assert_eq!(
+        parse_selector("[ data-state = \"two words\" ]"),
+        attribute(
+            "data-state",
+            AttributeMatch::Equals("two words".to_string())
+        )
+    );
+    assert_eq!(
+        parse_selector("[data-flags~=wide]"),
+        attribute("data-flags", AttributeMatch::Includes("wide".to_string()))
+    );
+    assert_eq!(
+        parse_selector(".tab[aria-selected=true]"),
+        Ok(vec![Selector::Compound(vec![
+            Selector::Class("tab".to_string()),
+            Selector::Attribute(
+                "aria-selected".to_string(),
+                AttributeMatch::Equals("true".to_string())
+            ),
+        ])])
+    );
+    // Other operators aren't supported
+    assert!(parse_selector("[lang|=en]").is_err());
+    assert!(parse_selector("[href^=http]").is_err());
+    assert!(parse_selector("[=open]").is_err());
+}
+
+#[test]
+fn test_parse_pseudo_classes() {
+    assert_eq!(
+        parse_selector(".button:hover"),
+        Ok(vec![Selector::Compound(vec![
+            Selector::Class("button".to_string()),
+            Selector::PseudoClass(PseudoClass::Hover),
+        ])])
+    );
+    assert_eq!(
+        parse_selector(":ACTIVE, input:focus"),
+        Ok(vec![
+            Selector::PseudoClass(PseudoClass::Active),
+            Selector::Compound(vec![
+                Selector::Tag("input".to_string()),
+                Selector::PseudoClass(PseudoClass::Focus),
+            ]),
+        ])
+    );
+    assert_eq!(
+        parse_selector("li:hover").unwrap()[0].specificity(),
+        (0, 1, 1)
+    );
+    // Unknown pseudo-classes and stray whitespace invalidate the selector
+    assert!(parse_selector("a:visited").is_err());
+    assert!(parse_selector("a: hover").is_err());
+}
+
+#[test]
+fn test_parse_structural_pseudo_classes() {
+    assert_eq!(
+        parse_selector("li:first-child, li:LAST-CHILD"),
+        Ok(vec![
+            Selector::Compound(vec![
+                Selector::Tag("li".to_string()),
+                Selector::NthChild(Nth::FIRST),
+            ]),
+            Selector::Compound(vec![
+                Selector::Tag("li".to_string()),
+                Selector::NthLastChild(Nth::FIRST),
+            ]),
+        ])
+    );
+    let nth = |css: &str| match parse_selector(css).unwrap().remove(0) {
+        Selector::NthChild(nth) | Selector::NthLastChild(nth) => (nth.a, nth.b),
+        selector => panic!("unexpected selector {:?}", selector),
+    };
+    assert_eq!(nth(":nth-child(odd)"), (2, 1));
+    assert_eq!(nth(":nth-child(even)"), (2, 0));
+    assert_eq!(nth(":nth-child(3)"), (0, 3));
+    assert_eq!(nth(":nth-child( -n + 3 )"), (-1, 3));
+    assert_eq!(nth(":nth-last-child(2n+1)"), (2, 1));
+    assert_eq!(
+        parse_selector("tr:nth-child(2n)").unwrap()[0].specificity(),
+        (0, 1, 1)
+    );
+    assert!(parse_selector(":nth-child()").is_err());
+    assert!(parse_selector(":nth-child(2n 1)").is_err());
+    assert!(parse_selector(":nth-of-type(2)").is_err());
+}
+
+#[test]
+fn test_parse_layers() {
+    let css = r#"
+        @layer base, theme;
+        .a { width: 1px; }
+        @layer theme { .a { width: 2px; } }
+        @layer base { .a { width: 3px; } .b { height: 4px; } }
+        @layer overrides;
+    "#;
+    let stylesheet = parse_css(css).unwrap();
+
+    assert_eq!(stylesheet.layers, vec!["base", "theme", "overrides"]);
+    // Rules are kept in cascade order: by layer, then unlayered rules last
+    let layers: Vec<_> = stylesheet
+        .rules
+        .iter()
+        .map(|rule| rule.layer.as_deref())
+        .collect();
+    assert_eq!(
+        layers,
+        vec![Some("base"), Some("base"), Some("theme"), None]
+    );
+    assert_eq!(
+        stylesheet.rules[1].selectors,
+        vec![Selector::Class("b".to_string())]
+    );
+}
+
+#[test]
+fn test_unsupported_layers_are_reported() {
+    let diagnostics = audit_stylesheet(
+        "@layer { .a { width: 1px; } } @layer a { @layer b { .c { width: 1px; } } } @supports (gap: 1px) {}",
+    );
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.to_string()).collect();
+
+    assert!(messages.iter().any(|m| m.contains("anonymous layers")));
+    assert!(messages.iter().any(|m| m.contains("@layer b")));
+    assert!(messages.iter().any(|m| m.contains("@supports")));
+}
+
+#[test]
+fn test_parse_pseudo_elements() {
+    assert_eq!(
+        parse_selector("li.item::before, ::AFTER, p:after"),
+        Ok(vec![
+            Selector::Compound(vec![
+                Selector::Tag("li".to_string()),
+                Selector::Class("item".to_string()),
+                Selector::PseudoElement(PseudoElement::Before),
+            ]),
+            Selector::PseudoElement(PseudoElement::After),
+            Selector::Compound(vec![
+                Selector::Tag("p".to_string()),
+                Selector::PseudoElement(PseudoElement::After),
+            ]),
+        ])
+    );
+    let selector = parse_selector("ul > li::before").unwrap().remove(0);
+    assert_eq!(selector.pseudo_element(), Some(PseudoElement::Before));
+    assert_eq!(selector.specificity(), (0, 0, 3));
+
+    // A pseudo-element ends the selector
+    assert!(parse_selector("li::before span").is_err());
+    assert!(parse_selector("li::before.item").is_err());
+    assert!(parse_selector("li::before:hover").is_err());
+    assert!(parse_selector("li::marker").is_err());
+    assert!(parse_selector("li: :before").is_err());
+}
+
+#[test]
+fn test_parse_content() {
+    let css = r#"
+        .a::before { content: "(" attr(data-count) ")"; }
+        .b::after { content: none; }
+        .c::after { content: normal; }
+        .d::after { content: 3; }
+        .e::after { content: attr(a b); }
+    "#;
+    let stylesheet = parse_css(css).unwrap();
+    let content = |index: usize| {
+        stylesheet.rules[index]
+            .declarations
+            .iter()
+            .find_map(|d| d.content.clone())
+    };
+    assert_eq!(
+        content(0),
+        Some(Content::Items(vec![
+            ContentItem::String("(".to_string()),
+            ContentItem::Attr("data-count".to_string()),
+            ContentItem::String(")".to_string()),
+        ]))
+    );
+    assert_eq!(content(1), Some(Content::None));
+    assert_eq!(content(2), Some(Content::None));
+    assert_eq!(content(3), None);
+    assert_eq!(content(4), None);
+
+    let attributes = [("data-count".to_string(), "7".to_string())].into();
+    assert_eq!(
+        content(0).unwrap().text(&attributes).as_deref(),
+        Some("(7)")
+    );
+    assert_eq!(
+        content(0).unwrap().text(&Default::default()).as_deref(),
+        Some("()")
+    );
+    assert_eq!(Content::None.text(&attributes), None);
+}
+
+#[test]
+fn test_parse_size_limits() {
+    let css = r#"
+        .a { min-width: 10px; max-width: none; min-height: auto; max-height: 50%; }
+        .b { max-width: auto; min-width: -1px; max-height: -10%; }
+    "#;
+    let stylesheet = parse_css(css).unwrap();
+    let style = &stylesheet.rules[0].declarations;
+    assert_eq!(style[0].min_width, Some(Length::Px(10.0)));
+    assert_eq!(style[1].max_width, Some(Length::Auto));
+    assert_eq!(style[2].min_height, Some(Length::Auto));
+    assert_eq!(style[3].max_height, Some(Length::Percent(50.0)));
+
+    // Maximums can't be `auto`, and limits can't be negative
+    assert!(stylesheet.rules[1].declarations.is_empty());
+}
+
+#[test]
+fn test_parse_direction() {
+    let stylesheet = parse_css(".a { direction: rtl; } .b { direction: up; }").unwrap();
+    assert_eq!(
+        stylesheet.rules[0].declarations[0].direction,
+        Some(TextDirection::Rtl)
+    );
+    assert!(stylesheet.rules[1].declarations.is_empty());
+}
+
+#[test]
+fn test_rules_keep_source_spans() {
+    let css = ".a { color: red; }\n@media (min-width: 10px) {\n  .b {\n    width: 5px !important;\n  }\n}";
+    let stylesheet = parse_css(css).unwrap();
+
+    let a = stylesheet.rules[0].source.as_ref().unwrap();
+    assert_eq!(&css[a.span.start..a.span.end], ".a { color: red; ");
+    let (property, span) = &a.declarations[0];
+    assert_eq!(property, "color");
+    assert_eq!(&css[span.start..span.end], "color: red");
+    assert_eq!((span.line, span.column), (1, 6));
+
+    // Nested rules are located in the whole source
+    let b = stylesheet.rules[1].source.as_ref().unwrap();
+    assert!(b.declarations.is_empty());
+    assert_eq!(
+        b.important_declarations,
+        vec![(
+            "width".to_string(),
+            SourceSpan {
+                start: 57,
+                end: 78,
+                line: 4,
+                column: 5,
+            }
+        )]
+    );
+    assert_eq!((b.span.line, b.span.column), (3, 3));
+}
+
+#[test]
+fn test_parse_overflow() {
+    let css = r#"
+        .a { overflow: hidden; overflow: scroll auto; overflow-x: visible; overflow-y: auto; }
+        .b { overflow: clip; overflow-x: none; overflow: auto auto auto; }
+    "#;
+    let stylesheet = parse_css(css).unwrap();
+    let style = &stylesheet.rules[0].declarations;
+    assert_eq!(
+        (style[0].overflow_x, style[0].overflow_y),
+        (Some(Overflow::Hidden), Some(Overflow::Hidden))
+    );
+    assert_eq!(
+        (style[1].overflow_x, style[1].overflow_y),
+        (Some(Overflow::Scroll), Some(Overflow::Auto))
+    );
+    assert_eq!(style[2].overflow_x, Some(Overflow::Visible));
+    assert_eq!(style[3].overflow_y, Some(Overflow::Auto));
+    assert!(stylesheet.rules[1].declarations.is_empty());
+}
+
+#[test]
+fn test_parse_position_and_insets() {
+    let css = r#"
+        .a { position: absolute; inset: 1px 2px; left: -50%; bottom: auto; }
+        .b { position: sticky; inset: 1px 2px 3px 4px 5px; top: none; }
+    "#;
+    let stylesheet = parse_css(css).unwrap();
+    let style = &stylesheet.rules[0].declarations;
+    assert_eq!(style[0].position, Some(Position::Absolute));
+    assert_eq!(style[1].inset.top, Some(Length::Px(1.0)));
+    assert_eq!(style[1].inset.right, Some(Length::Px(2.0)));
+    assert_eq!(style[1].inset.bottom, Some(Length::Px(1.0)));
+    assert_eq!(style[1].inset.left, Some(Length::Px(2.0)));
+    assert_eq!(style[2].inset.left, Some(Length::Percent(-50.0)));
+    assert_eq!(style[3].inset.bottom, Some(Length::Auto));
+    assert!(stylesheet.rules[1].declarations.is_empty());
+}
+
+#[test]
+fn test_parse_flex_shorthand() {
+    let css = r#"
+        .a {
+            flex: 2;
+            flex: 2 3;
+            flex: 2 3 10px;
+            flex: 10px 2;
+            flex: 50%;
+            flex: auto;
+            flex: none;
+            flex: initial;
+            flex: 0 auto;
+        }
+        .b { flex: -1; flex: 1 2 3px 4; flex: none 1; flex: 1px 2px; flex: ; }
+    "#;
+    let stylesheet = parse_css(css).unwrap();
+    let flex: Vec<_> = stylesheet.rules[0]
+        .declarations
+        .iter()
+        .map(|style| {
+            (
+                style.flex_grow.unwrap(),
+                style.flex_shrink.unwrap(),
+                style.flex_basis.unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        flex,
+        vec![
+            (2.0, 1.0, Length::Px(0.0)),
+            (2.0, 3.0, Length::Px(0.0)),
+            (2.0, 3.0, Length::Px(10.0)),
+            (2.0, 1.0, Length::Px(10.0)),
+            (1.0, 1.0, Length::Percent(50.0)),
+            (1.0, 1.0, Length::Auto),
+            (0.0, 0.0, Length::Auto),
+            (0.0, 1.0, Length::Auto),
+            (0.0, 1.0, Length::Auto),
+        ]
     );
+    assert!(stylesheet.rules[1].declarations.is_empty());
 }
@@ -0,0 +1,207 @@
+use crate::style::{
+    AlignItems, AlignSelf, BackgroundImage, BorderStyle, SourceSpan, Style, StyleImport,
+};
+use cssparser::SourceLocation;
+use std::fmt;
+use std::ops::Range;
+
+/// Something in a stylesheet that the engine ignores or only partly implements,
+/// reported by [`crate::audit_stylesheet`] and [`crate::Engine::on_css_diagnostic`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CssDiagnostic {
+    /// 1-based line of the offending rule or declaration.
+    pub line: u32,
+    /// 1-based column of the offending rule or declaration.
+    pub column: u32,
+    /// The source of the offending rule, declaration or query.
+    pub span: SourceSpan,
+    pub kind: CssDiagnosticKind,
+}
+
+/// How much of what was written a [`CssDiagnostic`] costs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CssSeverity {
+    /// Parsed, but only partly honored.
+    Warning,
+    /// Dropped entirely.
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CssDiagnosticKind {
+    /// A property the engine doesn't know; the declaration is dropped.
+    UnknownProperty(String),
+    /// A known property whose value couldn't be parsed; the declaration is dropped.
+    InvalidValue { property: String, value: String },
+    /// A rule that couldn't be parsed, usually because of its selector; it is dropped.
+    InvalidRule(String),
+    /// An `@import` whose URL the import resolver couldn't load, or that imports a
+    /// stylesheet already being imported; it is dropped.
+    UnresolvedImport(String),
+    /// Parsed, but not implemented yet; `message` says how it is treated instead.
+    Unsupported {
+        source: String,
+        message: &'static str,
+    },
+}
+
+impl CssDiagnostic {
+    /// A diagnostic about the bytes `range` of the stylesheet. The line and column of
+    /// the span are filled in by [`CssDiagnostic::locate`] once parsing is done.
+    pub(crate) fn new(
+        location: SourceLocation,
+        range: Range<usize>,
+        kind: CssDiagnosticKind,
+    ) -> Self {
+        Self {
+            line: location.line + 1,
+            column: location.column,
+            span: SourceSpan {
+                start: range.start,
+                end: range.end,
+                line: 0,
+                column: 0,
+            },
+            kind,
+        }
+    }
+
+    /// A diagnostic about an `@import` rule that couldn't be resolved.
+    pub(crate) fn unresolved_import(import: &StyleImport) -> Self {
+        Self {
+            line: import.span.line,
+            column: import.span.column,
+            span: import.span,
+            kind: CssDiagnosticKind::UnresolvedImport(import.url.clone()),
+        }
+    }
+
+    /// Classify a declaration the parser rejected, given its source text, which ends at
+    /// byte `end` of the stylesheet.
+    pub(crate) fn rejected_declaration(
+        location: SourceLocation,
+        unknown_property: bool,
+        source: &str,
+        end: usize,
+    ) -> Self {
+        let (property, value) = source.split_once(':').unwrap_or((source, ""));
+        let property = property.trim().to_string();
+        let kind = if unknown_property {
+            CssDiagnosticKind::UnknownProperty(property)
+        } else {
+            CssDiagnosticKind::InvalidValue {
+                property,
+                value: value.trim().trim_end_matches(';').trim_end().to_string(),
+            }
+        };
+        Self::new(location, end - source.len()..end, kind)
+    }
+
+    /// Set the line and column of the span from the stylesheet's source, counted like
+    /// those of [`SourceLocation`].
+    pub(crate) fn locate(&mut self, css: &str) {
+        let before = &css[..self.span.start];
+        let line_start = before
+            .rfind(['\n', '\r', '\x0C'])
+            .map_or(0, |newline| newline + 1);
+        self.span.line = before[..line_start]
+            .replace("\r\n", "\n")
+            .matches(['\n', '\r', '\x0C'])
+            .count() as u32
+            + 1;
+        self.span.column = before[line_start..]
+            .chars()
+            .map(char::len_utf16)
+            .sum::<usize>() as u32
+            + 1;
+    }
+
+    pub fn severity(&self) -> CssSeverity {
+        match self.kind {
+            CssDiagnosticKind::Unsupported { .. } => CssSeverity::Warning,
+            _ => CssSeverity::Error,
+        }
+    }
+
+    /// The property of a dropped declaration.
+    pub fn property(&self) -> Option<&str> {
+        match &self.kind {
+            CssDiagnosticKind::UnknownProperty(property)
+            | CssDiagnosticKind::InvalidValue { property, .. } => Some(property),
+            _ => None,
+        }
+    }
+
+    /// The value of a declaration dropped because it couldn't be parsed.
+    pub fn value(&self) -> Option<&str> {
+        match &self.kind {
+            CssDiagnosticKind::InvalidValue { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CssSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CssSeverity::Warning => "warning",
+            CssSeverity::Error => "error",
+        })
+    }
+}
+
+impl fmt::Display for CssDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            CssDiagnosticKind::UnknownProperty(property) => {
+                write!(f, "unknown property `{}`", property)
+            }
+            CssDiagnosticKind::InvalidValue { property, value } => {
+                write!(f, "invalid value `{}` for `{}`", value, property)
+            }
+            CssDiagnosticKind::InvalidRule(rule) => write!(f, "invalid rule `{}`", rule),
+            CssDiagnosticKind::UnresolvedImport(url) => {
+                write!(f, "couldn't resolve `@import \"{}\"`", url)
+            }
+            CssDiagnosticKind::Unsupported { source, message } => {
+                write!(f, "`{}` is not supported: {}", source, message)
+            }
+        }
+    }
+}
+
+/// Why a successfully parsed declaration won't render as written, if it won't.
+pub(crate) fn unsupported_declaration(style: &Style) -> Option<&'static str> {
+    if matches!(style.align_items, Some(AlignItems::Baseline))
+        || matches!(style.align_self, Some(AlignSelf::Baseline))
+    {
+        return Some("baseline alignment is treated as flex-start");
+    }
+    let border_styles = [
+        style.border_style.top,
+        style.border_style.right,
+        style.border_style.bottom,
+        style.border_style.left,
+    ];
+    if border_styles.into_iter().flatten().any(|border_style| {
+        !matches!(
+            border_style,
+            BorderStyle::None | BorderStyle::Hidden | BorderStyle::Solid
+        )
+    }) {
+        return Some("borders are always drawn solid");
+    }
+    if style
+        .background_image
+        .iter()
+        .flatten()
+        .any(|image| *image != BackgroundImage::None)
+    {
+        return Some("background images aren't painted yet");
+    }
+    if style.contain.is_some_and(|contain| contain.layout) {
+        return Some("layout containment has no effect yet");
+    }
+    None
+}
@@ -0,0 +1,103 @@
+use crate::css_parser::{
+    audit_stylesheet, parse_css, CssDiagnostic, CssDiagnosticKind, CssSeverity,
+};
+
+fn kinds(diagnostics: Vec<CssDiagnostic>) -> Vec<CssDiagnosticKind> {
+    diagnostics.into_iter().map(|d| d.kind).collect()
+}
+
+#[test]
+fn test_audit_clean_stylesheet() {
+    let css = ".box { display: flex; width: 10px; border: 1px solid red; }";
+    assert!(audit_stylesheet(css).is_empty());
+}
+
+#[test]
+fn test_audit_unknown_property_and_invalid_value() {
+    let css = ".box {\n  transform: rotate(45deg);\n  width: banana;\n}";
+    let diagnostics = audit_stylesheet(css);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].line, 2);
+    assert_eq!(diagnostics[1].line, 3);
+    assert_eq!(
+        kinds(diagnostics),
+        vec![
+            CssDiagnosticKind::UnknownProperty("transform".to_string()),
+            CssDiagnosticKind::InvalidValue {
+                property: "width".to_string(),
+                value: "banana".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_audit_reports_recognized_but_unimplemented() {
+    let css = r#"
+        .row { align-items: baseline; }
+        .box { border-style: dashed; contain: layout; }
+    "#;
+    let diagnostics = audit_stylesheet(css);
+    let messages: Vec<_> = diagnostics
+        .iter()
+        .map(|d| match &d.kind {
+            CssDiagnosticKind::Unsupported { source, .. } => source.as_str(),
+            other => panic!("unexpected diagnostic {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "align-items: baseline",
+            "border-style: dashed",
+            "contain: layout",
+        ]
+    );
+
+    // Unsupported declarations still apply as far as the engine can render them.
+    let sheet = parse_css(css).unwrap();
+    assert_eq!(sheet.rules[0].declarations.len(), 1);
+    assert_eq!(sheet.rules[1].declarations.len(), 2);
+}
+
+#[test]
+fn test_audit_invalid_rule() {
+    let diagnostics = audit_stylesheet("#id + .x { width: 1px; }\n.ok { width: 2px; }");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        &diagnostics[0].kind,
+        CssDiagnosticKind::InvalidRule(rule) if rule == "#id + .x"
+    ));
+    assert!(diagnostics[0].to_string().starts_with("1:"));
+}
+
+#[test]
+fn test_diagnostic_severity_and_span() {
+    let css = ".a { align-items: baseline; }\n.b {\n  width: banana;\n  colour: red;\n}\n#x + .y { width: 1px; }";
+    let diagnostics = audit_stylesheet(css);
+    let spans: Vec<_> = diagnostics
+        .iter()
+        .map(|d| {
+            (
+                d.severity(),
+                &css[d.span.start..d.span.end],
+                d.span.line,
+                d.span.column,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        spans,
+        vec![
+            (CssSeverity::Warning, "align-items: baseline", 1, 6),
+            (CssSeverity::Error, "width: banana;", 3, 3),
+            (CssSeverity::Error, "colour: red;", 4, 3),
+            (CssSeverity::Error, "#x + .y {", 6, 1),
+        ]
+    );
+    assert_eq!(diagnostics[1].property(), Some("width"));
+    assert_eq!(diagnostics[1].value(), Some("banana"));
+    assert_eq!(diagnostics[2].property(), Some("colour"));
+    assert_eq!(diagnostics[2].value(), None);
+}
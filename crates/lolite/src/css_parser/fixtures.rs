@@ -0,0 +1,7 @@
+use super::parse_css;
+use crate::style::Style;
+
+/// Declarations of the first rule in `css`.
+pub fn declarations(css: &str) -> Vec<Style> {
+    parse_css(css).unwrap().rules.remove(0).declarations
+}
@@ -0,0 +1,81 @@
+use crate::css_parser::{audit_stylesheet, parse_css, CssDiagnosticKind};
+use crate::style::{FontSource, FontStyle};
+
+#[test]
+fn test_parse_font_face() {
+    let css = r#"@font-face {
+    font-family: "Fira Code";
+    src: local(Fira Code), url("fira.woff2") format("woff2"), url(fira.ttf);
+    font-weight: bold;
+    font-style: italic;
+}
+.a { font-family: "Fira Code"; }"#;
+    let stylesheet = parse_css(css).unwrap();
+    assert_eq!(stylesheet.rules.len(), 1);
+    assert_eq!(stylesheet.font_faces.len(), 1);
+
+    let face = &stylesheet.font_faces[0];
+    assert_eq!(face.family, "Fira Code");
+    assert_eq!(
+        face.src,
+        vec![
+            FontSource::Local("Fira Code".to_string()),
+            FontSource::Url {
+                url: "fira.woff2".to_string(),
+                format: Some("woff2".to_string()),
+            },
+            FontSource::Url {
+                url: "fira.ttf".to_string(),
+                format: None,
+            },
+        ]
+    );
+    assert_eq!(face.weight, 700);
+    assert_eq!(face.style, FontStyle::Italic);
+    assert_eq!((face.span.line, face.span.column), (1, 1));
+    assert!(css[face.span.start..face.span.end].ends_with('}'));
+}
+
+#[test]
+fn test_font_face_defaults() {
+    let stylesheet = parse_css("@font-face { font-family: Inter; src: url(inter.ttf); }").unwrap();
+    let face = &stylesheet.font_faces[0];
+    assert_eq!(face.family, "Inter");
+    assert_eq!(face.weight, 400);
+    assert_eq!(face.style, FontStyle::Normal);
+}
+
+#[test]
+fn test_reject_invalid_font_faces() {
+    let css = r#"
+        @font-face { src: url(a.ttf); }
+        @font-face { font-family: A; }
+        @font-face { font-family: A, B; src: url(a.ttf); }
+        @font-face foo { font-family: A; src: url(a.ttf); }
+        @media (min-width: 1px) { @font-face { font-family: A; src: url(a.ttf); } }
+    "#;
+    assert!(parse_css(css).unwrap().font_faces.is_empty());
+    assert_eq!(audit_stylesheet(css).len(), 6);
+}
+
+#[test]
+fn test_invalid_font_face_descriptors_are_dropped() {
+    let css = "@font-face {
+        font-family: A;
+        src: url(a.ttf);
+        font-weight: heavy;
+        unicode-range: U+0-7F;
+    }";
+    assert_eq!(parse_css(css).unwrap().font_faces[0].weight, 400);
+
+    let diagnostics = audit_stylesheet(css);
+    assert_eq!(diagnostics.len(), 2);
+    assert!(matches!(
+        &diagnostics[0].kind,
+        CssDiagnosticKind::InvalidValue { property, .. } if property == "font-weight"
+    ));
+    assert_eq!(
+        diagnostics[1].kind,
+        CssDiagnosticKind::UnknownProperty("unicode-range".to_string())
+    );
+}
@@ -0,0 +1,148 @@
+use super::diagnostics::CssDiagnostic;
+use super::fonts::{parse_font_family, parse_font_style, parse_font_weight};
+use super::parser::{source_span, CssParser, CssRule};
+use crate::style::{FontFace, FontSource, FontStyle};
+use cssparser::{
+    match_ignore_ascii_case, AtRuleParser, CowRcStr, DeclarationParser, ParseError, ParseErrorKind,
+    Parser, ParserState, QualifiedRuleParser, RuleBodyItemParser, RuleBodyParser, Token,
+};
+
+impl CssParser {
+    /// Parse the descriptors of an `@font-face` block. Unknown descriptors and invalid
+    /// values are dropped, and so is the whole rule if it lacks a family or a source.
+    pub(super) fn parse_font_face_block<'i>(
+        &mut self,
+        start: &ParserState,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<CssRule, ParseError<'i, ()>> {
+        let mut descriptors = FontFaceDescriptors::default();
+        let mut parser = RuleBodyParser::new(input, &mut descriptors);
+        while let Some(item) = parser.next() {
+            if let Err((err, source)) = item {
+                let unknown_descriptor = matches!(err.kind, ParseErrorKind::Custom(()));
+                self.diagnostics.push(CssDiagnostic::rejected_declaration(
+                    err.location,
+                    unknown_descriptor,
+                    source,
+                    parser.input.position().byte_index(),
+                ));
+            }
+        }
+        let (Some(family), Some(src)) = (descriptors.family, descriptors.src) else {
+            return Err(start.source_location().new_custom_error(()));
+        };
+        Ok(CssRule::FontFace(FontFace {
+            family,
+            src,
+            weight: descriptors.weight.unwrap_or(400),
+            style: descriptors.style.unwrap_or_default(),
+            span: source_span(start, input),
+        }))
+    }
+}
+
+/// Parser for the descriptors of an `@font-face` block, keeping the last valid value of
+/// each.
+#[derive(Default)]
+struct FontFaceDescriptors {
+    family: Option<String>,
+    src: Option<Vec<FontSource>>,
+    weight: Option<u16>,
+    style: Option<FontStyle>,
+}
+
+impl<'i> DeclarationParser<'i> for FontFaceDescriptors {
+    type Declaration = ();
+    type Error = ();
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+        _declaration_start: &ParserState,
+    ) -> Result<(), ParseError<'i, ()>> {
+        match_ignore_ascii_case! { &name,
+            "font-family" => {
+                self.family = Some(parse_single_family(input)?);
+            },
+            "src" => {
+                self.src = Some(input.parse_comma_separated(parse_font_source)?);
+            },
+            "font-weight" => {
+                self.weight = Some(parse_font_weight(input)?);
+            },
+            "font-style" => {
+                self.style = Some(parse_font_style(input)?);
+            },
+            _ => return Err(input.new_custom_error(())),
+        }
+        Ok(())
+    }
+}
+
+impl<'i> AtRuleParser<'i> for FontFaceDescriptors {
+    type Prelude = ();
+    type AtRule = ();
+    type Error = ();
+}
+
+impl<'i> QualifiedRuleParser<'i> for FontFaceDescriptors {
+    type Prelude = ();
+    type QualifiedRule = ();
+    type Error = ();
+}
+
+impl<'i> RuleBodyItemParser<'i, (), ()> for FontFaceDescriptors {
+    fn parse_qualified(&self) -> bool {
+        false
+    }
+
+    fn parse_declarations(&self) -> bool {
+        true
+    }
+}
+
+/// Parse a `font-family` naming a single family, as a face defines one.
+fn parse_single_family<'i>(input: &mut Parser<'i, '_>) -> Result<String, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let family = parse_font_family(input)?;
+    if family.contains(',') {
+        return Err(location.new_unexpected_token_error(Token::Comma));
+    }
+    Ok(family)
+}
+
+/// Parse an entry of `src`: `url(...)` followed by an optional `format(...)`, or
+/// `local(...)` with a quoted or unquoted font name.
+fn parse_font_source<'i>(input: &mut Parser<'i, '_>) -> Result<FontSource, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let url = match input.next()?.clone() {
+        Token::UnquotedUrl(url) => url.to_string(),
+        Token::Function(name) if name.eq_ignore_ascii_case("url") => {
+            input.parse_nested_block(|input| -> Result<_, ParseError<'i, ()>> {
+                Ok(input.expect_string()?.to_string())
+            })?
+        }
+        Token::Function(name) if name.eq_ignore_ascii_case("local") => {
+            let name = input.parse_nested_block(parse_single_family)?;
+            return Ok(FontSource::Local(name));
+        }
+        token => return Err(location.new_unexpected_token_error(token)),
+    };
+    let format = input
+        .try_parse(|input| {
+            input.expect_function_matching("format")?;
+            input.parse_nested_block(parse_font_format)
+        })
+        .ok();
+    Ok(FontSource::Url { url, format })
+}
+
+/// The format hint inside `format()`, a string or a keyword such as `woff2`.
+fn parse_font_format<'i>(input: &mut Parser<'i, '_>) -> Result<String, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    match input.next()?.clone() {
+        Token::QuotedString(format) | Token::Ident(format) => Ok(format.to_string()),
+        token => Err(location.new_unexpected_token_error(token)),
+    }
+}
@@ -0,0 +1,83 @@
+use crate::css_parser::fixtures::declarations;
+use crate::style::{FontStyle, Length, LineHeight};
+
+#[test]
+fn test_parse_font_shorthand() {
+    let styles = declarations(
+        r#".a {
+            font: italic bold 14px/1.5 "Helvetica Neue", Noto Sans, sans-serif;
+            font: 12px serif;
+            font: normal 600 2em/150% monospace;
+        }"#,
+    );
+    assert_eq!(styles.len(), 3);
+
+    assert_eq!(styles[0].font_style, Some(FontStyle::Italic));
+    assert_eq!(styles[0].font_weight, Some(700));
+    assert_eq!(styles[0].font_size, Some(Length::Px(14.0)));
+    assert_eq!(styles[0].line_height, Some(LineHeight::Number(1.5)));
+    assert_eq!(
+        styles[0].font_family.as_deref(),
+        Some("Helvetica Neue, Noto Sans, sans-serif")
+    );
+
+    // Omitted longhands are reset to their initial values
+    assert_eq!(styles[1].font_style, Some(FontStyle::Normal));
+    assert_eq!(styles[1].font_weight, Some(400));
+    assert_eq!(styles[1].line_height, Some(LineHeight::Normal));
+    assert_eq!(styles[1].font_family.as_deref(), Some("serif"));
+
+    assert_eq!(styles[2].font_weight, Some(600));
+    assert_eq!(styles[2].font_size, Some(Length::Em(2.0)));
+    assert_eq!(
+        styles[2].line_height,
+        Some(LineHeight::Length(Length::Percent(150.0)))
+    );
+}
+
+#[test]
+fn test_parse_font_longhands() {
+    let styles = declarations(
+        ".a {
+            font-family: 'Fira Code', monospace;
+            font-weight: bold;
+            font-weight: 350;
+            font-style: oblique;
+            line-height: 20px;
+            line-height: normal;
+        }",
+    );
+    assert_eq!(styles.len(), 6);
+    assert_eq!(
+        styles[0].font_family.as_deref(),
+        Some("Fira Code, monospace")
+    );
+    assert_eq!(styles[1].font_weight, Some(700));
+    assert_eq!(styles[2].font_weight, Some(350));
+    assert_eq!(styles[3].font_style, Some(FontStyle::Oblique));
+    assert_eq!(
+        styles[4].line_height,
+        Some(LineHeight::Length(Length::Px(20.0)))
+    );
+    assert_eq!(styles[5].line_height, Some(LineHeight::Normal));
+}
+
+#[test]
+fn test_reject_invalid_font_values() {
+    let styles = declarations(
+        ".a {
+            font-weight: 0;
+            font-weight: 1001;
+            font-weight: bolder;
+            font-style: slanted;
+            line-height: -1;
+            line-height: auto;
+            font: bold serif;
+            font: 12px;
+            font: italic italic 12px serif;
+            width: 1px;
+        }",
+    );
+    assert_eq!(styles.len(), 1);
+    assert_eq!(styles[0].width, Some(Length::Px(1.0)));
+}
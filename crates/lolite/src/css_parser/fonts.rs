@@ -0,0 +1,146 @@
+use super::parser::StyleDeclarationParser;
+use crate::style::{FontStyle, Length, LineHeight, Style};
+use cssparser::{match_ignore_ascii_case, ParseError, Parser, Token};
+
+/// `font-weight` of `normal`.
+const NORMAL_FONT_WEIGHT: u16 = 400;
+
+impl StyleDeclarationParser {
+    /// Parse the `font` shorthand into `style`, resetting the longhands it omits:
+    /// `[<font-style> || <font-weight>]? <font-size> [/ <line-height>]? <font-family>`.
+    pub(crate) fn parse_font_shorthand<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+        style: &mut Style,
+    ) -> Result<(), ParseError<'i, ()>> {
+        let mut font_style = None;
+        let mut font_weight = None;
+        // Style, variant, weight and stretch may come first in any order; `normal` is
+        // the only value supported for variant and stretch.
+        for _ in 0..4 {
+            if input
+                .try_parse(|i| i.expect_ident_matching("normal"))
+                .is_ok()
+            {
+                continue;
+            }
+            if font_style.is_none() {
+                if let Ok(value) = input.try_parse(parse_font_style) {
+                    font_style = Some(value);
+                    continue;
+                }
+            }
+            if font_weight.is_none() {
+                if let Ok(value) = input.try_parse(parse_font_weight) {
+                    font_weight = Some(value);
+                    continue;
+                }
+            }
+            break;
+        }
+        let font_size = self.parse_font_size(input)?;
+        let line_height = if input.try_parse(|i| i.expect_delim('/')).is_ok() {
+            self.parse_line_height(input)?
+        } else {
+            LineHeight::Normal
+        };
+        let font_family = parse_font_family(input)?;
+
+        style.font_style = Some(font_style.unwrap_or_default());
+        style.font_weight = Some(font_weight.unwrap_or(NORMAL_FONT_WEIGHT));
+        style.font_size = Some(font_size);
+        style.line_height = Some(line_height);
+        style.font_family = Some(font_family);
+        Ok(())
+    }
+
+    /// Parse a `font-size`: a non-negative length, or a percentage of the parent's size.
+    pub(crate) fn parse_font_size<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<Length, ParseError<'i, ()>> {
+        let start = input.state();
+        let font_size = self.parse_length_value(input)?;
+        // `auto` isn't a font size, and negative sizes are invalid
+        if font_size == Length::Auto || font_size.is_negative() {
+            input.reset(&start);
+            return Err(input.new_error_for_next_token());
+        }
+        Ok(font_size)
+    }
+
+    /// Parse `line-height`: `normal`, a non-negative number, or a non-negative length or
+    /// percentage. Unlike for other lengths, a unitless number isn't in pixels.
+    pub(crate) fn parse_line_height<'i>(
+        &mut self,
+        input: &mut Parser<'i, '_>,
+    ) -> Result<LineHeight, ParseError<'i, ()>> {
+        if input
+            .try_parse(|i| i.expect_ident_matching("normal"))
+            .is_ok()
+        {
+            return Ok(LineHeight::Normal);
+        }
+        let start = input.state();
+        if let Ok(number) = input.try_parse(|i| i.expect_number()) {
+            if number < 0.0 {
+                input.reset(&start);
+                return Err(input.new_error_for_next_token());
+            }
+            return Ok(LineHeight::Number(number as f64));
+        }
+        let length = self.parse_length_value(input)?;
+        if length == Length::Auto || length.is_negative() {
+            input.reset(&start);
+            return Err(input.new_error_for_next_token());
+        }
+        Ok(LineHeight::Length(length))
+    }
+}
+
+/// Parse `font-family`: a comma-separated list of quoted names, or of names made of
+/// identifiers, joined into the comma-separated list [`Style::font_family`] holds.
+pub(crate) fn parse_font_family<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<String, ParseError<'i, ()>> {
+    let families = input.parse_comma_separated(|input| {
+        if let Ok(name) = input.try_parse(|i| i.expect_string_cloned()) {
+            return Ok(name.to_string());
+        }
+        let mut words = vec![input.expect_ident()?.to_string()];
+        while let Ok(word) = input.try_parse(|i| i.expect_ident_cloned()) {
+            words.push(word.to_string());
+        }
+        Ok(words.join(" "))
+    })?;
+    Ok(families.join(", "))
+}
+
+/// Parse `font-weight`: `normal`, `bold` or a number from 1 to 1000, rounded. The
+/// relative `bolder` and `lighter` aren't supported.
+pub(crate) fn parse_font_weight<'i>(input: &mut Parser<'i, '_>) -> Result<u16, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    match input.next()?.clone() {
+        Token::Number { value, .. } if (1.0..=1000.0).contains(&value) => Ok(value.round() as u16),
+        Token::Ident(keyword) => match_ignore_ascii_case! { &keyword,
+            "normal" => Ok(NORMAL_FONT_WEIGHT),
+            "bold" => Ok(700),
+            _ => Err(location.new_unexpected_token_error(Token::Ident(keyword))),
+        },
+        token => Err(location.new_unexpected_token_error(token)),
+    }
+}
+
+/// Parse `font-style`: `normal`, `italic` or `oblique`, without an angle.
+pub(crate) fn parse_font_style<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<FontStyle, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let keyword = input.expect_ident()?.clone();
+    match_ignore_ascii_case! { &keyword,
+        "normal" => Ok(FontStyle::Normal),
+        "italic" => Ok(FontStyle::Italic),
+        "oblique" => Ok(FontStyle::Oblique),
+        _ => Err(location.new_unexpected_token_error(Token::Ident(keyword))),
+    }
+}
@@ -0,0 +1,285 @@
+use super::parser::StyleDeclarationParser;
+use crate::style::{GridLine, GridTrack, Length, RepeatCount, TrackBreadth, TrackSize};
+use cssparser::{ParseError, Parser, Token};
+
+impl StyleDeclarationParser {
+    /// Parse `grid-template-columns` or `grid-template-rows`: `none`, or line names and
+    /// track sizes, possibly repeated. At most one `repeat()` may repeat automatically.
+    pub(crate) fn parse_track_list<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Vec<GridTrack>, ParseError<'i, ()>> {
+        if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+            return Ok(Vec::new());
+        }
+
+        let mut tracks = Vec::new();
+        let mut auto_repeat = false;
+        while !input.is_exhausted() {
+            if let Ok(names) = input.try_parse(parse_line_names) {
+                tracks.push(GridTrack::LineNames(names));
+                continue;
+            }
+            let repeat = input.try_parse(|i| {
+                i.expect_function_matching("repeat")?;
+                i.parse_nested_block(|i| self.parse_repeat(i))
+            });
+            match repeat {
+                Ok(GridTrack::Repeat(count, _)) if count_is_auto(count) && auto_repeat => {
+                    return Err(input.new_custom_error(()));
+                }
+                Ok(repeat) => {
+                    auto_repeat |=
+                        matches!(repeat, GridTrack::Repeat(count, _) if count_is_auto(count));
+                    tracks.push(repeat);
+                }
+                Err(_) => tracks.push(GridTrack::Size(self.parse_track_size(input)?)),
+            }
+        }
+
+        if !tracks
+            .iter()
+            .any(|track| !matches!(track, GridTrack::LineNames(_)))
+        {
+            return Err(input.new_custom_error(()));
+        }
+        Ok(tracks)
+    }
+
+    /// Parse the arguments of `repeat()`.
+    fn parse_repeat<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<GridTrack, ParseError<'i, ()>> {
+        let count = match input.next()?.clone() {
+            Token::Number {
+                int_value: Some(count),
+                ..
+            } if count > 0 => RepeatCount::Count(count as u32),
+            Token::Ident(name) if name.eq_ignore_ascii_case("auto-fill") => RepeatCount::AutoFill,
+            Token::Ident(name) if name.eq_ignore_ascii_case("auto-fit") => RepeatCount::AutoFit,
+            _ => return Err(input.new_error_for_next_token()),
+        };
+        input.expect_comma()?;
+
+        let mut tracks = Vec::new();
+        while !input.is_exhausted() {
+            match input.try_parse(parse_line_names) {
+                Ok(names) => tracks.push(GridTrack::LineNames(names)),
+                Err(_) => tracks.push(GridTrack::Size(self.parse_track_size(input)?)),
+            }
+        }
+
+        let sizes = tracks.iter().filter_map(|track| match track {
+            GridTrack::Size(size) => Some(size),
+            _ => None,
+        });
+        let mut sizes = sizes.peekable();
+        if sizes.peek().is_none() {
+            return Err(input.new_custom_error(()));
+        }
+        // Automatic repetitions need every track to have a definite size
+        if count_is_auto(count) && !sizes.all(is_fixed) {
+            return Err(input.new_custom_error(()));
+        }
+        Ok(GridTrack::Repeat(count, tracks))
+    }
+
+    fn parse_track_size<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<TrackSize, ParseError<'i, ()>> {
+        if input
+            .try_parse(|i| i.expect_function_matching("minmax"))
+            .is_ok()
+        {
+            return input.parse_nested_block(|input| {
+                let min = self.parse_track_breadth(input)?;
+                input.expect_comma()?;
+                let max = self.parse_track_breadth(input)?;
+                if matches!(min, TrackBreadth::Fraction(_)) {
+                    return Err(input.new_custom_error(()));
+                }
+                Ok(TrackSize::MinMax(min, max))
+            });
+        }
+        if input
+            .try_parse(|i| i.expect_function_matching("fit-content"))
+            .is_ok()
+        {
+            return input.parse_nested_block(|input| {
+                match self.parse_non_negative_length(input)? {
+                    Length::Auto => Err(input.new_custom_error(())),
+                    limit => Ok(TrackSize::FitContent(limit)),
+                }
+            });
+        }
+        Ok(TrackSize::Breadth(self.parse_track_breadth(input)?))
+    }
+
+    fn parse_track_breadth<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<TrackBreadth, ParseError<'i, ()>> {
+        let fraction = input.try_parse(|i| match i.next()? {
+            Token::Dimension { value, unit, .. }
+                if unit.eq_ignore_ascii_case("fr") && *value >= 0.0 =>
+            {
+                Ok(TrackBreadth::Fraction(*value as f64))
+            }
+            _ => Err(i.new_custom_error::<_, ()>(())),
+        });
+        if let Ok(fraction) = fraction {
+            return Ok(fraction);
+        }
+        if input
+            .try_parse(|i| i.expect_ident_matching("min-content"))
+            .is_ok()
+        {
+            return Ok(TrackBreadth::MinContent);
+        }
+        if input
+            .try_parse(|i| i.expect_ident_matching("max-content"))
+            .is_ok()
+        {
+            return Ok(TrackBreadth::MaxContent);
+        }
+        Ok(TrackBreadth::Length(self.parse_non_negative_length(input)?))
+    }
+
+    fn parse_non_negative_length<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Length, ParseError<'i, ()>> {
+        let location = input.current_source_location();
+        let length = self.parse_length_value(input)?;
+        if length.is_negative() {
+            return Err(location.new_custom_error(()));
+        }
+        Ok(length)
+    }
+
+    /// Parse a placement such as `grid-row-start`: `auto`, a line name, a line number
+    /// optionally restricted to a name, or `span` with a count and/or a name.
+    pub(crate) fn parse_grid_line<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<GridLine, ParseError<'i, ()>> {
+        if input.try_parse(|i| i.expect_ident_matching("auto")).is_ok() {
+            return Ok(GridLine::Auto);
+        }
+
+        let span = input.try_parse(|i| i.expect_ident_matching("span")).is_ok();
+        let mut index = None;
+        let mut name = None;
+        for _ in 0..2 {
+            if index.is_none() {
+                if let Ok(value) = input.try_parse(|i| i.expect_integer()) {
+                    index = Some(value);
+                    continue;
+                }
+            }
+            if name.is_none() {
+                if let Ok(ident) = input.try_parse(parse_line_name) {
+                    name = Some(ident);
+                    continue;
+                }
+            }
+            break;
+        }
+        // `span` may also come last
+        let span = span
+            || ((index.is_some() || name.is_some())
+                && input.try_parse(|i| i.expect_ident_matching("span")).is_ok());
+
+        match (span, index, name) {
+            (true, Some(count), name) if count > 0 => Ok(GridLine::Span(count as u32, name)),
+            (true, None, Some(name)) => Ok(GridLine::Span(1, Some(name))),
+            (false, Some(index), name) if index != 0 => Ok(GridLine::Index(index, name)),
+            (false, None, Some(name)) => Ok(GridLine::Name(name)),
+            _ => Err(input.new_custom_error(())),
+        }
+    }
+
+    /// Parse the `grid-row` or `grid-column` shorthand into its start and end lines.
+    pub(crate) fn parse_grid_line_pair<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<(GridLine, GridLine), ParseError<'i, ()>> {
+        let start = self.parse_grid_line(input)?;
+        let end = if input.try_parse(|i| i.expect_delim('/')).is_ok() {
+            self.parse_grid_line(input)?
+        } else {
+            omitted_line(&start)
+        };
+        Ok((start, end))
+    }
+
+    /// Parse the `grid-area` shorthand into row start, column start, row end and
+    /// column end lines.
+    pub(crate) fn parse_grid_area<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<[GridLine; 4], ParseError<'i, ()>> {
+        let mut lines = vec![self.parse_grid_line(input)?];
+        while lines.len() < 4 && input.try_parse(|i| i.expect_delim('/')).is_ok() {
+            lines.push(self.parse_grid_line(input)?);
+        }
+        // An omitted column start or row end copies the row start, and an omitted
+        // column end the column start.
+        while lines.len() < 4 {
+            let copied = &lines[if lines.len() == 3 { 1 } else { 0 }];
+            lines.push(omitted_line(copied));
+        }
+        let [row_start, column_start, row_end, column_end] = lines.try_into().unwrap();
+        Ok([row_start, column_start, row_end, column_end])
+    }
+}
+
+/// Line used for an end omitted from a shorthand: a name repeats, anything else is
+/// `auto`.
+fn omitted_line(start: &GridLine) -> GridLine {
+    match start {
+        GridLine::Name(name) => GridLine::Name(name.clone()),
+        _ => GridLine::Auto,
+    }
+}
+
+fn count_is_auto(count: RepeatCount) -> bool {
+    matches!(count, RepeatCount::AutoFill | RepeatCount::AutoFit)
+}
+
+/// Whether a track has a definite size, as `repeat(auto-fill, ...)` requires.
+fn is_fixed(size: &TrackSize) -> bool {
+    let fixed = |breadth: &TrackBreadth| matches!(breadth, TrackBreadth::Length(length) if *length != Length::Auto);
+    match size {
+        TrackSize::Breadth(breadth) => fixed(breadth),
+        TrackSize::MinMax(min, max) => fixed(min) || fixed(max),
+        TrackSize::FitContent(_) => false,
+    }
+}
+
+/// Parse `[name ...]`.
+fn parse_line_names<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Vec<String>, ParseError<'i, ()>> {
+    input.expect_square_bracket_block()?;
+    input.parse_nested_block(|input| {
+        let mut names = Vec::new();
+        while !input.is_exhausted() {
+            names.push(parse_line_name(input)?);
+        }
+        Ok(names)
+    })
+}
+
+/// Parse a line name, which can't be a keyword of the placement properties.
+fn parse_line_name<'i, 't>(input: &mut Parser<'i, 't>) -> Result<String, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let name = input.expect_ident()?.to_string();
+    if ["span", "auto", "inherit", "initial", "unset", "default"]
+        .iter()
+        .any(|keyword| name.eq_ignore_ascii_case(keyword))
+    {
+        return Err(location.new_custom_error(()));
+    }
+    Ok(name)
+}
@@ -0,0 +1,170 @@
+use crate::css_parser::fixtures::declarations;
+use crate::style::{GridLine, GridTrack, Length, RepeatCount, TrackBreadth, TrackSize};
+
+fn size(breadth: TrackBreadth) -> GridTrack {
+    GridTrack::Size(TrackSize::Breadth(breadth))
+}
+
+#[test]
+fn test_parse_track_lists() {
+    let styles = declarations(
+        r#"
+        .a {
+            grid-template-columns: 100px 1fr auto;
+            grid-template-rows: [top] minmax(min-content, 2fr) [middle end] fit-content(10em);
+            grid-template-columns: repeat(3, [col] 1fr);
+            grid-template-columns: repeat(auto-fill, minmax(120px, 1fr));
+            grid-template-rows: none;
+        }
+        "#,
+    );
+    let tracks: Vec<_> = styles
+        .iter()
+        .map(|style| {
+            style
+                .grid_template_columns
+                .clone()
+                .or(style.grid_template_rows.clone())
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(
+        tracks,
+        vec![
+            vec![
+                size(TrackBreadth::Length(Length::Px(100.0))),
+                size(TrackBreadth::Fraction(1.0)),
+                size(TrackBreadth::Length(Length::Auto)),
+            ],
+            vec![
+                GridTrack::LineNames(vec!["top".to_string()]),
+                GridTrack::Size(TrackSize::MinMax(
+                    TrackBreadth::MinContent,
+                    TrackBreadth::Fraction(2.0)
+                )),
+                GridTrack::LineNames(vec!["middle".to_string(), "end".to_string()]),
+                GridTrack::Size(TrackSize::FitContent(Length::Em(10.0))),
+            ],
+            vec![GridTrack::Repeat(
+                RepeatCount::Count(3),
+                vec![
+                    GridTrack::LineNames(vec!["col".to_string()]),
+                    size(TrackBreadth::Fraction(1.0)),
+                ]
+            )],
+            vec![GridTrack::Repeat(
+                RepeatCount::AutoFill,
+                vec![GridTrack::Size(TrackSize::MinMax(
+                    TrackBreadth::Length(Length::Px(120.0)),
+                    TrackBreadth::Fraction(1.0)
+                ))]
+            )],
+            vec![],
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_track_lists_are_dropped() {
+    for value in [
+        "-10px",
+        "minmax(1fr, 100px)",
+        "repeat(0, 1fr)",
+        "repeat(auto-fit, 1fr)",
+        "repeat(auto-fill, 10px) repeat(auto-fit, 10px)",
+        "repeat(2, repeat(2, 1fr))",
+        "[a]",
+        "[span] 1fr",
+    ] {
+        let css = format!(".a {{ grid-template-columns: {}; }}", value);
+        assert!(
+            declarations(&css).is_empty(),
+            "{} should be rejected",
+            value
+        );
+    }
+}
+
+#[test]
+fn test_parse_line_placement() {
+    let styles = declarations(
+        r#"
+        .a {
+            grid-row-start: 2;
+            grid-row-end: span 3;
+            grid-column-start: -1 col;
+            grid-column-end: span main;
+            grid-row: header;
+            grid-column: 1 / span 2;
+            grid-area: 1 / 2 / 3;
+        }
+        "#,
+    );
+    let name = |name: &str| Some(name.to_string());
+    assert_eq!(styles[0].grid_row_start, Some(GridLine::Index(2, None)));
+    assert_eq!(styles[1].grid_row_end, Some(GridLine::Span(3, None)));
+    assert_eq!(
+        styles[2].grid_column_start,
+        Some(GridLine::Index(-1, name("col")))
+    );
+    assert_eq!(
+        styles[3].grid_column_end,
+        Some(GridLine::Span(1, name("main")))
+    );
+
+    // An omitted end repeats a name, and is auto otherwise
+    let header = Some(GridLine::Name("header".to_string()));
+    assert_eq!(
+        (&styles[4].grid_row_start, &styles[4].grid_row_end),
+        (&header, &header)
+    );
+    assert_eq!(styles[5].grid_column_start, Some(GridLine::Index(1, None)));
+    assert_eq!(styles[5].grid_column_end, Some(GridLine::Span(2, None)));
+
+    let area = &styles[6];
+    assert_eq!(
+        [
+            &area.grid_row_start,
+            &area.grid_column_start,
+            &area.grid_row_end,
+            &area.grid_column_end
+        ],
+        [
+            &Some(GridLine::Index(1, None)),
+            &Some(GridLine::Index(2, None)),
+            &Some(GridLine::Index(3, None)),
+            &Some(GridLine::Auto)
+        ]
+    );
+
+    let invalid = declarations(
+        ".b { grid-row-start: 0; grid-row-start: span 0; grid-row-start: span; \
+         grid-row: 1 / 2 / 3; }",
+    );
+    assert!(invalid.is_empty());
+}
+
+#[test]
+fn test_parse_gap_shorthand() {
+    let styles = declarations(".a { gap: 10px 2em; grid-gap: 5px; grid-column-gap: 1px; }");
+    assert_eq!(styles[0].row_gap, Some(Length::Px(10.0)));
+    assert_eq!(styles[0].column_gap, Some(Length::Em(2.0)));
+    assert_eq!(
+        (styles[1].row_gap, styles[1].column_gap),
+        (Some(Length::Px(5.0)), Some(Length::Px(5.0)))
+    );
+    assert_eq!(styles[2].column_gap, Some(Length::Px(1.0)));
+}
+
+#[test]
+fn test_parse_gap_keywords_and_percentages() {
+    let styles = declarations(".a { gap: normal 10%; row-gap: 5%; column-gap: normal; }");
+    assert_eq!(styles[0].row_gap, Some(Length::Auto));
+    assert_eq!(styles[0].column_gap, Some(Length::Percent(10.0)));
+    assert_eq!(styles[1].row_gap, Some(Length::Percent(5.0)));
+    assert_eq!(styles[2].column_gap, Some(Length::Auto));
+
+    // `auto` and negative gaps are invalid
+    let invalid = declarations(".b { gap: auto; row-gap: -1px; column-gap: 10px -5px; }");
+    assert!(invalid.is_empty());
+}
@@ -0,0 +1,53 @@
+use crate::css_parser::{audit_stylesheet, parse_css, CssDiagnosticKind};
+
+fn import_urls(css: &str) -> Vec<String> {
+    parse_css(css)
+        .unwrap()
+        .imports
+        .into_iter()
+        .map(|import| import.url)
+        .collect()
+}
+
+#[test]
+fn test_parse_imports() {
+    let css = r#"@layer base;
+@import url("a.css");
+@import "b.css";
+@import url(c.css);
+.a { width: 1px; }"#;
+    assert_eq!(import_urls(css), vec!["a.css", "b.css", "c.css"]);
+
+    let stylesheet = parse_css(css).unwrap();
+    assert_eq!(stylesheet.rules.len(), 1);
+    let span = stylesheet.imports[1].span;
+    assert_eq!(&css[span.start..span.end], r#"@import "b.css""#);
+    assert_eq!((span.line, span.column), (3, 1));
+}
+
+#[test]
+fn test_imports_after_other_rules_are_ignored() {
+    let css = ".a { width: 1px; }\n@import \"late.css\";";
+    assert!(import_urls(css).is_empty());
+
+    let diagnostics = audit_stylesheet(css);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line, 2);
+    assert!(matches!(
+        diagnostics[0].kind,
+        CssDiagnosticKind::Unsupported { .. }
+    ));
+}
+
+#[test]
+fn test_reject_invalid_imports() {
+    let css = r#"
+        @import;
+        @import 10px;
+        @import "print.css" print;
+        @import url("a.css") { }
+        @media (min-width: 1px) { @import "nested.css"; }
+    "#;
+    assert!(import_urls(css).is_empty());
+    assert_eq!(audit_stylesheet(css).len(), 5);
+}
@@ -0,0 +1,20 @@
+use cssparser::{ParseError, Parser, Token};
+
+/// Parse the prelude of an `@import` rule: a `url()` or a string. Media, layer and
+/// supports conditions aren't supported, so an import with them is dropped.
+pub(super) fn parse_import_url<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<String, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let url = match input.next()?.clone() {
+        Token::QuotedString(url) | Token::UnquotedUrl(url) => url.to_string(),
+        Token::Function(name) if name.eq_ignore_ascii_case("url") => {
+            input.parse_nested_block(|input| -> Result<_, ParseError<'i, ()>> {
+                Ok(input.expect_string()?.to_string())
+            })?
+        }
+        token => return Err(location.new_unexpected_token_error(token)),
+    };
+    input.expect_exhausted()?;
+    Ok(url)
+}
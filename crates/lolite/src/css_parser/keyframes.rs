@@ -0,0 +1,128 @@
+use super::diagnostics::{CssDiagnostic, CssDiagnosticKind};
+use super::parser::{parse_declaration_block, parse_rule_list, source_range, CssParser, CssRule};
+use crate::style::{Keyframe, Keyframes};
+use cssparser::{
+    match_ignore_ascii_case, AtRuleParser, ParseError, Parser, ParserState, QualifiedRuleParser,
+    Token,
+};
+
+impl CssParser {
+    /// Parse the keyframes of an `@keyframes` block; invalid keyframes are dropped.
+    pub(super) fn parse_keyframes_block<'i>(
+        &mut self,
+        name: String,
+        input: &mut Parser<'i, '_>,
+    ) -> CssRule {
+        let mut keyframe_parser = KeyframeListParser {
+            diagnostics: Vec::new(),
+        };
+        let mut frames = Vec::new();
+        for keyframes in parse_rule_list(input, &mut keyframe_parser) {
+            match keyframes {
+                Ok(keyframes) => frames.extend(keyframes),
+                Err(diagnostic) => self.diagnostics.push(diagnostic),
+            }
+        }
+        self.diagnostics.append(&mut keyframe_parser.diagnostics);
+        frames.sort_by(|a: &Keyframe, b| a.offset.total_cmp(&b.offset));
+        CssRule::Keyframes(Keyframes { name, frames })
+    }
+}
+
+/// Parse the name in front of an `@keyframes` block: an identifier other than `none`
+/// and the CSS-wide keywords, or a string.
+pub(super) fn parse_keyframes_name<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<String, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    match input.next()?.clone() {
+        Token::Ident(name) => {
+            if [
+                "none",
+                "initial",
+                "inherit",
+                "unset",
+                "default",
+                "revert",
+                "revert-layer",
+            ]
+            .iter()
+            .any(|keyword| name.eq_ignore_ascii_case(keyword))
+            {
+                return Err(location.new_custom_error(()));
+            }
+            Ok(name.to_string())
+        }
+        Token::QuotedString(name) => Ok(name.to_string()),
+        token => Err(location.new_unexpected_token_error(token)),
+    }
+}
+
+/// Parser for the keyframes of an `@keyframes` block, such as `from, 50% { ... }`.
+struct KeyframeListParser {
+    diagnostics: Vec<CssDiagnostic>,
+}
+
+impl<'i> QualifiedRuleParser<'i> for KeyframeListParser {
+    /// Offsets of the keyframe selector list.
+    type Prelude = Vec<f64>;
+    /// One keyframe per offset, each with the block's declarations.
+    type QualifiedRule = Vec<Keyframe>;
+    type Error = ();
+
+    fn parse_prelude<'t>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        input.parse_comma_separated(parse_keyframe_offset)
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: Self::Prelude,
+        start: &ParserState,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
+        let (declarations, important_declarations, _) =
+            parse_declaration_block(input, &mut self.diagnostics);
+        if !important_declarations.is_empty() {
+            self.diagnostics.push(CssDiagnostic::new(
+                start.source_location(),
+                source_range(start, input),
+                CssDiagnosticKind::Unsupported {
+                    source: "!important".to_string(),
+                    message: "declarations marked !important are ignored in keyframes",
+                },
+            ));
+        }
+        Ok(prelude
+            .into_iter()
+            .map(|offset| Keyframe {
+                offset,
+                declarations: declarations.clone(),
+            })
+            .collect())
+    }
+}
+
+impl<'i> AtRuleParser<'i> for KeyframeListParser {
+    type Prelude = ();
+    type AtRule = Vec<Keyframe>;
+    type Error = ();
+}
+
+/// `from`, `to` or a percentage between `0%` and `100%`.
+fn parse_keyframe_offset<'i>(input: &mut Parser<'i, '_>) -> Result<f64, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    match input.next()?.clone() {
+        Token::Ident(keyword) => match_ignore_ascii_case! { &keyword,
+            "from" => Ok(0.0),
+            "to" => Ok(1.0),
+            _ => Err(location.new_unexpected_token_error(Token::Ident(keyword))),
+        },
+        Token::Percentage { unit_value, .. } if (0.0..=1.0).contains(&unit_value) => {
+            Ok(unit_value as f64)
+        }
+        token => Err(location.new_unexpected_token_error(token)),
+    }
+}
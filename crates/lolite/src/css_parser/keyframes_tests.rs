@@ -0,0 +1,81 @@
+use crate::css_parser::{audit_stylesheet, parse_css};
+use crate::style::{Keyframes, Length, StyleSheet};
+
+fn keyframes<'a>(stylesheet: &'a StyleSheet, name: &str) -> Option<&'a Keyframes> {
+    stylesheet
+        .keyframes
+        .iter()
+        .find(|keyframes| keyframes.name == name)
+}
+
+fn offsets_and_widths(stylesheet: &StyleSheet, name: &str) -> Vec<(f64, Option<Length>)> {
+    keyframes(stylesheet, name)
+        .unwrap()
+        .frames
+        .iter()
+        .map(|frame| (frame.offset, frame.declarations[0].width))
+        .collect()
+}
+
+#[test]
+fn test_parse_keyframes() {
+    let stylesheet = parse_css(
+        "@keyframes grow { to { width: 30px; } from { width: 10px; } 50% { width: 20px; } }
+         .a { width: 1px; }",
+    )
+    .unwrap();
+
+    assert_eq!(stylesheet.rules.len(), 1);
+    assert_eq!(
+        offsets_and_widths(&stylesheet, "grow"),
+        vec![
+            (0.0, Some(Length::Px(10.0))),
+            (0.5, Some(Length::Px(20.0))),
+            (1.0, Some(Length::Px(30.0))),
+        ]
+    );
+    assert!(keyframes(&stylesheet, "Grow").is_none());
+}
+
+#[test]
+fn test_keyframe_selector_lists_and_names() {
+    let stylesheet = parse_css(
+        "@keyframes \"pulse\" { 0%, 100% { width: 1px; } 25% { width: 2px; } }
+         @keyframes pulse { from, 25% { width: 3px; } }",
+    )
+    .unwrap();
+
+    // The later rule of the same name replaces the earlier one
+    assert_eq!(stylesheet.keyframes.len(), 1);
+    assert_eq!(
+        offsets_and_widths(&stylesheet, "pulse"),
+        vec![(0.0, Some(Length::Px(3.0))), (0.25, Some(Length::Px(3.0)))]
+    );
+
+    let stylesheet =
+        parse_css("@keyframes none { from { width: 1px; } } @keyframes a { 120% { width: 1px; } }")
+            .unwrap();
+    assert!(keyframes(&stylesheet, "none").is_none());
+    assert!(keyframes(&stylesheet, "a").unwrap().frames.is_empty());
+}
+
+#[test]
+fn test_audit_keyframes() {
+    let diagnostics = audit_stylesheet(
+        "@keyframes a { half { width: 1px; } to { width: 2px !important; colour: red; } }",
+    );
+    let messages: Vec<String> = diagnostics.iter().map(ToString::to_string).collect();
+
+    assert_eq!(messages.len(), 3, "{:?}", messages);
+    assert!(messages[0].contains("half"), "{:?}", messages);
+    assert!(
+        messages.iter().any(|m| m.contains("colour")),
+        "{:?}",
+        messages
+    );
+    assert!(
+        messages.iter().any(|m| m.contains("!important")),
+        "{:?}",
+        messages
+    );
+}
@@ -0,0 +1,67 @@
+use crate::css_parser::{audit_stylesheet, fixtures::declarations};
+use crate::style::CssWideKeyword;
+
+#[test]
+fn test_parse_css_wide_keywords() {
+    let styles = declarations(
+        ".a {
+            width: inherit;
+            color: INITIAL;
+            font-weight: unset;
+            height: revert;
+            display: inherit;
+        }",
+    );
+    assert_eq!(styles.len(), 5);
+    assert_eq!(
+        styles[0].css_wide_keywords.get("width"),
+        Some(CssWideKeyword::Inherit)
+    );
+    assert_eq!(styles[0].width, None);
+    assert_eq!(
+        styles[1].css_wide_keywords.get("color"),
+        Some(CssWideKeyword::Initial)
+    );
+    assert_eq!(
+        styles[2].css_wide_keywords.get("font-weight"),
+        Some(CssWideKeyword::Unset)
+    );
+    assert_eq!(
+        styles[3].css_wide_keywords.get("height"),
+        Some(CssWideKeyword::Revert)
+    );
+    assert_eq!(
+        styles[4].css_wide_keywords.get("display"),
+        Some(CssWideKeyword::Inherit)
+    );
+}
+
+#[test]
+fn test_css_wide_keywords_apply_to_the_longhands_of_shorthands() {
+    let styles = declarations(".a { margin: unset; border-top: inherit; }");
+    for side in ["top", "right", "bottom", "left"] {
+        assert_eq!(
+            styles[0].css_wide_keywords.get(&format!("margin-{}", side)),
+            Some(CssWideKeyword::Unset)
+        );
+    }
+    for longhand in ["border-top-color", "border-top-width", "border-top-style"] {
+        assert_eq!(
+            styles[1].css_wide_keywords.get(longhand),
+            Some(CssWideKeyword::Inherit)
+        );
+    }
+    assert_eq!(styles[1].css_wide_keywords.get("border-left-color"), None);
+}
+
+#[test]
+fn test_reject_invalid_css_wide_keywords() {
+    let css = ".a {
+        width: inherit 10px;
+        height: revert-layer;
+        colour: initial;
+        min-width: unset;
+    }";
+    assert_eq!(declarations(css).len(), 1);
+    assert_eq!(audit_stylesheet(css).len(), 3);
+}
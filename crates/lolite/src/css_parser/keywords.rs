@@ -0,0 +1,121 @@
+use crate::style::{longhand, CssWideKeyword};
+use cssparser::{match_ignore_ascii_case, ParseError, Parser, Token};
+
+/// Parse a CSS-wide keyword: `initial`, `inherit`, `unset` or `revert`.
+pub(super) fn parse_css_wide_keyword<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<CssWideKeyword, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let keyword = input.expect_ident()?.clone();
+    match_ignore_ascii_case! { &keyword,
+        "initial" => Ok(CssWideKeyword::Initial),
+        "inherit" => Ok(CssWideKeyword::Inherit),
+        "unset" => Ok(CssWideKeyword::Unset),
+        "revert" => Ok(CssWideKeyword::Revert),
+        _ => Err(location.new_unexpected_token_error(Token::Ident(keyword))),
+    }
+}
+
+/// The longhands a CSS-wide keyword declared for `property` applies to, or `None` if
+/// the property isn't known.
+pub(super) fn css_wide_longhands(property: &str) -> Option<Vec<&'static str>> {
+    let longhands: &[&str] = match property {
+        "background" => &[
+            "background-color",
+            "background-image",
+            "background-position",
+            "background-size",
+            "background-repeat",
+        ],
+        "border-color" => &[
+            "border-top-color",
+            "border-right-color",
+            "border-bottom-color",
+            "border-left-color",
+        ],
+        "border-width" => &[
+            "border-top-width",
+            "border-right-width",
+            "border-bottom-width",
+            "border-left-width",
+        ],
+        "border-style" => &[
+            "border-top-style",
+            "border-right-style",
+            "border-bottom-style",
+            "border-left-style",
+        ],
+        "border" => &[
+            "border-top-color",
+            "border-right-color",
+            "border-bottom-color",
+            "border-left-color",
+            "border-top-width",
+            "border-right-width",
+            "border-bottom-width",
+            "border-left-width",
+            "border-top-style",
+            "border-right-style",
+            "border-bottom-style",
+            "border-left-style",
+        ],
+        "border-top" => &["border-top-color", "border-top-width", "border-top-style"],
+        "border-right" => &[
+            "border-right-color",
+            "border-right-width",
+            "border-right-style",
+        ],
+        "border-bottom" => &[
+            "border-bottom-color",
+            "border-bottom-width",
+            "border-bottom-style",
+        ],
+        "border-left" => &[
+            "border-left-color",
+            "border-left-width",
+            "border-left-style",
+        ],
+        "border-radius" => &[
+            "border-top-left-radius",
+            "border-top-right-radius",
+            "border-bottom-right-radius",
+            "border-bottom-left-radius",
+        ],
+        "overflow" => &["overflow-x", "overflow-y"],
+        "font" => &[
+            "font-style",
+            "font-weight",
+            "font-size",
+            "line-height",
+            "font-family",
+        ],
+        "margin" => &["margin-top", "margin-right", "margin-bottom", "margin-left"],
+        "padding" => &[
+            "padding-top",
+            "padding-right",
+            "padding-bottom",
+            "padding-left",
+        ],
+        "inset" => &["top", "right", "bottom", "left"],
+        "flex" => &["flex-grow", "flex-shrink", "flex-basis"],
+        "gap" | "grid-gap" => &["row-gap", "column-gap"],
+        "grid-row-gap" => &["row-gap"],
+        "grid-column-gap" => &["column-gap"],
+        "grid-row" => &["grid-row-start", "grid-row-end"],
+        "grid-column" => &["grid-column-start", "grid-column-end"],
+        "grid-area" => &[
+            "grid-row-start",
+            "grid-column-start",
+            "grid-row-end",
+            "grid-column-end",
+        ],
+        "transition" => &[
+            "transition-property",
+            "transition-duration",
+            "transition-timing-function",
+            "transition-delay",
+        ],
+        _ => return longhand(property).map(|longhand| vec![longhand]),
+    };
+    Some(longhands.to_vec())
+}
@@ -0,0 +1,118 @@
+use super::diagnostics::{CssDiagnostic, CssDiagnosticKind};
+use super::parser::StyleDeclarationParser;
+use crate::style::{
+    ColorScheme, Length, MediaFeature, MediaQuery, MediaQueryList, MotionPreference,
+};
+use cssparser::{Delimiter, ParseError, Parser};
+
+/// Parse the prelude of an `@media` rule.
+///
+/// As in CSS, a query that can't be parsed never matches without affecting the rest of
+/// the list; it is reported in `diagnostics`.
+pub(crate) fn parse_media_query_list(
+    input: &mut Parser<'_, '_>,
+    diagnostics: &mut Vec<CssDiagnostic>,
+) -> MediaQueryList {
+    let mut list = MediaQueryList::default();
+    if input.is_exhausted() {
+        // `@media { ... }` applies everywhere
+        list.queries.push(MediaQuery::default());
+        return list;
+    }
+    loop {
+        let location = input.current_source_location();
+        let start = input.position();
+        match input.parse_until_before(Delimiter::Comma, parse_media_query) {
+            Ok(Some(query)) => list.queries.push(query),
+            Ok(None) => {}
+            Err(_) => diagnostics.push(CssDiagnostic::new(
+                location,
+                start.byte_index()..input.position().byte_index(),
+                CssDiagnosticKind::Unsupported {
+                    source: input.slice_from(start).trim().to_string(),
+                    message: "unknown media query; it never matches",
+                },
+            )),
+        }
+        // Everything up to the comma was consumed above
+        if input.next().is_err() {
+            break;
+        }
+    }
+    list
+}
+
+/// Parse `[only]? <media-type> [and <feature>]*` or `<feature> [and <feature>]*`.
+///
+/// Returns `None` for media types other than `screen` and `all`, which never match.
+fn parse_media_query<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<Option<MediaQuery>, ParseError<'i, ()>> {
+    let mut query = MediaQuery::default();
+    let mut screen = true;
+    match input.try_parse(|input| input.expect_ident_cloned()) {
+        Ok(mut media_type) => {
+            if media_type.as_ref() == "only" {
+                media_type = input.expect_ident_cloned()?;
+            }
+            match media_type.as_ref() {
+                "all" | "screen" => {}
+                // `not` would need negated queries
+                "not" | "and" | "or" | "only" => return Err(input.new_custom_error(())),
+                _ => screen = false,
+            }
+        }
+        Err(_) => query.features.push(parse_media_feature(input)?),
+    }
+    while !input.is_exhausted() {
+        input.expect_ident_matching("and")?;
+        query.features.push(parse_media_feature(input)?);
+    }
+    Ok(screen.then_some(query))
+}
+
+/// Parse a parenthesized feature like `(min-width: 600px)`.
+fn parse_media_feature<'i>(input: &mut Parser<'i, '_>) -> Result<MediaFeature, ParseError<'i, ()>> {
+    input.expect_parenthesis_block()?;
+    input.parse_nested_block(|input| {
+        let name = input.expect_ident_cloned()?;
+        // In the boolean form, anything but `no-preference` matches
+        if name.as_ref() == "prefers-reduced-motion" && input.is_exhausted() {
+            return Ok(MediaFeature::PrefersReducedMotion(MotionPreference::Reduce));
+        }
+        input.expect_colon()?;
+        let feature = match name.as_ref() {
+            "min-width" => MediaFeature::MinWidth(parse_media_length(input)?),
+            "max-width" => MediaFeature::MaxWidth(parse_media_length(input)?),
+            "min-height" => MediaFeature::MinHeight(parse_media_length(input)?),
+            "max-height" => MediaFeature::MaxHeight(parse_media_length(input)?),
+            "prefers-color-scheme" => {
+                let scheme = input.expect_ident()?;
+                MediaFeature::PrefersColorScheme(match scheme.as_ref() {
+                    "light" => ColorScheme::Light,
+                    "dark" => ColorScheme::Dark,
+                    _ => return Err(input.new_error_for_next_token()),
+                })
+            }
+            "prefers-reduced-motion" => {
+                let preference = input.expect_ident()?;
+                MediaFeature::PrefersReducedMotion(match preference.as_ref() {
+                    "no-preference" => MotionPreference::NoPreference,
+                    "reduce" => MotionPreference::Reduce,
+                    _ => return Err(input.new_error_for_next_token()),
+                })
+            }
+            _ => return Err(input.new_custom_error(())),
+        };
+        input.expect_exhausted()?;
+        Ok(feature)
+    })
+}
+
+/// Media queries take lengths, but not `auto` or percentages.
+fn parse_media_length<'i>(input: &mut Parser<'i, '_>) -> Result<Length, ParseError<'i, ()>> {
+    match StyleDeclarationParser::new().parse_length_value(input)? {
+        Length::Auto | Length::Percent(_) => Err(input.new_custom_error(())),
+        length => Ok(length),
+    }
+}
@@ -0,0 +1,206 @@
+use crate::css_parser::{audit_stylesheet, parse_css};
+use crate::layout::Size;
+use crate::style::{
+    ColorScheme, Length, MediaFeature, MediaQuery, MediaQueryList, MotionPreference,
+};
+
+fn media_of(css: &str) -> Option<MediaQueryList> {
+    let stylesheet = parse_css(css).unwrap();
+    assert_eq!(stylesheet.rules.len(), 1);
+    stylesheet.rules[0].media.clone()
+}
+
+fn size(width: f64, height: f64) -> Size {
+    Size { width, height }
+}
+
+#[test]
+fn test_parse_media_features() {
+    let media = media_of(
+        "@media screen and (min-width: 600px) and (max-height: 40em) { .a { width: 1px; } }",
+    );
+
+    assert_eq!(
+        media,
+        Some(MediaQueryList {
+            queries: vec![MediaQuery {
+                features: vec![
+                    MediaFeature::MinWidth(Length::Px(600.0)),
+                    MediaFeature::MaxHeight(Length::Em(40.0)),
+                ],
+            }],
+        })
+    );
+}
+
+#[test]
+fn test_rules_outside_media_blocks_have_no_condition() {
+    let stylesheet =
+        parse_css(".a { width: 1px; } @media (max-width: 10px) { .b { width: 2px; } }").unwrap();
+
+    assert_eq!(stylesheet.rules[0].media, None);
+    assert!(stylesheet.rules[1].media.is_some());
+}
+
+#[test]
+fn test_width_breakpoints_are_inclusive() {
+    let media = media_of("@media (min-width: 600px) and (max-width: 800px) { .a { width: 1px; } }")
+        .unwrap();
+
+    assert!(!media.matches(
+        size(599.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+    assert!(media.matches(
+        size(600.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+    assert!(media.matches(
+        size(800.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+    assert!(!media.matches(
+        size(801.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+}
+
+#[test]
+fn test_prefers_color_scheme() {
+    let media = media_of("@media (prefers-color-scheme: dark) { .a { width: 1px; } }").unwrap();
+
+    assert!(media.matches(
+        size(100.0, 100.0),
+        ColorScheme::Dark,
+        MotionPreference::NoPreference
+    ));
+    assert!(!media.matches(
+        size(100.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+}
+
+#[test]
+fn test_any_query_in_a_list_may_match() {
+    let media =
+        media_of("@media (max-width: 100px), (min-height: 500px) { .a { width: 1px; } }").unwrap();
+
+    assert!(media.matches(
+        size(50.0, 50.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+    assert!(media.matches(
+        size(900.0, 900.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+    assert!(!media.matches(
+        size(900.0, 50.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+}
+
+#[test]
+fn test_other_media_types_never_match() {
+    let media = media_of("@media print { .a { width: 1px; } }").unwrap();
+    assert!(!media.matches(
+        size(100.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+
+    let media = media_of("@media only screen { .a { width: 1px; } }").unwrap();
+    assert!(media.matches(
+        size(100.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+
+    let media = media_of("@media { .a { width: 1px; } }").unwrap();
+    assert!(media.matches(
+        size(100.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+}
+
+#[test]
+fn test_em_breakpoints_use_the_default_font_size() {
+    let media = media_of("@media (min-width: 10em) { .a { width: 1px; } }").unwrap();
+
+    assert!(media.matches(
+        size(120.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+    assert!(!media.matches(
+        size(119.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+}
+
+#[test]
+fn test_unknown_queries_never_match_and_are_reported() {
+    let css = "@media (hover: hover), (min-width: 10px) { .a { width: 1px; } }";
+    let media = media_of(css).unwrap();
+
+    // The unknown query is dropped, the rest of the list still applies
+    assert_eq!(media.queries.len(), 1);
+    assert!(media.matches(
+        size(100.0, 100.0),
+        ColorScheme::Light,
+        MotionPreference::NoPreference
+    ));
+
+    let messages: Vec<_> = audit_stylesheet(css)
+        .iter()
+        .map(|d| d.to_string())
+        .collect();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains("(hover: hover)"));
+}
+
+#[test]
+fn test_media_blocks_inside_layers() {
+    let stylesheet =
+        parse_css("@layer base { @media (min-width: 1px) { .a { width: 1px; } } }").unwrap();
+
+    assert_eq!(stylesheet.rules[0].layer.as_deref(), Some("base"));
+    assert!(stylesheet.rules[0].media.is_some());
+}
+
+#[test]
+fn test_at_rules_nested_in_media_blocks_are_reported() {
+    let diagnostics =
+        audit_stylesheet("@media screen { @media (min-width: 1px) { .a { width: 1px; } } }");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0]
+        .to_string()
+        .contains("nested in a media block"));
+}
+
+#[test]
+fn test_prefers_reduced_motion() {
+    let viewport = size(100.0, 100.0);
+    for css in [
+        "@media (prefers-reduced-motion: reduce) { .a { width: 1px; } }",
+        "@media (prefers-reduced-motion) { .a { width: 1px; } }",
+    ] {
+        let media = media_of(css).unwrap();
+        assert!(media.matches(viewport, ColorScheme::Light, MotionPreference::Reduce));
+        assert!(!media.matches(viewport, ColorScheme::Light, MotionPreference::NoPreference));
+    }
+
+    let media =
+        media_of("@media (prefers-reduced-motion: no-preference) { .a { width: 1px; } }").unwrap();
+    assert!(media.matches(viewport, ColorScheme::Light, MotionPreference::NoPreference));
+}
@@ -1,10 +1,28 @@
+mod backgrounds;
 mod borders;
 mod colors;
+mod diagnostics;
+mod font_faces;
+mod fonts;
+mod grid;
+mod imports;
+mod keyframes;
+mod keywords;
+mod media;
 mod named_colors;
 mod parser;
+mod transitions;
 mod values;
+mod variables;
 
-pub use parser::parse_css;
+pub use diagnostics::{CssDiagnostic, CssDiagnosticKind, CssSeverity};
+pub(crate) use parser::parse_css_with_diagnostics;
+pub(crate) use parser::parse_declaration;
+pub use parser::{audit_stylesheet, parse_css, parse_selector};
+pub(crate) use variables::substitute_vars;
+
+#[cfg(test)]
+mod fixtures;
 
 #[cfg(test)]
 mod color_tests;
@@ -17,3 +35,36 @@ mod css_parser_tests;
 
 #[cfg(test)]
 mod border_tests;
+
+#[cfg(test)]
+mod diagnostics_tests;
+
+#[cfg(test)]
+mod variables_tests;
+
+#[cfg(test)]
+mod media_tests;
+
+#[cfg(test)]
+mod grid_tests;
+
+#[cfg(test)]
+mod keyframes_tests;
+
+#[cfg(test)]
+mod transition_tests;
+
+#[cfg(test)]
+mod font_tests;
+
+#[cfg(test)]
+mod background_tests;
+
+#[cfg(test)]
+mod import_tests;
+
+#[cfg(test)]
+mod font_face_tests;
+
+#[cfg(test)]
+mod keyword_tests;
@@ -1,126 +1,688 @@
+use super::backgrounds::{parse_background_image, parse_background_repeat};
+use super::diagnostics::{unsupported_declaration, CssDiagnostic, CssDiagnosticKind};
+use super::fonts::{parse_font_family, parse_font_style, parse_font_weight};
+use super::imports::parse_import_url;
+use super::keyframes::parse_keyframes_name;
+use super::keywords::{css_wide_longhands, parse_css_wide_keyword};
+use super::media::parse_media_query_list;
+use super::transitions::{
+    parse_time_list, parse_timing_function_list, parse_transition, parse_transition_property_list,
+};
+use super::variables::references_var;
 use crate::style::{
-    AlignContent, AlignItems, AlignSelf, BoxSizing, Directional, Display, FlexDirection, FlexWrap,
-    JustifyContent, Rule, Selector, Style, StyleSheet,
+    AlignContent, AlignItems, AlignSelf, AttributeMatch, BoxSizing, Contain, ContentVisibility,
+    Directional, Display, FlexDirection, FlexWrap, FontFace, JustifyContent, Keyframes, Length,
+    MediaQueryList, Nth, Position, PseudoClass, PseudoElement, Rule, RuleSource, ScrollBehavior,
+    ScrollSnapAlign, ScrollSnapAxis, ScrollSnapStrictness, ScrollSnapType, Selector, SourceSpan,
+    Style, StyleImport, StyleOrigin, StyleSheet, TextDirection, VarDeclaration,
 };
 use cssparser::{
-    AtRuleParser, CowRcStr, DeclarationParser, ParseError, Parser, ParserInput, ParserState,
-    QualifiedRuleParser, RuleBodyItemParser, RuleBodyParser, StyleSheetParser,
+    match_ignore_ascii_case, parse_important, parse_nth, AtRuleParser, BasicParseErrorKind,
+    CowRcStr, DeclarationParser, Delimiter, ParseError, ParseErrorKind, Parser, ParserInput,
+    ParserState, QualifiedRuleParser, RuleBodyItemParser, RuleBodyParser, SourceLocation,
+    StyleSheetParser, Token,
 };
+use std::ops::Range;
 
-/// Parse a CSS string into a StyleSheet
+/// Parse a CSS string into a StyleSheet, logging anything that will be ignored
 pub fn parse_css(css: &str) -> Result<StyleSheet, String> {
+    let (stylesheet, diagnostics) = parse_css_with_diagnostics(css);
+    for diagnostic in diagnostics {
+        eprintln!("CSS: {}", diagnostic);
+    }
+    Ok(stylesheet)
+}
+
+/// Report everything in a stylesheet that the engine will ignore or only partly
+/// implement: unknown properties, invalid values, dropped rules, and recognized
+/// features that aren't implemented yet.
+pub fn audit_stylesheet(css: &str) -> Vec<CssDiagnostic> {
+    parse_css_with_diagnostics(css).1
+}
+
+/// Parse the value of a single declaration, e.g. one whose `var()` references were
+/// just substituted.
+pub(crate) fn parse_declaration(property: &str, value: &str) -> Option<Style> {
+    let mut input = ParserInput::new(value);
+    let mut input = Parser::new(&mut input);
+    let start = input.state();
+    let style = StyleDeclarationParser::new()
+        .parse_property(property.into(), &mut input, &start)
+        .ok()?;
+    input.expect_exhausted().ok()?;
+    Some(style)
+}
+
+/// Parse a stylesheet, returning what was dropped or is only partly honored in source
+/// order.
+pub(crate) fn parse_css_with_diagnostics(css: &str) -> (StyleSheet, Vec<CssDiagnostic>) {
     let mut input = ParserInput::new(css);
     let mut parser = Parser::new(&mut input);
 
     let mut stylesheet = StyleSheet::new();
     let mut css_parser = CssParser::new();
+    let mut diagnostics = Vec::new();
+    // `@import` rules must come first, with only `@layer` statements before them
+    let mut imports_allowed = true;
 
-    let rules = StyleSheetParser::new(&mut parser, &mut css_parser);
-
-    for rule in rules {
+    for rule in parse_rule_list(&mut parser, &mut css_parser) {
+        let layer_statement = matches!(&rule, Ok(CssRule::Layer(_, rules)) if rules.is_empty());
+        if !layer_statement && !matches!(rule, Ok(CssRule::Import(_)) | Err(_)) {
+            imports_allowed = false;
+        }
         match rule {
-            Ok(parsed_rule) => {
-                stylesheet.add_rule(parsed_rule);
+            Ok(CssRule::Import(import)) if imports_allowed => stylesheet.imports.push(import),
+            Ok(CssRule::Import(import)) => {
+                let location = SourceLocation {
+                    line: import.span.line - 1,
+                    column: import.span.column,
+                };
+                diagnostics.push(CssDiagnostic::new(
+                    location,
+                    import.span.start..import.span.end,
+                    CssDiagnosticKind::Unsupported {
+                        source: format!("@import \"{}\"", import.url),
+                        message: "@import after other rules is ignored",
+                    },
+                ));
+            }
+            Ok(CssRule::Style(rule)) => stylesheet.add_rule(rule),
+            Ok(CssRule::Layer(names, rules)) => {
+                for name in &names {
+                    stylesheet.declare_layer(name);
+                }
+                for rule in rules {
+                    stylesheet.add_rule(rule);
+                }
             }
-            Err(err) => {
-                eprintln!("CSS parsing error: {:?}", err);
+            Ok(CssRule::Media(rules)) => {
+                for rule in rules {
+                    stylesheet.add_rule(rule);
+                }
             }
+            Ok(CssRule::Keyframes(keyframes)) => stylesheet.add_keyframes(keyframes),
+            Ok(CssRule::FontFace(font_face)) => stylesheet.font_faces.push(font_face),
+            Err(diagnostic) => diagnostics.push(diagnostic),
         }
     }
 
-    Ok(stylesheet)
+    // Rule diagnostics are collected separately; keep everything in source order.
+    diagnostics.append(&mut css_parser.diagnostics);
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    for diagnostic in &mut diagnostics {
+        diagnostic.locate(css);
+    }
+    (stylesheet, diagnostics)
+}
+
+/// The source from `start` up to the current position of `input`.
+pub(super) fn source_span(start: &ParserState, input: &Parser<'_, '_>) -> SourceSpan {
+    let location = start.source_location();
+    SourceSpan {
+        start: start.position().byte_index(),
+        end: input.position().byte_index(),
+        line: location.line + 1,
+        column: location.column,
+    }
+}
+
+/// Parse a list of rules, turning those that can't be parsed into diagnostics.
+pub(super) fn parse_rule_list<'i, P, R>(
+    input: &mut Parser<'i, '_>,
+    parser: &mut P,
+) -> Vec<Result<R, CssDiagnostic>>
+where
+    P: QualifiedRuleParser<'i, QualifiedRule = R, Error = ()>
+        + AtRuleParser<'i, AtRule = R, Error = ()>,
+{
+    let mut rules = StyleSheetParser::new(input, parser);
+    let mut parsed = Vec::new();
+    while let Some(rule) = rules.next() {
+        // The source of a rule that failed ends where the parser stopped
+        let end = rules.input.position().byte_index();
+        parsed.push(rule.map_err(|(err, source)| {
+            let rule = source.split('{').next().unwrap_or(source).trim();
+            CssDiagnostic::new(
+                err.location,
+                end - source.len()..end,
+                CssDiagnosticKind::InvalidRule(rule.to_string()),
+            )
+        }));
+    }
+    parsed
+}
+
+/// Byte range from `start` up to the current position of `input`.
+pub(super) fn source_range(start: &ParserState, input: &Parser<'_, '_>) -> Range<usize> {
+    start.position().byte_index()..input.position().byte_index()
+}
+
+/// Parse a comma-separated selector list, as accepted in front of a rule block
+pub fn parse_selector(selector: &str) -> Result<Vec<Selector>, String> {
+    let mut input = ParserInput::new(selector);
+    let mut parser = Parser::new(&mut input);
+    parser
+        .parse_entirely(|input| QualifiedRuleParser::parse_prelude(&mut CssParser::new(), input))
+        .map_err(|err| format!("invalid selector {:?}: {:?}", selector, err.kind))
+}
+
+/// Parse the inside of `[name]`, `[name=value]` or `[name~=value]`; values may be quoted.
+fn parse_attribute_selector<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<Selector, ParseError<'i, ()>> {
+    let name = input.expect_ident()?.to_string();
+    if input.is_exhausted() {
+        return Ok(Selector::Attribute(name, AttributeMatch::Exists));
+    }
+    let matcher: fn(String) -> AttributeMatch = match input.next()? {
+        Token::Delim('=') => AttributeMatch::Equals,
+        Token::IncludeMatch => AttributeMatch::Includes,
+        token => {
+            let token = token.clone();
+            return Err(input.new_unexpected_token_error(token));
+        }
+    };
+    let value = input.expect_ident_or_string()?.to_string();
+    input.expect_exhausted()?;
+    Ok(Selector::Attribute(name, matcher(value)))
+}
+
+/// Parse compound selectors joined by descendant (whitespace) and child (`>`) combinators.
+fn parse_complex_selector<'i>(input: &mut Parser<'i, '_>) -> Result<Selector, ParseError<'i, ()>> {
+    let mut selector = parse_compound_selector(input)?;
+    loop {
+        let before = input.position();
+        input.skip_whitespace();
+        if input.is_exhausted() {
+            return Ok(selector);
+        }
+        // A pseudo-element ends the selector
+        if selector.pseudo_element().is_some() {
+            return Err(input.new_error_for_next_token());
+        }
+        let combinator: fn(Box<Selector>, Box<Selector>) -> Selector =
+            if input.try_parse(|input| input.expect_delim('>')).is_ok() {
+                Selector::Child
+            } else if input.position() != before {
+                Selector::Descendant
+            } else {
+                return Err(input.new_error_for_next_token());
+            };
+        let subject = parse_compound_selector(input)?;
+        selector = combinator(Box::new(selector), Box::new(subject));
+    }
+}
+
+/// Parse an optional type or universal selector followed by subclass selectors,
+/// with no whitespace in between.
+fn parse_compound_selector<'i>(input: &mut Parser<'i, '_>) -> Result<Selector, ParseError<'i, ()>> {
+    let mut selectors = Vec::new();
+    input.skip_whitespace();
+    if input.try_parse(|input| input.expect_delim('*')).is_ok() {
+        selectors.push(Selector::Universal);
+    } else if let Ok(name) = input.try_parse(|input| input.expect_ident_cloned()) {
+        selectors.push(Selector::Tag(name.to_string()));
+    } else {
+        selectors.push(parse_subclass_selector(input)?);
+    }
+    while selectors
+        .last()
+        .and_then(Selector::pseudo_element)
+        .is_none()
+    {
+        let Ok(selector) = input.try_parse(parse_subclass_selector) else {
+            break;
+        };
+        selectors.push(selector);
+    }
+    Ok(if selectors.len() == 1 {
+        selectors.remove(0)
+    } else {
+        Selector::Compound(selectors)
+    })
+}
+
+/// `.class`, `#id`, `[attribute]`, `:pseudo-class` or `::pseudo-element`, starting at the
+/// current token even if it's whitespace. `:before` and `:after` are accepted as the
+/// legacy spelling of the pseudo-elements.
+fn parse_subclass_selector<'i>(input: &mut Parser<'i, '_>) -> Result<Selector, ParseError<'i, ()>> {
+    match input.next_including_whitespace()?.clone() {
+        Token::IDHash(id) => Ok(Selector::Id(id.to_string())),
+        Token::SquareBracketBlock => input.parse_nested_block(parse_attribute_selector),
+        Token::Delim('.') => match input.next_including_whitespace()?.clone() {
+            Token::Ident(class) => Ok(Selector::Class(class.to_string())),
+            token => Err(input.new_unexpected_token_error(token)),
+        },
+        Token::Colon => match input.next_including_whitespace()?.clone() {
+            Token::Ident(name) => match_ignore_ascii_case! { &name,
+                "hover" => Ok(Selector::PseudoClass(PseudoClass::Hover)),
+                "active" => Ok(Selector::PseudoClass(PseudoClass::Active)),
+                "focus" => Ok(Selector::PseudoClass(PseudoClass::Focus)),
+                "first-child" => Ok(Selector::NthChild(Nth::FIRST)),
+                "last-child" => Ok(Selector::NthLastChild(Nth::FIRST)),
+                "before" => Ok(Selector::PseudoElement(PseudoElement::Before)),
+                "after" => Ok(Selector::PseudoElement(PseudoElement::After)),
+                _ => Err(input.new_unexpected_token_error(Token::Ident(name))),
+            },
+            Token::Colon => match input.next_including_whitespace()?.clone() {
+                Token::Ident(name) => match_ignore_ascii_case! { &name,
+                    "before" => Ok(Selector::PseudoElement(PseudoElement::Before)),
+                    "after" => Ok(Selector::PseudoElement(PseudoElement::After)),
+                    _ => Err(input.new_unexpected_token_error(Token::Ident(name))),
+                },
+                token => Err(input.new_unexpected_token_error(token)),
+            },
+            Token::Function(name) => match_ignore_ascii_case! { &name,
+                "nth-child" => input.parse_nested_block(|input| Ok(Selector::NthChild(parse_an_plus_b(input)?))),
+                "nth-last-child" => input.parse_nested_block(|input| Ok(Selector::NthLastChild(parse_an_plus_b(input)?))),
+                _ => Err(input.new_unexpected_token_error(Token::Function(name))),
+            },
+            token => Err(input.new_unexpected_token_error(token)),
+        },
+        token => Err(input.new_unexpected_token_error(token)),
+    }
+}
+
+/// The `An+B` argument of `:nth-child()`, including the `odd` and `even` keywords.
+fn parse_an_plus_b<'i>(input: &mut Parser<'i, '_>) -> Result<Nth, ParseError<'i, ()>> {
+    let (a, b) = parse_nth(input)?;
+    input.expect_exhausted()?;
+    Ok(Nth { a, b })
+}
+
+/// A top-level rule: a style rule, an `@layer` statement or block, an `@media` block,
+/// an `@keyframes` block, an `@import` statement or an `@font-face` block.
+pub enum CssRule {
+    Style(Rule),
+    /// Layers declared in order, and the rules of a layer block (empty for a statement).
+    Layer(Vec<String>, Vec<Rule>),
+    /// Rules of an `@media` block, each carrying the block's condition.
+    Media(Vec<Rule>),
+    Keyframes(Keyframes),
+    Import(StyleImport),
+    FontFace(FontFace),
+}
+
+/// Prelude of a supported at-rule.
+pub enum AtRulePrelude {
+    /// Names of the layers, e.g. `base, theme` in `@layer base, theme;`
+    Layer(Vec<String>),
+    Media(MediaQueryList),
+    /// Name of the animation defined by an `@keyframes` block.
+    Keyframes(String),
+    /// URL of an `@import` rule, and the byte offset where its prelude ends.
+    Import(String, usize),
+    FontFace,
 }
 
 /// CSS Parser implementation
 pub struct CssParser {
-    // We can add state here if needed
+    pub(super) diagnostics: Vec<CssDiagnostic>,
 }
 
 impl CssParser {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            diagnostics: Vec::new(),
+        }
     }
 }
 
 impl<'i> QualifiedRuleParser<'i> for CssParser {
-    type Prelude = Selector;
-    type QualifiedRule = Rule;
+    type Prelude = Vec<Selector>;
+    type QualifiedRule = CssRule;
     type Error = ();
 
     fn parse_prelude<'t>(
         &mut self,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
-        // Parse selector - for now we'll support simple class and tag selectors
-        if input.try_parse(|input| input.expect_delim('.')).is_ok() {
-            let class_name = input.expect_ident()?;
-            Ok(Selector::Class(class_name.to_string()))
-        } else {
-            let name = input.expect_ident()?;
-            Ok(Selector::Tag(name.to_string()))
-        }
+        // As in CSS, one invalid selector invalidates the whole list
+        input.parse_comma_separated(parse_complex_selector)
     }
 
     fn parse_block<'t>(
         &mut self,
         prelude: Self::Prelude,
-        _start: &ParserState,
+        start: &ParserState,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
-        let mut declarations = Vec::new();
-        let mut declaration_parser = StyleDeclarationParser::new();
-
-        let parser = RuleBodyParser::new(input, &mut declaration_parser);
-        for item in parser {
-            match item {
-                Ok(declaration) => declarations.push(declaration),
-                Err(err) => {
-                    eprintln!("Declaration parsing error: {:?}", err);
-                }
-            }
-        }
+        let (declarations, important_declarations, mut source) =
+            parse_declaration_block(input, &mut self.diagnostics);
+        source.span = source_span(start, input);
 
-        Ok(Rule {
-            selector: prelude,
+        Ok(CssRule::Style(Rule {
+            selectors: prelude,
             declarations,
-        })
+            important_declarations,
+            layer: None,
+            media: None,
+            scope: None,
+            origin: StyleOrigin::Author,
+            source: Some(source),
+        }))
     }
 }
 
 impl<'i> AtRuleParser<'i> for CssParser {
-    type Prelude = ();
-    type AtRule = Rule;
+    type Prelude = AtRulePrelude;
+    type AtRule = CssRule;
     type Error = ();
+
+    fn parse_prelude<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        if name.eq_ignore_ascii_case("media") {
+            let media = parse_media_query_list(input, &mut self.diagnostics);
+            return Ok(AtRulePrelude::Media(media));
+        }
+        if name.eq_ignore_ascii_case("keyframes") {
+            return Ok(AtRulePrelude::Keyframes(parse_keyframes_name(input)?));
+        }
+        if name.eq_ignore_ascii_case("import") {
+            let url = parse_import_url(input)?;
+            return Ok(AtRulePrelude::Import(url, input.position().byte_index()));
+        }
+        if name.eq_ignore_ascii_case("font-face") {
+            input.expect_exhausted()?;
+            return Ok(AtRulePrelude::FontFace);
+        }
+        if !name.eq_ignore_ascii_case("layer") {
+            return Err(input.new_error(BasicParseErrorKind::AtRuleInvalid(name)));
+        }
+        if input.is_exhausted() {
+            return Ok(AtRulePrelude::Layer(Vec::new()));
+        }
+        let layers = input.parse_comma_separated(|input| {
+            // Sub-layer names like `theme.dark` are kept as a single flat name.
+            let mut layer = input.expect_ident()?.to_string();
+            while input.try_parse(|input| input.expect_delim('.')).is_ok() {
+                layer.push('.');
+                layer.push_str(input.expect_ident()?);
+            }
+            Ok(layer)
+        })?;
+        Ok(AtRulePrelude::Layer(layers))
+    }
+
+    fn rule_without_block(
+        &mut self,
+        prelude: Self::Prelude,
+        start: &ParserState,
+    ) -> Result<Self::AtRule, ()> {
+        match prelude {
+            AtRulePrelude::Layer(layers) if !layers.is_empty() => {
+                Ok(CssRule::Layer(layers, Vec::new()))
+            }
+            AtRulePrelude::Import(url, end) => {
+                let location = start.source_location();
+                Ok(CssRule::Import(StyleImport {
+                    url,
+                    span: SourceSpan {
+                        start: start.position().byte_index(),
+                        end,
+                        line: location.line + 1,
+                        column: location.column,
+                    },
+                }))
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: Self::Prelude,
+        start: &ParserState,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::AtRule, ParseError<'i, Self::Error>> {
+        let layers = match prelude {
+            AtRulePrelude::Layer(layers) => layers,
+            AtRulePrelude::Media(media) => return Ok(self.parse_media_block(media, start, input)),
+            AtRulePrelude::Keyframes(name) => return Ok(self.parse_keyframes_block(name, input)),
+            AtRulePrelude::Import(..) => return Err(input.new_custom_error(())),
+            AtRulePrelude::FontFace => return self.parse_font_face_block(start, input),
+        };
+        let [name] = layers.as_slice() else {
+            if layers.is_empty() {
+                self.diagnostics.push(CssDiagnostic::new(
+                    start.source_location(),
+                    source_range(start, input),
+                    CssDiagnosticKind::Unsupported {
+                        source: "@layer".to_string(),
+                        message: "anonymous layers are not supported; name the layer",
+                    },
+                ));
+            }
+            return Err(input.new_custom_error(()));
+        };
+
+        let mut rules = Vec::new();
+        for rule in parse_rule_list(input, &mut *self) {
+            match rule {
+                Ok(CssRule::Style(mut rule)) => {
+                    rule.layer = Some(name.clone());
+                    rules.push(rule);
+                }
+                Ok(CssRule::Media(media_rules)) => {
+                    for mut rule in media_rules {
+                        rule.layer = Some(name.clone());
+                        rules.push(rule);
+                    }
+                }
+                Ok(CssRule::Layer(nested, _)) => {
+                    self.diagnostics.push(CssDiagnostic::new(
+                        start.source_location(),
+                        source_range(start, input),
+                        CssDiagnosticKind::Unsupported {
+                            source: format!("@layer {}", nested.join(", ")),
+                            message: "layers nested in a layer block are ignored",
+                        },
+                    ));
+                }
+                Ok(CssRule::Keyframes(keyframes)) => {
+                    self.diagnostics.push(CssDiagnostic::new(
+                        start.source_location(),
+                        source_range(start, input),
+                        CssDiagnosticKind::Unsupported {
+                            source: format!("@keyframes {}", keyframes.name),
+                            message: "keyframes in a layer block are ignored",
+                        },
+                    ));
+                }
+                Ok(CssRule::Import(import)) => {
+                    self.diagnostics.push(CssDiagnostic::new(
+                        start.source_location(),
+                        source_range(start, input),
+                        CssDiagnosticKind::Unsupported {
+                            source: format!("@import \"{}\"", import.url),
+                            message: "@import in a layer block is ignored",
+                        },
+                    ));
+                }
+                Ok(CssRule::FontFace(_)) => {
+                    self.diagnostics.push(CssDiagnostic::new(
+                        start.source_location(),
+                        source_range(start, input),
+                        CssDiagnosticKind::Unsupported {
+                            source: "@font-face".to_string(),
+                            message: "@font-face in a layer block is ignored",
+                        },
+                    ));
+                }
+                Err(diagnostic) => self.diagnostics.push(diagnostic),
+            }
+        }
+        Ok(CssRule::Layer(layers, rules))
+    }
+}
+
+impl CssParser {
+    /// Parse the rules of an `@media` block, tagging each with the block's condition.
+    fn parse_media_block<'i>(
+        &mut self,
+        media: MediaQueryList,
+        start: &ParserState,
+        input: &mut Parser<'i, '_>,
+    ) -> CssRule {
+        let mut rules = Vec::new();
+        for rule in parse_rule_list(input, &mut *self) {
+            match rule {
+                Ok(CssRule::Style(mut rule)) => {
+                    rule.media = Some(media.clone());
+                    rules.push(rule);
+                }
+                Ok(
+                    CssRule::Layer(..)
+                    | CssRule::Media(_)
+                    | CssRule::Keyframes(_)
+                    | CssRule::Import(_)
+                    | CssRule::FontFace(_),
+                ) => {
+                    self.diagnostics.push(CssDiagnostic::new(
+                        start.source_location(),
+                        source_range(start, input),
+                        CssDiagnosticKind::Unsupported {
+                            source: "@media".to_string(),
+                            message: "at-rules nested in a media block are ignored",
+                        },
+                    ));
+                }
+                Err(diagnostic) => self.diagnostics.push(diagnostic),
+            }
+        }
+        CssRule::Media(rules)
+    }
 }
 
+/// Parse the declarations of a block into normal and `!important` ones, reporting those
+/// that are rejected. The returned source has the declaration spans but no rule span.
+pub(super) fn parse_declaration_block(
+    input: &mut Parser<'_, '_>,
+    diagnostics: &mut Vec<CssDiagnostic>,
+) -> (Vec<Style>, Vec<Style>, RuleSource) {
+    let mut declarations = Vec::new();
+    let mut important_declarations = Vec::new();
+    let mut source = RuleSource::default();
+    let mut declaration_parser = StyleDeclarationParser::new();
+
+    let mut parser = RuleBodyParser::new(input, &mut declaration_parser);
+    while let Some(item) = parser.next() {
+        match item {
+            Ok((declaration, false, span)) => {
+                declarations.push(declaration);
+                source.declarations.push(span);
+            }
+            Ok((declaration, true, span)) => {
+                important_declarations.push(declaration);
+                source.important_declarations.push(span);
+            }
+            Err((err, source)) => {
+                let unknown_property = matches!(err.kind, ParseErrorKind::Custom(()));
+                diagnostics.push(CssDiagnostic::rejected_declaration(
+                    err.location,
+                    unknown_property,
+                    source,
+                    parser.input.position().byte_index(),
+                ));
+            }
+        }
+    }
+    diagnostics.append(&mut declaration_parser.diagnostics);
+    (declarations, important_declarations, source)
+}
+
+/// The declared style, whether it is marked `!important`, and the property name and
+/// source of the declaration.
+type ParsedDeclaration = (Style, bool, (String, SourceSpan));
+
 /// Declaration parser for style properties
 pub struct StyleDeclarationParser {
-    // State can be added here if needed
+    /// Declarations that parsed but aren't fully implemented.
+    diagnostics: Vec<CssDiagnostic>,
 }
 
 impl StyleDeclarationParser {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            diagnostics: Vec::new(),
+        }
     }
 }
 
 impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
-    type Declaration = Style;
+    type Declaration = ParsedDeclaration;
     type Error = ();
 
     fn parse_value<'t>(
         &mut self,
         name: CowRcStr<'i>,
         input: &mut Parser<'i, 't>,
-        _declaration_start: &ParserState,
+        declaration_start: &ParserState,
     ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+        let property = name.to_string();
+        let style = input.parse_until_before(Delimiter::Bang, |input| {
+            self.parse_property(name, input, declaration_start)
+        })?;
+        let important = input.try_parse(parse_important).is_ok();
+        let span = source_span(declaration_start, input);
+        Ok((style, important, (property, span)))
+    }
+}
+
+impl StyleDeclarationParser {
+    /// Parse a declaration's value, without any `!important` flag.
+    fn parse_property<'i, 't>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+        declaration_start: &ParserState,
+    ) -> Result<Style, ParseError<'i, ()>> {
         let mut style = Style::default();
 
+        // Custom properties keep their raw value; it is only interpreted where used.
+        let value_start = input.state();
+        if name.starts_with("--") {
+            while input.next().is_ok() {}
+            let value = input.slice_from(value_start.position()).trim();
+            style
+                .custom_properties
+                .insert(name.to_string(), value.to_string());
+            return Ok(style);
+        }
+        if references_var(input) {
+            style.var_declaration = Some(VarDeclaration {
+                property: name.to_string(),
+                value: input.slice_from(value_start.position()).trim().to_string(),
+            });
+            return Ok(style);
+        }
+        input.reset(&value_start);
+
+        if let Some(longhands) = css_wide_longhands(&name) {
+            let keyword = input.try_parse(|input| -> Result<_, ParseError<'i, ()>> {
+                let keyword = parse_css_wide_keyword(input)?;
+                input.expect_exhausted()?;
+                Ok(keyword)
+            });
+            if let Ok(keyword) = keyword {
+                for longhand in longhands {
+                    style
+                        .css_wide_keywords
+                        .insert(longhand.to_string(), keyword);
+                }
+                return Ok(style);
+            }
+        }
+
         match name.as_ref() {
             "display" => {
                 let ident = input.expect_ident()?;
                 match ident.as_ref() {
-                    "flex" => style.display = Display::Flex,
+                    "block" => style.display = Some(Display::Block),
+                    "flex" => style.display = Some(Display::Flex),
                     _ => return Err(input.new_error_for_next_token()),
                 }
             }
@@ -128,12 +690,27 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
                 style.color = Some(self.parse_color_value(input)?);
             }
             "background" => {
-                // only support color for now
-                style.background_color = Some(self.parse_color_value(input)?);
+                self.parse_background_shorthand(input, &mut style)?;
             }
             "background-color" => {
                 style.background_color = Some(self.parse_color_value(input)?);
             }
+            "background-image" => {
+                style.background_image = Some(input.parse_comma_separated(parse_background_image)?);
+            }
+            "background-position" => {
+                style.background_position = Some(
+                    input.parse_comma_separated(|input| self.parse_background_position(input))?,
+                );
+            }
+            "background-size" => {
+                style.background_size =
+                    Some(input.parse_comma_separated(|input| self.parse_background_size(input))?);
+            }
+            "background-repeat" => {
+                style.background_repeat =
+                    Some(input.parse_comma_separated(parse_background_repeat)?);
+            }
             "border-color" => {
                 style.border_color = Directional::set_all(Some(self.parse_color_value(input)?));
             }
@@ -186,6 +763,38 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
             "border" => {
                 self.parse_border_shorthand(input, &mut style)?;
             }
+            "border-top" => {
+                self.parse_border_side_shorthand(
+                    input,
+                    &mut style.border_width.top,
+                    &mut style.border_style.top,
+                    &mut style.border_color.top,
+                )?;
+            }
+            "border-right" => {
+                self.parse_border_side_shorthand(
+                    input,
+                    &mut style.border_width.right,
+                    &mut style.border_style.right,
+                    &mut style.border_color.right,
+                )?;
+            }
+            "border-bottom" => {
+                self.parse_border_side_shorthand(
+                    input,
+                    &mut style.border_width.bottom,
+                    &mut style.border_style.bottom,
+                    &mut style.border_color.bottom,
+                )?;
+            }
+            "border-left" => {
+                self.parse_border_side_shorthand(
+                    input,
+                    &mut style.border_width.left,
+                    &mut style.border_style.left,
+                    &mut style.border_color.left,
+                )?;
+            }
             "border-radius" => {
                 self.parse_border_radius_shorthand(input, &mut style)?;
             }
@@ -201,6 +810,14 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
             "border-bottom-left-radius" => {
                 self.parse_border_corner_radius(input, &mut style.border_radius.bottom_left)?;
             }
+            "direction" => {
+                let ident = input.expect_ident()?;
+                style.direction = Some(match ident.as_ref() {
+                    "ltr" => TextDirection::Ltr,
+                    "rtl" => TextDirection::Rtl,
+                    _ => return Err(input.new_error_for_next_token()),
+                });
+            }
             "box-sizing" => {
                 let ident = input.expect_ident()?;
                 style.box_sizing = Some(match ident.as_ref() {
@@ -209,37 +826,160 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
                     _ => return Err(input.new_error_for_next_token()),
                 });
             }
+            "scroll-behavior" => {
+                let ident = input.expect_ident()?;
+                style.scroll_behavior = Some(match ident.as_ref() {
+                    "auto" => ScrollBehavior::Auto,
+                    "smooth" => ScrollBehavior::Smooth,
+                    _ => return Err(input.new_error_for_next_token()),
+                });
+            }
+            "contain" => {
+                let mut contain = Contain::default();
+                let first = input.expect_ident_cloned()?;
+                match first.as_ref() {
+                    "none" => {}
+                    "content" => {
+                        contain.layout = true;
+                        contain.paint = true;
+                    }
+                    _ => {
+                        // Size containment isn't implemented; `style` has no effect
+                        // without counters or quotes, so it is accepted and ignored.
+                        let mut keyword = Some(first);
+                        while let Some(ident) = keyword {
+                            match ident.as_ref() {
+                                "layout" => contain.layout = true,
+                                "paint" => contain.paint = true,
+                                "style" => {}
+                                _ => return Err(input.new_error_for_next_token()),
+                            }
+                            keyword = input.try_parse(|input| input.expect_ident_cloned()).ok();
+                        }
+                    }
+                }
+                if !input.is_exhausted() {
+                    return Err(input.new_error_for_next_token());
+                }
+                style.contain = Some(contain);
+            }
+            "content" => {
+                style.content = Some(self.parse_content_value(input)?);
+            }
+            "content-visibility" => {
+                let ident = input.expect_ident()?;
+                style.content_visibility = Some(match ident.as_ref() {
+                    "visible" => ContentVisibility::Visible,
+                    "auto" => ContentVisibility::Auto,
+                    "hidden" => ContentVisibility::Hidden,
+                    _ => return Err(input.new_error_for_next_token()),
+                });
+            }
+            "overflow" => {
+                let x = self.parse_overflow_value(input)?;
+                let y = input
+                    .try_parse(|input| self.parse_overflow_value(input))
+                    .unwrap_or(x);
+                style.overflow_x = Some(x);
+                style.overflow_y = Some(y);
+            }
+            "overflow-x" => {
+                style.overflow_x = Some(self.parse_overflow_value(input)?);
+            }
+            "overflow-y" => {
+                style.overflow_y = Some(self.parse_overflow_value(input)?);
+            }
+            "scroll-snap-type" => {
+                let ident = input.expect_ident()?;
+                let axis = match ident.as_ref() {
+                    "none" => ScrollSnapAxis::None,
+                    "x" | "inline" => ScrollSnapAxis::X,
+                    "y" | "block" => ScrollSnapAxis::Y,
+                    "both" => ScrollSnapAxis::Both,
+                    _ => return Err(input.new_error_for_next_token()),
+                };
+                let strictness = match input.try_parse(|input| input.expect_ident_cloned()) {
+                    Ok(ident) => match ident.as_ref() {
+                        "mandatory" => ScrollSnapStrictness::Mandatory,
+                        "proximity" => ScrollSnapStrictness::Proximity,
+                        _ => return Err(input.new_error_for_next_token()),
+                    },
+                    Err(_) => ScrollSnapStrictness::Proximity,
+                };
+                style.scroll_snap_type = Some(ScrollSnapType { axis, strictness });
+            }
+            "scroll-snap-align" => {
+                let ident = input.expect_ident()?;
+                style.scroll_snap_align = Some(match ident.as_ref() {
+                    "none" => ScrollSnapAlign::None,
+                    "start" => ScrollSnapAlign::Start,
+                    "center" => ScrollSnapAlign::Center,
+                    "end" => ScrollSnapAlign::End,
+                    _ => return Err(input.new_error_for_next_token()),
+                });
+            }
             "width" => {
                 style.width = Some(self.parse_length_value(input)?);
             }
             "height" => {
                 style.height = Some(self.parse_length_value(input)?);
             }
+            "min-width" | "min-height" | "max-width" | "max-height" => {
+                let is_max = name.starts_with("max-");
+                let limit = if is_max
+                    && input
+                        .try_parse(|input| input.expect_ident_matching("none"))
+                        .is_ok()
+                {
+                    Length::Auto
+                } else {
+                    let start = input.state();
+                    let limit = self.parse_length_value(input)?;
+                    // Maximums are `none` rather than `auto`, and negative limits are invalid
+                    if limit.is_negative() || (is_max && limit == Length::Auto) {
+                        input.reset(&start);
+                        return Err(input.new_error_for_next_token());
+                    }
+                    limit
+                };
+                match name.as_ref() {
+                    "min-width" => style.min_width = Some(limit),
+                    "max-width" => style.max_width = Some(limit),
+                    "min-height" => style.min_height = Some(limit),
+                    _ => style.max_height = Some(limit),
+                }
+            }
+            "aspect-ratio" => {
+                style.aspect_ratio = Some(self.parse_aspect_ratio_value(input)?);
+            }
+            "font" => {
+                self.parse_font_shorthand(input, &mut style)?;
+            }
+            "font-family" => {
+                style.font_family = Some(parse_font_family(input)?);
+            }
+            "font-size" => {
+                style.font_size = Some(self.parse_font_size(input)?);
+            }
+            "font-weight" => {
+                style.font_weight = Some(parse_font_weight(input)?);
+            }
+            "font-style" => {
+                style.font_style = Some(parse_font_style(input)?);
+            }
+            "line-height" => {
+                style.line_height = Some(self.parse_line_height(input)?);
+            }
             "margin" => {
                 // NOTE: We currently implement the physical margin shorthands/sides:
                 // - `margin` (shorthand)
                 // - `margin-top/right/bottom/left`
                 // We do NOT yet implement logical properties like `margin-inline`,
                 // `margin-block`, `margin-inline-start/end`, etc.
-                let first = self.parse_length_value(input)?;
-                let second = input
-                    .try_parse(|input| self.parse_length_value(input))
-                    .unwrap_or(first.clone());
-                let third = input
-                    .try_parse(|input| self.parse_length_value(input))
-                    .unwrap_or(first.clone());
-                let fourth = input
-                    .try_parse(|input| self.parse_length_value(input))
-                    .unwrap_or(second.clone());
-
+                //
                 // Emit per-side declarations so later `margin-left` etc. can override
                 // a single side via Style::merge.
-                style.margin = Directional {
-                    top: Some(first),
-                    right: Some(second),
-                    bottom: Some(third),
-                    left: Some(fourth),
-                };
+                style.margin = self.parse_length_sides(input)?;
             }
             "margin-top" => {
                 style.margin.top = Some(self.parse_length_value(input)?);
@@ -253,26 +993,36 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
             "margin-left" => {
                 style.margin.left = Some(self.parse_length_value(input)?);
             }
+            "position" => {
+                let ident = input.expect_ident()?;
+                style.position = Some(match ident.as_ref() {
+                    "static" => Position::Static,
+                    "relative" => Position::Relative,
+                    "absolute" => Position::Absolute,
+                    "fixed" => Position::Fixed,
+                    _ => return Err(input.new_error_for_next_token()),
+                });
+            }
+            "inset" => {
+                // Same 1-4 value form as `margin`
+                style.inset = self.parse_length_sides(input)?;
+            }
+            "top" => {
+                style.inset.top = Some(self.parse_length_value(input)?);
+            }
+            "right" => {
+                style.inset.right = Some(self.parse_length_value(input)?);
+            }
+            "bottom" => {
+                style.inset.bottom = Some(self.parse_length_value(input)?);
+            }
+            "left" => {
+                style.inset.left = Some(self.parse_length_value(input)?);
+            }
             "padding" => {
-                let first = self.parse_length_value(input)?;
-                let second = input
-                    .try_parse(|input| self.parse_length_value(input))
-                    .unwrap_or(first.clone());
-                let third = input
-                    .try_parse(|input| self.parse_length_value(input))
-                    .unwrap_or(first.clone());
-                let fourth = input
-                    .try_parse(|input| self.parse_length_value(input))
-                    .unwrap_or(second.clone());
-
                 // Emit per-side declarations so later `padding-left` etc. can override
                 // a single side via Style::merge.
-                style.padding = Directional {
-                    top: Some(first),
-                    right: Some(second),
-                    bottom: Some(third),
-                    left: Some(fourth),
-                };
+                style.padding = self.parse_length_sides(input)?;
             }
             "padding-top" => {
                 style.padding.top = Some(self.parse_length_value(input)?);
@@ -287,8 +1037,10 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
                 style.padding.left = Some(self.parse_length_value(input)?);
             }
             "flex" => {
-                let value = input.expect_number()?;
-                style.flex_grow = Some(value as f64);
+                let (grow, shrink, basis) = self.parse_flex_shorthand(input)?;
+                style.flex_grow = Some(grow);
+                style.flex_shrink = Some(shrink);
+                style.flex_basis = Some(basis);
             }
             "flex-direction" => {
                 let ident = input.expect_ident()?;
@@ -318,6 +1070,10 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
                     "space-between" => JustifyContent::SpaceBetween,
                     "space-around" => JustifyContent::SpaceAround,
                     "space-evenly" => JustifyContent::SpaceEvenly,
+                    "start" => JustifyContent::Start,
+                    "end" => JustifyContent::End,
+                    "left" => JustifyContent::Left,
+                    "right" => JustifyContent::Right,
                     _ => return Err(input.new_error_for_next_token()),
                 });
             }
@@ -329,6 +1085,8 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
                     "flex-end" => AlignItems::FlexEnd,
                     "center" => AlignItems::Center,
                     "baseline" => AlignItems::Baseline,
+                    "start" => AlignItems::Start,
+                    "end" => AlignItems::End,
                     _ => return Err(input.new_error_for_next_token()),
                 });
             }
@@ -342,6 +1100,8 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
                     "space-between" => AlignContent::SpaceBetween,
                     "space-around" => AlignContent::SpaceAround,
                     "space-evenly" => AlignContent::SpaceEvenly,
+                    "start" => AlignContent::Start,
+                    "end" => AlignContent::End,
                     _ => return Err(input.new_error_for_next_token()),
                 });
             }
@@ -354,6 +1114,8 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
                     "center" => AlignSelf::Center,
                     "baseline" => AlignSelf::Baseline,
                     "stretch" => AlignSelf::Stretch,
+                    "start" => AlignSelf::Start,
+                    "end" => AlignSelf::End,
                     _ => return Err(input.new_error_for_next_token()),
                 });
             }
@@ -372,40 +1134,130 @@ impl<'i> DeclarationParser<'i> for StyleDeclarationParser {
                 let value = input.expect_number()?;
                 style.order = Some(value as i32);
             }
-            "gap" => {
-                let gap = self.parse_length_value(input)?;
-                style.row_gap = Some(gap);
-                style.column_gap = Some(gap);
+            // `grid-gap` and its longhands are legacy aliases
+            "gap" | "grid-gap" => {
+                let row_gap = self.parse_gap_value(input)?;
+                let column_gap = input
+                    .try_parse(|i| self.parse_gap_value(i))
+                    .unwrap_or(row_gap);
+                style.row_gap = Some(row_gap);
+                style.column_gap = Some(column_gap);
+            }
+            "row-gap" | "grid-row-gap" => {
+                style.row_gap = Some(self.parse_gap_value(input)?);
+            }
+            "column-gap" | "grid-column-gap" => {
+                style.column_gap = Some(self.parse_gap_value(input)?);
+            }
+            "grid-template-columns" => {
+                style.grid_template_columns = Some(self.parse_track_list(input)?);
+            }
+            "grid-template-rows" => {
+                style.grid_template_rows = Some(self.parse_track_list(input)?);
+            }
+            "grid-row-start" => {
+                style.grid_row_start = Some(self.parse_grid_line(input)?);
             }
-            "row-gap" => {
-                style.row_gap = Some(self.parse_length_value(input)?);
+            "grid-row-end" => {
+                style.grid_row_end = Some(self.parse_grid_line(input)?);
             }
-            "column-gap" => {
-                style.column_gap = Some(self.parse_length_value(input)?);
+            "grid-column-start" => {
+                style.grid_column_start = Some(self.parse_grid_line(input)?);
+            }
+            "grid-column-end" => {
+                style.grid_column_end = Some(self.parse_grid_line(input)?);
+            }
+            "grid-row" => {
+                let (start, end) = self.parse_grid_line_pair(input)?;
+                style.grid_row_start = Some(start);
+                style.grid_row_end = Some(end);
+            }
+            "grid-column" => {
+                let (start, end) = self.parse_grid_line_pair(input)?;
+                style.grid_column_start = Some(start);
+                style.grid_column_end = Some(end);
+            }
+            "grid-area" => {
+                let [row_start, column_start, row_end, column_end] = self.parse_grid_area(input)?;
+                style.grid_row_start = Some(row_start);
+                style.grid_column_start = Some(column_start);
+                style.grid_row_end = Some(row_end);
+                style.grid_column_end = Some(column_end);
+            }
+            "transition" => {
+                let transitions = parse_transition(input)?;
+                style.transition_property = Some(
+                    transitions
+                        .iter()
+                        .filter_map(|transition| transition.property.clone())
+                        .collect(),
+                );
+                style.transition_duration = Some(
+                    transitions
+                        .iter()
+                        .map(|transition| transition.duration)
+                        .collect(),
+                );
+                style.transition_timing_function = Some(
+                    transitions
+                        .iter()
+                        .map(|transition| transition.timing_function)
+                        .collect(),
+                );
+                style.transition_delay = Some(
+                    transitions
+                        .iter()
+                        .map(|transition| transition.delay)
+                        .collect(),
+                );
+            }
+            "transition-property" => {
+                style.transition_property = Some(parse_transition_property_list(input)?);
+            }
+            "transition-duration" => {
+                style.transition_duration = Some(parse_time_list(input, false)?);
+            }
+            "transition-timing-function" => {
+                style.transition_timing_function = Some(parse_timing_function_list(input)?);
+            }
+            "transition-delay" => {
+                style.transition_delay = Some(parse_time_list(input, true)?);
             }
             _ => {
-                // Skip unknown properties
-                return Err(input.new_error_for_next_token());
+                // Skip unknown properties; the custom error tells them apart from bad values
+                return Err(input.new_custom_error(()));
             }
         }
 
+        if let Some(message) = unsupported_declaration(&style) {
+            let source = input.slice_from(declaration_start.position());
+            self.diagnostics.push(CssDiagnostic::new(
+                declaration_start.source_location(),
+                source_range(declaration_start, input),
+                CssDiagnosticKind::Unsupported {
+                    source: source.trim().to_string(),
+                    message,
+                },
+            ));
+        }
+
         Ok(style)
     }
 }
 
 impl<'i> AtRuleParser<'i> for StyleDeclarationParser {
     type Prelude = ();
-    type AtRule = Style;
+    type AtRule = ParsedDeclaration;
     type Error = ();
 }
 
 impl<'i> QualifiedRuleParser<'i> for StyleDeclarationParser {
     type Prelude = ();
-    type QualifiedRule = Style;
+    type QualifiedRule = ParsedDeclaration;
     type Error = ();
 }
 
-impl<'i> RuleBodyItemParser<'i, Style, ()> for StyleDeclarationParser {
+impl<'i> RuleBodyItemParser<'i, ParsedDeclaration, ()> for StyleDeclarationParser {
     fn parse_qualified(&self) -> bool {
         false
     }
@@ -0,0 +1,95 @@
+use crate::animation::Easing;
+use crate::css_parser::{fixtures::declarations, parse_css};
+use crate::style::TransitionProperty;
+
+fn named(name: &str) -> TransitionProperty {
+    TransitionProperty::Named(name.to_string())
+}
+
+#[test]
+fn test_parse_transition_shorthand() {
+    let styles = declarations(
+        ".a {
+            transition: width 2s ease-in 100ms, cubic-bezier(0.25, -0.5, 0.75, 1.5) 0.5s Color;
+            transition: 1s;
+            transition: none;
+        }",
+    );
+    assert_eq!(styles.len(), 3);
+
+    assert_eq!(
+        styles[0].transition_property,
+        Some(vec![named("width"), named("color")])
+    );
+    assert_eq!(styles[0].transition_duration, Some(vec![2.0, 0.5]));
+    assert_eq!(
+        styles[0].transition_timing_function,
+        Some(vec![
+            Easing::EaseIn,
+            Easing::CubicBezier(0.25, -0.5, 0.75, 1.5)
+        ])
+    );
+    assert_eq!(styles[0].transition_delay, Some(vec![0.1, 0.0]));
+
+    // Omitted values take their initial values
+    assert_eq!(
+        styles[1].transition_property,
+        Some(vec![TransitionProperty::All])
+    );
+    assert_eq!(styles[1].transition_duration, Some(vec![1.0]));
+    assert_eq!(
+        styles[1].transition_timing_function,
+        Some(vec![Easing::Ease])
+    );
+    assert_eq!(styles[1].transition_delay, Some(vec![0.0]));
+
+    assert_eq!(styles[2].transition_property, Some(Vec::new()));
+}
+
+#[test]
+fn test_parse_transition_longhands() {
+    let styles = declarations(
+        ".a {
+            transition-property: opacity, all;
+            transition-property: none;
+            transition-duration: 250ms, 1s;
+            transition-timing-function: linear, ease-in-out;
+            transition-delay: -1s, 0s;
+        }",
+    );
+    assert_eq!(styles.len(), 5);
+    assert_eq!(
+        styles[0].transition_property,
+        Some(vec![named("opacity"), TransitionProperty::All])
+    );
+    assert_eq!(styles[1].transition_property, Some(Vec::new()));
+    assert_eq!(styles[2].transition_duration, Some(vec![0.25, 1.0]));
+    assert_eq!(
+        styles[3].transition_timing_function,
+        Some(vec![Easing::Linear, Easing::EaseInOut])
+    );
+    assert_eq!(styles[4].transition_delay, Some(vec![-1.0, 0.0]));
+}
+
+#[test]
+fn test_reject_invalid_transitions() {
+    for declaration in [
+        "transition: none, width 1s",
+        "transition: width 1s 2s 3s",
+        "transition: width height",
+        "transition: -1s",
+        "transition: 1",
+        "transition-duration: -1s",
+        "transition-duration: 0",
+        "transition-property: width, none",
+        "transition-timing-function: cubic-bezier(2, 0, 0, 1)",
+        "transition-timing-function: steps(4)",
+    ] {
+        let sheet = parse_css(&format!(".a {{ {}; }}", declaration)).unwrap();
+        assert!(
+            sheet.rules[0].declarations.is_empty(),
+            "{} should be rejected",
+            declaration
+        );
+    }
+}
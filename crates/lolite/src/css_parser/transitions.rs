@@ -0,0 +1,161 @@
+use crate::animation::Easing;
+use crate::style::TransitionProperty;
+use cssparser::{match_ignore_ascii_case, ParseError, Parser, Token};
+
+/// One transition of the `transition` shorthand, with omitted values defaulted.
+pub(crate) struct SingleTransition {
+    /// `None` for `none`, which is only valid as the sole transition.
+    pub property: Option<TransitionProperty>,
+    /// In seconds.
+    pub duration: f64,
+    pub timing_function: Easing,
+    /// In seconds.
+    pub delay: f64,
+}
+
+/// Parse the `transition` shorthand: comma-separated transitions of a property, a
+/// duration, a timing function and a delay in any order, the delay after the duration.
+pub(crate) fn parse_transition<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<Vec<SingleTransition>, ParseError<'i, ()>> {
+    let transitions = input.parse_comma_separated(parse_single_transition)?;
+    if transitions.len() > 1 && transitions.iter().any(|t| t.property.is_none()) {
+        return Err(input.new_custom_error(()));
+    }
+    Ok(transitions)
+}
+
+fn parse_single_transition<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<SingleTransition, ParseError<'i, ()>> {
+    let mut property = None;
+    let mut times = Vec::new();
+    let mut timing_function = None;
+    loop {
+        if times.len() < 2 {
+            if let Ok(time) = input.try_parse(parse_time) {
+                times.push(time);
+                continue;
+            }
+        }
+        if timing_function.is_none() {
+            if let Ok(easing) = input.try_parse(parse_timing_function) {
+                timing_function = Some(easing);
+                continue;
+            }
+        }
+        if property.is_none() {
+            if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+                property = Some(None);
+                continue;
+            }
+            if let Ok(name) = input.try_parse(parse_transition_property) {
+                property = Some(Some(name));
+                continue;
+            }
+        }
+        break;
+    }
+
+    if property.is_none() && times.is_empty() && timing_function.is_none() {
+        return Err(input.new_error_for_next_token());
+    }
+    let duration = times.first().copied().unwrap_or(0.0);
+    if duration < 0.0 {
+        return Err(input.new_custom_error(()));
+    }
+    Ok(SingleTransition {
+        property: property.unwrap_or(Some(TransitionProperty::All)),
+        duration,
+        timing_function: timing_function.unwrap_or(Easing::Ease),
+        delay: times.get(1).copied().unwrap_or(0.0),
+    })
+}
+
+/// Parse `transition-property`: `none`, or a list of `all` and property names.
+pub(crate) fn parse_transition_property_list<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<Vec<TransitionProperty>, ParseError<'i, ()>> {
+    if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+        return Ok(Vec::new());
+    }
+    input.parse_comma_separated(parse_transition_property)
+}
+
+fn parse_transition_property<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<TransitionProperty, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let name = input.expect_ident()?.to_ascii_lowercase();
+    match name.as_str() {
+        "all" => Ok(TransitionProperty::All),
+        "none" | "initial" | "inherit" | "unset" | "default" => Err(location.new_custom_error(())),
+        _ => Ok(TransitionProperty::Named(name)),
+    }
+}
+
+/// Parse a list of times, as in `transition-duration`, in seconds.
+pub(crate) fn parse_time_list<'i>(
+    input: &mut Parser<'i, '_>,
+    allow_negative: bool,
+) -> Result<Vec<f64>, ParseError<'i, ()>> {
+    input.parse_comma_separated(|input| {
+        let location = input.current_source_location();
+        let time = parse_time(input)?;
+        if time < 0.0 && !allow_negative {
+            return Err(location.new_custom_error(()));
+        }
+        Ok(time)
+    })
+}
+
+/// Parse a `<time>` in seconds; unlike lengths, a unitless zero isn't one.
+fn parse_time<'i>(input: &mut Parser<'i, '_>) -> Result<f64, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    match input.next()?.clone() {
+        Token::Dimension { value, unit, .. } => match_ignore_ascii_case! { &unit,
+            "s" => Ok(value as f64),
+            "ms" => Ok(value as f64 / 1000.0),
+            _ => Err(location.new_custom_error(())),
+        },
+        token => Err(location.new_unexpected_token_error(token)),
+    }
+}
+
+/// Parse a list of timing functions, as in `transition-timing-function`.
+pub(crate) fn parse_timing_function_list<'i>(
+    input: &mut Parser<'i, '_>,
+) -> Result<Vec<Easing>, ParseError<'i, ()>> {
+    input.parse_comma_separated(parse_timing_function)
+}
+
+/// Parse an easing keyword or `cubic-bezier(x1, y1, x2, y2)`, whose x coordinates must
+/// be between 0 and 1.
+fn parse_timing_function<'i>(input: &mut Parser<'i, '_>) -> Result<Easing, ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    match input.next()?.clone() {
+        Token::Ident(keyword) => match_ignore_ascii_case! { &keyword,
+            "linear" => Ok(Easing::Linear),
+            "ease" => Ok(Easing::Ease),
+            "ease-in" => Ok(Easing::EaseIn),
+            "ease-out" => Ok(Easing::EaseOut),
+            "ease-in-out" => Ok(Easing::EaseInOut),
+            _ => Err(location.new_unexpected_token_error(Token::Ident(keyword))),
+        },
+        Token::Function(name) if name.eq_ignore_ascii_case("cubic-bezier") => input
+            .parse_nested_block(|input| {
+                let x1 = input.expect_number()? as f64;
+                input.expect_comma()?;
+                let y1 = input.expect_number()? as f64;
+                input.expect_comma()?;
+                let x2 = input.expect_number()? as f64;
+                input.expect_comma()?;
+                let y2 = input.expect_number()? as f64;
+                if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) {
+                    return Err(location.new_custom_error(()));
+                }
+                Ok(Easing::CubicBezier(x1, y1, x2, y2))
+            }),
+        token => Err(location.new_unexpected_token_error(token)),
+    }
+}
@@ -1,5 +1,9 @@
 use crate::css_parser::parse_css;
-use crate::style::{BoxSizing, Length, Radius, Selector};
+use crate::style::{
+    AspectRatio, BoxSizing, Contain, ContentVisibility, Directional, Length, Radius,
+    ScrollBehavior, ScrollSnapAlign, ScrollSnapAxis, ScrollSnapStrictness, ScrollSnapType,
+    Selector,
+};
 
 #[test]
 fn test_parse_lengths() {
@@ -26,6 +30,56 @@ fn test_parse_lengths() {
     }
 }
 
+#[test]
+fn test_parse_font_relative_units() {
+    let stylesheet = parse_css(".f { width: 2em; height: 1.5rem; }").expect("Failed to parse CSS");
+    let declarations = &stylesheet.rules[0].declarations;
+    assert!(declarations
+        .iter()
+        .any(|d| d.width == Some(Length::Em(2.0))));
+    assert!(declarations
+        .iter()
+        .any(|d| d.height == Some(Length::Rem(1.5))));
+}
+
+#[test]
+fn test_parse_font_size() {
+    let css = ".a { font-size: 1.25rem; } .b { font-size: -2px; } .c { font-size: auto; }";
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    let font_size = |index: usize| {
+        stylesheet.rules[index]
+            .declarations
+            .iter()
+            .find_map(|d| d.font_size)
+    };
+
+    assert_eq!(font_size(0), Some(Length::Rem(1.25)));
+    assert_eq!(font_size(1), None);
+    assert_eq!(font_size(2), None);
+}
+
+#[test]
+fn test_parse_viewport_units() {
+    let stylesheet = parse_css(".v { width: 100vw; height: 50vh; }").expect("Failed to parse CSS");
+    let declarations = &stylesheet.rules[0].declarations;
+    assert!(declarations
+        .iter()
+        .any(|d| d.width == Some(Length::Vw(100.0))));
+    assert!(declarations
+        .iter()
+        .any(|d| d.height == Some(Length::Vh(50.0))));
+
+    let stylesheet =
+        parse_css(".v { width: 10vmin; height: 20vmax; }").expect("Failed to parse CSS");
+    let declarations = &stylesheet.rules[0].declarations;
+    assert!(declarations
+        .iter()
+        .any(|d| d.width == Some(Length::Vmin(10.0))));
+    assert!(declarations
+        .iter()
+        .any(|d| d.height == Some(Length::Vmax(20.0))));
+}
+
 #[test]
 fn test_parse_box_sizing() {
     let css = r#"
@@ -37,14 +91,14 @@ fn test_parse_box_sizing() {
     assert_eq!(stylesheet.rules.len(), 2);
 
     let a = &stylesheet.rules[0];
-    assert_eq!(a.selector, Selector::Class("a".to_string()));
+    assert_eq!(a.selectors, vec![Selector::Class("a".to_string())]);
     assert!(a
         .declarations
         .iter()
         .any(|d| d.box_sizing == Some(BoxSizing::BorderBox)));
 
     let b = &stylesheet.rules[1];
-    assert_eq!(b.selector, Selector::Class("b".to_string()));
+    assert_eq!(b.selectors, vec![Selector::Class("b".to_string())]);
     assert!(b
         .declarations
         .iter()
@@ -99,7 +153,7 @@ fn test_border_radius_corner_properties() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("corners".to_string()));
+    assert_eq!(rule.selectors, vec![Selector::Class("corners".to_string())]);
 
     assert!(rule
         .declarations
@@ -155,7 +209,7 @@ fn test_border_radius_corner_two_values() {
     assert_eq!(stylesheet.rules.len(), 1);
 
     let rule = &stylesheet.rules[0];
-    assert_eq!(rule.selector, Selector::Class("corner".to_string()));
+    assert_eq!(rule.selectors, vec![Selector::Class("corner".to_string())]);
 
     assert!(rule
         .declarations
@@ -244,7 +298,7 @@ fn test_parse_margin_sides() {
     assert_eq!(stylesheet.rules.len(), 2);
 
     let m_rule = &stylesheet.rules[0];
-    assert_eq!(m_rule.selector, Selector::Class("m".to_string()));
+    assert_eq!(m_rule.selectors, vec![Selector::Class("m".to_string())]);
     assert!(m_rule
         .declarations
         .iter()
@@ -263,7 +317,10 @@ fn test_parse_margin_sides() {
         .any(|d| matches!(d.margin.bottom, Some(Length::Px(15.0)))));
 
     let auto_rule = &stylesheet.rules[1];
-    assert_eq!(auto_rule.selector, Selector::Class("auto_left".to_string()));
+    assert_eq!(
+        auto_rule.selectors,
+        vec![Selector::Class("auto_left".to_string())]
+    );
     assert!(auto_rule
         .declarations
         .iter()
@@ -289,7 +346,7 @@ fn test_parse_padding_sides() {
     assert_eq!(stylesheet.rules.len(), 2);
 
     let p_rule = &stylesheet.rules[0];
-    assert_eq!(p_rule.selector, Selector::Class("p".to_string()));
+    assert_eq!(p_rule.selectors, vec![Selector::Class("p".to_string())]);
     assert!(p_rule
         .declarations
         .iter()
@@ -308,9 +365,166 @@ fn test_parse_padding_sides() {
         .any(|d| matches!(d.padding.bottom, Some(Length::Px(15.0)))));
 
     let auto_rule = &stylesheet.rules[1];
-    assert_eq!(auto_rule.selector, Selector::Class("auto_left".to_string()));
+    assert_eq!(
+        auto_rule.selectors,
+        vec![Selector::Class("auto_left".to_string())]
+    );
     assert!(auto_rule
         .declarations
         .iter()
         .any(|d| matches!(d.padding.left, Some(Length::Auto))));
 }
+
+#[test]
+fn test_parse_padding_shorthand_forms() {
+    let css = r#"
+        .one { padding: 1px; }
+        .two { padding: 1px 2px; }
+        .three { padding: 1px 2px 3px; }
+        .four { padding: 1px 2px 3px 4px; }
+        .five { padding: 1px 2px 3px 4px 5px; }
+    "#;
+
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    let padding = |index: usize| {
+        let mut padding = Directional::default();
+        for declaration in &stylesheet.rules[index].declarations {
+            padding.merge(&declaration.padding);
+        }
+        [padding.top, padding.right, padding.bottom, padding.left]
+    };
+    let px = |values: [f64; 4]| values.map(|value| Some(Length::Px(value)));
+
+    assert_eq!(padding(0), px([1.0, 1.0, 1.0, 1.0]));
+    assert_eq!(padding(1), px([1.0, 2.0, 1.0, 2.0]));
+    assert_eq!(padding(2), px([1.0, 2.0, 3.0, 2.0]));
+    assert_eq!(padding(3), px([1.0, 2.0, 3.0, 4.0]));
+    // Extra values make the declaration invalid
+    assert_eq!(padding(4), [None; 4]);
+}
+
+#[test]
+fn test_parse_containment() {
+    let css = r#"
+        .a { contain: layout paint; }
+        .b { contain: content; }
+        .c { contain: style paint; }
+        .d { contain: none; }
+        .e { contain: size; }
+        .f { content-visibility: auto; }
+        .g { content-visibility: hidden; }
+    "#;
+
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    let contain = |index: usize| {
+        stylesheet.rules[index]
+            .declarations
+            .iter()
+            .find_map(|d| d.contain)
+    };
+    let both = Contain {
+        layout: true,
+        paint: true,
+    };
+
+    assert_eq!(contain(0), Some(both));
+    assert_eq!(contain(1), Some(both));
+    assert_eq!(
+        contain(2),
+        Some(Contain {
+            layout: false,
+            paint: true
+        })
+    );
+    assert_eq!(contain(3), Some(Contain::default()));
+    // Size containment isn't supported
+    assert_eq!(contain(4), None);
+
+    assert!(stylesheet.rules[5]
+        .declarations
+        .iter()
+        .any(|d| d.content_visibility == Some(ContentVisibility::Auto)));
+    assert!(stylesheet.rules[6]
+        .declarations
+        .iter()
+        .any(|d| d.content_visibility == Some(ContentVisibility::Hidden) && d.contains_paint()));
+}
+
+#[test]
+fn test_parse_scroll_behavior() {
+    let css = r#"
+        .a { scroll-behavior: smooth; }
+        .b { scroll-behavior: auto; }
+    "#;
+
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    assert_eq!(stylesheet.rules.len(), 2);
+    assert!(stylesheet.rules[0]
+        .declarations
+        .iter()
+        .any(|d| d.scroll_behavior == Some(ScrollBehavior::Smooth)));
+    assert!(stylesheet.rules[1]
+        .declarations
+        .iter()
+        .any(|d| d.scroll_behavior == Some(ScrollBehavior::Auto)));
+}
+
+#[test]
+fn test_parse_scroll_snap() {
+    let css = r#"
+        .carousel { scroll-snap-type: x mandatory; }
+        .list { scroll-snap-type: y; }
+        .slide { scroll-snap-align: center; }
+    "#;
+
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    assert_eq!(stylesheet.rules.len(), 3);
+    assert!(stylesheet.rules[0]
+        .declarations
+        .iter()
+        .any(|d| d.scroll_snap_type
+            == Some(ScrollSnapType {
+                axis: ScrollSnapAxis::X,
+                strictness: ScrollSnapStrictness::Mandatory,
+            })));
+    assert!(stylesheet.rules[1]
+        .declarations
+        .iter()
+        .any(|d| d.scroll_snap_type
+            == Some(ScrollSnapType {
+                axis: ScrollSnapAxis::Y,
+                strictness: ScrollSnapStrictness::Proximity,
+            })));
+    assert!(stylesheet.rules[2]
+        .declarations
+        .iter()
+        .any(|d| d.scroll_snap_align == Some(ScrollSnapAlign::Center)));
+}
+
+#[test]
+fn test_parse_aspect_ratio() {
+    let css = r#"
+        .a { aspect-ratio: 16 / 9; }
+        .b { aspect-ratio: 1.5; }
+        .c { aspect-ratio: auto; }
+        .d { aspect-ratio: auto 4/3; }
+        .e { aspect-ratio: -1 / 2; }
+        .f { aspect-ratio: 16 / auto; }
+    "#;
+
+    let stylesheet = parse_css(css).expect("Failed to parse CSS");
+    let ratio = |index: usize| {
+        stylesheet.rules[index]
+            .declarations
+            .iter()
+            .find_map(|d| d.aspect_ratio)
+    };
+
+    assert_eq!(ratio(0), Some(AspectRatio::Ratio(16.0, 9.0)));
+    assert_eq!(ratio(1), Some(AspectRatio::Ratio(1.5, 1.0)));
+    assert_eq!(ratio(2), Some(AspectRatio::Auto));
+    assert_eq!(ratio(3), Some(AspectRatio::Ratio(4.0, 3.0)));
+    assert_eq!(ratio(4), None);
+    assert_eq!(ratio(5), None);
+    assert_eq!(AspectRatio::Ratio(0.0, 1.0).value(), None);
+}
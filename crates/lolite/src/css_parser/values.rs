@@ -1,5 +1,5 @@
 use super::parser::StyleDeclarationParser;
-use crate::style::Length;
+use crate::style::{AspectRatio, Content, ContentItem, Directional, Length, Overflow};
 use cssparser::{ParseError, Parser, Token};
 
 impl StyleDeclarationParser {
@@ -59,7 +59,12 @@ impl StyleDeclarationParser {
             Token::Dimension { value, unit, .. } => match unit.as_ref() {
                 "px" => Ok(Length::Px(*value as f64)),
                 "em" => Ok(Length::Em(*value as f64)),
+                "rem" => Ok(Length::Rem(*value as f64)),
                 "%" => Ok(Length::Percent(*value as f64)),
+                "vw" => Ok(Length::Vw(*value as f64)),
+                "vh" => Ok(Length::Vh(*value as f64)),
+                "vmin" => Ok(Length::Vmin(*value as f64)),
+                "vmax" => Ok(Length::Vmax(*value as f64)),
                 _ => Err(input.new_error_for_next_token()),
             },
             Token::Number { value, .. } => {
@@ -77,4 +82,204 @@ impl StyleDeclarationParser {
             _ => Err(input.new_error_for_next_token()),
         }
     }
+
+    /// Parse a `row-gap` or `column-gap`: `normal`, stored as `auto`, or a non-negative
+    /// length or percentage.
+    pub(crate) fn parse_gap_value<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Length, ParseError<'i, ()>> {
+        if input
+            .try_parse(|input| input.expect_ident_matching("normal"))
+            .is_ok()
+        {
+            return Ok(Length::Auto);
+        }
+        let start = input.state();
+        let gap = self.parse_length_value(input)?;
+        if gap.is_negative() || gap == Length::Auto {
+            input.reset(&start);
+            return Err(input.new_error_for_next_token());
+        }
+        Ok(gap)
+    }
+
+    /// Parse the 1–4 value form of the `margin` and `padding` shorthands:
+    /// `<top> [<right> [<bottom> [<left>]]]`, where a missing right copies top, a
+    /// missing bottom copies top and a missing left copies right.
+    pub(crate) fn parse_length_sides<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Directional<Option<Length>>, ParseError<'i, ()>> {
+        let top = self.parse_length_value(input)?;
+        let right = input
+            .try_parse(|input| self.parse_length_value(input))
+            .unwrap_or(top);
+        let bottom = input
+            .try_parse(|input| self.parse_length_value(input))
+            .unwrap_or(top);
+        let left = input
+            .try_parse(|input| self.parse_length_value(input))
+            .unwrap_or(right);
+
+        if !input.is_exhausted() {
+            return Err(input.new_error_for_next_token());
+        }
+
+        Ok(Directional {
+            top: Some(top),
+            right: Some(right),
+            bottom: Some(bottom),
+            left: Some(left),
+        })
+    }
+
+    /// Parse the `flex` shorthand into grow, shrink and basis: `none`, `initial`, or
+    /// `<grow> <shrink>?` and `<basis>` in either order. An omitted grow or shrink is 1,
+    /// and an omitted basis is 0, so `flex: 1` shares all of the free space.
+    pub(crate) fn parse_flex_shorthand<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<(f64, f64, Length), ParseError<'i, ()>> {
+        if let Ok(keyword) = input.try_parse(|input| {
+            let ident = input.expect_ident()?;
+            match ident.as_ref() {
+                "none" => Ok((0.0, 0.0, Length::Auto)),
+                "initial" => Ok((0.0, 1.0, Length::Auto)),
+                _ => Err(input.new_error_for_next_token::<()>()),
+            }
+        }) {
+            input.expect_exhausted()?;
+            return Ok(keyword);
+        }
+
+        let mut factors = None;
+        let mut basis = None;
+        while !input.is_exhausted() {
+            // Unitless numbers are flex factors before they are a basis in pixels
+            if factors.is_none() {
+                if let Ok(grow) = input.try_parse(parse_flex_factor) {
+                    let shrink = input.try_parse(parse_flex_factor).ok();
+                    factors = Some((grow, shrink));
+                    continue;
+                }
+            }
+            if basis.is_some() {
+                return Err(input.new_error_for_next_token());
+            }
+            let length = self.parse_length_value(input)?;
+            if length.is_negative() {
+                return Err(input.new_error_for_next_token());
+            }
+            basis = Some(length);
+        }
+
+        let (grow, shrink) = match (factors, basis) {
+            (None, None) => return Err(input.new_error_for_next_token()),
+            (Some((grow, shrink)), _) => (grow, shrink.unwrap_or(1.0)),
+            (None, Some(_)) => (1.0, 1.0),
+        };
+        Ok((grow, shrink, basis.unwrap_or(Length::Px(0.0))))
+    }
+
+    /// Parse one axis of `overflow`: `visible`, `hidden`, `scroll` or `auto`.
+    pub(crate) fn parse_overflow_value<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Overflow, ParseError<'i, ()>> {
+        let ident = input.expect_ident()?;
+        Ok(match ident.as_ref() {
+            "visible" => Overflow::Visible,
+            "hidden" => Overflow::Hidden,
+            "scroll" => Overflow::Scroll,
+            "auto" => Overflow::Auto,
+            _ => return Err(input.new_error_for_next_token()),
+        })
+    }
+
+    /// Parse `content`: `none`, `normal`, or a list of strings and `attr(name)`.
+    pub(crate) fn parse_content_value<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Content, ParseError<'i, ()>> {
+        if input
+            .try_parse(|input| {
+                let ident = input.expect_ident()?;
+                match ident.as_ref() {
+                    "none" | "normal" => Ok(()),
+                    _ => Err(input.new_error_for_next_token::<()>()),
+                }
+            })
+            .is_ok()
+        {
+            return Ok(Content::None);
+        }
+
+        let mut items = Vec::new();
+        while !input.is_exhausted() {
+            let item = match input.next()?.clone() {
+                Token::QuotedString(text) => ContentItem::String(text.to_string()),
+                Token::Function(name) if name.eq_ignore_ascii_case("attr") => input
+                    .parse_nested_block(|input| {
+                        let name = input.expect_ident()?.to_string();
+                        input.expect_exhausted()?;
+                        Ok(ContentItem::Attr(name))
+                    })?,
+                token => return Err(input.new_unexpected_token_error(token)),
+            };
+            items.push(item);
+        }
+        if items.is_empty() {
+            return Err(input.new_error_for_next_token());
+        }
+        Ok(Content::Items(items))
+    }
+
+    /// Parse `aspect-ratio`: `auto`, a `<width> / <height>` ratio whose height defaults
+    /// to 1, or both. With both, the ratio is used, as only replaced elements have a
+    /// natural ratio for `auto` to prefer.
+    pub(crate) fn parse_aspect_ratio_value<'i, 't>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<AspectRatio, ParseError<'i, ()>> {
+        let auto = input
+            .try_parse(|input| input.expect_ident_matching("auto"))
+            .is_ok();
+        let ratio = match input.try_parse(parse_ratio) {
+            Ok(ratio) => ratio,
+            Err(_) if auto => return Ok(AspectRatio::Auto),
+            Err(err) => return Err(err),
+        };
+        if !auto {
+            let _ = input.try_parse(|input| input.expect_ident_matching("auto"));
+        }
+        Ok(ratio)
+    }
+}
+
+/// A `<ratio>`: a width, then optionally `/` and a height, both non-negative.
+fn parse_ratio<'i>(input: &mut Parser<'i, '_>) -> Result<AspectRatio, ParseError<'i, ()>> {
+    let width = parse_ratio_term(input)?;
+    let height = match input.try_parse(|input| input.expect_delim('/')) {
+        Ok(()) => parse_ratio_term(input)?,
+        Err(_) => 1.0,
+    };
+    Ok(AspectRatio::Ratio(width, height))
+}
+
+fn parse_ratio_term<'i>(input: &mut Parser<'i, '_>) -> Result<f64, ParseError<'i, ()>> {
+    let value = input.expect_number()?;
+    if value < 0.0 {
+        return Err(input.new_error_for_next_token());
+    }
+    Ok(value as f64)
+}
+
+/// A non-negative flex grow or shrink factor.
+fn parse_flex_factor<'i>(input: &mut Parser<'i, '_>) -> Result<f64, ParseError<'i, ()>> {
+    let value = input.expect_number()?;
+    if value < 0.0 {
+        return Err(input.new_error_for_next_token());
+    }
+    Ok(value as f64)
 }
@@ -0,0 +1,102 @@
+use crate::style::CustomProperties;
+use cssparser::{Parser, Token};
+
+/// How deeply `var()` substitutions may nest before the value is treated as a cycle.
+const MAX_SUBSTITUTION_DEPTH: usize = 32;
+
+/// Longest value a substitution may produce, so a few properties referencing each
+/// other repeatedly can't expand into an enormous string.
+const MAX_SUBSTITUTED_LENGTH: usize = 64 * 1024;
+
+/// Whether the rest of the value contains a `var()` reference, at any nesting level.
+///
+/// Consumes the input.
+pub(crate) fn references_var(input: &mut Parser<'_, '_>) -> bool {
+    let mut found = false;
+    while let Ok(token) = input.next() {
+        let is_block = match token {
+            Token::Function(name) => {
+                found |= name.eq_ignore_ascii_case("var");
+                true
+            }
+            Token::ParenthesisBlock | Token::SquareBracketBlock | Token::CurlyBracketBlock => true,
+            _ => false,
+        };
+        if is_block && !found {
+            found = input
+                .parse_nested_block(|input| {
+                    Ok::<_, cssparser::ParseError<()>>(references_var(input))
+                })
+                .unwrap_or(false);
+        }
+    }
+    found
+}
+
+/// Replace every `var(--name)` and `var(--name, fallback)` in `value` with the
+/// property's value, or the fallback when it isn't defined.
+///
+/// Returns `None` when a reference can't be resolved.
+pub(crate) fn substitute_vars(value: &str, properties: &CustomProperties) -> Option<String> {
+    substitute(value, properties, 0)
+}
+
+fn substitute(value: &str, properties: &CustomProperties, depth: usize) -> Option<String> {
+    if depth > MAX_SUBSTITUTION_DEPTH {
+        return None;
+    }
+
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = find_var(rest) {
+        result.push_str(&rest[..start]);
+        let arguments = &rest[start + "var(".len()..];
+        let end = closing_paren(arguments)?;
+        let (name, fallback) = match arguments[..end].split_once(',') {
+            Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+            None => (arguments[..end].trim(), None),
+        };
+        if !name.starts_with("--") {
+            return None;
+        }
+        let replacement = properties.get(name).or(fallback)?;
+        result.push_str(&substitute(replacement, properties, depth + 1)?);
+        if result.len() > MAX_SUBSTITUTED_LENGTH {
+            return None;
+        }
+        rest = &arguments[end + 1..];
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+/// Byte offset of the next `var(` that starts a function, not the tail of a longer name.
+fn find_var(value: &str) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(found) = value[offset..].find("var(") {
+        let start = offset + found;
+        let preceded_by_name = value[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '-' || c == '_');
+        if !preceded_by_name {
+            return Some(start);
+        }
+        offset = start + "var(".len();
+    }
+    None
+}
+
+/// Byte offset of the `)` closing a function whose arguments start `arguments`.
+fn closing_paren(arguments: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,76 @@
+use crate::css_parser::{parse_css, substitute_vars};
+use crate::style::{CustomProperties, VarDeclaration};
+
+fn properties(entries: &[(&str, &str)]) -> CustomProperties {
+    let mut properties = CustomProperties::default();
+    for (name, value) in entries {
+        properties.insert(name.to_string(), value.to_string());
+    }
+    properties
+}
+
+#[test]
+fn test_parse_custom_property_and_var_reference() {
+    let sheet =
+        parse_css(".a { --Main-Color:  rgb(1, 2, 3) ; color: var(--Main-Color); }").unwrap();
+    let declarations = &sheet.rules[0].declarations;
+    assert_eq!(declarations.len(), 2);
+    assert_eq!(
+        declarations[0].custom_properties.get("--Main-Color"),
+        Some("rgb(1, 2, 3)")
+    );
+    assert_eq!(
+        declarations[1].var_declaration,
+        Some(VarDeclaration {
+            property: "color".to_string(),
+            value: "var(--Main-Color)".to_string(),
+        })
+    );
+    assert_eq!(declarations[1].color, None);
+}
+
+#[test]
+fn test_var_nested_in_function() {
+    let sheet = parse_css(".a { color: rgb(var(--r), 0, 0); }").unwrap();
+    assert!(sheet.rules[0].declarations[0].var_declaration.is_some());
+}
+
+#[test]
+fn test_substitute_vars() {
+    let props = properties(&[("--a", "1px"), ("--b", "var(--a) 2px")]);
+    assert_eq!(substitute_vars("var(--a)", &props).as_deref(), Some("1px"));
+    assert_eq!(
+        substitute_vars("var(--b) var(--a)", &props).as_deref(),
+        Some("1px 2px 1px")
+    );
+    assert_eq!(
+        substitute_vars("var(--missing, var(--a))", &props).as_deref(),
+        Some("1px")
+    );
+    assert_eq!(
+        substitute_vars("var(--missing, 1px, 2px)", &props).as_deref(),
+        Some("1px, 2px")
+    );
+    assert_eq!(substitute_vars("var(--missing)", &props), None);
+    assert_eq!(substitute_vars("var(a)", &props), None);
+    assert_eq!(
+        substitute_vars("myvar(--a)", &props).as_deref(),
+        Some("myvar(--a)")
+    );
+}
+
+#[test]
+fn test_substitute_vars_cycle() {
+    let props = properties(&[("--a", "var(--b)"), ("--b", "var(--a)")]);
+    assert_eq!(substitute_vars("var(--a)", &props), None);
+}
+
+#[test]
+fn test_substitute_vars_limits_expansion() {
+    let mut props = properties(&[("--v0", "xxxxxxxx")]);
+    for i in 1..20 {
+        let reference = format!("var(--v{}) ", i - 1);
+        props.insert(format!("--v{}", i), reference.repeat(4));
+    }
+    assert_eq!(substitute_vars("var(--v19)", &props), None);
+}
@@ -0,0 +1,246 @@
+use crate::layout::Rect;
+use crate::Id;
+#[cfg(feature = "windowing")]
+use {
+    crate::layout::{GeneratedBox, RenderNode},
+    crate::style::Rgba,
+    std::collections::HashMap,
+    std::sync::{Arc, Mutex},
+};
+
+/// Parts of a window that changed since the previous painted frame, see
+/// [`crate::Engine::on_damage`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Damage {
+    /// Areas to repaint, in window pixels; together they cover every change, and none
+    /// lies inside another.
+    pub rects: Vec<Rect>,
+    /// Nodes that appeared, disappeared, moved or changed how they are painted.
+    pub nodes: Vec<Id>,
+}
+
+impl Damage {
+    /// Whether the frame looks exactly like the previous one.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty() && self.nodes.is_empty()
+    }
+
+    /// Damage covering the whole of a snapshot, for a first frame.
+    #[cfg(feature = "windowing")]
+    pub(crate) fn full(snapshot: &RenderNode) -> Self {
+        let mut nodes = Vec::new();
+        let mut visit = vec![snapshot];
+        while let Some(node) = visit.pop() {
+            if node.generated.is_none() {
+                nodes.push(node.id);
+            }
+            visit.extend(node.children.iter().rev());
+        }
+        Self {
+            rects: vec![snapshot.bounds],
+            nodes,
+        }
+    }
+
+    /// Compare two snapshots painted one after the other.
+    ///
+    /// A node is damaged where it was painted before and where it is painted now,
+    /// clipped to the root's box. Descendants of a scrolled node move with it, but
+    /// nodes clipped by `contain: paint` may still report areas their ancestor hides.
+    #[cfg(feature = "windowing")]
+    pub(crate) fn between(previous: &RenderNode, current: &RenderNode) -> Self {
+        let before = painted_boxes(previous);
+        let after = painted_boxes(current);
+        let viewport = current.bounds;
+        let mut damage = Damage::default();
+        let mut visit = vec![current];
+        while let Some(node) = visit.pop() {
+            let key = (node.id, node.generated);
+            let (rect, _) = after[&key];
+            match before.get(&key) {
+                Some((old_rect, old)) if *old_rect == rect && paints_alike(old, node) => {}
+                Some((old_rect, _)) => damage.add(node.id, [*old_rect, rect], &viewport),
+                None => damage.add(node.id, [rect], &viewport),
+            }
+            visit.extend(node.children.iter().rev());
+        }
+        let mut removed: Vec<_> = before
+            .iter()
+            .filter(|(key, _)| !after.contains_key(key))
+            .collect();
+        removed.sort_by_key(|((id, generated), _)| (id.value(), *generated));
+        for ((id, _), (rect, _)) in removed {
+            damage.add(*id, [*rect], &viewport);
+        }
+        damage
+    }
+
+    /// Damage a node, or a box generated for it.
+    #[cfg(feature = "windowing")]
+    fn add(&mut self, id: Id, rects: impl IntoIterator<Item = Rect>, viewport: &Rect) {
+        if !self.nodes.contains(&id) {
+            self.nodes.push(id);
+        }
+        for rect in rects {
+            let Some(rect) = intersect(&rect, viewport) else {
+                continue;
+            };
+            if self.rects.iter().any(|r| covers(r, &rect)) {
+                continue;
+            }
+            self.rects.retain(|r| !covers(&rect, r));
+            self.rects.push(rect);
+        }
+    }
+
+    /// Convert layout pixels to window pixels.
+    #[cfg(feature = "windowing")]
+    pub(crate) fn scaled(mut self, zoom: f64) -> Self {
+        for rect in &mut self.rects {
+            *rect = Rect::new(
+                rect.x * zoom,
+                rect.y * zoom,
+                rect.width * zoom,
+                rect.height * zoom,
+            );
+        }
+        self
+    }
+}
+
+/// Where each node of a snapshot is painted, after the scroll offsets of its ancestors.
+/// Boxes generated for a node share its id, so they are told apart by their kind.
+#[cfg(feature = "windowing")]
+fn painted_boxes(root: &RenderNode) -> HashMap<(Id, Option<GeneratedBox>), (Rect, &RenderNode)> {
+    let mut boxes = HashMap::new();
+    let mut visit = vec![(root, 0.0, 0.0)];
+    while let Some((node, dx, dy)) = visit.pop() {
+        let b = node.bounds;
+        boxes.insert(
+            (node.id, node.generated),
+            (Rect::new(b.x - dx, b.y - dy, b.width, b.height), node),
+        );
+        let (dx, dy) = (dx + node.scroll_offset.x, dy + node.scroll_offset.y);
+        visit.extend(node.children.iter().map(|child| (child, dx, dy)));
+    }
+    boxes
+}
+
+/// Whether two versions of a node at the same place paint the same pixels, not
+/// counting their children.
+#[cfg(feature = "windowing")]
+fn paints_alike(a: &RenderNode, b: &RenderNode) -> bool {
+    (Arc::ptr_eq(&a.style, &b.style) || a.style == b.style) && a.text == b.text
+}
+
+#[cfg(feature = "windowing")]
+fn covers(outer: &Rect, inner: &Rect) -> bool {
+    outer.x <= inner.x
+        && outer.y <= inner.y
+        && outer.x + outer.width >= inner.x + inner.width
+        && outer.y + outer.height >= inner.y + inner.height
+}
+
+#[cfg(feature = "windowing")]
+fn intersect(a: &Rect, b: &Rect) -> Option<Rect> {
+    let x = a.x.max(b.x);
+    let y = a.y.max(b.y);
+    let right = (a.x + a.width).min(b.x + b.width);
+    let bottom = (a.y + a.height).min(b.y + b.height);
+    (right > x && bottom > y).then(|| Rect::new(x, y, right - x, bottom - y))
+}
+
+#[cfg(feature = "windowing")]
+pub(crate) type DamageCallback = Box<dyn FnMut(&Damage) + Send>;
+
+/// Tints of repainted areas in paint flashing mode, one per frame in turn so that
+/// consecutive repaints of the same area stand apart.
+#[cfg(feature = "windowing")]
+const FLASH_COLORS: [Rgba; 3] = [
+    Rgba {
+        r: 255,
+        g: 0,
+        b: 255,
+        a: 96,
+    },
+    Rgba {
+        r: 0,
+        g: 200,
+        b: 255,
+        a: 96,
+    },
+    Rgba {
+        r: 255,
+        g: 200,
+        b: 0,
+        a: 96,
+    },
+];
+
+/// Damage of a painted frame and who wants to know about it.
+#[cfg(feature = "windowing")]
+pub(crate) struct PaintedFrame {
+    /// In window pixels.
+    pub damage: Damage,
+    pub callback: Option<Arc<Mutex<DamageCallback>>>,
+    /// Tint to draw over the damaged areas, in paint flashing mode.
+    pub flash: Option<Rgba>,
+}
+
+/// Remembers the last painted snapshot of a window while a damage callback is set or
+/// paint flashing is on.
+#[cfg(feature = "windowing")]
+#[derive(Default)]
+pub(crate) struct DamageTracker {
+    // Shared so the callback runs without holding the tracker lock.
+    callback: Option<Arc<Mutex<DamageCallback>>>,
+    flashing: bool,
+    /// Frames flashed so far, to pick the next tint.
+    flashes: usize,
+    painted: Option<(Arc<RenderNode>, f64)>,
+}
+
+#[cfg(feature = "windowing")]
+impl DamageTracker {
+    pub fn set_callback(&mut self, callback: Option<DamageCallback>) {
+        self.callback = callback.map(|callback| Arc::new(Mutex::new(callback)));
+        // The next frame is reported as fully damaged
+        self.painted = None;
+    }
+
+    pub fn set_paint_flashing(&mut self, enabled: bool) {
+        self.flashing = enabled;
+        if self.callback.is_none() {
+            self.painted = None;
+        }
+    }
+
+    /// Record a painted snapshot and the zoom it was painted at.
+    ///
+    /// Returns what changed since the previous one, unless neither a callback nor
+    /// paint flashing needs it.
+    pub fn record_paint(&mut self, snapshot: Arc<RenderNode>, zoom: f64) -> Option<PaintedFrame> {
+        if self.callback.is_none() && !self.flashing {
+            return None;
+        }
+        let damage = match &self.painted {
+            Some((previous, previous_zoom)) if *previous_zoom == zoom => {
+                Damage::between(previous, &snapshot)
+            }
+            _ => Damage::full(&snapshot),
+        };
+        self.painted = Some((snapshot, zoom));
+        let flash = (self.flashing && !damage.rects.is_empty()).then(|| {
+            self.flashes += 1;
+            FLASH_COLORS[(self.flashes - 1) % FLASH_COLORS.len()]
+        });
+        Some(PaintedFrame {
+            damage: damage.scaled(zoom),
+            callback: self.callback.clone(),
+            flash,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "windowing"))]
+mod damage_tests;
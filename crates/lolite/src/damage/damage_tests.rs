@@ -0,0 +1,138 @@
+use super::*;
+use crate::layout::ScrollOffset;
+use crate::style::{Rgba, Style};
+
+fn node(id: u64, bounds: Rect, children: Vec<RenderNode>) -> RenderNode {
+    RenderNode {
+        id: Id::from_u64(id),
+        generated: None,
+        bounds,
+        style: Arc::new(Style::default()),
+        text: None,
+        debug_name: None,
+        scroll_offset: ScrollOffset::default(),
+        draggable: false,
+        embedded: None,
+        children,
+    }
+}
+
+fn sample() -> RenderNode {
+    node(
+        0,
+        Rect::new(0.0, 0.0, 200.0, 100.0),
+        vec![
+            node(1, Rect::new(0.0, 0.0, 50.0, 20.0), vec![]),
+            node(2, Rect::new(60.0, 0.0, 50.0, 20.0), vec![]),
+        ],
+    )
+}
+
+#[test]
+fn test_identical_snapshots_have_no_damage() {
+    // Equal styles in distinct allocations, as after a relayout
+    assert!(Damage::between(&sample(), &sample()).is_empty());
+}
+
+#[test]
+fn test_moved_and_restyled_nodes() {
+    let mut moved = sample();
+    moved.children[0].bounds.y = 30.0;
+    let damage = Damage::between(&sample(), &moved);
+    assert_eq!(damage.nodes, vec![Id::from_u64(1)]);
+    assert_eq!(
+        damage.rects,
+        vec![
+            Rect::new(0.0, 0.0, 50.0, 20.0),
+            Rect::new(0.0, 30.0, 50.0, 20.0)
+        ]
+    );
+
+    let mut restyled = sample();
+    let mut style = Style::default();
+    style.background_color = Some(Rgba::default());
+    restyled.children[1].style = Arc::new(style);
+    let damage = Damage::between(&sample(), &restyled);
+    assert_eq!(damage.nodes, vec![Id::from_u64(2)]);
+    assert_eq!(damage.rects, vec![Rect::new(60.0, 0.0, 50.0, 20.0)]);
+}
+
+#[test]
+fn test_added_removed_and_scrolled_nodes() {
+    let mut changed = sample();
+    changed.children.remove(1);
+    changed.children[0]
+        .children
+        .push(node(3, Rect::new(0.0, 0.0, 500.0, 30.0), vec![]));
+    let damage = Damage::between(&sample(), &changed);
+    assert_eq!(damage.nodes, vec![Id::from_u64(3), Id::from_u64(2)]);
+    // Clipped to the root, and the removed node's area is covered by the new one
+    assert_eq!(damage.rects, vec![Rect::new(0.0, 0.0, 200.0, 30.0)]);
+
+    let mut scrolled = sample();
+    scrolled.scroll_offset = ScrollOffset { x: 0.0, y: 5.0 };
+    let damage = Damage::between(&sample(), &scrolled);
+    // The root itself paints in place, its children shift up
+    assert_eq!(damage.nodes, vec![Id::from_u64(1), Id::from_u64(2)]);
+    assert_eq!(
+        damage.rects,
+        vec![
+            Rect::new(0.0, 0.0, 50.0, 20.0),
+            Rect::new(60.0, 0.0, 50.0, 20.0)
+        ]
+    );
+}
+
+#[test]
+fn test_tracker_reports_in_window_pixels() {
+    let mut tracker = DamageTracker::default();
+    assert!(tracker.record_paint(Arc::new(sample()), 1.0).is_none());
+
+    tracker.set_callback(Some(Box::new(|_| {})));
+    let first = tracker
+        .record_paint(Arc::new(sample()), 2.0)
+        .unwrap()
+        .damage;
+    assert_eq!(first.rects, vec![Rect::new(0.0, 0.0, 400.0, 200.0)]);
+    assert_eq!(first.nodes.len(), 3);
+
+    let mut moved = sample();
+    moved.children[1].bounds.x = 70.0;
+    let damage = tracker
+        .record_paint(Arc::new(moved.clone()), 2.0)
+        .unwrap()
+        .damage;
+    assert_eq!(
+        damage.rects,
+        vec![
+            Rect::new(120.0, 0.0, 100.0, 40.0),
+            Rect::new(140.0, 0.0, 100.0, 40.0)
+        ]
+    );
+
+    // A new zoom repaints everything
+    let zoomed = tracker.record_paint(Arc::new(moved), 1.0).unwrap().damage;
+    assert_eq!(zoomed.rects, vec![Rect::new(0.0, 0.0, 200.0, 100.0)]);
+}
+
+#[test]
+fn test_paint_flashing_tints_damaged_frames() {
+    let mut tracker = DamageTracker::default();
+    tracker.set_paint_flashing(true);
+    let first = tracker.record_paint(Arc::new(sample()), 1.0).unwrap();
+    assert!(first.callback.is_none());
+    assert_eq!(first.flash, Some(FLASH_COLORS[0]));
+
+    // Nothing changed, nothing to tint
+    let same = tracker.record_paint(Arc::new(sample()), 1.0).unwrap();
+    assert!(same.damage.is_empty());
+    assert_eq!(same.flash, None);
+
+    let mut moved = sample();
+    moved.children[0].bounds.y = 30.0;
+    let next = tracker.record_paint(Arc::new(moved), 1.0).unwrap();
+    assert_eq!(next.flash, Some(FLASH_COLORS[1]));
+
+    tracker.set_paint_flashing(false);
+    assert!(tracker.record_paint(Arc::new(sample()), 1.0).is_none());
+}
@@ -0,0 +1,191 @@
+use crate::Id;
+
+/// How far the pointer must move while pressed on a draggable node before it is
+/// dragged, in window pixels, so that clicks don't start drags.
+#[cfg(feature = "windowing")]
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// A step of dragging a node with the pointer, reported to [`crate::Params::on_drag`].
+///
+/// Nodes are made draggable with the `draggable="true"` attribute, or
+/// [`crate::Engine::set_draggable`]. Positions are in window pixels, as passed to
+/// [`crate::Params::on_click`]. The target is the topmost node under the pointer; its
+/// ancestors can be found with [`crate::Engine::parent`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum DragEvent {
+    /// The pointer was pressed on `source`, or a node inside it, and moved far enough
+    /// to drag it.
+    Start { source: Id },
+    /// The pointer moved over `target` while dragging.
+    Over {
+        source: Id,
+        target: Id,
+        x: f64,
+        y: f64,
+    },
+    /// The pointer moved off `target`, onto another node or out of the window.
+    Leave { source: Id, target: Id },
+    /// The pointer was released over `target`.
+    Drop {
+        source: Id,
+        target: Id,
+        x: f64,
+        y: f64,
+    },
+    /// The drag finished, right after a [`DragEvent::Drop`] if `dropped`.
+    End { source: Id, dropped: bool },
+}
+
+/// Turns the pointer updates of a window into drag events.
+#[cfg(feature = "windowing")]
+#[derive(Default)]
+pub(crate) struct DragTracker {
+    state: DragState,
+}
+
+#[cfg(feature = "windowing")]
+#[derive(Default)]
+enum DragState {
+    #[default]
+    Idle,
+    /// Pressed on a draggable node at `origin`, without moving far enough yet.
+    Pressed {
+        source: Id,
+        origin: (f64, f64),
+    },
+    /// Pressed where there's nothing to drag; nothing happens until the release.
+    Ignored,
+    Dragging(Drag),
+}
+
+/// A node being dragged.
+#[cfg(feature = "windowing")]
+struct Drag {
+    source: Id,
+    /// Where the pointer was pressed.
+    origin: (f64, f64),
+    /// Where the pointer was last seen in the window.
+    position: (f64, f64),
+    /// The node the pointer is over, if it is in the window.
+    over: Option<Id>,
+}
+
+#[cfg(feature = "windowing")]
+impl DragTracker {
+    /// Follow the pointer to `position`, or out of the window, with the left button
+    /// `pressed` or not. `draggable` is the node a press would drag, and `target` the
+    /// topmost node under the pointer. Returns the resulting events in order.
+    pub fn pointer(
+        &mut self,
+        position: Option<(f64, f64)>,
+        pressed: bool,
+        draggable: Option<Id>,
+        target: Option<Id>,
+    ) -> Vec<DragEvent> {
+        let mut events = Vec::new();
+        // Outside the window, there is nothing to drag over or drop on
+        let target = position.and(target);
+        self.state = match (std::mem::take(&mut self.state), pressed) {
+            (DragState::Idle, true) => match (draggable, position) {
+                (Some(source), Some(origin)) => DragState::Pressed { source, origin },
+                _ => DragState::Ignored,
+            },
+            (DragState::Pressed { source, origin }, true) => match position {
+                Some(position) if distance(origin, position) >= DRAG_THRESHOLD => {
+                    events.push(DragEvent::Start { source });
+                    let mut drag = Drag {
+                        source,
+                        origin,
+                        position,
+                        over: None,
+                    };
+                    drag.moved(Some(position), target, &mut events);
+                    DragState::Dragging(drag)
+                }
+                _ => DragState::Pressed { source, origin },
+            },
+            (DragState::Dragging(mut drag), true) => {
+                drag.moved(position, target, &mut events);
+                DragState::Dragging(drag)
+            }
+            (DragState::Dragging(drag), false) => {
+                drag.leave_unless_over(target, &mut events);
+                let source = drag.source;
+                let dropped = match (target, position) {
+                    (Some(target), Some((x, y))) => {
+                        events.push(DragEvent::Drop {
+                            source,
+                            target,
+                            x,
+                            y,
+                        });
+                        true
+                    }
+                    _ => false,
+                };
+                events.push(DragEvent::End { source, dropped });
+                DragState::Idle
+            }
+            (DragState::Ignored, true) => DragState::Ignored,
+            (_, false) => DragState::Idle,
+        };
+        events
+    }
+
+    /// The node being dragged, and how far the pointer moved it in window pixels, for
+    /// painting the drag image.
+    pub fn drag_image(&self) -> Option<(Id, f64, f64)> {
+        match &self.state {
+            DragState::Dragging(drag) => Some((
+                drag.source,
+                drag.position.0 - drag.origin.0,
+                drag.position.1 - drag.origin.1,
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "windowing")]
+impl Drag {
+    /// Move to `position`, over `target`.
+    fn moved(
+        &mut self,
+        position: Option<(f64, f64)>,
+        target: Option<Id>,
+        events: &mut Vec<DragEvent>,
+    ) {
+        self.leave_unless_over(target, events);
+        if let (Some(target), Some((x, y))) = (target, position) {
+            events.push(DragEvent::Over {
+                source: self.source,
+                target,
+                x,
+                y,
+            });
+        }
+        // The drag image stays where the pointer left the window
+        if let Some(position) = position {
+            self.position = position;
+        }
+        self.over = target;
+    }
+
+    /// Report leaving the node the pointer was over, if it is no longer over it.
+    fn leave_unless_over(&self, target: Option<Id>, events: &mut Vec<DragEvent>) {
+        if let Some(over) = self.over.filter(|&over| Some(over) != target) {
+            events.push(DragEvent::Leave {
+                source: self.source,
+                target: over,
+            });
+        }
+    }
+}
+
+#[cfg(feature = "windowing")]
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+#[cfg(all(test, feature = "windowing"))]
+mod drag_tests;
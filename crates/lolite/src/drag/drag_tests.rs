@@ -0,0 +1,108 @@
+use super::*;
+
+const SOURCE: Id = Id(1);
+const LIST: Id = Id(2);
+const OTHER_LIST: Id = Id(3);
+
+#[test]
+fn test_small_moves_do_not_start_a_drag() {
+    let mut tracker = DragTracker::default();
+    let events = [
+        tracker.pointer(Some((10.0, 10.0)), true, Some(SOURCE), Some(SOURCE)),
+        tracker.pointer(Some((12.0, 11.0)), true, Some(SOURCE), Some(SOURCE)),
+        tracker.pointer(Some((12.0, 11.0)), false, Some(SOURCE), Some(SOURCE)),
+    ];
+    assert!(events.iter().all(Vec::is_empty));
+    assert_eq!(tracker.drag_image(), None);
+}
+
+#[test]
+fn test_drag_over_targets_and_drop() {
+    let mut tracker = DragTracker::default();
+    assert!(tracker
+        .pointer(Some((10.0, 10.0)), true, Some(SOURCE), Some(SOURCE))
+        .is_empty());
+    assert_eq!(
+        tracker.pointer(Some((20.0, 10.0)), true, None, Some(LIST)),
+        vec![
+            DragEvent::Start { source: SOURCE },
+            DragEvent::Over {
+                source: SOURCE,
+                target: LIST,
+                x: 20.0,
+                y: 10.0,
+            },
+        ]
+    );
+    assert_eq!(tracker.drag_image(), Some((SOURCE, 10.0, 0.0)));
+
+    assert_eq!(
+        tracker.pointer(Some((50.0, 30.0)), true, None, Some(OTHER_LIST)),
+        vec![
+            DragEvent::Leave {
+                source: SOURCE,
+                target: LIST,
+            },
+            DragEvent::Over {
+                source: SOURCE,
+                target: OTHER_LIST,
+                x: 50.0,
+                y: 30.0,
+            },
+        ]
+    );
+    assert_eq!(
+        tracker.pointer(Some((50.0, 30.0)), false, None, Some(OTHER_LIST)),
+        vec![
+            DragEvent::Drop {
+                source: SOURCE,
+                target: OTHER_LIST,
+                x: 50.0,
+                y: 30.0,
+            },
+            DragEvent::End {
+                source: SOURCE,
+                dropped: true,
+            },
+        ]
+    );
+    assert_eq!(tracker.drag_image(), None);
+}
+
+#[test]
+fn test_release_outside_the_window_cancels_the_drag() {
+    let mut tracker = DragTracker::default();
+    tracker.pointer(Some((10.0, 10.0)), true, Some(SOURCE), Some(SOURCE));
+    tracker.pointer(Some((30.0, 10.0)), true, None, Some(LIST));
+
+    // The drag image stays where the pointer left
+    assert_eq!(
+        tracker.pointer(None, true, None, None),
+        vec![DragEvent::Leave {
+            source: SOURCE,
+            target: LIST,
+        }]
+    );
+    assert_eq!(tracker.drag_image(), Some((SOURCE, 20.0, 0.0)));
+    assert_eq!(
+        tracker.pointer(None, false, None, None),
+        vec![DragEvent::End {
+            source: SOURCE,
+            dropped: false,
+        }]
+    );
+}
+
+#[test]
+fn test_presses_outside_draggable_nodes_are_ignored() {
+    let mut tracker = DragTracker::default();
+    tracker.pointer(Some((10.0, 10.0)), true, None, Some(LIST));
+    // Moving onto a draggable node with the button held doesn't pick it up
+    assert!(tracker
+        .pointer(Some((40.0, 10.0)), true, Some(SOURCE), Some(SOURCE))
+        .is_empty());
+    assert!(tracker
+        .pointer(Some((40.0, 10.0)), false, Some(SOURCE), Some(SOURCE))
+        .is_empty());
+    assert_eq!(tracker.drag_image(), None);
+}
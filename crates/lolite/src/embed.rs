@@ -0,0 +1,35 @@
+use crate::commands::Command;
+use crate::layout::RenderNode;
+use crate::snapshot::SnapshotQueue;
+use std::sync::{mpsc::Sender, Arc, Mutex};
+
+/// Documents embedded deeper than this are neither painted nor hit-tested, which also
+/// ends documents embedded, directly or not, in themselves.
+pub(crate) const MAX_EMBED_DEPTH: usize = 8;
+
+/// A document embedded in a node of another one, see
+/// [`crate::Engine::set_embedded_document`]: where it publishes snapshots, and its
+/// command thread.
+#[derive(Clone)]
+pub(crate) struct EmbeddedDocument {
+    snapshots: Arc<Mutex<SnapshotQueue>>,
+    sender: Sender<Command>,
+}
+
+impl EmbeddedDocument {
+    pub fn new(snapshots: Arc<Mutex<SnapshotQueue>>, sender: Sender<Command>) -> Self {
+        Self { snapshots, sender }
+    }
+
+    /// The most recently published snapshot, which the embedding document paints and
+    /// hit-tests whether or not a window of its own drew it.
+    pub fn latest_snapshot(&self) -> Option<Arc<RenderNode>> {
+        self.snapshots.lock().unwrap().latest()
+    }
+
+    /// Queue a command for the document's command thread.
+    pub fn send(&self, command: Command) {
+        // The document may have been shut down while embedded.
+        let _ = self.sender.send(command);
+    }
+}
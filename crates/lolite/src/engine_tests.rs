@@ -0,0 +1,1325 @@
+use super::*;
+use std::time::Instant;
+
+fn wait_for_bounds(engine: &Engine, id: Id) -> Rect {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        if let Some(bounds) = engine.node_bounds(id) {
+            return bounds;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for layout");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_run_headless_lays_out_against_viewport() {
+    let engine = Engine::new();
+    let runner = {
+        let engine = engine.clone();
+        thread::spawn(move || engine.run_headless(320.0, 240.0))
+    };
+
+    let child = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), child).unwrap();
+
+    // The runner thread sets the viewport, racing the mutations above, so wait for a
+    // layout that uses it.
+    let root = engine.root_id();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while engine.node_bounds(root).map(|bounds| bounds.width) != Some(320.0) {
+        assert!(Instant::now() < deadline, "timed out waiting for viewport");
+        engine.flush().unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    let root_bounds = engine.node_bounds(root).unwrap();
+    assert_eq!(root_bounds.height, 240.0);
+    assert!(engine.node_bounds(child).is_some());
+
+    engine.request_exit().unwrap();
+    assert!(runner.join().unwrap().is_ok());
+}
+
+#[test]
+fn test_request_exit_before_run_headless_returns_immediately() {
+    let engine = Engine::new();
+    engine.request_exit().unwrap();
+
+    assert!(engine.run_headless(100.0, 100.0).is_ok());
+}
+
+#[test]
+fn test_transaction_applies_all_mutations_in_one_layout() {
+    let engine = Engine::new();
+    let layouts = Arc::new(AtomicU64::new(0));
+    {
+        let layouts = layouts.clone();
+        engine
+            .observe_resize(engine.root_id(), move |_, _, _| {
+                layouts.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+    }
+
+    let ids = engine
+        .transaction(|tx| {
+            tx.add_stylesheet(".row { flex-direction: row; }");
+            (1..=100)
+                .map(|i| {
+                    let id = tx.create_node(Id::from_u64(i), None);
+                    tx.set_parent(Id::from_u64(0), id);
+                    tx.set_attribute(id, "class".to_owned(), "row".to_owned());
+                    id
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap();
+
+    // The whole batch is laid out at once, rather than once per mutation.
+    engine.flush().unwrap();
+    assert!(engine.node_bounds(ids[99]).is_some());
+    assert_eq!(layouts.load(Ordering::SeqCst), 1);
+}
+
+fn wait_for_scroll_offset(engine: &Engine, id: Id, expected: ScrollOffset) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while engine.scroll_offset(id) != Some(expected) {
+        assert!(Instant::now() < deadline, "timed out waiting for scroll");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_scroll_to_smooth_and_instant() {
+    let engine = Engine::new();
+    engine
+        .transaction(|tx| {
+            tx.add_stylesheet(
+                ".list { height: 100px; overflow: auto; } .smooth { scroll-behavior: smooth; } .item { height: 300px; }",
+            );
+            let list = tx.create_node(Id::from_u64(1), None);
+            tx.set_parent(Id::from_u64(0), list);
+            tx.set_attribute(list, "class".to_owned(), "list".to_owned());
+            let item = tx.create_node(Id::from_u64(2), None);
+            tx.set_parent(list, item);
+            tx.set_attribute(item, "class".to_owned(), "item".to_owned());
+        })
+        .unwrap();
+    let list = Id::from_u64(1);
+    wait_for_bounds(&engine, Id::from_u64(2));
+
+    // Out-of-range offsets are clamped to the overflowing content
+    engine.scroll_to(list, 0.0, 1000.0).unwrap();
+    wait_for_scroll_offset(&engine, list, ScrollOffset { x: 0.0, y: 200.0 });
+
+    engine
+        .set_attribute(list, "class".to_owned(), "list smooth".to_owned())
+        .unwrap();
+    engine.flush().unwrap();
+
+    let started = Instant::now();
+    engine.scroll_to(list, 0.0, 0.0).unwrap();
+    wait_for_scroll_offset(&engine, list, ScrollOffset { x: 0.0, y: 0.0 });
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+fn test_scroll_into_view_nested_containers() {
+    let engine = Engine::new();
+    engine
+        .transaction(|tx| {
+            tx.add_stylesheet(
+                ".column { display: flex; flex-direction: column; }
+                 .outer { height: 100px; overflow: auto; } .inner { height: 200px; overflow-y: scroll; }
+                 .wrapper { height: 300px; }
+                 .spacer { height: 150px; } .item { height: 50px; }",
+            );
+            let outer = tx.create_node(Id::from_u64(1), None);
+            tx.set_parent(Id::from_u64(0), outer);
+            tx.set_attribute(outer, "class".to_owned(), "column outer".to_owned());
+            let spacer = tx.create_node(Id::from_u64(2), None);
+            tx.set_parent(outer, spacer);
+            tx.set_attribute(spacer, "class".to_owned(), "spacer".to_owned());
+            let inner = tx.create_node(Id::from_u64(3), None);
+            tx.set_parent(outer, inner);
+            tx.set_attribute(inner, "class".to_owned(), "column inner".to_owned());
+            // A wrapper that doesn't scroll, between the item and its container
+            let wrapper = tx.create_node(Id::from_u64(4), None);
+            tx.set_parent(inner, wrapper);
+            tx.set_attribute(wrapper, "class".to_owned(), "column wrapper".to_owned());
+            for i in 5..=10 {
+                let item = tx.create_node(Id::from_u64(i), None);
+                tx.set_parent(wrapper, item);
+                tx.set_attribute(item, "class".to_owned(), "item".to_owned());
+            }
+        })
+        .unwrap();
+    wait_for_bounds(&engine, Id::from_u64(10));
+
+    engine.scroll_into_view(Id::from_u64(7)).unwrap();
+    wait_for_scroll_offset(&engine, Id::from_u64(3), ScrollOffset { x: 0.0, y: 100.0 });
+    wait_for_scroll_offset(&engine, Id::from_u64(1), ScrollOffset { x: 0.0, y: 150.0 });
+    assert_eq!(
+        engine.scroll_offset(Id::from_u64(4)),
+        Some(ScrollOffset::default())
+    );
+}
+
+fn wait_for_width(engine: &Engine, id: Id, width: f64) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while engine.node_bounds(id).map(|b| b.width) != Some(width) {
+        assert!(
+            Instant::now() < deadline,
+            "timed out waiting for width {}",
+            width
+        );
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_replace_and_remove_stylesheet() {
+    let engine = Engine::new();
+    let (sheet, node) = engine
+        .transaction(|tx| {
+            let sheet = tx.add_stylesheet(".box { width: 50px; }");
+            let node = tx.create_node(Id::from_u64(1), None);
+            tx.set_parent(Id::from_u64(0), node);
+            tx.set_attribute(node, "class".to_owned(), "box".to_owned());
+            let unstyled = tx.create_node(Id::from_u64(2), None);
+            tx.set_parent(Id::from_u64(0), unstyled);
+            (sheet, node)
+        })
+        .unwrap();
+    wait_for_width(&engine, node, 50.0);
+
+    engine
+        .replace_stylesheet(sheet, ".box { width: 80px; }")
+        .unwrap();
+    wait_for_width(&engine, node, 80.0);
+
+    // Removed rules no longer apply, rather than lingering in the resolved style
+    let unstyled_width = engine.node_bounds(Id::from_u64(2)).unwrap().width;
+    engine.remove_stylesheet(sheet).unwrap();
+    wait_for_width(&engine, node, unstyled_width);
+}
+
+#[test]
+fn test_layers_order_stylesheets_regardless_of_load_order() {
+    let engine = Engine::new();
+    engine.declare_layers(&["theme", "components"]).unwrap();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    engine
+        .add_layered_stylesheet("components", ".box { width: 60px; }")
+        .unwrap();
+    engine
+        .add_layered_stylesheet("theme", ".box { width: 40px; height: 10px; }")
+        .unwrap();
+    wait_for_width(&engine, node, 60.0);
+
+    // Unlayered app styles win over every layer
+    engine.add_stylesheet(".box { width: 90px; }").unwrap();
+    engine
+        .add_layered_stylesheet("components", ".box { width: 70px; }")
+        .unwrap();
+    wait_for_width(&engine, node, 90.0);
+    assert_eq!(engine.node_bounds(node).unwrap().height, 10.0);
+}
+
+#[test]
+fn test_origins_outrank_load_order_and_specificity() {
+    let engine = Engine::new();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    engine.add_stylesheet(".box { width: 60px; }").unwrap();
+    engine
+        .add_stylesheet_with_origin(
+            StyleOrigin::Library,
+            ".box.box { width: 40px; height: 10px; }",
+        )
+        .unwrap();
+    wait_for_width(&engine, node, 60.0);
+    assert_eq!(engine.node_bounds(node).unwrap().height, 10.0);
+
+    engine
+        .add_stylesheet_with_origin(StyleOrigin::UserOverride, ".box { width: 80px; }")
+        .unwrap();
+    engine
+        .add_stylesheet(".box.box.box { width: 70px; }")
+        .unwrap();
+    wait_for_width(&engine, node, 80.0);
+
+    // Important declarations reverse the order of origins
+    engine
+        .add_stylesheet(".box { width: 20px !important; }")
+        .unwrap();
+    engine
+        .add_stylesheet_with_origin(StyleOrigin::Library, ".box { width: 30px !important; }")
+        .unwrap();
+    wait_for_width(&engine, node, 30.0);
+}
+
+#[test]
+fn test_important_declarations_outrank_normal_ones() {
+    let engine = Engine::new();
+    engine.declare_layers(&["reset", "theme"]).unwrap();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    engine
+        .add_stylesheet(
+            ".box { width: 30px !important; height: 5px; } #none, .box { width: 90px; }",
+        )
+        .unwrap();
+    wait_for_width(&engine, node, 30.0);
+
+    // Among important declarations the earliest layer wins, even over unlayered ones
+    engine
+        .add_layered_stylesheet("theme", ".box { width: 50px !important; }")
+        .unwrap();
+    engine
+        .add_layered_stylesheet("reset", ".box { width: 40px !important; }")
+        .unwrap();
+    wait_for_width(&engine, node, 40.0);
+    assert_eq!(engine.node_bounds(node).unwrap().height, 5.0);
+}
+
+#[test]
+fn test_interaction_state_restyles_nodes() {
+    let engine = Engine::new();
+    engine
+        .add_stylesheet(
+            ".button { width: 10px; } .button:hover { width: 20px; } \
+             .button:active { width: 30px; } .button:focus { height: 5px; }",
+        )
+        .unwrap();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "button".to_owned())
+        .unwrap();
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(node).unwrap().width, 10.0);
+
+    // Pointer changes are laid out without waiting for the debounce
+    engine.send(Command::SetPointer(Some(node), false)).unwrap();
+    wait_for_width(&engine, node, 20.0);
+    engine.send(Command::SetPointer(Some(node), true)).unwrap();
+    wait_for_width(&engine, node, 30.0);
+    engine.send(Command::SetPointer(None, false)).unwrap();
+    wait_for_width(&engine, node, 10.0);
+
+    engine.focus(Some(node));
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(node).unwrap().height, 5.0);
+}
+
+#[test]
+fn test_sibling_changes_restyle_structural_pseudo_classes() {
+    let engine = Engine::new();
+    engine
+        .add_stylesheet("div { width: 10px; } div:last-child { width: 20px; }")
+        .unwrap();
+    let first = engine
+        .create_node_with_tag(Id::from_u64(1), "div", None)
+        .unwrap();
+    let second = engine
+        .create_node_with_tag(Id::from_u64(2), "div", None)
+        .unwrap();
+    engine.set_parent(engine.root_id(), first).unwrap();
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(first).unwrap().width, 20.0);
+
+    // Appending a sibling takes `:last-child` away from the first node
+    engine.set_parent(engine.root_id(), second).unwrap();
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(first).unwrap().width, 10.0);
+    assert_eq!(engine.node_bounds(second).unwrap().width, 20.0);
+}
+
+#[test]
+fn test_flush_waits_for_layout() {
+    let engine = Engine::new();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+
+    // Without flushing, the debounced layout would not have run yet.
+    engine.flush().unwrap();
+    assert!(engine.node_bounds(node).is_some());
+
+    engine
+        .set_attribute(node, "style".to_owned(), String::new())
+        .unwrap();
+    let signal = engine.flush_async().unwrap();
+    assert!(signal.recv_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn test_flush_after_exit_fails() {
+    let engine = Engine::new();
+    engine.request_exit().unwrap();
+
+    assert_eq!(engine.flush(), Err(EngineError::CommandThreadDown));
+}
+
+#[test]
+fn test_save_and_load_document() {
+    let path = std::env::temp_dir().join(format!("lolite_document_{}.json", std::process::id()));
+
+    let engine = Engine::new();
+    engine
+        .transaction(|tx| {
+            tx.add_stylesheet(".box { width: 50px; }");
+            let node = tx.create_node(Id::from_u64(1), None);
+            tx.set_parent(Id::from_u64(0), node);
+            tx.set_attribute(node, "class".to_owned(), "box".to_owned());
+        })
+        .unwrap();
+    engine.save_document(&path).unwrap();
+
+    let restored = Engine::new();
+    let sheets = restored.load_document(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(sheets.len(), 1);
+
+    restored.flush().unwrap();
+    assert_eq!(restored.node_bounds(Id::from_u64(1)).unwrap().width, 50.0);
+}
+
+#[test]
+fn test_rollback_restores_checkpointed_nodes() {
+    let engine = Engine::new();
+    let list = engine.create_node(Id::from_u64(1), None).unwrap();
+    let first = engine.create_node(Id::from_u64(2), None).unwrap();
+    engine.set_parent(engine.root_id(), list).unwrap();
+    engine.set_parent(list, first).unwrap();
+    let checkpoint = engine.checkpoint().unwrap();
+
+    let second = engine.create_node(Id::from_u64(3), None).unwrap();
+    engine.set_parent(list, second).unwrap();
+    engine.set_parent(engine.root_id(), first).unwrap();
+    engine
+        .set_attribute(list, "class".to_owned(), "changed".to_owned())
+        .unwrap();
+    assert_eq!(engine.children(list), Ok(Some(vec![second])));
+
+    engine.rollback(checkpoint).unwrap();
+    assert_eq!(engine.children(list), Ok(Some(vec![first])));
+    assert_eq!(engine.children(second), Ok(None));
+    assert_eq!(engine.query_selector_all(".changed"), Ok(vec![]));
+
+    // A checkpoint can be rolled back to again, until discarded
+    engine.set_parent(engine.root_id(), first).unwrap();
+    engine.rollback(checkpoint).unwrap();
+    assert_eq!(engine.parent(first), Ok(Some(list)));
+    engine.discard_checkpoint(checkpoint).unwrap();
+    engine.set_parent(engine.root_id(), first).unwrap();
+    engine.rollback(checkpoint).unwrap();
+    assert_eq!(engine.parent(first), Ok(Some(engine.root_id())));
+}
+
+#[test]
+fn test_add_compiled_stylesheet() {
+    let engine = Engine::new();
+    let compiled = compile_stylesheet(".box { width: 70px; }").unwrap();
+
+    assert_eq!(
+        engine.add_compiled_stylesheet(b"not a stylesheet"),
+        Err(EngineError::InvalidCompiledStylesheet)
+    );
+    engine.add_compiled_stylesheet(&compiled).unwrap();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(node).unwrap().width, 70.0);
+}
+
+#[test]
+fn test_id_selectors_target_a_single_node() {
+    let engine = Engine::new();
+    engine
+        .add_stylesheet(".box { width: 10px; } #wide { width: 80px; }")
+        .unwrap();
+    let nodes: Vec<_> = (1..=2)
+        .map(|i| {
+            let node = engine.create_node(Id::from_u64(i), None).unwrap();
+            engine.set_parent(engine.root_id(), node).unwrap();
+            engine
+                .set_attribute(node, "class".to_owned(), "box".to_owned())
+                .unwrap();
+            node
+        })
+        .collect();
+    engine
+        .set_attribute(nodes[1], "id".to_owned(), "wide".to_owned())
+        .unwrap();
+
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(nodes[0]).unwrap().width, 10.0);
+    assert_eq!(engine.node_bounds(nodes[1]).unwrap().width, 80.0);
+}
+
+#[test]
+fn test_type_and_universal_selectors() {
+    let engine = Engine::new();
+    engine
+        .add_stylesheet("* { height: 5px; } button { width: 40px; }")
+        .unwrap();
+    let button = engine
+        .create_node_with_tag(Id::from_u64(1), "BUTTON", None)
+        .unwrap();
+    let plain = engine.create_node(Id::from_u64(2), None).unwrap();
+    for node in [button, plain] {
+        engine.set_parent(engine.root_id(), node).unwrap();
+    }
+
+    engine.flush().unwrap();
+    let bounds = engine.node_bounds(button).unwrap();
+    assert_eq!((bounds.width, bounds.height), (40.0, 5.0));
+    assert_eq!(engine.node_bounds(plain).unwrap().height, 5.0);
+    assert_eq!(engine.query_selector_all("button"), Ok(vec![button]));
+    assert_eq!(engine.query_selector_all("*").unwrap().len(), 3);
+}
+
+#[test]
+fn test_descendant_and_child_combinators() {
+    let engine = Engine::new();
+    engine
+        .add_stylesheet(".panel .title { height: 7px; } .panel > .title { width: 30px; }")
+        .unwrap();
+    let ids = [
+        ("panel", engine.root_id()),
+        ("title", Id::from_u64(1)),
+        ("body", Id::from_u64(1)),
+        ("title", Id::from_u64(3)),
+    ];
+    let nodes: Vec<_> = ids
+        .iter()
+        .enumerate()
+        .map(|(i, (class, parent))| {
+            let node = engine
+                .create_node(Id::from_u64(i as u64 + 1), None)
+                .unwrap();
+            engine.set_parent(*parent, node).unwrap();
+            engine
+                .set_attribute(node, "class".to_owned(), (*class).to_owned())
+                .unwrap();
+            node
+        })
+        .collect();
+
+    engine.flush().unwrap();
+    let direct = engine.node_bounds(nodes[1]).unwrap();
+    assert_eq!((direct.width, direct.height), (30.0, 7.0));
+    let nested = engine.node_bounds(nodes[3]).unwrap();
+    assert_eq!(nested.height, 7.0);
+    assert_ne!(nested.width, 30.0);
+    assert_eq!(
+        engine.query_selector_all(".panel .title"),
+        Ok(vec![nodes[1], nodes[3]])
+    );
+    assert_eq!(
+        engine.query_selector_all(".panel > .title"),
+        Ok(vec![nodes[1]])
+    );
+}
+
+#[test]
+fn test_scoped_stylesheets_stay_inside_their_subtree() {
+    let engine = Engine::new();
+    let app = Id::from_u64(1);
+    let widget = Id::from_u64(2);
+    let inner = Id::from_u64(3);
+    for (node, parent) in [
+        (app, engine.root_id()),
+        (widget, engine.root_id()),
+        (inner, widget),
+    ] {
+        engine.create_node(node, None).unwrap();
+        engine.set_parent(parent, node).unwrap();
+        engine
+            .set_attribute(node, "class".to_owned(), "card".to_owned())
+            .unwrap();
+    }
+    engine.add_stylesheet(".card { width: 50px; }").unwrap();
+    engine
+        .add_scoped_stylesheet(widget, ".card { height: 9px; }")
+        .unwrap();
+
+    engine.flush().unwrap();
+    let size = |node| {
+        let bounds = engine.node_bounds(node).unwrap();
+        (bounds.width, bounds.height)
+    };
+    // The scoped rule doesn't leak out
+    assert_eq!(size(app).0, 50.0);
+    assert_ne!(size(app).1, 9.0);
+    // Both apply to the scope root
+    assert_eq!(size(widget), (50.0, 9.0));
+    // Document rules don't reach inside
+    let inner_style = engine.computed_style(inner).unwrap();
+    assert_eq!(inner_style.get("width"), Some("auto"));
+    assert_eq!(size(inner).1, 9.0);
+}
+
+#[test]
+fn test_attribute_selectors_follow_state_changes() {
+    let engine = Engine::new();
+    engine
+        .add_stylesheet(
+            "[data-state] { height: 12px; } \
+             [data-state=open] { width: 60px; } \
+             [data-flags~=tall] { height: 40px; }",
+        )
+        .unwrap();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    let set = |key: &str, value: &str| {
+        engine
+            .set_attribute(node, key.to_owned(), value.to_owned())
+            .unwrap();
+        engine.flush().unwrap();
+        let bounds = engine.node_bounds(node).unwrap();
+        (bounds.width, bounds.height)
+    };
+
+    assert_eq!(set("data-state", "open"), (60.0, 12.0));
+    let (width, height) = set("data-state", "closed");
+    assert_ne!(width, 60.0);
+    assert_eq!(height, 12.0);
+    assert_eq!(set("data-flags", "wide tall").1, 40.0);
+    assert_eq!(
+        engine.query_selector_all("[data-state=closed]"),
+        Ok(vec![node])
+    );
+}
+
+#[test]
+fn test_selector_lists_use_the_specificity_of_the_matching_selector() {
+    let engine = Engine::new();
+    engine
+        .add_stylesheet(
+            "#hero, .card { width: 30px; } \
+             .card { width: 20px; height: 8px; } \
+             card, panel { width: 10px; height: 4px; }",
+        )
+        .unwrap();
+    let hero = engine
+        .create_node_with_tag(Id::from_u64(1), "card", None)
+        .unwrap();
+    let plain = engine
+        .create_node_with_tag(Id::from_u64(2), "card", None)
+        .unwrap();
+    for node in [hero, plain] {
+        engine.set_parent(engine.root_id(), node).unwrap();
+        engine
+            .set_attribute(node, "class".to_owned(), "card".to_owned())
+            .unwrap();
+    }
+    engine
+        .set_attribute(hero, "id".to_owned(), "hero".to_owned())
+        .unwrap();
+
+    engine.flush().unwrap();
+    let size = |node| {
+        let bounds = engine.node_bounds(node).unwrap();
+        (bounds.width, bounds.height)
+    };
+    // The id selector outranks the later class rule, the type rule loses to both
+    assert_eq!(size(hero), (30.0, 8.0));
+    // Only `.card` matches: equal specificity, so the later rule wins
+    assert_eq!(size(plain), (20.0, 8.0));
+    assert_eq!(engine.query_selector_all("#hero, panel"), Ok(vec![hero]));
+}
+
+#[test]
+fn test_traversal_and_query_selector() {
+    let engine = Engine::new();
+    engine
+        .transaction(|tx| {
+            let list = tx.create_node(Id::from_u64(1), None);
+            tx.set_parent(Id::from_u64(0), list);
+            for i in 2..=4 {
+                let item = tx.create_node(Id::from_u64(i), None);
+                tx.set_parent(list, item);
+                let class = if i == 3 { "item selected" } else { "item" };
+                tx.set_attribute(item, "class".to_owned(), class.to_owned());
+            }
+            tx.create_node(Id::from_u64(5), None);
+        })
+        .unwrap();
+    let list = Id::from_u64(1);
+
+    assert_eq!(engine.children(engine.root_id()), Ok(Some(vec![list])));
+    assert_eq!(
+        engine.children(list),
+        Ok(Some((2..=4).map(Id::from_u64).collect()))
+    );
+    assert_eq!(engine.children(Id::from_u64(99)), Ok(None));
+    assert_eq!(engine.parent(Id::from_u64(3)), Ok(Some(list)));
+    assert_eq!(engine.parent(engine.root_id()), Ok(None));
+    assert_eq!(engine.parent(Id::from_u64(5)), Ok(None));
+
+    assert_eq!(engine.query_selector(".item"), Ok(Some(Id::from_u64(2))));
+    assert_eq!(
+        engine.query_selector(".selected"),
+        Ok(Some(Id::from_u64(3)))
+    );
+    assert_eq!(engine.query_selector(".missing"), Ok(None));
+    assert_eq!(engine.query_selector_all(".item").unwrap().len(), 3);
+    assert_eq!(engine.query_selector("#list"), Ok(None));
+    engine
+        .set_attribute(list, "id".to_owned(), "list".to_owned())
+        .unwrap();
+    assert_eq!(engine.query_selector("#list"), Ok(Some(list)));
+    assert_eq!(
+        engine.query_selector(".item {"),
+        Err(EngineError::InvalidSelector)
+    );
+}
+
+#[test]
+fn test_clone_subtree_stamps_templates() {
+    let engine = Engine::new();
+    let list = engine.create_node(Id::from_u64(1), None).unwrap();
+    let template = engine.create_node(Id::from_u64(2), None).unwrap();
+    let label = engine
+        .create_node(Id::from_u64(3), Some("Row".to_owned()))
+        .unwrap();
+    engine.set_parent(engine.root_id(), list).unwrap();
+    engine.set_parent(template, label).unwrap();
+    engine
+        .set_attribute(template, "class".to_owned(), "row".to_owned())
+        .unwrap();
+
+    for _ in 0..3 {
+        let row = engine.clone_subtree(template).unwrap().unwrap();
+        engine.set_parent(list, row).unwrap();
+    }
+
+    let rows = engine.children(list).unwrap().unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(engine.query_selector_all(".row"), Ok(rows.clone()));
+    assert!(!rows.contains(&template));
+    assert_eq!(engine.children(rows[0]).unwrap().unwrap().len(), 1);
+    assert_eq!(engine.parent(template), Ok(None));
+    assert_eq!(engine.clone_subtree(Id::from_u64(99)), Ok(None));
+}
+
+#[test]
+fn test_invalid_mutations_keep_document_consistent() {
+    let engine = Engine::new();
+    let a = engine.create_node(Id::from_u64(1), None).unwrap();
+    let b = engine.create_node(Id::from_u64(2), None).unwrap();
+    engine.set_parent(engine.root_id(), a).unwrap();
+    engine.set_parent(a, b).unwrap();
+
+    engine.set_parent(b, a).unwrap();
+    engine.create_node(a, None).unwrap();
+    engine.create_node(engine.root_id(), None).unwrap();
+
+    assert_eq!(engine.parent(a), Ok(Some(engine.root_id())));
+    assert_eq!(engine.children(a), Ok(Some(vec![b])));
+    assert_eq!(engine.children(engine.root_id()), Ok(Some(vec![a])));
+}
+
+#[test]
+fn test_set_viewport_resizes_root() {
+    let engine = Engine::new();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine.add_stylesheet(".half { width: 50vw; }").unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "half".to_owned())
+        .unwrap();
+
+    engine.set_viewport(600.0, 300.0).unwrap();
+    engine.flush().unwrap();
+    let root = engine.node_bounds(engine.root_id()).unwrap();
+    assert_eq!((root.width, root.height), (600.0, 300.0));
+    assert_eq!(engine.node_bounds(node).unwrap().width, 300.0);
+}
+
+#[test]
+fn test_media_rules_follow_the_viewport() {
+    let engine = Engine::new();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .add_stylesheet(".box { width: 100px; } @media (min-width: 500px) { .box { width: 200px; height: 20px; } }")
+        .unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    engine.set_viewport(400.0, 300.0).unwrap();
+    engine.flush().unwrap();
+    let narrow = engine.node_bounds(node).unwrap();
+    assert_eq!(narrow.width, 100.0);
+
+    engine.set_viewport(600.0, 300.0).unwrap();
+    engine.flush().unwrap();
+    let bounds = engine.node_bounds(node).unwrap();
+    assert_eq!((bounds.width, bounds.height), (200.0, 20.0));
+
+    // Crossing back below the breakpoint drops the media rule's declarations entirely
+    engine.set_viewport(400.0, 300.0).unwrap();
+    engine.flush().unwrap();
+    let bounds = engine.node_bounds(node).unwrap();
+    assert_eq!((bounds.width, bounds.height), (narrow.width, narrow.height));
+}
+
+#[test]
+fn test_media_rules_follow_the_color_scheme() {
+    let engine = Engine::new();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .add_stylesheet("@media (prefers-color-scheme: dark) { .box { width: 30px; } }")
+        .unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    engine.flush().unwrap();
+    let light = engine.node_bounds(node).unwrap();
+
+    engine.set_color_scheme(ColorScheme::Dark).unwrap();
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(node).unwrap().width, 30.0);
+
+    engine.set_color_scheme(ColorScheme::Light).unwrap();
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(node).unwrap().width, light.width);
+}
+
+#[test]
+fn test_media_rules_follow_the_motion_preference() {
+    let engine = Engine::new();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .add_stylesheet("@media (prefers-reduced-motion) { .box { width: 30px; } }")
+        .unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    engine
+        .set_motion_preference(MotionPreference::Reduce)
+        .unwrap();
+    engine.flush().unwrap();
+    assert_eq!(engine.node_bounds(node).unwrap().width, 30.0);
+
+    engine
+        .set_motion_preference(MotionPreference::NoPreference)
+        .unwrap();
+    engine.flush().unwrap();
+    assert_ne!(engine.node_bounds(node).unwrap().width, 30.0);
+}
+
+#[test]
+fn test_on_idle_runs_with_deadline() {
+    let engine = Engine::new();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let list = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), list).unwrap();
+
+    // Split the work across idle callbacks, each adding one node
+    fn add_rows(engine: Engine, list: Id, next: u64, sender: std::sync::mpsc::Sender<Instant>) {
+        let worker = engine.clone();
+        engine
+            .on_idle(move |deadline| {
+                let row = worker.create_node(Id::from_u64(next), None).unwrap();
+                worker.set_parent(list, row).unwrap();
+                if next < 4 {
+                    add_rows(worker, list, next + 1, sender.clone());
+                }
+                sender.send(deadline).unwrap();
+            })
+            .unwrap();
+    }
+    let started = Instant::now();
+    add_rows(engine.clone(), list, 2, sender);
+
+    for _ in 0..3 {
+        let deadline = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(deadline > started);
+        assert!(deadline <= Instant::now() + Duration::from_millis(50));
+    }
+    assert_eq!(
+        engine.children(list),
+        Ok(Some((2..=4).map(Id::from_u64).collect()))
+    );
+}
+
+#[test]
+fn test_zoom_shrinks_layout_viewport() {
+    let engine = Engine::new();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine.add_stylesheet(".fixed { width: 100px; }").unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "fixed".to_owned())
+        .unwrap();
+    engine.set_viewport(600.0, 300.0).unwrap();
+
+    engine.set_zoom(2.0).unwrap();
+    engine.flush().unwrap();
+    assert_eq!(engine.zoom(), 2.0);
+    let root = engine.node_bounds(engine.root_id()).unwrap();
+    assert_eq!((root.width, root.height), (300.0, 150.0));
+    assert_eq!(engine.node_bounds(node).unwrap().width, 100.0);
+
+    // A resize while zoomed keeps the zoom
+    engine.set_viewport(800.0, 400.0).unwrap();
+    engine.flush().unwrap();
+    let root = engine.node_bounds(engine.root_id()).unwrap();
+    assert_eq!((root.width, root.height), (400.0, 200.0));
+
+    engine.set_zoom(0.0).unwrap();
+    assert_eq!(engine.zoom(), 0.25);
+}
+
+#[test]
+fn test_client_and_node_coordinates_account_for_scroll_and_zoom() {
+    let engine = Engine::new();
+    engine
+        .transaction(|tx| {
+            tx.add_stylesheet(
+                ".list { margin-top: 10px; height: 100px; overflow: auto; } .item { height: 300px; padding: 4px; }",
+            );
+            let list = tx.create_node(Id::from_u64(1), None);
+            tx.set_parent(Id::from_u64(0), list);
+            tx.set_attribute(list, "class".to_owned(), "list".to_owned());
+            let item = tx.create_node(Id::from_u64(2), None);
+            tx.set_parent(list, item);
+            tx.set_attribute(item, "class".to_owned(), "item".to_owned());
+        })
+        .unwrap();
+    let (list, item) = (Id::from_u64(1), Id::from_u64(2));
+    wait_for_bounds(&engine, item);
+    engine.scroll_to(list, 0.0, 50.0).unwrap();
+    wait_for_scroll_offset(&engine, list, ScrollOffset { x: 0.0, y: 50.0 });
+    engine.set_zoom(2.0).unwrap();
+    engine.flush().unwrap();
+
+    // The item's content box starts at y = 10 + 4 in the document, scrolled up by 50
+    assert_eq!(engine.node_to_client(item, 0.0, 0.0), Some((8.0, -72.0)));
+    assert_eq!(engine.client_to_node(item, 8.0, -72.0), Some((0.0, 0.0)));
+    assert_eq!(engine.client_to_node(item, 20.0, 100.0), Some((6.0, 86.0)));
+    // The scroll container itself isn't shifted by its own offset
+    assert_eq!(engine.client_to_node(list, 20.0, 100.0), Some((10.0, 40.0)));
+
+    let bounds = engine.client_bounds(item).unwrap();
+    assert_eq!((bounds.y, bounds.height), (-80.0, 616.0));
+    assert_eq!(engine.client_to_node(Id::from_u64(9), 0.0, 0.0), None);
+}
+
+#[test]
+fn test_each_window_keeps_its_focused_node() {
+    let engine = Engine::new();
+    let second = engine.window(engine.create_window()).unwrap();
+    assert_eq!(engine.focused_node(), None);
+    assert_eq!(engine.focused_window(), None);
+
+    engine.focus(Some(Id::from_u64(1)));
+    second.focus(Some(Id::from_u64(2)));
+    assert_eq!(engine.focused_node(), Some(Id::from_u64(1)));
+    assert_eq!(second.focused_node(), Some(Id::from_u64(2)));
+
+    // Clones share the window
+    engine.clone().focus(None);
+    assert_eq!(engine.focused_node(), None);
+    assert_eq!(second.focused_node(), Some(Id::from_u64(2)));
+}
+
+#[test]
+fn test_windows_have_independent_documents() {
+    let engine = Engine::new();
+    let second_id = engine.create_window();
+    let second = engine.window(second_id).unwrap();
+    assert_eq!(engine.window_id().value(), 0);
+    assert_eq!(second.window_id(), second_id);
+    assert!(engine.window(WindowId(7)).is_none());
+
+    engine.add_stylesheet(".box { width: 50px; }").unwrap();
+    second.add_stylesheet(".box { width: 70px; }").unwrap();
+    for window in [&engine, &second] {
+        let node = window.create_node(Id::from_u64(1), None).unwrap();
+        window.set_parent(window.root_id(), node).unwrap();
+        window
+            .set_attribute(node, "class".to_owned(), "box".to_owned())
+            .unwrap();
+    }
+    let only_in_second = second.create_node(Id::from_u64(2), None).unwrap();
+    second.set_parent(second.root_id(), only_in_second).unwrap();
+
+    engine.flush().unwrap();
+    second.flush().unwrap();
+    assert_eq!(engine.node_bounds(Id::from_u64(1)).unwrap().width, 50.0);
+    assert_eq!(second.node_bounds(Id::from_u64(1)).unwrap().width, 70.0);
+    assert!(engine.node_bounds(only_in_second).is_none());
+
+    // Exiting from one window shuts down the others too
+    engine.request_exit().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while second.flush().is_ok() {
+        assert!(Instant::now() < deadline, "second window kept running");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_stats_count_nodes_and_rules() {
+    let engine = Engine::new();
+    let stats = engine.stats().unwrap();
+    assert_eq!(stats.node_count, 1);
+    assert_eq!(stats.last_layout_duration, None);
+
+    engine
+        .transaction(|tx| {
+            tx.add_stylesheet(".a { width: 10px; } .b { width: 20px; }");
+            for i in 1..=3 {
+                let node = tx.create_node(Id::from_u64(i), None);
+                tx.set_parent(Id::from_u64(0), node);
+            }
+            // Never attached
+            tx.create_node(Id::from_u64(4), None);
+        })
+        .unwrap();
+    engine.flush().unwrap();
+
+    let stats = engine.stats().unwrap();
+    assert_eq!(stats.node_count, 5);
+    assert_eq!(stats.detached_node_count, 1);
+    assert_eq!(stats.stylesheet_count, 1);
+    assert_eq!(stats.rule_count, 2);
+    assert!(stats.last_layout_duration.is_some());
+    assert_eq!(stats.snapshot_node_count, 4);
+}
+
+#[test]
+fn test_limits_reject_oversized_documents() {
+    let engine = Engine::new();
+    engine
+        .set_limits(Limits {
+            max_nodes: 4,
+            max_tree_depth: 2,
+            max_stylesheet_bytes: 32,
+            max_selector_complexity: 3,
+        })
+        .unwrap();
+    let rejected = Arc::new(Mutex::new(Vec::new()));
+    let sink = rejected.clone();
+    engine
+        .on_limit_exceeded(move |err| sink.lock().unwrap().push(err))
+        .unwrap();
+
+    assert_eq!(
+        engine.add_stylesheet(&".a { width: 10px; }".repeat(2)),
+        Err(EngineError::LimitExceeded(LimitError::StylesheetTooLarge {
+            bytes: 38,
+            limit: 32
+        }))
+    );
+    assert_eq!(
+        engine.add_stylesheet(".a .b > .c .d { width: 1px; }"),
+        Err(EngineError::LimitExceeded(LimitError::SelectorTooComplex {
+            compounds: 4,
+            limit: 3
+        }))
+    );
+    assert!(engine.add_stylesheet(".a { width: 10px; }").is_ok());
+
+    let root = engine.root_id();
+    let a = engine.create_node(Id::from_u64(1), None).unwrap();
+    let b = engine.create_node(Id::from_u64(2), None).unwrap();
+    let c = engine.create_node(Id::from_u64(3), None).unwrap();
+    engine.create_node(Id::from_u64(4), None).unwrap();
+    engine.set_parent(root, a).unwrap();
+    engine.set_parent(a, b).unwrap();
+    engine.set_parent(b, c).unwrap();
+    assert_eq!(engine.clone_subtree(a), Ok(None));
+    engine.flush().unwrap();
+
+    assert_eq!(engine.stats().unwrap().node_count, 4);
+    assert_eq!(engine.parent(c), Ok(None));
+    assert_eq!(
+        *rejected.lock().unwrap(),
+        vec![
+            LimitError::TooManyNodes { limit: 4 },
+            LimitError::TooDeep { limit: 2 },
+            LimitError::TooManyNodes { limit: 4 },
+        ]
+    );
+}
+
+#[test]
+fn test_default_backend_options_prefer_available_backends() {
+    let options = BackendOptions::default();
+
+    assert_eq!(options.preference, vec![BackendType::default()]);
+    assert!(options.preference.iter().all(BackendType::is_available));
+    assert_eq!(options.msaa_samples, 0);
+    assert!(options.anti_alias);
+}
+
+#[test]
+fn test_embedded_document_is_sized_and_hit_tested_in_its_node() {
+    let engine = Engine::new();
+    engine
+        .add_stylesheet(".frame { width: 200px; height: 100px; padding: 10px; }")
+        .unwrap();
+    let frame = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine
+        .set_attribute(frame, "class".to_owned(), "frame".to_owned())
+        .unwrap();
+    engine.set_parent(engine.root_id(), frame).unwrap();
+
+    // The embedded document's styles don't apply to the embedding one, and vice versa
+    let document = engine.create_document();
+    document
+        .add_stylesheet(".frame { width: 50px; height: 20px; }")
+        .unwrap();
+    let button = document.create_node(Id::from_u64(1), None).unwrap();
+    document
+        .set_attribute(button, "class".to_owned(), "frame".to_owned())
+        .unwrap();
+    document.set_parent(document.root_id(), button).unwrap();
+
+    engine.set_embedded_document(frame, &document).unwrap();
+    engine.flush().unwrap();
+    document.flush().unwrap();
+
+    // The viewport is the content box
+    assert_eq!(
+        document.node_bounds(document.root_id()),
+        Some(Rect::new(0.0, 0.0, 200.0, 100.0))
+    );
+    assert_eq!(engine.node_bounds(frame).unwrap().width, 220.0);
+
+    // Embedded hits come first, with the node embedding them
+    let hits = engine.hit_test(15.0, 15.0);
+    let path: Vec<_> = hits.iter().map(|hit| (hit.id, hit.host)).collect();
+    assert_eq!(
+        path,
+        vec![
+            (button, Some(frame)),
+            (document.root_id(), Some(frame)),
+            (frame, None),
+            (engine.root_id(), None),
+        ]
+    );
+    assert_eq!((hits[0].x, hits[0].y), (5.0, 5.0));
+    // Nodes of the embedded document aren't part of this document's paths
+    let snapshot = engine.latest_snapshot().unwrap();
+    assert_eq!(
+        snapshot.find_element_at_position(15.0, 15.0),
+        vec![frame, engine.root_id()]
+    );
+
+    // In the padding, and once no longer embedded, only this document is hit
+    assert_eq!(engine.hit_test(5.0, 5.0)[0].host, None);
+    engine.clear_embedded_document(frame).unwrap();
+    engine.flush().unwrap();
+    assert_eq!(engine.hit_test(15.0, 15.0)[0].id, frame);
+
+    engine.request_exit().unwrap();
+}
+
+#[test]
+fn test_style_sources_locate_and_cross_out_declarations() {
+    let engine = Engine::new();
+    let base = engine
+        .add_stylesheet(
+            ".box { width: 10px; }\n.box { padding: 2px; padding-top: 3px; }\n\
+             .box { --h: 4px; height: var(--h); height: var(--missing); }",
+        )
+        .unwrap();
+    let theme = engine
+        .add_layered_stylesheet("theme", "\n.box { width: 20px !important; }")
+        .unwrap();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    let sources = engine.style_sources(node).unwrap().unwrap();
+    let located: Vec<_> = sources
+        .iter()
+        .map(|source| {
+            (
+                source.property.as_str(),
+                source.important,
+                source.stylesheet,
+                source.span.line,
+                source.overridden,
+            )
+        })
+        .collect();
+    // A shorthand is only overridden once all of its longhands are
+    assert_eq!(
+        located,
+        vec![
+            ("width", false, Some(base), 1, true),
+            ("padding", false, Some(base), 2, false),
+            ("padding-top", false, Some(base), 2, false),
+            ("--h", false, Some(base), 3, false),
+            // Unresolvable references make the declaration ignored
+            ("height", false, Some(base), 3, false),
+            ("height", false, Some(base), 3, true),
+            ("width", true, Some(theme), 2, false),
+        ]
+    );
+    assert_eq!(
+        sources[1].to_string(),
+        format!("padding in stylesheet {} at 2:8", base.value())
+    );
+    assert_eq!(
+        sources[0].to_string(),
+        format!("width in stylesheet {} at 1:8 (overridden)", base.value())
+    );
+    assert_eq!(engine.style_sources(Id::from_u64(2)).unwrap(), None);
+}
+
+#[test]
+fn test_css_diagnostics_are_reported_to_the_callback() {
+    let engine = Engine::new();
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let sink = reported.clone();
+    engine
+        .on_css_diagnostic(move |stylesheet, diagnostic| {
+            sink.lock().unwrap().push((
+                stylesheet,
+                diagnostic.severity(),
+                diagnostic.property().map(str::to_string),
+                diagnostic.span.line,
+            ))
+        })
+        .unwrap();
+
+    let sheet = engine
+        .add_stylesheet(".a { width: 1px; }\n.b { colour: red; }")
+        .unwrap();
+    engine
+        .replace_stylesheet(sheet, ".a { width: banana; }")
+        .unwrap();
+    engine.flush().unwrap();
+
+    assert_eq!(
+        *reported.lock().unwrap(),
+        vec![
+            (sheet, CssSeverity::Error, Some("colour".to_string()), 2),
+            (sheet, CssSeverity::Error, Some("width".to_string()), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_imports_are_loaded_with_the_resolver() {
+    let engine = Engine::new();
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let sink = reported.clone();
+    engine
+        .on_css_diagnostic(move |stylesheet, diagnostic| {
+            sink.lock()
+                .unwrap()
+                .push((stylesheet, diagnostic.kind.clone()))
+        })
+        .unwrap();
+    engine
+        .set_import_resolver(|url| match url {
+            "base.css" => Some(
+                r#"@import "missing.css"; @import url(base.css);
+                   .box { width: 30px; height: 10px; }"#
+                    .to_string(),
+            ),
+            _ => None,
+        })
+        .unwrap();
+    let node = engine.create_node(Id::from_u64(1), None).unwrap();
+    engine.set_parent(engine.root_id(), node).unwrap();
+    engine
+        .set_attribute(node, "class".to_owned(), "box".to_owned())
+        .unwrap();
+
+    // The importing stylesheet's own rules win over the imported ones
+    let sheet = engine
+        .add_stylesheet(r#"@import url("base.css"); .box { height: 20px; }"#)
+        .unwrap();
+    wait_for_width(&engine, node, 30.0);
+    assert_eq!(engine.node_bounds(node).unwrap().height, 20.0);
+
+    // Missing and cyclic imports are skipped
+    assert_eq!(
+        *reported.lock().unwrap(),
+        vec![
+            (
+                sheet,
+                CssDiagnosticKind::UnresolvedImport("missing.css".to_string())
+            ),
+            (
+                sheet,
+                CssDiagnosticKind::UnresolvedImport("base.css".to_string())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_font_faces_are_passed_to_the_font_loader() {
+    let engine = Engine::new();
+    let requested = Arc::new(Mutex::new(Vec::new()));
+    let sink = requested.clone();
+    engine
+        .set_font_loader(move |face: &FontFace| {
+            sink.lock()
+                .unwrap()
+                .push((face.family.clone(), face.src.clone()));
+            None
+        })
+        .unwrap();
+
+    engine
+        .add_stylesheet(
+            r#"@font-face { font-family: Inter; src: url("inter.woff2") format("woff2"); }
+               @font-face { font-family: Inter; src: local(Inter Bold); font-weight: bold; }"#,
+        )
+        .unwrap();
+    engine.flush().unwrap();
+
+    assert_eq!(
+        *requested.lock().unwrap(),
+        vec![
+            (
+                "Inter".to_string(),
+                vec![FontSource::Url {
+                    url: "inter.woff2".to_string(),
+                    format: Some("woff2".to_string()),
+                }]
+            ),
+            (
+                "Inter".to_string(),
+                vec![FontSource::Local("Inter Bold".to_string())]
+            ),
+        ]
+    );
+}
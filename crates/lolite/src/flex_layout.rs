@@ -1,9 +1,10 @@
-use crate::layout::{LayoutContext, Node};
+use crate::layout::{LayoutContext, MeasureConstraints, Node, Rect, Size, SizeLimits};
 use crate::style::{
-    AlignContent, AlignItems, AlignSelf, BoxSizing, Directional, FlexDirection, FlexWrap,
-    JustifyContent, Length, Style,
+    AlignContent, AlignItems, AlignSelf, AspectRatio, BoxSizing, Directional, FlexDirection,
+    FlexWrap, JustifyContent, Length, Style, TextDirection,
 };
-use crate::text::FontSpec;
+use crate::text::{collapse_white_space_run, FontSpec};
+use crate::units::ResolutionContext;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -18,22 +19,21 @@ impl FlexLayoutEngine {
     /// Runs a simplified flex layout.
     ///
     /// This is intentionally structured to follow the spec step-by-step over time.
-    /// Currently, it implements the §9.1 “Initial Setup” anonymous flex item generation
-    /// (in a limited form, due to the lack of explicit DOM/text node typing in the engine).
+    /// Currently, it implements the §9.1 “Initial Setup” anonymous flex item generation,
+    /// with the text of an anonymous item laid out on a single line.
+    ///
+    /// `units` is the context the container's own lengths are resolved in.
     pub fn layout_flex_children(
         &self,
         container: Rc<RefCell<Node>>,
         container_style: &Style,
         ctx: &LayoutContext,
+        units: &ResolutionContext,
     ) {
         // === §9.1 Initial Setup ===
-        // Generate anonymous flex items as described in §4 Flex Items.
-        //
-        // Spec note: each in-flow child becomes a flex item, and each child text sequence is
-        // wrapped in an anonymous block container flex item (and whitespace-only sequences are not rendered).
-        //
-        // TODO: When the engine distinguishes element nodes vs text nodes and supports true
-        // "text sequences", implement proper anonymous flex item wrappers.
+        // Generate anonymous flex items as described in §4 Flex Items: each element child
+        // becomes a flex item, and each contiguous run of text children is wrapped in an
+        // anonymous flex item (see `FlexItem::text_run`).
 
         let direction = container_style.flex_direction.unwrap_or(FlexDirection::Row);
         let wrap = container_style.flex_wrap.unwrap_or(FlexWrap::NoWrap);
@@ -52,12 +52,14 @@ impl FlexLayoutEngine {
             }
         };
 
-        // Positioning origin for the flex container’s content box.
-        // Lolite currently models `width/height` as the primary size and uses padding as an
-        // inset for child placement.
+        // Positioning origin for the flex container’s content box, inset from the
+        // border box by the border and the padding.
         let padding = container_style.padding.resolved();
-        let content_origin_x = container_x + padding.left.to_px();
-        let content_origin_y = container_y + padding.top.to_px();
+        let border = container_style.border_width.resolved();
+        let content_origin_x =
+            container_x + units.inline_or_zero(border.left) + units.inline_or_zero(padding.left);
+        let content_origin_y =
+            container_y + units.inline_or_zero(border.top) + units.inline_or_zero(padding.top);
 
         // === §9.2 Line Length Determination ===
         // §9.2 #2 Determine the available main and cross space for the flex items.
@@ -70,53 +72,110 @@ impl FlexLayoutEngine {
         // Where the definite size is determined:
         // `is_definite_container_content_box_size_*()` below is our current notion of
         // “definite” (right now: explicit px sizes only).
-        let available_main =
-            determine_available_space(container_main, container_style, &direction, Axis::Main);
-        let available_cross =
-            determine_available_space(container_cross, container_style, &direction, Axis::Cross);
-
-        let row_gap_px = container_style.row_gap.unwrap_or(Length::Px(0.0)).to_px();
-        let column_gap_px = container_style
-            .column_gap
-            .unwrap_or(Length::Px(0.0))
-            .to_px();
+        let available_main = determine_available_space(
+            container_main,
+            container_style,
+            &direction,
+            Axis::Main,
+            units,
+        );
+        let available_cross = determine_available_space(
+            container_cross,
+            container_style,
+            &direction,
+            Axis::Cross,
+            units,
+        );
+
+        // In right-to-left text rows start at the right: items are placed as in
+        // left-to-right text, then mirrored within the content box.
+        let mirrored = matches!(direction, FlexDirection::Row | FlexDirection::RowReverse)
+            && container_style.direction == Some(TextDirection::Rtl);
+
+        // The container's content box is the containing block of its items.
+        let item_units = units.with_containing_block(match direction {
+            FlexDirection::Row | FlexDirection::RowReverse => Size {
+                width: available_main,
+                height: available_cross,
+            },
+            FlexDirection::Column | FlexDirection::ColumnReverse => Size {
+                width: available_cross,
+                height: available_main,
+            },
+        });
+
+        let (row_gap_px, column_gap_px) = gaps_px(container_style, &item_units);
         let (main_gap_px, cross_gap_px) = match direction {
             FlexDirection::Row | FlexDirection::RowReverse => (column_gap_px, row_gap_px),
             FlexDirection::Column | FlexDirection::ColumnReverse => (row_gap_px, column_gap_px),
         };
 
-        // Collect children, applying the "anonymous flex item" rules as best as we can.
-        // In this engine, nodes are not typed; we treat a "text node" as:
-        // - has `text: Some`,
-        // - has no attributes,
-        // - has no children.
-        let mut children: Vec<Rc<RefCell<Node>>> = {
-            let c = container.borrow();
-            c.children.clone()
-        };
+        // Group children into element items and runs of text.
+        let mut runs: Vec<Vec<Rc<RefCell<Node>>>> = Vec::new();
+        for child in container.borrow().box_children() {
+            match runs.last_mut() {
+                Some(run) if is_text_run(run) && child.borrow().is_text_node() => run.push(child),
+                _ => runs.push(vec![child]),
+            }
+        }
+
+        // Runs whose white space collapses away entirely are not rendered.
+        runs.retain(|run| {
+            let blank = is_text_run(run) && collapsed_run_text(run).iter().all(String::is_empty);
+            if blank {
+                for node in run {
+                    let mut node = node.borrow_mut();
+                    node.layout.bounds = Rect::new(content_origin_x, content_origin_y, 0.0, 0.0);
+                    node.layout.text = None;
+                }
+            }
+            !blank
+        });
 
-        // Apply 'order' if present.
-        children.sort_by_key(|child| {
-            let style = resolve_style(child, ctx, container_style);
+        // Apply 'order' if present; anonymous items keep the initial value of 0.
+        runs.sort_by_key(|run| {
+            if is_text_run(run) {
+                return 0;
+            }
+            let style = resolve_style(&run[0], ctx, container_style, &item_units);
             style.order.unwrap_or(0)
         });
 
+        let text_style = container_style.inherited();
         let mut items: Vec<FlexItem> = Vec::new();
-        for child in children {
-            let is_text_node = child.borrow().is_text_node();
-
-            if is_text_node {
-                let text = child.borrow().text.clone().unwrap_or_default();
-                if text.trim().is_empty() {
-                    // Whitespace-only child text sequences are not rendered.
-                    continue;
-                }
+        for run in runs {
+            if is_text_run(&run) {
+                let size = text_run_size(&run, &text_style, ctx);
+                let (base_main, base_cross) = match direction {
+                    FlexDirection::Row | FlexDirection::RowReverse => (size.width, size.height),
+                    FlexDirection::Column | FlexDirection::ColumnReverse => {
+                        (size.height, size.width)
+                    }
+                };
+                items.push(FlexItem {
+                    node: run[0].clone(),
+                    text_run: run,
+                    style: text_style.clone(),
+                    base_main,
+                    final_main: base_main,
+                    final_cross: base_cross,
+                    limits: SizeLimits::NONE,
+                    margin_main_before: Length::Px(0.0),
+                    margin_main_after: Length::Px(0.0),
+                    margin_cross_before: Length::Px(0.0),
+                    margin_cross_after: Length::Px(0.0),
+                });
+                continue;
             }
 
-            let style = resolve_style(&child, ctx, container_style);
+            let child = run[0].clone();
+            let style = resolve_style(&child, ctx, container_style, &item_units);
             let margins = style.margin.resolved();
-            let (main_before, main_after, cross_before, cross_after) =
+            let (mut main_before, mut main_after, cross_before, cross_after) =
                 margins_for_direction(&margins, &direction);
+            if mirrored {
+                std::mem::swap(&mut main_before, &mut main_after);
+            }
             // NOTE: This currently approximates §9.2 #3 “Determine the flex base size and
             // hypothetical main size of each item”.
             //
@@ -124,17 +183,26 @@ impl FlexLayoutEngine {
             // `base_sizes_for_item()` currently applies `flex-basis` directly, but the spec’s
             // detailed cases (definite basis vs content-based basis, etc.) will replace this.
             //
-            // Where aspect ratio will later be handled:
-            // The spec has cases where an item’s preferred/intrinsic aspect ratio affects its
-            // flex base size (see §9.2 #3). Lolite does not model aspect ratio yet.
-            let (base_main, base_cross) = base_sizes_for_item(&child, &style, &direction, ctx);
-
+            // An item’s preferred aspect ratio gives it a flex base size from a definite
+            // cross size (§9.2 #3 B), see `base_sizes_for_item()`.
+            let (base_main, base_cross) = base_sizes_for_item(
+                &child,
+                &style,
+                &direction,
+                ctx,
+                &own_units(&item_units, &style),
+            );
+
+            let limits = SizeLimits::of(&style, &item_units);
             items.push(FlexItem {
                 node: child,
+                text_run: Vec::new(),
                 style,
                 base_main,
-                final_main: base_main,
-                final_cross: base_cross,
+                // The hypothetical main size, until the line is resolved
+                final_main: clamp_axis(&limits, &direction, Axis::Main, base_main),
+                final_cross: clamp_axis(&limits, &direction, Axis::Cross, base_cross),
+                limits,
                 margin_main_before: main_before,
                 margin_main_after: main_after,
                 margin_cross_before: cross_before,
@@ -155,10 +223,10 @@ impl FlexLayoutEngine {
 
         for (index, item) in items.iter().enumerate() {
             let additional_gap = if current.is_empty() { 0.0 } else { main_gap_px };
-            let item_outer_base_main = item.base_main
-                + length_px_or_zero(&item.margin_main_before)
-                + length_px_or_zero(&item.margin_main_after);
-            let candidate_used = current_used_main + additional_gap + item_outer_base_main;
+            let item_outer_hypothetical_main = item.final_main
+                + item_units.inline_or_zero(item.margin_main_before)
+                + item_units.inline_or_zero(item.margin_main_after);
+            let candidate_used = current_used_main + additional_gap + item_outer_hypothetical_main;
 
             let should_wrap = can_wrap && !current.is_empty() && candidate_used > available_main;
             if should_wrap {
@@ -168,7 +236,7 @@ impl FlexLayoutEngine {
             }
 
             let gap = if current.is_empty() { 0.0 } else { main_gap_px };
-            current_used_main += gap + item_outer_base_main;
+            current_used_main += gap + item_outer_hypothetical_main;
             current.push(index);
         }
         if !current.is_empty() {
@@ -180,48 +248,24 @@ impl FlexLayoutEngine {
         let mut processed_lines: Vec<FlexLine> = Vec::new();
 
         for line in &lines {
-            // Resolve flexing within the line.
-            let total_outer_base_main = line.iter().enumerate().fold(0.0, |acc, (pos, idx)| {
-                let gap = if pos > 0 { main_gap_px } else { 0.0 };
-                let item = &items[*idx];
-                let outer = item.base_main
-                    + length_px_or_zero(&item.margin_main_before)
-                    + length_px_or_zero(&item.margin_main_after);
-                acc + gap + outer
-            });
-
-            let free_space = available_main - total_outer_base_main;
-            if free_space > 0.0 {
-                let total_grow: f64 = line
-                    .iter()
-                    .map(|idx| items[*idx].style.flex_grow.unwrap_or(0.0))
-                    .sum();
-
-                if total_grow > 0.0 {
-                    for idx in line {
-                        let grow = items[*idx].style.flex_grow.unwrap_or(0.0);
-                        items[*idx].final_main =
-                            items[*idx].base_main + (free_space * (grow / total_grow));
-                    }
-                }
-            } else if free_space < 0.0 {
-                let shrink_needed = -free_space;
-                let weights: Vec<f64> = line
-                    .iter()
-                    .map(|idx| {
-                        // In this codebase/tests, unspecified flex-shrink means "don't shrink".
-                        let shrink = items[*idx].style.flex_shrink.unwrap_or(0.0);
-                        shrink * items[*idx].base_main
-                    })
-                    .collect();
-
-                let total_weight: f64 = weights.iter().sum();
-                if total_weight > 0.0 {
-                    for (i, idx) in line.iter().enumerate() {
-                        let weight = weights[i];
-                        items[*idx].final_main =
-                            items[*idx].base_main - (shrink_needed * (weight / total_weight));
-                    }
+            resolve_flexible_lengths(
+                &mut items,
+                line,
+                &direction,
+                available_main,
+                main_gap_px,
+                &item_units,
+            );
+
+            // §9.4 #7 The hypothetical cross size follows the used main size, which for
+            // an item with an aspect ratio and an auto cross size is its main size
+            // through the ratio.
+            for idx in line {
+                let item = &mut items[*idx];
+                if let Some(cross) =
+                    cross_from_aspect_ratio(item, &direction, &own_units(&item_units, &item.style))
+                {
+                    item.final_cross = clamp_axis(&item.limits, &direction, Axis::Cross, cross);
                 }
             }
 
@@ -230,8 +274,8 @@ impl FlexLayoutEngine {
             for idx in line {
                 let item = &items[*idx];
                 let outer_cross = item.final_cross
-                    + length_px_or_zero(&item.margin_cross_before)
-                    + length_px_or_zero(&item.margin_cross_after);
+                    + item_units.inline_or_zero(item.margin_cross_before)
+                    + item_units.inline_or_zero(item.margin_cross_after);
                 line_cross_size = line_cross_size.max(outer_cross);
             }
 
@@ -279,14 +323,28 @@ impl FlexLayoutEngine {
                     AlignSelf::Center => AlignItems::Center,
                     AlignSelf::Baseline => AlignItems::Baseline,
                     AlignSelf::Stretch => AlignItems::Stretch,
+                    AlignSelf::Start => AlignItems::Start,
+                    AlignSelf::End => AlignItems::End,
                 };
 
+                // Items sized by an aspect ratio keep their shape rather than stretch
+                let has_ratio = items[*idx]
+                    .style
+                    .aspect_ratio
+                    .and_then(AspectRatio::value)
+                    .is_some();
                 if matches!(align, AlignItems::Stretch)
                     && cross_size_is_auto(&items[*idx].style, &direction)
+                    && !has_ratio
                 {
-                    let margins = length_px_or_zero(&items[*idx].margin_cross_before)
-                        + length_px_or_zero(&items[*idx].margin_cross_after);
-                    items[*idx].final_cross = (line.cross_size - margins).max(0.0);
+                    let margins = item_units.inline_or_zero(items[*idx].margin_cross_before)
+                        + item_units.inline_or_zero(items[*idx].margin_cross_after);
+                    items[*idx].final_cross = clamp_axis(
+                        &items[*idx].limits,
+                        &direction,
+                        Axis::Cross,
+                        (line.cross_size - margins).max(0.0),
+                    );
                 }
             }
         }
@@ -303,8 +361,8 @@ impl FlexLayoutEngine {
                     let gap = if pos > 0 { main_gap_px } else { 0.0 };
                     let item = &items[*idx];
                     let outer = item.final_main
-                        + length_px_or_zero(&item.margin_main_before)
-                        + length_px_or_zero(&item.margin_main_after);
+                        + item_units.inline_or_zero(item.margin_main_before)
+                        + item_units.inline_or_zero(item.margin_main_after);
                     acc + gap + outer
                 });
 
@@ -336,6 +394,7 @@ impl FlexLayoutEngine {
                 justify_offsets(
                     &justify_content,
                     &direction,
+                    mirrored,
                     leftover_for_main,
                     main_gap_px,
                     line.indices.len(),
@@ -349,13 +408,15 @@ impl FlexLayoutEngine {
                 }
 
                 let item = &items[*idx];
-                let main_before_px = resolve_margin_px(&item.margin_main_before, auto_margin_share);
-                let main_after_px = resolve_margin_px(&item.margin_main_after, auto_margin_share);
+                let main_before_px =
+                    resolve_margin_px(item.margin_main_before, auto_margin_share, &item_units);
+                let main_after_px =
+                    resolve_margin_px(item.margin_main_after, auto_margin_share, &item_units);
                 let cross_auto_count: usize = (is_auto(&item.margin_cross_before) as usize)
                     + (is_auto(&item.margin_cross_after) as usize);
 
-                let mut cross_before_px = length_px_or_zero(&item.margin_cross_before);
-                let mut cross_after_px = length_px_or_zero(&item.margin_cross_after);
+                let mut cross_before_px = item_units.inline_or_zero(item.margin_cross_before);
+                let mut cross_after_px = item_units.inline_or_zero(item.margin_cross_after);
 
                 // Auto margins in the cross axis absorb extra space and override
                 // align-self/align-items positioning.
@@ -385,6 +446,8 @@ impl FlexLayoutEngine {
                     AlignSelf::Center => AlignItems::Center,
                     AlignSelf::Baseline => AlignItems::Baseline,
                     AlignSelf::Stretch => AlignItems::Stretch,
+                    AlignSelf::Start => AlignItems::Start,
+                    AlignSelf::End => AlignItems::End,
                 };
 
                 let cross_pos = if cross_auto_count > 0 {
@@ -392,10 +455,13 @@ impl FlexLayoutEngine {
                     line_cross_offset + cross_before_px
                 } else {
                     match align {
-                        AlignItems::FlexStart | AlignItems::Baseline | AlignItems::Stretch => {
-                            line_cross_offset + cross_before_px
-                        }
-                        AlignItems::FlexEnd => {
+                        // Lines never wrap in reverse, so the cross axis starts where
+                        // the writing mode does.
+                        AlignItems::FlexStart
+                        | AlignItems::Start
+                        | AlignItems::Baseline
+                        | AlignItems::Stretch => line_cross_offset + cross_before_px,
+                        AlignItems::FlexEnd | AlignItems::End => {
                             line_cross_offset + (line.cross_size - outer_cross) + cross_before_px
                         }
                         AlignItems::Center => {
@@ -407,6 +473,12 @@ impl FlexLayoutEngine {
                 };
 
                 let (x, y, w, h) = match direction {
+                    FlexDirection::Row | FlexDirection::RowReverse if mirrored => (
+                        content_origin_x + available_main - cursor_main - item.final_main,
+                        content_origin_y + cross_pos,
+                        item.final_main,
+                        item.final_cross,
+                    ),
                     FlexDirection::Row | FlexDirection::RowReverse => (
                         content_origin_x + cursor_main,
                         content_origin_y + cross_pos,
@@ -421,17 +493,23 @@ impl FlexLayoutEngine {
                     ),
                 };
 
-                {
+                if item.text_run.is_empty() {
                     let mut node_borrow = item.node.borrow_mut();
                     node_borrow.layout.bounds.x = x;
                     node_borrow.layout.bounds.y = y;
                     node_borrow.layout.bounds.width = w;
                     node_borrow.layout.bounds.height = h;
                     node_borrow.layout.style = std::sync::Arc::new(item.style.clone());
+                } else {
+                    place_text_run(&item.text_run, Rect::new(x, y, w, h), &item.style, ctx);
                 }
 
-                if !item.node.borrow().children.is_empty() {
-                    self.layout_flex_children(item.node.clone(), &item.style, ctx);
+                if item.node.borrow().has_box_children() {
+                    ctx.layout_children(
+                        item.node.clone(),
+                        &item.style,
+                        &own_units(&item_units, &item.style),
+                    );
                 }
 
                 cursor_main += item.final_main + main_after_px;
@@ -445,16 +523,138 @@ impl FlexLayoutEngine {
 #[derive(Clone)]
 struct FlexItem {
     node: Rc<RefCell<Node>>,
+    /// Text nodes wrapped in an anonymous item, starting with `node`; empty for an
+    /// element.
+    text_run: Vec<Rc<RefCell<Node>>>,
     style: Style,
     base_main: f64,
     final_main: f64,
     final_cross: f64,
+    limits: SizeLimits,
     margin_main_before: Length,
     margin_main_after: Length,
     margin_cross_before: Length,
     margin_cross_after: Length,
 }
 
+/// §9.7 Resolving Flexible Lengths: grow or shrink the items of a line to fill the
+/// available main space, freezing those their min/max sizes stop at and distributing
+/// the rest of the space among the others.
+fn resolve_flexible_lengths(
+    items: &mut [FlexItem],
+    line: &[usize],
+    direction: &FlexDirection,
+    available_main: f64,
+    main_gap_px: f64,
+    units: &ResolutionContext,
+) {
+    let gaps_and_margins: f64 = main_gap_px * line.len().saturating_sub(1) as f64
+        + line
+            .iter()
+            .map(|idx| {
+                units.inline_or_zero(items[*idx].margin_main_before)
+                    + units.inline_or_zero(items[*idx].margin_main_after)
+            })
+            .sum::<f64>();
+    let available = available_main - gaps_and_margins;
+
+    // §9.7 #1 Grow if the hypothetical sizes (`final_main` so far) leave space
+    let hypothetical: f64 = line.iter().map(|idx| items[*idx].final_main).sum();
+    let growing = hypothetical < available;
+    // In this codebase/tests, unspecified flex-shrink means "don't shrink".
+    let factor = |item: &FlexItem| {
+        if growing {
+            item.style.flex_grow.unwrap_or(0.0)
+        } else {
+            item.style.flex_shrink.unwrap_or(0.0)
+        }
+    };
+
+    // §9.7 #2 Inflexible items keep their hypothetical size
+    let mut frozen: Vec<bool> = line
+        .iter()
+        .map(|idx| {
+            let item = &items[*idx];
+            factor(item) == 0.0
+                || (growing && item.base_main > item.final_main)
+                || (!growing && item.base_main < item.final_main)
+        })
+        .collect();
+
+    // §9.7 #4 Each round freezes at least one item
+    while frozen.iter().any(|frozen| !frozen) {
+        let remaining = available
+            - line
+                .iter()
+                .zip(&frozen)
+                .map(|(idx, frozen)| {
+                    let item = &items[*idx];
+                    if *frozen {
+                        item.final_main
+                    } else {
+                        item.base_main
+                    }
+                })
+                .sum::<f64>();
+
+        // Shrinking takes more from larger items
+        let weight = |item: &FlexItem| {
+            if growing {
+                factor(item)
+            } else {
+                factor(item) * item.base_main
+            }
+        };
+        let total_weight: f64 = line
+            .iter()
+            .zip(&frozen)
+            .filter(|(_, frozen)| !**frozen)
+            .map(|(idx, _)| weight(&items[*idx]))
+            .sum();
+
+        let mut total_violation = 0.0;
+        let mut violations = vec![0.0; line.len()];
+        for (pos, idx) in line.iter().enumerate() {
+            if frozen[pos] {
+                continue;
+            }
+            let item = &mut items[*idx];
+            let target = if total_weight > 0.0 {
+                item.base_main + remaining * weight(item) / total_weight
+            } else {
+                item.base_main
+            };
+            let clamped = clamp_axis(&item.limits, direction, Axis::Main, target);
+            violations[pos] = clamped - target;
+            total_violation += violations[pos];
+            item.final_main = clamped;
+        }
+
+        // §9.7 #4.d Freeze every item if the limits cancel out, else the ones clamped
+        // in the direction of the total violation
+        for (pos, frozen) in frozen.iter_mut().enumerate() {
+            *frozen = *frozen
+                || total_violation == 0.0
+                || (total_violation > 0.0 && violations[pos] > 0.0)
+                || (total_violation < 0.0 && violations[pos] < 0.0);
+        }
+    }
+}
+
+/// Clamp a border-box size along a flex axis to the node's min/max sizes.
+fn clamp_axis(limits: &SizeLimits, direction: &FlexDirection, axis: Axis, size: f64) -> f64 {
+    match (direction, axis) {
+        (FlexDirection::Row | FlexDirection::RowReverse, Axis::Main)
+        | (FlexDirection::Column | FlexDirection::ColumnReverse, Axis::Cross) => {
+            limits.clamp_width(size)
+        }
+        (FlexDirection::Row | FlexDirection::RowReverse, Axis::Cross)
+        | (FlexDirection::Column | FlexDirection::ColumnReverse, Axis::Main) => {
+            limits.clamp_height(size)
+        }
+    }
+}
+
 #[derive(Clone)]
 struct FlexLine {
     indices: Vec<usize>,
@@ -466,84 +666,128 @@ fn base_sizes_for_item(
     style: &Style,
     direction: &FlexDirection,
     ctx: &LayoutContext,
+    units: &ResolutionContext,
 ) -> (f64, f64) {
     // Where flex-basis will later be handled: this function is the current stand-in for
     // §9.2 #3 “flex base size / hypothetical main size” rules.
 
     let padding = style.padding.resolved();
-    let padding_w = padding.left.to_px() + padding.right.to_px();
-    let padding_h = padding.top.to_px() + padding.bottom.to_px();
+    let padding_w = units.horizontal(&padding);
+    let padding_h = units.vertical(&padding);
     let border = style.border_width.resolved();
-    let border_w = border.left.to_px() + border.right.to_px();
-    let border_h = border.top.to_px() + border.bottom.to_px();
+    let border_w = units.horizontal(&border);
+    let border_h = units.vertical(&border);
     let box_sizing = style.box_sizing.unwrap_or(BoxSizing::ContentBox);
 
-    let width_opt = match style.width {
-        Some(Length::Px(px)) if px > 0.0 => Some(match box_sizing {
+    let width_opt = match style.width.and_then(|width| units.inline(width)) {
+        Some(px) if px > 0.0 => Some(match box_sizing {
             BoxSizing::ContentBox => px + padding_w + border_w,
             BoxSizing::BorderBox => px,
         }),
         _ => None,
     };
-    let height_opt = match style.height {
-        Some(Length::Px(px)) if px > 0.0 => Some(match box_sizing {
+    let height_opt = match style.height.and_then(|height| units.block(height)) {
+        Some(px) if px > 0.0 => Some(match box_sizing {
             BoxSizing::ContentBox => px + padding_h + border_h,
             BoxSizing::BorderBox => px,
         }),
         _ => None,
     };
 
-    let mut width = width_opt.unwrap_or(100.0);
-    let mut height = height_opt.unwrap_or(30.0);
+    // A preferred aspect ratio gives an auto width or height its size from the other.
+    let ratio = style.aspect_ratio.and_then(AspectRatio::value);
+    let (width_edges, height_edges) = (padding_w + border_w, padding_h + border_h);
+    let (width_opt, height_opt) = match (ratio, width_opt, height_opt) {
+        (Some(ratio), Some(width), None) => (
+            Some(width),
+            Some(transfer_size(
+                width,
+                1.0 / ratio,
+                width_edges,
+                height_edges,
+                box_sizing,
+            )),
+        ),
+        (Some(ratio), None, Some(height)) => (
+            Some(transfer_size(
+                height,
+                ratio,
+                height_edges,
+                width_edges,
+                box_sizing,
+            )),
+            Some(height),
+        ),
+        _ => (width_opt, height_opt),
+    };
+
+    let defaults = &ctx.text_node_defaults;
+    let mut width = width_opt.unwrap_or(defaults.size.width);
+    let mut height = height_opt.unwrap_or(defaults.size.height);
 
-    // If this looks like a text node and doesn't have explicit sizes, prefer intrinsic text sizing.
-    let is_text_node = node.borrow().is_text_node();
+    // If it holds text and doesn't have explicit sizes, prefer intrinsic text sizing.
+    if let Some(text) = node.borrow().layout.text.as_deref() {
+        let font = FontSpec::from_style(style);
 
-    if is_text_node {
-        if let Some(text) = node.borrow().text.as_deref() {
-            let font = FontSpec::from_style(style);
+        if width_opt.is_none() {
+            let text_size = ctx.text_measurer.measure_unwrapped(text, &font);
+            width = text_size.width + padding_w + border_w;
+        }
+
+        if height_opt.is_none() {
+            let content_max_width =
+                width_opt.map(|border_box| (border_box - padding_w - border_w).max(0.0));
+            let text_size =
+                defaults.measure(ctx.text_measurer.as_ref(), text, &font, content_max_width);
 
+            height = text_size.height + padding_h + border_h;
+        }
+    } else if !node.borrow().has_box_children() {
+        // Leaves with custom content report their own intrinsic size.
+        if let Some(measure) = ctx.measure_functions.get(&node.borrow().id) {
+            let size = measure(MeasureConstraints {
+                width: width_opt.map(|w| (w - padding_w - border_w).max(0.0)),
+                height: height_opt.map(|h| (h - padding_h - border_h).max(0.0)),
+            });
             if width_opt.is_none() {
-                let text_size = ctx.text_measurer.measure_unwrapped(text, &font);
-                width = text_size.width + padding_w + border_w;
+                width = size.width + padding_w + border_w;
             }
-
             if height_opt.is_none() {
-                let text_size = match style.width {
-                    Some(Length::Px(specified_width_px)) if specified_width_px > 0.0 => {
-                        let content_max_width = match box_sizing {
-                            BoxSizing::ContentBox => specified_width_px,
-                            BoxSizing::BorderBox => {
-                                (specified_width_px - padding_w - border_w).max(0.0)
-                            }
-                        };
-                        ctx.text_measurer
-                            .measure_wrapped(text, &font, content_max_width)
-                    }
-                    _ => ctx.text_measurer.measure_unwrapped(text, &font),
-                };
-
-                height = text_size.height + padding_h + border_h;
+                height = size.height + padding_h + border_h;
             }
         }
     }
 
+    // With neither size set, the height follows the width, but never leaves text
+    // overflowing.
+    if let (Some(ratio), None, None) = (ratio, width_opt, height_opt) {
+        let from_ratio = transfer_size(width, 1.0 / ratio, width_edges, height_edges, box_sizing);
+        height = if node.borrow().layout.text.is_some() {
+            height.max(from_ratio)
+        } else {
+            from_ratio
+        };
+    }
+
     let (main_from_size, cross_from_size) = match direction {
         FlexDirection::Row | FlexDirection::RowReverse => (width, height),
         FlexDirection::Column | FlexDirection::ColumnReverse => (height, width),
     };
 
-    let mut main = match style.flex_basis.as_ref() {
-        Some(Length::Px(px)) => *px,
-        Some(Length::Auto) => main_from_size,
-        Some(other) => other.to_px(),
-        None => main_from_size,
+    // Percentages of `flex-basis` refer to the container's inner main size.
+    let container_main = match direction {
+        FlexDirection::Row | FlexDirection::RowReverse => units.containing_block.width,
+        FlexDirection::Column | FlexDirection::ColumnReverse => units.containing_block.height,
     };
+    let mut main = style
+        .flex_basis
+        .and_then(|basis| units.resolve(basis, container_main))
+        .unwrap_or(main_from_size);
 
     // If the item is itself a container and has no explicit main size, approximate
     // shrink-to-fit by looking at its children’s fixed sizes.
     // This is a pragmatic bridge until we implement the full intrinsic sizing path.
-    let is_container = !node.borrow().children.is_empty();
+    let is_container = node.borrow().has_box_children();
     let has_explicit_main = match direction {
         FlexDirection::Row | FlexDirection::RowReverse => {
             matches!(style.width, Some(Length::Px(_)))
@@ -560,9 +804,12 @@ fn base_sizes_for_item(
             FlexDirection::Column | FlexDirection::ColumnReverse => height_opt.is_none(),
         };
 
-        let intrinsic = intrinsic_main_from_children(node, direction, ctx, style);
+        // The children fit in the content box
+        let intrinsic = intrinsic_main_from_children(node, direction, ctx, style, units);
         if intrinsic > 0.0 && main_was_default {
-            main = intrinsic;
+            main = intrinsic
+                + axis_padding_sum_px(style, direction, Axis::Main, units)
+                + axis_border_sum_px(style, direction, Axis::Main, units);
         }
     }
 
@@ -600,9 +847,10 @@ fn content_box_axis_size(
     style: &Style,
     direction: &FlexDirection,
     axis: Axis,
+    units: &ResolutionContext,
 ) -> f64 {
-    let padding = axis_padding_sum_px(style, direction, axis);
-    let border = axis_border_sum_px(style, direction, axis);
+    let padding = axis_padding_sum_px(style, direction, axis, units);
+    let border = axis_border_sum_px(style, direction, axis, units);
 
     let box_sizing = style.box_sizing.unwrap_or(BoxSizing::ContentBox);
     if let Some(Length::Px(px)) = specified_axis_length(style, direction, axis) {
@@ -620,42 +868,49 @@ fn determine_available_space(
     style: &Style,
     direction: &FlexDirection,
     axis: Axis,
+    units: &ResolutionContext,
 ) -> f64 {
     // §9.2 #2 Determine the available space in the container's content box.
     // Lolite stores `container_axis_size` as the border-box size.
     // If the container has a definite size, we derive the content-box size using `box-sizing`.
     // Otherwise, we approximate by subtracting padding/border from the border-box.
     let _is_definite = is_definite_container_content_box_size(style, direction, axis);
-    content_box_axis_size(container_axis_size, style, direction, axis)
+    content_box_axis_size(container_axis_size, style, direction, axis, units)
 }
 
-fn axis_padding_sum_px(style: &Style, direction: &FlexDirection, axis: Axis) -> f64 {
+fn axis_padding_sum_px(
+    style: &Style,
+    direction: &FlexDirection,
+    axis: Axis,
+    units: &ResolutionContext,
+) -> f64 {
     let p = style.padding.resolved();
 
     match (direction, axis) {
         (FlexDirection::Row | FlexDirection::RowReverse, Axis::Main)
         | (FlexDirection::Column | FlexDirection::ColumnReverse, Axis::Cross) => {
-            p.left.to_px() + p.right.to_px()
+            units.horizontal(&p)
         }
         (FlexDirection::Row | FlexDirection::RowReverse, Axis::Cross)
-        | (FlexDirection::Column | FlexDirection::ColumnReverse, Axis::Main) => {
-            p.top.to_px() + p.bottom.to_px()
-        }
+        | (FlexDirection::Column | FlexDirection::ColumnReverse, Axis::Main) => units.vertical(&p),
     }
 }
 
-fn axis_border_sum_px(style: &Style, direction: &FlexDirection, axis: Axis) -> f64 {
+fn axis_border_sum_px(
+    style: &Style,
+    direction: &FlexDirection,
+    axis: Axis,
+    units: &ResolutionContext,
+) -> f64 {
     let b = style.border_width.resolved();
 
     match (direction, axis) {
         (FlexDirection::Row | FlexDirection::RowReverse, Axis::Main)
         | (FlexDirection::Column | FlexDirection::ColumnReverse, Axis::Cross) => {
-            b.left.to_px() + b.right.to_px()
+            units.horizontal(&b)
         }
         (FlexDirection::Row | FlexDirection::RowReverse, Axis::Cross)
-        | (FlexDirection::Column | FlexDirection::ColumnReverse, Axis::Main) => {
-            b.top.to_px() + b.bottom.to_px()
-        }
+        | (FlexDirection::Column | FlexDirection::ColumnReverse, Axis::Main) => units.vertical(&b),
     }
 }
 
@@ -664,12 +919,13 @@ fn intrinsic_main_from_children(
     parent_direction: &FlexDirection,
     ctx: &LayoutContext,
     fallback: &Style,
+    units: &ResolutionContext,
 ) -> f64 {
     // Best-effort intrinsic main size used for shrink-to-fit containers.
     // We intentionally keep this conservative (max of child fixed sizes), since Lolite
     // does not yet implement min/max-content constraints or full intrinsic sizing.
 
-    let children = node.borrow().children.clone();
+    let children = node.borrow().box_children();
     if children.is_empty() {
         return 0.0;
     }
@@ -682,16 +938,88 @@ fn intrinsic_main_from_children(
     children
         .iter()
         .map(|c| {
-            let s = resolve_style(c, ctx, fallback);
-            if is_row_main {
-                s.width.as_ref().map(|l| l.to_px()).unwrap_or(100.0)
+            if c.borrow().is_text_node() {
+                let size = text_run_size(std::slice::from_ref(c), &fallback.inherited(), ctx);
+                return if is_row_main { size.width } else { size.height };
+            }
+            let s = resolve_style(c, ctx, fallback, units);
+            if c.borrow().generated_box().is_some() {
+                // Generated boxes are sized by their text
+                return base_sizes_for_item(c, &s, parent_direction, ctx, units).0;
+            }
+            let specified = if is_row_main {
+                s.width.map(|l| units.inline_or_zero(l))
             } else {
-                s.height.as_ref().map(|l| l.to_px()).unwrap_or(30.0)
+                s.height.map(|l| units.block(l).unwrap_or(0.0))
+            };
+            // Children contribute their border box
+            match (specified, s.box_sizing.unwrap_or_default()) {
+                (Some(size), BoxSizing::ContentBox) => {
+                    size + axis_padding_sum_px(&s, parent_direction, Axis::Main, units)
+                        + axis_border_sum_px(&s, parent_direction, Axis::Main, units)
+                }
+                (Some(size), BoxSizing::BorderBox) => size,
+                // The default size is a border-box size
+                (None, _) if is_row_main => ctx.text_node_defaults.size.width,
+                (None, _) => ctx.text_node_defaults.size.height,
             }
         })
         .fold(0.0, f64::max)
 }
 
+/// The row and column gaps of a container, with `units` resolving percentages against
+/// its content box. `normal` is no gap.
+fn gaps_px(style: &Style, units: &ResolutionContext) -> (f64, f64) {
+    let row = style.row_gap.and_then(|gap| units.block(gap));
+    let column = style.column_gap.and_then(|gap| units.inline(gap));
+    (row.unwrap_or(0.0), column.unwrap_or(0.0))
+}
+
+/// Convert a border-box size along one axis to the other through an aspect `ratio` of
+/// the other axis over this one. The ratio applies to the box `box-sizing` selects, so
+/// `from_edges` and `to_edges`, the padding and border along each axis, are taken off
+/// and added back for content boxes.
+pub(crate) fn transfer_size(
+    size: f64,
+    ratio: f64,
+    from_edges: f64,
+    to_edges: f64,
+    box_sizing: BoxSizing,
+) -> f64 {
+    match box_sizing {
+        BoxSizing::ContentBox => (size - from_edges).max(0.0) * ratio + to_edges,
+        BoxSizing::BorderBox => (size * ratio).max(to_edges),
+    }
+}
+
+/// The border-box cross size an item's aspect ratio gives it from its main size, if it
+/// has a ratio and an auto cross size.
+fn cross_from_aspect_ratio(
+    item: &FlexItem,
+    direction: &FlexDirection,
+    units: &ResolutionContext,
+) -> Option<f64> {
+    let ratio = item.style.aspect_ratio.and_then(AspectRatio::value)?;
+    if !item.text_run.is_empty() || !cross_size_is_auto(&item.style, direction) {
+        return None;
+    }
+    let ratio = match direction {
+        FlexDirection::Row | FlexDirection::RowReverse => 1.0 / ratio,
+        FlexDirection::Column | FlexDirection::ColumnReverse => ratio,
+    };
+    let edges = |axis| {
+        axis_padding_sum_px(&item.style, direction, axis, units)
+            + axis_border_sum_px(&item.style, direction, axis, units)
+    };
+    Some(transfer_size(
+        item.final_main,
+        ratio,
+        edges(Axis::Main),
+        edges(Axis::Cross),
+        item.style.box_sizing.unwrap_or_default(),
+    ))
+}
+
 fn cross_size_is_auto(style: &Style, direction: &FlexDirection) -> bool {
     match direction {
         FlexDirection::Row | FlexDirection::RowReverse => {
@@ -715,23 +1043,12 @@ fn margins_for_direction(
     }
 }
 
-fn is_auto(length: &Length) -> bool {
+pub(crate) fn is_auto(length: &Length) -> bool {
     matches!(length, Length::Auto)
 }
 
-fn length_px_or_zero(length: &Length) -> f64 {
-    match length {
-        Length::Auto => 0.0,
-        _ => length.to_px(),
-    }
-}
-
-fn resolve_margin_px(length: &Length, auto_share: Option<f64>) -> f64 {
-    match (length, auto_share) {
-        (Length::Auto, Some(share)) => share,
-        (Length::Auto, None) => 0.0,
-        (other, _) => other.to_px(),
-    }
+fn resolve_margin_px(length: Length, auto_share: Option<f64>, units: &ResolutionContext) -> f64 {
+    units.inline(length).unwrap_or(auto_share.unwrap_or(0.0))
 }
 
 fn align_content_offsets(
@@ -750,8 +1067,8 @@ fn align_content_offsets(
     let leftover = (available_cross - total_cross).max(0.0);
 
     match align_content {
-        AlignContent::FlexStart => (0.0, base_gap),
-        AlignContent::FlexEnd => (leftover, base_gap),
+        AlignContent::FlexStart | AlignContent::Start => (0.0, base_gap),
+        AlignContent::FlexEnd | AlignContent::End => (leftover, base_gap),
         AlignContent::Center => (leftover / 2.0, base_gap),
         AlignContent::SpaceBetween => {
             if line_count <= 1 {
@@ -782,6 +1099,7 @@ fn align_content_offsets(
 fn justify_offsets(
     justify: &JustifyContent,
     direction: &FlexDirection,
+    mirrored: bool,
     leftover: f64,
     base_gap: f64,
     item_count: usize,
@@ -790,20 +1108,28 @@ fn justify_offsets(
         return (0.0, base_gap);
     }
 
-    // Reverse directions flip the meaning of flex-start/flex-end.
+    // Items are placed from the start of the writing mode, so reverse directions flip
+    // the meaning of flex-start/flex-end but not of start/end.
     let is_reverse = matches!(
         direction,
         FlexDirection::RowReverse | FlexDirection::ColumnReverse
     );
+    let is_row = matches!(direction, FlexDirection::Row | FlexDirection::RowReverse);
     let justify = match (is_reverse, justify) {
         (true, JustifyContent::FlexStart) => JustifyContent::FlexEnd,
         (true, JustifyContent::FlexEnd) => JustifyContent::FlexStart,
+        // left and right are physical on rows, and act as start on columns
+        (_, JustifyContent::Left) if mirrored => JustifyContent::End,
+        (_, JustifyContent::Right) if is_row && !mirrored => JustifyContent::End,
         _ => justify.clone(),
     };
 
     match justify {
-        JustifyContent::FlexStart => (0.0, base_gap),
-        JustifyContent::FlexEnd => (leftover, base_gap),
+        JustifyContent::FlexStart
+        | JustifyContent::Start
+        | JustifyContent::Left
+        | JustifyContent::Right => (0.0, base_gap),
+        JustifyContent::FlexEnd | JustifyContent::End => (leftover, base_gap),
         JustifyContent::Center => (leftover / 2.0, base_gap),
         JustifyContent::SpaceBetween => {
             if item_count <= 1 {
@@ -824,33 +1150,97 @@ fn justify_offsets(
     }
 }
 
-fn resolve_style(node: &Rc<RefCell<Node>>, ctx: &LayoutContext, fallback: &Style) -> Style {
-    let node_borrow = node.borrow();
+pub(crate) fn is_text_run(run: &[Rc<RefCell<Node>>]) -> bool {
+    run.first().is_some_and(|node| node.borrow().is_text_node())
+}
 
-    // Start with existing style as base.
-    let mut style = node_borrow.layout.style.as_ref().clone();
+/// The text of each node of a run, with white space collapsed as on a single line.
+pub(crate) fn collapsed_run_text(run: &[Rc<RefCell<Node>>]) -> Vec<String> {
+    let nodes: Vec<_> = run.iter().map(|node| node.borrow()).collect();
+    collapse_white_space_run(
+        nodes
+            .iter()
+            .map(|node| node.text.as_deref().unwrap_or_default()),
+    )
+}
 
-    // Apply CSS rules for class selector.
-    if let Some(class_attr) = node_borrow.attributes.get("class") {
-        for class_name in class_attr.split_whitespace() {
-            let selector = crate::style::Selector::Class(class_name.to_string());
-            if let Some(rule) = ctx
-                .style_sheet
-                .rules
-                .iter()
-                .find(|rule| rule.selector == selector)
-            {
-                for declaration in &rule.declarations {
-                    style.merge(declaration);
-                }
-            }
-        }
+/// Size of a run of text nodes set side by side on one line.
+pub(crate) fn text_run_size(run: &[Rc<RefCell<Node>>], style: &Style, ctx: &LayoutContext) -> Size {
+    let font = FontSpec::from_style(style);
+    collapsed_run_text(run)
+        .iter()
+        .map(|text| ctx.text_measurer.measure_unwrapped(text, &font))
+        .fold(Size::default(), |run, size| Size {
+            width: run.width + size.width,
+            height: run.height.max(size.height),
+        })
+}
+
+/// Lay out the text nodes of an anonymous flex item side by side within its box, from
+/// the right in right-to-left text, the last one taking the remaining width.
+pub(crate) fn place_text_run(
+    run: &[Rc<RefCell<Node>>],
+    bounds: Rect,
+    style: &Style,
+    ctx: &LayoutContext,
+) {
+    let font = FontSpec::from_style(style);
+    let rtl = style.direction == Some(TextDirection::Rtl);
+    let style = std::sync::Arc::new(style.clone());
+    // Distance from the start edge of the box
+    let mut offset = 0.0;
+    for (index, (node, text)) in run.iter().zip(collapsed_run_text(run)).enumerate() {
+        let width = if index + 1 == run.len() {
+            (bounds.width - offset).max(0.0)
+        } else {
+            ctx.text_measurer.measure_unwrapped(&text, &font).width
+        };
+        let x = if rtl {
+            bounds.x + bounds.width - offset - width
+        } else {
+            bounds.x + offset
+        };
+        let mut node = node.borrow_mut();
+        node.layout.bounds = Rect::new(x, bounds.y, width, bounds.height);
+        node.layout.style = style.clone();
+        node.layout.text = Some(text);
+        offset += width;
     }
+}
+
+/// Context for a node's own lengths, from its parent's and its resolved style.
+pub(crate) fn own_units(parent: &ResolutionContext, style: &Style) -> ResolutionContext {
+    // The font size is already resolved to pixels.
+    parent.with_font_size(parent.font_size(style.font_size))
+}
 
-    // Best-effort inheritance for anonymous items.
-    if node_borrow.attributes.is_empty() && node_borrow.children.is_empty() {
-        style.display = fallback.display.clone();
+/// `fallback` is the parent's resolved style, and `units` the context for its lengths.
+pub(crate) fn resolve_style(
+    node: &Rc<RefCell<Node>>,
+    ctx: &LayoutContext,
+    fallback: &Style,
+    units: &ResolutionContext,
+) -> Style {
+    let node_borrow = node.borrow();
+    if node_borrow.is_text_node() {
+        return fallback.inherited();
+    }
+    // Generated boxes are styled when they are generated
+    if node_borrow.generated_box().is_some() {
+        return node_borrow.layout.style.as_ref().clone();
     }
 
+    // Start with existing style as base.
+    let mut style = node_borrow.layout.style.as_ref().clone();
+
+    // Apply CSS rules matching the node.
+    let declarations = ctx.cascaded_declarations(&node_borrow, None);
+    style.cascade(declarations, &fallback.custom_properties);
+    let own_units = style.resolve_units(units);
+    style.inherit_from(fallback);
+    ctx.text_node_defaults.apply(&mut style, &ctx.locale);
+    drop(node_borrow);
+    ctx.generate_boxes(node, &style, &own_units);
+
     style
 }
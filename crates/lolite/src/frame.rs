@@ -0,0 +1,174 @@
+use crate::backend::GpuCacheUsage;
+use std::time::Duration;
+#[cfg(feature = "windowing")]
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+/// Callback invoked with the frame timestamp (time since the first frame) and the time
+/// elapsed since the previous frame.
+pub(crate) type FrameCallback = Box<dyn FnMut(Duration, Duration) + Send>;
+
+/// Handle to a callback registered with [`crate::Engine::on_frame`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FrameCallbackId(u64);
+
+/// Per-frame callbacks, run by the windowing loop before each frame is drawn.
+#[derive(Default)]
+pub(crate) struct FrameScheduler {
+    callbacks: Vec<(FrameCallbackId, FrameCallback)>,
+    next_id: u64,
+    #[cfg(feature = "windowing")]
+    first_frame: Option<Instant>,
+    #[cfg(feature = "windowing")]
+    last_frame: Option<Instant>,
+    /// Set while callbacks run, so they can register or remove callbacks themselves.
+    running: bool,
+    added_while_running: Vec<(FrameCallbackId, FrameCallback)>,
+    removed_while_running: Vec<FrameCallbackId>,
+}
+
+impl FrameScheduler {
+    pub fn add(&mut self, callback: FrameCallback) -> FrameCallbackId {
+        // Don't report the idle period before this callback as a frame delta.
+        #[cfg(feature = "windowing")]
+        if self.is_empty() && !self.running {
+            self.last_frame = None;
+        }
+        self.next_id += 1;
+        let id = FrameCallbackId(self.next_id);
+        if self.running {
+            self.added_while_running.push((id, callback));
+        } else {
+            self.callbacks.push((id, callback));
+        }
+        id
+    }
+
+    pub fn remove(&mut self, id: FrameCallbackId) {
+        if self.running {
+            self.removed_while_running.push(id);
+        }
+        self.callbacks.retain(|(callback_id, _)| *callback_id != id);
+        self.added_while_running
+            .retain(|(callback_id, _)| *callback_id != id);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.callbacks.is_empty() && self.added_while_running.is_empty()
+    }
+
+    /// Take the callbacks due for the frame at `now`, with its timestamp and delta.
+    ///
+    /// The callbacks must be handed back with [`FrameScheduler::finish_frame`].
+    #[cfg(feature = "windowing")]
+    pub fn begin_frame(
+        &mut self,
+        now: Instant,
+    ) -> (Vec<(FrameCallbackId, FrameCallback)>, Duration, Duration) {
+        let first_frame = *self.first_frame.get_or_insert(now);
+        let delta = self
+            .last_frame
+            .map(|last| now.saturating_duration_since(last))
+            .unwrap_or_default();
+        self.last_frame = Some(now);
+        self.running = true;
+        (
+            std::mem::take(&mut self.callbacks),
+            now.saturating_duration_since(first_frame),
+            delta,
+        )
+    }
+
+    /// Put back the callbacks taken by [`FrameScheduler::begin_frame`], applying any
+    /// registrations and removals made while they ran.
+    #[cfg(feature = "windowing")]
+    pub fn finish_frame(&mut self, mut callbacks: Vec<(FrameCallbackId, FrameCallback)>) {
+        let removed = std::mem::take(&mut self.removed_while_running);
+        callbacks.retain(|(id, _)| !removed.contains(id));
+        callbacks.append(&mut self.added_while_running);
+        self.callbacks = callbacks;
+        self.running = false;
+    }
+}
+
+/// Timing and memory use of one rendered frame, reported to [`crate::Engine::on_frame_stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    /// Number of frames rendered in the window before this one.
+    pub frame: u64,
+    /// Time the command thread spent on layout since the previous frame.
+    pub layout: Duration,
+    /// Time spent running frame callbacks and painting the document.
+    pub paint: Duration,
+    /// Time spent flushing to the GPU and presenting, including any wait for vsync.
+    pub present: Duration,
+    /// Refresh intervals missed between the redraw request and the end of present.
+    pub missed_vsyncs: u32,
+    /// GPU memory cached for later frames once this one was presented.
+    pub gpu_cache: GpuCacheUsage,
+}
+
+#[cfg(feature = "windowing")]
+pub(crate) type FrameStatsCallback = Box<dyn FnMut(&FrameStats) + Send>;
+
+/// Collects the timings of the frame being rendered in a window.
+#[cfg(feature = "windowing")]
+#[derive(Default)]
+pub(crate) struct FrameStatsRecorder {
+    // Shared so the callback runs without holding the recorder lock.
+    callback: Option<Arc<Mutex<FrameStatsCallback>>>,
+    frames: u64,
+    paint: Duration,
+}
+
+#[cfg(feature = "windowing")]
+impl FrameStatsRecorder {
+    pub fn set_callback(&mut self, callback: Option<FrameStatsCallback>) {
+        self.callback = callback.map(|callback| Arc::new(Mutex::new(callback)));
+    }
+
+    pub fn record_paint(&mut self, paint: Duration) {
+        self.paint = paint;
+    }
+
+    /// Complete the stats of a presented frame, given the layout time since the previous
+    /// frame and the total time the backend spent rendering it.
+    ///
+    /// Returns the callback to report them to, if any.
+    pub fn finish_frame(
+        &mut self,
+        layout: Duration,
+        render: Duration,
+        missed_vsyncs: u32,
+        gpu_cache: GpuCacheUsage,
+    ) -> (FrameStats, Option<Arc<Mutex<FrameStatsCallback>>>) {
+        let paint = std::mem::take(&mut self.paint);
+        let stats = FrameStats {
+            frame: self.frames,
+            layout,
+            paint,
+            present: render.saturating_sub(paint),
+            missed_vsyncs,
+            gpu_cache,
+        };
+        self.frames += 1;
+        (stats, self.callback.clone())
+    }
+}
+
+/// Number of refresh intervals missed by a frame presented `latency` after it was requested.
+///
+/// A frame presented within one interval missed none.
+#[cfg(feature = "windowing")]
+pub(crate) fn missed_vsyncs(latency: Duration, refresh_interval: Duration) -> u32 {
+    if refresh_interval.is_zero() {
+        return 0;
+    }
+    let intervals = latency.as_secs_f64() / refresh_interval.as_secs_f64();
+    (intervals.ceil() as u32).saturating_sub(1)
+}
+
+#[cfg(all(test, feature = "windowing"))]
+mod frame_tests;
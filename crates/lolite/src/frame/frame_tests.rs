@@ -0,0 +1,126 @@
+use super::*;
+use std::sync::{Arc, Mutex};
+
+fn run_frame(scheduler: &mut FrameScheduler, now: Instant) {
+    let (mut callbacks, timestamp, delta) = scheduler.begin_frame(now);
+    for (_, callback) in callbacks.iter_mut() {
+        callback(timestamp, delta);
+    }
+    scheduler.finish_frame(callbacks);
+}
+
+#[test]
+fn test_frame_timestamps_and_deltas() {
+    let mut scheduler = FrameScheduler::default();
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    {
+        let frames = frames.clone();
+        scheduler.add(Box::new(move |timestamp, delta| {
+            frames.lock().unwrap().push((timestamp, delta));
+        }));
+    }
+
+    let start = Instant::now();
+    run_frame(&mut scheduler, start);
+    run_frame(&mut scheduler, start + Duration::from_millis(16));
+    run_frame(&mut scheduler, start + Duration::from_millis(40));
+
+    assert_eq!(
+        *frames.lock().unwrap(),
+        vec![
+            (Duration::ZERO, Duration::ZERO),
+            (Duration::from_millis(16), Duration::from_millis(16)),
+            (Duration::from_millis(40), Duration::from_millis(24)),
+        ]
+    );
+}
+
+#[test]
+fn test_remove_frame_callback() {
+    let mut scheduler = FrameScheduler::default();
+    let id = scheduler.add(Box::new(|_, _| {}));
+    assert!(!scheduler.is_empty());
+
+    scheduler.remove(id);
+    assert!(scheduler.is_empty());
+}
+
+#[test]
+fn test_changes_while_running_apply_after_frame() {
+    let mut scheduler = FrameScheduler::default();
+    let first = scheduler.add(Box::new(|_, _| {}));
+    let second = scheduler.add(Box::new(|_, _| {}));
+
+    let (callbacks, _, _) = scheduler.begin_frame(Instant::now());
+    // As if called from inside a callback
+    scheduler.remove(first);
+    let third = scheduler.add(Box::new(|_, _| {}));
+    scheduler.finish_frame(callbacks);
+
+    let ids: Vec<_> = scheduler.callbacks.iter().map(|(id, _)| *id).collect();
+    assert_eq!(ids, vec![second, third]);
+}
+
+#[test]
+fn test_frame_stats_split_render_time() {
+    let mut recorder = FrameStatsRecorder::default();
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    {
+        let reported = reported.clone();
+        recorder.set_callback(Some(Box::new(move |stats: &FrameStats| {
+            reported.lock().unwrap().push(*stats);
+        })));
+    }
+
+    recorder.record_paint(Duration::from_millis(4));
+    let gpu_cache = GpuCacheUsage {
+        resources: 3,
+        bytes: 4096,
+        limit: 1 << 20,
+    };
+    let (stats, callback) = recorder.finish_frame(
+        Duration::from_millis(2),
+        Duration::from_millis(10),
+        0,
+        gpu_cache,
+    );
+    (callback.unwrap().lock().unwrap())(&stats);
+
+    let (second, _) = recorder.finish_frame(
+        Duration::ZERO,
+        Duration::from_millis(3),
+        1,
+        GpuCacheUsage::default(),
+    );
+
+    assert_eq!(
+        *reported.lock().unwrap(),
+        vec![FrameStats {
+            frame: 0,
+            layout: Duration::from_millis(2),
+            paint: Duration::from_millis(4),
+            present: Duration::from_millis(6),
+            missed_vsyncs: 0,
+            gpu_cache,
+        }]
+    );
+    assert_eq!(second.frame, 1);
+    assert_eq!(second.paint, Duration::ZERO);
+    assert_eq!(second.present, Duration::from_millis(3));
+
+    recorder.set_callback(None);
+    assert!(recorder
+        .finish_frame(Duration::ZERO, Duration::ZERO, 0, GpuCacheUsage::default())
+        .1
+        .is_none());
+}
+
+#[test]
+fn test_missed_vsyncs() {
+    let interval = Duration::from_micros(16_667);
+    assert_eq!(missed_vsyncs(Duration::ZERO, interval), 0);
+    assert_eq!(missed_vsyncs(Duration::from_millis(16), interval), 0);
+    assert_eq!(missed_vsyncs(Duration::from_millis(20), interval), 1);
+    assert_eq!(missed_vsyncs(Duration::from_millis(50), interval), 2);
+    assert_eq!(missed_vsyncs(Duration::from_millis(50), Duration::ZERO), 0);
+}
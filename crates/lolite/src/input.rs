@@ -0,0 +1,59 @@
+#[cfg(feature = "windowing")]
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+/// A key pressed or released in a focused window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyEvent {
+    /// Key value as in the DOM, e.g. `a`, `A`, `Enter` or `ArrowLeft`.
+    pub key: String,
+    /// Text the key press produces, if any.
+    pub text: Option<String>,
+    pub pressed: bool,
+    /// Whether the key is held down and this press is an automatic repeat.
+    pub repeat: bool,
+    pub modifiers: Modifiers,
+}
+
+/// Modifier keys held during a key event.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    /// The Windows key, or Cmd on macOS.
+    pub meta: bool,
+}
+
+#[cfg(feature = "windowing")]
+impl KeyEvent {
+    pub(crate) fn new(event: &winit::event::KeyEvent, modifiers: ModifiersState) -> Self {
+        Self {
+            key: key_name(&event.logical_key),
+            text: event.text.as_ref().map(|text| text.to_string()),
+            pressed: event.state.is_pressed(),
+            repeat: event.repeat,
+            modifiers: Modifiers {
+                shift: modifiers.shift_key(),
+                control: modifiers.control_key(),
+                alt: modifiers.alt_key(),
+                meta: modifiers.super_key(),
+            },
+        }
+    }
+}
+
+/// The DOM key value of a logical key.
+#[cfg(feature = "windowing")]
+fn key_name(key: &Key) -> String {
+    match key {
+        Key::Character(text) => text.to_string(),
+        Key::Named(NamedKey::Space) => " ".to_string(),
+        // Named keys are spelled like their DOM values
+        Key::Named(named) => format!("{:?}", named),
+        Key::Dead(_) => "Dead".to_string(),
+        Key::Unidentified(_) => "Unidentified".to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "windowing"))]
+mod input_tests;
@@ -0,0 +1,11 @@
+use super::*;
+
+#[test]
+fn test_key_names_follow_the_dom() {
+    assert_eq!(key_name(&Key::Character("a".into())), "a");
+    assert_eq!(key_name(&Key::Character("A".into())), "A");
+    assert_eq!(key_name(&Key::Named(NamedKey::Enter)), "Enter");
+    assert_eq!(key_name(&Key::Named(NamedKey::ArrowLeft)), "ArrowLeft");
+    assert_eq!(key_name(&Key::Named(NamedKey::Space)), " ");
+    assert_eq!(key_name(&Key::Dead(Some('`'))), "Dead");
+}
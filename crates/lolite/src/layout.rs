@@ -1,24 +1,41 @@
 use crate::{
+    block_layout::BlockLayoutEngine,
+    embed::{EmbeddedDocument, MAX_EMBED_DEPTH},
     flex_layout::FlexLayoutEngine,
-    style::{BoxSizing, Length, Selector, Style, StyleSheet},
-    text::{default_text_measurer, FontSpec, TextMeasurer},
+    locale::Locale,
+    style::{
+        BoxSizing, ColorScheme, ContentVisibility, CustomProperties, Directional, Display, Length,
+        MotionPreference, PseudoClass, PseudoElement, Rule, ScrollSnapAlign, ScrollSnapAxis,
+        ScrollSnapStrictness, Selector, Style, StyleOrigin, StyleSheet, StyleSource,
+    },
+    text::{collapse_white_space, default_text_measurer, FontSpec, TextMeasurer, TextNodeDefaults},
+    units::ResolutionContext,
     Id,
 };
-use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::Arc};
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
 
 #[derive(Default)]
 pub struct Layout {
     pub bounds: Rect,
     pub style: Arc<Style>,
+    /// The node's text as laid out and painted, after white space processing; `None`
+    /// when its text moved into a generated box.
+    pub text: Option<String>,
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct Size {
     pub width: f64,
     pub height: f64,
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct Rect {
     pub x: f64,
     pub y: f64,
@@ -43,22 +60,166 @@ impl Rect {
     }
 }
 
+/// Part of a node's box a hit test landed in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HitArea {
+    Border,
+    Padding,
+    Content,
+}
+
+/// A node under a point, see [`RenderNode::hit_test`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    pub id: Id,
+    /// Position of the point relative to the top-left corner of the node's content box,
+    /// negative in the padding and border on the top and left sides.
+    pub x: f64,
+    pub y: f64,
+    pub area: HitArea,
+    /// For nodes of a document embedded with [`crate::Engine::set_embedded_document`],
+    /// the node embedding it, which follows that document's hits in the path; `None` for
+    /// nodes of the hit-tested document.
+    pub host: Option<Id>,
+}
+
+/// Content-box sizes already fixed by a node's style, passed to its measure function.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct MeasureConstraints {
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+}
+
+/// Limits on a node's border-box size from `min-width`, `max-width`, `min-height` and
+/// `max-height`. Unset and `auto` minimums are zero, and unset and `none` maximums
+/// infinite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SizeLimits {
+    pub min_width: f64,
+    pub max_width: f64,
+    pub min_height: f64,
+    pub max_height: f64,
+}
+
+impl SizeLimits {
+    pub(crate) const NONE: Self = Self {
+        min_width: 0.0,
+        max_width: f64::INFINITY,
+        min_height: 0.0,
+        max_height: f64::INFINITY,
+    };
+
+    /// Limits of a node with a resolved style; percentages refer to the containing block
+    /// of `units`.
+    pub(crate) fn of(style: &Style, units: &ResolutionContext) -> Self {
+        let (extra_width, extra_height) = match style.box_sizing.unwrap_or_default() {
+            BoxSizing::ContentBox => (
+                units.horizontal(&style.padding.resolved())
+                    + units.horizontal(&style.border_width.resolved()),
+                units.vertical(&style.padding.resolved())
+                    + units.vertical(&style.border_width.resolved()),
+            ),
+            BoxSizing::BorderBox => (0.0, 0.0),
+        };
+        // `auto` resolves to `None`
+        let width = |length: Option<Length>| {
+            length
+                .and_then(|length| units.inline(length))
+                .map(|px| px + extra_width)
+        };
+        let height = |length: Option<Length>| {
+            length
+                .and_then(|length| units.block(length))
+                .map(|px| px + extra_height)
+        };
+        Self {
+            min_width: width(style.min_width).unwrap_or(0.0),
+            max_width: width(style.max_width).unwrap_or(f64::INFINITY),
+            min_height: height(style.min_height).unwrap_or(0.0),
+            max_height: height(style.max_height).unwrap_or(f64::INFINITY),
+        }
+    }
+
+    /// A width within the limits; the minimum wins over the maximum.
+    pub(crate) fn clamp_width(&self, width: f64) -> f64 {
+        width.min(self.max_width).max(self.min_width)
+    }
+
+    /// A height within the limits; the minimum wins over the maximum.
+    pub(crate) fn clamp_height(&self, height: f64) -> f64 {
+        height.min(self.max_height).max(self.min_height)
+    }
+}
+
+/// Reports the intrinsic content size of a leaf node with custom content.
+pub(crate) type MeasureFn = Box<dyn Fn(MeasureConstraints) -> Size + Send>;
+
+/// Scroll position of a node's content, in px.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct ScrollOffset {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// What a document node is, fixed when it's created.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NodeKind {
+    /// A node that selectors match and that gets a box of its own; it may also hold
+    /// text, laid out as its content.
+    #[default]
+    Element,
+    /// A run of text inside its parent, styled by the parent's inherited properties.
+    /// Consecutive text children of a flex container share an anonymous flex item.
+    Text,
+    /// A box layout generates for an element, see [`Node::generated`]. It has the id of
+    /// that element and isn't part of the document.
+    Generated(GeneratedBox),
+}
+
+/// Boxes layout generates for an element, in the order they are laid out.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum GeneratedBox {
+    /// The `::before` pseudo-element, before the first child.
+    Before,
+    /// The element's own text, when generated boxes make the element a container
+    /// rather than a leaf laying out its text.
+    Text,
+    /// The `::after` pseudo-element, after the last child.
+    After,
+}
+
 #[derive(Default)]
 #[allow(unused)]
 pub struct Node {
     pub id: Id,
+    pub kind: NodeKind,
+    /// Element type matched by type selectors, e.g. `button`.
+    pub tag: Option<String>,
     pub text: Option<String>,
     pub attributes: HashMap<String, String>,
     pub children: Vec<Rc<RefCell<Node>>>,
     pub parent: Option<Id>, // Add parent member
+    /// Human-readable name shown in dumps and error messages.
+    pub debug_name: Option<String>,
     // modified when layouting
     pub layout: Layout,
+    /// Boxes generated for the node, rebuilt whenever its style is resolved.
+    pub generated: Vec<Rc<RefCell<Node>>>,
+    /// How far the children are scrolled; survives relayout.
+    pub scroll_offset: ScrollOffset,
+    /// Document shown in the content box, see [`crate::Engine::set_embedded_document`].
+    pub embedded: Option<EmbeddedDocument>,
 }
 
 impl Node {
     pub fn new(id: Id, text: Option<String>) -> Self {
         Self {
             id,
+            // Until laid out, as if the text were alone on its line
+            layout: Layout {
+                text: text.as_deref().map(collapse_white_space),
+                ..Default::default()
+            },
             text,
             ..Default::default()
         }
@@ -69,7 +230,175 @@ impl Node {
     }
 
     pub fn is_text_node(&self) -> bool {
-        self.text.is_some()
+        self.kind == NodeKind::Text
+    }
+
+    /// Which generated box this is, or `None` for a document node.
+    pub fn generated_box(&self) -> Option<GeneratedBox> {
+        match self.kind {
+            NodeKind::Generated(generated) => Some(generated),
+            _ => None,
+        }
+    }
+
+    /// Children as laid out: the document children, with the boxes generated for the
+    /// node around them.
+    pub fn box_children(&self) -> Vec<Rc<RefCell<Node>>> {
+        if self.generated.is_empty() {
+            return self.children.clone();
+        }
+        let (after, before): (Vec<_>, Vec<_>) = self
+            .generated
+            .iter()
+            .cloned()
+            .partition(|node| node.borrow().generated_box() == Some(GeneratedBox::After));
+        before
+            .into_iter()
+            .chain(self.children.iter().cloned())
+            .chain(after)
+            .collect()
+    }
+
+    pub fn has_box_children(&self) -> bool {
+        !self.children.is_empty() || !self.generated.is_empty()
+    }
+
+    /// Whether the node matches a selector.
+    ///
+    /// Combinators need the node's ancestors and never match here; see [`Document::matches`].
+    pub fn matches(&self, selector: &Selector) -> bool {
+        if self.kind != NodeKind::Element {
+            return false;
+        }
+        match selector {
+            Selector::Class(class) => self
+                .attributes
+                .get("class")
+                .is_some_and(|classes| classes.split_whitespace().any(|c| c == class)),
+            Selector::Id(id) => self.attributes.get("id") == Some(id),
+            // Tag names are case-insensitive, as in HTML
+            Selector::Tag(tag) => self
+                .tag
+                .as_ref()
+                .is_some_and(|own| own.eq_ignore_ascii_case(tag)),
+            Selector::Universal => true,
+            Selector::Attribute(name, matcher) => self
+                .attributes
+                .get(name)
+                .is_some_and(|value| matcher.matches(value)),
+            Selector::Compound(selectors) => selectors.iter().all(|s| self.matches(s)),
+            // Matched by the node the box is generated for, see
+            // `LayoutContext::cascaded_declarations`
+            Selector::PseudoElement(_) => true,
+            // Depend on the document, see `Document::matches`
+            Selector::PseudoClass(_)
+            | Selector::NthChild(_)
+            | Selector::NthLastChild(_)
+            | Selector::Descendant(..)
+            | Selector::Child(..) => false,
+        }
+    }
+
+    /// Largest scroll offset at which the children still cover the node's box; zero
+    /// unless the node is a scroll container.
+    pub fn max_scroll_offset(&self) -> ScrollOffset {
+        if !self.layout.style.is_scroll_container() {
+            return ScrollOffset::default();
+        }
+        let bounds = self.layout.bounds;
+        let mut right = bounds.x + bounds.width;
+        let mut bottom = bounds.y + bounds.height;
+        for child in &self.children {
+            let child_bounds = child.borrow().layout.bounds;
+            right = right.max(child_bounds.x + child_bounds.width);
+            bottom = bottom.max(child_bounds.y + child_bounds.height);
+        }
+        ScrollOffset {
+            x: right - (bounds.x + bounds.width),
+            y: bottom - (bounds.y + bounds.height),
+        }
+    }
+
+    /// Whether the children overflow the node's box in either direction.
+    pub fn is_scrollable(&self) -> bool {
+        let max = self.max_scroll_offset();
+        max.x > 0.0 || max.y > 0.0
+    }
+
+    /// Whether user input can scroll the node along an axis its children overflow.
+    pub fn is_user_scrollable(&self) -> bool {
+        let max = self.max_scroll_offset();
+        let (x, y) = self.layout.style.overflow();
+        (x.is_user_scrollable() && max.x > 0.0) || (y.is_user_scrollable() && max.y > 0.0)
+    }
+
+    /// Clamp `offset` to the range the node can actually scroll.
+    pub fn clamp_scroll_offset(&self, offset: ScrollOffset) -> ScrollOffset {
+        let max = self.max_scroll_offset();
+        ScrollOffset {
+            x: offset.x.clamp(0.0, max.x),
+            y: offset.y.clamp(0.0, max.y),
+        }
+    }
+
+    /// The offset the node's `scroll-snap-type` settles on from `current`, if any.
+    ///
+    /// Each snapping axis moves to the nearest snap position of a child with a
+    /// `scroll-snap-align`. With `proximity` strictness, positions further away than
+    /// half the node's size along that axis are ignored.
+    pub fn snap_scroll_offset(&self, current: ScrollOffset) -> Option<ScrollOffset> {
+        let snap_type = self.layout.style.scroll_snap_type?;
+        let (snap_x, snap_y) = match snap_type.axis {
+            ScrollSnapAxis::None => return None,
+            ScrollSnapAxis::X => (true, false),
+            ScrollSnapAxis::Y => (false, true),
+            ScrollSnapAxis::Both => (true, true),
+        };
+        let bounds = self.layout.bounds;
+        let mandatory = snap_type.strictness == ScrollSnapStrictness::Mandatory;
+
+        // Offset aligning a child span with the same span of this node.
+        let align =
+            |align: ScrollSnapAlign, start: f64, size: f64, origin: f64, extent: f64| match align {
+                ScrollSnapAlign::None | ScrollSnapAlign::Start => start - origin,
+                ScrollSnapAlign::Center => start + size / 2.0 - (origin + extent / 2.0),
+                ScrollSnapAlign::End => start + size - (origin + extent),
+            };
+        let closer = |best: Option<f64>, value: f64, position: f64| match best {
+            Some(best) => (value - position).abs() < (best - position).abs(),
+            None => true,
+        };
+
+        let mut best_x: Option<f64> = None;
+        let mut best_y: Option<f64> = None;
+        for child in &self.children {
+            let child = child.borrow();
+            let child_align = match child.layout.style.scroll_snap_align {
+                Some(ScrollSnapAlign::None) | None => continue,
+                Some(child_align) => child_align,
+            };
+            let cb = child.layout.bounds;
+            let candidate = self.clamp_scroll_offset(ScrollOffset {
+                x: align(child_align, cb.x, cb.width, bounds.x, bounds.width),
+                y: align(child_align, cb.y, cb.height, bounds.y, bounds.height),
+            });
+            if snap_x && closer(best_x, candidate.x, current.x) {
+                best_x = Some(candidate.x);
+            }
+            if snap_y && closer(best_y, candidate.y, current.y) {
+                best_y = Some(candidate.y);
+            }
+        }
+
+        let settle = |best: Option<f64>, position: f64, extent: f64| match best {
+            Some(best) if mandatory || (best - position).abs() <= extent / 2.0 => best,
+            _ => position,
+        };
+        let target = ScrollOffset {
+            x: settle(best_x, current.x, bounds.width),
+            y: settle(best_y, current.y, bounds.height),
+        };
+        (target != current).then_some(target)
     }
 }
 
@@ -77,6 +406,14 @@ pub struct Document {
     #[allow(unused)]
     root: Rc<RefCell<Node>>,
     nodes: HashMap<Id, Rc<RefCell<Node>>>,
+    /// Next candidate for an engine-allocated id; counts down so it stays clear of
+    /// ids picked by callers.
+    next_generated_id: u64,
+    /// Innermost node under the pointer.
+    hovered: Option<Id>,
+    /// Innermost node under the pointer when the left button was pressed, while held.
+    active: Option<Id>,
+    focused: Option<Id>,
 }
 
 impl Document {
@@ -84,7 +421,47 @@ impl Document {
         let root = Rc::new(RefCell::new(Node::new(Id(0), None)));
         let mut nodes = HashMap::new();
         nodes.insert(Id(0), root.clone());
-        Self { root, nodes }
+        Self {
+            root,
+            nodes,
+            next_generated_id: u64::MAX,
+            hovered: None,
+            active: None,
+            focused: None,
+        }
+    }
+
+    /// Allocate an id not used by any node.
+    fn generate_id(&mut self) -> Id {
+        loop {
+            let id = Id(self.next_generated_id);
+            self.next_generated_id -= 1;
+            if !self.nodes.contains_key(&id) {
+                return id;
+            }
+        }
+    }
+
+    /// Deep-copy a node and its descendants under freshly allocated ids, returning the
+    /// id of the copy, which has no parent.
+    pub fn clone_subtree(&mut self, id: Id) -> Option<Id> {
+        let source = self.get_node(id)?;
+        let source = source.borrow();
+        let copy_id = self.generate_id();
+        let mut copy = Node::new(copy_id, source.text.clone());
+        copy.kind = source.kind;
+        copy.tag = source.tag.clone();
+        copy.attributes = source.attributes.clone();
+        copy.debug_name = source.debug_name.clone();
+        self.nodes.insert(copy_id, Rc::new(RefCell::new(copy)));
+
+        for child in &source.children {
+            let child_id = child.borrow().id;
+            if let Some(child_copy) = self.clone_subtree(child_id) {
+                let _ = self.set_parent(copy_id, child_copy);
+            }
+        }
+        Some(copy_id)
     }
 
     pub fn create_node(&mut self, id: Id, text: Option<String>) -> Id {
@@ -93,6 +470,13 @@ impl Document {
         id
     }
 
+    pub fn create_text_node(&mut self, id: Id, text: String) -> Id {
+        let mut node = Node::new(id, Some(text));
+        node.kind = NodeKind::Text;
+        self.nodes.insert(id, Rc::new(RefCell::new(node)));
+        id
+    }
+
     pub fn set_parent(&mut self, parent_id: Id, child_id: Id) -> Result<(), &str> {
         // Check if the parent and child are the same
         if parent_id == child_id {
@@ -111,6 +495,19 @@ impl Document {
         }
 
         let parent = self.nodes.get(&parent_id).ok_or("Parent node not found")?;
+        if parent.borrow().is_text_node() {
+            return Err("Text nodes cannot have children");
+        }
+
+        // Reject moving a node under its own descendant, which would detach the
+        // subtree from the root in a cycle
+        let mut ancestor = parent.borrow().parent;
+        while let Some(id) = ancestor {
+            if id == child_id {
+                return Err("Child is an ancestor of the parent");
+            }
+            ancestor = self.nodes.get(&id).and_then(|node| node.borrow().parent);
+        }
 
         // Remove the child from its previous parent
         if let Some(old_parent_id) = child.borrow().parent {
@@ -130,7 +527,12 @@ impl Document {
 
     pub fn set_attribute(&mut self, node_id: Id, key: String, value: String) {
         if let Some(node) = self.nodes.get(&node_id) {
-            node.borrow_mut().attributes.insert(key, value);
+            let previous = node.borrow_mut().attributes.insert(key, value.clone());
+            // Rules matching the old value, on the node or through combinators on its
+            // descendants, must stop applying
+            if previous.is_some_and(|previous| previous != value) {
+                reset_subtree_styles(node);
+            }
         }
     }
 
@@ -142,6 +544,15 @@ impl Document {
             .flatten()
     }
 
+    /// Describe a node for error messages, e.g. `#3 "sidebar"`.
+    pub fn describe(&self, id: Id) -> String {
+        let name = self
+            .nodes
+            .get(&id)
+            .and_then(|node| node.borrow().debug_name.clone());
+        describe_node(id, name.as_deref())
+    }
+
     #[allow(unused)]
     pub fn root_id(&self) -> Id {
         Id(0)
@@ -155,13 +566,274 @@ impl Document {
     pub fn get_node(&self, id: Id) -> Option<Rc<RefCell<Node>>> {
         self.nodes.get(&id).cloned()
     }
+
+    /// All nodes, attached to the tree or not, in no particular order.
+    pub fn nodes(&self) -> impl Iterator<Item = &Rc<RefCell<Node>>> {
+        self.nodes.values()
+    }
+
+    /// Number of nodes, attached to the tree or not, including the root.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Number of ancestors of a node; 0 for the root and detached nodes.
+    pub fn depth(&self, id: Id) -> usize {
+        let mut depth = 0;
+        let mut ancestor = self.nodes.get(&id).and_then(|node| node.borrow().parent);
+        while let Some(id) = ancestor {
+            depth += 1;
+            ancestor = self.nodes.get(&id).and_then(|node| node.borrow().parent);
+        }
+        depth
+    }
+
+    /// Number of nodes in the subtree of a node and how many levels it spans below it.
+    pub fn subtree_extent(&self, id: Id) -> (usize, usize) {
+        let Some(node) = self.nodes.get(&id) else {
+            return (0, 0);
+        };
+        let (mut size, mut height) = (0, 0);
+        let mut visit = vec![(node.clone(), 0)];
+        while let Some((node, level)) = visit.pop() {
+            size += 1;
+            height = height.max(level);
+            visit.extend(
+                node.borrow()
+                    .children
+                    .iter()
+                    .map(|child| (child.clone(), level + 1)),
+            );
+        }
+        (size, height)
+    }
+
+    /// Nodes in the tree matching any of `selectors`, in document order.
+    pub fn query_selector_all(&self, selectors: &[Selector]) -> Vec<Id> {
+        let mut matches = Vec::new();
+        let mut visit = vec![self.root.clone()];
+        while let Some(node) = visit.pop() {
+            let node = node.borrow();
+            if selectors.iter().any(|selector| {
+                selector.pseudo_element().is_none() && self.matches(&node, selector)
+            }) {
+                matches.push(node.id);
+            }
+            visit.extend(node.children.iter().rev().cloned());
+        }
+        matches
+    }
+
+    /// Whether a node in this document matches a selector, walking up its ancestors
+    /// for combinators.
+    pub fn matches(&self, node: &Node, selector: &Selector) -> bool {
+        if node.kind != NodeKind::Element {
+            return false;
+        }
+        match selector {
+            Selector::Descendant(ancestor, subject) => {
+                if !self.matches(node, subject) {
+                    return false;
+                }
+                let mut parent = self.parent_of(node);
+                while let Some(current) = parent {
+                    let current = current.borrow();
+                    if self.matches(&current, ancestor) {
+                        return true;
+                    }
+                    parent = self.parent_of(&current);
+                }
+                false
+            }
+            Selector::Child(parent, subject) => {
+                self.matches(node, subject)
+                    && self
+                        .parent_of(node)
+                        .is_some_and(|current| self.matches(&current.borrow(), parent))
+            }
+            Selector::Compound(selectors) => selectors.iter().all(|s| self.matches(node, s)),
+            Selector::PseudoClass(PseudoClass::Hover) => {
+                self.inclusive_ancestors(self.hovered).contains(&node.id)
+            }
+            Selector::PseudoClass(PseudoClass::Active) => {
+                self.inclusive_ancestors(self.active).contains(&node.id)
+            }
+            Selector::PseudoClass(PseudoClass::Focus) => self.focused == Some(node.id),
+            Selector::NthChild(nth) => {
+                let (position, _) = self.sibling_position(node);
+                nth.matches(position)
+            }
+            Selector::NthLastChild(nth) => {
+                let (position, count) = self.sibling_position(node);
+                nth.matches(count - position + 1)
+            }
+            _ => node.matches(selector),
+        }
+    }
+
+    /// Root of the innermost scope the node is inside of, not counting a scope rooted at
+    /// the node itself.
+    pub(crate) fn scope_of(&self, node: &Node, scope_roots: &HashSet<Id>) -> Option<Id> {
+        if scope_roots.is_empty() {
+            return None;
+        }
+        let mut parent = node.parent;
+        while let Some(id) = parent {
+            if scope_roots.contains(&id) {
+                return Some(id);
+            }
+            parent = self.get_node(id).and_then(|node| node.borrow().parent);
+        }
+        None
+    }
+
+    fn parent_of(&self, node: &Node) -> Option<Rc<RefCell<Node>>> {
+        node.parent.and_then(|id| self.get_node(id))
+    }
+
+    /// 1-based position of an element among its parent's element children, and how many
+    /// there are. Text nodes don't count, and a node without a parent is its only sibling.
+    fn sibling_position(&self, node: &Node) -> (i32, i32) {
+        let Some(parent) = self.parent_of(node) else {
+            return (1, 1);
+        };
+        let parent = parent.borrow();
+        let mut position = 1;
+        let mut count = 0;
+        for sibling in &parent.children {
+            if sibling.borrow().id == node.id {
+                position = count + 1;
+            }
+            if !sibling.borrow().is_text_node() {
+                count += 1;
+            }
+        }
+        (position, count)
+    }
+
+    /// A node followed by its ancestors up to the root.
+    fn inclusive_ancestors(&self, id: Option<Id>) -> Vec<Id> {
+        let mut ancestors = Vec::new();
+        let mut current = id.filter(|id| self.nodes.contains_key(id));
+        while let Some(id) = current {
+            ancestors.push(id);
+            current = self.get_node(id).and_then(|node| node.borrow().parent);
+        }
+        ancestors
+    }
+
+    /// Carry over from `previous` the state that isn't saved with the nodes, for the
+    /// nodes that exist in both: scroll offsets, embedded documents, and which nodes
+    /// are hovered, active and focused.
+    pub(crate) fn keep_view_state(&mut self, previous: &Document) {
+        for (id, node) in &self.nodes {
+            if let Some(old) = previous.nodes.get(id) {
+                let old = old.borrow();
+                let mut node = node.borrow_mut();
+                node.scroll_offset = old.scroll_offset;
+                node.embedded = old.embedded.clone();
+            }
+        }
+        let exists = |id: &Id| self.nodes.contains_key(id);
+        self.hovered = previous.hovered.filter(exists);
+        self.active = previous.active.filter(exists);
+        self.focused = previous.focused.filter(exists);
+    }
+
+    /// Track the innermost node under the pointer, or `None` when the pointer is outside
+    /// the document, and whether the left button is held.
+    ///
+    /// Returns whether the `:hover` or `:active` state of any node changed.
+    #[cfg(any(test, feature = "windowing"))]
+    pub(crate) fn set_pointer(&mut self, hovered: Option<Id>, pressed: bool) -> bool {
+        let active = match (pressed, self.active) {
+            (false, _) => None,
+            // Stays with the node the press started on
+            (true, Some(active)) => Some(active),
+            (true, None) => hovered,
+        };
+        let hover_changed = self.restyle_ancestors(self.hovered, hovered);
+        let active_changed = self.restyle_ancestors(self.active, active);
+        self.hovered = hovered;
+        self.active = active;
+        hover_changed || active_changed
+    }
+
+    /// Track the node with keyboard focus. Returns whether it changed.
+    pub(crate) fn set_focused(&mut self, focused: Option<Id>) -> bool {
+        if focused == self.focused {
+            return false;
+        }
+        for node in [self.focused, focused].iter().flatten() {
+            if let Some(node) = self.nodes.get(node) {
+                reset_subtree_styles(node);
+            }
+        }
+        self.focused = focused;
+        true
+    }
+
+    /// Forget the styles of nodes that stop or start being ancestors of the node in a
+    /// state, and of everything below them. Returns whether any did.
+    fn restyle_ancestors(&self, before: Option<Id>, after: Option<Id>) -> bool {
+        let before = self.inclusive_ancestors(before);
+        let after = self.inclusive_ancestors(after);
+        // The outermost node leaving or entering the state covers the others
+        let left = before.iter().rfind(|id| !after.contains(id));
+        let entered = after.iter().rfind(|id| !before.contains(id));
+        for id in left.iter().chain(entered.iter()) {
+            reset_subtree_styles(&self.nodes[*id]);
+        }
+        left.is_some() || entered.is_some()
+    }
+
+    /// Clear the styles of a node's subtree so the next layout cascades them afresh.
+    pub(crate) fn reset_subtree_styles(&self, id: Id) {
+        if let Some(node) = self.nodes.get(&id) {
+            reset_subtree_styles(node);
+        }
+    }
+
+    /// Forget every resolved style, so the next layout cascades from scratch.
+    ///
+    /// Styles are resolved on top of the previous result; this is needed whenever rules
+    /// are removed from the cascade, or their values would linger.
+    pub fn reset_styles(&mut self) {
+        for node in self.nodes.values() {
+            node.borrow_mut().layout.style = Arc::default();
+        }
+    }
+}
+
+/// Forget the resolved styles of a node and everything below it.
+fn reset_subtree_styles(node: &Rc<RefCell<Node>>) {
+    let mut visit = vec![node.clone()];
+    while let Some(node) = visit.pop() {
+        let mut node = node.borrow_mut();
+        node.layout.style = Arc::default();
+        visit.extend(node.children.iter().cloned());
+    }
 }
 
 pub struct LayoutContext {
     pub document: Document,
     pub style_sheet: StyleSheet,
+    /// Per-node styles applied on top of the stylesheet cascade (e.g. running animations).
+    pub style_overrides: HashMap<Id, Style>,
+    /// Size used for the root node when it doesn't specify its own, and for `vw`/`vh` units.
+    pub viewport: Size,
+    /// Evaluated by `prefers-color-scheme` media queries.
+    pub color_scheme: ColorScheme,
+    pub motion_preference: MotionPreference,
+    /// Intrinsic sizing of leaf nodes rendering custom content.
+    pub measure_functions: HashMap<Id, MeasureFn>,
     flex_layout_engine: FlexLayoutEngine,
+    block_layout_engine: BlockLayoutEngine,
     pub text_measurer: Arc<dyn TextMeasurer>,
+    /// Sizes and fonts assumed for nodes the text heuristic applies to.
+    pub text_node_defaults: TextNodeDefaults,
+    /// Gives the root its default direction, and fonts their fallbacks.
+    pub locale: Locale,
 }
 
 impl LayoutContext {
@@ -169,8 +841,19 @@ impl LayoutContext {
         Self {
             document: Document::new(),
             style_sheet: StyleSheet::new(),
+            style_overrides: HashMap::new(),
+            viewport: Size {
+                width: 800.0,
+                height: 500.0,
+            },
+            color_scheme: ColorScheme::default(),
+            motion_preference: MotionPreference::default(),
+            measure_functions: HashMap::new(),
             flex_layout_engine: FlexLayoutEngine::new(),
+            block_layout_engine: BlockLayoutEngine::new(),
             text_measurer: default_text_measurer(),
+            text_node_defaults: TextNodeDefaults::default(),
+            locale: Locale::default(),
         }
     }
 
@@ -180,33 +863,224 @@ impl LayoutContext {
         self.text_measurer.end_layout_pass_and_sweep();
     }
 
+    /// Context for resolving the root's lengths, whose containing block is the viewport.
+    pub(crate) fn root_units(&self) -> ResolutionContext {
+        let font_size = self.text_node_defaults.font_size;
+        ResolutionContext::new(self.viewport)
+            .with_font_size(font_size)
+            .with_root_font_size(font_size)
+            .with_initial_font_size(font_size)
+    }
+
+    /// Declarations that apply to the node, or to the box generated for one of its
+    /// pseudo-elements, lowest priority first.
+    ///
+    /// Normal declarations of matching rules come first, ordered by layer, then by the
+    /// specificity of the most specific matching selector of each rule, then in
+    /// stylesheet order. Animated values follow, then `!important` declarations, for
+    /// which earlier layers win over later ones and over rules outside any layer.
+    pub(crate) fn cascaded_declarations<'a>(
+        &'a self,
+        node: &Node,
+        pseudo_element: Option<PseudoElement>,
+    ) -> Vec<(StyleOrigin, &'a Style)> {
+        let (normal, important) = self.matched_rules(node, pseudo_element);
+        let mut declarations: Vec<(StyleOrigin, &Style)> = normal
+            .iter()
+            .flat_map(|rule| rule.declarations.iter().map(|style| (rule.origin, style)))
+            .collect();
+        if pseudo_element.is_none() {
+            // Animated values don't come from a stylesheet; count them as the author's
+            let overrides = self.style_overrides.get(&node.id);
+            declarations.extend(overrides.map(|style| (StyleOrigin::Author, style)));
+        }
+        declarations.extend(important.iter().flat_map(|rule| {
+            rule.important_declarations
+                .iter()
+                .map(|style| (rule.origin, style))
+        }));
+        declarations
+    }
+
+    /// Where the stylesheet declarations that apply to the node were declared, in the
+    /// order of [`Self::cascaded_declarations`], and whether later declarations override
+    /// them. Rules that weren't parsed from CSS are left out.
+    pub(crate) fn style_sources(&self, node: &Node) -> Vec<StyleSource> {
+        let (normal, important) = self.matched_rules(node, None);
+        let mut cascade = declarations_with_sources(normal, false);
+        cascade.extend(
+            self.style_overrides
+                .get(&node.id)
+                .map(|style| (None, style)),
+        );
+        cascade.extend(declarations_with_sources(important, true));
+
+        // A declaration is overridden if the ones after it set every property it sets:
+        // applying it under them then changes nothing. Declarations whose `var()`
+        // references can't be resolved are ignored, so count as overridden too.
+        let custom_properties = &node.layout.style.custom_properties;
+        let resolved: Vec<Option<Style>> = cascade
+            .iter()
+            .map(|(_, declaration)| match &declaration.var_declaration {
+                Some(var) => var.resolve(custom_properties),
+                None => Some((*declaration).clone()),
+            })
+            .collect();
+        let mut later = Style::default();
+        let mut result = Vec::new();
+        for ((source, _), declaration) in cascade.into_iter().zip(resolved).rev() {
+            if let Some(mut source) = source {
+                source.overridden = match &declaration {
+                    Some(declaration) => {
+                        let mut applied = declaration.clone();
+                        applied.merge(&later);
+                        // Declarations that set nothing are never overridden
+                        *declaration != Style::default() && applied == later
+                    }
+                    None => true,
+                };
+                result.push(source);
+            }
+            if let Some(mut declaration) = declaration {
+                declaration.merge(&later);
+                later = declaration;
+            }
+        }
+        result.reverse();
+        result
+    }
+
+    /// Rules matching the node or one of its pseudo-elements, in the cascade order of
+    /// their normal declarations and then of their `!important` ones.
+    fn matched_rules(
+        &self,
+        node: &Node,
+        pseudo_element: Option<PseudoElement>,
+    ) -> (Vec<&Rule>, Vec<&Rule>) {
+        let rules = &self.style_sheet.rules;
+        let scope_roots: HashSet<Id> = rules.iter().filter_map(|rule| rule.scope).collect();
+        let scope = self.document.scope_of(node, &scope_roots);
+        let mut matched: Vec<_> = rules
+            .iter()
+            .filter(|rule| {
+                (rule.scope == scope || rule.scope == Some(node.id)) && self.media_matches(rule)
+            })
+            .filter_map(|rule| {
+                let specificity = rule
+                    .selectors
+                    .iter()
+                    .filter(|selector| {
+                        selector.pseudo_element() == pseudo_element
+                            && self.document.matches(node, selector)
+                    })
+                    .map(Selector::specificity)
+                    .max()?;
+                let layer = self.style_sheet.layer_rank(rule);
+                Some((rule.origin, layer, specificity, rule))
+            })
+            .collect();
+        // Stable, so equal rules keep their stylesheet order
+        matched.sort_by_key(|(origin, layer, specificity, _)| (*origin, *layer, *specificity));
+        let normal = matched.iter().map(|(_, _, _, rule)| *rule).collect();
+        matched.sort_by_key(|(origin, layer, specificity, _)| {
+            (Reverse(*origin), Reverse(*layer), *specificity)
+        });
+        let important = matched.iter().map(|(_, _, _, rule)| *rule).collect();
+        (normal, important)
+    }
+
+    /// Rebuild the boxes generated for an element from its resolved style, and `units`
+    /// for its own lengths: a `::before` and an `::after` box unless their `content` is
+    /// `none`, and then one for the element's own text. Otherwise the element lays out
+    /// its text itself.
+    pub(crate) fn generate_boxes(
+        &self,
+        node: &Rc<RefCell<Node>>,
+        style: &Style,
+        units: &ResolutionContext,
+    ) {
+        let generated = {
+            let element = node.borrow();
+            let before =
+                self.generate_pseudo_element(&element, style, units, PseudoElement::Before);
+            let after = self.generate_pseudo_element(&element, style, units, PseudoElement::After);
+            let text = element
+                .text
+                .clone()
+                .filter(|_| before.is_some() || after.is_some())
+                .map(|text| generated_box(&element, GeneratedBox::Text, text, style.inherited()));
+            [before, text, after]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+        };
+        let mut element = node.borrow_mut();
+        element.layout.text = element
+            .text
+            .as_deref()
+            .filter(|_| generated.is_empty())
+            .map(collapse_white_space);
+        element.generated = generated;
+    }
+
+    fn generate_pseudo_element(
+        &self,
+        element: &Node,
+        style: &Style,
+        units: &ResolutionContext,
+        pseudo_element: PseudoElement,
+    ) -> Option<Rc<RefCell<Node>>> {
+        let declarations = self.cascaded_declarations(element, Some(pseudo_element));
+        if declarations.is_empty() {
+            return None;
+        }
+        let mut box_style = style.inherited();
+        box_style.cascade(declarations, &style.custom_properties);
+        box_style.resolve_units(units);
+        self.text_node_defaults.apply(&mut box_style, &self.locale);
+        let text = box_style.content.as_ref()?.text(&element.attributes)?;
+        let kind = match pseudo_element {
+            PseudoElement::Before => GeneratedBox::Before,
+            PseudoElement::After => GeneratedBox::After,
+        };
+        Some(generated_box(element, kind, text, box_style))
+    }
+
+    fn media_matches(&self, rule: &Rule) -> bool {
+        rule.media.as_ref().is_none_or(|media| {
+            media.matches(self.viewport, self.color_scheme, self.motion_preference)
+        })
+    }
+
+    /// Which `@media` rules currently apply, in stylesheet order; when this changes,
+    /// styles must be recomputed from scratch.
+    pub(crate) fn media_state(&self) -> Vec<bool> {
+        self.style_sheet
+            .rules
+            .iter()
+            .filter(|rule| rule.media.is_some())
+            .map(|rule| self.media_matches(rule))
+            .collect()
+    }
+
     pub fn layout_node(&self, node: Rc<RefCell<Node>>, x: f64, y: f64) {
         // Get style for this node - merge existing style with CSS rules
-        let style = {
+        let (style, units) = {
             let node_borrow = node.borrow();
             // Start with existing style as base (this preserves manually set properties like flex_wrap)
             let mut style = node_borrow.layout.style.as_ref().clone();
 
             // Apply CSS rules on top of existing style.
-            // The `class` attribute is treated as a whitespace-separated list of classes.
-            if let Some(class_attr) = node_borrow.attributes.get("class") {
-                for class_name in class_attr.split_whitespace() {
-                    let selector = Selector::Class(class_name.to_string());
-
-                    if let Some(rule) = self
-                        .style_sheet
-                        .rules
-                        .iter()
-                        .find(|rule| rule.selector == selector)
-                    {
-                        for declaration in &rule.declarations {
-                            style.merge(declaration);
-                        }
-                    }
-                }
-            }
-            style
+            let declarations = self.cascaded_declarations(&node_borrow, None);
+            // The root has no parent to inherit custom properties from.
+            style.cascade(declarations, &CustomProperties::default());
+            self.text_node_defaults.apply(&mut style, &self.locale);
+            style.direction.get_or_insert(self.locale.direction());
+            // `rem` lengths, including the root's own, refer to the initial font size.
+            let units = style.resolve_units(&self.root_units());
+            (style, units.with_root_font_size(units.font_size))
         };
+        self.generate_boxes(&node, &style, &units);
 
         // Set position (margins will be applied by flex layout engine for flex items)
         {
@@ -215,22 +1089,26 @@ impl LayoutContext {
             node_borrow.layout.bounds.y = y;
         }
 
-        let is_leaf = node.borrow().children.is_empty();
-        let is_text_node = node.borrow().is_text_node();
+        // The root always fills the viewport, even when empty
+        let is_leaf =
+            !node.borrow().has_box_children() && node.borrow().id != self.document.root_id();
 
         // Lolite stores `layout.bounds` as the element's border-box.
         // `box-sizing` determines whether CSS `width/height` refer to the content-box or border-box.
         let resolved_box_sizing = style.box_sizing.unwrap_or(BoxSizing::ContentBox);
         let padding = style.padding.resolved();
-        let padding_w = padding.left.to_px() + padding.right.to_px();
-        let padding_h = padding.top.to_px() + padding.bottom.to_px();
+        let padding_w = units.horizontal(&padding);
+        let padding_h = units.vertical(&padding);
         let border = style.border_width.resolved();
-        let border_w = border.left.to_px() + border.right.to_px();
-        let border_h = border.top.to_px() + border.bottom.to_px();
+        let border_w = units.horizontal(&border);
+        let border_h = units.vertical(&border);
+        let specified_width = style.width.and_then(|width| units.inline(width));
+        let specified_height = style.height.and_then(|height| units.block(height));
+        let limits = SizeLimits::of(&style, &units);
 
         let resolve_border_box =
-            |specified: Option<Length>, fallback: f64, padding_sum: f64, border_sum: f64| -> f64 {
-                let Some(Length::Px(px)) = specified else {
+            |specified: Option<f64>, fallback: f64, padding_sum: f64, border_sum: f64| -> f64 {
+                let Some(px) = specified else {
                     return fallback;
                 };
 
@@ -242,56 +1120,68 @@ impl LayoutContext {
 
         if is_leaf {
             // Leaf node - use specified dimensions or defaults.
-            // If this is a text node, prefer intrinsic text sizing.
-            let mut fallback_width_border_box = 100.0;
-            let mut fallback_height_border_box = 30.0;
-
-            if is_text_node {
-                if let Some(text) = node.borrow().text.as_deref() {
-                    let font = FontSpec::from_style(&style);
-
-                    // Width: if not specified, use unwrapped intrinsic width.
-                    if matches!(style.width, Some(Length::Auto)) {
-                        let text_size = self.text_measurer.measure_unwrapped(text, &font);
-                        fallback_width_border_box = text_size.width + padding_w + border_w;
-                    }
+            // If it holds text, prefer intrinsic text sizing.
+            let mut fallback_width_border_box = self.text_node_defaults.size.width;
+            let mut fallback_height_border_box = self.text_node_defaults.size.height;
 
-                    // Height: if not specified, try to wrap to a specified width (if any), else unwrapped.
-                    if matches!(style.height, Some(Length::Auto)) {
-                        let text_size = match style.width {
-                            Some(Length::Px(specified_width_px)) if specified_width_px > 0.0 => {
-                                // Wrap within the content box width.
-                                let content_max_width = match resolved_box_sizing {
-                                    BoxSizing::ContentBox => specified_width_px,
-                                    BoxSizing::BorderBox => {
-                                        (specified_width_px - padding_w - border_w).max(0.0)
-                                    }
-                                };
-                                self.text_measurer
-                                    .measure_wrapped(text, &font, content_max_width)
-                            }
-                            _ => self.text_measurer.measure_unwrapped(text, &font),
-                        };
-
-                        fallback_height_border_box = text_size.height + padding_h + border_h;
-                    }
+            if let Some(text) = node.borrow().layout.text.as_deref() {
+                let font = FontSpec::from_style(&style);
+
+                // Width: if not specified, use unwrapped intrinsic width.
+                if matches!(style.width, Some(Length::Auto)) {
+                    let text_size = self.text_measurer.measure_unwrapped(text, &font);
+                    fallback_width_border_box = text_size.width + padding_w + border_w;
+                }
+
+                // Height: if not specified, try to wrap to a specified width (if any), else unwrapped.
+                if matches!(style.height, Some(Length::Auto)) {
+                    // Wrap within the content box width.
+                    let content_max_width =
+                        specified_width.filter(|px| *px > 0.0).map(
+                            |px| match resolved_box_sizing {
+                                BoxSizing::ContentBox => px,
+                                BoxSizing::BorderBox => (px - padding_w - border_w).max(0.0),
+                            },
+                        );
+                    let text_size = self.text_node_defaults.measure(
+                        self.text_measurer.as_ref(),
+                        text,
+                        &font,
+                        content_max_width,
+                    );
+
+                    fallback_height_border_box = text_size.height + padding_h + border_h;
                 }
             }
 
             let mut node_borrow = node.borrow_mut();
-            node_borrow.layout.bounds.width =
-                resolve_border_box(style.width, fallback_width_border_box, padding_w, border_w);
-            node_borrow.layout.bounds.height = resolve_border_box(
-                style.height,
+            node_borrow.layout.bounds.width = limits.clamp_width(resolve_border_box(
+                specified_width,
+                fallback_width_border_box,
+                padding_w,
+                border_w,
+            ));
+            node_borrow.layout.bounds.height = limits.clamp_height(resolve_border_box(
+                specified_height,
                 fallback_height_border_box,
                 padding_h,
                 border_h,
-            );
+            ));
             node_borrow.layout.style = Arc::new(style);
         } else {
-            // Container node - handle flexbox layout
-            let container_width = resolve_border_box(style.width, 800.0, padding_w, border_w);
-            let container_height = resolve_border_box(style.height, 500.0, padding_h, border_h);
+            // Container node - lay out its children as its display type says
+            let container_width = limits.clamp_width(resolve_border_box(
+                specified_width,
+                self.viewport.width,
+                padding_w,
+                border_w,
+            ));
+            let container_height = limits.clamp_height(resolve_border_box(
+                specified_height,
+                self.viewport.height,
+                padding_h,
+                border_h,
+            ));
 
             // Set container dimensions
             {
@@ -301,9 +1191,25 @@ impl LayoutContext {
                 node_borrow.layout.style = Arc::new(style.clone());
             }
 
-            // Layout children using the dedicated flex layout engine
-            self.flex_layout_engine
-                .layout_flex_children(node.clone(), &style, self);
+            self.layout_children(node.clone(), &style, &units);
+        }
+    }
+
+    /// Lay out the children of a sized container with the engine for its display type.
+    /// `units` is the context the container's own lengths are resolved in.
+    pub(crate) fn layout_children(
+        &self,
+        node: Rc<RefCell<Node>>,
+        style: &Style,
+        units: &ResolutionContext,
+    ) {
+        match style.display.unwrap_or_default() {
+            Display::Block => self
+                .block_layout_engine
+                .layout_block_children(node, style, self, units),
+            Display::Flex => self
+                .flex_layout_engine
+                .layout_flex_children(node, style, self, units),
         }
     }
 }
@@ -312,9 +1218,18 @@ impl LayoutContext {
 #[derive(Clone)]
 pub struct RenderNode {
     pub id: Id,
+    /// Set on boxes generated for the node `id`, which are neither hit nor animated on
+    /// their own.
+    pub generated: Option<GeneratedBox>,
     pub bounds: Rect,
     pub style: Arc<Style>,
     pub text: Option<String>,
+    pub debug_name: Option<String>,
+    pub scroll_offset: ScrollOffset,
+    /// Set by the `draggable="true"` attribute; see [`crate::DragEvent`].
+    pub draggable: bool,
+    /// Document painted and hit-tested in the content box, above the children.
+    pub(crate) embedded: Option<EmbeddedDocument>,
     pub children: Vec<RenderNode>,
 }
 
@@ -324,37 +1239,357 @@ impl RenderNode {
     /// Returns a `Vec<Id>` where the first element is the topmost element at the position,
     /// and subsequent elements are its parents up to the root.
     /// This enables event bubbling by providing the full parent chain.
+    ///
+    /// Nodes of embedded documents are left out, so the ids all belong to this document.
     pub fn find_element_at_position(&self, x: f64, y: f64) -> Vec<Id> {
-        self.find_path_at_position(x, y).unwrap_or_default()
+        self.hit_test(x, y)
+            .iter()
+            .filter(|hit| hit.host.is_none())
+            .map(|hit| hit.id)
+            .collect()
     }
 
-    fn find_path_at_position(&self, x: f64, y: f64) -> Option<Vec<Id>> {
+    /// Like [`Self::find_element_at_position`], with where each node was hit.
+    pub fn hit_test(&self, x: f64, y: f64) -> Vec<Hit> {
+        self.hit_path(x, y, 0).unwrap_or_default()
+    }
+
+    /// The padding box: the border box inset by the border.
+    #[cfg(feature = "windowing")]
+    pub(crate) fn padding_box(&self) -> Rect {
+        let units = self.paint_units();
+        let border = self.style.border_width.resolved();
+        let (top, right, bottom, left) = (
+            units.inline_or_zero(border.top),
+            units.inline_or_zero(border.right),
+            units.inline_or_zero(border.bottom),
+            units.inline_or_zero(border.left),
+        );
+        Rect::new(
+            self.bounds.x + left,
+            self.bounds.y + top,
+            (self.bounds.width - left - right).max(0.0),
+            (self.bounds.height - top - bottom).max(0.0),
+        )
+    }
+
+    /// The content box: the border box inset by the border and the padding.
+    pub(crate) fn content_box(&self) -> Rect {
+        let units = self.paint_units();
+        let border = self.style.border_width.resolved();
+        let padding = self.style.padding.resolved();
+        let inset = |border: Length, padding: Length| {
+            units.inline_or_zero(border) + units.inline_or_zero(padding)
+        };
+        let (top, right) = (
+            inset(border.top, padding.top),
+            inset(border.right, padding.right),
+        );
+        let (bottom, left) = (
+            inset(border.bottom, padding.bottom),
+            inset(border.left, padding.left),
+        );
+        Rect::new(
+            self.bounds.x + left,
+            self.bounds.y + top,
+            (self.bounds.width - left - right).max(0.0),
+            (self.bounds.height - top - bottom).max(0.0),
+        )
+    }
+
+    /// Context for resolving the lengths a node is painted and hit-tested with.
+    ///
+    /// Layout has already resolved viewport units. Percentages refer to the node's own
+    /// border box, which is right for radii and an approximation for padding.
+    pub(crate) fn paint_units(&self) -> ResolutionContext {
+        let units = ResolutionContext::new(Size::default()).with_containing_block(Size {
+            width: self.bounds.width,
+            height: self.bounds.height,
+        });
+        units.with_font_size(units.font_size(self.style.font_size))
+    }
+
+    /// Number of nodes in this subtree, including this one.
+    pub fn node_count(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(RenderNode::node_count)
+            .sum::<usize>()
+    }
+
+    /// Find the node with the given id in this subtree.
+    pub fn find(&self, id: Id) -> Option<&RenderNode> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+
+    /// The node a press on the hit-test path `path`, topmost first, would drag: the
+    /// innermost draggable one in this subtree.
+    #[cfg(feature = "windowing")]
+    pub(crate) fn draggable_in(&self, path: &[Id]) -> Option<Id> {
+        path.iter()
+            .copied()
+            .find(|&id| self.find(id).is_some_and(|node| node.draggable))
+    }
+
+    /// Find the node with the given id in this subtree, with how far the scroll
+    /// containers above it shift it up and left from where it was laid out.
+    pub fn find_scrolled(&self, id: Id) -> Option<(&RenderNode, ScrollOffset)> {
+        if self.id == id {
+            return Some((self, ScrollOffset::default()));
+        }
+        let (node, shift) = self
+            .children
+            .iter()
+            .find_map(|child| child.find_scrolled(id))?;
+        let shift = ScrollOffset {
+            x: shift.x + self.scroll_offset.x,
+            y: shift.y + self.scroll_offset.y,
+        };
+        Some((node, shift))
+    }
+
+    /// Write the animated paint properties (colors) of `overrides` into the styles of
+    /// this subtree, so color animations can be repainted each frame without a layout.
+    pub fn apply_paint_overrides(&mut self, overrides: &HashMap<Id, Style>) {
+        let mut remaining = overrides.len();
+        self.apply_paint_overrides_counted(overrides, &mut remaining);
+    }
+
+    fn apply_paint_overrides_counted(
+        &mut self,
+        overrides: &HashMap<Id, Style>,
+        remaining: &mut usize,
+    ) {
+        if let Some(animated) = overrides.get(&self.id).filter(|_| self.generated.is_none()) {
+            // Only this node's style is copied; the rest of the tree keeps sharing it.
+            let style = Arc::make_mut(&mut self.style);
+            if animated.color.is_some() {
+                style.color = animated.color;
+            }
+            if animated.background_color.is_some() {
+                style.background_color = animated.background_color;
+            }
+            *remaining -= 1;
+        }
+        for child in &mut self.children {
+            if *remaining == 0 {
+                return;
+            }
+            child.apply_paint_overrides_counted(overrides, remaining);
+        }
+    }
+
+    /// Render the subtree as indented text, one node per line, for debugging.
+    pub fn dump(&self) -> String {
+        let mut out = String::new();
+        self.dump_into(&mut out, 0);
+        out
+    }
+
+    fn dump_into(&self, out: &mut String, depth: usize) {
+        let b = self.bounds;
+        out.push_str(&format!(
+            "{}{} [{}, {}, {}x{}]",
+            "  ".repeat(depth),
+            describe_node(self.id, self.debug_name.as_deref()),
+            b.x,
+            b.y,
+            b.width,
+            b.height
+        ));
+        out.push_str(match self.generated {
+            Some(GeneratedBox::Before) => " ::before",
+            Some(GeneratedBox::Text) => " (text)",
+            Some(GeneratedBox::After) => " ::after",
+            None => "",
+        });
+        if let Some(text) = &self.text {
+            out.push_str(&format!(" {:?}", text));
+        }
+        out.push('\n');
+        for child in &self.children {
+            child.dump_into(out, depth + 1);
+        }
+    }
+
+    /// `depth` counts the embedded documents the node is nested in.
+    fn hit_path(&self, x: f64, y: f64, depth: usize) -> Option<Vec<Hit>> {
         if !self.bounds.contains_point(x, y) {
             return None;
         }
+        let hit = self.hit_at(x, y);
 
+        if self.style.content_visibility == Some(ContentVisibility::Hidden) {
+            return Some(vec![hit]);
+        }
+
+        // An embedded document covers the children, positioned at the content box
+        if let Some(document) = self.embedded.as_ref().filter(|_| depth < MAX_EMBED_DEPTH) {
+            // Clipped to the content box
+            let content_box = self.content_box();
+            let embedded_path = document
+                .latest_snapshot()
+                .filter(|_| content_box.contains_point(x, y))
+                .and_then(|root| root.hit_path(x - content_box.x, y - content_box.y, depth + 1));
+            if let Some(mut path) = embedded_path {
+                for embedded_hit in &mut path {
+                    embedded_hit.host.get_or_insert(self.id);
+                }
+                path.push(hit);
+                return Some(path);
+            }
+        }
+
+        // Scroll containers clip their children to the padding box
+        if self.style.is_scroll_container() && hit.area == HitArea::Border {
+            return Some(vec![hit]);
+        }
+
+        // Children are painted shifted by the scroll offset.
+        let (cx, cy) = (x + self.scroll_offset.x, y + self.scroll_offset.y);
+        // Generated boxes are hit as part of the element they belong to
         for child in self.children.iter().rev() {
-            if let Some(mut path) = child.find_path_at_position(x, y) {
-                path.push(self.id);
+            if child.generated.is_some() {
+                continue;
+            }
+            if let Some(mut path) = child.hit_path(cx, cy, depth) {
+                path.push(hit);
                 return Some(path);
             }
         }
 
-        Some(vec![self.id])
+        Some(vec![hit])
+    }
+
+    /// Where a point inside the border box hits this node.
+    fn hit_at(&self, x: f64, y: f64) -> Hit {
+        let units = self.paint_units();
+        let border = self.style.border_width.resolved();
+        let padding = self.style.padding.resolved();
+        let b = &self.bounds;
+        // Offsets of the padding box and content box from the border box, per side
+        let padding_box = Directional {
+            top: units.inline_or_zero(border.top),
+            right: units.inline_or_zero(border.right),
+            bottom: units.inline_or_zero(border.bottom),
+            left: units.inline_or_zero(border.left),
+        };
+        let content_box = Directional {
+            top: padding_box.top + units.inline_or_zero(padding.top),
+            right: padding_box.right + units.inline_or_zero(padding.right),
+            bottom: padding_box.bottom + units.inline_or_zero(padding.bottom),
+            left: padding_box.left + units.inline_or_zero(padding.left),
+        };
+        let inside = |insets: &Directional<f64>| {
+            x >= b.x + insets.left
+                && x <= b.x + b.width - insets.right
+                && y >= b.y + insets.top
+                && y <= b.y + b.height - insets.bottom
+        };
+        let area = if inside(&content_box) {
+            HitArea::Content
+        } else if inside(&padding_box) {
+            HitArea::Padding
+        } else {
+            HitArea::Border
+        };
+        Hit {
+            id: self.id,
+            x: x - (b.x + content_box.left),
+            y: y - (b.y + content_box.top),
+            area,
+            host: None,
+        }
+    }
+}
+
+/// The normal or `!important` declarations of rules, each with its source if the rule
+/// has one; see [`LayoutContext::style_sources`].
+fn declarations_with_sources(
+    rules: Vec<&Rule>,
+    important: bool,
+) -> Vec<(Option<StyleSource>, &Style)> {
+    rules
+        .into_iter()
+        .flat_map(|rule| {
+            let declarations = if important {
+                &rule.important_declarations
+            } else {
+                &rule.declarations
+            };
+            let spans = rule.source.as_ref().map_or(&[][..], |source| {
+                if important {
+                    &source.important_declarations
+                } else {
+                    &source.declarations
+                }
+            });
+            declarations
+                .iter()
+                .enumerate()
+                .map(move |(i, declaration)| {
+                    let source =
+                        rule.source
+                            .as_ref()
+                            .zip(spans.get(i))
+                            .map(|(source, (property, span))| StyleSource {
+                                property: property.clone(),
+                                important,
+                                stylesheet: source.stylesheet,
+                                span: *span,
+                                rule: source.span,
+                                overridden: false,
+                            });
+                    (source, declaration)
+                })
+        })
+        .collect()
+}
+
+fn generated_box(
+    element: &Node,
+    kind: GeneratedBox,
+    text: String,
+    style: Style,
+) -> Rc<RefCell<Node>> {
+    let mut node = Node::new(element.id, Some(text));
+    node.kind = NodeKind::Generated(kind);
+    node.parent = Some(element.id);
+    node.layout.style = Arc::new(style);
+    Rc::new(RefCell::new(node))
+}
+
+fn describe_node(id: Id, debug_name: Option<&str>) -> String {
+    match debug_name {
+        Some(name) => format!("#{} {:?}", id.value(), name),
+        None => format!("#{}", id.value()),
     }
 }
 
 pub fn build_render_tree(node: Rc<RefCell<Node>>) -> RenderNode {
     let nb = node.borrow();
-    let mut children = Vec::with_capacity(nb.children.len());
-    for c in &nb.children {
-        children.push(build_render_tree(c.clone()));
-    }
+    let children = nb
+        .box_children()
+        .into_iter()
+        .map(build_render_tree)
+        .collect();
     RenderNode {
         id: nb.id,
+        generated: nb.generated_box(),
         bounds: nb.layout.bounds,
         style: nb.layout.style.clone(),
-        text: nb.text.clone(),
+        text: nb.layout.text.clone(),
+        debug_name: nb.debug_name.clone(),
+        scroll_offset: nb.scroll_offset,
+        draggable: nb
+            .attributes
+            .get("draggable")
+            .is_some_and(|draggable| draggable == "true"),
+        embedded: nb.embedded.clone(),
         children,
     }
 }
@@ -362,9 +1597,42 @@ pub fn build_render_tree(node: Rc<RefCell<Node>>) -> RenderNode {
 #[cfg(test)]
 mod asserts;
 
+#[cfg(test)]
+mod box_sizing_tests;
+
+#[cfg(test)]
+mod direction_tests;
+
+#[cfg(test)]
+mod document_tests;
+
+#[cfg(test)]
+mod measure_tests;
+
+#[cfg(test)]
+mod render_tree_tests;
+
+#[cfg(test)]
+mod scroll_snap_tests;
+
+#[cfg(test)]
+mod size_limits_tests;
+
+#[cfg(test)]
+mod fixtures;
+
 #[cfg(test)]
 mod test_html;
 
+#[cfg(test)]
+mod viewport_tests;
+
+#[cfg(test)]
+mod custom_properties_tests;
+
+#[cfg(test)]
+mod font_size_tests;
+
 #[cfg(test)]
 mod flex_layout_flow_tests;
 
@@ -391,3 +1659,18 @@ mod flex_layout_margin_tests;
 
 #[cfg(test)]
 mod margin_tests;
+
+#[cfg(test)]
+mod text_node_tests;
+
+#[cfg(test)]
+mod pseudo_element_tests;
+
+#[cfg(test)]
+mod css_wide_keywords_tests;
+
+#[cfg(test)]
+mod aspect_ratio_tests;
+
+#[cfg(test)]
+mod block_layout_tests;
@@ -0,0 +1,86 @@
+use super::*;
+use crate::layout::fixtures::{add_node, bounds, context};
+
+#[test]
+fn test_height_follows_width() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 400px; height: 300px; }
+        .media { width: 160px; aspect-ratio: 16 / 9; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let media = add_node(&mut ctx, 2, row, "media");
+    ctx.layout();
+
+    // Not stretched to the row's height
+    assert_eq!(bounds(&ctx, media), Rect::new(0.0, 0.0, 160.0, 90.0));
+}
+
+#[test]
+fn test_flex_base_size_follows_cross_size() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 400px; height: 300px; }
+        .media { height: 50px; aspect-ratio: 2; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let media = add_node(&mut ctx, 2, row, "media");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, media), Rect::new(0.0, 0.0, 100.0, 50.0));
+}
+
+#[test]
+fn test_cross_size_follows_flexed_main_size() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 400px; height: 300px; }
+        .media { flex-grow: 1; aspect-ratio: 4 / 1; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let media = add_node(&mut ctx, 2, row, "media");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, media), Rect::new(0.0, 0.0, 400.0, 100.0));
+}
+
+#[test]
+fn test_ratio_applies_to_the_box_sizing_box() {
+    let mut ctx = context(
+        r#"
+        .column { display: flex; flex-direction: column; width: 400px; height: 300px; }
+        .content { width: 100px; padding: 10px; aspect-ratio: 1; }
+        .border { width: 100px; padding: 10px; aspect-ratio: 1; box-sizing: border-box; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let column = add_node(&mut ctx, 1, root, "column");
+    let content = add_node(&mut ctx, 2, column, "content");
+    let border = add_node(&mut ctx, 3, column, "border");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, content), Rect::new(0.0, 0.0, 120.0, 120.0));
+    assert_eq!(bounds(&ctx, border), Rect::new(0.0, 120.0, 100.0, 100.0));
+}
+
+#[test]
+fn test_explicit_sizes_win() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 400px; height: 300px; }
+        .media { width: 100px; height: 10px; aspect-ratio: 1; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let media = add_node(&mut ctx, 2, row, "media");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, media), Rect::new(0.0, 0.0, 100.0, 10.0));
+}
@@ -0,0 +1,158 @@
+use super::*;
+use crate::layout::fixtures::{add_node, bounds, context};
+
+#[test]
+fn test_children_stack_and_fill_the_width() {
+    let mut ctx = context(
+        r#"
+        .list { display: block; width: 400px; height: 300px; padding: 10px; }
+        .a { height: 50px; }
+        .b { height: 30px; margin: 5px 20px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let list = add_node(&mut ctx, 1, root, "list");
+    let a = add_node(&mut ctx, 2, list, "a");
+    let b = add_node(&mut ctx, 3, list, "b");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, list), Rect::new(0.0, 0.0, 420.0, 320.0));
+    assert_eq!(bounds(&ctx, a), Rect::new(10.0, 10.0, 400.0, 50.0));
+    assert_eq!(bounds(&ctx, b), Rect::new(30.0, 65.0, 360.0, 30.0));
+}
+
+#[test]
+fn test_sibling_margins_collapse() {
+    let mut ctx = context(
+        r#"
+        .a { height: 20px; margin-bottom: 30px; }
+        .b { height: 20px; margin-top: 10px; margin-bottom: -5px; }
+        .c { height: 20px; margin-top: 15px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let a = add_node(&mut ctx, 1, root, "a");
+    let b = add_node(&mut ctx, 2, root, "b");
+    let c = add_node(&mut ctx, 3, root, "c");
+    ctx.layout();
+
+    // The larger margin wins, and a negative one is taken off the positive one
+    assert_eq!(bounds(&ctx, a).y, 0.0);
+    assert_eq!(bounds(&ctx, b).y, 50.0);
+    assert_eq!(bounds(&ctx, c).y, 80.0);
+}
+
+#[test]
+fn test_height_follows_content() {
+    let mut ctx = context(
+        r#"
+        .outer { display: block; width: 200px; }
+        .inner { display: block; padding: 5px; }
+        .leaf { height: 40px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let outer = add_node(&mut ctx, 1, root, "outer");
+    let inner = add_node(&mut ctx, 2, outer, "inner");
+    let first = add_node(&mut ctx, 3, inner, "leaf");
+    let second = add_node(&mut ctx, 4, inner, "leaf");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, outer), Rect::new(0.0, 0.0, 200.0, 90.0));
+    assert_eq!(bounds(&ctx, inner), Rect::new(0.0, 0.0, 200.0, 90.0));
+    assert_eq!(bounds(&ctx, first), Rect::new(5.0, 5.0, 190.0, 40.0));
+    assert_eq!(bounds(&ctx, second), Rect::new(5.0, 45.0, 190.0, 40.0));
+}
+
+#[test]
+fn test_empty_leaf_is_as_tall_as_its_edges() {
+    let mut ctx = context(
+        r#"
+        .empty { padding: 10px; border: 2px solid black; }
+        .after { height: 10px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let empty = add_node(&mut ctx, 1, root, "empty");
+    let after = add_node(&mut ctx, 2, root, "after");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, empty), Rect::new(0.0, 0.0, 800.0, 24.0));
+    assert_eq!(bounds(&ctx, after).y, 24.0);
+}
+
+#[test]
+fn test_auto_margins_center() {
+    let mut ctx = context(
+        r#"
+        .centered { width: 100px; height: 10px; margin: 0 auto; }
+        .end { width: 100px; height: 10px; margin-left: auto; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let centered = add_node(&mut ctx, 1, root, "centered");
+    let end = add_node(&mut ctx, 2, root, "end");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, centered), Rect::new(350.0, 0.0, 100.0, 10.0));
+    assert_eq!(bounds(&ctx, end), Rect::new(700.0, 10.0, 100.0, 10.0));
+}
+
+#[test]
+fn test_aspect_ratio_gives_the_height() {
+    let mut ctx = context(
+        r#"
+        .media { aspect-ratio: 16 / 9; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let media = add_node(&mut ctx, 1, root, "media");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, media), Rect::new(0.0, 0.0, 800.0, 450.0));
+}
+
+#[test]
+fn test_flex_container_in_flow() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; }
+        .item { width: 50px; height: 30px; }
+        .after { height: 10px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let first = add_node(&mut ctx, 2, row, "item");
+    let second = add_node(&mut ctx, 3, row, "item");
+    let after = add_node(&mut ctx, 4, root, "after");
+    ctx.layout();
+
+    // The row fills the width and is as tall as its items
+    assert_eq!(bounds(&ctx, row), Rect::new(0.0, 0.0, 800.0, 30.0));
+    assert_eq!(bounds(&ctx, first), Rect::new(0.0, 0.0, 50.0, 30.0));
+    assert_eq!(bounds(&ctx, second), Rect::new(50.0, 0.0, 50.0, 30.0));
+    assert_eq!(bounds(&ctx, after), Rect::new(0.0, 30.0, 800.0, 10.0));
+}
+
+#[test]
+fn test_block_container_as_flex_item() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 300px; height: 100px; }
+        .column { display: block; width: 120px; }
+        .leaf { height: 20px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let column = add_node(&mut ctx, 2, row, "column");
+    let first = add_node(&mut ctx, 3, column, "leaf");
+    let second = add_node(&mut ctx, 4, column, "leaf");
+    ctx.layout();
+
+    // Stretched by the row, stacking its own children
+    assert_eq!(bounds(&ctx, column), Rect::new(0.0, 0.0, 120.0, 100.0));
+    assert_eq!(bounds(&ctx, first), Rect::new(0.0, 0.0, 120.0, 20.0));
+    assert_eq!(bounds(&ctx, second), Rect::new(0.0, 20.0, 120.0, 20.0));
+}
@@ -0,0 +1,46 @@
+use super::*;
+use crate::layout::fixtures::{add_node, bounds, context};
+
+#[test]
+fn test_universal_border_box_reset() {
+    let mut ctx = context(
+        r#"
+        * { box-sizing: border-box; }
+        .row { display: flex; width: 300px; height: 100px; padding: 10px; border: 5px solid black; }
+        .item { width: 100px; height: 50px; padding: 10px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let item = add_node(&mut ctx, 2, row, "item");
+    ctx.layout();
+
+    // Sizes include the padding and border, which the content box is inset by
+    assert_eq!(bounds(&ctx, row), Rect::new(0.0, 0.0, 300.0, 100.0));
+    assert_eq!(bounds(&ctx, item), Rect::new(15.0, 15.0, 100.0, 50.0));
+}
+
+#[test]
+fn test_shrink_to_fit_includes_padding() {
+    let mut ctx = context(
+        r#"
+        .page { display: flex; }
+        .row { display: flex; height: 40px; padding: 0 10px; align-items: flex-start; }
+        .content { width: 50px; height: 10px; padding: 0 5px; }
+        .border { width: 50px; height: 10px; padding: 0 5px; box-sizing: border-box; }
+        "#,
+    );
+    // Rows shrink to fit as flex items
+    let root = ctx.document.root_id();
+    ctx.document
+        .set_attribute(root, "class".to_owned(), "page".to_owned());
+    let content_row = add_node(&mut ctx, 1, root, "row");
+    add_node(&mut ctx, 2, content_row, "content");
+    let border_row = add_node(&mut ctx, 3, root, "row");
+    add_node(&mut ctx, 4, border_row, "border");
+    ctx.layout();
+
+    // The child's border box, plus the container's own padding
+    assert_eq!(bounds(&ctx, content_row).width, 80.0);
+    assert_eq!(bounds(&ctx, border_row).width, 70.0);
+}
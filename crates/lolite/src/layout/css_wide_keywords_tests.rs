@@ -0,0 +1,135 @@
+use super::*;
+use crate::css_parser::parse_css;
+use crate::layout::fixtures::add_node;
+use crate::style::{FontStyle, StyleOrigin};
+use crate::units::DEFAULT_FONT_SIZE_PX;
+
+fn load_css(ctx: &mut LayoutContext, origin: StyleOrigin, css: &str) {
+    let sheet = parse_css(css).unwrap().with_origin(origin);
+    ctx.style_sheet.extend(&sheet);
+}
+
+fn style(ctx: &LayoutContext, id: Id) -> Style {
+    let node = ctx.document.get_node(id).unwrap();
+    let node = node.borrow();
+    node.layout.style.as_ref().clone()
+}
+
+#[test]
+fn test_inherit_takes_the_parents_value() {
+    let mut ctx = LayoutContext::new();
+    load_css(
+        &mut ctx,
+        StyleOrigin::Author,
+        r#"
+        .parent { display: flex; width: 120px; height: 50px; }
+        .child { width: inherit; height: 10px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let parent = add_node(&mut ctx, 1, root, "parent");
+    let child = add_node(&mut ctx, 2, parent, "child");
+    ctx.layout();
+    assert_eq!(style(&ctx, child).width, Some(Length::Px(120.0)));
+    assert_eq!(
+        ctx.document
+            .get_node(child)
+            .unwrap()
+            .borrow()
+            .layout
+            .bounds
+            .width,
+        120.0
+    );
+}
+
+#[test]
+fn test_initial_stops_inheritance() {
+    let mut ctx = LayoutContext::new();
+    load_css(
+        &mut ctx,
+        StyleOrigin::Author,
+        r#"
+        .parent { display: flex; font: italic bold 30px Georgia; }
+        .child { font-weight: initial; font-size: initial; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let parent = add_node(&mut ctx, 1, root, "parent");
+    let child = add_node(&mut ctx, 2, parent, "child");
+    ctx.layout();
+
+    let child = style(&ctx, child);
+    assert_eq!(child.font_weight, None);
+    assert_eq!(child.font_size, Some(Length::Px(DEFAULT_FONT_SIZE_PX)));
+    assert_eq!(child.font_style, Some(FontStyle::Italic));
+}
+
+#[test]
+fn test_unset_inherits_only_inherited_properties() {
+    let mut ctx = LayoutContext::new();
+    load_css(
+        &mut ctx,
+        StyleOrigin::Author,
+        r#"
+        .parent { display: flex; width: 120px; color: red; }
+        .child { color: blue; color: unset; width: 40px; width: unset; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let parent = add_node(&mut ctx, 1, root, "parent");
+    let child = add_node(&mut ctx, 2, parent, "child");
+    ctx.layout();
+
+    let child = style(&ctx, child);
+    assert_eq!(child.color, style(&ctx, parent).color);
+    assert_eq!(child.width, None);
+}
+
+#[test]
+fn test_later_declarations_override_keywords() {
+    let mut ctx = LayoutContext::new();
+    load_css(
+        &mut ctx,
+        StyleOrigin::Author,
+        r#"
+        .parent { display: flex; width: 120px; }
+        .child { width: inherit; }
+        .parent .child { width: 30px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let parent = add_node(&mut ctx, 1, root, "parent");
+    let child = add_node(&mut ctx, 2, parent, "child");
+    ctx.layout();
+    assert_eq!(style(&ctx, child).width, Some(Length::Px(30.0)));
+}
+
+#[test]
+fn test_revert_goes_back_to_earlier_origins() {
+    let mut ctx = LayoutContext::new();
+    load_css(
+        &mut ctx,
+        StyleOrigin::UserAgent,
+        ".child { width: 50px; font-weight: 600; }",
+    );
+    load_css(
+        &mut ctx,
+        StyleOrigin::Author,
+        r#"
+        .parent { display: flex; font-weight: 300; }
+        .child { width: 80px; width: revert; font-weight: 900; font-weight: revert; }
+        .child { height: 20px; height: revert; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let parent = add_node(&mut ctx, 1, root, "parent");
+    let child = add_node(&mut ctx, 2, parent, "child");
+    ctx.layout();
+
+    let child = style(&ctx, child);
+    assert_eq!(child.width, Some(Length::Px(50.0)));
+    assert_eq!(child.font_weight, Some(600));
+    // Nothing to go back to: as if the property wasn't set
+    assert_eq!(child.height, None);
+}
@@ -0,0 +1,122 @@
+use super::*;
+use crate::layout::fixtures::{add_node, context};
+use crate::style::Rgba;
+
+fn node_style(ctx: &LayoutContext, id: Id) -> Arc<Style> {
+    ctx.document
+        .get_node(id)
+        .unwrap()
+        .borrow()
+        .layout
+        .style
+        .clone()
+}
+
+#[test]
+fn test_var_inherited_from_ancestor() {
+    let mut ctx = context(
+        r#"
+        .theme { display: flex; --accent: #ff0000; --size: 40px; }
+        .panel { display: flex; width: 100px; height: 100px; }
+        .swatch { width: var(--size); height: 10px; background-color: var(--accent); }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let theme = add_node(&mut ctx, 1, root, "theme");
+    let panel = add_node(&mut ctx, 2, theme, "panel");
+    let swatch = add_node(&mut ctx, 3, panel, "swatch");
+    ctx.layout();
+
+    let bounds = ctx
+        .document
+        .get_node(swatch)
+        .unwrap()
+        .borrow()
+        .layout
+        .bounds;
+    assert_eq!(bounds.width, 40.0);
+    assert_eq!(
+        node_style(&ctx, swatch).background_color,
+        Some(Rgba {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255
+        })
+    );
+}
+
+#[test]
+fn test_var_redefined_on_descendant() {
+    let mut ctx = context(
+        r#"
+        .theme { display: flex; --size: 40px; }
+        .compact { display: flex; --size: 15px; }
+        .item { width: var(--size); height: 10px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let theme = add_node(&mut ctx, 1, root, "theme");
+    let wide = add_node(&mut ctx, 2, theme, "item");
+    let compact = add_node(&mut ctx, 3, theme, "compact");
+    let narrow = add_node(&mut ctx, 4, compact, "item");
+    ctx.layout();
+
+    let width = |id: Id| {
+        ctx.document
+            .get_node(id)
+            .unwrap()
+            .borrow()
+            .layout
+            .bounds
+            .width
+    };
+    assert_eq!(width(wide), 40.0);
+    assert_eq!(width(narrow), 15.0);
+}
+
+#[test]
+fn test_var_fallback_and_invalid_reference() {
+    let mut ctx = context(
+        r#"
+        .box {
+            width: 30px;
+            width: var(--missing);
+            height: var(--missing, 25px);
+            --a: var(--b);
+            --b: var(--a);
+            padding: var(--a, 5px);
+        }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let node = add_node(&mut ctx, 1, root, "box");
+    ctx.layout();
+
+    let style = node_style(&ctx, node);
+    // An unresolvable reference leaves the earlier declaration in effect.
+    assert_eq!(style.width, Some(Length::Px(30.0)));
+    assert_eq!(style.height, Some(Length::Px(25.0)));
+    // Cyclic properties are invalid, so the fallback is used.
+    assert_eq!(style.padding.top, Some(Length::Px(5.0)));
+}
+
+#[test]
+fn test_var_in_custom_property_value() {
+    let mut ctx = context(
+        r#"
+        .theme { display: flex; --base: 12px; --gap: var(--base) 4px; }
+        .row { display: flex; --base: 20px; margin: var(--gap); width: 10px; height: 10px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let theme = add_node(&mut ctx, 1, root, "theme");
+    let row = add_node(&mut ctx, 2, theme, "row");
+    ctx.layout();
+
+    // `--gap` was resolved where it was declared, then inherited.
+    let style = node_style(&ctx, row);
+    assert_eq!(style.custom_properties.get("--gap"), Some("12px 4px"));
+    assert_eq!(style.margin.top, Some(Length::Px(12.0)));
+    assert_eq!(style.margin.left, Some(Length::Px(4.0)));
+}
@@ -0,0 +1,74 @@
+use super::*;
+use crate::layout::fixtures::{self, add_node};
+use crate::style::TextDirection;
+
+/// A context in `locale` whose root is styled by `.page`.
+fn context(css: &str, locale: &str) -> LayoutContext {
+    let mut ctx = fixtures::context(css);
+    ctx.locale = Locale::new(locale);
+    let root = ctx.document.root_id();
+    ctx.document
+        .set_attribute(root, "class".to_owned(), "page".to_owned());
+    ctx
+}
+
+fn add_item(ctx: &mut LayoutContext, id: u64, class: &str) -> Id {
+    let root = ctx.document.root_id();
+    add_node(ctx, id, root, class)
+}
+
+fn layout(ctx: &mut LayoutContext) -> RenderNode {
+    ctx.layout();
+    build_render_tree(ctx.document.root_node())
+}
+
+#[test]
+fn test_rtl_locale_mirrors_rows() {
+    let mut ctx = context(
+        r#"
+        .page { display: flex; }
+        .a, .b { width: 100px; height: 20px; }
+        .a { margin-right: 10px; }
+        "#,
+        "ar-EG",
+    );
+    add_item(&mut ctx, 1, "a");
+    add_item(&mut ctx, 2, "b");
+    let tree = layout(&mut ctx);
+
+    // Rows start at the right, and the start margin is the right one
+    assert_eq!(tree.style.direction, Some(TextDirection::Rtl));
+    assert_eq!(tree.children[0].bounds, Rect::new(690.0, 0.0, 100.0, 20.0));
+    assert_eq!(tree.children[1].bounds, Rect::new(590.0, 0.0, 100.0, 20.0));
+    assert_eq!(tree.children[1].style.direction, Some(TextDirection::Rtl));
+}
+
+#[test]
+fn test_direction_property_overrides_locale() {
+    let mut ctx = context(
+        r#"
+        .page { display: flex; direction: ltr; }
+        .a, .b { width: 100px; height: 20px; }
+        "#,
+        "he",
+    );
+    add_item(&mut ctx, 1, "a");
+    add_item(&mut ctx, 2, "b");
+    let tree = layout(&mut ctx);
+
+    assert_eq!(tree.children[0].bounds, Rect::new(0.0, 0.0, 100.0, 20.0));
+    assert_eq!(tree.children[1].bounds, Rect::new(100.0, 0.0, 100.0, 20.0));
+    assert_eq!(tree.children[1].style.direction, Some(TextDirection::Ltr));
+}
+
+#[test]
+fn test_locale_font_fallbacks_follow_default_family() {
+    let mut ctx = context("", "ja-JP");
+    add_item(&mut ctx, 1, "a");
+    let tree = layout(&mut ctx);
+
+    assert_eq!(
+        tree.children[0].style.font_family.as_deref(),
+        Some("Arial, Hiragino Sans, Yu Gothic, Noto Sans CJK JP")
+    );
+}
@@ -0,0 +1,176 @@
+use super::*;
+use crate::style::Nth;
+
+#[test]
+fn test_set_parent_rejects_cycles() {
+    let mut document = Document::new();
+    let root = document.root_id();
+    let a = document.create_node(Id::from_u64(1), None);
+    let b = document.create_node(Id::from_u64(2), None);
+    let c = document.create_node(Id::from_u64(3), None);
+    document.set_parent(root, a).unwrap();
+    document.set_parent(a, b).unwrap();
+    document.set_parent(b, c).unwrap();
+
+    assert!(document.set_parent(c, a).is_err());
+    assert!(document.set_parent(b, a).is_err());
+    assert!(document.set_parent(a, root).is_err());
+    assert!(document.set_parent(a, a).is_err());
+
+    // The tree is left untouched
+    assert_eq!(document.get_node(a).unwrap().borrow().parent, Some(root));
+    assert_eq!(document.get_node(c).unwrap().borrow().children.len(), 0);
+
+    // Moving a node to a sibling subtree is fine
+    document.set_parent(a, c).unwrap();
+    assert_eq!(document.get_node(c).unwrap().borrow().parent, Some(a));
+    assert_eq!(document.get_node(b).unwrap().borrow().children.len(), 0);
+}
+
+#[test]
+fn test_set_parent_rejects_unknown_ids() {
+    let mut document = Document::new();
+    let root = document.root_id();
+    let a = document.create_node(Id::from_u64(1), None);
+    document.set_parent(root, a).unwrap();
+
+    assert!(document.set_parent(Id::from_u64(9), a).is_err());
+    assert!(document.set_parent(root, Id::from_u64(9)).is_err());
+    assert_eq!(document.get_node(a).unwrap().borrow().parent, Some(root));
+    assert_eq!(document.root_node().borrow().children.len(), 1);
+}
+
+#[test]
+fn test_clone_subtree() {
+    let mut document = Document::new();
+    let root = document.root_id();
+    let row = document.create_node(Id::from_u64(1), None);
+    let label = document.create_node(Id::from_u64(2), Some("Label".to_owned()));
+    let icon = document.create_node(Id::from_u64(3), None);
+    document.set_parent(root, row).unwrap();
+    document.set_parent(row, label).unwrap();
+    document.set_parent(row, icon).unwrap();
+    document.set_attribute(row, "class".to_owned(), "row".to_owned());
+    document.set_attribute(icon, "class".to_owned(), "icon".to_owned());
+
+    let copy = document.clone_subtree(row).unwrap();
+    assert_ne!(copy, row);
+    let copy_node = document.get_node(copy).unwrap();
+    let copy_node = copy_node.borrow();
+    assert_eq!(copy_node.parent, None);
+    assert_eq!(copy_node.attributes.get("class").unwrap(), "row");
+
+    let children: Vec<_> = copy_node
+        .children
+        .iter()
+        .map(|child| child.borrow())
+        .collect();
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0].text.as_deref(), Some("Label"));
+    assert_eq!(children[0].parent, Some(copy));
+    assert_eq!(children[1].attributes.get("class").unwrap(), "icon");
+    assert!(children
+        .iter()
+        .all(|child| child.id != label && child.id != icon));
+
+    // The source is untouched
+    assert_eq!(document.get_node(row).unwrap().borrow().children.len(), 2);
+    assert_eq!(document.nodes().count(), 7);
+    assert_eq!(document.clone_subtree(Id::from_u64(9)), None);
+}
+
+#[test]
+fn test_generated_ids_skip_used_ids() {
+    let mut document = Document::new();
+    let taken = document.create_node(Id::from_u64(u64::MAX), None);
+
+    let copy = document.clone_subtree(taken).unwrap();
+    assert_eq!(copy, Id::from_u64(u64::MAX - 1));
+}
+
+#[test]
+fn test_interaction_state_pseudo_classes() {
+    let mut document = Document::new();
+    let root = document.root_id();
+    let a = document.create_node(Id::from_u64(1), None);
+    let b = document.create_node(Id::from_u64(2), None);
+    let c = document.create_node(Id::from_u64(3), None);
+    document.set_parent(root, a).unwrap();
+    document.set_parent(a, b).unwrap();
+    document.set_parent(root, c).unwrap();
+    let matching = |document: &Document, pseudo_class| {
+        let selector = Selector::PseudoClass(pseudo_class);
+        [root, a, b, c]
+            .into_iter()
+            .filter(|id| document.matches(&document.get_node(*id).unwrap().borrow(), &selector))
+            .collect::<Vec<_>>()
+    };
+
+    // Hovering a node hovers its ancestors too
+    assert!(document.set_pointer(Some(b), false));
+    assert_eq!(matching(&document, PseudoClass::Hover), vec![root, a, b]);
+    assert!(matching(&document, PseudoClass::Active).is_empty());
+    assert!(!document.set_pointer(Some(b), false));
+
+    // The press stays on the node it started on
+    assert!(document.set_pointer(Some(b), true));
+    assert!(document.set_pointer(Some(c), true));
+    assert_eq!(matching(&document, PseudoClass::Hover), vec![root, c]);
+    assert_eq!(matching(&document, PseudoClass::Active), vec![root, a, b]);
+    assert!(document.set_pointer(None, false));
+    assert!(matching(&document, PseudoClass::Hover).is_empty());
+    assert!(matching(&document, PseudoClass::Active).is_empty());
+
+    // Focus doesn't extend to ancestors
+    assert!(document.set_focused(Some(b)));
+    assert!(!document.set_focused(Some(b)));
+    assert_eq!(matching(&document, PseudoClass::Focus), vec![b]);
+}
+
+#[test]
+fn test_structural_pseudo_classes() {
+    let mut document = Document::new();
+    let root = document.root_id();
+    let items: Vec<Id> = (1..=5)
+        .map(|i| document.create_node(Id::from_u64(i), None))
+        .collect();
+    let text = document.create_text_node(Id::from_u64(10), "label".to_string());
+    document.set_parent(root, text).unwrap();
+    for item in &items {
+        document.set_parent(root, *item).unwrap();
+    }
+    let matching = |document: &Document, selector: Selector| {
+        items
+            .iter()
+            .copied()
+            .filter(|id| document.matches(&document.get_node(*id).unwrap().borrow(), &selector))
+            .collect::<Vec<_>>()
+    };
+
+    // The leading text node doesn't count as a sibling
+    assert_eq!(
+        matching(&document, Selector::NthChild(Nth::FIRST)),
+        vec![items[0]]
+    );
+    assert_eq!(
+        matching(&document, Selector::NthLastChild(Nth::FIRST)),
+        vec![items[4]]
+    );
+    assert_eq!(
+        matching(&document, Selector::NthChild(Nth { a: 2, b: 0 })),
+        vec![items[1], items[3]]
+    );
+    assert_eq!(
+        matching(&document, Selector::NthChild(Nth { a: -1, b: 3 })),
+        vec![items[0], items[1], items[2]]
+    );
+    assert_eq!(
+        matching(&document, Selector::NthLastChild(Nth { a: 2, b: 1 })),
+        vec![items[0], items[2], items[4]]
+    );
+
+    // The root is its own only sibling
+    let root_node = document.root_node();
+    assert!(document.matches(&root_node.borrow(), &Selector::NthChild(Nth::FIRST)));
+    assert!(document.matches(&root_node.borrow(), &Selector::NthLastChild(Nth::FIRST)));
+}
@@ -0,0 +1,24 @@
+use super::{Id, LayoutContext, Rect};
+use crate::css_parser::parse_css;
+
+/// A context styled by `css`.
+pub fn context(css: &str) -> LayoutContext {
+    let mut ctx = LayoutContext::new();
+    for rule in parse_css(css).unwrap().rules {
+        ctx.style_sheet.add_rule(rule);
+    }
+    ctx
+}
+
+/// Add a node of the given class to `parent`.
+pub fn add_node(ctx: &mut LayoutContext, id: u64, parent: Id, class: &str) -> Id {
+    let node = ctx.document.create_node(Id::from_u64(id), None);
+    ctx.document.set_parent(parent, node).unwrap();
+    ctx.document
+        .set_attribute(node, "class".to_owned(), class.to_owned());
+    node
+}
+
+pub fn bounds(ctx: &LayoutContext, id: Id) -> Rect {
+    ctx.document.get_node(id).unwrap().borrow().layout.bounds
+}
@@ -1,5 +1,6 @@
 use crate::style::{
     AlignContent, AlignItems, Display, FlexDirection, FlexWrap, JustifyContent, Length, Rule,
+    StyleOrigin,
 };
 
 use super::*;
@@ -31,9 +32,15 @@ fn create_flex_container_with_alignment(
     // Add a CSS rule for the flex container
     let class_name = format!("flex_container_{}", container_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
-            display: Display::Flex,
+            display: Some(Display::Flex),
             flex_direction,
             justify_content,
             align_items,
@@ -58,7 +65,13 @@ fn create_flex_item(ctx: &mut LayoutContext, width: f64, height: f64) -> Id {
     // Add a CSS rule for the flex item
     let class_name = format!("flex_item_{}", item_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
             width: Some(Length::Px(width)),
             height: Some(Length::Px(height)),
@@ -171,6 +184,58 @@ fn test_justify_content_flex_end() {
     assert_eq!(y2, 0.0); // Same cross-axis position
 }
 
+#[test]
+fn test_logical_and_physical_alignment_keywords() {
+    let css = r#"
+        .box { display: flex; width: 300px; height: 100px; }
+        .reverse { flex-direction: row-reverse; }
+        .column { flex-direction: column; }
+        .rtl { direction: rtl; }
+        .start { justify-content: start; }
+        .end { justify-content: end; align-items: end; }
+        .left { justify-content: left; }
+        .right { justify-content: right; }
+        .item { width: 50px; height: 20px; }
+    "#;
+    let mut ctx = create_ctx();
+    for rule in crate::css_parser::parse_css(css).unwrap().rules {
+        ctx.style_sheet.add_rule(rule);
+    }
+    let root = ctx.document.root_id();
+    let cases = [
+        // start and end ignore reversed directions, unlike flex-start and flex-end
+        ("box reverse start", (0.0, 0.0)),
+        ("box reverse end", (250.0, 80.0)),
+        ("box left", (0.0, 0.0)),
+        ("box right", (250.0, 0.0)),
+        ("box rtl left", (0.0, 0.0)),
+        ("box rtl right", (250.0, 0.0)),
+        ("box rtl start", (250.0, 0.0)),
+        // left and right act as start on columns
+        ("box column right", (0.0, 0.0)),
+        ("box column end", (250.0, 80.0)),
+    ];
+    let mut items = Vec::new();
+    for (class, _) in &cases {
+        let container = ctx.document.create_node(next_test_id(), None);
+        ctx.document
+            .set_attribute(container, "class".to_owned(), class.to_string());
+        ctx.document.set_parent(root, container).unwrap();
+        let item = ctx.document.create_node(next_test_id(), None);
+        ctx.document
+            .set_attribute(item, "class".to_owned(), "item".to_owned());
+        ctx.document.set_parent(container, item).unwrap();
+        items.push((container, item));
+    }
+    ctx.layout();
+
+    for ((class, expected), (container, item)) in cases.iter().zip(items) {
+        let (cx, cy, _, _) = get_bounds(&ctx, container);
+        let (x, y, _, _) = get_bounds(&ctx, item);
+        assert_eq!((x - cx, y - cy), *expected, "{}", class);
+    }
+}
+
 #[test]
 fn test_justify_content_center() {
     let mut ctx = create_ctx();
@@ -1,4 +1,4 @@
-use crate::style::{Display, FlexDirection, FlexWrap, Length, Rule};
+use crate::style::{Display, FlexDirection, FlexWrap, Length, Rule, StyleOrigin};
 
 use super::*;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -26,9 +26,15 @@ fn create_flex_container(
     // Add a CSS rule for the flex container
     let class_name = format!("flex_container_{}", container_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
-            display: Display::Flex,
+            display: Some(Display::Flex),
             flex_direction,
             flex_wrap,
             width: width.map(Length::Px),
@@ -57,7 +63,13 @@ fn create_flex_item(
     // Add a CSS rule for the flex item
     let class_name = format!("flex_item_{}", item_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
             width: Some(Length::Px(width)),
             height: Some(Length::Px(height)),
@@ -1,4 +1,4 @@
-use crate::style::{Display, FlexDirection, FlexWrap, JustifyContent, Length, Rule};
+use crate::style::{Display, FlexDirection, FlexWrap, JustifyContent, Length, Rule, StyleOrigin};
 
 use super::*;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -32,7 +32,7 @@ fn create_flex_container_with_gap(
 
     // Base declaration: individual row/column gaps.
     declarations.push(Style {
-        display: Display::Flex,
+        display: Some(Display::Flex),
         flex_direction,
         row_gap: row_gap.map(Length::Px),
         column_gap: column_gap.map(Length::Px),
@@ -53,7 +53,13 @@ fn create_flex_container_with_gap(
     }
 
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations,
     });
 
@@ -71,7 +77,13 @@ fn create_flex_item(ctx: &mut LayoutContext, width: f64, height: f64) -> Id {
     // Add a CSS rule for the flex item
     let class_name = format!("flex_item_{}", item_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
             width: Some(Length::Px(width)),
             height: Some(Length::Px(height)),
@@ -425,9 +437,15 @@ fn test_gap_with_justify_content_center() {
     let container_id = ctx.document.create_node(next_test_id(), None);
     let class_name = format!("flex_container_{}", container_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
-            display: Display::Flex,
+            display: Some(Display::Flex),
             flex_direction: Some(FlexDirection::Row),
             justify_content: Some(JustifyContent::Center),
             column_gap: Some(Length::Px(20.0)),
@@ -481,9 +499,15 @@ fn test_gap_with_justify_content_space_between() {
     let container_id = ctx.document.create_node(next_test_id(), None);
     let class_name = format!("flex_container_{}", container_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
-            display: Display::Flex,
+            display: Some(Display::Flex),
             flex_direction: Some(FlexDirection::Row),
             justify_content: Some(JustifyContent::SpaceBetween),
             column_gap: Some(Length::Px(10.0)),
@@ -534,3 +558,59 @@ fn test_gap_with_justify_content_space_between() {
     assert_eq!(y2, 0.0);
     assert_eq!(y3, 0.0);
 }
+
+#[test]
+fn test_percentage_gaps_refer_to_the_content_box() {
+    let mut ctx = create_ctx();
+    let css = r#"
+        .wrap {
+            display: flex; flex-wrap: wrap; width: 300px; height: 200px;
+            padding: 50px; gap: 10%;
+        }
+    "#;
+    for rule in crate::css_parser::parse_css(css).unwrap().rules {
+        ctx.style_sheet.add_rule(rule);
+    }
+    let root = ctx.document.root_id();
+    let container = ctx.document.create_node(next_test_id(), None);
+    ctx.document
+        .set_attribute(container, "class".to_owned(), "wrap".to_owned());
+    ctx.document.set_parent(root, container).unwrap();
+
+    let item1 = create_flex_item(&mut ctx, 100.0, 40.0);
+    let item2 = create_flex_item(&mut ctx, 100.0, 40.0);
+    let item3 = create_flex_item(&mut ctx, 100.0, 40.0);
+    ctx.document.set_parent(container, item1).unwrap();
+    ctx.document.set_parent(container, item2).unwrap();
+    ctx.document.set_parent(container, item3).unwrap();
+
+    ctx.layout();
+
+    // 10% of the 300x200 content box: 30px between columns, 20px between rows
+    assert_eq!(get_bounds(&ctx, item1), (50.0, 50.0, 100.0, 40.0));
+    assert_eq!(get_bounds(&ctx, item2), (180.0, 50.0, 100.0, 40.0));
+    assert_eq!(get_bounds(&ctx, item3), (50.0, 110.0, 100.0, 40.0));
+}
+
+#[test]
+fn test_normal_gap_is_no_gap() {
+    let mut ctx = create_ctx();
+    let css = ".row { display: flex; width: 300px; height: 100px; column-gap: normal; }";
+    for rule in crate::css_parser::parse_css(css).unwrap().rules {
+        ctx.style_sheet.add_rule(rule);
+    }
+    let root = ctx.document.root_id();
+    let container = ctx.document.create_node(next_test_id(), None);
+    ctx.document
+        .set_attribute(container, "class".to_owned(), "row".to_owned());
+    ctx.document.set_parent(root, container).unwrap();
+
+    let item1 = create_flex_item(&mut ctx, 50.0, 30.0);
+    let item2 = create_flex_item(&mut ctx, 60.0, 40.0);
+    ctx.document.set_parent(container, item1).unwrap();
+    ctx.document.set_parent(container, item2).unwrap();
+
+    ctx.layout();
+
+    assert_eq!(get_bounds(&ctx, item2).0, 50.0);
+}
@@ -1,4 +1,4 @@
-use crate::style::{Display, FlexDirection, Length, Rule};
+use crate::style::{Display, FlexDirection, Length, Rule, StyleOrigin};
 
 use super::*;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -25,9 +25,15 @@ fn create_flex_container(
     // Add a CSS rule for the flex container
     let class_name = format!("flex_container_{}", container_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
-            display: Display::Flex,
+            display: Some(Display::Flex),
             flex_direction,
             width: width.map(Length::Px),
             height: height.map(Length::Px),
@@ -56,7 +62,13 @@ fn create_flex_item_with_flex(
     // Add a CSS rule for the flex item
     let class_name = format!("flex_item_{}", item_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
             width: width.map(Length::Px),
             height: height.map(Length::Px),
@@ -657,3 +669,37 @@ fn test_flex_zero_values() {
     assert_eq!(y1, 0.0);
     assert_eq!(y2, 0.0);
 }
+
+#[test]
+fn test_flex_shorthand_shares_space_regardless_of_width() {
+    let mut ctx = create_ctx();
+    for rule in crate::css_parser::parse_css(
+        ".row { display: flex; width: 300px; height: 30px; } \
+         .item { flex: 1; height: 30px; } .wide { width: 200px; }",
+    )
+    .unwrap()
+    .rules
+    {
+        ctx.style_sheet.add_rule(rule);
+    }
+    let row = ctx.document.create_node(next_test_id(), None);
+    ctx.document
+        .set_attribute(row, "class".to_owned(), "row".to_owned());
+    ctx.document
+        .set_parent(ctx.document.root_id(), row)
+        .unwrap();
+    let narrow = ctx.document.create_node(next_test_id(), None);
+    ctx.document
+        .set_attribute(narrow, "class".to_owned(), "item".to_owned());
+    ctx.document.set_parent(row, narrow).unwrap();
+    let wide = ctx.document.create_node(next_test_id(), None);
+    ctx.document
+        .set_attribute(wide, "class".to_owned(), "item wide".to_owned());
+    ctx.document.set_parent(row, wide).unwrap();
+
+    ctx.layout();
+
+    // `flex: 1` has a zero basis, so the width doesn't count
+    assert_eq!(get_bounds(&ctx, narrow), (0.0, 0.0, 150.0, 30.0));
+    assert_eq!(get_bounds(&ctx, wide), (150.0, 0.0, 150.0, 30.0));
+}
@@ -0,0 +1,104 @@
+use super::*;
+use crate::layout::fixtures::{add_node, context};
+use crate::style::{FontStyle, LineHeight};
+
+fn node(ctx: &LayoutContext, id: Id) -> (Rect, Option<Length>) {
+    let node = ctx.document.get_node(id).unwrap();
+    let node = node.borrow();
+    (node.layout.bounds, node.layout.style.font_size)
+}
+
+#[test]
+fn test_em_and_rem_follow_inherited_font_size() {
+    let mut ctx = context(
+        r#"
+        .root { font-size: 20px; }
+        .theme { display: flex; font-size: 1.5em; }
+        .panel { display: flex; width: 10em; height: 2rem; }
+        .label { font-size: 50%; width: 2em; height: 1rem; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    ctx.document
+        .set_attribute(root, "class".to_owned(), "root".to_owned());
+    let theme = add_node(&mut ctx, 1, root, "theme");
+    let panel = add_node(&mut ctx, 2, theme, "panel");
+    let label = add_node(&mut ctx, 3, panel, "label");
+    ctx.layout();
+
+    let (bounds, font_size) = node(&ctx, panel);
+    assert_eq!(font_size, Some(Length::Px(30.0)));
+    assert_eq!((bounds.width, bounds.height), (300.0, 40.0));
+
+    let (bounds, font_size) = node(&ctx, label);
+    assert_eq!(font_size, Some(Length::Px(15.0)));
+    assert_eq!((bounds.width, bounds.height), (30.0, 20.0));
+}
+
+#[test]
+fn test_inherited_font_size_is_recomputed() {
+    let mut ctx = context(
+        r#"
+        .big { display: flex; font-size: 30px; }
+        .small { display: flex; font-size: 10px; }
+        .box { width: 2em; height: 10px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let parent = add_node(&mut ctx, 1, root, "big");
+    let item = add_node(&mut ctx, 2, parent, "box");
+    ctx.layout();
+    assert_eq!(node(&ctx, item).0.width, 60.0);
+
+    ctx.document
+        .set_attribute(parent, "class".to_owned(), "small".to_owned());
+    ctx.layout();
+    assert_eq!(node(&ctx, item).1, Some(Length::Px(10.0)));
+    assert_eq!(node(&ctx, item).0.width, 20.0);
+}
+
+#[test]
+fn test_font_properties_are_inherited() {
+    let mut ctx = context(
+        r#"
+        .card { display: flex; font: italic bold 20px/150% Georgia, serif; }
+        .plain { display: flex; font-size: 10px; }
+        .title { display: flex; font-weight: 300; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let card = add_node(&mut ctx, 1, root, "card");
+    let title = add_node(&mut ctx, 2, card, "title");
+    ctx.layout();
+
+    let style = ctx
+        .document
+        .get_node(title)
+        .unwrap()
+        .borrow()
+        .layout
+        .style
+        .clone();
+    assert_eq!(style.font_family.as_deref(), Some("Georgia, serif"));
+    assert_eq!(style.font_weight, Some(300));
+    assert_eq!(style.font_style, Some(FontStyle::Italic));
+    // Percentages are resolved against the parent's font size before being inherited
+    assert_eq!(
+        style.line_height,
+        Some(LineHeight::Length(Length::Px(30.0)))
+    );
+
+    ctx.document
+        .set_attribute(card, "class".to_owned(), "plain".to_owned());
+    ctx.layout();
+    let style = ctx
+        .document
+        .get_node(title)
+        .unwrap()
+        .borrow()
+        .layout
+        .style
+        .clone();
+    assert_eq!(style.font_style, None);
+    assert_eq!(style.line_height, None);
+}
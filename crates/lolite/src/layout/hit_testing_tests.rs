@@ -1,4 +1,5 @@
 use super::*;
+use crate::style::Overflow;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 fn next_test_id() -> Id {
@@ -224,3 +225,211 @@ fn test_find_element_at_position_overlapping_siblings() {
     assert_eq!(result[0], child2_id);
     assert_eq!(result[1], root_id);
 }
+
+#[test]
+fn test_find_element_at_position_scrolled_content() {
+    let mut ctx = LayoutContext::new();
+    let root_id = ctx.document.root_id();
+    let child_id = next_test_id();
+
+    ctx.document.create_node(child_id, None);
+    ctx.document.set_parent(root_id, child_id).unwrap();
+
+    {
+        let root = ctx.document.root_node();
+        let mut root_borrow = root.borrow_mut();
+        root_borrow.layout.bounds = Rect::new(0.0, 0.0, 200.0, 100.0);
+        root_borrow.scroll_offset = ScrollOffset { x: 0.0, y: 150.0 };
+    }
+    ctx.document
+        .nodes
+        .get(&child_id)
+        .unwrap()
+        .borrow_mut()
+        .layout
+        .bounds = Rect::new(0.0, 150.0, 200.0, 50.0);
+
+    let tree = build_render_tree(ctx.document.root_node());
+
+    // The child is scrolled up to the top of the root
+    let result = tree.find_element_at_position(20.0, 20.0);
+    assert_eq!(result, vec![child_id, root_id]);
+
+    // Its unscrolled position is now empty
+    let result = tree.find_element_at_position(20.0, 90.0);
+    assert_eq!(result, vec![root_id]);
+}
+
+#[test]
+fn test_find_element_at_position_clips_scroll_containers_to_padding_box() {
+    let mut ctx = LayoutContext::new();
+    let root_id = ctx.document.root_id();
+    let child_id = next_test_id();
+
+    ctx.document.create_node(child_id, None);
+    ctx.document.set_parent(root_id, child_id).unwrap();
+
+    {
+        let root = ctx.document.root_node();
+        let mut root_borrow = root.borrow_mut();
+        root_borrow.layout.bounds = Rect::new(0.0, 0.0, 200.0, 100.0);
+        root_borrow.layout.style = Arc::new(Style {
+            border_width: Directional::set_all(Some(Length::Px(10.0))),
+            overflow_x: Some(Overflow::Hidden),
+            overflow_y: Some(Overflow::Hidden),
+            ..Default::default()
+        });
+    }
+    ctx.document
+        .nodes
+        .get(&child_id)
+        .unwrap()
+        .borrow_mut()
+        .layout
+        .bounds = Rect::new(0.0, 0.0, 200.0, 100.0);
+
+    let tree = build_render_tree(ctx.document.root_node());
+    assert_eq!(tree.find_element_at_position(5.0, 50.0), vec![root_id]);
+    assert_eq!(
+        tree.find_element_at_position(50.0, 50.0),
+        vec![child_id, root_id]
+    );
+}
+
+#[test]
+fn test_find_element_at_position_skips_hidden_content() {
+    let mut ctx = LayoutContext::new();
+    let root_id = ctx.document.root_id();
+    let container_id = next_test_id();
+    let child_id = next_test_id();
+
+    ctx.document.create_node(container_id, None);
+    ctx.document.create_node(child_id, None);
+    ctx.document.set_parent(root_id, container_id).unwrap();
+    ctx.document.set_parent(container_id, child_id).unwrap();
+
+    ctx.document.root_node().borrow_mut().layout.bounds = Rect::new(0.0, 0.0, 200.0, 200.0);
+    {
+        let container = ctx.document.get_node(container_id).unwrap();
+        let mut container = container.borrow_mut();
+        container.layout.bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+        container.layout.style = Arc::new(Style {
+            content_visibility: Some(ContentVisibility::Hidden),
+            ..Default::default()
+        });
+    }
+    ctx.document
+        .get_node(child_id)
+        .unwrap()
+        .borrow_mut()
+        .layout
+        .bounds = Rect::new(10.0, 10.0, 50.0, 50.0);
+
+    let tree = build_render_tree(ctx.document.root_node());
+
+    // The container itself is still hit, but not its contents
+    let result = tree.find_element_at_position(20.0, 20.0);
+    assert_eq!(result, vec![container_id, root_id]);
+}
+
+#[test]
+fn test_hit_test_reports_local_position_and_area() {
+    let mut ctx = LayoutContext::new();
+    let root_id = ctx.document.root_id();
+    let slider_id = next_test_id();
+    ctx.document.create_node(slider_id, None);
+    ctx.document.set_parent(root_id, slider_id).unwrap();
+
+    {
+        let root = ctx.document.root_node();
+        let mut root_borrow = root.borrow_mut();
+        root_borrow.layout.bounds = Rect::new(0.0, 0.0, 200.0, 200.0);
+        root_borrow.scroll_offset = ScrollOffset { x: 0.0, y: 10.0 };
+    }
+    {
+        let slider = ctx.document.get_node(slider_id).unwrap();
+        let mut slider = slider.borrow_mut();
+        slider.layout.bounds = Rect::new(20.0, 40.0, 100.0, 50.0);
+        let sides = |px| Directional {
+            top: Some(Length::Px(px)),
+            right: Some(Length::Px(px)),
+            bottom: Some(Length::Px(px)),
+            left: Some(Length::Px(px)),
+        };
+        slider.layout.style = Arc::new(Style {
+            border_width: sides(2.0),
+            padding: sides(3.0),
+            ..Default::default()
+        });
+    }
+
+    let tree = build_render_tree(ctx.document.root_node());
+
+    // Scrolled up by 10px, the content box starts at (25, 35) in the window
+    let hits = tree.hit_test(35.0, 40.0);
+    assert_eq!(hits.len(), 2);
+    assert_eq!(
+        hits[0],
+        Hit {
+            id: slider_id,
+            x: 10.0,
+            y: 5.0,
+            area: HitArea::Content,
+            host: None,
+        }
+    );
+    assert_eq!(
+        hits[1],
+        Hit {
+            id: root_id,
+            x: 35.0,
+            y: 40.0,
+            area: HitArea::Content,
+            host: None,
+        }
+    );
+
+    assert_eq!(tree.hit_test(23.0, 40.0)[0].area, HitArea::Padding);
+    assert_eq!(tree.hit_test(21.0, 40.0)[0].area, HitArea::Border);
+    assert_eq!(tree.hit_test(21.0, 40.0)[0].x, -4.0);
+    assert_eq!(tree.hit_test(60.0, 78.0)[0].area, HitArea::Padding);
+}
+
+#[test]
+fn test_clamp_scroll_offset_to_overflow() {
+    let mut ctx = LayoutContext::new();
+    let root_id = ctx.document.root_id();
+    let child_id = next_test_id();
+
+    ctx.document.create_node(child_id, None);
+    ctx.document.set_parent(root_id, child_id).unwrap();
+    ctx.document.root_node().borrow_mut().layout.bounds = Rect::new(0.0, 0.0, 200.0, 100.0);
+    ctx.document
+        .nodes
+        .get(&child_id)
+        .unwrap()
+        .borrow_mut()
+        .layout
+        .bounds = Rect::new(0.0, 0.0, 200.0, 300.0);
+
+    // Only scroll containers scroll
+    assert!(!ctx.document.root_node().borrow().is_scrollable());
+    ctx.document.root_node().borrow_mut().layout.style = Arc::new(Style {
+        overflow_x: Some(Overflow::Hidden),
+        ..Default::default()
+    });
+
+    let root = ctx.document.root_node();
+    let root = root.borrow();
+    assert!(root.is_scrollable());
+    // `hidden` only stops the user from scrolling; `visible` on the other axis is `auto`
+    assert!(root.is_user_scrollable());
+    assert_eq!(
+        root.clamp_scroll_offset(ScrollOffset { x: 50.0, y: 500.0 }),
+        ScrollOffset { x: 0.0, y: 200.0 }
+    );
+    assert_eq!(
+        root.clamp_scroll_offset(ScrollOffset { x: 0.0, y: -10.0 }),
+        ScrollOffset { x: 0.0, y: 0.0 }
+    );
+}
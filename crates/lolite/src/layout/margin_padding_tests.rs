@@ -1,7 +1,9 @@
 use super::*;
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use crate::style::{Directional, Display, FlexDirection, Length, Rule, Selector, Style};
+use crate::style::{
+    Directional, Display, FlexDirection, Length, Rule, Selector, Style, StyleOrigin,
+};
 
 fn next_test_id() -> Id {
     static NEXT: AtomicU64 = AtomicU64::new(1);
@@ -26,9 +28,15 @@ fn create_container(
     // Add a CSS rule for the container
     let class_name = format!("container_{}", container_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
-            display: Display::Flex,
+            display: Some(Display::Flex),
             flex_direction: Some(FlexDirection::Row),
             width: width.map(Length::Px),
             height: height.map(Length::Px),
@@ -74,7 +82,13 @@ fn create_item_with_spacing(
     // Add a CSS rule for the item
     let class_name = format!("item_{}", item_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
             width: Some(Length::Px(width)),
             height: Some(Length::Px(height)),
@@ -261,9 +275,15 @@ fn test_margin_column_direction() {
     let container_id = ctx.document.create_node(next_test_id(), None);
     let class_name = format!("container_{}", container_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
-            display: Display::Flex,
+            display: Some(Display::Flex),
             flex_direction: Some(FlexDirection::Column),
             width: Some(Length::Px(200.0)),
             height: Some(Length::Px(400.0)),
@@ -437,9 +457,15 @@ fn test_padding_column_direction() {
     let container_id = ctx.document.create_node(next_test_id(), None);
     let class_name = format!("container_{}", container_id.0);
     ctx.style_sheet.add_rule(Rule {
-        selector: Selector::Class(class_name.clone()),
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
         declarations: vec![Style {
-            display: Display::Flex,
+            display: Some(Display::Flex),
             flex_direction: Some(FlexDirection::Column),
             width: Some(Length::Px(200.0)),
             height: Some(Length::Px(400.0)),
@@ -25,3 +25,14 @@ fn test_margin_override_2() {
     ctx.assert_node_bounds_eq(container, &Rect::new(0.0, 0.0, 400.0, 200.0));
     ctx.assert_node_bounds_eq(child, &Rect::new(20.0, 20.0, 60.0, 40.0));
 }
+
+#[test]
+fn test_margin_override_across_rules() {
+    let (ctx, nodes_by_id) = load_html_test_example(HTML, "margin-override-3");
+    let child = nodes_by_id["child"];
+    ctx.assert_node_bounds_eq(child, &Rect::new(20.0, 20.0, 60.0, 40.0));
+
+    let (ctx, nodes_by_id) = load_html_test_example(HTML, "margin-override-4");
+    let child = nodes_by_id["child"];
+    ctx.assert_node_bounds_eq(child, &Rect::new(40.0, 10.0, 60.0, 40.0));
+}
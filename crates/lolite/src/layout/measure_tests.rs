@@ -0,0 +1,98 @@
+use super::*;
+use crate::layout::fixtures::bounds;
+use crate::style::{Directional, Display, Length, Rule, StyleOrigin};
+use std::sync::Mutex;
+
+fn leaf_with_style(ctx: &mut LayoutContext, id: u64, style: Style) -> Id {
+    let root_id = ctx.document.root_id();
+    let node = ctx.document.create_node(Id::from_u64(id), None);
+    ctx.document.set_parent(root_id, node).unwrap();
+
+    let class_name = format!("leaf_{}", id);
+    ctx.style_sheet.add_rule(Rule {
+        selectors: vec![Selector::Class(class_name.clone())],
+        layer: None,
+        media: None,
+        scope: None,
+        origin: StyleOrigin::Author,
+        source: None,
+        important_declarations: Vec::new(),
+        declarations: vec![style],
+    });
+    ctx.document
+        .set_attribute(node, "class".to_owned(), class_name);
+    node
+}
+
+#[test]
+fn test_measure_function_sizes_leaf() {
+    let mut ctx = LayoutContext::new();
+    // A flex item, so the measured width isn't stretched to the container
+    ctx.document.root_node().borrow_mut().layout.style = Arc::new(Style {
+        display: Some(Display::Flex),
+        ..Default::default()
+    });
+    let chart = leaf_with_style(
+        &mut ctx,
+        1,
+        Style {
+            padding: Directional::set_all(Some(Length::Px(5.0))),
+            ..Default::default()
+        },
+    );
+    ctx.measure_functions.insert(
+        chart,
+        Box::new(|_| Size {
+            width: 240.0,
+            height: 120.0,
+        }),
+    );
+
+    ctx.layout();
+
+    let b = bounds(&ctx, chart);
+    assert_eq!(b.width, 250.0);
+    assert_eq!(b.height, 130.0);
+}
+
+#[test]
+fn test_measure_function_receives_fixed_width() {
+    let mut ctx = LayoutContext::new();
+    let image = leaf_with_style(
+        &mut ctx,
+        1,
+        Style {
+            width: Some(Length::Px(200.0)),
+            ..Default::default()
+        },
+    );
+    let seen = std::sync::Arc::new(Mutex::new(None));
+    {
+        let seen = seen.clone();
+        // Keep a 2:1 aspect ratio for whatever width the style fixes.
+        ctx.measure_functions.insert(
+            image,
+            Box::new(move |constraints: MeasureConstraints| {
+                *seen.lock().unwrap() = Some(constraints);
+                let width = constraints.width.unwrap_or(100.0);
+                Size {
+                    width,
+                    height: width / 2.0,
+                }
+            }),
+        );
+    }
+
+    ctx.layout();
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        Some(MeasureConstraints {
+            width: Some(200.0),
+            height: None,
+        })
+    );
+    let b = bounds(&ctx, image);
+    assert_eq!(b.width, 200.0);
+    assert_eq!(b.height, 100.0);
+}
@@ -0,0 +1,110 @@
+use super::*;
+use crate::layout::fixtures::context;
+
+fn add_item(ctx: &mut LayoutContext, id: u64, text: Option<&str>) -> Id {
+    let id = ctx
+        .document
+        .create_node(Id::from_u64(id), text.map(str::to_owned));
+    ctx.document
+        .set_attribute(id, "class".to_owned(), "item".to_owned());
+    ctx.document.set_parent(ctx.document.root_id(), id).unwrap();
+    id
+}
+
+fn layout(ctx: &mut LayoutContext) -> RenderNode {
+    ctx.layout();
+    build_render_tree(ctx.document.root_node())
+}
+
+#[test]
+fn test_pseudo_elements_generate_boxes_around_children() {
+    let mut ctx = context(
+        r#"
+        .row { font-size: 10px; }
+        .item { display: flex; width: 100px; height: 20px; align-items: flex-start; color: red; }
+        .item::before { content: "> "; }
+        .item::after { content: "(" attr(data-count) ")"; color: blue; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    ctx.document
+        .set_attribute(root, "class".to_owned(), "row".to_owned());
+    let item = add_item(&mut ctx, 1, Some("ab"));
+    ctx.document
+        .set_attribute(item, "data-count".to_owned(), "3".to_owned());
+    let tree = layout(&mut ctx);
+
+    // 6px glyphs on 12px lines; the element's own text moves into a box between them,
+    // and each box is a line of its own, trimmed at the edges
+    let item_box = &tree.children[0];
+    assert_eq!(item_box.text, None);
+    let boxes: Vec<_> = item_box
+        .children
+        .iter()
+        .map(|b| (b.id, b.generated, b.text.as_deref(), b.bounds))
+        .collect();
+    assert_eq!(
+        boxes,
+        vec![
+            (
+                item,
+                Some(GeneratedBox::Before),
+                Some(">"),
+                Rect::new(0.0, 0.0, 6.0, 12.0)
+            ),
+            (
+                item,
+                Some(GeneratedBox::Text),
+                Some("ab"),
+                Rect::new(6.0, 0.0, 12.0, 12.0)
+            ),
+            (
+                item,
+                Some(GeneratedBox::After),
+                Some("(3)"),
+                Rect::new(18.0, 0.0, 18.0, 12.0)
+            ),
+        ]
+    );
+    // Generated boxes inherit from their element and cascade their own rules
+    assert_eq!(item_box.children[0].style.color, item_box.style.color);
+    assert_ne!(item_box.children[2].style.color, item_box.style.color);
+
+    // Points over a generated box hit its element
+    let hits = tree.find_element_at_position(30.0, 5.0);
+    assert_eq!(hits, vec![item, root]);
+
+    // Pseudo-element selectors never match document nodes
+    let selectors = crate::css_parser::parse_selector(".item::before").unwrap();
+    assert!(ctx.document.query_selector_all(&selectors).is_empty());
+}
+
+#[test]
+fn test_pseudo_elements_without_content_generate_nothing() {
+    let mut ctx = context(
+        r#"
+        .item { width: 50px; height: 20px; }
+        .item::before { color: red; }
+        .item::after { content: "x"; }
+        .item.plain::after { content: none; }
+        "#,
+    );
+    let item = add_item(&mut ctx, 1, Some("ab"));
+    let tree = layout(&mut ctx);
+    assert_eq!(tree.children[0].children.len(), 2);
+
+    // Dropping the boxes makes the element a leaf holding its text again
+    ctx.document
+        .set_attribute(item, "class".to_owned(), "item plain".to_owned());
+    let tree = layout(&mut ctx);
+    let item_box = &tree.children[0];
+    assert!(item_box.children.is_empty());
+    assert_eq!(item_box.text.as_deref(), Some("ab"));
+    assert!(ctx
+        .document
+        .get_node(item)
+        .unwrap()
+        .borrow()
+        .generated
+        .is_empty());
+}
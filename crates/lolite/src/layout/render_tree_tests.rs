@@ -0,0 +1,61 @@
+use super::*;
+use crate::style::Rgba;
+
+#[test]
+fn test_dump_includes_debug_names() {
+    let mut ctx = LayoutContext::new();
+    let root_id = ctx.document.root_id();
+    let sidebar = ctx.document.create_node(Id::from_u64(1), None);
+    let label = ctx
+        .document
+        .create_node(Id::from_u64(2), Some("Menu".to_string()));
+    ctx.document.set_parent(root_id, sidebar).unwrap();
+    ctx.document.set_parent(sidebar, label).unwrap();
+
+    {
+        let node = ctx.document.get_node(sidebar).unwrap();
+        let mut node = node.borrow_mut();
+        node.debug_name = Some("sidebar".to_string());
+        node.layout.bounds = Rect::new(0.0, 0.0, 200.0, 100.0);
+    }
+
+    let tree = build_render_tree(ctx.document.root_node());
+    assert_eq!(
+        tree.dump(),
+        "#0 [0, 0, 0x0]\n  #1 \"sidebar\" [0, 0, 200x100]\n    #2 [0, 0, 0x0] \"Menu\"\n"
+    );
+    assert_eq!(ctx.document.describe(sidebar), "#1 \"sidebar\"");
+    assert_eq!(ctx.document.describe(label), "#2");
+}
+
+#[test]
+fn test_apply_paint_overrides_patches_only_animated_nodes() {
+    let mut ctx = LayoutContext::new();
+    let root_id = ctx.document.root_id();
+    let a = ctx.document.create_node(Id::from_u64(1), None);
+    let b = ctx.document.create_node(Id::from_u64(2), None);
+    ctx.document.set_parent(root_id, a).unwrap();
+    ctx.document.set_parent(root_id, b).unwrap();
+    ctx.layout();
+
+    let mut tree = build_render_tree(ctx.document.root_node());
+    let shared = tree.find(b).unwrap().style.clone();
+    let red = Rgba {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    let overrides = HashMap::from([(
+        a,
+        Style {
+            background_color: Some(red),
+            ..Default::default()
+        },
+    )]);
+    tree.apply_paint_overrides(&overrides);
+
+    assert_eq!(tree.find(a).unwrap().style.background_color, Some(red));
+    assert_eq!(tree.find(a).unwrap().style.color, None);
+    assert!(Arc::ptr_eq(&tree.find(b).unwrap().style, &shared));
+}
@@ -0,0 +1,85 @@
+use super::*;
+use crate::style::{Overflow, ScrollSnapStrictness, ScrollSnapType};
+
+/// A 100px tall scroll container with three 80px tall children stacked vertically.
+fn snap_container(strictness: ScrollSnapStrictness, align: ScrollSnapAlign) -> LayoutContext {
+    let mut ctx = LayoutContext::new();
+    let root_id = ctx.document.root_id();
+
+    {
+        let root = ctx.document.root_node();
+        let mut root = root.borrow_mut();
+        root.layout.bounds = Rect::new(0.0, 0.0, 200.0, 100.0);
+        root.layout.style = Arc::new(Style {
+            overflow_y: Some(Overflow::Auto),
+            scroll_snap_type: Some(ScrollSnapType {
+                axis: ScrollSnapAxis::Y,
+                strictness,
+            }),
+            ..Default::default()
+        });
+    }
+
+    for i in 0..3 {
+        let id = ctx.document.create_node(Id::from_u64(i + 1), None);
+        ctx.document.set_parent(root_id, id).unwrap();
+        let node = ctx.document.get_node(id).unwrap();
+        let mut node = node.borrow_mut();
+        node.layout.bounds = Rect::new(0.0, i as f64 * 80.0, 200.0, 80.0);
+        node.layout.style = Arc::new(Style {
+            scroll_snap_align: Some(align),
+            ..Default::default()
+        });
+    }
+
+    ctx
+}
+
+fn snap_from(ctx: &LayoutContext, y: f64) -> Option<ScrollOffset> {
+    let root = ctx.document.root_node();
+    let root = root.borrow();
+    root.snap_scroll_offset(ScrollOffset { x: 0.0, y })
+}
+
+#[test]
+fn test_mandatory_snap_to_nearest_start() {
+    let ctx = snap_container(ScrollSnapStrictness::Mandatory, ScrollSnapAlign::Start);
+
+    assert_eq!(snap_from(&ctx, 30.0), Some(ScrollOffset { x: 0.0, y: 0.0 }));
+    assert_eq!(
+        snap_from(&ctx, 50.0),
+        Some(ScrollOffset { x: 0.0, y: 80.0 })
+    );
+    // The last child's start is past the scroll range, so it clamps to the end
+    assert_eq!(
+        snap_from(&ctx, 135.0),
+        Some(ScrollOffset { x: 0.0, y: 140.0 })
+    );
+    assert_eq!(snap_from(&ctx, 80.0), None);
+}
+
+#[test]
+fn test_center_snap_alignment() {
+    let ctx = snap_container(ScrollSnapStrictness::Mandatory, ScrollSnapAlign::Center);
+
+    // The middle child's center (120) lines up with the container's center (50)
+    assert_eq!(
+        snap_from(&ctx, 60.0),
+        Some(ScrollOffset { x: 0.0, y: 70.0 })
+    );
+}
+
+#[test]
+fn test_proximity_snap_ignores_distant_points() {
+    let ctx = snap_container(ScrollSnapStrictness::Proximity, ScrollSnapAlign::Start);
+
+    assert_eq!(
+        snap_from(&ctx, 70.0),
+        Some(ScrollOffset { x: 0.0, y: 80.0 })
+    );
+
+    let root = ctx.document.root_node();
+    root.borrow_mut().children[1].borrow_mut().layout.style = Arc::default();
+    // Nearest remaining snap point is 60px away, beyond half the container height
+    assert_eq!(snap_from(&ctx, 80.0), None);
+}
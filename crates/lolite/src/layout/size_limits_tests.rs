@@ -0,0 +1,94 @@
+use super::*;
+use crate::layout::fixtures::{add_node, bounds, context};
+
+#[test]
+fn test_root_size_is_clamped() {
+    let mut ctx = context(".root { max-width: 300px; min-height: 600px; }");
+    let root = ctx.document.root_id();
+    ctx.document
+        .set_attribute(root, "class".to_owned(), "root".to_owned());
+    add_node(&mut ctx, 1, root, "item");
+    ctx.layout();
+
+    // The viewport is 800x500
+    assert_eq!(bounds(&ctx, root), Rect::new(0.0, 0.0, 300.0, 600.0));
+}
+
+#[test]
+fn test_growing_items_freeze_at_max_size() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 400px; height: 50px; }
+        .a, .b { width: 50px; height: 10px; flex-grow: 1; }
+        .a { max-width: 100px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let a = add_node(&mut ctx, 2, row, "a");
+    let b = add_node(&mut ctx, 3, row, "b");
+    ctx.layout();
+
+    // `a` stops at 100px, and `b` takes the rest of the free space
+    assert_eq!(bounds(&ctx, a), Rect::new(0.0, 0.0, 100.0, 10.0));
+    assert_eq!(bounds(&ctx, b), Rect::new(100.0, 0.0, 300.0, 10.0));
+}
+
+#[test]
+fn test_shrinking_items_freeze_at_min_size() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 200px; height: 50px; }
+        .a, .b { width: 200px; height: 10px; flex-shrink: 1; }
+        .a { min-width: 150px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let a = add_node(&mut ctx, 2, row, "a");
+    let b = add_node(&mut ctx, 3, row, "b");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, a).width, 150.0);
+    assert_eq!(bounds(&ctx, b), Rect::new(150.0, 0.0, 50.0, 10.0));
+}
+
+#[test]
+fn test_limits_apply_to_the_box_sizing_box() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 400px; height: 50px; align-items: flex-start; }
+        .a { width: 300px; height: 10px; max-width: 100px; min-width: 120px; padding: 0 10px; }
+        .b { width: 50%; height: 10px; max-width: 25%; box-sizing: border-box; padding: 0 10px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let a = add_node(&mut ctx, 2, row, "a");
+    let b = add_node(&mut ctx, 3, row, "b");
+    ctx.layout();
+
+    // The minimum wins over the maximum, and both exclude the padding
+    assert_eq!(bounds(&ctx, a).width, 140.0);
+    // Percentages refer to the container's width
+    assert_eq!(bounds(&ctx, b).width, 100.0);
+}
+
+#[test]
+fn test_cross_size_is_clamped() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; width: 400px; height: 100px; }
+        .stretched { width: 10px; max-height: 40px; }
+        .short { width: 10px; height: 10px; min-height: 30px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    let row = add_node(&mut ctx, 1, root, "row");
+    let stretched = add_node(&mut ctx, 2, row, "stretched");
+    let short = add_node(&mut ctx, 3, row, "short");
+    ctx.layout();
+
+    assert_eq!(bounds(&ctx, stretched).height, 40.0);
+    assert_eq!(bounds(&ctx, short).height, 30.0);
+}
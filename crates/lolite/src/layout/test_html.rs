@@ -69,13 +69,13 @@ impl Reader {
             let id = Id::from_u64(self.next_id);
             self.next_id += 1;
 
-            // we only support text if it's the only child
-            let text = element
-                .children
-                .get(0)
-                .and_then(|child| child.text())
-                .map(|s| s.to_owned());
+            // A lone text child is the element's content; otherwise text becomes text nodes
+            let text = match element.children.as_slice() {
+                [child] => child.text().map(|s| s.to_owned()),
+                _ => None,
+            };
 
+            let has_text = text.is_some();
             let node = self.ctx.document.create_node(id, text);
             self.ctx.document.set_parent(parent, node).unwrap();
 
@@ -105,21 +105,17 @@ impl Reader {
                 self.nodes_by_id.insert(id_attr.to_owned(), node);
             }
 
-            // check multiple text children
-            if element
-                .children
-                .iter()
-                .filter(|child| child.text().is_some())
-                .count()
-                > 1
-            {
-                panic!("multiple text children not supported");
-            }
-
             // recurse into children
-            for child in &element.children {
-                self.copy_nodes(child, node);
+            if !has_text {
+                for child in &element.children {
+                    self.copy_nodes(child, node);
+                }
             }
+        } else if let html_parser::Node::Text(text) = html_node {
+            let id = Id::from_u64(self.next_id);
+            self.next_id += 1;
+            let node = self.ctx.document.create_text_node(id, text.to_owned());
+            self.ctx.document.set_parent(parent, node).unwrap();
         }
     }
 }
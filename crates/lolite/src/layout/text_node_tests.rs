@@ -0,0 +1,105 @@
+use super::*;
+use crate::layout::fixtures::{bounds, context};
+
+#[test]
+fn test_text_runs_become_anonymous_flex_items() {
+    let mut ctx = context(
+        r#"
+        .row { display: flex; align-items: flex-start; font-size: 10px; color: red; }
+        .box { width: 10px; height: 5px; }
+        "#,
+    );
+    let root = ctx.document.root_id();
+    ctx.document
+        .set_attribute(root, "class".to_owned(), "row".to_owned());
+    let mut next_id = 1;
+    let mut add = |ctx: &mut LayoutContext, text: Option<&str>| {
+        let id = Id::from_u64(next_id);
+        next_id += 1;
+        match text {
+            Some(text) => ctx.document.create_text_node(id, text.to_owned()),
+            None => {
+                ctx.document.create_node(id, None);
+                ctx.document
+                    .set_attribute(id, "class".to_owned(), "box".to_owned());
+                id
+            }
+        };
+        ctx.document.set_parent(root, id).unwrap();
+        id
+    };
+    let ab = add(&mut ctx, Some("ab"));
+    let first_box = add(&mut ctx, None);
+    let cd = add(&mut ctx, Some("cd"));
+    let ef = add(&mut ctx, Some("ef"));
+    let second_box = add(&mut ctx, None);
+    let blank = add(&mut ctx, Some("  "));
+    ctx.layout();
+
+    // 6px glyphs on 12px lines, in the container's font
+    assert_eq!(bounds(&ctx, ab), Rect::new(0.0, 0.0, 12.0, 12.0));
+    assert_eq!(bounds(&ctx, first_box), Rect::new(12.0, 0.0, 10.0, 5.0));
+    // Adjacent text nodes share one item, side by side
+    assert_eq!(bounds(&ctx, cd), Rect::new(22.0, 0.0, 12.0, 12.0));
+    assert_eq!(bounds(&ctx, ef), Rect::new(34.0, 0.0, 12.0, 12.0));
+    assert_eq!(bounds(&ctx, second_box), Rect::new(46.0, 0.0, 10.0, 5.0));
+    // Whitespace-only runs generate no item
+    assert_eq!(bounds(&ctx, blank), Rect::new(0.0, 0.0, 0.0, 0.0));
+
+    let node = ctx.document.get_node(cd).unwrap();
+    let style = node.borrow().layout.style.clone();
+    assert_eq!(style.font_size, Some(Length::Px(10.0)));
+    assert!(style.color.is_some());
+}
+
+#[test]
+fn test_text_nodes_are_not_elements() {
+    let mut document = Document::new();
+    let root = document.root_id();
+    let text = document.create_text_node(Id::from_u64(1), "hello".to_owned());
+    let child = document.create_node(Id::from_u64(2), None);
+    document.set_parent(root, text).unwrap();
+
+    assert!(document.set_parent(text, child).is_err());
+    assert_eq!(
+        document.query_selector_all(&[Selector::Universal]),
+        vec![root]
+    );
+
+    let copy = document.clone_subtree(text).unwrap();
+    assert!(document.get_node(copy).unwrap().borrow().is_text_node());
+}
+
+#[test]
+fn test_white_space_collapses_before_layout() {
+    let mut ctx = context(".row { display: flex; font-size: 10px; align-items: flex-start; }");
+    let root = ctx.document.root_id();
+    ctx.document
+        .set_attribute(root, "class".to_owned(), "row".to_owned());
+    let leading = ctx
+        .document
+        .create_text_node(Id::from_u64(1), "\n  ab  ".to_owned());
+    let trailing = ctx
+        .document
+        .create_text_node(Id::from_u64(2), "\t cd \n".to_owned());
+    let element = ctx
+        .document
+        .create_node(Id::from_u64(3), Some("  e \n f ".to_owned()));
+    for id in [leading, trailing, element] {
+        ctx.document.set_parent(root, id).unwrap();
+    }
+    ctx.layout();
+
+    // "ab cd" on one line: one space between the nodes, none at the edges
+    assert_eq!(bounds(&ctx, leading), Rect::new(0.0, 0.0, 18.0, 12.0));
+    assert_eq!(bounds(&ctx, trailing), Rect::new(18.0, 0.0, 12.0, 12.0));
+    assert_eq!(bounds(&ctx, element).width, 18.0);
+
+    let tree = build_render_tree(ctx.document.root_node());
+    let texts: Vec<_> = tree
+        .children
+        .iter()
+        .map(|child| child.text.as_deref())
+        .collect();
+    assert_eq!(texts, vec![Some("ab "), Some("cd"), Some("e f")]);
+}
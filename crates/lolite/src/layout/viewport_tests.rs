@@ -0,0 +1,89 @@
+use super::*;
+use crate::layout::fixtures::{add_node, bounds, context};
+
+#[test]
+fn test_root_tracks_viewport() {
+    let mut ctx = LayoutContext::new();
+    ctx.viewport = Size {
+        width: 1024.0,
+        height: 768.0,
+    };
+    ctx.layout();
+    assert_eq!(bounds(&ctx, ctx.document.root_id()).width, 1024.0);
+
+    ctx.viewport = Size {
+        width: 640.0,
+        height: 480.0,
+    };
+    ctx.layout();
+    let root = bounds(&ctx, ctx.document.root_id());
+    assert_eq!((root.width, root.height), (640.0, 480.0));
+}
+
+#[test]
+fn test_viewport_units_follow_viewport() {
+    let mut ctx = context(".panel { width: 50vw; height: 25vh; padding: 1vw; }");
+    let root = ctx.document.root_id();
+    let panel = add_node(&mut ctx, 1, root, "panel");
+    ctx.viewport = Size {
+        width: 1000.0,
+        height: 400.0,
+    };
+    ctx.layout();
+    let b = bounds(&ctx, panel);
+    assert_eq!((b.width, b.height), (520.0, 120.0));
+
+    // Resolved pixels from the previous layout don't stick
+    ctx.viewport = Size {
+        width: 200.0,
+        height: 400.0,
+    };
+    ctx.layout();
+    assert_eq!(bounds(&ctx, panel).width, 104.0);
+}
+
+#[test]
+fn test_percentages_refer_to_the_container_content_box() {
+    let mut ctx = context(".panel { width: 50%; height: 25%; padding: 12.5%; }");
+    let root = ctx.document.root_id();
+    let panel = add_node(&mut ctx, 1, root, "panel");
+    ctx.viewport = Size {
+        width: 1000.0,
+        height: 400.0,
+    };
+    ctx.layout();
+    let b = bounds(&ctx, panel);
+    // Padding percentages on every side refer to the containing block's width
+    assert_eq!((b.width, b.height), (750.0, 350.0));
+}
+
+#[test]
+fn test_vmin_and_vmax_follow_orientation() {
+    let mut ctx = context(".hero { width: 50vmax; height: 50vmin; }");
+    let root = ctx.document.root_id();
+    let hero = add_node(&mut ctx, 1, root, "hero");
+    ctx.viewport = Size {
+        width: 1000.0,
+        height: 400.0,
+    };
+    ctx.layout();
+    let b = bounds(&ctx, hero);
+    assert_eq!((b.width, b.height), (500.0, 200.0));
+
+    // Rotating the viewport swaps which dimension each unit follows
+    ctx.viewport = Size {
+        width: 400.0,
+        height: 1000.0,
+    };
+    ctx.layout();
+    let b = bounds(&ctx, hero);
+    assert_eq!((b.width, b.height), (500.0, 200.0));
+
+    ctx.viewport = Size {
+        width: 300.0,
+        height: 200.0,
+    };
+    ctx.layout();
+    let b = bounds(&ctx, hero);
+    assert_eq!((b.width, b.height), (150.0, 100.0));
+}
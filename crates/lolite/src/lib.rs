@@ -1,28 +1,118 @@
+mod animation;
 mod backend;
+mod block_layout;
 mod commands;
+mod compiled_stylesheet;
+mod computed_style;
 mod css_parser;
+mod damage;
+mod drag;
+mod embed;
 mod flex_layout;
+mod frame;
+mod input;
 mod layout;
+mod limits;
+mod locale;
+#[cfg(feature = "windowing")]
 mod painter;
+mod persist;
+mod snapshot;
+mod stats;
 mod style;
 mod text;
+mod transaction;
+mod units;
 mod windowing;
+mod zoom;
 
+pub use animation::{AnimatedProperty, AnimationHandle, AnimationValue, Easing, ReducedMotion};
+pub use backend::{BackendOptions, BackendReport, BackendType, GpuCacheBudget, GpuCacheUsage};
+pub use compiled_stylesheet::compile_stylesheet;
+pub use computed_style::ComputedStyle;
+pub use css_parser::{audit_stylesheet, CssDiagnostic, CssDiagnosticKind, CssSeverity};
+pub use damage::Damage;
+pub use drag::DragEvent;
+pub use frame::{FrameCallbackId, FrameStats};
+pub use input::{KeyEvent, Modifiers};
+pub use layout::{Hit, HitArea, MeasureConstraints, Rect, ScrollOffset, Size};
+pub use limits::{LimitError, Limits};
+pub use locale::Locale;
+pub use persist::DocumentError;
+pub use snapshot::FramePolicy;
+pub use stats::EngineStats;
+pub use style::{
+    ColorScheme, FontFace, FontSource, FontStyle, MotionPreference, Rgba, SourceSpan, StyleOrigin,
+    StyleSource, TextDirection,
+};
+pub use text::TextNodeDefaults;
+pub use transaction::Transaction;
+pub use windowing::WindowOptions;
+
+use animation::AnimationSpec;
 use commands::Command;
+#[cfg(feature = "windowing")]
+use damage::DamageTracker;
+#[cfg(feature = "windowing")]
+use drag::DragTracker;
+use embed::EmbeddedDocument;
+use frame::FrameScheduler;
+#[cfg(feature = "windowing")]
+use frame::FrameStatsRecorder;
 use layout::RenderNode;
+#[cfg(feature = "windowing")]
 use painter::Painter;
-use std::sync::Mutex;
+use persist::SavedDocument;
+use snapshot::SnapshotQueue;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+#[cfg(feature = "windowing")]
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{
     mpsc::{channel, Receiver, Sender},
-    Arc, RwLock,
+    Arc,
 };
+use std::sync::{Condvar, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::windowing::WindowMessageSender;
+use crate::windowing::{WindowMessage, WindowMessageSender};
 
 #[derive(Clone, Copy, Default, Debug, Eq, Hash, PartialEq)]
 pub struct Id(u64);
 
+/// Handle to a stylesheet added with [`Engine::add_stylesheet`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct StylesheetId(u64);
+
+impl StylesheetId {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Handle to a document state saved with [`Engine::checkpoint`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CheckpointId(u64);
+
+impl CheckpointId {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Handle to a window and its document, see [`Engine::create_window`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct WindowId(u64);
+
+impl WindowId {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
 impl Id {
     pub fn value(&self) -> u64 {
         self.0
@@ -37,18 +127,115 @@ impl Id {
     }
 }
 
+/// A window's document: its command thread, published snapshot and frame callbacks.
 #[derive(Clone)]
-pub struct Engine {
+struct WindowDocument {
+    id: WindowId,
     sender: Sender<Command>,
-    snapshot: Arc<RwLock<Option<RenderNode>>>,
+    snapshots: Arc<Mutex<SnapshotQueue>>,
+    options: Arc<Mutex<WindowOptions>>,
+    frames: Arc<Mutex<FrameScheduler>>,
+    #[cfg(feature = "windowing")]
+    frame_stats: Arc<Mutex<FrameStatsRecorder>>,
+    #[cfg(feature = "windowing")]
+    damage: Arc<Mutex<DamageTracker>>,
+    /// Pointer drag in the window, followed by the event loop.
+    #[cfg(feature = "windowing")]
+    drag: Arc<Mutex<DragTracker>>,
+    /// Layout time accumulated by the command thread since the last frame.
+    #[cfg(feature = "windowing")]
+    layout_time: Arc<Mutex<Duration>>,
+    zoom: Arc<Mutex<f64>>,
+    /// Copy of the limits enforced by the command thread, for checks made up front.
+    limits: Arc<Mutex<Limits>>,
+    /// Node receiving key events while the window is focused.
+    focused_node: Arc<Mutex<Option<Id>>>,
+    /// Created with [`Engine::create_document`]: never opened as a window.
+    headless: bool,
+}
+
+impl WindowDocument {
+    fn spawn(id: WindowId, headless: bool, message_sender: WindowMessageSender) -> Self {
+        let (tx, rx): (Sender<Command>, Receiver<Command>) = channel();
+        let snapshots = Arc::new(Mutex::new(SnapshotQueue::default()));
+        let snapshots_for_thread = Arc::clone(&snapshots);
+        let layout_time = Arc::new(Mutex::new(Duration::ZERO));
+        let layout_time_for_thread = Arc::clone(&layout_time);
+
+        // Spawn thread to handle the commands without blocking the main thread
+        thread::spawn(move || {
+            commands::handle_commands(
+                rx,
+                snapshots_for_thread,
+                layout_time_for_thread,
+                message_sender,
+            )
+        });
+
+        Self {
+            id,
+            sender: tx,
+            snapshots,
+            options: Arc::new(Mutex::new(WindowOptions::default())),
+            frames: Arc::new(Mutex::new(FrameScheduler::default())),
+            #[cfg(feature = "windowing")]
+            frame_stats: Arc::new(Mutex::new(FrameStatsRecorder::default())),
+            #[cfg(feature = "windowing")]
+            damage: Arc::new(Mutex::new(DamageTracker::default())),
+            #[cfg(feature = "windowing")]
+            drag: Arc::new(Mutex::new(DragTracker::default())),
+            #[cfg(feature = "windowing")]
+            layout_time,
+            zoom: Arc::new(Mutex::new(1.0)),
+            limits: Arc::new(Mutex::new(Limits::default())),
+            focused_node: Arc::new(Mutex::new(None)),
+            headless,
+        }
+    }
+
+    /// Handle for showing this document in a node of another one.
+    fn embedded(&self) -> EmbeddedDocument {
+        EmbeddedDocument::new(Arc::clone(&self.snapshots), self.sender.clone())
+    }
+}
+
+/// Handle to a lolite engine, operating on the document of one of its windows.
+///
+/// Clones share the same window; use [`Engine::window`] to get a handle to another one.
+#[derive(Clone)]
+pub struct Engine {
+    window: WindowDocument,
+    windows: Arc<Mutex<Vec<WindowDocument>>>,
     root_id: Id,
     running: Arc<Mutex<()>>,
     message_sender: WindowMessageSender,
+    next_animation_id: Arc<AtomicU64>,
+    next_stylesheet_id: Arc<AtomicU64>,
+    next_checkpoint_id: Arc<AtomicU64>,
+    exit_signal: Arc<(Mutex<bool>, Condvar)>,
+    backend_report: Arc<Mutex<Option<BackendReport>>>,
+    backend_options: Arc<Mutex<BackendOptions>>,
+    /// Window receiving keyboard input, if any of the engine's windows has focus.
+    focused_window: Arc<Mutex<Option<WindowId>>>,
 }
 
+type ClickCallback = Box<dyn Fn(f64, f64, Vec<Id>)>;
+type KeyCallback = Box<dyn Fn(WindowId, Option<Id>, &KeyEvent)>;
+type WindowFocusCallback = Box<dyn Fn(WindowId, bool)>;
+type DragCallback = Box<dyn Fn(WindowId, &DragEvent)>;
+
 #[derive(Default)]
 pub struct Params {
-    pub on_click: Option<Box<dyn Fn(f64, f64, Vec<Id>)>>,
+    /// Left click at a position in the window under the cursor, with the hit-test path
+    /// in that window's document, topmost node first.
+    pub on_click: Option<ClickCallback>,
+    /// Key pressed or released in the focused window, with the node focused with
+    /// [`Engine::focus`] in that window.
+    pub on_key: Option<KeyCallback>,
+    /// A window was activated (`true`) or deactivated.
+    pub on_window_focus: Option<WindowFocusCallback>,
+    /// A draggable node was dragged in a window, see [`DragEvent`].
+    pub on_drag: Option<DragCallback>,
 }
 
 #[derive(Debug)]
@@ -57,89 +244,888 @@ pub enum Error {
     UnknownError(String),
 }
 
+/// Errors returned by the document mutation APIs of [`Engine`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EngineError {
+    /// The background command thread is no longer running, so the command was not delivered.
+    CommandThreadDown,
+    /// The compiled stylesheet is corrupt or was produced by another version of lolite.
+    InvalidCompiledStylesheet,
+    /// The selector passed to [`Engine::query_selector`] could not be parsed.
+    InvalidSelector,
+    /// The mutation would make the document exceed the limits set with [`Engine::set_limits`].
+    LimitExceeded(LimitError),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::CommandThreadDown => write!(f, "engine command thread is down"),
+            EngineError::InvalidCompiledStylesheet => write!(f, "invalid compiled stylesheet"),
+            EngineError::InvalidSelector => write!(f, "invalid selector"),
+            EngineError::LimitExceeded(err) => write!(f, "limit exceeded: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
 impl Engine {
     /// Create a new CSS engine instance
     pub fn new() -> Self {
-        let (tx, rx): (Sender<Command>, Receiver<Command>) = channel();
-        let snapshot: Arc<RwLock<Option<RenderNode>>> = Arc::new(RwLock::new(None));
-        let snapshot_for_thread = Arc::clone(&snapshot);
         let message_sender = WindowMessageSender::new();
-        let message_sender_for_thread = message_sender.clone();
-
-        // Spawn thread to handle the commands without blocking the main thread
-        thread::spawn(move || {
-            commands::handle_commands(rx, snapshot_for_thread, message_sender_for_thread)
-        });
+        let window = WindowDocument::spawn(WindowId(0), false, message_sender.clone());
 
         Self {
-            sender: tx,
-            snapshot,
+            windows: Arc::new(Mutex::new(vec![window.clone()])),
+            window,
             root_id: Id::from_u64(0),
             running: Arc::new(Mutex::new(())),
             message_sender,
+            next_animation_id: Arc::new(AtomicU64::new(1)),
+            next_stylesheet_id: Arc::new(AtomicU64::new(1)),
+            next_checkpoint_id: Arc::new(AtomicU64::new(1)),
+            exit_signal: Arc::new((Mutex::new(false), Condvar::new())),
+            backend_report: Arc::new(Mutex::new(None)),
+            backend_options: Arc::new(Mutex::new(BackendOptions::default())),
+            focused_window: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Create another window with its own, empty document and stylesheets.
+    ///
+    /// Use [`Engine::window`] to get a handle operating on it. The window opens with the
+    /// next call to [`Engine::run`], or right away if the event loop is already running.
+    pub fn create_window(&self) -> WindowId {
+        let mut windows = self.windows.lock().unwrap();
+        let id = WindowId(windows.len() as u64);
+        windows.push(WindowDocument::spawn(
+            id,
+            false,
+            self.message_sender.clone(),
+        ));
+        self.message_sender.send(WindowMessage::OpenWindow(id.0));
+        id
+    }
+
+    /// Create a document with its own stylesheets that is never opened as a window, to
+    /// be shown in a node with [`Engine::set_embedded_document`].
+    ///
+    /// Returns a handle operating on it, like [`Engine::window`] does for windows.
+    pub fn create_document(&self) -> Engine {
+        let mut windows = self.windows.lock().unwrap();
+        let id = WindowId(windows.len() as u64);
+        let document = WindowDocument::spawn(id, true, self.message_sender.clone());
+        windows.push(document.clone());
+        Engine {
+            window: document,
+            ..self.clone()
+        }
+    }
+
+    /// Get a handle operating on the document of another window of this engine
+    pub fn window(&self, id: WindowId) -> Option<Engine> {
+        let window = self.windows.lock().unwrap().get(id.0 as usize)?.clone();
+        Some(Engine {
+            window,
+            ..self.clone()
+        })
+    }
+
+    /// Id of the window this handle operates on; [`Engine::new`] starts with window 0
+    pub fn window_id(&self) -> WindowId {
+        self.window.id
+    }
+
+    /// Configure this window, taking effect when it is opened by [`Engine::run`]
+    pub fn window_options(&self, options: WindowOptions) {
+        *self.window.options.lock().unwrap() = options;
+    }
+
+    /// Give keyboard focus within this window to a node, or clear it with `None`.
+    ///
+    /// Key events received while the window is focused are reported to
+    /// [`Params::on_key`] with this node, and it matches `:focus` rules. Each window
+    /// keeps its own focused node.
+    pub fn focus(&self, node: Option<Id>) {
+        *self.window.focused_node.lock().unwrap() = node;
+        if let Err(err) = self.send(Command::SetFocus(node)) {
+            eprintln!("Failed to update focus: {}", err);
         }
     }
 
-    // Run the event loop
+    /// Get the node focused with [`Engine::focus`] in this window
+    pub fn focused_node(&self) -> Option<Id> {
+        *self.window.focused_node.lock().unwrap()
+    }
+
+    /// Get the window receiving keyboard input, if one of the engine's windows is focused
+    pub fn focused_window(&self) -> Option<WindowId> {
+        *self.focused_window.lock().unwrap()
+    }
+
+    /// Choose and configure the rendering backend, taking effect with the next call to
+    /// [`Engine::run`]. Applies to all windows of the engine.
+    pub fn set_backend_options(&self, options: BackendOptions) {
+        *self.backend_options.lock().unwrap() = options;
+    }
+
+    /// Run the event loop, opening every window of the engine.
+    ///
+    /// Can be called from the handle of any window. `params` apply to all of them, with
+    /// clicked node ids coming from the document of the clicked window. Blocks until all
+    /// windows are closed or [`Engine::request_exit`] is called.
+    #[cfg(feature = "windowing")]
     pub fn run(&self, params: Params) -> Result<(), Error> {
         // only allow running once
         let _lock = self.running.try_lock().map_err(|_| Error::AlreadyRunning)?;
 
+        let params = Rc::new(params);
+        let ids_engine = self.clone();
+        let backend_report = self.backend_report.clone();
+        let open_engine = self.clone();
+        let windows = windowing::Windows {
+            ids: Box::new(move || {
+                let windows = ids_engine.windows.lock().unwrap();
+                windows
+                    .iter()
+                    .filter(|window| !window.headless)
+                    .map(|window| window.id.0)
+                    .collect()
+            }),
+            open: Box::new(move |id| {
+                let engine = open_engine
+                    .window(WindowId(id))
+                    .expect("window ids come from the engine");
+                let options = engine.window.options.lock().unwrap().clone();
+                (engine.window_params(params.clone()), options)
+            }),
+            on_backend: Box::new(move |report| {
+                *backend_report.lock().unwrap() = Some(report);
+            }),
+        };
+
+        let options = self.backend_options.lock().unwrap().clone();
+        windowing::run(windows, options, self.message_sender.clone())
+            .map_err(|err| Error::UnknownError(err.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Get the rendering backend chosen by [`Engine::run`] once the first window has
+    /// opened, with the reasons more preferred backends were skipped
+    pub fn backend_report(&self) -> Option<BackendReport> {
+        self.backend_report.lock().unwrap().clone()
+    }
+
+    /// Event handlers of this handle's window.
+    #[cfg(feature = "windowing")]
+    fn window_params(&self, params: Rc<Params>) -> windowing::Params {
         let this1 = self.clone();
         let this2 = self.clone();
+        let this3 = self.clone();
+        let this4 = self.clone();
+        let this5 = self.clone();
+        let this6 = self.clone();
+        let this7 = self.clone();
+        let this8 = self.clone();
+        let this9 = self.clone();
+        let this10 = self.clone();
+        // Pointer state last sent to the document, so plain moves within a node are free
+        let mut pointer = (None, false);
+        let on_key_params = params.clone();
+        let on_focus_params = params.clone();
+        let on_drag_params = params.clone();
+        let anti_alias = self.backend_options.lock().unwrap().anti_alias;
 
-        let mut params = windowing::Params {
+        windowing::Params {
             on_draw: Box::new(move |canvas| {
-                if let Some(snapshot) = this1.get_current_snapshot() {
-                    let mut painter = Painter::new(canvas);
+                let started = Instant::now();
+                this1.run_frame_callbacks();
+                let (snapshot, more_pending) = {
+                    let mut snapshots = this1.window.snapshots.lock().unwrap();
+                    (snapshots.next_frame(), snapshots.has_pending())
+                };
+                if more_pending {
+                    // Draw the rest of a bounded queue on the following frames
+                    this1.message_sender.send(WindowMessage::Redraw);
+                }
+                if let Some(snapshot) = snapshot {
+                    let zoom = this1.zoom();
+                    canvas.save();
+                    canvas.scale((zoom as f32, zoom as f32));
+                    let mut painter = Painter::new(canvas).with_anti_alias(anti_alias);
                     painter.paint(&snapshot);
+                    if let Some((source, dx, dy)) = this1.window.drag.lock().unwrap().drag_image() {
+                        painter.paint_drag_image(&snapshot, source, dx / zoom, dy / zoom);
+                    }
+                    canvas.restore();
+                    let frame = this1
+                        .window
+                        .damage
+                        .lock()
+                        .unwrap()
+                        .record_paint(snapshot, zoom);
+                    if let Some(frame) = frame {
+                        if let Some(color) = frame.flash {
+                            Painter::new(canvas).paint_flash(&frame.damage.rects, color);
+                        }
+                        // Run without holding the tracker lock, so the callback can replace itself.
+                        if let Some(callback) = frame.callback {
+                            (callback.lock().unwrap())(&frame.damage);
+                        }
+                    }
                 }
+                let paint = started.elapsed();
+                this1.window.frame_stats.lock().unwrap().record_paint(paint);
             }),
             on_click: Box::new(move |x, y| {
-                if let Some(snapshot) = this2.get_current_snapshot() {
-                    let elements = snapshot.find_element_at_position(x, y);
+                if let Some(snapshot) = this2.displayed_snapshot() {
+                    let zoom = this2.zoom();
+                    let elements = snapshot.find_element_at_position(x / zoom, y / zoom);
 
-                    if let Some(ref on_click) = params.on_click {
+                    if let Some(on_click) = &params.on_click {
                         on_click(x, y, elements);
                     }
                 }
             }),
-        };
+            on_scroll: Box::new(move |x, y, dx, dy| {
+                if let Some(snapshot) = this3.displayed_snapshot() {
+                    let zoom = this3.zoom();
+                    let elements = snapshot.find_element_at_position(x / zoom, y / zoom);
+                    let command = Command::UserScroll(elements, dx / zoom, dy / zoom);
+                    if let Err(err) = this3.send(command) {
+                        eprintln!("Failed to scroll: {}", err);
+                    }
+                }
+            }),
+            on_pointer: Box::new(move |position, pressed| {
+                let (hovered, draggable) = match (position, this10.displayed_snapshot()) {
+                    (Some(position), Some(snapshot)) => {
+                        let zoom = this10.zoom();
+                        let elements =
+                            snapshot.find_element_at_position(position.x / zoom, position.y / zoom);
+                        (elements.first().copied(), snapshot.draggable_in(&elements))
+                    }
+                    _ => (None, None),
+                };
+                if pointer != (hovered, pressed) {
+                    pointer = (hovered, pressed);
+                    if let Err(err) = this10.send(Command::SetPointer(hovered, pressed)) {
+                        eprintln!("Failed to update pointer state: {}", err);
+                    }
+                }
 
-        windowing::run(&mut params, self.message_sender.clone())
-            .map_err(|err| Error::UnknownError(err.to_string()))?;
+                let position = position.map(|position| (position.x, position.y));
+                let events = this10
+                    .window
+                    .drag
+                    .lock()
+                    .unwrap()
+                    .pointer(position, pressed, draggable, hovered);
+                if events.is_empty() {
+                    return;
+                }
+                // Move or remove the drag image
+                this10.message_sender.send(WindowMessage::Redraw);
+                if let Some(on_drag) = &on_drag_params.on_drag {
+                    for event in &events {
+                        on_drag(this10.window_id(), event);
+                    }
+                }
+            }),
+            on_resize: Box::new(move |width, height| {
+                if let Err(err) = this4.set_viewport(width, height) {
+                    eprintln!("Failed to resize viewport: {}", err);
+                }
+            }),
+            on_present: Box::new(move |render, missed_vsyncs, gpu_cache| {
+                this5.report_frame_stats(render, missed_vsyncs, gpu_cache);
+            }),
+            on_zoom: Box::new(move |step| {
+                if let Err(err) = this6.set_zoom(zoom::step_zoom(this6.zoom(), step)) {
+                    eprintln!("Failed to zoom: {}", err);
+                }
+            }),
+            on_color_scheme: Box::new(move |color_scheme| {
+                if let Err(err) = this7.set_color_scheme(color_scheme) {
+                    eprintln!("Failed to set color scheme: {}", err);
+                }
+            }),
+            on_key: Box::new(move |event| {
+                if let Some(on_key) = &on_key_params.on_key {
+                    on_key(this8.window_id(), this8.focused_node(), event);
+                }
+            }),
+            on_focus: Box::new(move |focused| {
+                let window = this9.window_id();
+                {
+                    let mut focused_window = this9.focused_window.lock().unwrap();
+                    if focused {
+                        *focused_window = Some(window);
+                    } else if *focused_window == Some(window) {
+                        // Another window may have been activated first
+                        *focused_window = None;
+                    }
+                }
+                if let Some(on_window_focus) = &on_focus_params.on_window_focus {
+                    on_window_focus(window, focused);
+                }
+            }),
+        }
+    }
 
-        Ok(())
+    /// Add a CSS stylesheet, returning a handle to remove or replace it later
+    pub fn add_stylesheet(&self, css_content: &str) -> Result<StylesheetId, EngineError> {
+        self.add_stylesheet_with_origin(StyleOrigin::Author, css_content)
     }
 
-    /// Add a CSS stylesheet
-    pub fn add_stylesheet(&self, css_content: &str) {
-        let _ = self
-            .sender
-            .send(Command::AddStylesheet(css_content.to_string()))
-            .expect("data thread down");
+    /// Add a stylesheet whose rules take part in the cascade as `origin`, so that e.g.
+    /// a widget library's styles lose to the application's regardless of load order,
+    /// cascade layers or selector specificity. See [`StyleOrigin`].
+    pub fn add_stylesheet_with_origin(
+        &self,
+        origin: StyleOrigin,
+        css_content: &str,
+    ) -> Result<StylesheetId, EngineError> {
+        self.check_stylesheet(css_content)?;
+        let id = StylesheetId(self.next_stylesheet_id.fetch_add(1, Ordering::Relaxed));
+        self.send(Command::AddStylesheet(
+            id,
+            None,
+            origin,
+            css_content.to_string(),
+        ))?;
+        Ok(id)
+    }
+
+    /// Add a stylesheet whose rules only apply to `root` and the nodes below it, so a
+    /// component can ship styles that neither leak into nor get overridden by the rest
+    /// of the document.
+    ///
+    /// Nodes below `root` are only matched by its scoped stylesheets; other stylesheets,
+    /// including those of enclosing scopes, still match `root` itself. Inherited
+    /// properties and animations cross scope boundaries as usual.
+    pub fn add_scoped_stylesheet(
+        &self,
+        root: Id,
+        css_content: &str,
+    ) -> Result<StylesheetId, EngineError> {
+        self.check_stylesheet(css_content)?;
+        let id = StylesheetId(self.next_stylesheet_id.fetch_add(1, Ordering::Relaxed));
+        self.send(Command::AddStylesheet(
+            id,
+            Some(root),
+            StyleOrigin::Author,
+            css_content.to_string(),
+        ))?;
+        Ok(id)
+    }
+
+    /// Fix the priority of cascade layers, lowest first, regardless of the order
+    /// stylesheets using them are loaded in. Rules outside any layer win over all layers.
+    pub fn declare_layers(&self, layers: &[&str]) -> Result<StylesheetId, EngineError> {
+        self.add_stylesheet(&format!("@layer {};", layers.join(", ")))
+    }
+
+    /// Add a stylesheet whose rules all belong to a cascade layer, as if wrapped in
+    /// `@layer <layer> { ... }`.
+    pub fn add_layered_stylesheet(
+        &self,
+        layer: &str,
+        css_content: &str,
+    ) -> Result<StylesheetId, EngineError> {
+        // On the first line, so that source positions keep their line numbers
+        self.add_stylesheet(&format!("@layer {} {{ {}\n}}", layer, css_content))
+    }
+
+    /// Add a stylesheet produced by [`compile_stylesheet`], skipping CSS parsing
+    pub fn add_compiled_stylesheet(&self, compiled: &[u8]) -> Result<StylesheetId, EngineError> {
+        let (css, sheet) = compiled_stylesheet::decode_stylesheet(compiled)
+            .ok_or(EngineError::InvalidCompiledStylesheet)?;
+        self.check_stylesheet(&css)?;
+        let id = StylesheetId(self.next_stylesheet_id.fetch_add(1, Ordering::Relaxed));
+        self.send(Command::AddCompiledStylesheet(id, css, sheet))?;
+        Ok(id)
+    }
+
+    /// Remove a stylesheet and all of its rules from the cascade
+    pub fn remove_stylesheet(&self, id: StylesheetId) -> Result<(), EngineError> {
+        self.send(Command::RemoveStylesheet(id))
+    }
+
+    /// Replace the rules of a stylesheet, keeping its position in the cascade.
+    ///
+    /// If the new CSS fails to parse, the previous rules stay in effect.
+    pub fn replace_stylesheet(
+        &self,
+        id: StylesheetId,
+        css_content: &str,
+    ) -> Result<(), EngineError> {
+        self.check_stylesheet(css_content)?;
+        self.send(Command::ReplaceStylesheet(id, css_content.to_string()))
     }
 
     /// Create a new document node with optional text content
-    pub fn create_node(&self, id: Id, text: Option<String>) -> Id {
-        self.sender
-            .send(Command::CreateNode(id, text))
-            .expect("data thread down");
-        id
+    pub fn create_node(&self, id: Id, text: Option<String>) -> Result<Id, EngineError> {
+        self.send(Command::CreateNode(id, None, text))?;
+        Ok(id)
+    }
+
+    /// Create a new document node with a tag, such as `div` or `button`, that type
+    /// selectors match; tags are compared case-insensitively
+    pub fn create_node_with_tag(
+        &self,
+        id: Id,
+        tag: &str,
+        text: Option<String>,
+    ) -> Result<Id, EngineError> {
+        self.send(Command::CreateNode(id, Some(tag.to_string()), text))?;
+        Ok(id)
+    }
+
+    /// Create a text node: a run of text inside its parent, styled by the parent's
+    /// inherited properties and never matched by selectors.
+    ///
+    /// Unlike text given to [`Engine::create_node`], which is the content of an element
+    /// with a box of its own, consecutive text nodes in a flex container are wrapped
+    /// together in one anonymous flex item, so text can sit between elements. Text nodes
+    /// can't have children.
+    pub fn create_text_node(&self, id: Id, text: &str) -> Result<Id, EngineError> {
+        self.send(Command::CreateTextNode(id, text.to_string()))?;
+        Ok(id)
     }
 
     /// Set a parent-child relationship between nodes
-    pub fn set_parent(&self, parent_id: Id, child_id: Id) {
-        self.sender
-            .send(Command::SetParent(parent_id, child_id))
-            .expect("data thread down");
+    pub fn set_parent(&self, parent_id: Id, child_id: Id) -> Result<(), EngineError> {
+        self.send(Command::SetParent(parent_id, child_id))
     }
 
     /// Set an attribute on a node
-    pub fn set_attribute(&self, node_id: Id, key: String, value: String) {
-        self.sender
-            .send(Command::SetAttribute(node_id, key, value))
-            .expect("data thread down");
+    pub fn set_attribute(
+        &self,
+        node_id: Id,
+        key: String,
+        value: String,
+    ) -> Result<(), EngineError> {
+        self.send(Command::SetAttribute(node_id, key, value))
+    }
+
+    /// Let the user drag a node with the pointer, as with the `draggable="true"`
+    /// attribute, or stop letting them. Drags are reported to [`Params::on_drag`].
+    pub fn set_draggable(&self, id: Id, draggable: bool) -> Result<(), EngineError> {
+        self.set_attribute(id, "draggable".to_owned(), draggable.to_string())
+    }
+
+    /// Deep-copy a node and its descendants, with their text and attributes, in one command
+    ///
+    /// The copy is detached; attach it with [`Engine::set_parent`]. Its nodes get ids
+    /// allocated by the engine, counting down from `u64::MAX` and skipping ids in use, so
+    /// they don't collide with ids picked by the caller counting up. Returns `None` if the
+    /// node does not exist.
+    pub fn clone_subtree(&self, id: Id) -> Result<Option<Id>, EngineError> {
+        self.query(|reply| Command::CloneSubtree(id, reply))
+    }
+
+    /// Record several document mutations and apply them as one batch.
+    ///
+    /// Everything issued on the [`Transaction`] inside `f` is sent to the command thread
+    /// as a single command and laid out once, which is much cheaper than issuing the
+    /// same mutations one by one when building large trees.
+    pub fn transaction<F, R>(&self, f: F) -> Result<R, EngineError>
+    where
+        F: FnOnce(&mut Transaction) -> R,
+    {
+        let mut tx = Transaction::new(self.next_stylesheet_id.clone());
+        let result = f(&mut tx);
+        if !tx.is_empty() {
+            self.send(Command::Batch(tx.into_commands()))?;
+        }
+        Ok(result)
+    }
+
+    /// Save the document (nodes, attributes, text, debug names) and its stylesheets
+    /// to a JSON file, once every command sent so far has been applied.
+    pub fn save_document(&self, path: impl AsRef<Path>) -> Result<(), DocumentError> {
+        let (reply, saved) = channel();
+        self.send(Command::SaveDocument(reply))?;
+        let saved = saved.recv().map_err(|_| EngineError::CommandThreadDown)?;
+        fs::write(path, saved.to_json()?)?;
+        Ok(())
+    }
+
+    /// Replace the document and all stylesheets with those saved by
+    /// [`Engine::save_document`], returning handles to the loaded stylesheets.
+    pub fn load_document(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<StylesheetId>, DocumentError> {
+        let saved = SavedDocument::from_json(&fs::read(path)?)?;
+        let limits = self.limits();
+        if saved.nodes.len() > limits.max_nodes {
+            let limit = limits.max_nodes;
+            return Err(EngineError::LimitExceeded(LimitError::TooManyNodes { limit }).into());
+        }
+        for stylesheet in &saved.stylesheets {
+            self.check_stylesheet(&stylesheet.css)?;
+        }
+        let ids: Vec<_> = saved
+            .stylesheets
+            .iter()
+            .map(|_| StylesheetId(self.next_stylesheet_id.fetch_add(1, Ordering::Relaxed)))
+            .collect();
+        self.send(Command::LoadDocument(saved, ids.clone()))?;
+        Ok(ids)
+    }
+
+    /// Save the nodes of the document (tree, attributes, text, debug names) as they are
+    /// once every command sent so far has been applied, to go back to with
+    /// [`Engine::rollback`].
+    ///
+    /// The state is kept on the command thread until [`Engine::discard_checkpoint`], and
+    /// can be rolled back to any number of times. Stylesheets aren't part of it.
+    ///
+    /// Each checkpoint holds a full copy of the nodes, which doesn't count against
+    /// [`Limits`]; callers must discard the checkpoints they no longer need.
+    pub fn checkpoint(&self) -> Result<CheckpointId, EngineError> {
+        let id = CheckpointId(self.next_checkpoint_id.fetch_add(1, Ordering::Relaxed));
+        self.send(Command::Checkpoint(id))?;
+        Ok(id)
+    }
+
+    /// Put the nodes of the document back as saved by [`Engine::checkpoint`], dropping
+    /// the nodes created since.
+    ///
+    /// Nodes that existed at both points keep their scroll offset, embedded document,
+    /// measure function and resize observers. Rolling back to an unknown or discarded
+    /// checkpoint is logged and ignored.
+    pub fn rollback(&self, checkpoint: CheckpointId) -> Result<(), EngineError> {
+        self.send(Command::Rollback(checkpoint))
+    }
+
+    /// Free the state saved by [`Engine::checkpoint`] once it is no longer needed.
+    pub fn discard_checkpoint(&self, checkpoint: CheckpointId) -> Result<(), EngineError> {
+        self.send(Command::DiscardCheckpoint(checkpoint))
+    }
+
+    /// Register a function reporting the intrinsic content size of a leaf node.
+    ///
+    /// Used by flex layout for nodes without children or text, instead of the default
+    /// size, so embedders drawing custom content (charts, images) can size it. The
+    /// constraints carry the content-box width and height already fixed by the node's
+    /// style; explicit sizes always take precedence over the measured ones. The function
+    /// runs on the engine's command thread during layout.
+    pub fn set_measure<F>(&self, id: Id, measure: F) -> Result<(), EngineError>
+    where
+        F: Fn(MeasureConstraints) -> Size + Send + 'static,
+    {
+        self.send(Command::SetMeasure(id, Some(Box::new(measure))))
+    }
+
+    /// Remove the measure function registered with [`Engine::set_measure`]
+    pub fn clear_measure(&self, id: Id) -> Result<(), EngineError> {
+        self.send(Command::SetMeasure(id, None))
+    }
+
+    /// Show the document of another handle in the content box of a node, like an iframe,
+    /// replacing any document shown there before.
+    ///
+    /// The document keeps its own stylesheets, and its styles don't cascade from this
+    /// one. It is laid out with the node's content box as its viewport, painted over the
+    /// node's children clipped to the content box, and hit-tested there by
+    /// [`Engine::hit_test`]. A document shown in several nodes takes the size of the last
+    /// one. Use [`Engine::create_document`] for documents without a window of their own.
+    pub fn set_embedded_document(&self, id: Id, document: &Engine) -> Result<(), EngineError> {
+        self.send(Command::SetEmbeddedDocument(
+            id,
+            Some(document.window.embedded()),
+        ))
+    }
+
+    /// Stop showing the document set with [`Engine::set_embedded_document`] in a node
+    pub fn clear_embedded_document(&self, id: Id) -> Result<(), EngineError> {
+        self.send(Command::SetEmbeddedDocument(id, None))
+    }
+
+    /// Name a node for debugging; the name shows up in [`Engine::dump_render_tree`]
+    /// and in error messages about the node.
+    pub fn set_debug_name(&self, id: Id, name: &str) -> Result<(), EngineError> {
+        self.send(Command::SetDebugName(id, name.to_string()))
+    }
+
+    /// Render the latest published snapshot as indented text, for debugging
+    pub fn dump_render_tree(&self) -> Option<String> {
+        self.latest_snapshot().map(|root| root.dump())
+    }
+
+    /// Block until every command sent so far has been applied and laid out, and the
+    /// published snapshot reflects it.
+    pub fn flush(&self) -> Result<(), EngineError> {
+        self.flush_async()?
+            .recv()
+            .map_err(|_| EngineError::CommandThreadDown)
+    }
+
+    /// Like [`Engine::flush`], but returns immediately with a receiver that gets a
+    /// message once the snapshot is up to date.
+    pub fn flush_async(&self) -> Result<Receiver<()>, EngineError> {
+        let (done, signal) = channel();
+        self.send(Command::Flush(done))?;
+        Ok(signal)
+    }
+
+    /// Register a callback fired whenever the laid-out size of a node changes.
+    ///
+    /// The callback runs on the engine's command thread when a new snapshot is published,
+    /// and receives the node id with its new border-box width and height. It also fires
+    /// once for the first layout after registration.
+    pub fn observe_resize<F>(&self, id: Id, callback: F) -> Result<(), EngineError>
+    where
+        F: Fn(Id, f64, f64) + Send + 'static,
+    {
+        self.send(Command::ObserveResize(id, Box::new(callback)))
+    }
+
+    /// Remove all resize callbacks registered for a node
+    pub fn unobserve_resize(&self, id: Id) -> Result<(), EngineError> {
+        self.send(Command::UnobserveResize(id))
+    }
+
+    /// Run a callback once on the engine's command thread when it has no commands pending,
+    /// like `requestIdleCallback`
+    ///
+    /// The callback receives a deadline: the instant by which it should return so layout,
+    /// animations and new commands aren't delayed. Work that takes longer can be split
+    /// by registering another idle callback. Mutations issued from the callback are
+    /// applied after it returns; it must not wait on queries such as
+    /// [`Engine::children`] or [`Engine::flush`], which would deadlock.
+    pub fn on_idle<F>(&self, callback: F) -> Result<(), EngineError>
+    where
+        F: FnOnce(Instant) + Send + 'static,
+    {
+        self.send(Command::OnIdle(Box::new(callback)))
+    }
+
+    /// Limit the size of this window's document, for documents from untrusted sources.
+    ///
+    /// Stylesheets over the size limit are rejected right away with
+    /// [`EngineError::LimitExceeded`]. Node creation, reparenting, [`Engine::clone_subtree`]
+    /// and [`Engine::load_document`] are checked when the command thread applies them, as
+    /// are stylesheets added in a [`Transaction`]; those rejections are reported to the
+    /// callback set with [`Engine::on_limit_exceeded`], or logged. The document as it is
+    /// when the limits are set is left alone, even if it already exceeds them.
+    pub fn set_limits(&self, limits: Limits) -> Result<(), EngineError> {
+        *self.window.limits.lock().unwrap() = limits;
+        self.send(Command::SetLimits(limits))
+    }
+
+    /// Get the limits set with [`Engine::set_limits`]
+    pub fn limits(&self) -> Limits {
+        *self.window.limits.lock().unwrap()
+    }
+
+    /// Call `callback` on the command thread with each mutation rejected for exceeding
+    /// the limits, instead of logging it.
+    pub fn on_limit_exceeded<F>(&self, callback: F) -> Result<(), EngineError>
+    where
+        F: FnMut(LimitError) + Send + 'static,
+    {
+        self.send(Command::OnLimitExceeded(Box::new(callback)))
+    }
+
+    /// Call `callback` on the command thread with everything the stylesheets added from
+    /// now on drop or only partly honor, and the stylesheet it was found in, instead of
+    /// logging it.
+    ///
+    /// Lets embedders show CSS problems in their own developer tools;
+    /// [`audit_stylesheet`] reports the same for a stylesheet that isn't loaded.
+    pub fn on_css_diagnostic<F>(&self, callback: F) -> Result<(), EngineError>
+    where
+        F: FnMut(StylesheetId, &CssDiagnostic) + Send + 'static,
+    {
+        self.send(Command::OnCssDiagnostic(Box::new(callback)))
+    }
+
+    /// Load the stylesheets of `@import` rules in stylesheets added from now on with
+    /// `resolver`, which is called on the command thread with the URL as written and
+    /// returns the CSS it refers to, or `None` if there is none.
+    ///
+    /// Imported rules come before those of the importing stylesheet in the cascade, and
+    /// share its handle, origin and scope. Imports that can't be resolved, including
+    /// all of them while no resolver is set, are reported to
+    /// [`Engine::on_css_diagnostic`] and skipped.
+    pub fn set_import_resolver<F>(&self, resolver: F) -> Result<(), EngineError>
+    where
+        F: FnMut(&str) -> Option<String> + Send + 'static,
+    {
+        self.send(Command::SetImportResolver(Box::new(resolver)))
+    }
+
+    /// Load the fonts of `@font-face` rules in stylesheets added from now on with
+    /// `loader`, which is called on the command thread with each rule's descriptors and
+    /// returns the contents of a font file for it, e.g. from the first of its sources
+    /// it can fetch, or `None` to leave the family to the installed fonts.
+    ///
+    /// Text with the family in its `font-family` is then drawn with the loaded font.
+    /// Weights and styles aren't matched yet: of the faces loaded for a family, the one
+    /// closest to a normal weight and style is used.
+    pub fn set_font_loader<F>(&self, loader: F) -> Result<(), EngineError>
+    where
+        F: FnMut(&FontFace) -> Option<Vec<u8>> + Send + 'static,
+    {
+        self.send(Command::SetFontLoader(Box::new(loader)))
+    }
+
+    fn check_stylesheet(&self, css: &str) -> Result<(), EngineError> {
+        self.limits()
+            .check_stylesheet(css)
+            .map_err(EngineError::LimitExceeded)
+    }
+
+    /// Animate a style property of a node from one value to another.
+    ///
+    /// The animation runs on the engine's frame ticker. While it runs, the animated value
+    /// takes precedence over stylesheet rules for that property; once it finishes or is
+    /// cancelled, the property goes back to its stylesheet-driven value.
+    pub fn animate(
+        &self,
+        id: Id,
+        property: AnimatedProperty,
+        from: AnimationValue,
+        to: AnimationValue,
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<AnimationHandle, EngineError> {
+        let animation_id = self.next_animation_id.fetch_add(1, Ordering::Relaxed);
+        self.send(Command::StartAnimation(AnimationSpec {
+            id: animation_id,
+            node: id,
+            property,
+            from,
+            to,
+            duration,
+            easing,
+        }))?;
+        Ok(AnimationHandle::new(
+            animation_id,
+            self.window.sender.clone(),
+        ))
+    }
+
+    /// Set the size the root node is laid out at, which `vw` and `vh` units refer to,
+    /// divided by the zoom factor.
+    ///
+    /// [`Engine::run`] keeps the viewport in sync with the window's inner size; the last
+    /// resize or call wins.
+    pub fn set_viewport(&self, width: f64, height: f64) -> Result<(), EngineError> {
+        self.send(Command::SetViewport(width, height))
+    }
+
+    /// Zoom the page by a uniform factor, clamped to 0.25–5
+    ///
+    /// Unlike DPI scaling this changes layout: the document is laid out in a viewport
+    /// shrunk by the factor, then painted scaled up, so lengths and text grow and content
+    /// reflows to fit. [`WindowOptions::zoom_shortcuts`] binds Ctrl+= / Ctrl+- / Ctrl+0.
+    pub fn set_zoom(&self, zoom: f64) -> Result<(), EngineError> {
+        let zoom = zoom::clamp_zoom(zoom);
+        *self.window.zoom.lock().unwrap() = zoom;
+        self.send(Command::SetZoom(zoom))
+    }
+
+    /// Set the color scheme `prefers-color-scheme` media queries match against.
+    ///
+    /// [`Engine::run`] follows the system theme and the window's theme changes; the last
+    /// change or call wins.
+    pub fn set_color_scheme(&self, color_scheme: ColorScheme) -> Result<(), EngineError> {
+        self.send(Command::SetColorScheme(color_scheme))
+    }
+
+    /// Set whether the user prefers reduced motion, which `prefers-reduced-motion` media
+    /// queries match against and animations follow, see [`Engine::set_reduced_motion`].
+    ///
+    /// The window system doesn't report this setting, so pass on the operating system's
+    /// accessibility preference.
+    pub fn set_motion_preference(&self, preference: MotionPreference) -> Result<(), EngineError> {
+        self.send(Command::SetMotionPreference(preference))
+    }
+
+    /// Choose what animations and smooth scrolls started from now on do while the user
+    /// prefers reduced motion; they are skipped by default.
+    pub fn set_reduced_motion(&self, handling: ReducedMotion) -> Result<(), EngineError> {
+        self.send(Command::SetReducedMotion(handling))
+    }
+
+    /// Change what layout assumes about this window's nodes that it sizes with the text
+    /// heuristic: nodes created with text, and leaves it has no other way to measure.
+    pub fn set_text_node_defaults(&self, defaults: TextNodeDefaults) -> Result<(), EngineError> {
+        self.send(Command::SetTextNodeDefaults(defaults))
+    }
+
+    /// Set this window's locale: the direction of the root when the stylesheet doesn't
+    /// set one, and the fonts tried for text the default font has no glyphs for.
+    ///
+    /// Windows start with the locale of the process environment, or `en-US`.
+    pub fn set_locale(&self, locale: Locale) -> Result<(), EngineError> {
+        self.send(Command::SetLocale(locale))
+    }
+
+    /// Choose how snapshots published after layout wait for the window to draw them.
+    ///
+    /// The default, [`FramePolicy::LatestWins`], always draws the newest layout.
+    /// [`FramePolicy::Bounded`] draws each layout in turn, dropping the oldest when the
+    /// window falls behind, so memory stays bounded either way.
+    pub fn set_frame_policy(&self, policy: FramePolicy) {
+        self.window.snapshots.lock().unwrap().set_policy(policy);
+    }
+
+    /// Get the page zoom factor set with [`Engine::set_zoom`]
+    pub fn zoom(&self) -> f64 {
+        *self.window.zoom.lock().unwrap()
+    }
+
+    /// Run without a window, laying out against a virtual viewport of the given size.
+    ///
+    /// The command thread keeps publishing snapshots as the document changes, which can be
+    /// inspected with [`Engine::node_bounds`]. No windowing system or GPU is required.
+    /// Blocks until [`Engine::request_exit`] is called.
+    pub fn run_headless(&self, width: f64, height: f64) -> Result<(), Error> {
+        // only allow running once
+        let _lock = self.running.try_lock().map_err(|_| Error::AlreadyRunning)?;
+
+        let (exited, condvar) = &*self.exit_signal;
+        let mut exited = exited.lock().unwrap();
+        if *exited {
+            return Ok(());
+        }
+
+        self.set_viewport(width, height)
+            .map_err(|err| Error::UnknownError(err.to_string()))?;
+
+        while !*exited {
+            exited = condvar.wait(exited).unwrap();
+        }
+
+        Ok(())
+    }
+
+    /// Stop the engine: exit the event loop and shut down the command threads of all windows.
+    ///
+    /// Commands sent before this call are still applied. [`Engine::run`] returns once the
+    /// event loop has exited, and later mutation calls fail with
+    /// [`EngineError::CommandThreadDown`]. Calling this before `run` makes `run` return
+    /// immediately.
+    pub fn request_exit(&self) -> Result<(), EngineError> {
+        let (exited, condvar) = &*self.exit_signal;
+        *exited.lock().unwrap() = true;
+        condvar.notify_all();
+        self.message_sender.send(WindowMessage::Exit);
+        for window in self.windows.lock().unwrap().iter() {
+            if window.id != self.window.id {
+                // Other windows may have shut down already.
+                let _ = window.sender.send(Command::Shutdown);
+            }
+        }
+        self.send(Command::Shutdown)
     }
 
     /// Get the root node ID of the document
@@ -147,12 +1133,288 @@ impl Engine {
         self.root_id
     }
 
-    /// Get a cloned copy of the current render snapshot for drawing
-    fn get_current_snapshot(&self) -> Option<RenderNode> {
-        self.snapshot.read().unwrap().as_ref().cloned()
+    /// Get the children of a node in order, or `None` if the node does not exist.
+    ///
+    /// Like the other queries, waits until every command sent so far has been applied,
+    /// but not for layout.
+    pub fn children(&self, id: Id) -> Result<Option<Vec<Id>>, EngineError> {
+        self.query(|reply| Command::GetChildren(id, reply))
+    }
+
+    /// Get the parent of a node, or `None` for the root and detached or unknown nodes.
+    pub fn parent(&self, id: Id) -> Result<Option<Id>, EngineError> {
+        self.query(|reply| Command::GetParent(id, reply))
+    }
+
+    /// Find the first node in the tree matching a selector such as `.item`, in document order.
+    pub fn query_selector(&self, selector: &str) -> Result<Option<Id>, EngineError> {
+        Ok(self.query_selector_all(selector)?.into_iter().next())
+    }
+
+    /// Find every node in the tree matching a selector, or any selector of a
+    /// comma-separated list, in document order.
+    pub fn query_selector_all(&self, selector: &str) -> Result<Vec<Id>, EngineError> {
+        let selectors =
+            css_parser::parse_selector(selector).map_err(|_| EngineError::InvalidSelector)?;
+        self.query(|reply| Command::QuerySelector(selectors, reply))
+    }
+
+    /// Get node, stylesheet and layout counters, once every command sent so far has been
+    /// applied; useful to detect nodes that are created but never removed.
+    pub fn stats(&self) -> Result<EngineStats, EngineError> {
+        self.query(Command::GetStats)
+    }
+
+    /// Send a command carrying a reply channel and wait for the answer.
+    fn query<T>(&self, command: impl FnOnce(Sender<T>) -> Command) -> Result<T, EngineError> {
+        let (reply, answer) = channel();
+        self.send(command(reply))?;
+        answer.recv().map_err(|_| EngineError::CommandThreadDown)
+    }
+
+    /// Queue a command for the background command thread
+    fn send(&self, command: Command) -> Result<(), EngineError> {
+        self.window
+            .sender
+            .send(command)
+            .map_err(|_| EngineError::CommandThreadDown)
+    }
+
+    /// Get the laid-out border-box bounds of a node from the latest published snapshot
+    pub fn node_bounds(&self, id: Id) -> Option<Rect> {
+        self.latest_snapshot()?.find(id).map(|node| node.bounds)
+    }
+
+    /// Hit-test the latest published snapshot at a position in window pixels, as passed
+    /// to [`Params::on_click`]
+    ///
+    /// Returns the nodes under the position, topmost first and up to the root, each with
+    /// the position in its content box in CSS pixels and the part of its box that was hit.
+    pub fn hit_test(&self, x: f64, y: f64) -> Vec<Hit> {
+        let zoom = self.zoom();
+        self.latest_snapshot()
+            .map(|root| root.hit_test(x / zoom, y / zoom))
+            .unwrap_or_default()
+    }
+
+    /// Convert a position in window pixels, as passed to [`Params::on_click`], to CSS
+    /// pixels relative to the top-left corner of a node's content box, as in [`Hit`].
+    ///
+    /// Accounts for the page zoom and the scroll offsets of the scroll containers above
+    /// the node in the latest published snapshot. Returns `None` for unknown nodes.
+    pub fn client_to_node(&self, id: Id, x: f64, y: f64) -> Option<(f64, f64)> {
+        let zoom = self.zoom();
+        let snapshot = self.latest_snapshot()?;
+        let (node, shift) = snapshot.find_scrolled(id)?;
+        let content_box = node.content_box();
+        Some((
+            x / zoom + shift.x - content_box.x,
+            y / zoom + shift.y - content_box.y,
+        ))
+    }
+
+    /// Convert a position relative to a node's content box to window pixels; the
+    /// inverse of [`Engine::client_to_node`].
+    pub fn node_to_client(&self, id: Id, x: f64, y: f64) -> Option<(f64, f64)> {
+        let zoom = self.zoom();
+        let snapshot = self.latest_snapshot()?;
+        let (node, shift) = snapshot.find_scrolled(id)?;
+        let content_box = node.content_box();
+        Some((
+            (content_box.x + x - shift.x) * zoom,
+            (content_box.y + y - shift.y) * zoom,
+        ))
+    }
+
+    /// Get the border box of a node in window pixels, where it is painted in the latest
+    /// published snapshot once scrolled and zoomed, e.g. to place a popup next to it
+    pub fn client_bounds(&self, id: Id) -> Option<Rect> {
+        let zoom = self.zoom();
+        let snapshot = self.latest_snapshot()?;
+        let (node, shift) = snapshot.find_scrolled(id)?;
+        Some(Rect::new(
+            (node.bounds.x - shift.x) * zoom,
+            (node.bounds.y - shift.y) * zoom,
+            node.bounds.width * zoom,
+            node.bounds.height * zoom,
+        ))
+    }
+
+    /// Get the computed style of a node from the latest published snapshot
+    pub fn computed_style(&self, id: Id) -> Option<ComputedStyle> {
+        self.latest_snapshot()?
+            .find(id)
+            .map(|node| ComputedStyle::from_style(&node.style))
+    }
+
+    /// Find where the stylesheet declarations that apply to a node were declared, once
+    /// every command sent so far has been applied, lowest priority first: for each
+    /// property, the last declaration setting it gives the cascaded value.
+    ///
+    /// Declarations that lose to later ones for every property they set are marked
+    /// [`StyleSource::overridden`], to debug why a property doesn't apply.
+    ///
+    /// Returns `None` if the node doesn't exist. Declarations of compiled stylesheets
+    /// are included; styles set on the node directly are not.
+    pub fn style_sources(&self, id: Id) -> Result<Option<Vec<StyleSource>>, EngineError> {
+        self.query(|reply| Command::GetStyleSources(id, reply))
+    }
+
+    /// Scroll the content of a node to the given offset, clamped to its overflowing content.
+    ///
+    /// Animates when the node has `scroll-behavior: smooth`; wheel input interrupts the animation.
+    pub fn scroll_to(&self, id: Id, x: f64, y: f64) -> Result<(), EngineError> {
+        self.send(Command::ScrollTo(id, x, y))
+    }
+
+    /// Scroll the nearest scrollable ancestor of a node so that the node is aligned with its
+    /// top-left corner, like jumping to an anchor; outer scroll containers are scrolled
+    /// the same way to reveal the inner one.
+    pub fn scroll_into_view(&self, id: Id) -> Result<(), EngineError> {
+        self.send(Command::ScrollIntoView(id))
+    }
+
+    /// Get the scroll offset of a node from the latest published snapshot
+    pub fn scroll_offset(&self, id: Id) -> Option<ScrollOffset> {
+        self.latest_snapshot()?
+            .find(id)
+            .map(|node| node.scroll_offset)
+    }
+
+    /// Register a callback invoked once per rendered frame, before it is drawn.
+    ///
+    /// The callback receives the frame timestamp (time since the first frame with a
+    /// registered callback) and the time elapsed since the previous frame. While any
+    /// callback is registered the window redraws continuously. Mutations made through
+    /// [`Engine::transaction`] are laid out in time for the next frame.
+    pub fn on_frame<F>(&self, callback: F) -> FrameCallbackId
+    where
+        F: FnMut(Duration, Duration) + Send + 'static,
+    {
+        let id = self.window.frames.lock().unwrap().add(Box::new(callback));
+        self.message_sender.send(WindowMessage::Redraw);
+        id
+    }
+
+    /// Unregister a callback added with [`Engine::on_frame`]
+    pub fn cancel_frame_callback(&self, id: FrameCallbackId) {
+        self.window.frames.lock().unwrap().remove(id);
+    }
+
+    /// Register a callback receiving the timings of every frame rendered in this window,
+    /// replacing any previous one
+    ///
+    /// Runs on the windowing thread after each frame is presented, so keep it short.
+    #[cfg(feature = "windowing")]
+    pub fn on_frame_stats<F>(&self, callback: F)
+    where
+        F: FnMut(&FrameStats) + Send + 'static,
+    {
+        self.window
+            .frame_stats
+            .lock()
+            .unwrap()
+            .set_callback(Some(Box::new(callback)));
+    }
+
+    /// Remove the callback registered with [`Engine::on_frame_stats`]
+    #[cfg(feature = "windowing")]
+    pub fn clear_frame_stats(&self) {
+        self.window.frame_stats.lock().unwrap().set_callback(None);
+    }
+
+    /// Register a callback receiving, for every frame painted in this window, the areas
+    /// and nodes that changed since the previous one, replacing any previous callback
+    ///
+    /// Lets embedders compositing the window into their own surfaces update only what
+    /// changed. The first frame after registering reports the whole window. Runs on the
+    /// windowing thread after each frame is painted, so keep it short.
+    #[cfg(feature = "windowing")]
+    pub fn on_damage<F>(&self, callback: F)
+    where
+        F: FnMut(&Damage) + Send + 'static,
+    {
+        self.window
+            .damage
+            .lock()
+            .unwrap()
+            .set_callback(Some(Box::new(callback)));
+    }
+
+    /// Remove the callback registered with [`Engine::on_damage`]
+    #[cfg(feature = "windowing")]
+    pub fn clear_damage_callback(&self) {
+        self.window.damage.lock().unwrap().set_callback(None);
+    }
+
+    /// Tint the areas repainted in each frame of this window, to check that changes
+    /// only repaint what they affect
+    ///
+    /// Successive repaints cycle through a few colors. A tint stays until the next
+    /// frame is painted; the first frame after turning this on tints the whole window.
+    #[cfg(feature = "windowing")]
+    pub fn set_paint_flashing(&self, enabled: bool) {
+        self.window
+            .damage
+            .lock()
+            .unwrap()
+            .set_paint_flashing(enabled);
+        self.message_sender.send(WindowMessage::Redraw);
+    }
+
+    #[cfg(feature = "windowing")]
+    fn report_frame_stats(&self, render: Duration, missed_vsyncs: u32, gpu_cache: GpuCacheUsage) {
+        let layout = std::mem::take(&mut *self.window.layout_time.lock().unwrap());
+        let (stats, callback) = self.window.frame_stats.lock().unwrap().finish_frame(
+            layout,
+            render,
+            missed_vsyncs,
+            gpu_cache,
+        );
+        // Run without holding the recorder lock, so the callback can replace itself.
+        if let Some(callback) = callback {
+            (callback.lock().unwrap())(&stats);
+        }
+    }
+
+    /// Run the frame callbacks and keep frames coming while any are registered.
+    #[cfg(feature = "windowing")]
+    fn run_frame_callbacks(&self) {
+        let (mut callbacks, timestamp, delta) = {
+            let mut frames = self.window.frames.lock().unwrap();
+            if frames.is_empty() {
+                return;
+            }
+            frames.begin_frame(Instant::now())
+        };
+
+        // Run without holding the lock, so callbacks can register or cancel callbacks.
+        for (_, callback) in callbacks.iter_mut() {
+            callback(timestamp, delta);
+        }
+
+        let mut frames = self.window.frames.lock().unwrap();
+        frames.finish_frame(callbacks);
+        if !frames.is_empty() {
+            self.message_sender.send(WindowMessage::Redraw);
+        }
+    }
+
+    /// The most recently published snapshot, drawn or not
+    fn latest_snapshot(&self) -> Option<Arc<RenderNode>> {
+        self.window.snapshots.lock().unwrap().latest()
+    }
+
+    /// The snapshot on screen, for hit-testing input
+    #[cfg(feature = "windowing")]
+    fn displayed_snapshot(&self) -> Option<Arc<RenderNode>> {
+        self.window.snapshots.lock().unwrap().displayed()
     }
 }
 
+#[cfg(test)]
+mod engine_tests;
+
 impl Default for Engine {
     fn default() -> Self {
         Self::new()
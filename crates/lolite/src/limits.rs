@@ -0,0 +1,121 @@
+use crate::css_parser::parse_css_with_diagnostics;
+use crate::style::Selector;
+use std::fmt;
+
+/// Caps on the size of a window's document, so that an untrusted document can't exhaust
+/// memory or stall layout. Set with [`crate::Engine::set_limits`].
+///
+/// The default places no limits; [`Limits::untrusted`] suits documents from sources
+/// that aren't trusted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Limits {
+    /// Most nodes the document may hold, including the root and detached nodes.
+    pub max_nodes: usize,
+    /// Deepest a node may be nested; children of the root are at depth 1.
+    pub max_tree_depth: usize,
+    /// Largest stylesheet source accepted, in bytes.
+    pub max_stylesheet_bytes: usize,
+    /// Most compound selectors a selector may chain with combinators, such as the three
+    /// of `nav > ul li`; each one more can multiply the ancestors matching visits.
+    pub max_selector_complexity: usize,
+}
+
+impl Limits {
+    pub const fn unlimited() -> Self {
+        Self {
+            max_nodes: usize::MAX,
+            max_tree_depth: usize::MAX,
+            max_stylesheet_bytes: usize::MAX,
+            max_selector_complexity: usize::MAX,
+        }
+    }
+
+    /// Limits generous enough for real documents, while keeping layout fast and
+    /// well clear of the stack depth its recursion can handle.
+    pub const fn untrusted() -> Self {
+        Self {
+            max_nodes: 100_000,
+            max_tree_depth: 256,
+            max_stylesheet_bytes: 1024 * 1024,
+            max_selector_complexity: 16,
+        }
+    }
+
+    pub(crate) fn check_stylesheet(&self, css: &str) -> Result<(), LimitError> {
+        if css.len() > self.max_stylesheet_bytes {
+            return Err(LimitError::StylesheetTooLarge {
+                bytes: css.len(),
+                limit: self.max_stylesheet_bytes,
+            });
+        }
+        if self.max_selector_complexity == usize::MAX {
+            return Ok(());
+        }
+        // Problems with the stylesheet are reported once it is loaded.
+        let (sheet, _) = parse_css_with_diagnostics(css);
+        let selectors = sheet.rules.iter().flat_map(|rule| &rule.selectors);
+        match selectors.map(compound_selectors).max() {
+            Some(compounds) if compounds > self.max_selector_complexity => {
+                Err(LimitError::SelectorTooComplex {
+                    compounds,
+                    limit: self.max_selector_complexity,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Number of compound selectors chained by the combinators of `selector`.
+fn compound_selectors(selector: &Selector) -> usize {
+    match selector {
+        Selector::Descendant(ancestor, subject) | Selector::Child(ancestor, subject) => {
+            compound_selectors(ancestor) + compound_selectors(subject)
+        }
+        _ => 1,
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+/// A mutation rejected because it would exceed one of the [`Limits`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LimitError {
+    TooManyNodes { limit: usize },
+    TooDeep { limit: usize },
+    StylesheetTooLarge { bytes: usize, limit: usize },
+    SelectorTooComplex { compounds: usize, limit: usize },
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::TooManyNodes { limit } => {
+                write!(f, "document would exceed {} nodes", limit)
+            }
+            LimitError::TooDeep { limit } => {
+                write!(f, "tree would be nested deeper than {} levels", limit)
+            }
+            LimitError::StylesheetTooLarge { bytes, limit } => {
+                write!(
+                    f,
+                    "stylesheet is {} bytes, over the {} byte limit",
+                    bytes, limit
+                )
+            }
+            LimitError::SelectorTooComplex { compounds, limit } => {
+                write!(
+                    f,
+                    "selector chains {} compound selectors, over the limit of {}",
+                    compounds, limit
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LimitError {}
@@ -0,0 +1,184 @@
+use crate::style::TextDirection;
+
+/// A language with an optional script and region, as a BCP 47 tag like `en-US`,
+/// `ar-EG` or `zh-Hant-TW`.
+///
+/// Set for a window with [`crate::Engine::set_locale`], it gives the root its text
+/// direction when the stylesheet sets none, and the fonts tried when the default font
+/// lacks glyphs for some text. The `format_*` helpers format values the way the locale
+/// writes them, for apps building their text.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Locale {
+    /// Canonical tag: lowercase language, titlecase script, uppercase region.
+    tag: String,
+}
+
+impl Locale {
+    /// Parse a tag, also accepting POSIX locale names like `de_DE.UTF-8`. Subtags after
+    /// the region, like variants and extensions, are dropped.
+    pub fn new(tag: &str) -> Self {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default();
+        let mut subtags = tag.split(['-', '_']).filter(|subtag| !subtag.is_empty());
+        let language = subtags
+            .next()
+            .filter(|language| language.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(str::to_ascii_lowercase)
+            // `C` and `POSIX` locales
+            .filter(|language| language != "c" && language != "posix")
+            .unwrap_or_else(|| "en".to_string());
+        let mut canonical = vec![language];
+        for subtag in subtags.take(2) {
+            if subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+                let mut script = subtag.to_ascii_lowercase();
+                script[..1].make_ascii_uppercase();
+                canonical.push(script);
+            } else if subtag.len() == 2 || subtag.len() == 3 {
+                canonical.push(subtag.to_ascii_uppercase());
+                break;
+            } else {
+                break;
+            }
+        }
+        Self {
+            tag: canonical.join("-"),
+        }
+    }
+
+    /// The locale of the process environment, from `LC_ALL`, `LC_MESSAGES` or `LANG`.
+    pub fn from_environment() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map(|value| Self::new(&value))
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// The language subtag, e.g. `en`.
+    pub fn language(&self) -> &str {
+        self.tag.split('-').next().unwrap_or_default()
+    }
+
+    /// The script subtag, e.g. `Hant`, if the tag has one.
+    pub fn script(&self) -> Option<&str> {
+        self.tag.split('-').skip(1).find(|subtag| subtag.len() == 4)
+    }
+
+    /// The region subtag, e.g. `US`, if the tag has one.
+    pub fn region(&self) -> Option<&str> {
+        self.tag.split('-').skip(1).find(|subtag| subtag.len() != 4)
+    }
+
+    /// The direction text in the locale's script is written in.
+    pub fn direction(&self) -> TextDirection {
+        let rtl = match self.script() {
+            Some(script) => matches!(script, "Arab" | "Hebr" | "Syrc" | "Thaa" | "Nkoo"),
+            None => matches!(
+                self.language(),
+                "ar" | "he" | "iw" | "fa" | "ur" | "ps" | "yi" | "dv" | "ckb" | "sd" | "ug"
+            ),
+        };
+        if rtl {
+            TextDirection::Rtl
+        } else {
+            TextDirection::Ltr
+        }
+    }
+
+    /// Families to try, in order, for text the default font has no glyphs for: common
+    /// system fonts for the locale's script on macOS, Windows and Linux.
+    pub fn font_fallbacks(&self) -> &'static [&'static str] {
+        let traditional = self.script() == Some("Hant")
+            || (self.script().is_none() && matches!(self.region(), Some("TW" | "HK" | "MO")));
+        match self.language() {
+            "ja" => &["Hiragino Sans", "Yu Gothic", "Noto Sans CJK JP"],
+            "zh" if traditional => &["PingFang TC", "Microsoft JhengHei", "Noto Sans CJK TC"],
+            "zh" => &["PingFang SC", "Microsoft YaHei", "Noto Sans CJK SC"],
+            "ko" => &["Apple SD Gothic Neo", "Malgun Gothic", "Noto Sans CJK KR"],
+            "ar" | "fa" | "ur" | "ps" | "ckb" | "sd" | "ug" => {
+                &["Geeza Pro", "Segoe UI", "Noto Sans Arabic"]
+            }
+            "he" | "iw" | "yi" => &["Arial Hebrew", "Segoe UI", "Noto Sans Hebrew"],
+            "th" => &["Thonburi", "Leelawadee UI", "Noto Sans Thai"],
+            "hi" | "mr" | "ne" => &["Kohinoor Devanagari", "Nirmala UI", "Noto Sans Devanagari"],
+            _ => &[],
+        }
+    }
+
+    /// Format a number rounded to `fraction_digits` decimals, with the locale's digit
+    /// grouping and decimal separator, e.g. `1,234.5` in `en` and `1.234,5` in `de`.
+    pub fn format_number(&self, value: f64, fraction_digits: usize) -> String {
+        if value.is_nan() {
+            return "NaN".to_string();
+        }
+        let sign = if value.is_sign_negative() && value != 0.0 {
+            "-"
+        } else {
+            ""
+        };
+        if value.is_infinite() {
+            return format!("{}∞", sign);
+        }
+        let (group, decimal) = self.number_separators();
+        let digits = format!("{:.*}", fraction_digits, value.abs());
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+        let mut out = sign.to_string();
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0 && (integer.len() - index) % 3 == 0 {
+                out.push_str(group);
+            }
+            out.push(digit);
+        }
+        if !fraction.is_empty() {
+            out.push_str(decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    /// Format a calendar date numerically, in the locale's order and separators, e.g.
+    /// `3/14/2025` in `en-US`, `14.03.2025` in `de` and `2025/03/14` in `ja`.
+    pub fn format_date(&self, year: i32, month: u32, day: u32) -> String {
+        match (self.language(), self.region()) {
+            ("en", None | Some("US")) => format!("{}/{}/{}", month, day, year),
+            ("en", Some("CA")) | ("sv" | "lt", _) => {
+                format!("{:04}-{:02}-{:02}", year, month, day)
+            }
+            ("en" | "fr" | "es" | "it" | "pt" | "el" | "ar" | "he" | "vi" | "id", _) => {
+                format!("{:02}/{:02}/{}", day, month, year)
+            }
+            ("de" | "ru" | "pl" | "cs" | "sk" | "fi" | "nb" | "da" | "tr" | "uk", _) => {
+                format!("{:02}.{:02}.{}", day, month, year)
+            }
+            ("nl", _) => format!("{:02}-{:02}-{}", day, month, year),
+            ("ja" | "zh", _) => format!("{}/{:02}/{:02}", year, month, day),
+            ("ko" | "hu", _) => format!("{}. {:02}. {:02}.", year, month, day),
+            _ => format!("{:04}-{:02}-{:02}", year, month, day),
+        }
+    }
+
+    /// Digit group and decimal separators.
+    fn number_separators(&self) -> (&'static str, &'static str) {
+        match (self.language(), self.region()) {
+            ("de", Some("CH" | "LI")) => ("’", "."),
+            ("de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el", _) => (".", ","),
+            ("fr", _) => ("\u{202F}", ","),
+            ("ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "hu" | "bg", _) => {
+                ("\u{A0}", ",")
+            }
+            _ => (",", "."),
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new("en-US")
+    }
+}
+
+#[cfg(test)]
+mod locale_tests;
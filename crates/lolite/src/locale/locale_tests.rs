@@ -0,0 +1,63 @@
+use super::*;
+
+#[test]
+fn test_parse_locale_tags() {
+    let locale = Locale::new("zh-hant-tw");
+    assert_eq!(locale.tag(), "zh-Hant-TW");
+    assert_eq!(locale.language(), "zh");
+    assert_eq!(locale.script(), Some("Hant"));
+    assert_eq!(locale.region(), Some("TW"));
+
+    // POSIX names
+    assert_eq!(Locale::new("de_DE.UTF-8").tag(), "de-DE");
+    assert_eq!(Locale::new("sr_RS@latin").tag(), "sr-RS");
+    assert_eq!(Locale::new("C").tag(), "en");
+
+    // Variants and extensions are dropped
+    assert_eq!(Locale::new("en-US-u-ca-gregory").tag(), "en-US");
+    assert_eq!(Locale::new("fr").region(), None);
+}
+
+#[test]
+fn test_locale_direction() {
+    assert_eq!(Locale::default().direction(), TextDirection::Ltr);
+    assert_eq!(Locale::new("ar-EG").direction(), TextDirection::Rtl);
+    assert_eq!(Locale::new("he").direction(), TextDirection::Rtl);
+
+    // The script decides over the language
+    assert_eq!(Locale::new("az-Arab").direction(), TextDirection::Rtl);
+    assert_eq!(Locale::new("ug-Latn").direction(), TextDirection::Ltr);
+}
+
+#[test]
+fn test_locale_font_fallbacks() {
+    assert!(Locale::default().font_fallbacks().is_empty());
+    assert_eq!(Locale::new("ja-JP").font_fallbacks()[0], "Hiragino Sans");
+    assert_eq!(Locale::new("zh-CN").font_fallbacks()[0], "PingFang SC");
+    assert_eq!(Locale::new("zh-TW").font_fallbacks()[0], "PingFang TC");
+    assert_eq!(Locale::new("zh-Hans-HK").font_fallbacks()[0], "PingFang SC");
+}
+
+#[test]
+fn test_format_number() {
+    assert_eq!(
+        Locale::default().format_number(1234567.891, 2),
+        "1,234,567.89"
+    );
+    assert_eq!(Locale::new("de").format_number(1234.5, 1), "1.234,5");
+    assert_eq!(Locale::new("de-CH").format_number(1234.5, 1), "1’234.5");
+    assert_eq!(Locale::new("fr").format_number(-1234.0, 0), "-1\u{202F}234");
+    assert_eq!(Locale::default().format_number(999.0, 0), "999");
+    assert_eq!(Locale::default().format_number(-0.0, 0), "0");
+    assert_eq!(Locale::default().format_number(f64::INFINITY, 0), "∞");
+}
+
+#[test]
+fn test_format_date() {
+    assert_eq!(Locale::default().format_date(2025, 3, 14), "3/14/2025");
+    assert_eq!(Locale::new("en-GB").format_date(2025, 3, 14), "14/03/2025");
+    assert_eq!(Locale::new("de-DE").format_date(2025, 3, 14), "14.03.2025");
+    assert_eq!(Locale::new("ja").format_date(2025, 3, 14), "2025/03/14");
+    assert_eq!(Locale::new("sv").format_date(2025, 3, 14), "2025-03-14");
+    assert_eq!(Locale::new("ko").format_date(2025, 3, 14), "2025. 03. 14.");
+}
@@ -1,17 +1,34 @@
 use crate::{
+    embed::{EmbeddedDocument, MAX_EMBED_DEPTH},
     layout::RenderNode,
-    style::{BorderStyle, Length, Rgba},
+    style::{BorderStyle, ContentVisibility, Length, Radius, Rgba, TextDirection},
     text::{FontSpec, SkiaTextMeasurer},
+    Id,
 };
 use skia_safe::{Canvas, Color, Color4f, Paint, RRect, Rect};
 
+/// Opacity of the drag image, so that drop targets show through it.
+const DRAG_IMAGE_OPACITY: f32 = 0.6;
+
 pub struct Painter<'a> {
     canvas: &'a Canvas,
+    /// Smooth the edges of backgrounds and borders by their pixel coverage.
+    anti_alias: bool,
+    /// Embedded documents the node being painted is nested in.
+    embed_depth: usize,
 }
 
 impl<'a> Painter<'a> {
     pub fn new(canvas: &'a Canvas) -> Self {
-        Self { canvas }
+        Self {
+            canvas,
+            anti_alias: true,
+            embed_depth: 0,
+        }
+    }
+
+    pub fn with_anti_alias(self, anti_alias: bool) -> Self {
+        Self { anti_alias, ..self }
     }
 
     pub fn paint(&mut self, root: &RenderNode) {
@@ -19,6 +36,33 @@ impl<'a> Painter<'a> {
         self.paint_node(root);
     }
 
+    /// Paint the node `source` of `root` again, translucent and moved by `dx` and `dy`,
+    /// as the image of a node being dragged.
+    pub fn paint_drag_image(&mut self, root: &RenderNode, source: Id, dx: f64, dy: f64) {
+        let Some((node, shift)) = root.find_scrolled(source) else {
+            return;
+        };
+        self.canvas.save_layer_alpha_f(None, DRAG_IMAGE_OPACITY);
+        self.canvas
+            .translate(((dx - shift.x) as f32, (dy - shift.y) as f32));
+        self.paint_node(node);
+        self.canvas.restore();
+    }
+
+    /// Tint areas of the canvas, for paint flashing.
+    pub fn paint_flash(&self, rects: &[crate::layout::Rect], color: Rgba) {
+        let paint = self.fill(color);
+        for rect in rects {
+            let rect = Rect::from_xywh(
+                rect.x as f32,
+                rect.y as f32,
+                rect.width as f32,
+                rect.height as f32,
+            );
+            self.canvas.draw_rect(rect, &paint);
+        }
+    }
+
     fn paint_node(&mut self, node: &RenderNode) {
         // Draw the node's background color if it has one
         let style = &node.style;
@@ -30,29 +74,20 @@ impl<'a> Painter<'a> {
             (node.bounds.y + node.bounds.height) as f32,
         );
 
+        let units = node.paint_units();
         let client_rrect = if style.border_radius.is_empty() {
             RRect::new_rect_xy(client_rect, 0.0, 0.0)
         } else {
-            let tl = style
-                .border_radius
-                .top_left
-                .as_ref()
-                .map(|r| (r.x.to_px() as f32, r.y.to_px() as f32));
-            let tr = style
-                .border_radius
-                .top_right
-                .as_ref()
-                .map(|r| (r.x.to_px() as f32, r.y.to_px() as f32));
-            let br = style
-                .border_radius
-                .bottom_right
-                .as_ref()
-                .map(|r| (r.x.to_px() as f32, r.y.to_px() as f32));
-            let bl = style
-                .border_radius
-                .bottom_left
-                .as_ref()
-                .map(|r| (r.x.to_px() as f32, r.y.to_px() as f32));
+            let radius = |r: &Radius| {
+                (
+                    units.inline_or_zero(r.x) as f32,
+                    units.block(r.y).unwrap_or(0.0) as f32,
+                )
+            };
+            let tl = style.border_radius.top_left.as_ref().map(radius);
+            let tr = style.border_radius.top_right.as_ref().map(radius);
+            let br = style.border_radius.bottom_right.as_ref().map(radius);
+            let bl = style.border_radius.bottom_left.as_ref().map(radius);
 
             RRect::new_rect_radii(
                 client_rect,
@@ -78,40 +113,11 @@ impl<'a> Painter<'a> {
         };
 
         if let Some(background_color) = &style.background_color {
-            let paint = Paint::new(background_color.to_color4f(), None);
-
-            self.canvas.draw_rrect(client_rrect, &paint);
+            self.canvas
+                .draw_rrect(client_rrect, &self.fill(*background_color));
         }
 
-        let border_is_hidden = matches!(
-            style.border_style.top,
-            Some(BorderStyle::None) | Some(BorderStyle::Hidden)
-        );
-
-        if !border_is_hidden {
-            let border_width = style.border_width.resolved();
-            let stroke_width_px = border_width
-                .top
-                .to_px()
-                .max(border_width.right.to_px())
-                .max(border_width.bottom.to_px())
-                .max(border_width.left.to_px());
-
-            if stroke_width_px > 0.0 {
-                let color = style.border_color.top.unwrap_or(Rgba {
-                    r: 0,
-                    g: 0,
-                    b: 0,
-                    a: 255,
-                });
-
-                let mut paint = Paint::new(color.to_color4f(), None);
-                paint.set_style(skia_safe::paint::Style::Stroke);
-                paint.set_stroke_width(stroke_width_px as f32);
-                paint.set_anti_alias(true);
-                self.canvas.draw_rrect(client_rrect, &paint);
-            }
-        }
+        self.paint_border(node, client_rect, client_rrect);
 
         // Draw the node's text if it has any
         if let Some(text) = &node.text {
@@ -125,35 +131,178 @@ impl<'a> Painter<'a> {
             let mut paint = Paint::new(text_color.to_color4f(), None);
             paint.set_anti_alias(true);
 
-            let padding = style.padding.resolved();
-            let x = (node.bounds.x + padding.left.to_px()) as f32;
+            // Usually shaped already, when layout measured the text
+            let shaped = SkiaTextMeasurer::shared().shape(text, &FontSpec::from_style(style));
+
+            // Right-to-left text starts at the right of the content box
+            let content_box = node.content_box();
+            let x = match style.direction.unwrap_or_default() {
+                TextDirection::Ltr => content_box.x,
+                TextDirection::Rtl => content_box.x + content_box.width - shaped.width,
+            } as f32;
+            let baseline_y = (content_box.y + shaped.ascent) as f32;
 
-            let font_spec = FontSpec::from_style(style);
-            let font = SkiaTextMeasurer::make_font(&font_spec);
-            let (_scale, metrics) = font.metrics();
-            let baseline_y =
-                (node.bounds.y + padding.top.to_px() + (-metrics.ascent as f64)) as f32;
+            if let Some(blob) = &shaped.blob {
+                self.canvas.draw_text_blob(blob, (x, baseline_y), &paint);
+            }
+        }
+        // Skip the contents of hidden nodes, and of `auto` ones outside the visible area
+        let skip_contents = match style.content_visibility.unwrap_or_default() {
+            ContentVisibility::Visible => false,
+            ContentVisibility::Auto => self
+                .canvas
+                .local_clip_bounds()
+                .is_some_and(|clip| !clip.intersects(client_rect)),
+            ContentVisibility::Hidden => true,
+        };
+        if skip_contents {
+            return;
+        }
 
-            self.canvas.draw_str(text, (x, baseline_y), &font, &paint);
+        // Recursively paint the children, shifted by the scroll offset
+        let scroll = node.scroll_offset;
+        let scrolled = scroll.x != 0.0 || scroll.y != 0.0;
+        let contained = style.contains_paint();
+        let overflow_clipped = style.is_scroll_container();
+        let clipped = contained || overflow_clipped;
+        if scrolled || clipped {
+            self.canvas.save();
+        }
+        // Rounded corners clip scroll containers too
+        if contained || (overflow_clipped && !style.border_radius.is_empty()) {
+            self.canvas.clip_rrect(client_rrect, None, true);
+        }
+        if overflow_clipped {
+            // Scroll containers clip to the padding box, inside the border
+            let padding_box = node.padding_box();
+            self.canvas.clip_rect(
+                Rect::from_xywh(
+                    padding_box.x as f32,
+                    padding_box.y as f32,
+                    padding_box.width as f32,
+                    padding_box.height as f32,
+                ),
+                None,
+                self.anti_alias,
+            );
+        }
+        if scrolled {
+            self.canvas.translate((-scroll.x as f32, -scroll.y as f32));
         }
-        // Recursively paint the children
         for child in &node.children {
             self.paint_node(child);
         }
+        if scrolled || clipped {
+            self.canvas.restore();
+        }
+
+        // An embedded document covers the children, clipped to the content box
+        if let Some(root) = node
+            .embedded
+            .as_ref()
+            .filter(|_| self.embed_depth < MAX_EMBED_DEPTH)
+            .and_then(EmbeddedDocument::latest_snapshot)
+        {
+            let content_box = node.content_box();
+            self.canvas.save();
+            self.canvas.clip_rect(
+                Rect::from_xywh(
+                    content_box.x as f32,
+                    content_box.y as f32,
+                    content_box.width as f32,
+                    content_box.height as f32,
+                ),
+                None,
+                self.anti_alias,
+            );
+            self.canvas
+                .translate((content_box.x as f32, content_box.y as f32));
+            self.embed_depth += 1;
+            self.paint_node(&root);
+            self.embed_depth -= 1;
+            self.canvas.restore();
+        }
     }
 }
 
-// Helper method to convert Length to pixels
-#[allow(unused)]
-trait ToPx {
-    fn to_px(&self) -> f64;
-}
+impl Painter<'_> {
+    /// Paint for filling with a solid color.
+    fn fill(&self, color: Rgba) -> Paint {
+        let mut paint = Paint::new(color.to_color4f(), None);
+        paint.set_anti_alias(self.anti_alias);
+        paint
+    }
+
+    fn paint_border(&mut self, node: &RenderNode, client_rect: Rect, client_rrect: RRect) {
+        let style = &node.style;
+        let units = node.paint_units();
+        let width = style.border_width.resolved();
+        let black = Rgba {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        // An unset style is drawn as solid
+        let side = |border_style: Option<BorderStyle>, color: Option<Rgba>, width: Length| {
+            let visible = !matches!(border_style, Some(BorderStyle::None | BorderStyle::Hidden));
+            let width = if visible {
+                units.inline_or_zero(width) as f32
+            } else {
+                0.0
+            };
+            (width, color.unwrap_or(black))
+        };
+        let [top, right, bottom, left] = [
+            side(style.border_style.top, style.border_color.top, width.top),
+            side(
+                style.border_style.right,
+                style.border_color.right,
+                width.right,
+            ),
+            side(
+                style.border_style.bottom,
+                style.border_color.bottom,
+                width.bottom,
+            ),
+            side(style.border_style.left, style.border_color.left, width.left),
+        ];
 
-impl ToPx for Length {
-    fn to_px(&self) -> f64 {
-        match self {
-            Length::Px(value) => *value,
-            _ => 0.0, // Handle other cases as needed
+        if [right, bottom, left].iter().all(|side| *side == top) {
+            // Uniform borders follow the rounded corners. They are filled between the
+            // border box and the padding box rather than stroked along the edge, so a
+            // 1px border on a pixel-aligned box covers whole pixels.
+            let (width, color) = top;
+            if width > 0.0 {
+                let inner = client_rrect.with_inset((width, width));
+                if inner.rect().is_empty() {
+                    self.canvas.draw_rrect(client_rrect, &self.fill(color));
+                } else {
+                    self.canvas
+                        .draw_drrect(client_rrect, inner, &self.fill(color));
+                }
+            }
+            return;
+        }
+
+        // Sides that differ are drawn as separate bands inside the border box
+        let r = client_rect;
+        let bands = [
+            (top, Rect::new(r.left, r.top, r.right, r.top + top.0)),
+            (
+                right,
+                Rect::new(r.right - right.0, r.top, r.right, r.bottom),
+            ),
+            (
+                bottom,
+                Rect::new(r.left, r.bottom - bottom.0, r.right, r.bottom),
+            ),
+            (left, Rect::new(r.left, r.top, r.left + left.0, r.bottom)),
+        ];
+        for ((width, color), band) in bands {
+            if width > 0.0 {
+                self.canvas.draw_rect(band, &self.fill(color));
+            }
         }
     }
 }
@@ -0,0 +1,197 @@
+use crate::{commands::LoadedStylesheet, layout::Document, style::StyleOrigin, Id};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, io};
+
+/// Version of the saved document format written by [`crate::Engine::save_document`].
+const FORMAT_VERSION: u32 = 2;
+
+/// Errors returned by [`crate::Engine::save_document`] and [`crate::Engine::load_document`].
+#[derive(Debug)]
+pub enum DocumentError {
+    Io(io::Error),
+    /// The file is not a saved document this version of the engine understands.
+    Format(String),
+    Engine(crate::EngineError),
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentError::Io(err) => write!(f, "document I/O failed: {}", err),
+            DocumentError::Format(msg) => write!(f, "invalid document: {}", msg),
+            DocumentError::Engine(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DocumentError::Io(err) => Some(err),
+            DocumentError::Engine(err) => Some(err),
+            DocumentError::Format(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for DocumentError {
+    fn from(err: io::Error) -> Self {
+        DocumentError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for DocumentError {
+    fn from(err: serde_json::Error) -> Self {
+        DocumentError::Format(err.to_string())
+    }
+}
+
+impl From<crate::EngineError> for DocumentError {
+    fn from(err: crate::EngineError) -> Self {
+        DocumentError::Engine(err)
+    }
+}
+
+/// Serializable form of a whole document: its nodes and stylesheets.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SavedDocument {
+    pub version: u32,
+    /// Stylesheets in cascade order.
+    pub stylesheets: Vec<SavedStylesheet>,
+    /// Nodes in tree order, so replaying `parent` links restores child order.
+    pub nodes: Vec<SavedNode>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SavedStylesheet {
+    pub css: String,
+    /// Root of the subtree a scoped stylesheet applies to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_author")]
+    pub origin: StyleOrigin,
+}
+
+fn is_author(origin: &StyleOrigin) -> bool {
+    *origin == StyleOrigin::Author
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SavedNode {
+    pub id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Whether the node is a text node rather than an element holding text.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub text_node: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug_name: Option<String>,
+}
+
+impl SavedDocument {
+    /// Capture the document and stylesheets owned by the command thread.
+    pub fn capture(document: &Document, stylesheets: &[LoadedStylesheet]) -> Self {
+        let mut nodes = Vec::new();
+        let mut visit = vec![document.root_node()];
+        while let Some(node) = visit.pop() {
+            let node = node.borrow();
+            nodes.push(saved_node(&node));
+            visit.extend(node.children.iter().rev().cloned());
+        }
+
+        // Nodes created but never attached to the tree.
+        let mut detached: Vec<_> = document
+            .nodes()
+            .filter(|node| {
+                let node = node.borrow();
+                node.parent.is_none() && node.id != document.root_id()
+            })
+            .map(|node| saved_node(&node.borrow()))
+            .collect();
+        detached.sort_by_key(|node| node.id);
+        nodes.extend(detached);
+
+        Self {
+            version: FORMAT_VERSION,
+            stylesheets: stylesheets
+                .iter()
+                .map(|sheet| SavedStylesheet {
+                    css: sheet.css.clone(),
+                    scope: sheet.scope.map(|scope| scope.value()),
+                    origin: sheet.origin,
+                })
+                .collect(),
+            nodes,
+        }
+    }
+
+    /// Build a fresh document from the saved nodes.
+    pub fn restore(&self) -> Document {
+        let mut document = Document::new();
+        for saved in &self.nodes {
+            let id = Id::from_u64(saved.id);
+            if id != document.root_id() {
+                let text = saved.text.clone();
+                if saved.text_node {
+                    document.create_text_node(id, text.unwrap_or_default());
+                } else {
+                    document.create_node(id, text);
+                }
+            }
+            for (key, value) in &saved.attributes {
+                document.set_attribute(id, key.clone(), value.clone());
+            }
+            if let Some(node) = document.get_node(id) {
+                let mut node = node.borrow_mut();
+                node.tag = saved.tag.clone();
+                node.debug_name = saved.debug_name.clone();
+            }
+            if let Some(parent) = saved.parent {
+                if let Err(e) = document.set_parent(Id::from_u64(parent), id) {
+                    eprintln!("Failed to restore parent of #{}: {}", saved.id, e);
+                }
+            }
+        }
+        document
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, DocumentError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_json(bytes: &[u8]) -> Result<Self, DocumentError> {
+        let saved: Self = serde_json::from_slice(bytes)?;
+        if saved.version != FORMAT_VERSION {
+            return Err(DocumentError::Format(format!(
+                "unsupported version {}",
+                saved.version
+            )));
+        }
+        Ok(saved)
+    }
+}
+
+fn saved_node(node: &crate::layout::Node) -> SavedNode {
+    SavedNode {
+        id: node.id.value(),
+        parent: node.parent.map(|parent| parent.value()),
+        tag: node.tag.clone(),
+        text: node.text.clone(),
+        text_node: node.is_text_node(),
+        attributes: node
+            .attributes
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        debug_name: node.debug_name.clone(),
+    }
+}
+
+#[cfg(test)]
+mod persist_tests;
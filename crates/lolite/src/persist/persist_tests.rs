@@ -0,0 +1,63 @@
+use super::*;
+
+fn sample_document() -> Document {
+    let mut document = Document::new();
+    let root = document.root_id();
+    let list = document.create_node(Id::from_u64(1), None);
+    let first = document.create_node(Id::from_u64(2), Some("First".to_string()));
+    let second = document.create_node(Id::from_u64(3), Some("Second".to_string()));
+    document.create_text_node(Id::from_u64(4), "Note".to_string());
+
+    document.set_parent(root, list).unwrap();
+    document.set_parent(list, second).unwrap();
+    document.set_parent(list, first).unwrap();
+    document.set_attribute(list, "class".to_owned(), "list".to_owned());
+    document.get_node(list).unwrap().borrow_mut().debug_name = Some("list".to_string());
+    document.get_node(list).unwrap().borrow_mut().tag = Some("ul".to_string());
+    document
+}
+
+#[test]
+fn test_capture_and_restore_round_trip() {
+    let stylesheets = vec![LoadedStylesheet {
+        id: crate::StylesheetId(1),
+        css: ".list { width: 10px; }".to_string(),
+        scope: Some(Id::from_u64(1)),
+        origin: StyleOrigin::Library,
+        sheet: crate::style::StyleSheet::new(),
+    }];
+    let saved = SavedDocument::capture(&sample_document(), &stylesheets);
+
+    assert_eq!(
+        saved.stylesheets,
+        vec![SavedStylesheet {
+            css: ".list { width: 10px; }".to_string(),
+            scope: Some(1),
+            origin: StyleOrigin::Library,
+        }]
+    );
+    let ids: Vec<_> = saved.nodes.iter().map(|node| node.id).collect();
+    // Tree order first, keeping child order, then detached nodes
+    assert_eq!(ids, vec![0, 1, 3, 2, 4]);
+    assert!(saved.nodes[4].text_node && !saved.nodes[3].text_node);
+
+    let json = saved.to_json().unwrap();
+    let loaded = SavedDocument::from_json(&json).unwrap();
+    assert_eq!(loaded, saved);
+
+    let restored = loaded.restore();
+    assert_eq!(SavedDocument::capture(&restored, &stylesheets), saved);
+}
+
+#[test]
+fn test_reject_unknown_version() {
+    let json = br#"{"version": 99, "stylesheets": [], "nodes": []}"#;
+    assert!(matches!(
+        SavedDocument::from_json(json),
+        Err(DocumentError::Format(_))
+    ));
+    assert!(matches!(
+        SavedDocument::from_json(b"not json"),
+        Err(DocumentError::Format(_))
+    ));
+}
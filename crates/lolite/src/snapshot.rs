@@ -0,0 +1,88 @@
+use crate::layout::RenderNode;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// How snapshots published by the command thread wait for the window to draw them,
+/// see [`crate::Engine::set_frame_policy`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FramePolicy {
+    /// Draw the newest snapshot, skipping any published since the previous frame.
+    /// Lowest latency.
+    #[default]
+    LatestWins,
+    /// Draw snapshots in the order they were published, one per frame, keeping at most
+    /// this many waiting; beyond that the oldest are skipped. Shows intermediate states
+    /// of fast mutations at the cost of up to that many frames of latency.
+    Bounded(usize),
+}
+
+/// Snapshots waiting to be drawn, and the one on screen.
+///
+/// The command thread publishes into it and the window takes a snapshot per frame, so
+/// neither waits for the other beyond a brief lock.
+#[derive(Default)]
+pub(crate) struct SnapshotQueue {
+    policy: FramePolicy,
+    pending: VecDeque<Arc<RenderNode>>,
+    displayed: Option<Arc<RenderNode>>,
+}
+
+impl SnapshotQueue {
+    pub fn set_policy(&mut self, policy: FramePolicy) {
+        self.policy = policy;
+        self.trim();
+    }
+
+    pub fn publish(&mut self, snapshot: RenderNode) {
+        self.pending.push_back(Arc::new(snapshot));
+        self.trim();
+    }
+
+    /// Drop the oldest waiting snapshots beyond the policy's limit.
+    fn trim(&mut self) {
+        let capacity = match self.policy {
+            FramePolicy::LatestWins => 1,
+            FramePolicy::Bounded(capacity) => capacity.max(1),
+        };
+        while self.pending.len() > capacity {
+            self.pending.pop_front();
+        }
+    }
+
+    /// The most recently published snapshot, drawn or not.
+    pub fn latest(&self) -> Option<Arc<RenderNode>> {
+        self.pending.back().or(self.displayed.as_ref()).cloned()
+    }
+
+    /// Mutable access to the most recently published snapshot, to patch it in place.
+    pub fn latest_mut(&mut self) -> Option<&mut RenderNode> {
+        self.pending
+            .back_mut()
+            .or(self.displayed.as_mut())
+            .map(Arc::make_mut)
+    }
+
+    /// The snapshot on screen, which input is hit-tested against.
+    #[cfg(feature = "windowing")]
+    pub fn displayed(&self) -> Option<Arc<RenderNode>> {
+        self.displayed.clone()
+    }
+
+    /// Advance to the next snapshot to draw, if one is waiting, and return it.
+    #[cfg(feature = "windowing")]
+    pub fn next_frame(&mut self) -> Option<Arc<RenderNode>> {
+        if let Some(next) = self.pending.pop_front() {
+            self.displayed = Some(next);
+        }
+        self.displayed.clone()
+    }
+
+    /// Whether snapshots are still waiting to be drawn.
+    #[cfg(feature = "windowing")]
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+#[cfg(all(test, feature = "windowing"))]
+mod snapshot_tests;
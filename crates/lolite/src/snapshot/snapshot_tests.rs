@@ -0,0 +1,70 @@
+use super::*;
+use crate::layout::{Rect, ScrollOffset};
+use crate::Id;
+
+fn snapshot(width: f64) -> RenderNode {
+    RenderNode {
+        id: Id::from_u64(0),
+        generated: None,
+        bounds: Rect::new(0.0, 0.0, width, 10.0),
+        style: Arc::default(),
+        text: None,
+        debug_name: None,
+        scroll_offset: ScrollOffset::default(),
+        draggable: false,
+        embedded: None,
+        children: Vec::new(),
+    }
+}
+
+fn width(snapshot: Option<Arc<RenderNode>>) -> Option<f64> {
+    snapshot.map(|root| root.bounds.width)
+}
+
+#[test]
+fn test_latest_wins_skips_intermediate_snapshots() {
+    let mut queue = SnapshotQueue::default();
+    assert_eq!(width(queue.next_frame()), None);
+    for i in 1..=3 {
+        queue.publish(snapshot(i as f64));
+    }
+    assert_eq!(width(queue.latest()), Some(3.0));
+    assert_eq!(width(queue.displayed()), None);
+
+    assert_eq!(width(queue.next_frame()), Some(3.0));
+    assert!(!queue.has_pending());
+    // Nothing new: the same snapshot is drawn again
+    assert_eq!(width(queue.next_frame()), Some(3.0));
+}
+
+#[test]
+fn test_bounded_queue_draws_in_order_and_drops_the_oldest() {
+    let mut queue = SnapshotQueue::default();
+    queue.set_policy(FramePolicy::Bounded(2));
+    for i in 1..=3 {
+        queue.publish(snapshot(i as f64));
+    }
+    assert_eq!(width(queue.next_frame()), Some(2.0));
+    assert!(queue.has_pending());
+    // Readers outside the window see the newest layout
+    assert_eq!(width(queue.latest()), Some(3.0));
+    assert_eq!(width(queue.displayed()), Some(2.0));
+    assert_eq!(width(queue.next_frame()), Some(3.0));
+    assert!(!queue.has_pending());
+
+    // Shrinking the bound drops what no longer fits
+    queue.publish(snapshot(4.0));
+    queue.publish(snapshot(5.0));
+    queue.set_policy(FramePolicy::LatestWins);
+    assert_eq!(width(queue.next_frame()), Some(5.0));
+}
+
+#[test]
+fn test_patching_the_latest_snapshot_leaves_drawn_copies_alone() {
+    let mut queue = SnapshotQueue::default();
+    queue.publish(snapshot(1.0));
+    let drawn = queue.next_frame().unwrap();
+    queue.latest_mut().unwrap().bounds.width = 7.0;
+    assert_eq!(drawn.bounds.width, 1.0);
+    assert_eq!(width(queue.next_frame()), Some(7.0));
+}
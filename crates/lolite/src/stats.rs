@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+/// Counters describing the size of an engine's document, see [`crate::Engine::stats`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EngineStats {
+    /// Nodes in the document, including the root and nodes never attached to it.
+    pub node_count: usize,
+    /// Nodes other than the root without a parent; a growing count usually means nodes
+    /// are created but never removed.
+    pub detached_node_count: usize,
+    pub stylesheet_count: usize,
+    /// Rules across all stylesheets.
+    pub rule_count: usize,
+    /// Time spent in the most recent layout pass, `None` before the first one.
+    pub last_layout_duration: Option<Duration>,
+    /// Nodes in the latest published snapshot, i.e. the attached tree.
+    pub snapshot_node_count: usize,
+}
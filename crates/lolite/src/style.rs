@@ -1,6 +1,14 @@
+use crate::animation::Easing;
+use crate::css_parser::{parse_declaration, substitute_vars};
+use crate::layout::Size;
+use crate::units::ResolutionContext;
+use crate::{Id, StylesheetId};
 use lolite_macros::MergeProperties;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
-#[derive(Clone, Copy, Default, Debug, PartialEq)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Rgba {
     pub r: u8,
     pub g: u8,
@@ -8,28 +16,58 @@ pub struct Rgba {
     pub a: u8,
 }
 
-#[derive(Clone, Copy, Default, Debug, PartialEq)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
 #[allow(unused)]
 pub enum Length {
     #[default]
     Auto,
     Px(f64),
+    /// Multiple of the element's font size, or of its parent's for `font-size`.
     Em(f64),
+    /// Multiple of the root element's font size.
+    Rem(f64),
     Percent(f64),
+    /// Percent of the viewport width.
+    Vw(f64),
+    /// Percent of the viewport height.
+    Vh(f64),
+    /// Percent of the smaller viewport dimension.
+    Vmin(f64),
+    /// Percent of the larger viewport dimension.
+    Vmax(f64),
 }
 
 impl Length {
-    pub fn to_px(&self) -> f64 {
-        match self {
-            Length::Px(value) => *value,
-            Length::Auto => 0.0,
-            Length::Em(_) => 0.0,      // TODO: Implement em conversion
-            Length::Percent(_) => 0.0, // TODO: Implement percentage conversion
+    /// Convert font-relative and viewport lengths to pixels, leaving `auto` and
+    /// percentages, which depend on layout, unchanged.
+    pub(crate) fn resolve_units(&mut self, units: &ResolutionContext) {
+        if let Length::Em(_)
+        | Length::Rem(_)
+        | Length::Vw(_)
+        | Length::Vh(_)
+        | Length::Vmin(_)
+        | Length::Vmax(_) = *self
+        {
+            *self = Length::Px(units.resolve(*self, 0.0).unwrap_or(0.0));
+        }
+    }
+
+    pub(crate) fn is_negative(&self) -> bool {
+        match *self {
+            Length::Px(value)
+            | Length::Em(value)
+            | Length::Rem(value)
+            | Length::Percent(value)
+            | Length::Vw(value)
+            | Length::Vh(value)
+            | Length::Vmin(value)
+            | Length::Vmax(value) => value < 0.0,
+            Length::Auto => false,
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[allow(unused)]
 pub struct Directional<T> {
     pub top: T,
@@ -105,13 +143,13 @@ impl Directional<Option<Length>> {
     }
 }
 
-#[derive(Clone, Copy, Default, Debug, PartialEq)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Radius {
     pub x: Length,
     pub y: Length,
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct BorderRadius {
     pub top_left: Option<Radius>,
     pub top_right: Option<Radius>,
@@ -143,7 +181,7 @@ impl BorderRadius {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BorderStyle {
     None,
     Hidden,
@@ -157,17 +195,20 @@ pub enum BorderStyle {
     Outset,
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Display {
-    // Block,
+    /// The initial value, unlike CSS's `inline`: children are stacked vertically in
+    /// normal flow.
+    #[default]
+    Block,
     // Inline,
     // InlineBlock,
-    #[default]
+    /// Children are laid out as flex items.
     Flex,
     // Grid,
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum FlexDirection {
     #[default]
     Row,
@@ -176,7 +217,7 @@ pub enum FlexDirection {
     ColumnReverse,
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum FlexWrap {
     #[default]
     NoWrap,
@@ -184,7 +225,7 @@ pub enum FlexWrap {
     WrapReverse,
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum JustifyContent {
     #[default]
     FlexStart,
@@ -193,9 +234,16 @@ pub enum JustifyContent {
     SpaceBetween,
     SpaceAround,
     SpaceEvenly,
+    /// Start of the inline or block direction, unlike `FlexStart` unaffected by
+    /// reversed flex directions.
+    Start,
+    End,
+    /// Physical sides; on a vertical main axis both act as `Start`.
+    Left,
+    Right,
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum AlignItems {
     #[default]
     Stretch,
@@ -203,9 +251,11 @@ pub enum AlignItems {
     FlexEnd,
     Center,
     Baseline,
+    Start,
+    End,
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum AlignContent {
     #[default]
     Stretch,
@@ -215,9 +265,11 @@ pub enum AlignContent {
     SpaceBetween,
     SpaceAround,
     SpaceEvenly,
+    Start,
+    End,
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum AlignSelf {
     #[default]
     Auto,
@@ -226,20 +278,556 @@ pub enum AlignSelf {
     Center,
     Baseline,
     Stretch,
+    Start,
+    End,
 }
 
-#[derive(Clone, Copy, Default, Debug, PartialEq)]
+/// Direction of inline text and of the main axis of `row` flex containers.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+/// `font-style`; oblique angles aren't supported.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    Italic,
+    Oblique,
+}
+
+/// `line-height`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LineHeight {
+    #[default]
+    Normal,
+    /// Multiple of the font size, inherited as a multiple.
+    Number(f64),
+    /// Percentages and font-relative lengths refer to the element's own font size, and
+    /// are resolved to pixels before children inherit them.
+    Length(Length),
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub enum BoxSizing {
     #[default]
     ContentBox,
     BorderBox,
 }
 
-#[derive(Clone, Default, MergeProperties)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScrollBehavior {
+    #[default]
+    Auto,
+    Smooth,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScrollSnapAxis {
+    #[default]
+    None,
+    X,
+    Y,
+    Both,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScrollSnapStrictness {
+    Mandatory,
+    #[default]
+    Proximity,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScrollSnapType {
+    pub axis: ScrollSnapAxis,
+    pub strictness: ScrollSnapStrictness,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScrollSnapAlign {
+    #[default]
+    None,
+    Start,
+    Center,
+    End,
+}
+
+/// Kinds of containment turned on by the `contain` property.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Contain {
+    pub layout: bool,
+    /// Descendants are clipped to the node's box.
+    pub paint: bool,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ContentVisibility {
+    #[default]
+    Visible,
+    /// Contents are skipped while the node is outside the visible area.
+    Auto,
+    /// Contents are never painted or hit.
+    Hidden,
+}
+
+/// Value of the `aspect-ratio` property, as a width over a height.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AspectRatio {
+    #[default]
+    Auto,
+    Ratio(f64, f64),
+}
+
+impl AspectRatio {
+    /// The width divided by the height, or `None` for `auto` and for ratios with a zero
+    /// term, which behave as `auto`.
+    pub fn value(self) -> Option<f64> {
+        match self {
+            AspectRatio::Ratio(width, height) if width > 0.0 && height > 0.0 => {
+                Some(width / height)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Value of the `position` property.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Position {
+    #[default]
+    Static,
+    Relative,
+    Absolute,
+    Fixed,
+}
+
+/// Size of a grid track, or one bound of a `minmax()` track.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TrackBreadth {
+    /// A length, a percentage or `auto`.
+    Length(Length),
+    /// Share of the free space, in `fr`.
+    Fraction(f64),
+    MinContent,
+    MaxContent,
+}
+
+/// Sizing of a grid track in `grid-template-columns` and `grid-template-rows`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TrackSize {
+    Breadth(TrackBreadth),
+    /// `minmax(min, max)`; the minimum is never a fraction.
+    MinMax(TrackBreadth, TrackBreadth),
+    /// `fit-content(limit)`.
+    FitContent(Length),
+}
+
+/// How many times `repeat()` repeats its tracks.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RepeatCount {
+    Count(u32),
+    /// As many times as fit, keeping empty tracks.
+    AutoFill,
+    /// As many times as fit, collapsing empty tracks.
+    AutoFit,
+}
+
+/// Entry of a grid track list; `none` is an empty list.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GridTrack {
+    /// Names of the line between the neighbouring tracks, as in `[header-start]`.
+    LineNames(Vec<String>),
+    Size(TrackSize),
+    /// `repeat(count, tracks)`, whose tracks never repeat themselves.
+    Repeat(RepeatCount, Vec<GridTrack>),
+}
+
+impl GridTrack {
+    /// Lengths within the entry, to resolve their units.
+    fn lengths_mut(&mut self) -> Vec<&mut Length> {
+        fn breadth(breadth: &mut TrackBreadth) -> Option<&mut Length> {
+            match breadth {
+                TrackBreadth::Length(length) => Some(length),
+                _ => None,
+            }
+        }
+        match self {
+            GridTrack::LineNames(_) => Vec::new(),
+            GridTrack::Size(TrackSize::Breadth(size)) => breadth(size).into_iter().collect(),
+            GridTrack::Size(TrackSize::MinMax(min, max)) => {
+                breadth(min).into_iter().chain(breadth(max)).collect()
+            }
+            GridTrack::Size(TrackSize::FitContent(limit)) => vec![limit],
+            GridTrack::Repeat(_, tracks) => tracks.iter_mut().flat_map(Self::lengths_mut).collect(),
+        }
+    }
+}
+
+/// Property a transition applies to, in `transition-property`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TransitionProperty {
+    All,
+    /// A lowercase property name; names that can't be animated are kept, as in CSS.
+    Named(String),
+}
+
+/// Image of a background layer, in `background-image`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BackgroundImage {
+    #[default]
+    None,
+    /// The URL as written, not resolved against the stylesheet's.
+    Url(String),
+}
+
+/// Where a background layer's image is placed in `background-position`, from the top
+/// left corner of the node. A percentage aligns that point of the image with the same
+/// point of the node.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundPosition {
+    pub x: Length,
+    pub y: Length,
+}
+
+impl Default for BackgroundPosition {
+    fn default() -> Self {
+        Self {
+            x: Length::Percent(0.0),
+            y: Length::Percent(0.0),
+        }
+    }
+}
+
+/// Size of a background layer's image, in `background-size`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BackgroundSize {
+    /// The width and height; `auto` keeps the image's aspect ratio, or its natural
+    /// size when both are `auto`.
+    Explicit(Length, Length),
+    /// The smallest size covering the node, keeping the aspect ratio.
+    Cover,
+    /// The largest size fitting in the node, keeping the aspect ratio.
+    Contain,
+}
+
+impl Default for BackgroundSize {
+    fn default() -> Self {
+        BackgroundSize::Explicit(Length::Auto, Length::Auto)
+    }
+}
+
+/// How a background layer's image repeats along each axis, in `background-repeat`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundRepeat {
+    pub x: RepeatStyle,
+    pub y: RepeatStyle,
+}
+
+/// How a background image repeats along one axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum RepeatStyle {
+    #[default]
+    Repeat,
+    NoRepeat,
+    /// Repeated as many whole times as fit, spaced out to fill the node.
+    Space,
+    /// Repeated a whole number of times, scaled to fill the node.
+    Round,
+}
+
+/// Grid line an item starts or ends at, in `grid-row-start` and the other placement
+/// properties.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum GridLine {
+    #[default]
+    Auto,
+    /// The start or end of the named grid area, or else the first line of that name.
+    Name(String),
+    /// The nth line, or nth line of the given name; negative counts from the end.
+    Index(i32, Option<String>),
+    /// Span this many tracks, or this many lines of the given name, from the other edge.
+    Span(u32, Option<String>),
+}
+
+/// How content overflowing a node's padding box along one axis is handled.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Overflow {
+    #[default]
+    Visible,
+    /// Clipped, and only scrolled programmatically, not by the user.
+    Hidden,
+    /// Clipped and scrollable.
+    Scroll,
+    /// Clipped and scrollable; no different from `scroll` without scrollbars.
+    Auto,
+}
+
+impl Overflow {
+    /// Whether user input such as the wheel scrolls along the axis.
+    pub fn is_user_scrollable(self) -> bool {
+        matches!(self, Overflow::Scroll | Overflow::Auto)
+    }
+}
+
+/// Value of the `content` property, giving the text of a `::before` or `::after` box.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Content {
+    /// `none` or `normal`: no box is generated.
+    None,
+    /// Strings and `attr()` references, concatenated.
+    Items(Vec<ContentItem>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ContentItem {
+    String(String),
+    /// `attr(name)`: the value of an attribute of the originating element, or nothing if
+    /// it isn't set.
+    Attr(String),
+}
+
+impl Content {
+    /// The generated text, with attributes looked up on the originating element; `None`
+    /// if no box is generated.
+    pub fn text(&self, attributes: &HashMap<String, String>) -> Option<String> {
+        match self {
+            Content::None => None,
+            Content::Items(items) => Some(
+                items
+                    .iter()
+                    .map(|item| match item {
+                        ContentItem::String(text) => text.as_str(),
+                        ContentItem::Attr(name) => {
+                            attributes.get(name).map(String::as_str).unwrap_or_default()
+                        }
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Custom properties (`--name: value`) in effect on a node, keyed by name including
+/// the leading dashes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CustomProperties(BTreeMap<String, String>);
+
+impl CustomProperties {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, name: String, value: String) {
+        self.0.insert(name, value);
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.0
+            .extend(other.0.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+
+    /// Substitute `var()` references between the properties themselves.
+    ///
+    /// Properties that reference an undefined property without a fallback, or that take
+    /// part in a cycle, are invalid and dropped.
+    fn resolved(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter_map(|(name, value)| Some((name.clone(), substitute_vars(value, self)?)))
+                .collect(),
+        )
+    }
+}
+
+/// A CSS-wide keyword, accepted as the value of any property.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CssWideKeyword {
+    /// The initial value of the property, even if it is inherited.
+    Initial,
+    /// The parent's value of the property, even if it isn't inherited.
+    Inherit,
+    /// `inherit` for inherited properties, `initial` for the others.
+    Unset,
+    /// The value the property would have if the declaration's origin didn't set it,
+    /// e.g. the user agent's value for an author declaration.
+    Revert,
+}
+
+/// Longhands set to a CSS-wide keyword, keyed by name. The fields of the style holding
+/// them are left unset; the keyword says what they mean.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CssWideKeywords(BTreeMap<String, CssWideKeyword>);
+
+impl CssWideKeywords {
+    pub fn get(&self, property: &str) -> Option<CssWideKeyword> {
+        self.0.get(property).copied()
+    }
+
+    pub fn insert(&mut self, property: String, keyword: CssWideKeyword) {
+        self.0.insert(property, keyword);
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.0.extend(other.0.iter().map(|(k, v)| (k.clone(), *v)));
+    }
+}
+
+/// Defines how longhands map to the fields of [`Style`], for CSS-wide keywords.
+macro_rules! longhands {
+    ($($name:literal => $($field:ident).+,)*) => {
+        /// The name of a longhand, as a static string.
+        pub(crate) fn longhand(property: &str) -> Option<&'static str> {
+            match property {
+                $($name => Some($name),)*
+                _ => None,
+            }
+        }
+
+        /// Whether `style` sets a value for the longhand `property`.
+        fn longhand_is_set(style: &Style, property: &str) -> bool {
+            match property {
+                $($name => style.$($field).+.is_some(),)*
+                _ => false,
+            }
+        }
+
+        /// Give `to` the value of the longhand `property` in `from`, set or not.
+        fn copy_longhand(to: &mut Style, from: &Style, property: &str) {
+            match property {
+                $($name => to.$($field).+ = from.$($field).+.clone(),)*
+                _ => {}
+            }
+        }
+    };
+}
+
+longhands! {
+    "display" => display,
+    "color" => color,
+    "background-color" => background_color,
+    "background-image" => background_image,
+    "background-position" => background_position,
+    "background-size" => background_size,
+    "background-repeat" => background_repeat,
+    "border-top-color" => border_color.top,
+    "border-right-color" => border_color.right,
+    "border-bottom-color" => border_color.bottom,
+    "border-left-color" => border_color.left,
+    "border-top-width" => border_width.top,
+    "border-right-width" => border_width.right,
+    "border-bottom-width" => border_width.bottom,
+    "border-left-width" => border_width.left,
+    "border-top-style" => border_style.top,
+    "border-right-style" => border_style.right,
+    "border-bottom-style" => border_style.bottom,
+    "border-left-style" => border_style.left,
+    "border-top-left-radius" => border_radius.top_left,
+    "border-top-right-radius" => border_radius.top_right,
+    "border-bottom-right-radius" => border_radius.bottom_right,
+    "border-bottom-left-radius" => border_radius.bottom_left,
+    "box-sizing" => box_sizing,
+    "margin-top" => margin.top,
+    "margin-right" => margin.right,
+    "margin-bottom" => margin.bottom,
+    "margin-left" => margin.left,
+    "padding-top" => padding.top,
+    "padding-right" => padding.right,
+    "padding-bottom" => padding.bottom,
+    "padding-left" => padding.left,
+    "position" => position,
+    "top" => inset.top,
+    "right" => inset.right,
+    "bottom" => inset.bottom,
+    "left" => inset.left,
+    "width" => width,
+    "height" => height,
+    "min-width" => min_width,
+    "max-width" => max_width,
+    "min-height" => min_height,
+    "max-height" => max_height,
+    "aspect-ratio" => aspect_ratio,
+    "scroll-behavior" => scroll_behavior,
+    "scroll-snap-type" => scroll_snap_type,
+    "scroll-snap-align" => scroll_snap_align,
+    "contain" => contain,
+    "content-visibility" => content_visibility,
+    "overflow-x" => overflow_x,
+    "overflow-y" => overflow_y,
+    "content" => content,
+    "font-family" => font_family,
+    "font-size" => font_size,
+    "font-weight" => font_weight,
+    "font-style" => font_style,
+    "line-height" => line_height,
+    "direction" => direction,
+    "flex-direction" => flex_direction,
+    "flex-wrap" => flex_wrap,
+    "justify-content" => justify_content,
+    "align-items" => align_items,
+    "align-content" => align_content,
+    "row-gap" => row_gap,
+    "column-gap" => column_gap,
+    "grid-template-columns" => grid_template_columns,
+    "grid-template-rows" => grid_template_rows,
+    "flex-grow" => flex_grow,
+    "flex-shrink" => flex_shrink,
+    "flex-basis" => flex_basis,
+    "align-self" => align_self,
+    "order" => order,
+    "grid-row-start" => grid_row_start,
+    "grid-row-end" => grid_row_end,
+    "grid-column-start" => grid_column_start,
+    "grid-column-end" => grid_column_end,
+    "transition-property" => transition_property,
+    "transition-duration" => transition_duration,
+    "transition-timing-function" => transition_timing_function,
+    "transition-delay" => transition_delay,
+}
+
+/// Longhands inherited by default, which `unset` inherits.
+const INHERITED_LONGHANDS: [&str; 7] = [
+    "color",
+    "font-family",
+    "font-size",
+    "font-weight",
+    "font-style",
+    "line-height",
+    "direction",
+];
+
+/// A declaration whose value references custom properties, so it can only be parsed
+/// once their values on the node are known.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VarDeclaration {
+    pub property: String,
+    pub value: String,
+}
+
+impl VarDeclaration {
+    /// Substitute the custom properties and parse the result, or `None` if a reference
+    /// can't be resolved or the result isn't a valid value for the property.
+    pub fn resolve(&self, custom_properties: &CustomProperties) -> Option<Style> {
+        let value = substitute_vars(&self.value, custom_properties)?;
+        parse_declaration(&self.property, &value)
+    }
+}
+
+#[derive(Clone, Default, PartialEq, MergeProperties, Serialize, Deserialize)]
 pub struct Style {
-    pub display: Display,
+    pub display: Option<Display>,
     pub color: Option<Rgba>,
     pub background_color: Option<Rgba>,
+    // Background layers, whose lists pair up by index as in CSS, topmost first. There
+    // are as many layers as images; the other lists repeat to match.
+    pub background_image: Option<Vec<BackgroundImage>>,
+    pub background_position: Option<Vec<BackgroundPosition>>,
+    pub background_size: Option<Vec<BackgroundSize>>,
+    pub background_repeat: Option<Vec<BackgroundRepeat>>,
     #[merge_by_method_call]
     pub border_color: Directional<Option<Rgba>>,
     #[merge_by_method_call]
@@ -253,12 +841,42 @@ pub struct Style {
     pub margin: Directional<Option<Length>>,
     #[merge_by_method_call]
     pub padding: Directional<Option<Length>>,
+    pub position: Option<Position>,
+    /// `top`, `right`, `bottom` and `left`; only used by positioned nodes.
+    #[merge_by_method_call]
+    pub inset: Directional<Option<Length>>,
     pub width: Option<Length>,
     pub height: Option<Length>,
+    /// `auto` doesn't constrain the width.
+    pub min_width: Option<Length>,
+    /// `auto` stands for `none`, which doesn't constrain the width.
+    pub max_width: Option<Length>,
+    /// `auto` doesn't constrain the height.
+    pub min_height: Option<Length>,
+    /// `auto` stands for `none`, which doesn't constrain the height.
+    pub max_height: Option<Length>,
+    /// Gives an `auto` width or height its size from the other one.
+    pub aspect_ratio: Option<AspectRatio>,
+    pub scroll_behavior: Option<ScrollBehavior>,
+    pub scroll_snap_type: Option<ScrollSnapType>,
+    pub scroll_snap_align: Option<ScrollSnapAlign>,
+    pub contain: Option<Contain>,
+    pub content_visibility: Option<ContentVisibility>,
+    pub overflow_x: Option<Overflow>,
+    pub overflow_y: Option<Overflow>,
+    /// Only used by `::before` and `::after`.
+    pub content: Option<Content>,
 
-    // Text / font properties
+    // Text / font properties, all inherited
+    /// Font families tried in order, as a comma-separated list.
     pub font_family: Option<String>,
     pub font_size: Option<Length>,
+    /// From 1 to 1000; `normal` is 400 and `bold` 700.
+    pub font_weight: Option<u16>,
+    pub font_style: Option<FontStyle>,
+    pub line_height: Option<LineHeight>,
+    /// Inherited; the root defaults to the direction of the locale.
+    pub direction: Option<TextDirection>,
 
     // Flexbox container properties
     pub flex_direction: Option<FlexDirection>,
@@ -266,38 +884,804 @@ pub struct Style {
     pub justify_content: Option<JustifyContent>,
     pub align_items: Option<AlignItems>,
     pub align_content: Option<AlignContent>,
+    /// `auto` stands for `normal`, which is no gap in flex containers. Percentages refer
+    /// to the container's content box.
     pub row_gap: Option<Length>,
+    /// `auto` stands for `normal`, as for `row_gap`.
     pub column_gap: Option<Length>,
 
+    // Grid container properties
+    pub grid_template_columns: Option<Vec<GridTrack>>,
+    pub grid_template_rows: Option<Vec<GridTrack>>,
+
     // Flexbox item properties
     pub flex_grow: Option<f64>,
     pub flex_shrink: Option<f64>,
     pub flex_basis: Option<Length>,
     pub align_self: Option<AlignSelf>,
     pub order: Option<i32>,
+
+    // Grid item properties
+    pub grid_row_start: Option<GridLine>,
+    pub grid_row_end: Option<GridLine>,
+    pub grid_column_start: Option<GridLine>,
+    pub grid_column_end: Option<GridLine>,
+
+    // Transitions, whose lists pair up by index as in CSS; an empty property list is
+    // `none`. Times are in seconds.
+    pub transition_property: Option<Vec<TransitionProperty>>,
+    pub transition_duration: Option<Vec<f64>>,
+    pub transition_timing_function: Option<Vec<Easing>>,
+    pub transition_delay: Option<Vec<f64>>,
+
+    // Custom properties, inherited by descendants.
+    #[merge_by_method_call]
+    pub custom_properties: CustomProperties,
+    /// Set on a parsed declaration that uses `var()`; resolved by [`Style::cascade`].
+    #[merge_skip]
+    pub var_declaration: Option<VarDeclaration>,
+    /// Longhands declared with a CSS-wide keyword. [`Style::cascade`] and
+    /// [`Style::inherit_from`] resolve them, except for `inherit` at the root.
+    #[merge_by_method_call]
+    pub css_wide_keywords: CssWideKeywords,
 }
 
+impl Style {
+    /// Apply declarations in cascade order on top of this style, each with the origin
+    /// of its rule.
+    ///
+    /// Custom properties are inherited from `inherited` (the parent's), and `var()`
+    /// references are resolved against the node's final custom properties; a
+    /// declaration whose references can't be resolved is ignored.
+    ///
+    /// `revert` is resolved here, against the declarations of the origins before the
+    /// declaration's; the other CSS-wide keywords unset the longhands they apply to,
+    /// for [`Style::inherit_from`] to resolve.
+    pub fn cascade<'a>(
+        &mut self,
+        declarations: impl IntoIterator<Item = (StyleOrigin, &'a Style)>,
+        inherited: &CustomProperties,
+    ) {
+        let declarations: Vec<(StyleOrigin, &Style)> = declarations.into_iter().collect();
+        // Font properties are inherited, so recompute them rather than keep the last ones.
+        self.font_family = None;
+        self.font_size = None;
+        self.font_weight = None;
+        self.font_style = None;
+        self.line_height = None;
+        self.css_wide_keywords = CssWideKeywords::default();
+        let mut custom_properties = inherited.clone();
+        for (_, declaration) in &declarations {
+            custom_properties.merge(&declaration.custom_properties);
+        }
+        let custom_properties = custom_properties.resolved();
+
+        // Declarations are in origin order, except for `!important` ones; the style as
+        // it was before the first declaration of each origin is what `revert` goes back
+        // to. A `var()` reference may resolve to `revert`, so those count too.
+        let may_revert = declarations.iter().any(|(_, declaration)| {
+            declaration.var_declaration.is_some()
+                || declaration
+                    .css_wide_keywords
+                    .0
+                    .values()
+                    .any(|keyword| *keyword == CssWideKeyword::Revert)
+        });
+        let mut before_origin: Vec<(StyleOrigin, Style)> = Vec::new();
+        for (origin, declaration) in declarations {
+            if may_revert && !before_origin.iter().any(|(before, _)| *before == origin) {
+                before_origin.push((origin, self.clone()));
+            }
+            let resolved;
+            let declaration = match &declaration.var_declaration {
+                Some(var) => match var.resolve(&custom_properties) {
+                    Some(style) => {
+                        resolved = style;
+                        &resolved
+                    }
+                    None => continue,
+                },
+                None => declaration,
+            };
+            let reverted = before_origin
+                .iter()
+                .find(|(before, _)| *before == origin)
+                .map(|(_, style)| style);
+            self.apply_declaration(declaration, reverted);
+        }
+        self.custom_properties = custom_properties;
+    }
+
+    /// Apply one declaration of the cascade, with the style `revert` goes back to.
+    fn apply_declaration(&mut self, declaration: &Style, reverted: Option<&Style>) {
+        // Setting a longhand replaces a keyword it was declared with earlier
+        self.css_wide_keywords
+            .0
+            .retain(|property, _| !longhand_is_set(declaration, property));
+        self.merge(declaration);
+        for (property, keyword) in &declaration.css_wide_keywords.0 {
+            match (keyword, reverted) {
+                (CssWideKeyword::Revert, Some(reverted)) => {
+                    copy_longhand(self, reverted, property);
+                    // With no value to go back to, the longhand is as if never declared
+                    match reverted.css_wide_keywords.get(property) {
+                        Some(keyword) => self.css_wide_keywords.insert(property.clone(), keyword),
+                        None if longhand_is_set(reverted, property) => {
+                            self.css_wide_keywords.0.remove(property);
+                        }
+                        None => self
+                            .css_wide_keywords
+                            .insert(property.clone(), CssWideKeyword::Unset),
+                    }
+                }
+                _ => copy_longhand(self, &Style::default(), property),
+            }
+        }
+    }
+
+    /// The properties children inherit, which make up the whole style of text nodes and
+    /// anonymous flex items.
+    pub(crate) fn inherited(&self) -> Style {
+        Style {
+            color: self.color,
+            font_family: self.font_family.clone(),
+            font_size: self.font_size,
+            font_weight: self.font_weight,
+            font_style: self.font_style,
+            line_height: self.line_height,
+            direction: self.direction,
+            custom_properties: self.custom_properties.clone(),
+            ..Style::default()
+        }
+    }
+
+    /// Take the parent's value of the inherited properties this style doesn't set,
+    /// other than `font-size`, which [`Style::resolve_units`] inherits, unless they are
+    /// declared `initial`. Longhands declared `inherit`, or `unset` if inherited, take
+    /// the parent's value too.
+    pub(crate) fn inherit_from(&mut self, parent: &Style) {
+        for property in [
+            "font-family",
+            "font-weight",
+            "font-style",
+            "line-height",
+            "direction",
+        ] {
+            let initial = self.css_wide_keywords.get(property) == Some(CssWideKeyword::Initial);
+            if !initial && !longhand_is_set(self, property) {
+                copy_longhand(self, parent, property);
+            }
+        }
+
+        let keywords = self.css_wide_keywords.0.clone();
+        for (property, keyword) in keywords {
+            let inherits = match keyword {
+                CssWideKeyword::Inherit => true,
+                CssWideKeyword::Unset => INHERITED_LONGHANDS.contains(&property.as_str()),
+                CssWideKeyword::Initial | CssWideKeyword::Revert => false,
+            };
+            if inherits {
+                copy_longhand(self, parent, &property);
+            }
+        }
+    }
+
+    /// Whether descendants are clipped to the node's box, either by `contain: paint` or
+    /// by a `content-visibility` that implies it.
+    pub fn contains_paint(&self) -> bool {
+        self.contain.is_some_and(|contain| contain.paint)
+            || matches!(
+                self.content_visibility,
+                Some(ContentVisibility::Auto | ContentVisibility::Hidden)
+            )
+    }
+
+    /// Used `overflow-x` and `overflow-y`: if only one axis is `visible`, it behaves as
+    /// `auto`, since a node can't clip along one axis only.
+    pub fn overflow(&self) -> (Overflow, Overflow) {
+        match (
+            self.overflow_x.unwrap_or_default(),
+            self.overflow_y.unwrap_or_default(),
+        ) {
+            (Overflow::Visible, Overflow::Visible) => (Overflow::Visible, Overflow::Visible),
+            (Overflow::Visible, y) => (Overflow::Auto, y),
+            (x, Overflow::Visible) => (x, Overflow::Auto),
+            (x, y) => (x, y),
+        }
+    }
+
+    /// Whether the node clips its children to its padding box and can be scrolled.
+    pub fn is_scroll_container(&self) -> bool {
+        self.overflow() != (Overflow::Visible, Overflow::Visible)
+    }
+
+    /// Resolve `font-size` against the parent's context, then convert font-relative and
+    /// viewport lengths to pixels. Returns the context for the node's own lengths.
+    pub(crate) fn resolve_units(&mut self, parent: &ResolutionContext) -> ResolutionContext {
+        let font_size = match self.css_wide_keywords.get("font-size") {
+            Some(CssWideKeyword::Initial) => parent.initial_font_size,
+            _ => parent.font_size(self.font_size),
+        };
+        self.font_size = Some(Length::Px(font_size));
+        let units = parent.with_font_size(font_size);
+        if let Some(LineHeight::Length(length)) = &mut self.line_height {
+            if let Some(px) = units.resolve(*length, font_size) {
+                *length = Length::Px(px);
+            }
+        }
+
+        let directional = [
+            &mut self.border_width,
+            &mut self.margin,
+            &mut self.padding,
+            &mut self.inset,
+        ];
+        let sides = directional
+            .into_iter()
+            .flat_map(|d| [&mut d.top, &mut d.right, &mut d.bottom, &mut d.left]);
+        let radii = [
+            &mut self.border_radius.top_left,
+            &mut self.border_radius.top_right,
+            &mut self.border_radius.bottom_right,
+            &mut self.border_radius.bottom_left,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(|radius| [&mut radius.x, &mut radius.y]);
+        let tracks = [
+            &mut self.grid_template_columns,
+            &mut self.grid_template_rows,
+        ]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flat_map(GridTrack::lengths_mut);
+        let background_positions = self
+            .background_position
+            .iter_mut()
+            .flatten()
+            .flat_map(|position| [&mut position.x, &mut position.y]);
+        let background_sizes =
+            self.background_size
+                .iter_mut()
+                .flatten()
+                .flat_map(|size| match size {
+                    BackgroundSize::Explicit(width, height) => vec![width, height],
+                    BackgroundSize::Cover | BackgroundSize::Contain => Vec::new(),
+                });
+        let lengths = [
+            &mut self.width,
+            &mut self.height,
+            &mut self.min_width,
+            &mut self.max_width,
+            &mut self.min_height,
+            &mut self.max_height,
+            &mut self.row_gap,
+            &mut self.column_gap,
+            &mut self.flex_basis,
+        ]
+        .into_iter()
+        .chain(sides)
+        .flatten()
+        .chain(radii)
+        .chain(tracks)
+        .chain(background_positions)
+        .chain(background_sizes);
+
+        for length in lengths {
+            length.resolve_units(&units);
+        }
+        units
+    }
+}
+
+#[derive(Clone, Default)]
 pub struct StyleSheet {
+    /// Rules in cascade order: by layer, then in the order they were added.
     pub rules: Vec<Rule>,
+    /// Cascade layer names in the order they were first declared; later layers win,
+    /// and rules outside any layer win over all of them.
+    pub layers: Vec<String>,
+    /// `@keyframes` rules by name; a later rule replaces an earlier one of the same name.
+    pub keyframes: Vec<Keyframes>,
+    /// `@import` rules in source order. Their stylesheets are loaded by the engine, and
+    /// their rules come before these in the cascade.
+    pub imports: Vec<StyleImport>,
+    /// `@font-face` rules in source order.
+    pub font_faces: Vec<FontFace>,
 }
 
 impl StyleSheet {
     pub fn new() -> Self {
-        Self { rules: vec![] }
+        Self::default()
+    }
+
+    /// Declare a cascade layer, fixing its priority if it is new.
+    pub fn declare_layer(&mut self, name: &str) {
+        if !self.layers.iter().any(|layer| layer == name) {
+            self.layers.push(name.to_string());
+        }
     }
 
     pub fn add_rule(&mut self, rule: Rule) {
-        self.rules.push(rule);
+        if let Some(layer) = &rule.layer {
+            self.declare_layer(layer);
+        }
+        let rank = self.layer_rank(&rule);
+        let index = self
+            .rules
+            .partition_point(|existing| self.layer_rank(existing) <= rank);
+        self.rules.insert(index, rule);
+    }
+
+    /// Append the layers and rules of another stylesheet to the cascade.
+    pub fn extend(&mut self, other: &StyleSheet) {
+        for layer in &other.layers {
+            self.declare_layer(layer);
+        }
+        for rule in &other.rules {
+            self.add_rule(rule.clone());
+        }
+        for keyframes in &other.keyframes {
+            self.add_keyframes(keyframes.clone());
+        }
+        self.font_faces.extend(other.font_faces.iter().cloned());
+    }
+
+    pub fn add_keyframes(&mut self, keyframes: Keyframes) {
+        self.keyframes
+            .retain(|existing| existing.name != keyframes.name);
+        self.keyframes.push(keyframes);
+    }
+
+    /// Confine every rule to the subtree under `scope`, if given; see [`Rule::scope`].
+    pub fn scoped_to(mut self, scope: Option<Id>) -> Self {
+        for rule in &mut self.rules {
+            rule.scope = scope;
+        }
+        self
+    }
+
+    /// Place every rule in `origin`; see [`Rule::origin`].
+    pub(crate) fn with_origin(mut self, origin: StyleOrigin) -> Self {
+        for rule in &mut self.rules {
+            rule.origin = origin;
+        }
+        self
+    }
+
+    /// Record that every rule was loaded from the stylesheet `id`.
+    pub(crate) fn loaded_from(mut self, id: StylesheetId) -> Self {
+        for source in self
+            .rules
+            .iter_mut()
+            .filter_map(|rule| rule.source.as_mut())
+        {
+            source.stylesheet = Some(id);
+        }
+        self
+    }
+
+    /// Whether any rule depends on interaction state.
+    pub(crate) fn uses_pseudo_classes(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.selectors.iter().any(Selector::has_pseudo_class))
+    }
+
+    /// Whether any rule depends on sibling position.
+    pub(crate) fn uses_structural_pseudo_classes(&self) -> bool {
+        self.rules.iter().any(|rule| {
+            rule.selectors
+                .iter()
+                .any(Selector::has_structural_pseudo_class)
+        })
+    }
+
+    pub(crate) fn layer_rank(&self, rule: &Rule) -> usize {
+        match &rule.layer {
+            Some(name) => self
+                .layers
+                .iter()
+                .position(|layer| layer == name)
+                .unwrap_or(usize::MAX),
+            None => usize::MAX,
+        }
     }
 }
 
+/// Who a stylesheet comes from, which decides its priority in the cascade ahead of
+/// cascade layers and specificity.
+///
+/// Normal declarations of a later origin override those of an earlier one, whatever
+/// their layer or specificity; for `!important` declarations the order is reversed.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum StyleOrigin {
+    /// Defaults built into the embedding, below everything else.
+    UserAgent,
+    /// Styles shipped by a widget library, meant to be overridden by the application.
+    Library,
+    /// The application's own styles.
+    #[default]
+    Author,
+    /// Styles applied on behalf of the user, such as accessibility preferences.
+    UserOverride,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Rule {
-    pub selector: Selector,
+    /// Selectors of a comma-separated list; the rule applies to nodes matching any.
+    pub selectors: Vec<Selector>,
+    pub declarations: Vec<Style>,
+    /// Declarations marked `!important`, which take precedence over every normal one.
+    #[serde(default)]
+    pub important_declarations: Vec<Style>,
+    /// Cascade layer declared with `@layer`, or `None` for rules outside any layer.
+    #[serde(default)]
+    pub layer: Option<String>,
+    /// Condition of the enclosing `@media` rule, or `None` if the rule always applies.
+    #[serde(default)]
+    pub media: Option<MediaQueryList>,
+    /// Root of the subtree a scoped stylesheet applies to, or `None` for document rules.
+    ///
+    /// Scoped rules match the root and the nodes below it that no nested scope claims;
+    /// document rules and rules of other scopes don't match those nodes.
+    #[serde(skip)]
+    pub scope: Option<Id>,
+    /// Origin of the stylesheet the rule was loaded from, set once it is added to the
+    /// engine.
+    #[serde(skip)]
+    pub origin: StyleOrigin,
+    /// Where the rule and its declarations are in the source, or `None` for rules that
+    /// weren't parsed from CSS.
+    #[serde(default)]
+    pub source: Option<RuleSource>,
+}
+
+/// An `@keyframes` rule, the input of an animation.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Keyframes {
+    pub name: String,
+    /// One keyframe per offset, ordered by offset; keyframes at the same offset stay in
+    /// source order, later ones taking precedence.
+    pub frames: Vec<Keyframe>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// Progress through the animation, from `0.0` for `from` to `1.0` for `to`.
+    pub offset: f64,
     pub declarations: Vec<Style>,
 }
 
-#[derive(Debug, PartialEq)]
+/// An `@import` rule, resolved with [`crate::Engine::set_import_resolver`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StyleImport {
+    /// The URL as written, not resolved against the stylesheet's.
+    pub url: String,
+    pub span: SourceSpan,
+}
+
+/// An `@font-face` rule, loaded with [`crate::Engine::set_font_loader`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FontFace {
+    /// The family name text refers to the font by in `font-family`.
+    pub family: String,
+    /// Where the font can be found, in order of preference.
+    pub src: Vec<FontSource>,
+    /// `font-weight`, `400` unless given.
+    pub weight: u16,
+    pub style: FontStyle,
+    pub span: SourceSpan,
+}
+
+/// An entry of the `src` descriptor of an `@font-face` rule.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FontSource {
+    /// A font file, with the format hint of `format()` if given, e.g. `woff2`.
+    Url { url: String, format: Option<String> },
+    /// A font installed on the system, by full name.
+    Local(String),
+}
+
+/// A range of a stylesheet's source.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSpan {
+    /// Byte offset of the start of the range.
+    pub start: usize,
+    /// Byte offset just past the end of the range.
+    pub end: usize,
+    /// 1-based line of the start of the range.
+    pub line: u32,
+    /// 1-based column of the start of the range.
+    pub column: u32,
+}
+
+/// Source positions of a rule, kept for tooling; see [`crate::Engine::style_sources`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleSource {
+    /// Stylesheet the rule was loaded from, set once it is added to the engine.
+    #[serde(skip)]
+    pub stylesheet: Option<StylesheetId>,
+    /// From the start of the selector list up to the closing brace of the block.
+    pub span: SourceSpan,
+    /// Property name and span of each of [`Rule::declarations`], in the same order.
+    pub declarations: Vec<(String, SourceSpan)>,
+    /// Property name and span of each of [`Rule::important_declarations`].
+    pub important_declarations: Vec<(String, SourceSpan)>,
+}
+
+/// A stylesheet declaration that applies to a node, and where it was declared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyleSource {
+    /// The property as written, e.g. `padding` for a shorthand.
+    pub property: String,
+    pub important: bool,
+    pub stylesheet: Option<StylesheetId>,
+    /// The declaration, from its property name to the end of its value.
+    pub span: SourceSpan,
+    /// The rule containing the declaration.
+    pub rule: SourceSpan,
+    /// Whether declarations later in the cascade set every property this one sets, so
+    /// it has no effect; inspectors show these crossed out.
+    pub overridden: bool,
+}
+
+impl fmt::Display for StyleSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.property)?;
+        if self.important {
+            write!(f, " !important")?;
+        }
+        if let Some(stylesheet) = self.stylesheet {
+            write!(f, " in stylesheet {}", stylesheet.value())?;
+        }
+        write!(f, " at {}:{}", self.span.line, self.span.column)?;
+        if self.overridden {
+            write!(f, " (overridden)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Condition of an `@media` rule: it applies if any of its queries matches.
+///
+/// Queries for media types other than `screen` and `all` never match and aren't kept,
+/// so a list can be empty.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaQueryList {
+    pub queries: Vec<MediaQuery>,
+}
+
+/// A single media query, matching when all of its features do.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaQuery {
+    pub features: Vec<MediaFeature>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MediaFeature {
+    MinWidth(Length),
+    MaxWidth(Length),
+    MinHeight(Length),
+    MaxHeight(Length),
+    PrefersColorScheme(ColorScheme),
+    PrefersReducedMotion(MotionPreference),
+}
+
+/// Color scheme the window prefers, for `prefers-color-scheme`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// Whether the user asked for less motion, for `prefers-reduced-motion`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MotionPreference {
+    #[default]
+    NoPreference,
+    Reduce,
+}
+
+impl MediaQueryList {
+    /// Whether the rule applies in a viewport of this size, in CSS pixels.
+    pub fn matches(
+        &self,
+        viewport: Size,
+        color_scheme: ColorScheme,
+        motion: MotionPreference,
+    ) -> bool {
+        self.queries
+            .iter()
+            .any(|query| query.matches(viewport, color_scheme, motion))
+    }
+}
+
+impl MediaQuery {
+    pub fn matches(
+        &self,
+        viewport: Size,
+        color_scheme: ColorScheme,
+        motion: MotionPreference,
+    ) -> bool {
+        // Relative lengths in media queries refer to the initial font size and the viewport.
+        let units = ResolutionContext::new(viewport);
+        let at_least =
+            |length: Length, actual: f64| units.inline(length).is_some_and(|px| actual >= px);
+        let at_most =
+            |length: Length, actual: f64| units.inline(length).is_some_and(|px| actual <= px);
+        self.features.iter().all(|feature| match *feature {
+            MediaFeature::MinWidth(length) => at_least(length, viewport.width),
+            MediaFeature::MaxWidth(length) => at_most(length, viewport.width),
+            MediaFeature::MinHeight(length) => at_least(length, viewport.height),
+            MediaFeature::MaxHeight(length) => at_most(length, viewport.height),
+            MediaFeature::PrefersColorScheme(scheme) => scheme == color_scheme,
+            MediaFeature::PrefersReducedMotion(preference) => preference == motion,
+        })
+    }
+}
+
+/// How an attribute selector tests the attribute's value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AttributeMatch {
+    /// `[name]`: the attribute is set, to any value.
+    Exists,
+    /// `[name=value]`: the value is exactly `value`.
+    Equals(String),
+    /// `[name~=value]`: `value` is one of the value's whitespace-separated words.
+    Includes(String),
+}
+
+impl AttributeMatch {
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            AttributeMatch::Exists => true,
+            AttributeMatch::Equals(expected) => value == expected,
+            AttributeMatch::Includes(word) => value.split_whitespace().any(|w| w == word),
+        }
+    }
+}
+
+/// The `An+B` pattern of `:nth-child()`, matching the 1-based positions `a * n + b` for
+/// some `n >= 0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Nth {
+    pub a: i32,
+    pub b: i32,
+}
+
+impl Nth {
+    /// The first position only, as in `:first-child`.
+    pub const FIRST: Nth = Nth { a: 0, b: 1 };
+
+    pub fn matches(&self, position: i32) -> bool {
+        let offset = position - self.b;
+        if self.a == 0 {
+            offset == 0
+        } else {
+            offset % self.a == 0 && offset / self.a >= 0
+        }
+    }
+}
+
+/// Specificity of a selector: counts of ids, of classes and attributes, and of types.
+///
+/// Among rules of the same cascade layer, more specific ones win, then later ones.
+pub type Specificity = (u32, u32, u32);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Selector {
+    /// Type selector like `button`, matching nodes created with that tag.
     Tag(String),
     Class(String),
+    /// `#name`, matching the node whose `id` attribute is `name`.
+    Id(String),
+    /// `*`, matching every node.
+    Universal,
+    /// `[name]`, `[name=value]` or `[name~=value]`, testing one of the node's attributes.
+    Attribute(String, AttributeMatch),
+    /// `:hover`, `:active` or `:focus`, matching nodes in that interaction state.
+    PseudoClass(PseudoClass),
+    /// `:nth-child(An+B)` or `:first-child`, matching by position among the element
+    /// siblings, counting from the first.
+    NthChild(Nth),
+    /// `:nth-last-child(An+B)` or `:last-child`, counting from the last sibling.
+    NthLastChild(Nth),
+    /// `::before` or `::after`, always last in its compound selector. Rules with one
+    /// style the box generated for the matching node rather than the node itself.
+    PseudoElement(PseudoElement),
+    /// Simple selectors that must all match the same node, like `button.primary`.
+    Compound(Vec<Selector>),
+    /// `ancestor subject`, matching `subject` anywhere below a node matching `ancestor`.
+    Descendant(Box<Selector>, Box<Selector>),
+    /// `parent > subject`, matching `subject` whose parent matches `parent`.
+    Child(Box<Selector>, Box<Selector>),
+}
+
+impl Selector {
+    pub fn specificity(&self) -> Specificity {
+        let add = |a: Specificity, b: Specificity| (a.0 + b.0, a.1 + b.1, a.2 + b.2);
+        match self {
+            Selector::Id(_) => (1, 0, 0),
+            Selector::Class(_)
+            | Selector::Attribute(..)
+            | Selector::PseudoClass(_)
+            | Selector::NthChild(_)
+            | Selector::NthLastChild(_) => (0, 1, 0),
+            Selector::Tag(_) | Selector::PseudoElement(_) => (0, 0, 1),
+            Selector::Universal => (0, 0, 0),
+            Selector::Compound(selectors) => selectors
+                .iter()
+                .map(Selector::specificity)
+                .fold((0, 0, 0), add),
+            Selector::Descendant(ancestor, subject) | Selector::Child(ancestor, subject) => {
+                add(ancestor.specificity(), subject.specificity())
+            }
+        }
+    }
+
+    /// The pseudo-element the selector ends with, if any.
+    pub fn pseudo_element(&self) -> Option<PseudoElement> {
+        match self {
+            Selector::PseudoElement(pseudo_element) => Some(*pseudo_element),
+            Selector::Compound(selectors) => selectors.last().and_then(Selector::pseudo_element),
+            Selector::Descendant(_, subject) | Selector::Child(_, subject) => {
+                subject.pseudo_element()
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether matching depends on interaction state, so rules need restyling when it
+    /// changes.
+    pub(crate) fn has_pseudo_class(&self) -> bool {
+        match self {
+            Selector::PseudoClass(_) => true,
+            Selector::Compound(selectors) => selectors.iter().any(Selector::has_pseudo_class),
+            Selector::Descendant(ancestor, subject) | Selector::Child(ancestor, subject) => {
+                ancestor.has_pseudo_class() || subject.has_pseudo_class()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether matching depends on the node's position among its siblings, so rules
+    /// need restyling when children are added or moved.
+    pub(crate) fn has_structural_pseudo_class(&self) -> bool {
+        match self {
+            Selector::NthChild(_) | Selector::NthLastChild(_) => true,
+            Selector::Compound(selectors) => {
+                selectors.iter().any(Selector::has_structural_pseudo_class)
+            }
+            Selector::Descendant(ancestor, subject) | Selector::Child(ancestor, subject) => {
+                ancestor.has_structural_pseudo_class() || subject.has_structural_pseudo_class()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A box generated next to a node's children, styled by rules of its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PseudoElement {
+    /// Laid out before the first child.
+    Before,
+    /// Laid out after the last child.
+    After,
+}
+
+/// Interaction state a node can be in, driven by the pointer and keyboard focus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PseudoClass {
+    /// The pointer is over the node or one of its descendants.
+    Hover,
+    /// The left button was pressed over the node or one of its descendants and is still
+    /// held.
+    Active,
+    /// The node has keyboard focus, see [`crate::Engine::focus`].
+    Focus,
 }
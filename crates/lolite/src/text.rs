@@ -1,10 +1,15 @@
 use crate::{
     layout::Size,
-    style::{Length, Style},
+    locale::Locale,
+    style::{FontFace, FontStyle as CssFontStyle, Length, Style},
+    units::DEFAULT_FONT_SIZE_PX,
 };
 use parking_lot::RwLock;
-use skia_safe::{Font, FontMgr, FontStyle};
-use std::{collections::HashMap, sync::Arc};
+use skia_safe::{Font, FontMgr, FontStyle, TextBlob, Typeface};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FontSpec {
@@ -28,6 +33,103 @@ impl FontSpec {
     }
 }
 
+/// What layout assumes about nodes sized by the text heuristic, until the engine has
+/// typed text nodes. Set with [`crate::Engine::set_text_node_defaults`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextNodeDefaults {
+    /// Border-box size of leaves without an explicit size whose content can't be
+    /// measured: they have no text and no measure function.
+    pub size: Size,
+    /// Font family of nodes that don't set `font-family`, followed by the fallback fonts
+    /// of the window's locale.
+    pub font_family: String,
+    /// Font size of the root in pixels, inherited by nodes that don't set `font-size`.
+    pub font_size: f64,
+    /// Whether text wraps to fit an explicit width; otherwise the height is that of a
+    /// single line.
+    pub wrap: bool,
+}
+
+impl TextNodeDefaults {
+    /// Give a resolved style the default font family if it has none, with the fallback
+    /// fonts of `locale` for text the default family has no glyphs for.
+    pub(crate) fn apply(&self, style: &mut Style, locale: &Locale) {
+        if style.font_family.is_none() {
+            let families = std::iter::once(self.font_family.as_str())
+                .chain(locale.font_fallbacks().iter().copied());
+            style.font_family = Some(families.collect::<Vec<_>>().join(", "));
+        }
+    }
+
+    /// Size of a text node's content: wrapped to `max_width` if given and wrapping is on.
+    pub(crate) fn measure(
+        &self,
+        measurer: &dyn TextMeasurer,
+        text: &str,
+        font: &FontSpec,
+        max_width: Option<f64>,
+    ) -> Size {
+        match max_width {
+            Some(max_width) if self.wrap => measurer.measure_wrapped(text, font, max_width),
+            _ => measurer.measure_unwrapped(text, font),
+        }
+    }
+}
+
+impl Default for TextNodeDefaults {
+    fn default() -> Self {
+        Self {
+            size: Size {
+                width: 100.0,
+                height: 30.0,
+            },
+            font_family: "Arial".to_string(),
+            font_size: DEFAULT_FONT_SIZE_PX,
+            wrap: true,
+        }
+    }
+}
+
+/// Apply the white space processing rules of `white-space: normal` to text laid out on
+/// one line.
+pub(crate) fn collapse_white_space(text: &str) -> String {
+    collapse_white_space_run([text]).remove(0)
+}
+
+/// Like [`collapse_white_space`], for a line made of consecutive pieces of text that may
+/// belong to different nodes.
+///
+/// Each sequence of spaces, tabs and line breaks collapses to a single space, even
+/// across pieces, and spaces at the start and end of the line are removed.
+pub(crate) fn collapse_white_space_run<'a>(
+    pieces: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let mut collapsed: Vec<String> = Vec::new();
+    // At the start of the line, spaces are removed as if they followed another one
+    let mut after_space = true;
+    for piece in pieces {
+        let mut out = String::with_capacity(piece.len());
+        for c in piece.chars() {
+            if matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C') {
+                if !after_space {
+                    out.push(' ');
+                    after_space = true;
+                }
+            } else {
+                out.push(c);
+                after_space = false;
+            }
+        }
+        collapsed.push(out);
+    }
+    if let Some(last) = collapsed.iter_mut().rev().find(|piece| !piece.is_empty()) {
+        if last.ends_with(' ') {
+            last.pop();
+        }
+    }
+    collapsed
+}
+
 pub trait TextMeasurer: Send + Sync {
     /// Called at the start of a layout pass.
     ///
@@ -42,8 +144,29 @@ pub trait TextMeasurer: Send + Sync {
 
     fn measure_unwrapped(&self, text: &str, font: &FontSpec) -> Size;
     fn measure_wrapped(&self, text: &str, font: &FontSpec, max_width_px: f64) -> Size;
+
+    /// Make the font file `data` available under the family of `face`, as loaded for an
+    /// `@font-face` rule. Returns whether the font could be read.
+    fn register_font_face(&self, _face: &FontFace, _data: &[u8]) -> bool {
+        false
+    }
 }
 
+/// Text shaped into glyphs with a font, shared by layout (measurement) and paint.
+pub(crate) struct ShapedText {
+    /// Glyphs positioned relative to the baseline origin; `None` for empty text.
+    pub blob: Option<TextBlob>,
+    pub width: f64,
+    /// Distance from the top of the line to the baseline.
+    pub ascent: f64,
+    pub line_height: f64,
+}
+
+/// Measures text with Skia, caching shaped text by (text, font, size).
+///
+/// Clones share one cache, so text measured during layout isn't shaped again to be
+/// painted. Editing a node's text or font shapes the new combination; entries nobody
+/// used during a layout pass or since the previous one are dropped at the end of it.
 #[derive(Clone, Default)]
 pub struct SkiaTextMeasurer {
     cache: Arc<RwLock<CacheState>>,
@@ -52,78 +175,127 @@ pub struct SkiaTextMeasurer {
 #[derive(Default)]
 struct CacheState {
     epoch: u64,
-    map: HashMap<CacheKey, CacheEntry>,
+    /// Installed typeface of each family looked up, `None` for missing families.
+    typefaces: HashMap<String, Option<Typeface>>,
+    /// How far the face loaded for each `@font-face` family is from a normal weight and
+    /// style. Fonts are always used at their normal weight and style, so a family is
+    /// drawn with its face closest to them.
+    font_faces: HashMap<String, u32>,
+    shaped: HashMap<CacheKey, CacheEntry>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 struct CacheEntry {
-    size: Size,
+    shaped: Arc<ShapedText>,
     last_used_epoch: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct CacheKey {
     text: String,
-    family: String,
-    size_px: u32,
-    max_width_px_rounded: u32,
+    font: FontSpec,
 }
 
+static SHARED_MEASURER: LazyLock<SkiaTextMeasurer> = LazyLock::new(SkiaTextMeasurer::new);
+
 impl SkiaTextMeasurer {
-    #[allow(unused)]
     pub fn new() -> Self {
         Self {
             cache: Arc::new(RwLock::new(CacheState::default())),
         }
     }
 
-    pub(crate) fn make_font(font: &FontSpec) -> Font {
-        let typeface = FontMgr::default()
-            .match_family(&font.family)
+    /// The measurer used for layout and painting in every window.
+    pub(crate) fn shared() -> Self {
+        SHARED_MEASURER.clone()
+    }
+
+    /// The installed typeface of a family, if any.
+    fn find_typeface(family: &str) -> Option<Typeface> {
+        FontMgr::default()
+            .match_family(family)
             .match_style(FontStyle::normal())
-            .unwrap_or_else(|| {
-                // Fallback typeface.
-                FontMgr::default()
-                    .legacy_make_typeface(None, FontStyle::normal())
-                    .expect("Failed to load any typeface")
-            });
+    }
 
-        Font::new(typeface, font.size_px as f32)
+    /// Typeface to shape `text` with, given a comma-separated list of families: the
+    /// first installed family with glyphs for all of the text, else the first installed
+    /// family, else the default typeface.
+    fn select_typeface(
+        typefaces: &mut HashMap<String, Option<Typeface>>,
+        families: &str,
+        text: &str,
+    ) -> Typeface {
+        let mut first_installed = None;
+        for family in families.split(',') {
+            let family = family.trim().trim_matches(['"', '\'']);
+            if family.is_empty() {
+                continue;
+            }
+            let Some(typeface) = typefaces
+                .entry(family.to_string())
+                .or_insert_with(|| Self::find_typeface(family))
+            else {
+                continue;
+            };
+            let covers_text = text
+                .chars()
+                .filter(|c| !c.is_whitespace() && !c.is_control())
+                .all(|c| typeface.unichar_to_glyph(c as i32) != 0);
+            if covers_text {
+                return typeface.clone();
+            }
+            first_installed.get_or_insert_with(|| typeface.clone());
+        }
+        first_installed.unwrap_or_else(|| {
+            FontMgr::default()
+                .legacy_make_typeface(None, FontStyle::normal())
+                .expect("Failed to load any typeface")
+        })
     }
 
-    fn measure_unwrapped_uncached(&self, text: &str, font: &FontSpec) -> Size {
-        let font = Self::make_font(font);
+    /// Shape `text`, reusing the result of a previous call with the same text and font.
+    pub(crate) fn shape(&self, text: &str, font: &FontSpec) -> Arc<ShapedText> {
+        let key = CacheKey {
+            text: text.to_string(),
+            font: font.clone(),
+        };
 
+        let mut state = self.cache.write();
+        let epoch = state.epoch;
+
+        if let Some(entry) = state.shaped.get_mut(&key) {
+            entry.last_used_epoch = epoch;
+            return entry.shaped.clone();
+        }
+
+        // Cache miss.
+        let typeface = Self::select_typeface(&mut state.typefaces, &font.family, text);
+        let shaped = Arc::new(Self::shape_uncached(
+            text,
+            &Font::new(typeface, font.size_px as f32),
+        ));
+        state.shaped.insert(
+            key,
+            CacheEntry {
+                shaped: shaped.clone(),
+                last_used_epoch: epoch,
+            },
+        );
+        shaped
+    }
+
+    fn shape_uncached(text: &str, font: &Font) -> ShapedText {
         // `measure_str` gives us an advance width; height comes from font metrics.
         let (advance_width, _bounds) = font.measure_str(text, None);
 
         let (_scale, metrics) = font.metrics();
         let height = (metrics.descent - metrics.ascent + metrics.leading) as f64;
 
-        Size {
+        ShapedText {
+            blob: TextBlob::from_str(text, font),
             width: advance_width as f64,
-            height: height.max(0.0),
-        }
-    }
-
-    fn measure_wrapped_uncached(&self, text: &str, font: &FontSpec, max_width_px: f64) -> Size {
-        // NOTE: Skia has a proper paragraph layout API, but Lolite doesn’t depend on it yet.
-        // This approximation is good enough to drive basic layout decisions.
-        let max_width_px = max_width_px.max(0.0);
-        if max_width_px == 0.0 {
-            return Size::default();
-        }
-
-        let unwrapped = self.measure_unwrapped_uncached(text, font);
-        if unwrapped.width <= max_width_px {
-            return unwrapped;
-        }
-
-        // Naive wrapping: estimate number of lines by width ratio.
-        let lines = (unwrapped.width / max_width_px).ceil().max(1.0);
-        Size {
-            width: max_width_px,
-            height: unwrapped.height * lines,
+            ascent: -metrics.ascent as f64,
+            line_height: height.max(0.0),
         }
     }
 }
@@ -137,66 +309,69 @@ impl TextMeasurer for SkiaTextMeasurer {
     fn end_layout_pass_and_sweep(&self) {
         let mut state = self.cache.write();
         let epoch = state.epoch;
-        state.map.retain(|_, entry| entry.last_used_epoch == epoch);
+        // Keep what was painted since the previous pass, even if layout didn't need it
+        state
+            .shaped
+            .retain(|_, entry| epoch.wrapping_sub(entry.last_used_epoch) <= 1);
     }
 
-    fn measure_unwrapped(&self, text: &str, font: &FontSpec) -> Size {
-        let key = CacheKey {
-            text: text.to_string(),
-            family: font.family.clone(),
-            size_px: font.size_px,
-            max_width_px_rounded: 0,
+    fn register_font_face(&self, face: &FontFace, data: &[u8]) -> bool {
+        let Some(typeface) = FontMgr::default().new_from_data(data, None) else {
+            return false;
         };
-
+        let style_distance = match face.style {
+            CssFontStyle::Normal => 0,
+            CssFontStyle::Italic | CssFontStyle::Oblique => 1000,
+        };
+        let distance = u32::from(face.weight.abs_diff(400)) + style_distance;
         let mut state = self.cache.write();
-        let epoch = state.epoch;
-
-        if let Some(entry) = state.map.get_mut(&key) {
-            entry.last_used_epoch = epoch;
-            return entry.size;
+        if state
+            .font_faces
+            .get(&face.family)
+            .is_some_and(|&existing| existing < distance)
+        {
+            return true;
         }
+        state.font_faces.insert(face.family.clone(), distance);
+        state.typefaces.insert(face.family.clone(), Some(typeface));
+        // Text may have been shaped with a fallback for this family
+        state.shaped.clear();
+        true
+    }
 
-        // Cache miss.
-        let size = self.measure_unwrapped_uncached(text, font);
-        state.map.insert(
-            key,
-            CacheEntry {
-                size,
-                last_used_epoch: epoch,
-            },
-        );
-        size
+    fn measure_unwrapped(&self, text: &str, font: &FontSpec) -> Size {
+        let shaped = self.shape(text, font);
+        Size {
+            width: shaped.width,
+            height: shaped.line_height,
+        }
     }
 
     fn measure_wrapped(&self, text: &str, font: &FontSpec, max_width_px: f64) -> Size {
-        let key = CacheKey {
-            text: text.to_string(),
-            family: font.family.clone(),
-            size_px: font.size_px,
-            max_width_px_rounded: max_width_px.round().clamp(0.0, 1_000_000.0) as u32,
-        };
-
-        let mut state = self.cache.write();
-        let epoch = state.epoch;
+        // NOTE: Skia has a proper paragraph layout API, but Lolite doesn’t depend on it yet.
+        // This approximation is good enough to drive basic layout decisions.
+        let max_width_px = max_width_px.max(0.0);
+        if max_width_px == 0.0 {
+            return Size::default();
+        }
 
-        if let Some(entry) = state.map.get_mut(&key) {
-            entry.last_used_epoch = epoch;
-            return entry.size;
+        let unwrapped = self.measure_unwrapped(text, font);
+        if unwrapped.width <= max_width_px {
+            return unwrapped;
         }
 
-        // Cache miss.
-        let size = self.measure_wrapped_uncached(text, font, max_width_px);
-        state.map.insert(
-            key,
-            CacheEntry {
-                size,
-                last_used_epoch: epoch,
-            },
-        );
-        size
+        // Naive wrapping: estimate number of lines by width ratio.
+        let lines = (unwrapped.width / max_width_px).ceil().max(1.0);
+        Size {
+            width: max_width_px,
+            height: unwrapped.height * lines,
+        }
     }
 }
 
+#[cfg(test)]
+mod text_tests;
+
 #[cfg(test)]
 #[derive(Clone, Default)]
 #[allow(unused)]
@@ -242,6 +417,6 @@ pub fn default_text_measurer() -> Arc<dyn TextMeasurer> {
 
     #[cfg(not(test))]
     {
-        Arc::new(SkiaTextMeasurer::new())
+        Arc::new(SkiaTextMeasurer::shared())
     }
 }
@@ -0,0 +1,118 @@
+use super::*;
+use crate::css_parser::parse_css;
+use crate::layout::LayoutContext;
+use crate::Id;
+
+fn font(size_px: u32) -> FontSpec {
+    FontSpec {
+        family: "Arial".to_string(),
+        size_px,
+    }
+}
+
+#[test]
+fn test_shaping_is_cached_per_text_and_font() {
+    let measurer = SkiaTextMeasurer::new();
+    let first = measurer.shape("Label", &font(12));
+    assert!(Arc::ptr_eq(&first, &measurer.shape("Label", &font(12))));
+    // Clones share the cache, as layout and paint do
+    assert!(Arc::ptr_eq(
+        &first,
+        &measurer.clone().shape("Label", &font(12))
+    ));
+
+    assert!(!Arc::ptr_eq(&first, &measurer.shape("Label!", &font(12))));
+    assert!(!Arc::ptr_eq(&first, &measurer.shape("Label", &font(14))));
+    assert!(measurer.shape("", &font(12)).blob.is_none());
+}
+
+#[test]
+fn test_sweep_keeps_text_used_since_the_previous_pass() {
+    let measurer = SkiaTextMeasurer::new();
+    measurer.begin_layout_pass();
+    let measured = measurer.shape("Measured", &font(12));
+    measurer.end_layout_pass_and_sweep();
+    // Painted between passes
+    let painted = measurer.shape("Painted", &font(12));
+
+    measurer.begin_layout_pass();
+    measurer.end_layout_pass_and_sweep();
+    assert!(Arc::ptr_eq(&painted, &measurer.shape("Painted", &font(12))));
+
+    // Unused for a whole pass and the time since the one before
+    measurer.begin_layout_pass();
+    measurer.end_layout_pass_and_sweep();
+    measurer.begin_layout_pass();
+    measurer.end_layout_pass_and_sweep();
+    assert!(!Arc::ptr_eq(
+        &measured,
+        &measurer.shape("Measured", &font(12))
+    ));
+}
+
+#[test]
+fn test_layout_uses_text_node_defaults() {
+    let mut ctx = LayoutContext::new();
+    let css = ".root { display: flex; align-items: flex-start; } .fixed { width: 50px; }";
+    for rule in parse_css(css).unwrap().rules {
+        ctx.style_sheet.add_rule(rule);
+    }
+    let root = ctx.document.root_id();
+    ctx.document
+        .set_attribute(root, "class".to_owned(), "root".to_owned());
+    let empty = ctx.document.create_node(Id::from_u64(1), None);
+    let text = ctx
+        .document
+        .create_node(Id::from_u64(2), Some("abcdefghij".to_owned()));
+    ctx.document
+        .set_attribute(text, "class".to_owned(), "fixed".to_owned());
+    for node in [empty, text] {
+        ctx.document.set_parent(root, node).unwrap();
+    }
+    ctx.text_node_defaults = TextNodeDefaults {
+        size: Size {
+            width: 40.0,
+            height: 10.0,
+        },
+        font_family: "Inter".to_string(),
+        font_size: 10.0,
+        wrap: true,
+    };
+    let layout = |ctx: &mut LayoutContext| {
+        ctx.document.reset_styles();
+        ctx.layout();
+        [empty, text].map(|id| {
+            let node = ctx.document.get_node(id).unwrap();
+            let node = node.borrow();
+            let style = node.layout.style.clone();
+            (node.layout.bounds, style)
+        })
+    };
+
+    let [(empty_bounds, _), (text_bounds, text_style)] = layout(&mut ctx);
+    assert_eq!((empty_bounds.width, empty_bounds.height), (40.0, 10.0));
+    // Ten 6px glyphs wrap onto two 12px lines
+    assert_eq!((text_bounds.width, text_bounds.height), (50.0, 24.0));
+    assert_eq!(text_style.font_family.as_deref(), Some("Inter"));
+    assert_eq!(text_style.font_size, Some(Length::Px(10.0)));
+
+    ctx.text_node_defaults.wrap = false;
+    let [_, (text_bounds, _)] = layout(&mut ctx);
+    assert_eq!((text_bounds.width, text_bounds.height), (50.0, 12.0));
+}
+
+#[test]
+fn test_collapse_white_space() {
+    assert_eq!(
+        collapse_white_space("  hello \t\n  world \n"),
+        "hello world"
+    );
+    assert_eq!(collapse_white_space(" \n\t "), "");
+
+    // Spaces collapse across pieces, and the line is trimmed at its edges
+    assert_eq!(
+        collapse_white_space_run([" a ", " b", "", "  ", "c  "]),
+        vec!["a ", "b", "", " ", "c"]
+    );
+    assert_eq!(collapse_white_space_run(["a  ", "  "]), vec!["a", ""]);
+}
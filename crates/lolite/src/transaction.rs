@@ -0,0 +1,118 @@
+use crate::{commands::Command, style::StyleOrigin, Id, StylesheetId};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// A set of document mutations applied atomically by [`crate::Engine::transaction`].
+///
+/// Mutations are recorded in order and sent to the command thread as a single batch,
+/// which triggers exactly one layout pass once all of them have been applied.
+pub struct Transaction {
+    commands: Vec<Command>,
+    next_stylesheet_id: Arc<AtomicU64>,
+}
+
+impl Transaction {
+    pub(crate) fn new(next_stylesheet_id: Arc<AtomicU64>) -> Self {
+        Self {
+            commands: Vec::new(),
+            next_stylesheet_id,
+        }
+    }
+
+    pub(crate) fn into_commands(self) -> Vec<Command> {
+        self.commands
+    }
+
+    /// Add a CSS stylesheet, returning a handle to remove or replace it later
+    pub fn add_stylesheet(&mut self, css_content: &str) -> StylesheetId {
+        self.add_stylesheet_with_origin(StyleOrigin::Author, css_content)
+    }
+
+    /// Add a stylesheet whose rules take part in the cascade as `origin`
+    pub fn add_stylesheet_with_origin(
+        &mut self,
+        origin: StyleOrigin,
+        css_content: &str,
+    ) -> StylesheetId {
+        let id = StylesheetId(self.next_stylesheet_id.fetch_add(1, Ordering::Relaxed));
+        self.commands.push(Command::AddStylesheet(
+            id,
+            None,
+            origin,
+            css_content.to_string(),
+        ));
+        id
+    }
+
+    /// Add a stylesheet whose rules only apply to `root` and the nodes below it
+    pub fn add_scoped_stylesheet(&mut self, root: Id, css_content: &str) -> StylesheetId {
+        let id = StylesheetId(self.next_stylesheet_id.fetch_add(1, Ordering::Relaxed));
+        self.commands.push(Command::AddStylesheet(
+            id,
+            Some(root),
+            StyleOrigin::Author,
+            css_content.to_string(),
+        ));
+        id
+    }
+
+    /// Remove a stylesheet and all of its rules from the cascade
+    pub fn remove_stylesheet(&mut self, id: StylesheetId) {
+        self.commands.push(Command::RemoveStylesheet(id));
+    }
+
+    /// Replace the rules of a stylesheet, keeping its position in the cascade
+    pub fn replace_stylesheet(&mut self, id: StylesheetId, css_content: &str) {
+        self.commands
+            .push(Command::ReplaceStylesheet(id, css_content.to_string()));
+    }
+
+    /// Create a new document node with optional text content
+    pub fn create_node(&mut self, id: Id, text: Option<String>) -> Id {
+        self.commands.push(Command::CreateNode(id, None, text));
+        id
+    }
+
+    /// Create a new document node with a tag matched by type selectors like `button`
+    pub fn create_node_with_tag(&mut self, id: Id, tag: &str, text: Option<String>) -> Id {
+        self.commands
+            .push(Command::CreateNode(id, Some(tag.to_string()), text));
+        id
+    }
+
+    /// Create a text node, see [`crate::Engine::create_text_node`]
+    pub fn create_text_node(&mut self, id: Id, text: &str) -> Id {
+        self.commands
+            .push(Command::CreateTextNode(id, text.to_string()));
+        id
+    }
+
+    /// Set a parent-child relationship between nodes
+    pub fn set_parent(&mut self, parent_id: Id, child_id: Id) {
+        self.commands.push(Command::SetParent(parent_id, child_id));
+    }
+
+    /// Set an attribute on a node
+    pub fn set_attribute(&mut self, node_id: Id, key: String, value: String) {
+        self.commands
+            .push(Command::SetAttribute(node_id, key, value));
+    }
+
+    /// Name a node for debugging
+    pub fn set_debug_name(&mut self, id: Id, name: &str) {
+        self.commands
+            .push(Command::SetDebugName(id, name.to_string()));
+    }
+
+    /// Number of mutations recorded so far
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether no mutations have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
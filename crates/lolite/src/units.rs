@@ -0,0 +1,120 @@
+use crate::layout::Size;
+use crate::style::{Directional, Length};
+
+/// Font size used when none is set, in pixels.
+pub(crate) const DEFAULT_FONT_SIZE_PX: f64 = 12.0;
+
+/// Everything a length may be relative to when resolving it to a used value in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ResolutionContext {
+    /// Font size of the element, for `em`.
+    pub font_size: f64,
+    /// Font size of the root element, for `rem`.
+    pub root_font_size: f64,
+    /// Font size of a root that doesn't set one, for `font-size: initial`.
+    pub initial_font_size: f64,
+    /// For `vw`, `vh`, `vmin` and `vmax`.
+    pub viewport: Size,
+    /// Content box of the containing block, for percentages.
+    pub containing_block: Size,
+}
+
+impl ResolutionContext {
+    /// Context for the root element, whose containing block is the viewport.
+    pub fn new(viewport: Size) -> Self {
+        Self {
+            font_size: DEFAULT_FONT_SIZE_PX,
+            root_font_size: DEFAULT_FONT_SIZE_PX,
+            initial_font_size: DEFAULT_FONT_SIZE_PX,
+            viewport,
+            containing_block: viewport,
+        }
+    }
+
+    pub fn with_font_size(self, font_size: f64) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn with_root_font_size(self, root_font_size: f64) -> Self {
+        Self {
+            root_font_size,
+            ..self
+        }
+    }
+
+    pub fn with_initial_font_size(self, initial_font_size: f64) -> Self {
+        Self {
+            initial_font_size,
+            ..self
+        }
+    }
+
+    pub fn with_containing_block(self, containing_block: Size) -> Self {
+        Self {
+            containing_block,
+            ..self
+        }
+    }
+
+    /// Used value of `length` in pixels, with percentages taken of `percent_basis`.
+    ///
+    /// Returns `None` for `auto`, whose meaning depends on the property.
+    pub fn resolve(&self, length: Length, percent_basis: f64) -> Option<f64> {
+        match length {
+            Length::Auto => None,
+            Length::Px(value) => Some(value),
+            Length::Em(value) => Some(value * self.font_size),
+            Length::Rem(value) => Some(value * self.root_font_size),
+            Length::Percent(value) => Some(value / 100.0 * percent_basis),
+            Length::Vw(value) => Some(value / 100.0 * self.viewport.width),
+            Length::Vh(value) => Some(value / 100.0 * self.viewport.height),
+            Length::Vmin(value) => {
+                Some(value / 100.0 * self.viewport.width.min(self.viewport.height))
+            }
+            Length::Vmax(value) => {
+                Some(value / 100.0 * self.viewport.width.max(self.viewport.height))
+            }
+        }
+    }
+
+    /// A length whose percentages refer to the containing block's width, like `width`
+    /// and, on every side, `margin` and `padding`.
+    pub fn inline(&self, length: Length) -> Option<f64> {
+        self.resolve(length, self.containing_block.width)
+    }
+
+    /// A length whose percentages refer to the containing block's height, like `height`.
+    pub fn block(&self, length: Length) -> Option<f64> {
+        self.resolve(length, self.containing_block.height)
+    }
+
+    /// Like [`Self::inline`], treating `auto` as zero.
+    pub fn inline_or_zero(&self, length: Length) -> f64 {
+        self.inline(length).unwrap_or(0.0)
+    }
+
+    /// Total of the left and right sides of a margin, padding or border.
+    pub fn horizontal(&self, sides: &Directional<Length>) -> f64 {
+        self.inline_or_zero(sides.left) + self.inline_or_zero(sides.right)
+    }
+
+    /// Total of the top and bottom sides of a margin, padding or border.
+    ///
+    /// Percentages still refer to the containing block's width, as in CSS.
+    pub fn vertical(&self, sides: &Directional<Length>) -> f64 {
+        self.inline_or_zero(sides.top) + self.inline_or_zero(sides.bottom)
+    }
+
+    /// Used `font-size` of an element, where this context holds its parent's font size.
+    ///
+    /// Unset, `auto` and non-positive sizes inherit the parent's size.
+    pub fn font_size(&self, font_size: Option<Length>) -> f64 {
+        match font_size.and_then(|size| self.resolve(size, self.font_size)) {
+            Some(px) if px > 0.0 => px,
+            _ => self.font_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod units_tests;
@@ -0,0 +1,81 @@
+use super::*;
+
+fn context() -> ResolutionContext {
+    ResolutionContext::new(Size {
+        width: 800.0,
+        height: 600.0,
+    })
+    .with_containing_block(Size {
+        width: 200.0,
+        height: 100.0,
+    })
+    .with_font_size(20.0)
+}
+
+#[test]
+fn test_new_uses_viewport_as_containing_block() {
+    let viewport = Size {
+        width: 800.0,
+        height: 600.0,
+    };
+    let units = ResolutionContext::new(viewport);
+
+    assert_eq!(units.containing_block, viewport);
+    assert_eq!(units.font_size, DEFAULT_FONT_SIZE_PX);
+    assert_eq!(units.root_font_size, DEFAULT_FONT_SIZE_PX);
+}
+
+#[test]
+fn test_resolve_each_unit() {
+    let units = context();
+
+    assert_eq!(units.resolve(Length::Auto, 50.0), None);
+    assert_eq!(units.resolve(Length::Px(7.5), 50.0), Some(7.5));
+    assert_eq!(units.resolve(Length::Em(1.5), 50.0), Some(30.0));
+    assert_eq!(units.resolve(Length::Rem(2.0), 50.0), Some(24.0));
+    assert_eq!(units.resolve(Length::Percent(10.0), 50.0), Some(5.0));
+    assert_eq!(units.resolve(Length::Vw(10.0), 50.0), Some(80.0));
+    assert_eq!(units.resolve(Length::Vh(10.0), 50.0), Some(60.0));
+    assert_eq!(units.resolve(Length::Vmin(10.0), 50.0), Some(60.0));
+    assert_eq!(units.resolve(Length::Vmax(10.0), 50.0), Some(80.0));
+    assert_eq!(units.resolve(Length::Px(-4.0), 50.0), Some(-4.0));
+}
+
+#[test]
+fn test_percentages_refer_to_the_containing_block() {
+    let units = context();
+
+    assert_eq!(units.inline(Length::Percent(50.0)), Some(100.0));
+    assert_eq!(units.block(Length::Percent(50.0)), Some(50.0));
+    assert_eq!(units.inline(Length::Auto), None);
+    assert_eq!(units.inline_or_zero(Length::Auto), 0.0);
+}
+
+#[test]
+fn test_side_totals() {
+    let units = context();
+    let sides = Directional {
+        top: Length::Percent(10.0),
+        right: Length::Px(3.0),
+        bottom: Length::Em(1.0),
+        left: Length::Auto,
+    };
+
+    assert_eq!(units.horizontal(&sides), 3.0);
+    // Vertical percentages also refer to the containing block's width
+    assert_eq!(units.vertical(&sides), 40.0);
+}
+
+#[test]
+fn test_font_size_is_relative_to_the_parent() {
+    let units = context();
+
+    assert_eq!(units.font_size(None), 20.0);
+    assert_eq!(units.font_size(Some(Length::Px(14.0))), 14.0);
+    assert_eq!(units.font_size(Some(Length::Em(2.0))), 40.0);
+    assert_eq!(units.font_size(Some(Length::Percent(50.0))), 10.0);
+    assert_eq!(units.font_size(Some(Length::Vw(1.0))), 8.0);
+    assert_eq!(units.font_size(Some(Length::Auto)), 20.0);
+    assert_eq!(units.font_size(Some(Length::Px(0.0))), 20.0);
+    assert_eq!(units.font_size(Some(Length::Px(-3.0))), 20.0);
+}
@@ -1,16 +1,102 @@
-use crate::backend::{BackendType, RenderingBackend};
 use std::sync::{Arc, Mutex};
-use winit::event_loop::EventLoopProxy;
+#[cfg(feature = "windowing")]
+use {
+    crate::backend::{BackendOptions, BackendReport, BackendType, RenderingBackend},
+    crate::input::KeyEvent,
+    crate::style::ColorScheme,
+    crate::zoom::ZoomStep,
+    std::collections::{HashMap, HashSet},
+    std::time::{Duration, Instant},
+    winit::{
+        dpi::{LogicalSize, Size},
+        event_loop::EventLoopProxy,
+        window::WindowAttributes,
+    },
+};
 
 // Re-export types
+#[cfg(feature = "windowing")]
 pub use crate::backend::Params;
 
+/// Configuration of the window created by [`crate::Engine::run`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowOptions {
+    pub title: String,
+    /// Initial inner size, in logical pixels.
+    pub inner_size: (f64, f64),
+    pub min_inner_size: Option<(f64, f64)>,
+    pub max_inner_size: Option<(f64, f64)>,
+    pub resizable: bool,
+    /// Whether the window has a title bar and borders.
+    pub decorations: bool,
+    /// Whether Ctrl+= / Ctrl+- / Ctrl+0 (Cmd on macOS) zoom the page, see
+    /// [`crate::Engine::set_zoom`].
+    pub zoom_shortcuts: bool,
+}
+
+impl Default for WindowOptions {
+    fn default() -> Self {
+        Self {
+            title: "Lolite CSS".to_string(),
+            inner_size: (800.0, 800.0),
+            min_inner_size: None,
+            max_inner_size: None,
+            resizable: true,
+            decorations: true,
+            zoom_shortcuts: false,
+        }
+    }
+}
+
+#[cfg(feature = "windowing")]
+impl WindowOptions {
+    fn to_window_attributes(&self) -> WindowAttributes {
+        let logical = |(width, height): (f64, f64)| Size::new(LogicalSize::new(width, height));
+        let mut attributes = WindowAttributes::default()
+            .with_title(self.title.clone())
+            .with_inner_size(logical(self.inner_size))
+            .with_resizable(self.resizable)
+            .with_decorations(self.decorations);
+        if let Some(size) = self.min_inner_size {
+            attributes = attributes.with_min_inner_size(logical(size));
+        }
+        if let Some(size) = self.max_inner_size {
+            attributes = attributes.with_max_inner_size(logical(size));
+        }
+        attributes
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum WindowMessage {
+    /// Redraw every open window.
     Redraw,
+    /// Open the window with the given engine window id.
+    OpenWindow(u64),
+    Exit,
 }
 
-pub struct WindowMessageSender(Arc<Mutex<Option<EventLoopProxy<WindowMessage>>>>);
+/// The windows driven by the event loop, identified by engine window ids.
+#[cfg(feature = "windowing")]
+pub struct Windows {
+    /// Ids of the windows to open, read once the event loop has started.
+    pub ids: Box<dyn Fn() -> Vec<u64>>,
+    /// Create the params and options of a window.
+    pub open: Box<dyn FnMut(u64) -> (Params, WindowOptions)>,
+    /// Called once the backend has been chosen, when the first window opens.
+    pub on_backend: Box<dyn FnMut(BackendReport)>,
+}
+
+#[derive(Default)]
+struct SenderState {
+    #[cfg(feature = "windowing")]
+    proxy: Option<EventLoopProxy<WindowMessage>>,
+    // An exit requested before the event loop started is delivered once it does.
+    #[cfg(feature = "windowing")]
+    exit_pending: bool,
+}
+
+pub struct WindowMessageSender(Arc<Mutex<SenderState>>);
 
 impl Clone for WindowMessageSender {
     fn clone(&self) -> Self {
@@ -20,65 +106,82 @@ impl Clone for WindowMessageSender {
 
 impl WindowMessageSender {
     pub fn new() -> Self {
-        Self(Arc::new(Mutex::new(None)))
+        Self(Arc::new(Mutex::new(SenderState::default())))
     }
 
+    #[cfg(feature = "windowing")]
     pub(crate) fn set_proxy(&self, proxy: EventLoopProxy<WindowMessage>) {
-        *self.0.lock().unwrap() = Some(proxy);
+        let mut state = self.0.lock().unwrap();
+        if state.exit_pending {
+            let _ = proxy.send_event(WindowMessage::Exit);
+        }
+        state.proxy = Some(proxy);
     }
 
+    #[cfg(feature = "windowing")]
     pub fn send(&self, message: WindowMessage) {
-        if let Some(proxy) = self.0.lock().unwrap().as_ref() {
-            let _ = proxy.send_event(message);
+        let mut state = self.0.lock().unwrap();
+        match state.proxy.as_ref() {
+            Some(proxy) => {
+                let _ = proxy.send_event(message);
+            }
+            None => {
+                if matches!(message, WindowMessage::Exit) {
+                    state.exit_pending = true;
+                }
+            }
         }
     }
+
+    /// Without an event loop there is nothing to redraw or exit.
+    #[cfg(not(feature = "windowing"))]
+    pub fn send(&self, _message: WindowMessage) {}
 }
 
-/// Run the windowing system with the default backend for the current platform
-pub fn run(
-    params: &mut crate::backend::Params,
-    message_sender: WindowMessageSender,
-) -> anyhow::Result<()> {
-    run_with_backend(params, BackendType::default(), message_sender)
+/// Pixels scrolled per line for line-based mouse wheel deltas.
+#[cfg(feature = "windowing")]
+const SCROLL_LINE_HEIGHT: f64 = 40.0;
+
+/// Refresh rate assumed when the monitor doesn't report one, in millihertz.
+#[cfg(feature = "windowing")]
+const DEFAULT_REFRESH_RATE: u32 = 60_000;
+
+/// The zoom step bound to a key pressed with Ctrl (or Cmd), if any.
+#[cfg(feature = "windowing")]
+fn zoom_shortcut(key: &winit::keyboard::Key) -> Option<ZoomStep> {
+    match key.as_ref() {
+        winit::keyboard::Key::Character("=" | "+") => Some(ZoomStep::In),
+        winit::keyboard::Key::Character("-") => Some(ZoomStep::Out),
+        winit::keyboard::Key::Character("0") => Some(ZoomStep::Reset),
+        _ => None,
+    }
 }
 
-/// Run the windowing system with a specific backend
-pub fn run_with_backend(
-    params: &mut crate::backend::Params,
-    backend_type: BackendType,
-    message_sender: WindowMessageSender,
-) -> anyhow::Result<()> {
-    println!(
-        "Starting windowing system with {} backend",
-        backend_type.name()
-    );
-
-    match backend_type {
-        #[cfg(all(target_os = "windows"))]
-        BackendType::D3D12 => {
-            run_with_backend_impl::<crate::backend::d3d12::D3D12Backend>(params, message_sender)
-        }
-        #[cfg(target_os = "macos")]
-        BackendType::Metal => {
-            run_with_backend_impl::<crate::backend::metal::MetalBackend>(params, message_sender)
-        }
-        #[cfg(target_os = "linux")]
-        BackendType::OpenGL => {
-            run_with_backend_impl::<crate::backend::gl::OpenGlBackend>(params, message_sender)
-        }
+/// The color scheme `prefers-color-scheme` media queries see for a window theme.
+#[cfg(feature = "windowing")]
+fn color_scheme(theme: winit::window::Theme) -> ColorScheme {
+    match theme {
+        winit::window::Theme::Light => ColorScheme::Light,
+        winit::window::Theme::Dark => ColorScheme::Dark,
     }
 }
 
-/// Generic implementation that works with any backend
-fn run_with_backend_impl<'a, B: RenderingBackend>(
-    params: &'a mut crate::backend::Params,
+/// Run the windowing system, picking the first backend in the preference list of
+/// `options` that starts
+///
+/// Opens the windows listed when the event loop starts, and later ones on
+/// [`WindowMessage::OpenWindow`]. Returns once every window is closed.
+#[cfg(feature = "windowing")]
+pub fn run(
+    windows: Windows,
+    options: BackendOptions,
     message_sender: WindowMessageSender,
 ) -> anyhow::Result<()> {
     use winit::{
         application::ApplicationHandler,
-        event::{ElementState, MouseButton, WindowEvent},
+        event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent},
         event_loop::{ActiveEventLoop, EventLoop},
-        keyboard::{Key, NamedKey},
+        keyboard::{Key, ModifiersState, NamedKey},
         window::WindowId,
     };
 
@@ -87,49 +190,197 @@ fn run_with_backend_impl<'a, B: RenderingBackend>(
     // Publish a proxy so non-UI threads (layout/commands) can request redraws.
     message_sender.set_proxy(event_loop.create_proxy());
 
-    struct Application<'a, B: RenderingBackend> {
-        backend: Option<B>,
-        params: &'a mut crate::backend::Params,
+    struct OpenedWindow {
+        backend: Box<dyn RenderingBackend>,
+        params: crate::backend::Params,
+        /// When the pending redraw was first requested, to count missed vsyncs.
+        redraw_requested: Option<Instant>,
+        refresh_interval: Duration,
+        zoom_shortcuts: bool,
     }
 
-    impl<'a, B: RenderingBackend> ApplicationHandler<WindowMessage> for Application<'a, B> {
-        fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-            assert!(self.backend.is_none());
+    impl OpenedWindow {
+        fn request_redraw(&mut self) {
+            self.redraw_requested.get_or_insert_with(Instant::now);
+            self.backend.request_redraw();
+        }
 
-            self.backend = Some(B::new(event_loop).expect("Failed to create rendering backend"));
+        fn render(&mut self) {
+            let started = Instant::now();
+            self.backend.render(&mut self.params);
+            let finished = Instant::now();
 
-            if let Some(ref backend) = self.backend {
-                backend.request_redraw();
+            let requested = self.redraw_requested.take().unwrap_or(started);
+            let missed = crate::frame::missed_vsyncs(
+                finished.saturating_duration_since(requested),
+                self.refresh_interval,
+            );
+            let gpu_cache = self.backend.gpu_cache_usage();
+            (self.params.on_present)(finished - started, missed, gpu_cache);
+        }
+    }
+
+    struct Application {
+        windows: HashMap<WindowId, OpenedWindow>,
+        /// Engine ids of the windows opened so far.
+        opened: HashSet<u64>,
+        source: Windows,
+        /// Backends not tried yet; the first one that starts is used for all windows.
+        preference: Vec<BackendType>,
+        backend_type: Option<BackendType>,
+        options: BackendOptions,
+        /// Set when no backend could be started.
+        error: Option<anyhow::Error>,
+        modifiers: ModifiersState,
+    }
+
+    impl Application {
+        fn open(&mut self, event_loop: &ActiveEventLoop, id: u64) {
+            // A window created while the event loop starts is both listed and announced.
+            if !self.opened.insert(id) {
+                return;
+            }
+            let (params, options) = (self.source.open)(id);
+            let attributes = options.to_window_attributes();
+            let zoom_shortcuts = options.zoom_shortcuts;
+
+            let backend = match self.backend_type {
+                Some(backend_type) => {
+                    match backend_type.create(event_loop, attributes, &self.options) {
+                        Ok(backend) => backend,
+                        Err(err) => {
+                            eprintln!("Failed to open window {}: {}", id, err);
+                            return;
+                        }
+                    }
+                }
+                None => match self.select_backend(event_loop, attributes) {
+                    Some(backend) => backend,
+                    None => {
+                        event_loop.exit();
+                        return;
+                    }
+                },
+            };
+
+            let refresh_rate = event_loop
+                .primary_monitor()
+                .and_then(|monitor| monitor.refresh_rate_millihertz())
+                .unwrap_or(DEFAULT_REFRESH_RATE);
+            let mut window = OpenedWindow {
+                backend,
+                params,
+                redraw_requested: None,
+                refresh_interval: Duration::from_secs_f64(1000.0 / refresh_rate as f64),
+                zoom_shortcuts,
+            };
+            if let Some(theme) = event_loop.system_theme() {
+                (window.params.on_color_scheme)(color_scheme(theme));
+            }
+            window.request_redraw();
+            self.windows.insert(window.backend.window_id(), window);
+        }
+
+        /// Start the first backend in the preference list that works, with its first window.
+        fn select_backend(
+            &mut self,
+            event_loop: &ActiveEventLoop,
+            attributes: WindowAttributes,
+        ) -> Option<Box<dyn RenderingBackend>> {
+            let mut skipped = Vec::new();
+            for backend_type in std::mem::take(&mut self.preference) {
+                match backend_type.create(event_loop, attributes.clone(), &self.options) {
+                    Ok(backend) => {
+                        println!(
+                            "Using {} backend: {}",
+                            backend_type.name(),
+                            backend.describe()
+                        );
+                        self.backend_type = Some(backend_type);
+                        (self.source.on_backend)(BackendReport {
+                            backend: backend_type.name(),
+                            details: backend.describe(),
+                            skipped,
+                        });
+                        return Some(backend);
+                    }
+                    Err(err) => {
+                        eprintln!("{} backend unavailable: {}", backend_type.name(), err);
+                        skipped.push((backend_type.name(), err.to_string()));
+                    }
+                }
             }
+
+            let reasons: Vec<_> = skipped
+                .iter()
+                .map(|(name, reason)| format!("{}: {}", name, reason))
+                .collect();
+            self.error = Some(anyhow::anyhow!(
+                "no usable rendering backend ({})",
+                reasons.join("; ")
+            ));
+            None
         }
+    }
 
-        fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: WindowMessage) {
+    impl ApplicationHandler<WindowMessage> for Application {
+        fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+            for id in (self.source.ids)() {
+                self.open(event_loop, id);
+            }
+        }
+
+        fn user_event(&mut self, event_loop: &ActiveEventLoop, event: WindowMessage) {
             match event {
                 WindowMessage::Redraw => {
-                    if let Some(ref backend) = self.backend {
-                        backend.request_redraw();
+                    for window in self.windows.values_mut() {
+                        window.request_redraw();
                     }
                 }
+                WindowMessage::OpenWindow(id) => self.open(event_loop, id),
+                WindowMessage::Exit => event_loop.exit(),
             }
         }
 
         fn window_event(
             &mut self,
             event_loop: &ActiveEventLoop,
-            _window_id: WindowId,
+            window_id: WindowId,
             event: WindowEvent,
         ) {
-            let backend = self.backend.as_mut().unwrap();
+            let Some(window) = self.windows.get_mut(&window_id) else {
+                return;
+            };
+
+            // Backends consume resizes to recreate their surfaces, so report them first
+            if let WindowEvent::Resized(size) = &event {
+                (window.params.on_resize)(size.width as f64, size.height as f64);
+            }
 
             // First, let the backend handle any backend-specific events
-            if backend.handle_window_event(&event) {
+            if window.backend.handle_window_event(&event) {
                 return; // Event was handled by the backend
             }
 
             // Handle common events
             match event {
+                WindowEvent::ModifiersChanged(modifiers) => self.modifiers = modifiers.state(),
+                WindowEvent::ThemeChanged(theme) => {
+                    (window.params.on_color_scheme)(color_scheme(theme))
+                }
                 WindowEvent::KeyboardInput { event, .. } => {
-                    let input_state = backend.input_state_mut();
+                    let command_key = self.modifiers.control_key() || self.modifiers.super_key();
+                    if window.zoom_shortcuts && command_key {
+                        if let Some(step) = zoom_shortcut(&event.logical_key) {
+                            if event.state == ElementState::Pressed {
+                                (window.params.on_zoom)(step);
+                            }
+                            return;
+                        }
+                    }
+                    // Only the focused window receives key events
+                    (window.params.on_key)(&KeyEvent::new(&event, self.modifiers));
+                    let input_state = window.backend.input_state_mut();
                     match event.logical_key {
                         Key::Named(NamedKey::ArrowLeft) => input_state.x -= 10.0,
                         Key::Named(NamedKey::ArrowRight) => input_state.x += 10.0,
@@ -138,36 +389,78 @@ fn run_with_backend_impl<'a, B: RenderingBackend>(
                         Key::Named(NamedKey::Escape) => event_loop.exit(),
                         _ => return,
                     }
-                    backend.request_redraw();
+                    window.request_redraw();
                 }
                 WindowEvent::MouseInput {
-                    state: ElementState::Pressed,
+                    state,
                     button: MouseButton::Left,
                     ..
                 } => {
-                    let input_state = backend.input_state();
+                    let pressed = state == ElementState::Pressed;
+                    let input_state = window.backend.input_state_mut();
+                    input_state.left_button_pressed = pressed;
+                    let position = input_state.cursor_position;
+                    (window.params.on_pointer)(position, pressed);
+                    if let (true, Some(position)) = (pressed, position) {
+                        (window.params.on_click)(position.x, position.y);
+                    }
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    // Positive wheel deltas scroll up, i.e. towards a smaller scroll offset.
+                    let (dx, dy) = match delta {
+                        MouseScrollDelta::LineDelta(x, y) => (
+                            -x as f64 * SCROLL_LINE_HEIGHT,
+                            -y as f64 * SCROLL_LINE_HEIGHT,
+                        ),
+                        MouseScrollDelta::PixelDelta(position) => (-position.x, -position.y),
+                    };
+                    let input_state = window.backend.input_state();
                     if let Some(cursor_position) = &input_state.cursor_position {
-                        (self.params.on_click)(cursor_position.x, cursor_position.y);
+                        (window.params.on_scroll)(cursor_position.x, cursor_position.y, dx, dy);
                     }
                 }
                 WindowEvent::CursorMoved { position, .. } => {
-                    backend.input_state_mut().cursor_position = Some(position);
+                    let input_state = window.backend.input_state_mut();
+                    input_state.cursor_position = Some(position);
+                    let pressed = input_state.left_button_pressed;
+                    (window.params.on_pointer)(Some(position), pressed);
+                }
+                // Pointer input goes to the window under the cursor, even where the
+                // platform reports wheel events to the focused window instead.
+                WindowEvent::CursorLeft { .. } => {
+                    let input_state = window.backend.input_state_mut();
+                    input_state.cursor_position = None;
+                    let pressed = input_state.left_button_pressed;
+                    (window.params.on_pointer)(None, pressed);
+                }
+                WindowEvent::Focused(focused) => (window.params.on_focus)(focused),
+                WindowEvent::RedrawRequested => window.render(),
+                WindowEvent::CloseRequested => {
+                    self.windows.remove(&window_id);
+                    if self.windows.is_empty() {
+                        event_loop.exit();
+                    }
                 }
-                WindowEvent::RedrawRequested => backend.render(self.params),
-                WindowEvent::CloseRequested => event_loop.exit(),
                 _ => {}
             }
         }
     }
 
-    // unsafe: We avoid lifetime issues by transmuting the params reference.
-    // The params always outlife the Application struct
-    let mut application = Application::<'a, B> {
-        backend: None,
-        params,
+    let mut application = Application {
+        windows: HashMap::new(),
+        opened: HashSet::new(),
+        source: windows,
+        preference: options.preference.clone(),
+        backend_type: None,
+        options,
+        error: None,
+        modifiers: ModifiersState::default(),
     };
 
     event_loop.run_app(&mut application)?;
 
-    Ok(())
+    match application.error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
 }
@@ -0,0 +1,47 @@
+/// Zoom levels stepped through by the zoom shortcuts, as in most browsers.
+const ZOOM_LEVELS: [f64; 15] = [
+    0.25, 0.33, 0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 3.0, 5.0,
+];
+
+pub(crate) const MIN_ZOOM: f64 = ZOOM_LEVELS[0];
+pub(crate) const MAX_ZOOM: f64 = ZOOM_LEVELS[ZOOM_LEVELS.len() - 1];
+
+/// Direction of a zoom shortcut.
+#[cfg(feature = "windowing")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ZoomStep {
+    In,
+    Out,
+    Reset,
+}
+
+/// Keep a zoom factor within the supported range, treating NaN as no zoom.
+pub(crate) fn clamp_zoom(zoom: f64) -> f64 {
+    if zoom.is_nan() {
+        1.0
+    } else {
+        zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+    }
+}
+
+/// The zoom level one step away from `current`, which need not be one of the levels.
+#[cfg(feature = "windowing")]
+pub(crate) fn step_zoom(current: f64, step: ZoomStep) -> f64 {
+    match step {
+        ZoomStep::In => ZOOM_LEVELS
+            .iter()
+            .copied()
+            .find(|&level| level > current)
+            .unwrap_or(MAX_ZOOM),
+        ZoomStep::Out => ZOOM_LEVELS
+            .iter()
+            .rev()
+            .copied()
+            .find(|&level| level < current)
+            .unwrap_or(MIN_ZOOM),
+        ZoomStep::Reset => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod zoom_tests;
@@ -0,0 +1,25 @@
+use super::*;
+
+#[test]
+#[cfg(feature = "windowing")]
+fn test_step_zoom_through_levels() {
+    assert_eq!(step_zoom(1.0, ZoomStep::In), 1.1);
+    assert_eq!(step_zoom(1.1, ZoomStep::Out), 1.0);
+    assert_eq!(step_zoom(2.5, ZoomStep::Reset), 1.0);
+
+    // Factors between levels step to the nearest level in that direction
+    assert_eq!(step_zoom(1.2, ZoomStep::In), 1.25);
+    assert_eq!(step_zoom(1.2, ZoomStep::Out), 1.1);
+
+    // The range ends are sticky
+    assert_eq!(step_zoom(MAX_ZOOM, ZoomStep::In), MAX_ZOOM);
+    assert_eq!(step_zoom(MIN_ZOOM, ZoomStep::Out), MIN_ZOOM);
+}
+
+#[test]
+fn test_clamp_zoom() {
+    assert_eq!(clamp_zoom(1.5), 1.5);
+    assert_eq!(clamp_zoom(0.0), MIN_ZOOM);
+    assert_eq!(clamp_zoom(100.0), MAX_ZOOM);
+    assert_eq!(clamp_zoom(f64::NAN), 1.0);
+}
@@ -29,10 +29,42 @@ pub enum WorkerRequest {
         key: String,
         value: String,
     },
+    /// Apply several mutations atomically, replying once they have all been applied.
+    Transaction {
+        handle: u64,
+        mutations: Vec<Mutation>,
+        reply_to: IpcSender<i32>,
+    },
+    /// Limit the size of the document; 0 leaves a dimension unlimited.
+    SetLimits {
+        handle: u64,
+        max_nodes: u64,
+        max_tree_depth: u64,
+        max_stylesheet_bytes: u64,
+        max_selector_complexity: u64,
+        reply_to: IpcSender<i32>,
+    },
     RootId {
         handle: u64,
         reply_to: IpcSender<u64>,
     },
+    /// Convert a position in window pixels to a node's local coordinates; `None` if the
+    /// node hasn't been laid out.
+    ClientToNode {
+        handle: u64,
+        node_id: u64,
+        x: f64,
+        y: f64,
+        reply_to: IpcSender<Option<(f64, f64)>>,
+    },
+    /// Convert a position in a node's local coordinates to window pixels.
+    NodeToClient {
+        handle: u64,
+        node_id: u64,
+        x: f64,
+        y: f64,
+        reply_to: IpcSender<Option<(f64, f64)>>,
+    },
     Run {
         handle: u64,
         reply_to: IpcSender<i32>,
@@ -43,3 +75,24 @@ pub enum WorkerRequest {
     },
     Shutdown,
 }
+
+/// A single document mutation, as batched into a [`WorkerRequest::Transaction`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Mutation {
+    AddStylesheet {
+        css: String,
+    },
+    CreateNode {
+        node_id: u64,
+        text: Option<String>,
+    },
+    SetParent {
+        parent_id: u64,
+        child_id: u64,
+    },
+    SetAttribute {
+        node_id: u64,
+        key: String,
+        value: String,
+    },
+}
@@ -38,6 +38,19 @@ typedef uint64_t lolite_id_t;
  */
 LOLITE_API lolite_engine_handle_t lolite_init(bool use_same_process);
 
+/*
+ * Keep worker processes started ahead of time, so that lolite_init with
+ * use_same_process false doesn't wait for a process to start and connect.
+ * Engines return their worker to the pool on lolite_destroy to be reused,
+ * unless lolite_run was called on them.
+ *
+ * size: how many idle workers to keep; 0 shuts the idle workers down
+ *
+ * Returns:
+ *   0 once the idle workers are ready, -1 if a worker failed to start
+ */
+LOLITE_API int lolite_set_worker_pool_size(size_t size);
+
 /*
  * Add a CSS stylesheet to the engine.
  *
@@ -45,11 +58,21 @@ LOLITE_API lolite_engine_handle_t lolite_init(bool use_same_process);
  */
 LOLITE_API void lolite_add_stylesheet(lolite_engine_handle_t handle, const char* css_content);
 
+/*
+ * Reserve count consecutive node ids, to create nodes with without picking
+ * ids. Ids are allocated by the library, without waiting for the worker in
+ * worker mode, and never collide with ids passed to lolite_create_node before.
+ *
+ * Returns:
+ *   the first reserved id on success, 0 on error
+ */
+LOLITE_API lolite_id_t lolite_reserve_ids(lolite_engine_handle_t handle, uint64_t count);
+
 /*
  * Create a new document node.
  *
  * node_id:
- *   caller-provided node id (must be non-zero; 0 is reserved for root)
+ *   caller-provided node id, or 0 to have one reserved as by lolite_reserve_ids
  *
  * text_content:
  *   optional null-terminated UTF-8 string (may be NULL)
@@ -71,6 +94,38 @@ LOLITE_API void lolite_set_parent(lolite_engine_handle_t handle, lolite_id_t par
  */
 LOLITE_API void lolite_set_attribute(lolite_engine_handle_t handle, lolite_id_t node_id, const char* key, const char* value);
 
+/*
+ * Start queueing mutations (stylesheets, nodes, parents, attributes) until
+ * lolite_commit_transaction, which applies them atomically with a single layout.
+ *
+ * Returns:
+ *   0 on success, -1 on error (including when a transaction is already open)
+ */
+LOLITE_API int lolite_begin_transaction(lolite_engine_handle_t handle);
+
+/*
+ * Apply the mutations queued since lolite_begin_transaction.
+ *
+ * Returns:
+ *   0 once applied, -1 on error (including when no transaction is open)
+ */
+LOLITE_API int lolite_commit_transaction(lolite_engine_handle_t handle);
+
+/*
+ * Limit the size of the document, for documents from untrusted sources.
+ * Mutations that would exceed a limit are rejected and logged.
+ *
+ * max_nodes: most nodes the document may hold, including the root
+ * max_tree_depth: deepest a node may be nested below the root
+ * max_stylesheet_bytes: largest stylesheet accepted, in bytes
+ * max_selector_complexity: most compound selectors a selector may chain
+ * Passing 0 leaves that limit unset.
+ *
+ * Returns:
+ *   0 on success, -1 on error
+ */
+LOLITE_API int lolite_set_limits(lolite_engine_handle_t handle, uint64_t max_nodes, uint64_t max_tree_depth, uint64_t max_stylesheet_bytes, uint64_t max_selector_complexity);
+
 /*
  * Get the root node ID of the document.
  *
@@ -79,6 +134,29 @@ LOLITE_API void lolite_set_attribute(lolite_engine_handle_t handle, lolite_id_t
  */
 LOLITE_API lolite_id_t lolite_root_id(lolite_engine_handle_t handle);
 
+/*
+ * Convert a position in window pixels, as reported for clicks, to CSS pixels
+ * relative to the top-left corner of a node's content box, accounting for the
+ * page zoom and the scroll offsets of the node's scroll containers.
+ *
+ * out_x/out_y: receive the converted position (must not be NULL)
+ *
+ * Returns:
+ *   0 on success, -1 on error (including when the node hasn't been laid out)
+ */
+LOLITE_API int lolite_client_to_node(lolite_engine_handle_t handle, lolite_id_t node_id, double x, double y, double* out_x, double* out_y);
+
+/*
+ * Convert a position relative to a node's content box to window pixels; the
+ * inverse of lolite_client_to_node.
+ *
+ * out_x/out_y: receive the converted position (must not be NULL)
+ *
+ * Returns:
+ *   0 on success, -1 on error (including when the node hasn't been laid out)
+ */
+LOLITE_API int lolite_node_to_client(lolite_engine_handle_t handle, lolite_id_t node_id, double x, double y, double* out_x, double* out_y);
+
 /*
  * Run the engine event loop (blocking).
  *
@@ -1,5 +1,6 @@
 use crate::engine_backend::{EngineBackend, LoliteId};
-use lolite::{Engine, Id, Params};
+use lolite::{Engine, Id, Limits, Params};
+use lolite_common::Mutation;
 
 pub struct DirectBackend {
     engine: Engine,
@@ -15,28 +16,88 @@ impl DirectBackend {
 
 impl EngineBackend for DirectBackend {
     fn add_stylesheet(&self, css: String) {
-        self.engine.add_stylesheet(&css);
+        if let Err(err) = self.engine.add_stylesheet(&css) {
+            eprintln!("lolite_add_stylesheet failed: {:?}", err);
+        }
     }
 
     fn create_node(&self, node_id: LoliteId, text: Option<String>) {
-        let _ = self.engine.create_node(Id::from_u64(node_id), text);
+        if let Err(err) = self.engine.create_node(Id::from_u64(node_id), text) {
+            eprintln!("lolite_create_node failed: {:?}", err);
+        }
     }
 
     fn set_parent(&self, parent_id: LoliteId, child_id: LoliteId) {
-        self.engine
-            .set_parent(Id::from_u64(parent_id), Id::from_u64(child_id));
+        if let Err(err) = self
+            .engine
+            .set_parent(Id::from_u64(parent_id), Id::from_u64(child_id))
+        {
+            eprintln!("lolite_set_parent failed: {:?}", err);
+        }
     }
 
     fn set_attribute(&self, node_id: LoliteId, key: String, value: String) {
-        self.engine.set_attribute(Id::from_u64(node_id), key, value);
+        if let Err(err) = self.engine.set_attribute(Id::from_u64(node_id), key, value) {
+            eprintln!("lolite_set_attribute failed: {:?}", err);
+        }
+    }
+
+    fn apply_transaction(&self, mutations: Vec<Mutation>) -> i32 {
+        let result = self.engine.transaction(|tx| {
+            for mutation in mutations {
+                match mutation {
+                    Mutation::AddStylesheet { css } => {
+                        tx.add_stylesheet(&css);
+                    }
+                    Mutation::CreateNode { node_id, text } => {
+                        tx.create_node(Id::from_u64(node_id), text);
+                    }
+                    Mutation::SetParent {
+                        parent_id,
+                        child_id,
+                    } => tx.set_parent(Id::from_u64(parent_id), Id::from_u64(child_id)),
+                    Mutation::SetAttribute {
+                        node_id,
+                        key,
+                        value,
+                    } => tx.set_attribute(Id::from_u64(node_id), key, value),
+                }
+            }
+        });
+
+        match result {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("lolite_commit_transaction failed: {:?}", err);
+                -1
+            }
+        }
+    }
+
+    fn set_limits(&self, limits: Limits) -> i32 {
+        match self.engine.set_limits(limits) {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("lolite_set_limits failed: {:?}", err);
+                -1
+            }
+        }
     }
 
     fn root_id(&self) -> LoliteId {
         self.engine.root_id().as_u64()
     }
 
+    fn client_to_node(&self, node_id: LoliteId, x: f64, y: f64) -> Option<(f64, f64)> {
+        self.engine.client_to_node(Id::from_u64(node_id), x, y)
+    }
+
+    fn node_to_client(&self, node_id: LoliteId, x: f64, y: f64) -> Option<(f64, f64)> {
+        self.engine.node_to_client(Id::from_u64(node_id), x, y)
+    }
+
     fn run(&self) -> i32 {
-        match self.engine.run(Params { on_click: None }) {
+        match self.engine.run(Params::default()) {
             Ok(()) => 0,
             Err(err) => {
                 eprintln!("lolite_run failed: {:?}", err);
@@ -1,3 +1,6 @@
+use lolite::Limits;
+use lolite_common::Mutation;
+
 pub type LoliteId = u64;
 
 pub trait EngineBackend: Send {
@@ -5,7 +8,14 @@ pub trait EngineBackend: Send {
     fn create_node(&self, node_id: LoliteId, text: Option<String>);
     fn set_parent(&self, parent_id: LoliteId, child_id: LoliteId);
     fn set_attribute(&self, node_id: LoliteId, key: String, value: String);
+    /// Apply the mutations atomically, with a single layout pass.
+    fn apply_transaction(&self, mutations: Vec<Mutation>) -> i32;
+    fn set_limits(&self, limits: Limits) -> i32;
     fn root_id(&self) -> LoliteId;
+    /// Convert window pixels to CSS pixels relative to the node's content box.
+    fn client_to_node(&self, node_id: LoliteId, x: f64, y: f64) -> Option<(f64, f64)>;
+    /// Convert CSS pixels relative to the node's content box to window pixels.
+    fn node_to_client(&self, node_id: LoliteId, x: f64, y: f64) -> Option<(f64, f64)>;
     fn run(&self) -> i32;
     fn destroy(&self) -> i32;
 }
@@ -8,9 +8,12 @@ use std::sync::Mutex;
 mod direct_backend;
 mod engine_backend;
 mod worker_backend;
+mod worker_pool;
 
 use direct_backend::DirectBackend;
 use engine_backend::EngineBackend;
+use lolite::Limits;
+use lolite_common::Mutation;
 use worker_backend::WorkerBackend;
 
 /// Handle type for engine instances
@@ -20,7 +23,58 @@ pub type EngineHandle = usize;
 pub type LoliteId = u64;
 
 type EngineBox = Box<dyn EngineBackend>;
-type EngineRef = Arc<Mutex<EngineBox>>;
+type EngineRef = Arc<Mutex<EngineInstance>>;
+
+/// An engine backend, plus the mutations queued by an open transaction.
+struct EngineInstance {
+    backend: EngineBox,
+    transaction: Option<Vec<Mutation>>,
+    /// Next node id handed out by `lolite_reserve_ids`, above every id used so far.
+    ///
+    /// Ids are allocated here rather than by the backend, so creating nodes doesn't wait
+    /// for a worker, and a node keeps its id whichever worker hosts the engine. The
+    /// engine's own ids, for cloned subtrees, count down from the top of the range.
+    next_id: LoliteId,
+}
+
+impl EngineInstance {
+    fn new(backend: EngineBox) -> Self {
+        Self {
+            backend,
+            transaction: None,
+            next_id: 1,
+        }
+    }
+
+    /// Reserve `count` consecutive node ids, returning the first.
+    fn reserve_ids(&mut self, count: u64) -> Option<LoliteId> {
+        let first = self.next_id;
+        self.next_id = first.checked_add(count)?;
+        Some(first)
+    }
+
+    /// Queue the mutation while a transaction is open, otherwise apply it right away.
+    fn mutate(&mut self, mutation: Mutation) {
+        if let Some(pending) = &mut self.transaction {
+            pending.push(mutation);
+            return;
+        }
+
+        match mutation {
+            Mutation::AddStylesheet { css } => self.backend.add_stylesheet(css),
+            Mutation::CreateNode { node_id, text } => self.backend.create_node(node_id, text),
+            Mutation::SetParent {
+                parent_id,
+                child_id,
+            } => self.backend.set_parent(parent_id, child_id),
+            Mutation::SetAttribute {
+                node_id,
+                key,
+                value,
+            } => self.backend.set_attribute(node_id, key, value),
+        }
+    }
+}
 
 static ENGINE_INSTANCES: std::sync::LazyLock<Mutex<HashMap<EngineHandle, EngineRef>>> =
     std::sync::LazyLock::new(|| Mutex::new(HashMap::new()));
@@ -54,17 +108,41 @@ pub extern "C" fn lolite_init(use_same_process: bool) -> EngineHandle {
     ENGINE_INSTANCES
         .lock()
         .unwrap()
-        .insert(handle, Arc::new(Mutex::new(backend)));
+        .insert(handle, Arc::new(Mutex::new(EngineInstance::new(backend))));
 
     handle
 }
 
+/// Keep worker processes started ahead of time, so that `lolite_init` with
+/// `use_same_process` false doesn't wait for a process to start and connect
+///
+/// Engines take their worker from the pool, which is refilled in the background, and
+/// return it on `lolite_destroy` to be reused, unless `lolite_run` was called on them.
+///
+/// # Arguments
+/// * `size` - How many idle workers to keep; 0 shuts the idle workers down
+///
+/// # Returns
+/// * 0 once the idle workers are ready, -1 if a worker failed to start
+#[no_mangle]
+pub extern "C" fn lolite_set_worker_pool_size(size: usize) -> c_int {
+    match worker_pool::set_pool_size(size) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Failed to fill worker pool: {}", e);
+            -1
+        }
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn lolite_init_internal(handle: EngineHandle) {
-    ENGINE_INSTANCES
-        .lock()
-        .unwrap()
-        .insert(handle, Arc::new(Mutex::new(Box::new(DirectBackend::new()))));
+    ENGINE_INSTANCES.lock().unwrap().insert(
+        handle,
+        Arc::new(Mutex::new(EngineInstance::new(Box::new(
+            DirectBackend::new(),
+        )))),
+    );
 }
 
 fn get_engine(handle: EngineHandle) -> Option<EngineRef> {
@@ -101,13 +179,47 @@ pub extern "C" fn lolite_add_stylesheet(handle: EngineHandle, css_content: *cons
         return;
     };
 
-    engine.lock().unwrap().add_stylesheet(css_str);
+    engine
+        .lock()
+        .unwrap()
+        .mutate(Mutation::AddStylesheet { css: css_str });
+}
+
+/// Reserve a range of node ids, to create nodes with without picking ids
+///
+/// Ids are allocated by the library, without waiting for the worker in worker mode,
+/// and never collide with ids passed to `lolite_create_node` before.
+///
+/// # Arguments
+/// * `handle` - Engine handle returned from lolite_init
+/// * `count` - How many consecutive ids to reserve
+///
+/// # Returns
+/// * The first reserved id on success, 0 on error
+#[no_mangle]
+pub extern "C" fn lolite_reserve_ids(handle: EngineHandle, count: u64) -> LoliteId {
+    if handle == 0 {
+        eprintln!("Invalid engine handle");
+        return 0;
+    }
+
+    let Some(engine) = get_engine(handle) else {
+        eprintln!("Engine handle not found");
+        return 0;
+    };
+
+    let first = engine.lock().unwrap().reserve_ids(count);
+    first.unwrap_or_else(|| {
+        eprintln!("Node ids exhausted");
+        0
+    })
 }
 
 /// Create a new document node
 ///
 /// # Arguments
 /// * `handle` - Engine handle returned from lolite_init
+/// * `node_id` - Id of the new node, or 0 to have one reserved as by lolite_reserve_ids
 /// * `text_content` - Optional null-terminated text content (can be null)
 ///
 /// # Returns
@@ -123,11 +235,6 @@ pub extern "C" fn lolite_create_node(
         return 0;
     }
 
-    if node_id == 0 {
-        eprintln!("Invalid node id (0 is reserved for root)");
-        return 0;
-    }
-
     let text = if text_content.is_null() {
         None
     } else {
@@ -145,7 +252,19 @@ pub extern "C" fn lolite_create_node(
         return 0;
     };
 
-    engine.lock().unwrap().create_node(node_id, text);
+    let mut engine = engine.lock().unwrap();
+    let node_id = if node_id == 0 {
+        let Some(node_id) = engine.reserve_ids(1) else {
+            eprintln!("Node ids exhausted");
+            return 0;
+        };
+        node_id
+    } else {
+        // Keep reserved ids clear of the ones picked by the caller
+        engine.next_id = engine.next_id.max(node_id.saturating_add(1));
+        node_id
+    };
+    engine.mutate(Mutation::CreateNode { node_id, text });
     node_id
 }
 
@@ -170,7 +289,10 @@ pub extern "C" fn lolite_set_parent(handle: EngineHandle, parent_id: LoliteId, c
         return;
     };
 
-    engine.lock().unwrap().set_parent(parent_id, child_id);
+    engine.lock().unwrap().mutate(Mutation::SetParent {
+        parent_id,
+        child_id,
+    });
 }
 
 /// Set an attribute on a node
@@ -221,10 +343,115 @@ pub extern "C" fn lolite_set_attribute(
         return;
     };
 
-    engine
-        .lock()
-        .unwrap()
-        .set_attribute(node_id, key_str, value_str);
+    engine.lock().unwrap().mutate(Mutation::SetAttribute {
+        node_id,
+        key: key_str,
+        value: value_str,
+    });
+}
+
+/// Start queueing mutations, to be applied together by `lolite_commit_transaction`
+///
+/// Stylesheet, node, parent and attribute calls made until the commit are applied
+/// atomically with a single layout pass, and in worker mode sent in a single request.
+///
+/// # Arguments
+/// * `handle` - Engine handle returned from lolite_init
+///
+/// # Returns
+/// * 0 on success, -1 on error (including when a transaction is already open)
+#[no_mangle]
+pub extern "C" fn lolite_begin_transaction(handle: EngineHandle) -> c_int {
+    if handle == 0 {
+        eprintln!("Invalid engine handle");
+        return -1;
+    }
+
+    let Some(engine) = get_engine(handle) else {
+        eprintln!("Engine handle not found");
+        return -1;
+    };
+
+    let mut engine = engine.lock().unwrap();
+    if engine.transaction.is_some() {
+        eprintln!("A transaction is already open");
+        return -1;
+    }
+    engine.transaction = Some(Vec::new());
+    0
+}
+
+/// Apply the mutations queued since `lolite_begin_transaction`
+///
+/// # Arguments
+/// * `handle` - Engine handle returned from lolite_init
+///
+/// # Returns
+/// * 0 once the mutations have been applied, -1 on error (including when no transaction is open)
+#[no_mangle]
+pub extern "C" fn lolite_commit_transaction(handle: EngineHandle) -> c_int {
+    if handle == 0 {
+        eprintln!("Invalid engine handle");
+        return -1;
+    }
+
+    let Some(engine) = get_engine(handle) else {
+        eprintln!("Engine handle not found");
+        return -1;
+    };
+
+    let mut engine = engine.lock().unwrap();
+    let Some(mutations) = engine.transaction.take() else {
+        eprintln!("No transaction is open");
+        return -1;
+    };
+    engine.backend.apply_transaction(mutations)
+}
+
+/// Limit the size of the document, for documents from untrusted sources
+///
+/// Mutations that would exceed a limit are rejected and logged. Passing 0 leaves
+/// that dimension unlimited.
+///
+/// # Arguments
+/// * `handle` - Engine handle returned from lolite_init
+/// * `max_nodes` - Most nodes the document may hold, including the root
+/// * `max_tree_depth` - Deepest a node may be nested below the root
+/// * `max_stylesheet_bytes` - Largest stylesheet accepted, in bytes
+/// * `max_selector_complexity` - Most compound selectors a selector may chain
+///
+/// # Returns
+/// * 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn lolite_set_limits(
+    handle: EngineHandle,
+    max_nodes: u64,
+    max_tree_depth: u64,
+    max_stylesheet_bytes: u64,
+    max_selector_complexity: u64,
+) -> c_int {
+    if handle == 0 {
+        eprintln!("Invalid engine handle");
+        return -1;
+    }
+
+    let Some(engine) = get_engine(handle) else {
+        eprintln!("Engine handle not found");
+        return -1;
+    };
+
+    let decode = |limit: u64| match limit {
+        0 => usize::MAX,
+        limit => usize::try_from(limit).unwrap_or(usize::MAX),
+    };
+    let limits = Limits {
+        max_nodes: decode(max_nodes),
+        max_tree_depth: decode(max_tree_depth),
+        max_stylesheet_bytes: decode(max_stylesheet_bytes),
+        max_selector_complexity: decode(max_selector_complexity),
+    };
+    let code = engine.lock().unwrap().backend.set_limits(limits);
+    code
 }
 
 /// Get the root node ID of the document
@@ -246,10 +473,96 @@ pub extern "C" fn lolite_root_id(handle: EngineHandle) -> LoliteId {
         return 0;
     };
 
-    let id = engine.lock().unwrap().root_id();
+    let id = engine.lock().unwrap().backend.root_id();
     id
 }
 
+/// Convert a position in window pixels, as reported for clicks, to CSS pixels relative
+/// to the top-left corner of a node's content box
+///
+/// Accounts for the page zoom and the scroll offsets of the node's scroll containers.
+///
+/// # Arguments
+/// * `handle` - Engine handle returned from lolite_init
+/// * `node_id` - Node whose coordinate space to convert to
+/// * `x`, `y` - Position in window pixels
+/// * `out_x`, `out_y` - Receive the position in the node's coordinates (must not be NULL)
+///
+/// # Returns
+/// * 0 on success, -1 on error (including when the node hasn't been laid out)
+#[no_mangle]
+pub extern "C" fn lolite_client_to_node(
+    handle: EngineHandle,
+    node_id: LoliteId,
+    x: f64,
+    y: f64,
+    out_x: *mut f64,
+    out_y: *mut f64,
+) -> c_int {
+    convert_point("lolite_client_to_node", handle, out_x, out_y, |backend| {
+        backend.client_to_node(node_id, x, y)
+    })
+}
+
+/// Convert a position in CSS pixels relative to a node's content box to window pixels;
+/// the inverse of lolite_client_to_node
+///
+/// # Arguments
+/// * `handle` - Engine handle returned from lolite_init
+/// * `node_id` - Node whose coordinate space to convert from
+/// * `x`, `y` - Position in the node's coordinates
+/// * `out_x`, `out_y` - Receive the position in window pixels (must not be NULL)
+///
+/// # Returns
+/// * 0 on success, -1 on error (including when the node hasn't been laid out)
+#[no_mangle]
+pub extern "C" fn lolite_node_to_client(
+    handle: EngineHandle,
+    node_id: LoliteId,
+    x: f64,
+    y: f64,
+    out_x: *mut f64,
+    out_y: *mut f64,
+) -> c_int {
+    convert_point("lolite_node_to_client", handle, out_x, out_y, |backend| {
+        backend.node_to_client(node_id, x, y)
+    })
+}
+
+fn convert_point(
+    name: &str,
+    handle: EngineHandle,
+    out_x: *mut f64,
+    out_y: *mut f64,
+    convert: impl FnOnce(&dyn EngineBackend) -> Option<(f64, f64)>,
+) -> c_int {
+    if handle == 0 {
+        eprintln!("Invalid engine handle");
+        return -1;
+    }
+
+    if out_x.is_null() || out_y.is_null() {
+        eprintln!("{name}: output pointers must not be NULL");
+        return -1;
+    }
+
+    let Some(engine) = get_engine(handle) else {
+        eprintln!("Engine handle not found");
+        return -1;
+    };
+
+    let point = convert(engine.lock().unwrap().backend.as_ref());
+    let Some((x, y)) = point else {
+        eprintln!("{name}: node has no layout");
+        return -1;
+    };
+    unsafe {
+        *out_x = x;
+        *out_y = y;
+    }
+    0
+}
+
 /// Run the engine event loop (blocking).
 ///
 /// # Arguments
@@ -269,7 +582,7 @@ pub extern "C" fn lolite_run(handle: EngineHandle) -> c_int {
         return -1;
     };
 
-    let code = engine.lock().unwrap().run();
+    let code = engine.lock().unwrap().backend.run();
     code
 }
 
@@ -293,6 +606,6 @@ pub extern "C" fn lolite_destroy(handle: EngineHandle) -> c_int {
         return -1;
     };
 
-    let code = engine.lock().unwrap().destroy();
+    let code = engine.lock().unwrap().backend.destroy();
     code
 }
@@ -1,32 +1,31 @@
 use crate::engine_backend::{EngineBackend, LoliteId};
-use ipc_channel::ipc::{self, IpcOneShotServer, IpcSender};
+use crate::worker_pool::{self, WorkerProcess};
+use ipc_channel::ipc::{self, IpcSender};
+use lolite::Limits;
+use lolite_common::Mutation;
 use std::os::raw::c_int;
-use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub struct WorkerBackend {
     handle: usize,
-    process: Child,
+    /// Taken back on drop, to return the worker to the pool.
+    worker: Option<WorkerProcess>,
     sender: IpcSender<lolite_common::WorkerRequest>,
+    destroyed: AtomicBool,
+    /// Set once the worker runs an event loop, which can only be created once per
+    /// process, so that the worker isn't reused.
+    ran_event_loop: AtomicBool,
 }
 
 impl WorkerBackend {
     pub fn new(handle: usize) -> std::io::Result<Self> {
-        // Worker connects back and sends an IpcSender that we can use to send requests.
-        let (server, server_name) =
-            IpcOneShotServer::<IpcSender<lolite_common::WorkerRequest>>::new()
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
-
-        let process = spawn_worker("ipc_channel", &server_name)?;
-
-        let (_rx, sender) = server
-            .accept()
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
-
+        let worker = worker_pool::take_worker()?;
         let backend = Self {
             handle,
-            process,
-            sender,
+            sender: worker.sender.clone(),
+            worker: Some(worker),
+            destroyed: AtomicBool::new(false),
+            ran_event_loop: AtomicBool::new(false),
         };
 
         backend.init_internal();
@@ -44,8 +43,32 @@ impl WorkerBackend {
         }
     }
 
-    fn shutdown(&self) {
-        let _ = self.sender.send(lolite_common::WorkerRequest::Shutdown);
+    /// Send a coordinate conversion built by `request` and wait for the converted point.
+    fn convert_point(
+        &self,
+        name: &str,
+        request: impl FnOnce(IpcSender<Option<(f64, f64)>>) -> lolite_common::WorkerRequest,
+    ) -> Option<(f64, f64)> {
+        let (reply_tx, reply_rx) = match ipc::channel::<Option<(f64, f64)>>() {
+            Ok(ch) => ch,
+            Err(e) => {
+                eprintln!("Failed to create reply channel: {e}");
+                return None;
+            }
+        };
+
+        if let Err(e) = self.sender.send(request(reply_tx)) {
+            eprintln!("Failed to send {name} to worker: {e}");
+            return None;
+        }
+
+        match reply_rx.recv() {
+            Ok(point) => point,
+            Err(e) => {
+                eprintln!("Failed to receive {name} response: {e}");
+                None
+            }
+        }
     }
 }
 
@@ -96,6 +119,65 @@ impl EngineBackend for WorkerBackend {
         }
     }
 
+    fn apply_transaction(&self, mutations: Vec<Mutation>) -> c_int {
+        let (reply_tx, reply_rx) = match ipc::channel::<i32>() {
+            Ok(ch) => ch,
+            Err(e) => {
+                eprintln!("Failed to create reply channel: {e}");
+                return -1;
+            }
+        };
+
+        if let Err(e) = self.sender.send(lolite_common::WorkerRequest::Transaction {
+            handle: self.handle as u64,
+            mutations,
+            reply_to: reply_tx,
+        }) {
+            eprintln!("Failed to send Transaction to worker: {e}");
+            return -1;
+        }
+
+        match reply_rx.recv() {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("Failed to receive Transaction response: {e}");
+                -1
+            }
+        }
+    }
+
+    fn set_limits(&self, limits: Limits) -> c_int {
+        let (reply_tx, reply_rx) = match ipc::channel::<i32>() {
+            Ok(ch) => ch,
+            Err(e) => {
+                eprintln!("Failed to create reply channel: {e}");
+                return -1;
+            }
+        };
+
+        // The FFI encodes "no limit" as 0.
+        let encode = |limit: usize| if limit == usize::MAX { 0 } else { limit as u64 };
+        if let Err(e) = self.sender.send(lolite_common::WorkerRequest::SetLimits {
+            handle: self.handle as u64,
+            max_nodes: encode(limits.max_nodes),
+            max_tree_depth: encode(limits.max_tree_depth),
+            max_stylesheet_bytes: encode(limits.max_stylesheet_bytes),
+            max_selector_complexity: encode(limits.max_selector_complexity),
+            reply_to: reply_tx,
+        }) {
+            eprintln!("Failed to send SetLimits to worker: {e}");
+            return -1;
+        }
+
+        match reply_rx.recv() {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("Failed to receive SetLimits response: {e}");
+                -1
+            }
+        }
+    }
+
     fn root_id(&self) -> LoliteId {
         let (reply_tx, reply_rx) = match ipc::channel::<u64>() {
             Ok(ch) => ch,
@@ -122,7 +204,33 @@ impl EngineBackend for WorkerBackend {
         }
     }
 
+    fn client_to_node(&self, node_id: LoliteId, x: f64, y: f64) -> Option<(f64, f64)> {
+        self.convert_point("ClientToNode", |reply_to| {
+            lolite_common::WorkerRequest::ClientToNode {
+                handle: self.handle as u64,
+                node_id,
+                x,
+                y,
+                reply_to,
+            }
+        })
+    }
+
+    fn node_to_client(&self, node_id: LoliteId, x: f64, y: f64) -> Option<(f64, f64)> {
+        self.convert_point("NodeToClient", |reply_to| {
+            lolite_common::WorkerRequest::NodeToClient {
+                handle: self.handle as u64,
+                node_id,
+                x,
+                y,
+                reply_to,
+            }
+        })
+    }
+
     fn run(&self) -> c_int {
+        self.ran_event_loop.store(true, Ordering::SeqCst);
+
         let (reply_tx, reply_rx) = match ipc::channel::<i32>() {
             Ok(ch) => ch,
             Err(e) => {
@@ -166,7 +274,10 @@ impl EngineBackend for WorkerBackend {
         }
 
         match reply_rx.recv() {
-            Ok(code) => code,
+            Ok(code) => {
+                self.destroyed.store(code == 0, Ordering::SeqCst);
+                code
+            }
             Err(e) => {
                 eprintln!("Failed to receive Destroy response: {e}");
                 -1
@@ -177,43 +288,12 @@ impl EngineBackend for WorkerBackend {
 
 impl Drop for WorkerBackend {
     fn drop(&mut self) {
-        self.shutdown();
-        let _ = self.process.kill();
-    }
-}
-
-#[cfg(windows)]
-const WORKER_FILE: &str = "lolite_worker.exe";
-#[cfg(not(windows))]
-const WORKER_FILE: &str = "lolite_worker";
-
-fn spawn_worker(method: &str, connection_key: &str) -> std::io::Result<Child> {
-    let worker_path = resolve_worker_path().expect("Failed to resolve worker path");
-
-    println!("Running worker at {worker_path:?}");
-
-    Command::new(worker_path)
-        .arg(method)
-        .arg(connection_key)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-}
-
-fn resolve_worker_path() -> Option<PathBuf> {
-    if let Ok(path) = std::env::var("LOLITE_WORKER_PATH") {
-        return Some(PathBuf::from(path));
-    }
-
-    if let Ok(exe_path) = std::env::current_exe() {
-        if let Some(dir) = exe_path.parent() {
-            let candidate = dir.join(WORKER_FILE);
-            if candidate.exists() {
-                return Some(candidate);
-            }
+        let Some(worker) = self.worker.take() else {
+            return;
+        };
+        // A worker still hosting the engine, or that ran an event loop, is shut down
+        if self.destroyed.load(Ordering::SeqCst) && !self.ran_event_loop.load(Ordering::SeqCst) {
+            worker_pool::release_worker(worker);
         }
     }
-
-    // We do not do PATH lookup, so we return None
-    None
 }
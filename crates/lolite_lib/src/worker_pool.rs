@@ -0,0 +1,167 @@
+use ipc_channel::ipc::{IpcOneShotServer, IpcSender};
+use lolite_common::WorkerRequest;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{LazyLock, Mutex};
+
+/// A worker process that has connected back to us, ready to host engines.
+pub struct WorkerProcess {
+    process: Child,
+    pub sender: IpcSender<WorkerRequest>,
+}
+
+impl WorkerProcess {
+    /// Spawn a worker and wait for it to send the channel we send requests on.
+    fn spawn() -> std::io::Result<Self> {
+        let (server, server_name) = IpcOneShotServer::<IpcSender<WorkerRequest>>::new()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+
+        let process = spawn_worker("ipc_channel", &server_name)?;
+
+        let (_rx, sender) = server
+            .accept()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+
+        Ok(Self { process, sender })
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.process.try_wait(), Ok(None))
+    }
+}
+
+impl Drop for WorkerProcess {
+    fn drop(&mut self) {
+        let _ = self.sender.send(WorkerRequest::Shutdown);
+        let _ = self.process.kill();
+    }
+}
+
+/// Idle workers kept around so that creating an engine doesn't wait for a process to
+/// start and connect.
+struct WorkerPool {
+    idle: Vec<WorkerProcess>,
+    /// How many idle workers to keep; 0 disables the pool.
+    size: usize,
+    /// Workers being spawned to fill the pool.
+    spawning: usize,
+}
+
+static WORKER_POOL: LazyLock<Mutex<WorkerPool>> = LazyLock::new(|| {
+    Mutex::new(WorkerPool {
+        idle: Vec::new(),
+        size: 0,
+        spawning: 0,
+    })
+});
+
+/// Keep `size` idle workers, spawning the missing ones before returning and shutting
+/// down the extra ones.
+pub fn set_pool_size(size: usize) -> std::io::Result<()> {
+    let excess = {
+        let mut pool = WORKER_POOL.lock().unwrap();
+        pool.size = size;
+        let keep = pool.idle.len().min(size);
+        pool.idle.split_off(keep)
+    };
+    drop(excess);
+    fill_pool()
+}
+
+/// Spawn workers until the pool, counting the workers already being spawned, is full.
+fn fill_pool() -> std::io::Result<()> {
+    loop {
+        {
+            let mut pool = WORKER_POOL.lock().unwrap();
+            if pool.idle.len() + pool.spawning >= pool.size {
+                return Ok(());
+            }
+            pool.spawning += 1;
+        }
+
+        // Catch panics too, so that `spawning` always goes back down
+        let worker = std::panic::catch_unwind(WorkerProcess::spawn).unwrap_or_else(|_| {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "panicked while spawning a worker",
+            ))
+        });
+
+        let mut pool = WORKER_POOL.lock().unwrap();
+        pool.spawning -= 1;
+        let worker = worker?;
+        if pool.idle.len() < pool.size {
+            pool.idle.push(worker);
+        }
+    }
+}
+
+/// Take an idle worker from the pool, refilling it in the background, or spawn one if
+/// the pool is empty.
+pub fn take_worker() -> std::io::Result<WorkerProcess> {
+    loop {
+        let worker = WORKER_POOL.lock().unwrap().idle.pop();
+        let Some(mut worker) = worker else {
+            return WorkerProcess::spawn();
+        };
+        // Skip workers that exited while idle
+        if worker.is_alive() {
+            std::thread::spawn(|| {
+                if let Err(e) = fill_pool() {
+                    eprintln!("Failed to refill worker pool: {e}");
+                }
+            });
+            return Ok(worker);
+        }
+    }
+}
+
+/// Return a worker whose engines have all been destroyed, to be reused by a later
+/// engine. The worker is shut down if the pool is disabled or full.
+pub fn release_worker(worker: WorkerProcess) {
+    let mut pool = WORKER_POOL.lock().unwrap();
+    if pool.idle.len() < pool.size {
+        pool.idle.push(worker);
+    }
+}
+
+#[cfg(windows)]
+const WORKER_FILE: &str = "lolite_worker.exe";
+#[cfg(not(windows))]
+const WORKER_FILE: &str = "lolite_worker";
+
+fn spawn_worker(method: &str, connection_key: &str) -> std::io::Result<Child> {
+    let worker_path = resolve_worker_path().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{WORKER_FILE} not found next to the executable; set LOLITE_WORKER_PATH"),
+        )
+    })?;
+
+    println!("Running worker at {worker_path:?}");
+
+    Command::new(worker_path)
+        .arg(method)
+        .arg(connection_key)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+}
+
+fn resolve_worker_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("LOLITE_WORKER_PATH") {
+        return Some(PathBuf::from(path));
+    }
+
+    if let Ok(exe_path) = std::env::current_exe() {
+        if let Some(dir) = exe_path.parent() {
+            let candidate = dir.join(WORKER_FILE);
+            if candidate.exists() {
+                return Some(candidate);
+            }
+        }
+    }
+
+    // We do not do PATH lookup, so we return None
+    None
+}
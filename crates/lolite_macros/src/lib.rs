@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
-#[proc_macro_derive(MergeProperties, attributes(merge_by_method_call))]
+#[proc_macro_derive(MergeProperties, attributes(merge_by_method_call, merge_skip))]
 pub fn derive_merge_properties(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -33,6 +33,15 @@ pub fn derive_merge_properties(input: TokenStream) -> TokenStream {
             };
         }
 
+        let merge_skip = field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("merge_skip"));
+
+        if merge_skip {
+            return quote! {};
+        }
+
         let is_option = if let syn::Type::Path(ty) = &field.ty {
             ty.path.segments.len() == 1 && ty.path.segments[0].ident == "Option"
         } else {
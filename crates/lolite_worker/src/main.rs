@@ -1,6 +1,6 @@
 use ipc_channel::ipc;
 use libloading::Library;
-use lolite_common::WorkerRequest;
+use lolite_common::{Mutation, WorkerRequest};
 use std::env;
 use std::ffi::CString;
 use std::os::raw::c_char;
@@ -13,7 +13,12 @@ type LoliteAddStylesheet = unsafe extern "C" fn(EngineHandle, *const c_char);
 type LoliteCreateNode = unsafe extern "C" fn(EngineHandle, u64, *const c_char) -> u64;
 type LoliteSetParent = unsafe extern "C" fn(EngineHandle, u64, u64);
 type LoliteSetAttribute = unsafe extern "C" fn(EngineHandle, u64, *const c_char, *const c_char);
+type LoliteBeginTransaction = unsafe extern "C" fn(EngineHandle) -> i32;
+type LoliteCommitTransaction = unsafe extern "C" fn(EngineHandle) -> i32;
+type LoliteSetLimits = unsafe extern "C" fn(EngineHandle, u64, u64, u64, u64) -> i32;
 type LoliteRootId = unsafe extern "C" fn(EngineHandle) -> u64;
+type LoliteConvertPoint =
+    unsafe extern "C" fn(EngineHandle, u64, f64, f64, *mut f64, *mut f64) -> i32;
 type LoliteRun = unsafe extern "C" fn(EngineHandle) -> i32;
 type LoliteDestroy = unsafe extern "C" fn(EngineHandle) -> i32;
 
@@ -69,9 +74,24 @@ fn main() {
         let lolite_set_attribute: libloading::Symbol<LoliteSetAttribute> = lib
             .get(b"lolite_set_attribute\0")
             .expect("worker: missing symbol lolite_set_attribute");
+        let lolite_begin_transaction: libloading::Symbol<LoliteBeginTransaction> = lib
+            .get(b"lolite_begin_transaction\0")
+            .expect("worker: missing symbol lolite_begin_transaction");
+        let lolite_commit_transaction: libloading::Symbol<LoliteCommitTransaction> = lib
+            .get(b"lolite_commit_transaction\0")
+            .expect("worker: missing symbol lolite_commit_transaction");
+        let lolite_set_limits: libloading::Symbol<LoliteSetLimits> = lib
+            .get(b"lolite_set_limits\0")
+            .expect("worker: missing symbol lolite_set_limits");
         let lolite_root_id: libloading::Symbol<LoliteRootId> = lib
             .get(b"lolite_root_id\0")
             .expect("worker: missing symbol lolite_root_id");
+        let lolite_client_to_node: libloading::Symbol<LoliteConvertPoint> = lib
+            .get(b"lolite_client_to_node\0")
+            .expect("worker: missing symbol lolite_client_to_node");
+        let lolite_node_to_client: libloading::Symbol<LoliteConvertPoint> = lib
+            .get(b"lolite_node_to_client\0")
+            .expect("worker: missing symbol lolite_node_to_client");
         let lolite_run: libloading::Symbol<LoliteRun> = lib
             .get(b"lolite_run\0")
             .expect("worker: missing symbol lolite_run");
@@ -79,47 +99,38 @@ fn main() {
             .get(b"lolite_destroy\0")
             .expect("worker: missing symbol lolite_destroy");
 
-        loop {
-            let msg = match rx.recv() {
-                Ok(m) => m,
-                Err(e) => {
-                    eprintln!("worker: ipc receive error: {e}");
-                    break;
-                }
-            };
+        let convert_point = |convert: &LoliteConvertPoint, handle: u64, node_id, x, y| {
+            let (mut out_x, mut out_y) = (0.0, 0.0);
+            let code = convert(
+                handle as EngineHandle,
+                node_id,
+                x,
+                y,
+                &mut out_x,
+                &mut out_y,
+            );
+            (code == 0).then_some((out_x, out_y))
+        };
 
-            match msg {
-                WorkerRequest::InitInternal { handle } => {
-                    lolite_init_internal(handle as EngineHandle);
-                }
-                WorkerRequest::AddStylesheet { handle, css } => match CString::new(css) {
+        let apply_mutation = |handle: u64, mutation: Mutation| {
+            let handle = handle as EngineHandle;
+            match mutation {
+                Mutation::AddStylesheet { css } => match CString::new(css) {
                     Ok(c_css) => {
-                        lolite_add_stylesheet(handle as EngineHandle, c_css.as_ptr());
+                        lolite_add_stylesheet(handle, c_css.as_ptr());
                     }
                     Err(_) => {
                         eprintln!("worker: stylesheet contains interior NUL byte");
                     }
                 },
-                WorkerRequest::CreateNode {
-                    handle,
-                    node_id,
-                    text,
-                } => {
+                Mutation::CreateNode { node_id, text } => {
                     match text {
                         None => {
-                            let _ = lolite_create_node(
-                                handle as EngineHandle,
-                                node_id,
-                                std::ptr::null(),
-                            );
+                            let _ = lolite_create_node(handle, node_id, std::ptr::null());
                         }
                         Some(s) => match CString::new(s) {
                             Ok(c_text) => {
-                                let _ = lolite_create_node(
-                                    handle as EngineHandle,
-                                    node_id,
-                                    c_text.as_ptr(),
-                                );
+                                let _ = lolite_create_node(handle, node_id, c_text.as_ptr());
                             }
                             Err(_) => {
                                 eprintln!("worker: text content contains interior NUL byte");
@@ -127,15 +138,13 @@ fn main() {
                         },
                     };
                 }
-                WorkerRequest::SetParent {
-                    handle,
+                Mutation::SetParent {
                     parent_id,
                     child_id,
                 } => {
-                    lolite_set_parent(handle as EngineHandle, parent_id, child_id);
+                    lolite_set_parent(handle, parent_id, child_id);
                 }
-                WorkerRequest::SetAttribute {
-                    handle,
+                Mutation::SetAttribute {
                     node_id,
                     key,
                     value,
@@ -144,28 +153,129 @@ fn main() {
                         Ok(s) => s,
                         Err(_) => {
                             eprintln!("worker: attribute key contains interior NUL byte");
-                            continue;
+                            return;
                         }
                     };
                     let c_value = match CString::new(value) {
                         Ok(s) => s,
                         Err(_) => {
                             eprintln!("worker: attribute value contains interior NUL byte");
-                            continue;
+                            return;
                         }
                     };
 
-                    lolite_set_attribute(
+                    lolite_set_attribute(handle, node_id, c_key.as_ptr(), c_value.as_ptr());
+                }
+            }
+        };
+
+        loop {
+            let msg = match rx.recv() {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("worker: ipc receive error: {e}");
+                    break;
+                }
+            };
+
+            match msg {
+                WorkerRequest::InitInternal { handle } => {
+                    lolite_init_internal(handle as EngineHandle);
+                }
+                WorkerRequest::AddStylesheet { handle, css } => {
+                    apply_mutation(handle, Mutation::AddStylesheet { css });
+                }
+                WorkerRequest::CreateNode {
+                    handle,
+                    node_id,
+                    text,
+                } => {
+                    apply_mutation(handle, Mutation::CreateNode { node_id, text });
+                }
+                WorkerRequest::SetParent {
+                    handle,
+                    parent_id,
+                    child_id,
+                } => {
+                    apply_mutation(
+                        handle,
+                        Mutation::SetParent {
+                            parent_id,
+                            child_id,
+                        },
+                    );
+                }
+                WorkerRequest::SetAttribute {
+                    handle,
+                    node_id,
+                    key,
+                    value,
+                } => {
+                    apply_mutation(
+                        handle,
+                        Mutation::SetAttribute {
+                            node_id,
+                            key,
+                            value,
+                        },
+                    );
+                }
+                WorkerRequest::Transaction {
+                    handle,
+                    mutations,
+                    reply_to,
+                } => {
+                    // Queue the mutations in the library and apply them all at commit.
+                    let mut code = lolite_begin_transaction(handle as EngineHandle);
+                    if code == 0 {
+                        for mutation in mutations {
+                            apply_mutation(handle, mutation);
+                        }
+                        code = lolite_commit_transaction(handle as EngineHandle);
+                    }
+                    let _ = reply_to.send(code);
+                }
+                WorkerRequest::SetLimits {
+                    handle,
+                    max_nodes,
+                    max_tree_depth,
+                    max_stylesheet_bytes,
+                    max_selector_complexity,
+                    reply_to,
+                } => {
+                    let code = lolite_set_limits(
                         handle as EngineHandle,
-                        node_id,
-                        c_key.as_ptr(),
-                        c_value.as_ptr(),
+                        max_nodes,
+                        max_tree_depth,
+                        max_stylesheet_bytes,
+                        max_selector_complexity,
                     );
+                    let _ = reply_to.send(code);
                 }
                 WorkerRequest::RootId { handle, reply_to } => {
                     let id = lolite_root_id(handle as EngineHandle);
                     let _ = reply_to.send(id);
                 }
+                WorkerRequest::ClientToNode {
+                    handle,
+                    node_id,
+                    x,
+                    y,
+                    reply_to,
+                } => {
+                    let point = convert_point(&lolite_client_to_node, handle, node_id, x, y);
+                    let _ = reply_to.send(point);
+                }
+                WorkerRequest::NodeToClient {
+                    handle,
+                    node_id,
+                    x,
+                    y,
+                    reply_to,
+                } => {
+                    let point = convert_point(&lolite_node_to_client, handle, node_id, x, y);
+                    let _ = reply_to.send(point);
+                }
                 WorkerRequest::Run { handle, reply_to } => {
                     let code = lolite_run(handle as EngineHandle);
                     let _ = reply_to.send(code);
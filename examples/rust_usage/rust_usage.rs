@@ -1,6 +1,6 @@
-use lolite::{Engine, Id};
+use lolite::{Engine, EngineError, Id};
 
-fn main() {
+fn main() -> Result<(), EngineError> {
     // Create a thread-safe CSS engine
     let engine = Engine::new();
 
@@ -28,34 +28,45 @@ fn main() {
     "#;
 
     // Parse the CSS and load it into the engine
-    engine.add_stylesheet(css_content);
+    engine.add_stylesheet(css_content)?;
 
     // Create document structure
     let root = engine.root_id();
     let mut next_id = 1u64;
 
-    let a = engine.create_node(Id::from_u64(next_id), Some("Hello".to_string()));
+    let a = engine.create_node(Id::from_u64(next_id), Some("Hello".to_string()))?;
     next_id += 1;
-    let b = engine.create_node(Id::from_u64(next_id), Some("World".to_string()));
+    let b = engine.create_node(Id::from_u64(next_id), Some("World".to_string()))?;
     next_id += 1;
-    let c = engine.create_node(Id::from_u64(next_id), Some("xD".to_string()));
+    let c = engine.create_node(Id::from_u64(next_id), Some("xD".to_string()))?;
 
-    engine.set_parent(root, a);
-    engine.set_parent(root, b);
-    engine.set_parent(root, c);
+    engine.set_parent(root, a)?;
+    engine.set_parent(root, b)?;
+    engine.set_parent(root, c)?;
 
-    engine.set_attribute(root, "class".to_owned(), "flex_container".to_owned());
-    engine.set_attribute(a, "class".to_owned(), "red_box".to_owned());
-    engine.set_attribute(b, "class".to_owned(), "green_box".to_owned());
+    engine.set_attribute(root, "class".to_owned(), "flex_container".to_owned())?;
+    engine.set_attribute(a, "class".to_owned(), "red_box".to_owned())?;
+    engine.set_attribute(b, "class".to_owned(), "green_box".to_owned())?;
 
     // Run
     let params = lolite::Params {
         on_click: Some(Box::new(|x, y, elements| {
             println!("Clicked at ({}, {}), elements: {:?}", x, y, elements);
         })),
+        on_key: Some(Box::new(|window, node, event| {
+            if event.pressed {
+                println!(
+                    "Key {:?} in {:?}, focused node: {:?}",
+                    event.key, window, node
+                );
+            }
+        })),
+        ..Default::default()
     };
 
     if let Err(e) = engine.run(params) {
         eprintln!("Error encountered: {:?}", e);
     }
+
+    Ok(())
 }
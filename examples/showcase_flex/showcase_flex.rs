@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use lolite::{Engine, Id, Params};
+use lolite::{Engine, EngineError, Id, Params, WindowOptions};
 
 fn next_id() -> Id {
     use std::sync::atomic::{AtomicU64, Ordering};
@@ -75,7 +75,7 @@ fn apply_state(
     item1: Id,
     item2: Id,
     item3: Id,
-) {
+) -> Result<(), EngineError> {
     let justify_class = match state.justify_content {
         JustifyContent::FlexStart => "justify-flex-start",
         JustifyContent::FlexEnd => "justify-flex-end",
@@ -124,7 +124,7 @@ fn apply_state(
             flex_container,
             "class".to_owned(),
             container_classes_joined.clone(),
-        );
+        )?;
         state.previous_container_classes = container_classes_joined;
     }
 
@@ -165,7 +165,7 @@ fn apply_state(
 
     if state.previous_item1_classes != item1_classes_joined {
         println!("Item1 classes changed: {item1_classes_joined}");
-        engine.set_attribute(item1, "class".to_owned(), item1_classes_joined.clone());
+        engine.set_attribute(item1, "class".to_owned(), item1_classes_joined.clone())?;
         state.previous_item1_classes = item1_classes_joined;
     }
 
@@ -177,25 +177,27 @@ fn apply_state(
             item2,
             "class".to_owned(),
             format!("box green_box {}", item2_class),
-        );
+        )?;
         engine.set_attribute(
             item3,
             "class".to_owned(),
             format!("box blue_box {}", item3_class),
-        );
+        )?;
         state.previous_item2_classes = item2_class.to_string();
         state.previous_item3_classes = item3_class.to_string();
     }
+
+    Ok(())
 }
 
-fn div(engine: &Engine, text: Option<String>, parent: Id, class: &str) -> Id {
-    let node = engine.create_node(next_id(), text);
-    engine.set_parent(parent, node);
-    engine.set_attribute(node, "class".to_owned(), class.to_owned());
-    node
+fn div(engine: &Engine, text: Option<String>, parent: Id, class: &str) -> Result<Id, EngineError> {
+    let node = engine.create_node(next_id(), text)?;
+    engine.set_parent(parent, node)?;
+    engine.set_attribute(node, "class".to_owned(), class.to_owned())?;
+    Ok(node)
 }
 
-fn main() {
+fn main() -> Result<(), EngineError> {
     // Create a thread-safe CSS engine
     let engine = Engine::new();
 
@@ -299,7 +301,7 @@ fn main() {
     "#;
 
     // Parse the CSS and load it into the engine
-    engine.add_stylesheet(css_content);
+    engine.add_stylesheet(css_content)?;
 
     // Create document structure
     let root = engine.root_id();
@@ -308,52 +310,52 @@ fn main() {
     let state = Rc::new(RefCell::new(State::default()));
 
     // Buttons
-    let top_box = div(&engine, None, root, "buttons");
-    let wrap_button = div(&engine, Some("Wrap".to_string()), top_box, "button");
-    let direction_button = div(&engine, Some("Direction".to_string()), top_box, "button");
+    let top_box = div(&engine, None, root, "buttons")?;
+    let wrap_button = div(&engine, Some("Wrap".to_string()), top_box, "button")?;
+    let direction_button = div(&engine, Some("Direction".to_string()), top_box, "button")?;
     let justify_content_button = div(
         &engine,
         Some("Justify Content".to_string()),
         top_box,
         "button",
-    );
+    )?;
 
-    let align_items_button = div(&engine, Some("Align Items".to_string()), top_box, "button");
+    let align_items_button = div(&engine, Some("Align Items".to_string()), top_box, "button")?;
     let align_content_button = div(
         &engine,
         Some("Align Content".to_string()),
         top_box,
         "button",
-    );
-    let align_self_button = div(&engine, Some("Align Self".to_string()), top_box, "button");
-    let gap_button = div(&engine, Some("Gap".to_string()), top_box, "button");
-    let order_button = div(&engine, Some("Order".to_string()), top_box, "button");
-    let grow_button = div(&engine, Some("Grow".to_string()), top_box, "button");
-    let shrink_button = div(&engine, Some("Shrink".to_string()), top_box, "button");
-    let basis_button = div(&engine, Some("Basis".to_string()), top_box, "button");
-    let flex_button = div(&engine, Some("Flex".to_string()), top_box, "button");
+    )?;
+    let align_self_button = div(&engine, Some("Align Self".to_string()), top_box, "button")?;
+    let gap_button = div(&engine, Some("Gap".to_string()), top_box, "button")?;
+    let order_button = div(&engine, Some("Order".to_string()), top_box, "button")?;
+    let grow_button = div(&engine, Some("Grow".to_string()), top_box, "button")?;
+    let shrink_button = div(&engine, Some("Shrink".to_string()), top_box, "button")?;
+    let basis_button = div(&engine, Some("Basis".to_string()), top_box, "button")?;
+    let flex_button = div(&engine, Some("Flex".to_string()), top_box, "button")?;
 
     // Example
-    let flex_container = div(&engine, None, root, "flex_container");
+    let flex_container = div(&engine, None, root, "flex_container")?;
 
     let item1 = div(
         &engine,
         Some("First Item".to_string()),
         flex_container,
         "box red_box",
-    );
+    )?;
     let item2 = div(
         &engine,
         Some("Second Item".to_string()),
         flex_container,
         "box green_box",
-    );
+    )?;
     let item3 = div(
         &engine,
         Some("Third Item".to_string()),
         flex_container,
         "box blue_box",
-    );
+    )?;
 
     // Initial state application
     apply_state(
@@ -363,7 +365,7 @@ fn main() {
         item1,
         item2,
         item3,
-    );
+    )?;
 
     // Run
     let params = Params {
@@ -432,12 +434,26 @@ fn main() {
                     return;
                 }
 
-                apply_state(&engine, &mut state, flex_container, item1, item2, item3);
+                if let Err(e) =
+                    apply_state(&engine, &mut state, flex_container, item1, item2, item3)
+                {
+                    eprintln!("Failed to apply state: {:?}", e);
+                }
             }))
         },
+        ..Default::default()
     };
 
+    engine.window_options(WindowOptions {
+        title: "Lolite - Flexbox showcase".to_string(),
+        inner_size: (900.0, 700.0),
+        min_inner_size: Some((400.0, 300.0)),
+        ..Default::default()
+    });
+
     if let Err(e) = engine.run(params) {
         eprintln!("Error encountered: {:?}", e);
     }
+
+    Ok(())
 }
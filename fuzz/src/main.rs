@@ -0,0 +1,509 @@
+//! Differential testing of lolite's flex layout against Taffy.
+//!
+//! Generates random trees of flex containers and items, lays each out with both
+//! engines and reports the cases where a box differs by more than the tolerance.
+//! Every case derives from `--seed` plus its index, so `--case` replays one with the
+//! stylesheet and both layouts printed.
+
+use lolite::{Engine, Id};
+use std::process::ExitCode;
+use taffy::prelude::*;
+
+const VIEWPORT: (f32, f32) = (800.0, 600.0);
+
+struct Options {
+    cases: u64,
+    seed: u64,
+    tolerance: f32,
+    case: Option<u64>,
+}
+
+impl Options {
+    fn parse() -> Result<Self, String> {
+        let mut options = Options {
+            cases: 500,
+            seed: 0,
+            tolerance: 0.5,
+            case: None,
+        };
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--cases" => options.cases = parse_arg(&value()?)?,
+                "--seed" => options.seed = parse_arg(&value()?)?,
+                "--tolerance" => options.tolerance = parse_arg(&value()?)?,
+                "--case" => options.case = Some(parse_arg(&value()?)?),
+                _ => {
+                    return Err(format!(
+                        "unknown argument {}; expected --cases, --seed, --tolerance or --case",
+                        arg
+                    ))
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn parse_arg<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {}", value))
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse() {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Some(case) = options.case {
+        let tree = Sample::generate(&mut Rng::new(options.seed.wrapping_add(case)));
+        let comparison = compare(&tree);
+        println!("{}", tree.stylesheet());
+        for (path, lolite, taffy) in &comparison {
+            println!("{:<12} lolite {:?}  taffy {:?}", path, lolite, taffy);
+        }
+        return match first_divergence(&comparison, options.tolerance) {
+            Some(path) => {
+                println!("diverges at {}", path);
+                ExitCode::FAILURE
+            }
+            None => ExitCode::SUCCESS,
+        };
+    }
+
+    let mut diverged = 0;
+    for case in 0..options.cases {
+        let tree = Sample::generate(&mut Rng::new(options.seed.wrapping_add(case)));
+        if let Some(path) = first_divergence(&compare(&tree), options.tolerance) {
+            diverged += 1;
+            println!("case {} diverges at {}", case, path);
+        }
+    }
+    println!(
+        "{} of {} cases diverged (seed {}, tolerance {}px)",
+        diverged, options.cases, options.seed, options.tolerance
+    );
+    if diverged > 0 {
+        println!("replay one with --seed {} --case <case>", options.seed);
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Path of the first box whose position or size differs by more than `tolerance`.
+fn first_divergence(comparison: &[(String, Bounds, Bounds)], tolerance: f32) -> Option<&str> {
+    comparison
+        .iter()
+        .find(|(_, lolite, taffy)| {
+            lolite
+                .iter()
+                .zip(taffy)
+                .any(|(a, b)| (a - b).abs() > tolerance)
+        })
+        .map(|(path, _, _)| path.as_str())
+}
+
+/// `x`, `y`, `width` and `height` in the viewport.
+type Bounds = [f32; 4];
+
+/// Lay a tree out with both engines, returning the bounds of every box in document
+/// order, named by their path from the root such as `0.2.1`.
+fn compare(tree: &Sample) -> Vec<(String, Bounds, Bounds)> {
+    let lolite = lolite_layout(tree);
+    let taffy = taffy_layout(tree);
+    let mut paths = Vec::new();
+    tree.paths("0".to_string(), &mut paths);
+    paths
+        .into_iter()
+        .zip(lolite.into_iter().zip(taffy))
+        .map(|(path, (lolite, taffy))| (path, lolite, taffy))
+        .collect()
+}
+
+fn lolite_layout(tree: &Sample) -> Vec<Bounds> {
+    let engine = Engine::new();
+    engine
+        .set_viewport(VIEWPORT.0 as f64, VIEWPORT.1 as f64)
+        .unwrap();
+    engine.add_stylesheet(&tree.stylesheet()).unwrap();
+    engine
+        .set_attribute(engine.root_id(), "class".to_string(), "root".to_string())
+        .unwrap();
+
+    let mut ids = Vec::new();
+    let mut pending = vec![(tree, engine.root_id())];
+    while let Some((node, parent)) = pending.pop() {
+        let id = engine
+            .create_node(Id::from_u64(ids.len() as u64 + 1), None)
+            .unwrap();
+        engine
+            .set_attribute(id, "class".to_string(), format!("n{}", ids.len()))
+            .unwrap();
+        engine.set_parent(parent, id).unwrap();
+        ids.push(id);
+        pending.extend(node.children.iter().rev().map(|child| (child, id)));
+    }
+    engine.flush().unwrap();
+
+    ids.into_iter()
+        .map(|id| {
+            let b = engine.node_bounds(id).expect("laid out");
+            [b.x as f32, b.y as f32, b.width as f32, b.height as f32]
+        })
+        .collect()
+}
+
+fn taffy_layout(tree: &Sample) -> Vec<Bounds> {
+    fn build(taffy: &mut TaffyTree, node: &Sample) -> NodeId {
+        let children: Vec<_> = node
+            .children
+            .iter()
+            .map(|child| build(taffy, child))
+            .collect();
+        taffy
+            .new_with_children(node.taffy_style(), &children)
+            .unwrap()
+    }
+
+    let mut taffy = TaffyTree::new();
+    let top = build(&mut taffy, tree);
+    // Stand-in for lolite's root, a viewport-sized flex container
+    let root = taffy
+        .new_with_children(
+            Style {
+                size: Size {
+                    width: length(VIEWPORT.0),
+                    height: length(VIEWPORT.1),
+                },
+                ..Style::default()
+            },
+            &[top],
+        )
+        .unwrap();
+    taffy
+        .compute_layout(
+            root,
+            Size {
+                width: AvailableSpace::Definite(VIEWPORT.0),
+                height: AvailableSpace::Definite(VIEWPORT.1),
+            },
+        )
+        .unwrap();
+
+    // Taffy positions boxes relative to their parent
+    let mut bounds = Vec::new();
+    let mut pending = vec![(top, (0.0, 0.0))];
+    while let Some((node, (x, y))) = pending.pop() {
+        let layout = taffy.layout(node).unwrap();
+        let origin = (x + layout.location.x, y + layout.location.y);
+        bounds.push([origin.0, origin.1, layout.size.width, layout.size.height]);
+        let children = taffy.children(node).unwrap();
+        pending.extend(children.into_iter().rev().map(|child| (child, origin)));
+    }
+    bounds
+}
+
+/// Deterministic splitmix64, so that cases replay the same on every platform.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    /// Multiple of 5 up to `max`, keeping layouts free of rounding noise.
+    fn px(&mut self, max: u32) -> f32 {
+        (self.below(max as usize / 5 + 1) * 5) as f32
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+const DIRECTIONS: [(&str, FlexDirection); 4] = [
+    ("row", FlexDirection::Row),
+    ("column", FlexDirection::Column),
+    ("row-reverse", FlexDirection::RowReverse),
+    ("column-reverse", FlexDirection::ColumnReverse),
+];
+const JUSTIFY: [(&str, JustifyContent); 6] = [
+    ("flex-start", JustifyContent::FlexStart),
+    ("flex-end", JustifyContent::FlexEnd),
+    ("center", JustifyContent::Center),
+    ("space-between", JustifyContent::SpaceBetween),
+    ("space-around", JustifyContent::SpaceAround),
+    ("space-evenly", JustifyContent::SpaceEvenly),
+];
+const ALIGN: [(&str, AlignItems); 4] = [
+    ("stretch", AlignItems::Stretch),
+    ("flex-start", AlignItems::FlexStart),
+    ("flex-end", AlignItems::FlexEnd),
+    ("center", AlignItems::Center),
+];
+const ALIGN_CONTENT: [(&str, AlignContent); 5] = [
+    ("stretch", AlignContent::Stretch),
+    ("flex-start", AlignContent::FlexStart),
+    ("flex-end", AlignContent::FlexEnd),
+    ("center", AlignContent::Center),
+    ("space-between", AlignContent::SpaceBetween),
+];
+
+/// A generated flex container or item; unset properties keep their initial values.
+#[derive(Default)]
+struct Sample {
+    direction: Option<usize>,
+    wrap: bool,
+    justify: Option<usize>,
+    align_items: Option<usize>,
+    align_content: Option<usize>,
+    align_self: Option<usize>,
+    width: Option<f32>,
+    height: Option<f32>,
+    min_width: Option<f32>,
+    max_width: Option<f32>,
+    grow: Option<f32>,
+    shrink: Option<f32>,
+    basis: Option<f32>,
+    gap: Option<f32>,
+    /// Top, right, bottom and left.
+    margin: [f32; 4],
+    padding: [f32; 4],
+    children: Vec<Sample>,
+}
+
+impl Sample {
+    /// The top container, sized to fit the viewport, and its descendants.
+    fn generate(rng: &mut Rng) -> Self {
+        let mut top = Self::item(rng, 0);
+        top.width = Some(100.0 + rng.px(600));
+        top.height = Some(100.0 + rng.px(400));
+        top.min_width = None;
+        top.max_width = None;
+        top.margin = [0.0; 4];
+        top
+    }
+
+    fn item(rng: &mut Rng, depth: usize) -> Self {
+        let mut node = Sample::default();
+        if rng.chance(60) {
+            node.width = Some(rng.px(200));
+        }
+        if rng.chance(60) {
+            node.height = Some(rng.px(150));
+        }
+        if rng.chance(15) {
+            node.min_width = Some(rng.px(100));
+        }
+        if rng.chance(15) {
+            node.max_width = Some(rng.px(200));
+        }
+        if rng.chance(40) {
+            node.grow = Some(rng.below(4) as f32);
+        }
+        if rng.chance(30) {
+            node.shrink = Some(rng.below(4) as f32);
+        }
+        if rng.chance(25) {
+            node.basis = Some(rng.px(200));
+        }
+        if rng.chance(20) {
+            node.align_self = Some(rng.below(ALIGN.len()));
+        }
+        if rng.chance(30) {
+            node.margin = [(); 4].map(|_| rng.px(20));
+        }
+
+        let child_count = match depth {
+            0 => 1 + rng.below(6),
+            1 | 2 if rng.chance(30) => 1 + rng.below(4),
+            _ => 0,
+        };
+        if child_count > 0 {
+            node.direction = Some(rng.below(DIRECTIONS.len()));
+            node.wrap = rng.chance(30);
+            node.justify = Some(rng.below(JUSTIFY.len()));
+            node.align_items = Some(rng.below(ALIGN.len()));
+            if node.wrap {
+                node.align_content = Some(rng.below(ALIGN_CONTENT.len()));
+            }
+            if rng.chance(30) {
+                node.gap = Some(rng.px(20));
+            }
+            if rng.chance(30) {
+                node.padding = [(); 4].map(|_| rng.px(20));
+            }
+            node.children = (0..child_count)
+                .map(|_| Self::item(rng, depth + 1))
+                .collect();
+        }
+        node
+    }
+
+    /// Paths of this box and its descendants in document order.
+    fn paths(&self, path: String, paths: &mut Vec<String>) {
+        paths.push(path.clone());
+        for (i, child) in self.children.iter().enumerate() {
+            child.paths(format!("{}.{}", path, i), paths);
+        }
+    }
+
+    /// One rule per box, selected by a class numbered in document order, after one
+    /// making lolite's root a flex container like Taffy's stand-in for it.
+    fn stylesheet(&self) -> String {
+        let mut css = String::from(".root { display: flex; }\n");
+        let mut pending = vec![self];
+        let mut index = 0;
+        while let Some(node) = pending.pop() {
+            css.push_str(&format!(".n{} {{ {}}}\n", index, node.declarations()));
+            index += 1;
+            pending.extend(node.children.iter().rev());
+        }
+        css
+    }
+
+    fn declarations(&self) -> String {
+        let mut css = String::new();
+        let mut push = |property: &str, value: String| {
+            css.push_str(&format!("{}: {}; ", property, value));
+        };
+        let px = |value: f32| format!("{}px", value);
+        let sides = |[top, right, bottom, left]: [f32; 4]| {
+            format!("{}px {}px {}px {}px", top, right, bottom, left)
+        };
+
+        // Block is lolite's initial display, and Taffy lays every box out as flex
+        push("display", "flex".to_string());
+        if let Some(i) = self.direction {
+            push("flex-direction", DIRECTIONS[i].0.to_string());
+        }
+        if self.wrap {
+            push("flex-wrap", "wrap".to_string());
+        }
+        if let Some(i) = self.justify {
+            push("justify-content", JUSTIFY[i].0.to_string());
+        }
+        if let Some(i) = self.align_items {
+            push("align-items", ALIGN[i].0.to_string());
+        }
+        if let Some(i) = self.align_content {
+            push("align-content", ALIGN_CONTENT[i].0.to_string());
+        }
+        if let Some(i) = self.align_self {
+            push("align-self", ALIGN[i].0.to_string());
+        }
+        if let Some(width) = self.width {
+            push("width", px(width));
+        }
+        if let Some(height) = self.height {
+            push("height", px(height));
+        }
+        if let Some(min_width) = self.min_width {
+            push("min-width", px(min_width));
+        }
+        if let Some(max_width) = self.max_width {
+            push("max-width", px(max_width));
+        }
+        if let Some(grow) = self.grow {
+            push("flex-grow", grow.to_string());
+        }
+        if let Some(shrink) = self.shrink {
+            push("flex-shrink", shrink.to_string());
+        }
+        if let Some(basis) = self.basis {
+            push("flex-basis", px(basis));
+        }
+        if let Some(gap) = self.gap {
+            push("gap", px(gap));
+        }
+        if self.margin != [0.0; 4] {
+            push("margin", sides(self.margin));
+        }
+        if self.padding != [0.0; 4] {
+            push("padding", sides(self.padding));
+        }
+        css
+    }
+
+    fn taffy_style(&self) -> Style {
+        let dimension = |value: Option<f32>| value.map_or(auto(), length);
+        let [top, right, bottom, left] = self.padding;
+        let padding = Rect {
+            top: length(top),
+            right: length(right),
+            bottom: length(bottom),
+            left: length(left),
+        };
+        let [top, right, bottom, left] = self.margin;
+        let margin = Rect {
+            top: length(top),
+            right: length(right),
+            bottom: length(bottom),
+            left: length(left),
+        };
+        let gap = length(self.gap.unwrap_or(0.0));
+
+        Style {
+            display: Display::Flex,
+            // Like CSS and lolite, unlike Taffy's default
+            box_sizing: BoxSizing::ContentBox,
+            flex_direction: self
+                .direction
+                .map_or(FlexDirection::Row, |i| DIRECTIONS[i].1),
+            flex_wrap: if self.wrap {
+                FlexWrap::Wrap
+            } else {
+                FlexWrap::NoWrap
+            },
+            justify_content: self.justify.map(|i| JUSTIFY[i].1),
+            align_items: self.align_items.map(|i| ALIGN[i].1),
+            align_content: self.align_content.map(|i| ALIGN_CONTENT[i].1),
+            align_self: self.align_self.map(|i| ALIGN[i].1),
+            size: Size {
+                width: dimension(self.width),
+                height: dimension(self.height),
+            },
+            min_size: Size {
+                width: dimension(self.min_width),
+                height: auto(),
+            },
+            max_size: Size {
+                width: dimension(self.max_width),
+                height: auto(),
+            },
+            flex_grow: self.grow.unwrap_or(0.0),
+            flex_shrink: self.shrink.unwrap_or(1.0),
+            flex_basis: dimension(self.basis),
+            gap: Size {
+                width: gap,
+                height: gap,
+            },
+            margin,
+            padding,
+            ..Style::default()
+        }
+    }
+}